@@ -0,0 +1,338 @@
+/**
+ * [INPUT]: 依赖 crate::commands::report 的活跃度聚合与命令用量, crate::security, serde
+ * [OUTPUT]: 对外提供 get_achievements 命令
+ * [POS]: commands/ 模块的成就引擎，从历史与会话数据计算徽章并持久化解锁时间
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::report::{aggregate_activity, load_command_usage, longest_streak};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Serializes read-modify-write of achievements.json
+static ACHIEVEMENTS_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Metric {
+    Sessions,
+    ActiveDays,
+    LongestStreak,
+    /// Prompts sent between 00:00 and 04:59 local time
+    NightPrompts,
+    /// Prompts sent between 05:00 and 06:59 local time
+    EarlyPrompts,
+    CommandUses,
+    DistinctCommands,
+}
+
+struct AchievementDef {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    metric: Metric,
+    threshold: u64,
+}
+
+const ACHIEVEMENTS: &[AchievementDef] = &[
+    AchievementDef {
+        id: "first_session",
+        title: "Hello, Claude",
+        description: "Start your first session",
+        metric: Metric::Sessions,
+        threshold: 1,
+    },
+    AchievementDef {
+        id: "sessions_100",
+        title: "Centurion",
+        description: "Reach 100 sessions",
+        metric: Metric::Sessions,
+        threshold: 100,
+    },
+    AchievementDef {
+        id: "sessions_1000",
+        title: "Thousand Conversations",
+        description: "Reach 1,000 sessions",
+        metric: Metric::Sessions,
+        threshold: 1000,
+    },
+    AchievementDef {
+        id: "active_days_100",
+        title: "Regular",
+        description: "Be active on 100 different days",
+        metric: Metric::ActiveDays,
+        threshold: 100,
+    },
+    AchievementDef {
+        id: "streak_7",
+        title: "Week Warrior",
+        description: "Use Claude Code 7 days in a row",
+        metric: Metric::LongestStreak,
+        threshold: 7,
+    },
+    AchievementDef {
+        id: "streak_30",
+        title: "Unstoppable",
+        description: "Use Claude Code 30 days in a row",
+        metric: Metric::LongestStreak,
+        threshold: 30,
+    },
+    AchievementDef {
+        id: "night_owl",
+        title: "Night Owl",
+        description: "Send 50 prompts between midnight and 5 AM",
+        metric: Metric::NightPrompts,
+        threshold: 50,
+    },
+    AchievementDef {
+        id: "early_bird",
+        title: "Early Bird",
+        description: "Send 50 prompts between 5 AM and 7 AM",
+        metric: Metric::EarlyPrompts,
+        threshold: 50,
+    },
+    AchievementDef {
+        id: "command_user",
+        title: "Slash Apprentice",
+        description: "Run slash commands 100 times",
+        metric: Metric::CommandUses,
+        threshold: 100,
+    },
+    AchievementDef {
+        id: "command_power_user",
+        title: "Command Power User",
+        description: "Run slash commands 1,000 times",
+        metric: Metric::CommandUses,
+        threshold: 1000,
+    },
+    AchievementDef {
+        id: "command_collector",
+        title: "Toolsmith",
+        description: "Use 20 different slash commands",
+        metric: Metric::DistinctCommands,
+        threshold: 20,
+    },
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Achievement {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub progress: u64,
+    pub threshold: u64,
+    pub unlocked: bool,
+    /// Unix ms of the first time the threshold was observed as reached
+    pub unlocked_at: Option<i64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn get_achievements_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("achievements.json")
+}
+
+/// achievement id -> unlock time (Unix ms)
+fn load_unlocks(path: &Path) -> HashMap<String, i64> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_unlocks(path: &Path, unlocks: &HashMap<String, i64>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(unlocks).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content).map_err(|e| e.to_string())
+}
+
+/// Main session transcripts (agent-*.jsonl sidechains excluded)
+fn count_sessions(projects_dir: &Path) -> u64 {
+    let mut count = 0u64;
+    for project in fs::read_dir(projects_dir).into_iter().flatten().flatten() {
+        if !project.path().is_dir() {
+            continue;
+        }
+        for session in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let name = session.file_name().to_string_lossy().to_string();
+            if name.ends_with(".jsonl") && !name.starts_with("agent-") {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn collect_metrics(
+    claude_dir: &Path,
+    command_usage: &HashMap<String, (usize, Option<i64>)>,
+) -> HashMap<Metric, u64> {
+    let history = fs::read_to_string(claude_dir.join("history.jsonl")).unwrap_or_default();
+    let activity = aggregate_activity(&history, None);
+    let hour_range_total = |hours: std::ops::Range<usize>| -> u64 {
+        activity
+            .weekday_hour
+            .iter()
+            .flat_map(|row| row.get(hours.clone()).unwrap_or_default())
+            .sum::<usize>() as u64
+    };
+
+    HashMap::from([
+        (
+            Metric::Sessions,
+            count_sessions(&claude_dir.join("projects")),
+        ),
+        (Metric::ActiveDays, activity.daily.len() as u64),
+        (
+            Metric::LongestStreak,
+            longest_streak(&activity.daily) as u64,
+        ),
+        (Metric::NightPrompts, hour_range_total(0..5)),
+        (Metric::EarlyPrompts, hour_range_total(5..7)),
+        (
+            Metric::CommandUses,
+            command_usage.values().map(|(n, _)| *n as u64).sum(),
+        ),
+        (
+            Metric::DistinctCommands,
+            command_usage.values().filter(|(n, _)| *n > 0).count() as u64,
+        ),
+    ])
+}
+
+/// Unlocks never expire: once recorded, a badge stays unlocked even if the underlying
+/// data is later deleted. Returns the badges and whether new unlocks were recorded.
+fn evaluate(
+    metrics: &HashMap<Metric, u64>,
+    unlocks: &mut HashMap<String, i64>,
+    now_ms: i64,
+) -> (Vec<Achievement>, bool) {
+    let mut changed = false;
+    let achievements = ACHIEVEMENTS
+        .iter()
+        .map(|def| {
+            let progress = metrics.get(&def.metric).copied().unwrap_or(0);
+            if progress >= def.threshold && !unlocks.contains_key(def.id) {
+                unlocks.insert(def.id.to_string(), now_ms);
+                changed = true;
+            }
+            let unlocked_at = unlocks.get(def.id).copied();
+            Achievement {
+                id: def.id.to_string(),
+                title: def.title.to_string(),
+                description: def.description.to_string(),
+                progress: progress.min(def.threshold),
+                threshold: def.threshold,
+                unlocked: unlocked_at.is_some(),
+                unlocked_at,
+            }
+        })
+        .collect();
+    (achievements, changed)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_achievements() -> AppResult<Vec<Achievement>> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let metrics = collect_metrics(
+            &security::get_claude_dir_or_fallback(),
+            &load_command_usage(),
+        );
+
+        let _guard = ACHIEVEMENTS_LOCK
+            .lock()
+            .map_err(|_| "Achievements lock poisoned")?;
+        let path = get_achievements_path();
+        let mut unlocks = load_unlocks(&path);
+        let (achievements, changed) = evaluate(
+            &metrics,
+            &mut unlocks,
+            chrono::Utc::now().timestamp_millis(),
+        );
+        if changed {
+            save_unlocks(&path, &unlocks).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+        }
+        Ok(achievements)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_persists_first_unlock_time() {
+        let mut metrics = HashMap::from([(Metric::Sessions, 150), (Metric::LongestStreak, 3)]);
+        let mut unlocks = HashMap::new();
+
+        let (achievements, changed) = evaluate(&metrics, &mut unlocks, 1000);
+        assert!(changed);
+        let by_id: HashMap<&str, &Achievement> =
+            achievements.iter().map(|a| (a.id.as_str(), a)).collect();
+        assert_eq!(by_id["first_session"].unlocked_at, Some(1000));
+        assert_eq!(by_id["sessions_100"].progress, 100);
+        assert!(!by_id["sessions_1000"].unlocked);
+        assert_eq!(by_id["sessions_1000"].progress, 150);
+        assert_eq!(by_id["streak_7"].progress, 3);
+
+        // Later evaluations keep the original timestamp, even if data shrinks
+        metrics.insert(Metric::Sessions, 0);
+        let (achievements, changed) = evaluate(&metrics, &mut unlocks, 2000);
+        assert!(!changed);
+        let first = achievements
+            .iter()
+            .find(|a| a.id == "first_session")
+            .expect("first_session");
+        assert_eq!(first.unlocked_at, Some(1000));
+    }
+
+    #[test]
+    fn test_collect_metrics() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let project = temp.path().join("projects").join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        fs::write(project.join("a.jsonl"), "").expect("write session");
+        fs::write(project.join("b.jsonl"), "").expect("write session");
+        fs::write(project.join("agent-1.jsonl"), "").expect("write agent");
+
+        let usage = HashMap::from([
+            ("commit".to_string(), (30, None)),
+            ("review".to_string(), (12, None)),
+            ("unused".to_string(), (0, None)),
+        ]);
+        let metrics = collect_metrics(temp.path(), &usage);
+        assert_eq!(metrics[&Metric::Sessions], 2);
+        assert_eq!(metrics[&Metric::CommandUses], 42);
+        assert_eq!(metrics[&Metric::DistinctCommands], 2);
+        assert_eq!(metrics[&Metric::ActiveDays], 0);
+    }
+
+    #[test]
+    fn test_unlocks_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("lovcode").join("achievements.json");
+        assert!(load_unlocks(&path).is_empty());
+
+        let unlocks = HashMap::from([("streak_7".to_string(), 42)]);
+        save_unlocks(&path, &unlocks).expect("save unlocks");
+        assert_eq!(load_unlocks(&path), unlocks);
+    }
+}
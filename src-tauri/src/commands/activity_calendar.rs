@@ -0,0 +1,327 @@
+/**
+ * [INPUT]: 依赖 ~/.claude/history.jsonl (提问时间戳), workspace_store (feature 关联会话), crate::commands::report::ReportPeriod, chrono, crate::security
+ * [OUTPUT]: 对外提供 export_activity_ical 命令
+ * [POS]: commands/ 模块的活跃度日历导出，把提问时间戳切成工作时段，每段写成一个 iCalendar VEVENT，可叠加到个人日历
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::report::ReportPeriod;
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::types::HistoryEntry;
+use crate::workspace_store;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A pause longer than this between prompts ends the work block
+const IDLE_GAP_MS: u64 = 30 * 60 * 1000;
+/// Time credited after a block's last prompt, for reading the reply and reviewing the change
+const TAIL_MS: u64 = 15 * 60 * 1000;
+/// RFC 5545 content lines are folded at 75 octets
+const MAX_LINE_OCTETS: usize = 75;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// One prompt from history.jsonl
+struct Prompt {
+    timestamp: u64,
+    project: Option<String>,
+    session_id: Option<String>,
+}
+
+/// Prompts with no pause longer than IDLE_GAP_MS between them
+#[derive(Debug, PartialEq)]
+struct WorkBlock {
+    /// Unix milliseconds
+    start: u64,
+    end: u64,
+    prompts: usize,
+    /// Project directory names, sorted
+    projects: BTreeSet<String>,
+    /// "Project: feature" for workspace features whose sessions had prompts in the block
+    features: BTreeSet<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Prompts in the period, oldest first
+fn parse_history(content: &str, period: ReportPeriod) -> Vec<Prompt> {
+    let mut prompts: Vec<Prompt> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+        .filter_map(|entry| {
+            let timestamp = entry.timestamp?;
+            (timestamp >= period.start && timestamp < period.end).then_some(Prompt {
+                timestamp,
+                project: entry.project,
+                session_id: entry.session_id,
+            })
+        })
+        .collect();
+    prompts.sort_by_key(|p| p.timestamp);
+    prompts
+}
+
+/// Session id -> "Project: feature" for every session linked to a workspace feature
+fn feature_sessions() -> HashMap<String, String> {
+    let Ok(data) = workspace_store::load_workspace() else {
+        return HashMap::new();
+    };
+    let mut sessions = HashMap::new();
+    for project in &data.projects {
+        for feature in &project.features {
+            let label = format!("{}: {}", project.name, feature.name);
+            for id in feature.session_ids.iter().chain(&feature.chat_session_id) {
+                sessions.insert(id.clone(), label.clone());
+            }
+        }
+    }
+    sessions
+}
+
+fn project_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |n| n.to_string_lossy().to_string())
+}
+
+/// Split sorted prompts into blocks wherever the gap exceeds IDLE_GAP_MS
+fn work_blocks(prompts: &[Prompt], features: &HashMap<String, String>) -> Vec<WorkBlock> {
+    let mut blocks: Vec<WorkBlock> = Vec::new();
+    for prompt in prompts {
+        // block.end - TAIL_MS is the block's last prompt so far
+        let continues = blocks
+            .last()
+            .is_some_and(|block| prompt.timestamp <= block.end - TAIL_MS + IDLE_GAP_MS);
+        if !continues {
+            blocks.push(WorkBlock {
+                start: prompt.timestamp,
+                end: prompt.timestamp,
+                prompts: 0,
+                projects: BTreeSet::new(),
+                features: BTreeSet::new(),
+            });
+        }
+        let Some(block) = blocks.last_mut() else {
+            continue;
+        };
+        block.end = prompt.timestamp + TAIL_MS;
+        block.prompts += 1;
+        if let Some(project) = prompt.project.as_deref().filter(|p| !p.is_empty()) {
+            block.projects.insert(project_name(project));
+        }
+        if let Some(feature) = prompt.session_id.as_ref().and_then(|id| features.get(id)) {
+            block.features.insert(feature.clone());
+        }
+    }
+    blocks
+}
+
+fn ical_time(ms: u64) -> String {
+    DateTime::from_timestamp_millis(i64::try_from(ms).unwrap_or(i64::MAX))
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// TEXT value escaping (RFC 5545 §3.3.11)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into 75-octet pieces, never splitting a UTF-8 character
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        // Continuation lines start with a space, which counts toward the limit
+        if width + ch.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn render_ical(blocks: &[WorkBlock], now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//lovstudio//Lovcode//EN",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+        "X-WR-CALNAME:Lovcode activity",
+    ] {
+        push_line(&mut out, line);
+    }
+
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    for block in blocks {
+        let projects: Vec<&str> = block.projects.iter().map(String::as_str).collect();
+        let summary = if projects.is_empty() {
+            "Coding".to_string()
+        } else {
+            format!("Coding: {}", projects.join(", "))
+        };
+        let mut description = format!(
+            "{} prompt{}",
+            block.prompts,
+            if block.prompts == 1 { "" } else { "s" }
+        );
+        if !block.features.is_empty() {
+            let features: Vec<&str> = block.features.iter().map(String::as_str).collect();
+            let _ = write!(description, "\nFeatures: {}", features.join(", "));
+        }
+
+        push_line(&mut out, "BEGIN:VEVENT");
+        // Stable across exports, so re-importing updates events instead of duplicating them
+        push_line(&mut out, &format!("UID:activity-{}@lovcode", block.start));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut out, &format!("DTSTART:{}", ical_time(block.start)));
+        push_line(&mut out, &format!("DTEND:{}", ical_time(block.end)));
+        push_line(&mut out, &format!("SUMMARY:{}", escape_text(&summary)));
+        push_line(
+            &mut out,
+            &format!("DESCRIPTION:{}", escape_text(&description)),
+        );
+        push_line(&mut out, "CATEGORIES:Lovcode");
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Write Claude Code activity in the period (all time when omitted) as an .ics calendar.
+///
+/// Prompts from ~/.claude/history.jsonl are grouped into work blocks, one event each,
+/// listing the projects and workspace features worked on.
+///
+/// Written to `path`, or to ~/.lovstudio/lovcode/exports/activity.ics when omitted;
+/// returns the path.
+#[tauri::command]
+pub async fn export_activity_ical(
+    period: Option<ReportPeriod>,
+    path: Option<String>,
+) -> AppResult<String> {
+    let period = period.unwrap_or(ReportPeriod {
+        start: 0,
+        end: u64::MAX,
+    });
+    if period.start >= period.end {
+        return Err(AppError::invalid_input(
+            "Report period start must be before end",
+        ));
+    }
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(security::validate_decoded_path(&path)?),
+        None => security::get_lovstudio_dir_or_fallback()
+            .join("exports")
+            .join("activity.ics"),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let history_path = security::get_claude_dir_or_fallback().join("history.jsonl");
+        let content = fs::read_to_string(&history_path).unwrap_or_default();
+        let blocks = work_blocks(&parse_history(&content, period), &feature_sessions());
+        let calendar = render_ical(&blocks, Utc::now());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("Failed to create export directory", &e))?;
+        }
+        security::atomic_write_string(&path, &calendar)?;
+        Ok(path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: u64 = 60 * 1000;
+
+    #[test]
+    fn test_work_blocks_split_on_idle_gap() {
+        let base = 1_741_082_400_000; // 2025-03-04 10:00 UTC
+        let history = [
+            (base, "/work/app", "s1"),
+            (base + 20 * MIN, "/work/app", "s1"),
+            (base + 45 * MIN, "/work/site", "s2"),
+            (base + 3 * 60 * MIN, "/work/app", "s3"),
+            (base + 10 * 24 * 60 * MIN, "/work/app", "s3"),
+        ]
+        .iter()
+        .map(|(ts, project, session)| {
+            format!(
+                r#"{{"display":"x","timestamp":{},"project":"{}","sessionId":"{}"}}"#,
+                ts, project, session
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+        let prompts = parse_history(
+            &history,
+            ReportPeriod {
+                start: base,
+                end: base + 24 * 60 * MIN,
+            },
+        );
+        let features = HashMap::from([("s2".to_string(), "Site: Redesign".to_string())]);
+        let blocks = work_blocks(&prompts, &features);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start, base);
+        assert_eq!(blocks[0].end, base + 60 * MIN);
+        assert_eq!(blocks[0].prompts, 3);
+        assert_eq!(
+            blocks[0].projects.iter().collect::<Vec<_>>(),
+            ["app", "site"]
+        );
+        assert_eq!(
+            blocks[0].features.iter().collect::<Vec<_>>(),
+            ["Site: Redesign"]
+        );
+        assert_eq!(blocks[1].prompts, 1);
+        assert!(blocks[1].features.is_empty());
+    }
+
+    #[test]
+    fn test_render_ical_escapes_and_folds() {
+        let block = WorkBlock {
+            start: 1_741_082_400_000,
+            end: 1_741_086_000_000,
+            prompts: 1,
+            projects: BTreeSet::from(["a,b;c".to_string()]),
+            features: BTreeSet::from(["x".repeat(100)]),
+        };
+        let now = DateTime::from_timestamp(1_741_100_000, 0).unwrap_or_default();
+        let ics = render_ical(&[block], now);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20250304T100000Z\r\nDTEND:20250304T110000Z\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Coding: a\\,b\\;c\r\n"));
+        assert!(ics.contains("DESCRIPTION:1 prompt\\nFeatures: xxx"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert!(ics.contains("\r\n x"));
+    }
+}
@@ -1,6 +1,6 @@
 /**
- * [INPUT]: 依赖 std::fs, std::path, std::process, serde, crate::security, crate::commands::local_commands
- * [OUTPUT]: 对外提供 list_local_agents, list_local_skills, list_agents, get_agent_info 命令
+ * [INPUT]: 依赖 std::fs, std::path, std::process, serde, crate::security, crate::commands::local_commands, chrono
+ * [OUTPUT]: 对外提供 list_local_agents, create_local_agent, update_local_agent, delete_local_agent, archive_local_agent, list_local_skills, create_skill, delete_skill, archive_skill, validate_agent, validate_skill, list_agents, get_agent_info 命令
  * [POS]: commands/ 模块的 Agent 和 Skill 管理命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  *
@@ -9,11 +9,14 @@
  * - Agent 能力声明
  * - MCP 配置路径
  */
-use crate::commands::parse_frontmatter;
+use crate::commands::local_commands::validate_allowed_tools;
+use crate::commands::{parse_frontmatter, update_frontmatter_field};
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 // ============================================================================
@@ -73,6 +76,12 @@ pub struct LocalAgent {
     pub model: Option<String>,
     pub tools: Option<String>,
     pub content: String,
+    #[serde(default = "default_agent_status")]
+    pub status: String, // "active" | "archived"
+}
+
+fn default_agent_status() -> String {
+    "active".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -81,29 +90,72 @@ pub struct LocalSkill {
     pub path: String,
     pub description: Option<String>,
     pub content: String,
+    #[serde(default = "default_agent_status")]
+    pub status: String, // "active" | "archived"
+}
+
+/// A problem found by validate_agent / validate_skill
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssetProblem {
+    pub severity: String, // "error" (Claude Code ignores the asset) | "warning"
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl AssetProblem {
+    fn error(field: Option<&str>, message: String) -> Self {
+        Self {
+            severity: "error".to_string(),
+            field: field.map(String::from),
+            message,
+        }
+    }
+
+    fn warning(field: Option<&str>, message: String) -> Self {
+        Self {
+            severity: "warning".to_string(),
+            field: field.map(String::from),
+            message,
+        }
+    }
+}
+
+/// A supporting file for a new skill, relative to the skill directory.
+/// A path ending in "/" creates an empty resource directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkillFile {
+    pub path: String,
+    #[serde(default)]
+    pub content: String,
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Collect agents below `current_dir`. When `require_model` is set (legacy agents
+/// kept in commands/), only files with a `model` field count as agents.
 fn collect_agents(
     base_dir: &PathBuf,
     current_dir: &PathBuf,
     agents: &mut Vec<LocalAgent>,
-) -> Result<(), String> {
-    for entry in fs::read_dir(current_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
+    require_model: bool,
+    status: &str,
+) -> AppResult<()> {
+    let entries = fs::read_dir(current_dir)
+        .map_err(|e| AppError::io("Failed to read directory", &e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
         let path = entry.path();
 
         if path.is_dir() {
-            collect_agents(base_dir, &path, agents)?;
+            collect_agents(base_dir, &path, agents, require_model, status)?;
         } else if path.extension().map_or(false, |e| e == "md") {
             let content = fs::read_to_string(&path).unwrap_or_default();
             let (frontmatter, _, body) = parse_frontmatter(&content);
 
             // Only include if it has a 'model' field (agents have model, commands don't)
-            if frontmatter.contains_key("model") {
+            if !require_model || frontmatter.contains_key("model") {
                 let relative = path.strip_prefix(base_dir).unwrap_or(&path);
                 let name = relative
                     .to_string_lossy()
@@ -118,6 +170,7 @@ fn collect_agents(
                     model: frontmatter.get("model").cloned(),
                     tools: frontmatter.get("tools").cloned(),
                     content: body,
+                    status: status.to_string(),
                 });
             }
         }
@@ -125,37 +178,607 @@ fn collect_agents(
     Ok(())
 }
 
+/// (agents/, .agents/archived/, .agents/trash/) under a .claude directory
+fn agent_dirs(claude_dir: &Path) -> (PathBuf, PathBuf, PathBuf) {
+    let dot_agents = claude_dir.join(".agents");
+    (
+        claude_dir.join("agents"),
+        dot_agents.join("archived"),
+        dot_agents.join("trash"),
+    )
+}
+
+/// Agent names: lowercase letters, digits and hyphens, optionally nested with "/"
+fn validate_agent_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.split('/').all(|seg| {
+            !seg.is_empty()
+                && seg
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid agent name '{}': use lowercase letters, digits and hyphens",
+            name
+        ))
+    }
+}
+
+/// Compose an agent file, making sure the frontmatter carries `name`
+fn build_agent_content(name: &str, frontmatter: Option<&str>, body: &str) -> String {
+    let fm = frontmatter.map(str::trim).unwrap_or_default();
+    if fm.is_empty() {
+        return format!("---\nname: {}\n---\n\n{}", name, body.trim_start());
+    }
+    let content = format!("---\n{}\n---\n\n{}", fm, body.trim_start());
+    let (fields, _, _) = parse_frontmatter(&content);
+    if fields.contains_key("name") {
+        content
+    } else {
+        update_frontmatter_field(&content, "name", name)
+    }
+}
+
+/// Accept only active agent files inside ~/.claude/agents
+fn resolve_agent_file(claude_dir: &Path, path: &str) -> AppResult<PathBuf> {
+    let file = PathBuf::from(path);
+    let (agents_dir, _, _) = agent_dirs(claude_dir);
+    if path.contains("..") || !file.starts_with(&agents_dir) {
+        return Err(AppError::invalid_input(format!(
+            "Not an agent in {}: {}",
+            agents_dir.display(),
+            path
+        )));
+    }
+    if !file.is_file() {
+        return Err(AppError::not_found(format!("Agent file not found: {}", path)));
+    }
+    Ok(file)
+}
+
+fn create_agent_in(
+    claude_dir: &Path,
+    name: &str,
+    frontmatter: Option<&str>,
+    body: &str,
+) -> AppResult<String> {
+    let name = name.trim().trim_end_matches(".md");
+    validate_agent_name(name).map_err(AppError::invalid_input)?;
+
+    let (agents_dir, _, _) = agent_dirs(claude_dir);
+    let file = agents_dir.join(format!("{}.md", name));
+    if file.exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("An agent named '{}' already exists", name),
+        ));
+    }
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+
+    let agent_name = name.rsplit('/').next().unwrap_or(name);
+    let content = build_agent_content(agent_name, frontmatter, body);
+    security::atomic_write_string(&file, &content)?;
+    Ok(file.to_string_lossy().to_string())
+}
+
+/// (skills/, .skills/archived/, .skills/trash/) under a .claude directory
+fn skill_dirs(claude_dir: &Path) -> (PathBuf, PathBuf, PathBuf) {
+    let dot_skills = claude_dir.join(".skills");
+    (
+        claude_dir.join("skills"),
+        dot_skills.join("archived"),
+        dot_skills.join("trash"),
+    )
+}
+
+/// Skill names: lowercase letters, digits and hyphens, at most 64 characters
+fn validate_skill_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("Skill name must be 1-64 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(format!(
+            "Invalid skill name '{}': use lowercase letters, digits and hyphens",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Skill descriptions tell Claude when to use the skill: single line, at most 1024 characters
+fn validate_skill_description(description: &str) -> Result<(), String> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err("Skill description cannot be empty".to_string());
+    }
+    if description.contains('\n') {
+        return Err("Skill description must be a single line".to_string());
+    }
+    if description.chars().count() > 1024 {
+        return Err("Skill description must be at most 1024 characters".to_string());
+    }
+    Ok(())
+}
+
+fn resolve_skill_dir(skills_dir: &Path, name: &str) -> AppResult<PathBuf> {
+    validate_skill_name(name).map_err(AppError::invalid_input)?;
+    let dir = skills_dir.join(name);
+    if !dir.is_dir() {
+        return Err(AppError::not_found(format!("Skill not found: {}", name)));
+    }
+    Ok(dir)
+}
+
+fn create_skill_in(
+    claude_dir: &Path,
+    name: &str,
+    description: &str,
+    files: &[SkillFile],
+) -> AppResult<String> {
+    let name = name.trim();
+    validate_skill_name(name).map_err(AppError::invalid_input)?;
+    validate_skill_description(description).map_err(AppError::invalid_input)?;
+
+    // Validate supporting file paths before touching the filesystem
+    for file in files {
+        let rel = Path::new(file.path.trim_end_matches('/'));
+        let safe = !file.path.is_empty()
+            && rel.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !safe {
+            return Err(AppError::invalid_input(format!(
+                "Invalid skill file path: {}",
+                file.path
+            )));
+        }
+        if rel == Path::new("SKILL.md") {
+            return Err(AppError::invalid_input(
+                "SKILL.md is generated from name and description",
+            ));
+        }
+    }
+
+    let (skills_dir, _, _) = skill_dirs(claude_dir);
+    let skill_dir = skills_dir.join(name);
+    if skill_dir.exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("A skill named '{}' already exists", name),
+        ));
+    }
+    fs::create_dir_all(&skill_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
+
+    let skill_md = skill_dir.join("SKILL.md");
+    let content = format!(
+        "---\nname: {}\ndescription: {}\n---\n\n# {}\n\n",
+        name,
+        yaml_scalar(description.trim()),
+        name
+    );
+    security::atomic_write_string(&skill_md, &content)?;
+
+    for file in files {
+        let target = skill_dir.join(file.path.trim_end_matches('/'));
+        if file.path.ends_with('/') {
+            fs::create_dir_all(&target)
+                .map_err(|e| AppError::io("Failed to create directory", &e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("Failed to create directory", &e))?;
+        }
+        security::atomic_write_string(&target, &file.content)?;
+    }
+
+    Ok(skill_md.to_string_lossy().to_string())
+}
+
+/// Quote a frontmatter value when plain YAML would misread it (e.g. "Use when: ...")
+fn yaml_scalar(value: &str) -> String {
+    let needs_quotes = value.contains(": ")
+        || value.contains(" #")
+        || value.starts_with(|c: char| "'\"[]{}>|*&!%@`#,-?".contains(c));
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Model aliases accepted in agent frontmatter; full ids ("claude-...") are also fine
+const AGENT_MODEL_ALIASES: &[&str] = &["sonnet", "opus", "haiku", "inherit"];
+
+/// Words Claude Code rejects in skill names
+const RESERVED_SKILL_WORDS: &[&str] = &["anthropic", "claude"];
+
+/// Read a file's frontmatter fields, or None when it has no frontmatter block
+fn read_frontmatter_fields(path: &Path) -> AppResult<Option<HashMap<String, String>>> {
+    let content = fs::read_to_string(path).map_err(|e| AppError::io("Failed to read file", &e))?;
+    let (fields, raw, _) = parse_frontmatter(&content);
+    Ok(raw.map(|_| fields))
+}
+
+fn validate_agent_in(claude_dir: &Path, file: &Path) -> AppResult<Vec<AssetProblem>> {
+    if !file.is_file() {
+        return Err(AppError::not_found(format!("Agent file not found: {}", file.display())));
+    }
+
+    let mut problems = Vec::new();
+    let Some(fields) = read_frontmatter_fields(file)? else {
+        problems.push(AssetProblem::error(
+            None,
+            "Missing frontmatter block; Claude Code ignores agents without one".to_string(),
+        ));
+        return Ok(problems);
+    };
+
+    let name = fields.get("name").map(|n| n.trim()).unwrap_or_default();
+    if name.is_empty() {
+        problems.push(AssetProblem::error(
+            Some("name"),
+            "Missing required field: name".to_string(),
+        ));
+    } else if name.contains('/') {
+        problems.push(AssetProblem::error(
+            Some("name"),
+            "Agent name cannot contain '/'".to_string(),
+        ));
+    } else if let Err(e) = validate_agent_name(name) {
+        problems.push(AssetProblem::error(Some("name"), e));
+    }
+
+    if fields.get("description").is_none_or(|d| d.trim().is_empty()) {
+        problems.push(AssetProblem::error(
+            Some("description"),
+            "Missing required field: description".to_string(),
+        ));
+    }
+
+    match fields.get("tools").map(|t| t.trim()) {
+        None => problems.push(AssetProblem::warning(
+            Some("tools"),
+            "No tools listed; the agent inherits every tool".to_string(),
+        )),
+        Some(tools) => {
+            if let Err(e) = validate_allowed_tools(tools) {
+                problems.push(AssetProblem::error(
+                    Some("tools"),
+                    e.replace("allowed-tools", "tools"),
+                ));
+            }
+        }
+    }
+
+    match fields.get("model").map(|m| m.trim()) {
+        None => problems.push(AssetProblem::warning(
+            Some("model"),
+            "No model set; the agent uses the default subagent model".to_string(),
+        )),
+        Some(model) => {
+            if !AGENT_MODEL_ALIASES.contains(&model) && !model.starts_with("claude-") {
+                problems.push(AssetProblem::error(
+                    Some("model"),
+                    format!(
+                        "Unknown model '{}': use sonnet, opus, haiku, inherit or a full model id",
+                        model
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Another agent with the same name shadows this one
+    if !name.is_empty() {
+        let (agents_dir, _, _) = agent_dirs(claude_dir);
+        for other in find_md_files(&agents_dir) {
+            if other == file {
+                continue;
+            }
+            let other_name = read_frontmatter_fields(&other)
+                .ok()
+                .flatten()
+                .and_then(|f| f.get("name").cloned());
+            if other_name.as_deref().map(str::trim) == Some(name) {
+                problems.push(AssetProblem::error(
+                    Some("name"),
+                    format!("Name '{}' is also used by {}", name, other.display()),
+                ));
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+fn validate_skill_in(claude_dir: &Path, path: &Path) -> AppResult<Vec<AssetProblem>> {
+    let skill_md = if path.is_dir() {
+        path.join("SKILL.md")
+    } else {
+        path.to_path_buf()
+    };
+    if !skill_md.is_file() {
+        return Err(AppError::not_found(format!("SKILL.md not found: {}", skill_md.display())));
+    }
+    let dir_name = skill_md
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut problems = Vec::new();
+    let Some(fields) = read_frontmatter_fields(&skill_md)? else {
+        problems.push(AssetProblem::error(
+            None,
+            "Missing frontmatter block; Claude Code ignores skills without one".to_string(),
+        ));
+        return Ok(problems);
+    };
+
+    let name = fields.get("name").map(|n| n.trim()).unwrap_or_default();
+    if name.is_empty() {
+        problems.push(AssetProblem::error(
+            Some("name"),
+            "Missing required field: name".to_string(),
+        ));
+    } else {
+        if let Err(e) = validate_skill_name(name) {
+            problems.push(AssetProblem::error(Some("name"), e));
+        }
+        if let Some(word) = RESERVED_SKILL_WORDS.iter().find(|w| name.contains(**w)) {
+            problems.push(AssetProblem::error(
+                Some("name"),
+                format!("Skill names cannot contain the reserved word '{}'", word),
+            ));
+        }
+        if name != dir_name {
+            problems.push(AssetProblem::warning(
+                Some("name"),
+                format!("Name '{}' differs from directory name '{}'", name, dir_name),
+            ));
+        }
+    }
+
+    match fields.get("description") {
+        None => problems.push(AssetProblem::error(
+            Some("description"),
+            "Missing required field: description".to_string(),
+        )),
+        Some(description) => {
+            if let Err(e) = validate_skill_description(description) {
+                problems.push(AssetProblem::error(Some("description"), e));
+            }
+        }
+    }
+
+    // Another skill with the same name shadows this one
+    if !name.is_empty() {
+        let (skills_dir, _, _) = skill_dirs(claude_dir);
+        for entry in fs::read_dir(&skills_dir).into_iter().flatten().flatten() {
+            let other = entry.path().join("SKILL.md");
+            if other == skill_md || !other.is_file() {
+                continue;
+            }
+            let other_name = read_frontmatter_fields(&other)
+                .ok()
+                .flatten()
+                .and_then(|f| f.get("name").cloned());
+            if other_name.as_deref().map(str::trim) == Some(name) {
+                problems.push(AssetProblem::error(
+                    Some("name"),
+                    format!("Name '{}' is also used by {}", name, other.display()),
+                ));
+            }
+        }
+    }
+
+    Ok(problems)
+}
+
+/// All .md files below a directory (recursive)
+fn find_md_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(find_md_files(&path));
+        } else if path.extension().is_some_and(|e| e == "md") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+fn move_file(src: &Path, dest: &Path) -> AppResult<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    fs::rename(src, dest).map_err(|e| AppError::io("Failed to move file", &e))
+}
+
 // ============================================================================
 // Commands
 // ============================================================================
 
 #[tauri::command]
-pub fn list_local_agents() -> Result<Vec<LocalAgent>, String> {
-    let commands_dir = security::get_claude_dir_or_fallback().join("commands");
-
-    if !commands_dir.exists() {
-        return Ok(vec![]);
-    }
+pub fn list_local_agents() -> AppResult<Vec<LocalAgent>> {
+    let claude_dir = security::get_claude_dir_or_fallback();
+    let commands_dir = claude_dir.join("commands");
+    let (agents_dir, archived_dir, _) = agent_dirs(&claude_dir);
 
     let mut agents = Vec::new();
-    collect_agents(&commands_dir, &commands_dir, &mut agents)?;
+    if agents_dir.exists() {
+        collect_agents(&agents_dir, &agents_dir, &mut agents, false, "active")?;
+    }
+    if archived_dir.exists() {
+        collect_agents(&archived_dir, &archived_dir, &mut agents, false, "archived")?;
+    }
+    // Legacy: agents stored alongside slash commands
+    if commands_dir.exists() {
+        collect_agents(&commands_dir, &commands_dir, &mut agents, true, "active")?;
+    }
 
     agents.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(agents)
 }
 
+/// Create ~/.claude/agents/<name>.md. `name` is also written to the frontmatter
+/// when missing, since Claude Code identifies subagents by it.
+#[tauri::command]
+pub fn create_local_agent(
+    name: String,
+    frontmatter: Option<String>,
+    body: String,
+) -> AppResult<String> {
+    let claude_dir = security::get_claude_dir()?;
+    create_agent_in(&claude_dir, &name, frontmatter.as_deref(), &body)
+}
+
+/// Rewrite an existing agent's frontmatter and body
 #[tauri::command]
-pub fn list_local_skills() -> Result<Vec<LocalSkill>, String> {
-    let skills_dir = security::get_claude_dir_or_fallback().join("skills");
+pub fn update_local_agent(
+    path: String,
+    frontmatter: Option<String>,
+    body: String,
+) -> AppResult<()> {
+    let claude_dir = security::get_claude_dir()?;
+    let file = resolve_agent_file(&claude_dir, &path)?;
+    let name = file.file_stem().unwrap_or_default().to_string_lossy().to_string();
+
+    let content = build_agent_content(&name, frontmatter.as_deref(), &body);
+    security::atomic_write_string(&file, &content)
+}
 
-    if !skills_dir.exists() {
-        return Ok(vec![]);
+/// Move an agent to ~/.claude/.agents/trash/ (timestamped) instead of deleting it
+#[tauri::command]
+pub fn delete_local_agent(path: String) -> AppResult<String> {
+    let claude_dir = security::get_claude_dir()?;
+    let file = resolve_agent_file(&claude_dir, &path)?;
+    let (_, _, trash_dir) = agent_dirs(&claude_dir);
+
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let dest = trash_dir.join(format!(
+        "{}.{}.md",
+        stem,
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    move_file(&file, &dest)?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Archive an agent by moving it to ~/.claude/.agents/archived/ so Claude Code
+/// stops loading it (mirrors deprecate_command)
+#[tauri::command]
+pub fn archive_local_agent(path: String) -> AppResult<String> {
+    let claude_dir = security::get_claude_dir()?;
+    let file = resolve_agent_file(&claude_dir, &path)?;
+    let (agents_dir, archived_dir, _) = agent_dirs(&claude_dir);
+
+    let relative = file
+        .strip_prefix(&agents_dir)
+        .map_err(|_| AppError::invalid_input("Agent is not in agents directory"))?;
+    let dest = archived_dir.join(relative);
+    if dest.exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("Cannot archive: {} already exists", dest.display()),
+        ));
     }
+    move_file(&file, &dest)?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn list_local_skills() -> AppResult<Vec<LocalSkill>> {
+    let (skills_dir, archived_dir, _) = skill_dirs(&security::get_claude_dir_or_fallback());
 
     let mut skills = Vec::new();
+    if skills_dir.exists() {
+        collect_skills(&skills_dir, &mut skills, "active")?;
+    }
+    if archived_dir.exists() {
+        collect_skills(&archived_dir, &mut skills, "archived")?;
+    }
+
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(skills)
+}
+
+/// Scaffold ~/.claude/skills/<name>/SKILL.md plus optional supporting files
+#[tauri::command]
+pub fn create_skill(
+    name: String,
+    description: String,
+    files: Option<Vec<SkillFile>>,
+) -> AppResult<String> {
+    let claude_dir = security::get_claude_dir()?;
+    create_skill_in(&claude_dir, &name, &description, &files.unwrap_or_default())
+}
+
+/// Move a skill directory to ~/.claude/.skills/trash/ (timestamped)
+#[tauri::command]
+pub fn delete_skill(name: String) -> AppResult<String> {
+    let claude_dir = security::get_claude_dir()?;
+    let (skills_dir, _, trash_dir) = skill_dirs(&claude_dir);
+    let src = resolve_skill_dir(&skills_dir, &name)?;
+
+    let dest = trash_dir.join(format!(
+        "{}.{}",
+        name,
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    move_file(&src, &dest)?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Move a skill directory to ~/.claude/.skills/archived/ so Claude Code stops loading it
+#[tauri::command]
+pub fn archive_skill(name: String) -> AppResult<String> {
+    let claude_dir = security::get_claude_dir()?;
+    let (skills_dir, archived_dir, _) = skill_dirs(&claude_dir);
+    let src = resolve_skill_dir(&skills_dir, &name)?;
+
+    let dest = archived_dir.join(&name);
+    if dest.exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("Cannot archive: {} already exists", dest.display()),
+        ));
+    }
+    move_file(&src, &dest)?;
+    Ok(dest.to_string_lossy().to_string())
+}
 
-    for entry in fs::read_dir(&skills_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
+/// Check an agent file for problems that would make Claude Code skip or misread it
+#[tauri::command]
+pub fn validate_agent(path: String) -> AppResult<Vec<AssetProblem>> {
+    let claude_dir = security::get_claude_dir_or_fallback();
+    validate_agent_in(&claude_dir, Path::new(&path))
+}
+
+/// Check a skill (its directory or SKILL.md) for problems that would make Claude Code skip it
+#[tauri::command]
+pub fn validate_skill(path: String) -> AppResult<Vec<AssetProblem>> {
+    let claude_dir = security::get_claude_dir_or_fallback();
+    validate_skill_in(&claude_dir, Path::new(&path))
+}
+
+fn collect_skills(
+    skills_dir: &Path,
+    skills: &mut Vec<LocalSkill>,
+    status: &str,
+) -> AppResult<()> {
+    let entries = fs::read_dir(skills_dir)
+        .map_err(|e| AppError::io("Failed to read directory", &e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
         let path = entry.path();
 
         if path.is_dir() {
@@ -174,13 +797,12 @@ pub fn list_local_skills() -> Result<Vec<LocalSkill>, String> {
                     path: skill_md.to_string_lossy().to_string(),
                     description: frontmatter.get("description").cloned(),
                     content: body,
+                    status: status.to_string(),
                 });
             }
         }
     }
-
-    skills.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(skills)
+    Ok(())
 }
 
 // ============================================================================
@@ -360,7 +982,7 @@ fn detect_amp() -> CodingAgentInfo {
 
 /// 获取所有支持的 Coding Agent 及其可用性
 #[tauri::command]
-pub fn list_coding_agents() -> Result<Vec<CodingAgentInfo>, String> {
+pub fn list_coding_agents() -> AppResult<Vec<CodingAgentInfo>> {
     let agents = vec![
         detect_claude(),
         detect_codex(),
@@ -373,14 +995,14 @@ pub fn list_coding_agents() -> Result<Vec<CodingAgentInfo>, String> {
 
 /// 获取单个 Coding Agent 信息
 #[tauri::command]
-pub fn get_coding_agent_info(agent_id: String) -> Result<CodingAgentInfo, String> {
+pub fn get_coding_agent_info(agent_id: String) -> AppResult<CodingAgentInfo> {
     match agent_id.as_str() {
         "claude" => Ok(detect_claude()),
         "codex" => Ok(detect_codex()),
         "gemini" => Ok(detect_gemini()),
         "aider" => Ok(detect_aider()),
         "amp" => Ok(detect_amp()),
-        _ => Err(format!("Unknown agent: {}", agent_id)),
+        _ => Err(AppError::not_found(format!("Unknown agent: {}", agent_id))),
     }
 }
 
@@ -415,4 +1037,118 @@ mod tests {
         let result = get_coding_agent_info("unknown_agent".to_string());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_validate_agent_name() {
+        assert!(validate_agent_name("code-reviewer").is_ok());
+        assert!(validate_agent_name("team/reviewer-2").is_ok());
+        assert!(validate_agent_name("Code Reviewer").is_err());
+        assert!(validate_agent_name("team//reviewer").is_err());
+        assert!(validate_agent_name("../escape").is_err());
+    }
+
+    #[test]
+    fn test_create_agent_adds_name() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = create_agent_in(
+            temp.path(),
+            "team/reviewer",
+            Some("description: Reviews code\nmodel: sonnet"),
+            "You review code.",
+        )
+        .expect("create agent");
+
+        let content = fs::read_to_string(&path).expect("read agent");
+        let (fields, _, body) = parse_frontmatter(&content);
+        assert_eq!(fields.get("name").map(String::as_str), Some("reviewer"));
+        assert_eq!(fields.get("model").map(String::as_str), Some("sonnet"));
+        assert_eq!(body, "You review code.");
+
+        assert!(create_agent_in(temp.path(), "team/reviewer", None, "dup").is_err());
+        assert!(resolve_agent_file(temp.path(), &path).is_ok());
+        assert!(resolve_agent_file(temp.path(), "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_create_skill_scaffold() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let files = vec![
+            SkillFile {
+                path: "scripts/run.sh".to_string(),
+                content: "echo hi".to_string(),
+            },
+            SkillFile {
+                path: "resources/".to_string(),
+                content: String::new(),
+            },
+        ];
+        let path = create_skill_in(temp.path(), "pdf-tools", "Work with PDF files", &files)
+            .expect("create skill");
+
+        let content = fs::read_to_string(&path).expect("read SKILL.md");
+        let (fields, _, _) = parse_frontmatter(&content);
+        assert_eq!(fields.get("name").map(String::as_str), Some("pdf-tools"));
+        let skill_dir = temp.path().join("skills/pdf-tools");
+        assert!(skill_dir.join("scripts/run.sh").is_file());
+        assert!(skill_dir.join("resources").is_dir());
+
+        assert_eq!(yaml_scalar("Use when: editing PDFs"), "\"Use when: editing PDFs\"");
+        assert!(create_skill_in(temp.path(), "pdf-tools", "again", &[]).is_err());
+        assert!(create_skill_in(temp.path(), "PDF", "desc", &[]).is_err());
+        assert!(create_skill_in(temp.path(), "other", "", &[]).is_err());
+
+        let escape = vec![SkillFile {
+            path: "../escape.txt".to_string(),
+            content: String::new(),
+        }];
+        assert!(create_skill_in(temp.path(), "other", "desc", &escape).is_err());
+        assert!(!temp.path().join("skills/other").exists());
+    }
+
+    #[test]
+    fn test_validate_agent() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let agents_dir = temp.path().join("agents");
+        fs::create_dir_all(&agents_dir).expect("create agents dir");
+
+        let good = agents_dir.join("reviewer.md");
+        fs::write(
+            &good,
+            "---\nname: reviewer\ndescription: Reviews code\ntools: Read, Grep\nmodel: sonnet\n---\n\nReview.",
+        )
+        .expect("write agent");
+        assert!(validate_agent_in(temp.path(), &good).expect("validate").is_empty());
+
+        let bad = agents_dir.join("dup.md");
+        fs::write(&bad, "---\nname: reviewer\ntools: Read, Fly\nmodel: gpt-4\n---\n")
+            .expect("write agent");
+        let problems = validate_agent_in(temp.path(), &bad).expect("validate");
+        let fields: Vec<_> = problems.iter().filter_map(|p| p.field.as_deref()).collect();
+        assert_eq!(fields, vec!["description", "tools", "model", "name"]);
+
+        let bare = agents_dir.join("bare.md");
+        fs::write(&bare, "Just a prompt").expect("write agent");
+        assert_eq!(validate_agent_in(temp.path(), &bare).expect("validate").len(), 1);
+    }
+
+    #[test]
+    fn test_validate_skill() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let skill_dir = temp.path().join("skills/pdf-tools");
+        fs::create_dir_all(&skill_dir).expect("create skill dir");
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: Work with PDFs\n---\n",
+        )
+        .expect("write skill");
+        assert!(validate_skill_in(temp.path(), &skill_dir).expect("validate").is_empty());
+
+        let other_dir = temp.path().join("skills/claude-pdf");
+        fs::create_dir_all(&other_dir).expect("create skill dir");
+        fs::write(other_dir.join("SKILL.md"), "---\nname: claude-pdf\n---\n").expect("write");
+        let problems = validate_skill_in(temp.path(), &other_dir).expect("validate");
+        let messages: Vec<_> = problems.iter().map(|p| p.message.as_str()).collect();
+        assert!(messages.iter().any(|m| m.contains("reserved word 'claude'")));
+        assert!(messages.iter().any(|m| m.contains("Missing required field: description")));
+    }
 }
@@ -0,0 +1,300 @@
+/**
+ * [INPUT]: 依赖 crate::store (bookmarks.json 预写日志存储), crate::commands::sessions (read_session_messages), crate::commands::projects (decode_project_path), crate::security
+ * [OUTPUT]: 对外提供 bookmark_message, remove_bookmark, list_bookmarks 命令与 Bookmark/BookmarkFilter 类型
+ * [POS]: commands/ 模块的消息级书签，收藏关键回答并保存内容快照，无需重新搜索即可找回
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::importers::IMPORTED_PROJECT_PREFIX;
+use crate::commands::projects::decode_project_path;
+use crate::commands::sessions::read_session_messages;
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::store::{self, Schema};
+use crate::types::Message;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A starred message, with a copy of its content so the list never re-reads sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub project_id: String,
+    pub session_id: String,
+    pub uuid: String,
+    /// None for imported sessions
+    pub project_path: Option<String>,
+    pub note: Option<String>,
+    pub role: String,
+    pub content: String,
+    /// Message timestamp (RFC 3339)
+    pub timestamp: String,
+    /// Unix seconds when the message was first bookmarked
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BookmarkFilter {
+    pub project_id: Option<String>,
+    pub session_id: Option<String>,
+    /// Case-insensitive substring of the note or content
+    pub query: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct BookmarkStore {
+    /// Oldest first
+    bookmarks: Vec<Bookmark>,
+}
+
+const BOOKMARKS_SCHEMA: Schema = Schema::new(1);
+
+/// Serializes read-modify-write of bookmarks.json
+static BOOKMARKS_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn bookmarks_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("bookmarks.json")
+}
+
+fn load_bookmarks(path: &Path) -> Result<BookmarkStore, String> {
+    Ok(store::load_as(path, &BOOKMARKS_SCHEMA)?.unwrap_or_default())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn is_same(b: &Bookmark, project_id: &str, session_id: &str, uuid: &str) -> bool {
+    b.uuid == uuid && b.session_id == session_id && b.project_id == project_id
+}
+
+/// Add a bookmark for `message`, or update the note of an existing one
+fn upsert_bookmark(
+    path: &Path,
+    project_id: &str,
+    session_id: &str,
+    message: Message,
+    note: Option<String>,
+) -> Result<Bookmark, String> {
+    let _guard = BOOKMARKS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut data = load_bookmarks(path)?;
+    let note = note.filter(|n| !n.trim().is_empty());
+
+    let existing = data
+        .bookmarks
+        .iter_mut()
+        .find(|b| is_same(b, project_id, session_id, &message.uuid));
+    let bookmark = if let Some(existing) = existing {
+        existing.note = note;
+        existing.clone()
+    } else {
+        let bookmark = Bookmark {
+            project_id: project_id.to_string(),
+            session_id: session_id.to_string(),
+            uuid: message.uuid,
+            project_path: (!project_id.starts_with(IMPORTED_PROJECT_PREFIX))
+                .then(|| decode_project_path(project_id)),
+            note,
+            role: message.role,
+            content: message.content,
+            timestamp: message.timestamp,
+            created_at: now_secs(),
+        };
+        data.bookmarks.push(bookmark.clone());
+        bookmark
+    };
+    store::save(path, &BOOKMARKS_SCHEMA, &data)?;
+    Ok(bookmark)
+}
+
+/// Returns whether a bookmark was removed
+fn remove_bookmark_in(
+    path: &Path,
+    project_id: &str,
+    session_id: &str,
+    uuid: &str,
+) -> Result<bool, String> {
+    let _guard = BOOKMARKS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut data = load_bookmarks(path)?;
+    let before = data.bookmarks.len();
+    data.bookmarks
+        .retain(|b| !is_same(b, project_id, session_id, uuid));
+    if data.bookmarks.len() == before {
+        return Ok(false);
+    }
+    store::save(path, &BOOKMARKS_SCHEMA, &data)?;
+    Ok(true)
+}
+
+fn matches_filter(bookmark: &Bookmark, filter: &BookmarkFilter) -> bool {
+    if filter
+        .project_id
+        .as_ref()
+        .is_some_and(|p| *p != bookmark.project_id)
+    {
+        return false;
+    }
+    if filter
+        .session_id
+        .as_ref()
+        .is_some_and(|s| *s != bookmark.session_id)
+    {
+        return false;
+    }
+    match filter.query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => {
+            let query = query.to_lowercase();
+            bookmark.content.to_lowercase().contains(&query)
+                || bookmark
+                    .note
+                    .as_ref()
+                    .is_some_and(|n| n.to_lowercase().contains(&query))
+        }
+        _ => true,
+    }
+}
+
+/// Matching bookmarks, most recently added first
+fn list_bookmarks_in(path: &Path, filter: &BookmarkFilter) -> Result<Vec<Bookmark>, String> {
+    let data = load_bookmarks(path)?;
+    Ok(data
+        .bookmarks
+        .into_iter()
+        .rev()
+        .filter(|b| matches_filter(b, filter))
+        .take(filter.limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Star a message; bookmarking it again replaces the note
+#[tauri::command]
+pub async fn bookmark_message(
+    project_id: String,
+    session_id: String,
+    uuid: String,
+    note: Option<String>,
+) -> AppResult<Bookmark> {
+    tauri::async_runtime::spawn_blocking(move || -> AppResult<Bookmark> {
+        let message = read_session_messages(&project_id, &session_id)?
+            .into_iter()
+            .find(|m| m.uuid == uuid)
+            .ok_or_else(|| AppError::not_found("Message not found").with_context(uuid.clone()))?;
+        Ok(upsert_bookmark(
+            &bookmarks_path(),
+            &project_id,
+            &session_id,
+            message,
+            note,
+        )?)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Unstar a message; false when it wasn't bookmarked
+#[tauri::command]
+pub async fn remove_bookmark(
+    project_id: String,
+    session_id: String,
+    uuid: String,
+) -> AppResult<bool> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        remove_bookmark_in(&bookmarks_path(), &project_id, &session_id, &uuid)
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[tauri::command]
+pub async fn list_bookmarks(filter: Option<BookmarkFilter>) -> AppResult<Vec<Bookmark>> {
+    let filter = filter.unwrap_or_default();
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || list_bookmarks_in(&bookmarks_path(), &filter))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn message(uuid: &str, content: &str) -> Message {
+        Message {
+            uuid: uuid.to_string(),
+            role: "assistant".to_string(),
+            content: content.to_string(),
+            timestamp: "2026-10-16T09:00:00Z".to_string(),
+            is_meta: false,
+            is_tool: false,
+            line_number: 1,
+            thinking: None,
+        }
+    }
+
+    #[test]
+    fn test_bookmark_upsert_filter_and_remove() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("bookmarks.json");
+
+        upsert_bookmark(&path, "p1", "s1", message("a", "Use a RwLock here"), None).expect("add a");
+        upsert_bookmark(&path, "p2", "s2", message("b", "Final snippet"), None).expect("add b");
+        let updated = upsert_bookmark(
+            &path,
+            "p1",
+            "s1",
+            message("a", "Use a RwLock here"),
+            Some("locking explained".to_string()),
+        )
+        .expect("update a");
+        assert_eq!(updated.note.as_deref(), Some("locking explained"));
+
+        let all = list_bookmarks_in(&path, &BookmarkFilter::default()).expect("list");
+        let uuids: Vec<&str> = all.iter().map(|b| b.uuid.as_str()).collect();
+        assert_eq!(uuids, vec!["b", "a"]);
+
+        let by_note = BookmarkFilter {
+            query: Some("LOCKING".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(list_bookmarks_in(&path, &by_note).expect("list").len(), 1);
+        let by_project = BookmarkFilter {
+            project_id: Some("p2".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            list_bookmarks_in(&path, &by_project).expect("list")[0].content,
+            "Final snippet"
+        );
+
+        assert!(remove_bookmark_in(&path, "p1", "s1", "a").expect("remove"));
+        assert!(!remove_bookmark_in(&path, "p1", "s1", "a").expect("remove again"));
+        assert_eq!(
+            list_bookmarks_in(&path, &BookmarkFilter::default())
+                .expect("list")
+                .len(),
+            1
+        );
+    }
+}
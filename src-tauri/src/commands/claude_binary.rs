@@ -0,0 +1,418 @@
+/**
+ * [INPUT]: 依赖 std::process, serde_json, commands::version::run_shell_command (登录 shell PATH), crate::security
+ * [OUTPUT]: 对外提供 claude_program, with_preferred_claude 工具函数, detect_claude_installations, set_preferred_claude_binary 命令
+ * [POS]: commands/ 模块的 Claude CLI 安装探测，列出 PATH/常见目录/包管理器中的所有 claude，并决定 CLI 测试与 PTY 实际运行哪一个
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::version::run_shell_command;
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, RwLock};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaudeInstallMethod {
+    Native,
+    Npm,
+    Homebrew,
+    Bun,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeInstallation {
+    pub path: String,
+    /// Where `path` points after following symlinks
+    pub resolved_path: String,
+    /// None when `claude --version` failed
+    pub version: Option<String>,
+    pub method: ClaudeInstallMethod,
+    /// Reachable as `claude` through the login shell's PATH
+    pub on_path: bool,
+    /// The binary lovcode runs: the preferred one if set, otherwise the first on PATH
+    pub active: bool,
+    pub preferred: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+struct ClaudeBinarySettings {
+    /// Absolute path of the claude binary to run instead of the PATH lookup
+    #[serde(default)]
+    path: Option<String>,
+}
+
+static PREFERRED_BINARY: LazyLock<RwLock<Option<String>>> =
+    LazyLock::new(|| RwLock::new(load_settings(&settings_path()).path));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn settings_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("claude_binary.json")
+}
+
+fn load_settings(path: &Path) -> ClaudeBinarySettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(path: &Path, settings: &ClaudeBinarySettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content).map_err(|e| e.to_string())
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.is_file() && meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        meta.is_file()
+    }
+}
+
+/// Classify by the path as found and where it resolves to, e.g. /opt/homebrew/bin/claude
+/// pointing into Caskroom
+fn install_method(path: &Path, resolved: &Path) -> ClaudeInstallMethod {
+    let paths = format!("{}\n{}", path.display(), resolved.display());
+    let has = |needles: &[&str]| needles.iter().any(|n| paths.contains(n));
+    if has(&[
+        "node_modules",
+        ".nvm/",
+        "/npm",
+        ".npm-global",
+        "pnpm",
+        ".volta/",
+        ".claude/local/",
+    ]) {
+        ClaudeInstallMethod::Npm
+    } else if has(&[".bun/"]) {
+        ClaudeInstallMethod::Bun
+    } else if has(&["/Cellar/", "/Caskroom/", "homebrew", "linuxbrew"]) {
+        ClaudeInstallMethod::Homebrew
+    } else if has(&[".local/share/claude", ".local/bin/claude", ".claude-code"]) {
+        ClaudeInstallMethod::Native
+    } else {
+        ClaudeInstallMethod::Other
+    }
+}
+
+/// PATH as the user's login shell sets it up (nvm, brew shellenv, ...); GUI apps on macOS
+/// start with a minimal PATH
+fn login_shell_path() -> Option<OsString> {
+    const MARKER: &str = "__LOVCODE_PATH__";
+    let output = run_shell_command(&format!("printf '{}%s' \"$PATH\"", MARKER)).ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Interactive rc files may print their own output first
+    let path = stdout.rsplit_once(MARKER)?.1.trim();
+    (!path.is_empty()).then(|| OsString::from(path))
+}
+
+/// Install locations of the official installer and the common package managers
+fn known_locations(home: &Path) -> Vec<PathBuf> {
+    let mut locations: Vec<PathBuf> = [
+        ".local/bin/claude",
+        ".claude/local/claude",
+        ".npm-global/bin/claude",
+        ".bun/bin/claude",
+        ".volta/bin/claude",
+        ".local/share/pnpm/claude",
+        "Library/pnpm/claude",
+    ]
+    .iter()
+    .map(|rel| home.join(rel))
+    .collect();
+    locations.extend(
+        [
+            "/opt/homebrew/bin/claude",
+            "/usr/local/bin/claude",
+            "/home/linuxbrew/.linuxbrew/bin/claude",
+        ]
+        .iter()
+        .map(PathBuf::from),
+    );
+    // Every node version nvm manages has its own global bin
+    if let Ok(entries) = fs::read_dir(home.join(".nvm/versions/node")) {
+        let mut nvm: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path().join("bin/claude"))
+            .collect();
+        nvm.sort();
+        locations.extend(nvm);
+    }
+    locations
+}
+
+/// `npm prefix -g`/bin, wherever npm's global prefix was configured
+fn npm_global_location() -> Option<PathBuf> {
+    let output = run_shell_command("npm prefix -g 2>/dev/null").ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let prefix = stdout
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| l.starts_with('/'))?;
+    Some(Path::new(prefix).join("bin").join("claude"))
+}
+
+/// Keep executable candidates, once per resolved binary, in the order given.
+/// Returns (path, resolved path, on PATH).
+fn dedupe_candidates(
+    candidates: impl IntoIterator<Item = (PathBuf, bool)>,
+) -> Vec<(PathBuf, PathBuf, bool)> {
+    let mut found: Vec<(PathBuf, PathBuf, bool)> = Vec::new();
+    for (path, on_path) in candidates {
+        if !is_executable(&path) {
+            continue;
+        }
+        let resolved = path.canonicalize().unwrap_or_else(|_| path.clone());
+        match found.iter_mut().find(|(_, r, _)| *r == resolved) {
+            Some(existing) => existing.2 |= on_path,
+            None => found.push((path, resolved, on_path)),
+        }
+    }
+    found
+}
+
+fn binary_version(path: &Path, search_path: Option<&OsString>) -> Option<String> {
+    let mut cmd = Command::new(path);
+    cmd.arg("--version").stdin(Stdio::null());
+    // npm installs are `#!/usr/bin/env node` scripts and need node on PATH
+    if let Some(search_path) = search_path {
+        cmd.env("PATH", search_path);
+    }
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Output looks like "2.0.76 (Claude Code)"
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+fn scan_installations(preferred: Option<&str>) -> Vec<ClaudeInstallation> {
+    let search_path = login_shell_path().or_else(|| std::env::var_os("PATH"));
+    let mut candidates: Vec<(PathBuf, bool)> = search_path
+        .iter()
+        .flat_map(std::env::split_paths)
+        .map(|dir| (dir.join("claude"), true))
+        .collect();
+    if let Ok(home) = security::safe_home_dir() {
+        candidates.extend(known_locations(&home).into_iter().map(|p| (p, false)));
+    }
+    candidates.extend(npm_global_location().map(|p| (p, false)));
+    candidates.extend(preferred.map(|p| (PathBuf::from(p), false)));
+
+    let preferred_resolved = preferred.and_then(|p| Path::new(p).canonicalize().ok());
+    let found = dedupe_candidates(candidates);
+    let has_preferred = found
+        .iter()
+        .any(|(_, resolved, _)| Some(resolved) == preferred_resolved.as_ref());
+    let winner = if has_preferred {
+        preferred_resolved
+    } else {
+        found
+            .iter()
+            .find(|(_, _, on_path)| *on_path)
+            .map(|(_, r, _)| r.clone())
+    };
+
+    found
+        .into_iter()
+        .map(|(path, resolved, on_path)| ClaudeInstallation {
+            version: binary_version(&path, search_path.as_ref()),
+            method: install_method(&path, &resolved),
+            on_path,
+            active: winner.as_ref() == Some(&resolved),
+            preferred: has_preferred && winner.as_ref() == Some(&resolved),
+            path: path.to_string_lossy().to_string(),
+            resolved_path: resolved.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+/// The preferred claude binary, if one is set and still exists
+fn preferred_claude_binary() -> Option<String> {
+    PREFERRED_BINARY
+        .read()
+        .ok()?
+        .clone()
+        .filter(|path| is_executable(Path::new(path)))
+}
+
+/// Program to spawn for the Claude CLI: the preferred binary or a plain `claude` lookup
+pub fn claude_program() -> String {
+    preferred_claude_binary().unwrap_or_else(|| "claude".to_string())
+}
+
+fn rewrite_claude_command(command: &str, preferred: Option<&str>) -> String {
+    let trimmed = command.trim_start();
+    let invokes_claude = trimmed == "claude" || trimmed.starts_with("claude ");
+    match preferred {
+        Some(binary) if invokes_claude => {
+            format!(
+                "{}{}",
+                security::shell_escape(binary),
+                &trimmed["claude".len()..]
+            )
+        }
+        _ => command.to_string(),
+    }
+}
+
+/// Point a shell command line that starts with `claude` at the preferred binary
+pub fn with_preferred_claude(command: &str) -> String {
+    rewrite_claude_command(command, preferred_claude_binary().as_deref())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Every claude binary found on PATH, in common install locations and package manager
+/// prefixes; `active` marks the one lovcode runs
+#[tauri::command]
+pub async fn detect_claude_installations() -> AppResult<Vec<ClaudeInstallation>> {
+    let preferred = PREFERRED_BINARY
+        .read()
+        .map_err(|_| "Claude binary setting lock poisoned")?
+        .clone();
+    Ok(tauri::async_runtime::spawn_blocking(move || scan_installations(preferred.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?)
+}
+
+/// Run this claude binary instead of the first one on PATH; None or "" goes back to PATH
+#[tauri::command]
+pub fn set_preferred_claude_binary(path: Option<String>) -> AppResult<()> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(path) = &path {
+        if !Path::new(path).is_absolute() {
+            return Err(AppError::invalid_input(format!("Path must be absolute: {}", path)));
+        }
+        if !is_executable(Path::new(path)) {
+            return Err(AppError::invalid_input(format!(
+                "Not an executable file: {}",
+                path
+            )));
+        }
+    }
+    save_settings(
+        &settings_path(),
+        &ClaudeBinarySettings { path: path.clone() },
+    )
+    .map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    *PREFERRED_BINARY
+        .write()
+        .map_err(|_| "Claude binary setting lock poisoned")? = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_install_method() {
+        let method =
+            |path: &str, resolved: &str| install_method(Path::new(path), Path::new(resolved));
+        assert_eq!(
+            method(
+                "/Users/me/.local/bin/claude",
+                "/Users/me/.local/share/claude/versions/2.0.76"
+            ),
+            ClaudeInstallMethod::Native
+        );
+        assert_eq!(
+            method(
+                "/usr/local/bin/claude",
+                "/usr/local/lib/node_modules/@anthropic-ai/claude-code/cli.js"
+            ),
+            ClaudeInstallMethod::Npm
+        );
+        assert_eq!(
+            method(
+                "/opt/homebrew/bin/claude",
+                "/opt/homebrew/Caskroom/claude-code/2.0.76/claude"
+            ),
+            ClaudeInstallMethod::Homebrew
+        );
+        assert_eq!(
+            method("/Users/me/.bun/bin/claude", "/Users/me/.bun/bin/claude"),
+            ClaudeInstallMethod::Bun
+        );
+        assert_eq!(
+            method("/usr/bin/claude", "/usr/bin/claude"),
+            ClaudeInstallMethod::Other
+        );
+    }
+
+    #[test]
+    fn test_rewrite_claude_command() {
+        let binary = Some("/Users/me/My Tools/claude");
+        assert_eq!(
+            rewrite_claude_command("claude --resume abc", binary),
+            "'/Users/me/My Tools/claude' --resume abc"
+        );
+        assert_eq!(
+            rewrite_claude_command("claude", binary),
+            "'/Users/me/My Tools/claude'"
+        );
+        assert_eq!(rewrite_claude_command("claudette", binary), "claudette");
+        assert_eq!(rewrite_claude_command("npm run dev", binary), "npm run dev");
+        assert_eq!(rewrite_claude_command("claude -c", None), "claude -c");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedupe_candidates_follows_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().expect("create temp dir");
+        let real = temp.path().join("versions").join("claude");
+        fs::create_dir_all(real.parent().expect("parent")).expect("create dir");
+        fs::write(&real, "#!/bin/sh\necho 2.0.76\n").expect("write binary");
+        fs::set_permissions(&real, fs::Permissions::from_mode(0o755)).expect("chmod");
+        let link = temp.path().join("claude");
+        std::os::unix::fs::symlink(&real, &link).expect("symlink");
+        let plain = temp.path().join("notes.txt");
+        fs::write(&plain, "not executable").expect("write file");
+
+        let found = dedupe_candidates([
+            (link.clone(), false),
+            (plain, true),
+            (real.clone(), true),
+            (temp.path().join("missing"), true),
+        ]);
+        assert_eq!(found.len(), 1);
+        let (path, resolved, on_path) = &found[0];
+        assert_eq!(path, &link);
+        assert_eq!(resolved, &real.canonicalize().expect("canonicalize"));
+        assert!(on_path);
+        assert_eq!(binary_version(&link, None).as_deref(), Some("2.0.76"));
+    }
+}
@@ -0,0 +1,492 @@
+/**
+ * [INPUT]: 依赖 ignore::WalkBuilder (仓库扫描), crate::diagnostics (技术栈检测), commands::project_setup (构建/测试命令), commands::file_diff (预览 diff), services::distill (claude -p)
+ * [OUTPUT]: 对外提供 generate_claude_md 命令, scan_repository / render_template 函数, RepoScan/ClaudeMdDraft 类型
+ * [POS]: commands/ 模块的 CLAUDE.md 草稿生成器，扫描语言/清单/命令/目录结构，经 claude -p 或本地模板产出草稿，由前端预览 diff 后交给 update_memory 写入
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::file_diff::unified_diff;
+use crate::commands::project_setup::stack_commands;
+use crate::diagnostics::{self, TechStack};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use crate::services::distill::run_claude_print;
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files looked at when counting languages and directory sizes
+const MAX_SCAN_FILES: usize = 20_000;
+
+/// Languages listed in the draft
+const MAX_LANGUAGES: usize = 8;
+
+/// README lines passed to the model
+const README_EXCERPT_LINES: usize = 60;
+
+/// Build and dependency manifests reported in the scan (root or one level down)
+const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "requirements.txt",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Gemfile",
+    "composer.json",
+    "Package.swift",
+    "mix.exs",
+    "CMakeLists.txt",
+    "Makefile",
+    "Dockerfile",
+    "docker-compose.yml",
+];
+
+/// Make targets worth listing as project commands
+const MAKE_TARGETS: &[(&str, &str)] = &[
+    ("Build", "build"),
+    ("Test", "test"),
+    ("Lint", "lint"),
+    ("Format", "fmt"),
+];
+
+const GENERATE_PROMPT: &str = "Write a CLAUDE.md for the repository described below. \
+Claude Code reads this file at the start of every session in the repo, so it should tell \
+an AI coding assistant what it can't quickly infer: what the project is, the exact commands \
+to build, test and lint, how the code is organised, and conventions to follow.
+
+Keep it concise Markdown with a `# <project name>` title. Only state what the scan or README \
+supports; where the maintainer needs to fill something in, leave an HTML comment. \
+Reply with the file content only, no preamble.
+
+## Repository scan (also a starting draft)
+
+{{draft}}
+
+## README excerpt
+
+{{readme}}
+";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageStat {
+    pub language: String,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectCommand {
+    pub label: String,
+    pub command: String,
+}
+
+/// A top-level directory and the files under it
+#[derive(Debug, Clone, Serialize)]
+pub struct LayoutEntry {
+    pub path: String,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoScan {
+    pub name: String,
+    pub stack: TechStack,
+    /// Most common languages first
+    pub languages: Vec<LanguageStat>,
+    /// Project-relative manifest paths
+    pub manifests: Vec<String>,
+    pub commands: Vec<ProjectCommand>,
+    pub layout: Vec<LayoutEntry>,
+    /// Stopped at MAX_SCAN_FILES
+    pub truncated: bool,
+    #[serde(skip)]
+    readme: Option<String>,
+}
+
+/// Draft CLAUDE.md, not yet written. Save it with `update_memory` (scope "project").
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeMdDraft {
+    /// <project>/CLAUDE.md
+    pub path: String,
+    pub content: String,
+    /// Current CLAUDE.md, if any
+    pub existing: Option<String>,
+    /// Unified diff from the current file to the draft
+    pub diff: String,
+    /// "model" | "template"
+    pub generator: String,
+    /// Why the model run failed and the template was used instead
+    pub model_error: Option<String>,
+    pub scan: RepoScan,
+}
+
+// ============================================================================
+// Repository Scan
+// ============================================================================
+
+fn language_for(ext: &str) -> Option<&'static str> {
+    Some(match ext {
+        "rs" => "Rust",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "py" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "cs" => "C#",
+        "dart" => "Dart",
+        "ex" | "exs" => "Elixir",
+        "scala" => "Scala",
+        "lua" => "Lua",
+        "zig" => "Zig",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "html" => "HTML",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        _ => return None,
+    })
+}
+
+fn make_commands(root: &Path) -> Vec<ProjectCommand> {
+    let Ok(makefile) = fs::read_to_string(root.join("Makefile")) else {
+        return Vec::new();
+    };
+    MAKE_TARGETS
+        .iter()
+        .filter(|(_, target)| {
+            makefile.lines().any(|line| {
+                line.strip_prefix(target)
+                    .is_some_and(|rest| rest.starts_with(':'))
+            })
+        })
+        .map(|(label, target)| ProjectCommand {
+            label: (*label).to_string(),
+            command: format!("make {}", target),
+        })
+        .collect()
+}
+
+fn project_commands(root: &Path, stack: &TechStack) -> Vec<ProjectCommand> {
+    let mut commands: Vec<ProjectCommand> = stack_commands(root, stack)
+        .into_iter()
+        .map(|(label, command)| ProjectCommand { label, command })
+        .collect();
+    if root.join("go.mod").is_file() {
+        for (label, command) in [("Build", "go build ./..."), ("Test", "go test ./...")] {
+            commands.push(ProjectCommand {
+                label: label.to_string(),
+                command: command.to_string(),
+            });
+        }
+    }
+    commands.extend(make_commands(root));
+    commands
+}
+
+fn readme_excerpt(root: &Path) -> Option<String> {
+    ["README.md", "README", "readme.md", "README.rst"]
+        .iter()
+        .find_map(|name| fs::read_to_string(root.join(name)).ok())
+        .map(|content| {
+            content
+                .lines()
+                .take(README_EXCERPT_LINES)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|excerpt| !excerpt.trim().is_empty())
+}
+
+/// Scan a project for its stack, languages, manifests, commands and top-level layout.
+/// Respects .gitignore and skips hidden files.
+pub fn scan_repository(root: &Path) -> Result<RepoScan, String> {
+    let stack = diagnostics::detect_tech_stack(&root.to_string_lossy())?;
+
+    let mut languages: HashMap<&'static str, usize> = HashMap::new();
+    let mut layout: BTreeMap<String, usize> = BTreeMap::new();
+    let mut manifests = Vec::new();
+    let mut files = 0;
+    let mut truncated = false;
+    for entry in WalkBuilder::new(root).require_git(false).build().flatten() {
+        let Ok(rel) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let Some(top) = rel.components().next() else {
+            continue;
+        };
+        let is_file = entry.file_type().is_some_and(|t| t.is_file());
+        if entry.depth() == 1 && !is_file {
+            layout
+                .entry(top.as_os_str().to_string_lossy().to_string())
+                .or_default();
+        }
+        if !is_file {
+            continue;
+        }
+        if files >= MAX_SCAN_FILES {
+            truncated = true;
+            break;
+        }
+        files += 1;
+
+        if entry.depth() <= 2 && MANIFESTS.iter().any(|m| entry.file_name() == *m) {
+            manifests.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+        if let Some(language) = rel
+            .extension()
+            .and_then(|e| language_for(&e.to_string_lossy().to_lowercase()))
+        {
+            *languages.entry(language).or_default() += 1;
+        }
+        if entry.depth() > 1 {
+            *layout
+                .entry(top.as_os_str().to_string_lossy().to_string())
+                .or_default() += 1;
+        }
+    }
+
+    let mut languages: Vec<LanguageStat> = languages
+        .into_iter()
+        .map(|(language, files)| LanguageStat {
+            language: language.to_string(),
+            files,
+        })
+        .collect();
+    languages.sort_by(|a, b| b.files.cmp(&a.files).then(a.language.cmp(&b.language)));
+    languages.truncate(MAX_LANGUAGES);
+    manifests.sort();
+
+    Ok(RepoScan {
+        name: root.file_name().map_or_else(
+            || "Project".to_string(),
+            |n| n.to_string_lossy().to_string(),
+        ),
+        commands: project_commands(root, &stack),
+        stack,
+        languages,
+        manifests,
+        layout: layout
+            .into_iter()
+            .map(|(path, files)| LayoutEntry { path, files })
+            .collect(),
+        truncated,
+        readme: readme_excerpt(root),
+    })
+}
+
+// ============================================================================
+// Draft Generation
+// ============================================================================
+
+/// CLAUDE.md built from the scan alone, with comments where the maintainer fills in
+pub fn render_template(scan: &RepoScan) -> String {
+    let mut lines = vec![format!("# {}", scan.name), String::new()];
+    lines.push("<!-- One paragraph: what this project is and who uses it -->".to_string());
+
+    lines.extend([String::new(), "## Stack".to_string(), String::new()]);
+    if !scan.languages.is_empty() {
+        let languages: Vec<String> = scan
+            .languages
+            .iter()
+            .map(|l| format!("{} ({} files)", l.language, l.files))
+            .collect();
+        lines.push(format!("- Languages: {}", languages.join(", ")));
+    }
+    lines.push(format!("- Runtime: {}", scan.stack.runtime));
+    if let Some(pm) = &scan.stack.package_manager {
+        lines.push(format!("- Package manager: {}", pm));
+    }
+    if !scan.stack.frameworks.is_empty() {
+        lines.push(format!(
+            "- Frameworks: {}",
+            scan.stack.frameworks.join(", ")
+        ));
+    }
+    if let Some(orm) = &scan.stack.orm {
+        lines.push(format!("- ORM: {}", orm));
+    }
+    if !scan.manifests.is_empty() {
+        let manifests: Vec<String> = scan.manifests.iter().map(|m| format!("`{}`", m)).collect();
+        lines.push(format!("- Manifests: {}", manifests.join(", ")));
+    }
+
+    lines.extend([String::new(), "## Commands".to_string(), String::new()]);
+    if scan.commands.is_empty() {
+        lines.push("<!-- How to build, test and run the project -->".to_string());
+    }
+    for command in &scan.commands {
+        lines.push(format!("- {}: `{}`", command.label, command.command));
+    }
+
+    lines.extend([String::new(), "## Architecture".to_string(), String::new()]);
+    for entry in &scan.layout {
+        lines.push(format!(
+            "- `{}/` ({} files) <!-- purpose -->",
+            entry.path, entry.files
+        ));
+    }
+    if scan.layout.is_empty() {
+        lines.push("<!-- Main modules and how they fit together -->".to_string());
+    }
+
+    lines.extend([
+        String::new(),
+        "## Conventions".to_string(),
+        String::new(),
+        "<!-- Style, naming and review rules Claude should follow -->".to_string(),
+    ]);
+    lines.join("\n") + "\n"
+}
+
+/// Drop a ```markdown fence the model may wrap its reply in
+fn strip_code_fence(output: &str) -> &str {
+    let trimmed = output.trim();
+    trimmed
+        .strip_prefix("```markdown")
+        .or_else(|| trimmed.strip_prefix("```md"))
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map_or(trimmed, str::trim)
+}
+
+fn draft(root: &Path, use_model: bool) -> Result<ClaudeMdDraft, String> {
+    let scan = scan_repository(root)?;
+    let template = render_template(&scan);
+
+    let (content, generator, model_error) = if use_model {
+        let prompt = GENERATE_PROMPT
+            .replace("{{readme}}", scan.readme.as_deref().unwrap_or("(none)"))
+            .replace("{{draft}}", &template);
+        // Run outside the project so the headless run is not filed under its sessions
+        let cwd = security::get_lovstudio_dir_or_fallback();
+        fs::create_dir_all(&cwd).map_err(|e| e.to_string())?;
+        match run_claude_print(&prompt, &cwd) {
+            Ok(output) => (format!("{}\n", strip_code_fence(&output)), "model", None),
+            Err(e) => {
+                tracing::warn!("CLAUDE.md generation fell back to the template: {}", e);
+                (template, "template", Some(e))
+            }
+        }
+    } else {
+        (template, "template", None)
+    };
+
+    let path = root.join("CLAUDE.md");
+    let existing = fs::read_to_string(&path).ok();
+    let diff = unified_diff(
+        existing.as_deref().unwrap_or(""),
+        &content,
+        "CLAUDE.md",
+        "CLAUDE.md (draft)",
+    );
+    Ok(ClaudeMdDraft {
+        path: path.to_string_lossy().to_string(),
+        content,
+        existing,
+        diff,
+        generator: generator.to_string(),
+        model_error,
+        scan,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Draft a CLAUDE.md for a project.
+///
+/// With `use_model` the draft is written by `claude -p` (the user's configured model),
+/// falling back to the local template. Nothing is written; the frontend previews `diff`
+/// and saves through `update_memory`.
+#[tauri::command]
+pub async fn generate_claude_md(
+    project_path: String,
+    use_model: Option<bool>,
+) -> AppResult<ClaudeMdDraft> {
+    security::validate_decoded_path(&project_path)?;
+    let root = PathBuf::from(&project_path);
+    if !root.is_dir() {
+        return Err(AppError::not_found("Project directory not found").with_context(project_path));
+    }
+    tauri::async_runtime::spawn_blocking(move || draft(&root, use_model.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())?
+        // Only creating the directory for the headless run can fail
+        .map_err(|e| AppError::new(ErrorCode::Io, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_and_template_draft() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = temp.path().join("demo");
+        for (file, content) in [
+            ("Cargo.toml", "[package]\nname = \"demo\"\n"),
+            ("Makefile", "fmt:\n\tcargo fmt\nrelease: build\n"),
+            ("src/main.rs", "fn main() {}\n"),
+            ("src/lib.rs", ""),
+            ("web/app.ts", ""),
+            ("web/package.json", "{}"),
+            (".hidden/x.rs", ""),
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().expect("parent")).expect("dir");
+            fs::write(path, content).expect("write");
+        }
+
+        let scan = scan_repository(&root).expect("scan");
+        let languages: Vec<(&str, usize)> = scan
+            .languages
+            .iter()
+            .map(|l| (l.language.as_str(), l.files))
+            .collect();
+        assert_eq!(languages, [("Rust", 2), ("TypeScript", 1)]);
+        assert_eq!(
+            scan.manifests,
+            ["Cargo.toml", "Makefile", "web/package.json"]
+        );
+        let layout: Vec<(&str, usize)> = scan
+            .layout
+            .iter()
+            .map(|e| (e.path.as_str(), e.files))
+            .collect();
+        assert_eq!(layout, [("src", 2), ("web", 2)]);
+        let commands: Vec<&str> = scan.commands.iter().map(|c| c.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            ["cargo build", "cargo test", "cargo clippy", "make fmt"]
+        );
+
+        fs::write(root.join("CLAUDE.md"), "# demo\n").expect("existing");
+        let draft = draft(&root, false).expect("draft");
+        assert_eq!(draft.generator, "template");
+        assert!(draft.content.starts_with("# demo\n"));
+        assert!(draft.content.contains("- Test: `cargo test`\n"));
+        assert!(draft.content.contains("- `src/` (2 files)"));
+        assert_eq!(draft.existing.as_deref(), Some("# demo\n"));
+        assert!(draft.diff.contains("+## Commands"));
+
+        assert_eq!(strip_code_fence("```markdown\n# A\n```\n"), "# A");
+        assert_eq!(strip_code_fence("# A\n"), "# A");
+    }
+}
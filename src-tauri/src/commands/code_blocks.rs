@@ -0,0 +1,193 @@
+/**
+ * [INPUT]: 依赖 pulldown-cmark (Markdown 解析), crate::commands::sessions (read_session_messages), crate::types::Message
+ * [OUTPUT]: 对外提供 extract_code_blocks 命令与 CodeBlock 类型
+ * [POS]: commands/ 模块的会话代码片段提取，从助手消息中取出围栏代码块并附带语言与提问上下文，支撑"本会话片段"面板与一键复制
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::sessions::read_session_messages;
+use crate::errors::AppResult;
+use crate::types::Message;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A fenced code block from an assistant message
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeBlock {
+    /// uuid of the assistant message
+    pub message_uuid: String,
+    /// Line in the session JSONL
+    pub line_number: usize,
+    pub timestamp: String,
+    /// Position of the block within its message, from 0
+    pub index: usize,
+    /// First word of the fence info string, e.g. "rust" for ```rust
+    pub language: Option<String>,
+    pub code: String,
+    /// The user prompt the message answered
+    pub prompt: Option<String>,
+    /// Last paragraph of the message text before the block, e.g. "Here's the fixed loop:"
+    pub context: Option<String>,
+}
+
+const CONTEXT_CHARS: usize = 200;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let mut chars = text.chars();
+    let head: String = chars.by_ref().take(max_chars).collect();
+    if chars.next().is_some() {
+        format!("{}…", head)
+    } else {
+        head
+    }
+}
+
+/// Last non-empty paragraph of `text`
+fn last_paragraph(text: &str) -> Option<String> {
+    text.trim_end()
+        .rsplit("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty())
+        .map(|p| truncate(p, CONTEXT_CHARS))
+}
+
+/// (language, code, byte offset of the opening fence) for each fenced block
+fn fenced_blocks(text: &str) -> Vec<(Option<String>, String, usize)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, String, usize)> = None;
+    for (event, range) in Parser::new_ext(text, Options::empty()).into_offset_iter() {
+        match (event, current.as_mut()) {
+            (Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))), _) => {
+                let language = info
+                    .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+                    .next()
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string);
+                current = Some((language, String::new(), range.start));
+            }
+            (Event::Text(chunk), Some((_, code, _))) => code.push_str(&chunk),
+            (Event::End(TagEnd::CodeBlock), Some(_)) => {
+                if let Some(block) = current.take() {
+                    blocks.push(block);
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+fn collect_code_blocks(messages: &[Message]) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut prompt: Option<String> = None;
+
+    for message in messages.iter().filter(|m| !m.is_meta) {
+        match message.role.as_str() {
+            // Tool results come back as user messages; they aren't prompts
+            "user" if !message.is_tool => {
+                prompt = Some(truncate(message.content.trim(), CONTEXT_CHARS));
+            }
+            "assistant" => {
+                for (index, (language, code, start)) in
+                    fenced_blocks(&message.content).into_iter().enumerate()
+                {
+                    if code.trim().is_empty() {
+                        continue;
+                    }
+                    blocks.push(CodeBlock {
+                        message_uuid: message.uuid.clone(),
+                        line_number: message.line_number,
+                        timestamp: message.timestamp.clone(),
+                        index,
+                        language,
+                        code,
+                        prompt: prompt.clone(),
+                        context: last_paragraph(&message.content[..start]),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Fenced code blocks from a session's assistant messages, in order
+#[tauri::command]
+pub async fn extract_code_blocks(
+    project_id: String,
+    session_id: String,
+) -> AppResult<Vec<CodeBlock>> {
+    tauri::async_runtime::spawn_blocking(move || {
+        read_session_messages(&project_id, &session_id).map(|m| collect_code_blocks(&m))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str, is_tool: bool, line_number: usize) -> Message {
+        Message {
+            uuid: format!("m{}", line_number),
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: String::new(),
+            is_meta: false,
+            is_tool,
+            line_number,
+            thinking: None,
+        }
+    }
+
+    #[test]
+    fn test_collect_code_blocks() {
+        let answer = "Two steps.\n\nHere's the fixed loop:\n\n```rust title=\"main.rs\"\nfor i in 0..3 {}\n```\n\nThen run:\n\n~~~\ncargo test\n~~~\n\n```\n```\n\nInline `code` is ignored.";
+        let messages = vec![
+            message("user", "Fix the loop", false, 1),
+            message("assistant", answer, false, 2),
+            message("user", "```txt\nnot a prompt\n```", true, 3),
+            message("assistant", "````md\n```js\nnested\n```\n````", false, 4),
+        ];
+
+        let blocks = collect_code_blocks(&messages);
+        assert_eq!(blocks.len(), 3);
+
+        assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[0].code, "for i in 0..3 {}\n");
+        assert_eq!(blocks[0].prompt.as_deref(), Some("Fix the loop"));
+        assert_eq!(blocks[0].context.as_deref(), Some("Here's the fixed loop:"));
+        assert_eq!(
+            (blocks[0].message_uuid.as_str(), blocks[0].index),
+            ("m2", 0)
+        );
+
+        assert_eq!(blocks[1].language, None);
+        assert_eq!(blocks[1].code, "cargo test\n");
+        assert_eq!(blocks[1].context.as_deref(), Some("Then run:"));
+
+        // Tool results don't replace the prompt; nested fences stay in the outer block
+        assert_eq!(blocks[2].language.as_deref(), Some("md"));
+        assert_eq!(blocks[2].code, "```js\nnested\n```\n");
+        assert_eq!(blocks[2].prompt.as_deref(), Some("Fix the loop"));
+        assert_eq!(blocks[2].context, None);
+    }
+}
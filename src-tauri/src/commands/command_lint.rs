@@ -0,0 +1,317 @@
+/**
+ * [INPUT]: 依赖 std::path, std::collections::HashMap, crate::types::LocalCommand, commands::local_commands, commands::report
+ * [OUTPUT]: 对外提供 lint_command, lint_all_commands 命令
+ * [POS]: commands/ 模块的命令质量检查器，复用 local_commands 的校验逻辑
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::local_commands::{
+    list_local_commands, list_project_commands, parse_frontmatter, resolve_claude_dir_for,
+    split_allowed_tools, validate_command_frontmatter,
+};
+use crate::commands::report::normalize_command_key;
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::types::LocalCommand;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CommandLintWarning {
+    pub path: String,
+    pub command: String,
+    pub rule: String,     // e.g. "missing-description", "duplicate-alias"
+    pub severity: String, // "error" | "warning"
+    pub message: String,
+}
+
+/// Frontmatter keys understood by Claude Code or by lovcode itself
+const KNOWN_FRONTMATTER_KEYS: &[&str] = &[
+    "description",
+    "allowed-tools",
+    "argument-hint",
+    "model",
+    "disable-model-invocation",
+    "version",
+    "aliases",
+    "replaced-by",
+    "deprecation-note",
+];
+
+/// allowed-tools entries that grant unrestricted shell access
+const BROAD_TOOL_PATTERNS: &[&str] = &["Bash", "Bash(*)", "Bash(:*)", "Bash(*:*)"];
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Lint a single command against the rest of its command tree
+#[tauri::command]
+pub fn lint_command(path: String) -> AppResult<Vec<CommandLintWarning>> {
+    let file = PathBuf::from(&path);
+    if !file.exists() {
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
+    }
+
+    let tree = load_command_tree_for(&file)?;
+    let command = tree
+        .iter()
+        .find(|c| Path::new(&c.path) == file)
+        .ok_or_else(|| {
+            AppError::invalid_input(format!("Not a command in a commands directory: {}", path))
+        })?;
+
+    Ok(lint_one(command, &tree))
+}
+
+/// Lint every active command (user scope, or a project's commands when project_path is set)
+#[tauri::command]
+pub fn lint_all_commands(project_path: Option<String>) -> AppResult<Vec<CommandLintWarning>> {
+    let tree = match project_path {
+        Some(p) => list_project_commands(p)?,
+        None => list_local_commands()?,
+    };
+
+    Ok(tree
+        .iter()
+        .filter(|c| c.status == "active")
+        .flat_map(|c| lint_one(c, &tree))
+        .collect())
+}
+
+// ============================================================================
+// Rules
+// ============================================================================
+
+fn load_command_tree_for(file: &Path) -> AppResult<Vec<LocalCommand>> {
+    let claude_dir = resolve_claude_dir_for(file);
+    if claude_dir == security::get_claude_dir_or_fallback() {
+        return list_local_commands();
+    }
+
+    let project = claude_dir
+        .parent()
+        .ok_or_else(|| AppError::invalid_input("Cannot determine project directory"))?;
+    list_project_commands(project.to_string_lossy().to_string())
+}
+
+fn lint_one(cmd: &LocalCommand, tree: &[LocalCommand]) -> Vec<CommandLintWarning> {
+    let mut warnings = Vec::new();
+    let mut warn = |rule: &str, severity: &str, message: String| {
+        warnings.push(CommandLintWarning {
+            path: cmd.path.clone(),
+            command: cmd.name.clone(),
+            rule: rule.to_string(),
+            severity: severity.to_string(),
+            message,
+        });
+    };
+
+    let fields = cmd
+        .frontmatter
+        .as_deref()
+        .map(|fm| parse_frontmatter(&format!("---\n{}\n---\n", fm)).0)
+        .unwrap_or_default();
+
+    // Missing description: Claude Code shows nothing useful in the slash menu
+    if fields.get("description").is_none_or(|d| d.trim().is_empty()) {
+        warn(
+            "missing-description",
+            "warning",
+            "No description in frontmatter".to_string(),
+        );
+    }
+
+    // Unknown frontmatter keys (usually typos like `allowed_tools`)
+    let mut unknown: Vec<&String> = fields
+        .keys()
+        .filter(|k| !KNOWN_FRONTMATTER_KEYS.contains(&k.as_str()))
+        .collect();
+    unknown.sort();
+    for key in unknown {
+        warn(
+            "unknown-key",
+            "warning",
+            format!("Unknown frontmatter key: {}", key),
+        );
+    }
+
+    // Malformed allowed-tools / argument-hint / description
+    if let Err(e) = validate_command_frontmatter(&fields) {
+        warn("invalid-frontmatter", "error", e);
+    }
+
+    // Overly broad allowed-tools
+    if let Some(tools) = fields.get("allowed-tools") {
+        for entry in split_allowed_tools(tools) {
+            if BROAD_TOOL_PATTERNS.contains(&entry.as_str()) {
+                warn(
+                    "broad-allowed-tools",
+                    "warning",
+                    format!(
+                        "'{}' allows any shell command; restrict it, e.g. Bash(git status:*)",
+                        entry
+                    ),
+                );
+            }
+        }
+    }
+
+    // Aliases claimed by another command (or shadowing another command's name)
+    for alias in &cmd.aliases {
+        let key = normalize_command_key(alias);
+        for other in tree.iter().filter(|o| o.path != cmd.path) {
+            let clashes = normalize_command_key(&other.name) == key
+                || other
+                    .aliases
+                    .iter()
+                    .any(|a| normalize_command_key(a) == key);
+            if clashes {
+                warn(
+                    "duplicate-alias",
+                    "warning",
+                    format!("Alias {} is also used by {}", alias, other.name),
+                );
+            }
+        }
+    }
+
+    // replaced-by must eventually lead to an active command
+    if let Some(target) = cmd.deprecated_by.as_deref().filter(|t| !t.is_empty()) {
+        if let Some(message) = check_replacement_chain(cmd, target, tree) {
+            warn("deprecated-reference", "warning", message);
+        }
+    }
+
+    warnings
+}
+
+/// Follow replaced-by links; report a missing target, a cycle, or a chain ending at a non-active command
+fn check_replacement_chain(
+    cmd: &LocalCommand,
+    first_target: &str,
+    tree: &[LocalCommand],
+) -> Option<String> {
+    let by_name: HashMap<String, &LocalCommand> = tree
+        .iter()
+        .map(|c| (normalize_command_key(&c.name), c))
+        .collect();
+
+    let mut visited = HashSet::from([normalize_command_key(&cmd.name)]);
+    let mut target = first_target.to_string();
+
+    loop {
+        let key = normalize_command_key(&target);
+        if !visited.insert(key.clone()) {
+            return Some(format!("replaced-by chain loops back to {}", target));
+        }
+
+        let Some(next) = by_name.get(&key) else {
+            return Some(format!("replaced-by points to unknown command {}", target));
+        };
+
+        if next.status == "active" {
+            return None;
+        }
+
+        match next.deprecated_by.as_deref().filter(|t| !t.is_empty()) {
+            Some(t) => target = t.to_string(),
+            None => {
+                return Some(format!(
+                    "replaced-by chain ends at {} command {}",
+                    next.status, next.name
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str, frontmatter: &str, status: &str) -> LocalCommand {
+        let (fields, raw, body) = parse_frontmatter(&format!("---\n{}\n---\n", frontmatter));
+        LocalCommand {
+            name: name.to_string(),
+            path: format!("/tmp/commands{}.md", name),
+            description: fields.get("description").cloned(),
+            allowed_tools: fields.get("allowed-tools").cloned(),
+            argument_hint: fields.get("argument-hint").cloned(),
+            content: body,
+            version: None,
+            status: status.to_string(),
+            deprecated_by: fields.get("replaced-by").cloned(),
+            changelog: None,
+            aliases: fields
+                .get("aliases")
+                .map(|a| a.split(',').map(|s| s.trim().to_string()).collect())
+                .unwrap_or_default(),
+            frontmatter: raw,
+            scope: "user".to_string(),
+            usage_count: 0,
+            last_used: None,
+        }
+    }
+
+    fn rules(warnings: &[CommandLintWarning]) -> Vec<&str> {
+        warnings.iter().map(|w| w.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_clean_command() {
+        let cmd = command(
+            "/git/commit",
+            "description: Commit changes\nallowed-tools: Bash(git commit:*)",
+            "active",
+        );
+        assert!(lint_one(&cmd, std::slice::from_ref(&cmd)).is_empty());
+    }
+
+    #[test]
+    fn test_lint_frontmatter_rules() {
+        let cmd = command("/deploy", "allowed_tools: Read\nallowed-tools: Bash, Fly", "active");
+        let warnings = lint_one(&cmd, std::slice::from_ref(&cmd));
+        assert_eq!(
+            rules(&warnings),
+            vec![
+                "missing-description",
+                "unknown-key",
+                "invalid-frontmatter",
+                "broad-allowed-tools"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_duplicate_alias() {
+        let a = command("/review", "description: Review\naliases: /old-review", "active");
+        let b = command("/review2", "description: Review v2\naliases: /old-review", "active");
+        let tree = vec![a.clone(), b];
+        assert_eq!(rules(&lint_one(&a, &tree)), vec!["duplicate-alias"]);
+    }
+
+    #[test]
+    fn test_lint_replacement_chain() {
+        let old = command("/old", "description: Old\nreplaced-by: /mid", "deprecated");
+        let mid = command("/mid", "description: Mid\nreplaced-by: /new", "deprecated");
+        let new = command("/new", "description: New", "active");
+
+        let tree = vec![old.clone(), mid.clone(), new];
+        assert!(lint_one(&old, &tree).is_empty());
+
+        let tree = vec![old.clone(), mid];
+        let warnings = lint_one(&old, &tree);
+        assert_eq!(rules(&warnings), vec!["deprecated-reference"]);
+        assert!(warnings[0].message.contains("unknown command /new"));
+
+        let looped = command("/mid", "description: Mid\nreplaced-by: /old", "deprecated");
+        let tree = vec![old.clone(), looped];
+        assert!(lint_one(&old, &tree)[0].message.contains("loops back"));
+    }
+}
@@ -1,15 +1,16 @@
 /**
  * [INPUT]: 依赖 std::fs, std::path, serde, crate::security, crate::commands::decode_project_path
- * [OUTPUT]: 对外提供 get_context_files, get_project_context 命令
+ * [OUTPUT]: 对外提供 get_context_files, get_project_context, get_expanded_context 命令
  * [POS]: commands/ 模块的上下文文件管理命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
 use crate::commands::decode_project_path;
+use crate::errors::AppResult;
 use crate::security;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // ============================================================================
 // Types
@@ -24,12 +25,29 @@ pub struct ContextFile {
     pub last_modified: u64,
 }
 
+/// A memory file with its `@path` imports resolved recursively
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContextNode {
+    pub name: String, // file name for roots, the import spec as written for imports
+    pub path: String,
+    pub scope: String, // "global" | "project" | "local" | "import"
+    pub content: String,
+    pub last_modified: u64,
+    pub depth: usize,
+    pub imports: Vec<ContextNode>,
+    /// Why this file was not loaded (missing, cycle, depth limit)
+    pub error: Option<String>,
+}
+
+/// Claude Code follows imports at most this many hops deep
+const MAX_IMPORT_DEPTH: usize = 5;
+
 // ============================================================================
 // Commands
 // ============================================================================
 
 #[tauri::command]
-pub fn get_context_files() -> Result<Vec<ContextFile>, String> {
+pub fn get_context_files() -> AppResult<Vec<ContextFile>> {
     let mut files = Vec::new();
 
     // Global CLAUDE.md
@@ -98,7 +116,7 @@ pub fn get_context_files() -> Result<Vec<ContextFile>, String> {
 }
 
 #[tauri::command]
-pub fn get_project_context(project_path: String) -> Result<Vec<ContextFile>, String> {
+pub fn get_project_context(project_path: String) -> AppResult<Vec<ContextFile>> {
     let mut files = Vec::new();
     let project_dir = PathBuf::from(&project_path);
 
@@ -179,3 +197,211 @@ pub fn get_project_context(project_path: String) -> Result<Vec<ContextFile>, Str
     files.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
     Ok(files)
 }
+
+/// Resolve the memory files Claude loads (user CLAUDE.md, plus the project's
+/// CLAUDE.md, .claude/CLAUDE.md and CLAUDE.local.md) and expand their `@path` imports
+#[tauri::command]
+pub fn get_expanded_context(project_path: Option<String>) -> AppResult<Vec<ContextNode>> {
+    let mut roots = vec![(
+        "CLAUDE.md".to_string(),
+        security::get_claude_dir_or_fallback().join("CLAUDE.md"),
+        "global",
+    )];
+
+    if let Some(project) = project_path.as_deref() {
+        security::validate_decoded_path(project)?;
+        let project_dir = Path::new(project);
+        roots.push(("CLAUDE.md".to_string(), project_dir.join("CLAUDE.md"), "project"));
+        roots.push((
+            ".claude/CLAUDE.md".to_string(),
+            project_dir.join(".claude").join("CLAUDE.md"),
+            "project",
+        ));
+        roots.push((
+            "CLAUDE.local.md".to_string(),
+            project_dir.join("CLAUDE.local.md"),
+            "local",
+        ));
+    }
+
+    Ok(roots
+        .into_iter()
+        .filter(|(_, path, _)| path.exists())
+        .map(|(name, path, scope)| expand_context_file(name, &path, scope, 0, &mut Vec::new()))
+        .collect())
+}
+
+// ============================================================================
+// Import Resolution
+// ============================================================================
+
+fn file_mtime(path: &Path) -> u64 {
+    fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Read `path` and expand its imports. `stack` holds the canonical paths of the
+/// files currently being expanded, so an import of an ancestor is reported as a cycle.
+fn expand_context_file(
+    name: String,
+    path: &Path,
+    scope: &str,
+    depth: usize,
+    stack: &mut Vec<PathBuf>,
+) -> ContextNode {
+    let mut node = ContextNode {
+        name,
+        path: path.to_string_lossy().to_string(),
+        scope: scope.to_string(),
+        content: String::new(),
+        last_modified: 0,
+        depth,
+        imports: Vec::new(),
+        error: None,
+    };
+
+    if depth > MAX_IMPORT_DEPTH {
+        node.error = Some(format!(
+            "Not loaded: imports are followed at most {} levels deep",
+            MAX_IMPORT_DEPTH
+        ));
+        return node;
+    }
+
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical) {
+        node.error = Some("Import cycle: file is already being loaded".to_string());
+        return node;
+    }
+
+    match fs::read_to_string(path) {
+        Ok(content) => node.content = content,
+        Err(e) => {
+            node.error = Some(format!("Failed to read file: {}", e));
+            return node;
+        }
+    }
+    node.last_modified = file_mtime(path);
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("/"));
+    stack.push(canonical);
+    node.imports = extract_imports(&node.content)
+        .into_iter()
+        .map(|spec| {
+            let target = resolve_import(&spec, base_dir);
+            expand_context_file(spec, &target, "import", depth + 1, stack)
+        })
+        .collect();
+    stack.pop();
+
+    node
+}
+
+/// Collect `@path` tokens, skipping fenced code blocks and inline code spans
+fn extract_imports(content: &str) -> Vec<String> {
+    let mut imports: Vec<String> = Vec::new();
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        // Even-numbered segments between backticks are outside inline code
+        let outside_code = line.split('`').step_by(2).collect::<Vec<_>>().join(" ");
+        for token in outside_code.split_whitespace() {
+            let Some(spec) = token.strip_prefix('@') else {
+                continue;
+            };
+            let spec = spec.trim_end_matches(['.', ',', ';', ':', ')']);
+            if !spec.is_empty() && !imports.iter().any(|i| i == spec) {
+                imports.push(spec.to_string());
+            }
+        }
+    }
+
+    imports
+}
+
+/// Imports are relative to the importing file; `~/` refers to the home directory
+fn resolve_import(spec: &str, base_dir: &Path) -> PathBuf {
+    if let Some(rest) = spec.strip_prefix("~/") {
+        if let Ok(home) = security::safe_home_dir() {
+            return home.join(rest);
+        }
+    }
+    let path = Path::new(spec);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_imports_skips_code() {
+        let content = "See @README.md and @docs/setup.md.\n\
+                       Email me at someone@example.com\n\
+                       Run `npm i @scope/pkg` first\n\
+                       ```\n@inside/fence.md\n```\n\
+                       Again @README.md";
+        assert_eq!(extract_imports(content), vec!["README.md", "docs/setup.md"]);
+    }
+
+    #[test]
+    fn test_expand_context_nested_and_cycle() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let root = temp.path().join("CLAUDE.md");
+        fs::create_dir_all(temp.path().join("docs")).expect("create docs");
+        fs::write(&root, "Root\n@docs/a.md\n@missing.md").expect("write root");
+        fs::write(temp.path().join("docs/a.md"), "A imports @../CLAUDE.md").expect("write a");
+
+        let node = expand_context_file("CLAUDE.md".to_string(), &root, "project", 0, &mut Vec::new());
+        assert!(node.error.is_none());
+        assert_eq!(node.imports.len(), 2);
+
+        let a = &node.imports[0];
+        assert_eq!(a.name, "docs/a.md");
+        assert_eq!(a.depth, 1);
+        assert!(a.content.starts_with("A imports"));
+        assert!(a.imports[0].error.as_deref().is_some_and(|e| e.contains("cycle")));
+
+        assert!(node.imports[1].error.as_deref().is_some_and(|e| e.contains("read")));
+    }
+
+    #[test]
+    fn test_expand_context_depth_limit() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        for i in 0..=MAX_IMPORT_DEPTH + 1 {
+            fs::write(temp.path().join(format!("{}.md", i)), format!("@{}.md", i + 1))
+                .expect("write chain file");
+        }
+
+        let mut node = expand_context_file(
+            "0.md".to_string(),
+            &temp.path().join("0.md"),
+            "project",
+            0,
+            &mut Vec::new(),
+        );
+        let mut depth = 0;
+        while node.error.is_none() {
+            node = node.imports.remove(0);
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_IMPORT_DEPTH + 1);
+        assert!(node.error.as_deref().is_some_and(|e| e.contains("levels deep")));
+    }
+}
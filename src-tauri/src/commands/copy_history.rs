@@ -0,0 +1,188 @@
+/**
+ * [INPUT]: 依赖 crate::store (copy-history.json 预写日志存储), crate::security
+ * [OUTPUT]: 对外提供 record_copy 工具函数 (供 copy_to_clipboard 调用), list_copy_history, clear_copy_history, get_copy_history_enabled, set_copy_history_enabled 命令与 CopySource/CopyHistoryItem 类型
+ * [POS]: commands/ 模块的剪贴板历史，可选开启，本地保存经 lovcode 复制的最近内容及其来源会话
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::AppResult;
+use crate::security;
+use crate::store::{self, Schema};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Where copied text came from; every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CopySource {
+    pub project_id: Option<String>,
+    pub session_id: Option<String>,
+    pub message_uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyHistoryItem {
+    pub text: String,
+    /// Unix seconds
+    pub copied_at: u64,
+    pub source: Option<CopySource>,
+    /// `text` was cut to MAX_ITEM_CHARS
+    pub truncated: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CopyHistoryStore {
+    /// Off until the user turns it on
+    #[serde(default)]
+    enabled: bool,
+    /// Newest first
+    #[serde(default)]
+    items: Vec<CopyHistoryItem>,
+}
+
+/// Items kept (oldest are dropped first)
+const MAX_ITEMS: usize = 50;
+const MAX_ITEM_CHARS: usize = 20_000;
+const COPY_HISTORY_SCHEMA: Schema = Schema::new(1);
+
+static ENABLED: LazyLock<AtomicBool> = LazyLock::new(|| {
+    AtomicBool::new(load_history(&history_path()).is_ok_and(|data| data.enabled))
+});
+
+/// Serializes read-modify-write of copy-history.json
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn history_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("copy-history.json")
+}
+
+fn load_history(path: &Path) -> Result<CopyHistoryStore, String> {
+    Ok(store::load_as(path, &COPY_HISTORY_SCHEMA)?.unwrap_or_default())
+}
+
+fn update_history(path: &Path, f: impl FnOnce(&mut CopyHistoryStore)) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut data = load_history(path)?;
+    f(&mut data);
+    store::save(path, &COPY_HISTORY_SCHEMA, &data)
+}
+
+fn push_item(data: &mut CopyHistoryStore, text: &str, source: Option<CopySource>, now: u64) {
+    let mut chars = text.chars();
+    let kept: String = chars.by_ref().take(MAX_ITEM_CHARS).collect();
+    let truncated = chars.next().is_some();
+    // Copying the same text again moves it to the top
+    data.items.retain(|item| item.text != kept);
+    data.items.insert(
+        0,
+        CopyHistoryItem {
+            text: kept,
+            copied_at: now,
+            source,
+            truncated,
+        },
+    );
+    data.items.truncate(MAX_ITEMS);
+}
+
+/// Remember text copied through the app, if the history is turned on. Failures are
+/// logged, never returned: the copy itself already succeeded.
+pub fn record_copy(text: &str, source: Option<CopySource>) {
+    if !ENABLED.load(Ordering::Relaxed) || text.is_empty() {
+        return;
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    if let Err(e) = update_history(&history_path(), |data| push_item(data, text, source, now)) {
+        tracing::warn!("Failed to record copy history: {}", e);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Copied items, newest first; empty while the history is off and was never used
+#[tauri::command]
+pub fn list_copy_history() -> AppResult<Vec<CopyHistoryItem>> {
+    Ok(load_history(&history_path())?.items)
+}
+
+#[tauri::command]
+pub fn clear_copy_history() -> AppResult<()> {
+    Ok(update_history(&history_path(), |data| data.items.clear())?)
+}
+
+#[tauri::command]
+pub fn get_copy_history_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turn recording on or off; items already recorded stay until cleared
+#[tauri::command]
+pub fn set_copy_history_enabled(enabled: bool) -> AppResult<()> {
+    update_history(&history_path(), |data| data.enabled = enabled)?;
+    ENABLED.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_push_item_dedupes_truncates_and_caps() {
+        let mut data = CopyHistoryStore::default();
+        let source = CopySource {
+            session_id: Some("s1".to_string()),
+            ..Default::default()
+        };
+        push_item(&mut data, "first", None, 1);
+        push_item(&mut data, "second", Some(source.clone()), 2);
+        push_item(&mut data, "first", None, 3);
+        let texts: Vec<&str> = data.items.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, vec!["first", "second"]);
+        assert_eq!(data.items[0].copied_at, 3);
+        assert_eq!(data.items[1].source, Some(source));
+
+        push_item(&mut data, &"x".repeat(MAX_ITEM_CHARS + 1), None, 4);
+        assert!(data.items[0].truncated);
+        assert_eq!(data.items[0].text.len(), MAX_ITEM_CHARS);
+
+        for n in 0..MAX_ITEMS {
+            push_item(&mut data, &n.to_string(), None, 5);
+        }
+        assert_eq!(data.items.len(), MAX_ITEMS);
+        assert_eq!(data.items[0].text, (MAX_ITEMS - 1).to_string());
+    }
+
+    #[test]
+    fn test_history_store_roundtrip() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("copy-history.json");
+        assert!(!load_history(&path).expect("load missing").enabled);
+
+        update_history(&path, |data| data.enabled = true).expect("enable");
+        update_history(&path, |data| push_item(data, "snippet", None, 1)).expect("push");
+        update_history(&path, |data| data.items.clear()).expect("clear");
+        let data = load_history(&path).expect("load");
+        assert!(data.enabled);
+        assert!(data.items.is_empty());
+    }
+}
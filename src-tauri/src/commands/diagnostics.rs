@@ -5,31 +5,35 @@
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 use crate::diagnostics::{self, EnvCheckResult, FileLineCount, TechStack};
+use crate::errors::{AppError, AppResult, ErrorCode};
 
 // ============================================================================
 // Diagnostics Commands
 // ============================================================================
 
 #[tauri::command]
-pub async fn diagnostics_detect_stack(project_path: String) -> Result<TechStack, String> {
-    tauri::async_runtime::spawn_blocking(move || diagnostics::detect_tech_stack(&project_path))
-        .await
-        .map_err(|e| e.to_string())?
+pub async fn diagnostics_detect_stack(project_path: String) -> AppResult<TechStack> {
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || diagnostics::detect_tech_stack(&project_path))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
 }
 
 #[tauri::command]
-pub async fn diagnostics_check_env(project_path: String) -> Result<EnvCheckResult, String> {
-    tauri::async_runtime::spawn_blocking(move || diagnostics::check_env_vars(&project_path))
-        .await
-        .map_err(|e| e.to_string())?
+pub async fn diagnostics_check_env(project_path: String) -> AppResult<EnvCheckResult> {
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || diagnostics::check_env_vars(&project_path))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
 }
 
 #[tauri::command]
-pub fn diagnostics_add_missing_keys(
-    project_path: String,
-    keys: Vec<String>,
-) -> Result<usize, String> {
+pub fn diagnostics_add_missing_keys(project_path: String, keys: Vec<String>) -> AppResult<usize> {
+    // Writing .env is the only thing that can fail
     diagnostics::add_missing_keys_to_env(&project_path, keys)
+        .map_err(|e| AppError::new(ErrorCode::Io, e))
 }
 
 #[tauri::command]
@@ -37,10 +41,10 @@ pub async fn diagnostics_scan_file_lines(
     project_path: String,
     limit: usize,
     ignored_paths: Vec<String>,
-) -> Result<Vec<FileLineCount>, String> {
-    tauri::async_runtime::spawn_blocking(move || {
+) -> AppResult<Vec<FileLineCount>> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
         diagnostics::scan_file_lines(&project_path, limit, &ignored_paths)
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())??)
 }
@@ -4,6 +4,7 @@
  * [POS]: 管理 AI Coding Agent 的执行配置
  * [PROTOCOL]: 变更时更新此头部，然后检查 commands/mod.rs
  */
+use crate::errors::{AppError, AppResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -55,7 +56,7 @@ fn get_builtin_profiles() -> ExecutorProfiles {
 
 /// 获取所有 Executor Profiles (扁平化列表)
 #[tauri::command]
-pub fn list_executor_profiles() -> Result<Vec<FlatProfile>, String> {
+pub fn list_executor_profiles() -> AppResult<Vec<FlatProfile>> {
     let profiles = get_builtin_profiles();
     let mut result = Vec::new();
 
@@ -87,13 +88,13 @@ pub fn list_executor_profiles() -> Result<Vec<FlatProfile>, String> {
 
 /// 获取指定 Agent 的所有 Profiles
 #[tauri::command]
-pub fn get_agent_profiles(agent: String) -> Result<Vec<FlatProfile>, String> {
+pub fn get_agent_profiles(agent: String) -> AppResult<Vec<FlatProfile>> {
     let profiles = get_builtin_profiles();
 
     let agent_config = profiles
         .agents
         .get(&agent)
-        .ok_or_else(|| format!("Unknown agent: {}", agent))?;
+        .ok_or_else(|| AppError::not_found(format!("Unknown agent: {}", agent)))?;
 
     let mut result: Vec<FlatProfile> = agent_config
         .profiles
@@ -132,7 +133,7 @@ pub fn get_agent_profiles(agent: String) -> Result<Vec<FlatProfile>, String> {
 
 /// 获取支持的 Agent 列表
 #[tauri::command]
-pub fn list_supported_agents() -> Result<Vec<(String, String)>, String> {
+pub fn list_supported_agents() -> AppResult<Vec<(String, String)>> {
     let profiles = get_builtin_profiles();
 
     let mut agents: Vec<(String, String)> = profiles
@@ -0,0 +1,110 @@
+/**
+ * [INPUT]: 依赖 similar (行级 diff), commands::files::read_file (路径校验/大小限制/二进制检测), crate::security
+ * [OUTPUT]: 对外提供 unified_diff 工具函数, diff_files, diff_against_content 命令
+ * [POS]: commands/ 模块的通用文本 diff，知识库、市场预演、设置预览与命令版本共用同一种输出
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::files::read_file;
+use crate::errors::AppResult;
+use crate::security;
+use similar::TextDiff;
+use std::path::Path;
+use std::time::Duration;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Lines of unchanged context around each hunk
+const CONTEXT_LINES: usize = 3;
+/// Past this, the diff may be less minimal but still correct
+const DIFF_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Label used for a side that doesn't exist (new or deleted file)
+const MISSING_LABEL: &str = "/dev/null";
+
+/// Unified diff with `---`/`+++` headers; empty when the texts are equal
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    TextDiff::configure()
+        .timeout(DIFF_TIMEOUT)
+        .diff_lines(old, new)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+/// Text of `path` and its diff label; a file that doesn't exist yet reads as empty
+fn read_side(path: &str) -> AppResult<(String, String)> {
+    if Path::new(path).exists() {
+        return Ok((read_file(path.to_string())?, path.to_string()));
+    }
+    security::validate_user_path(path)?;
+    Ok((String::new(), MISSING_LABEL.to_string()))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Unified diff from `path_a` to `path_b`; a missing file counts as empty
+#[tauri::command]
+pub fn diff_files(path_a: String, path_b: String) -> AppResult<String> {
+    let (old, old_label) = read_side(&path_a)?;
+    let (new, new_label) = read_side(&path_b)?;
+    Ok(unified_diff(&old, &new, &old_label, &new_label))
+}
+
+/// Unified diff from the file at `path` to `new_content`, e.g. to preview a write
+#[tauri::command]
+pub fn diff_against_content(path: String, new_content: String) -> AppResult<String> {
+    let (old, old_label) = read_side(&path)?;
+    Ok(unified_diff(&old, &new_content, &old_label, &path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_files() {
+        let temp = TempDir::new().expect("create temp dir");
+        let a = temp.path().join("a.md");
+        let b = temp.path().join("b.md");
+        fs::write(&a, "one\ntwo\nthree\n").expect("write a");
+        fs::write(&b, "one\n2\nthree\n").expect("write b");
+        let (a, b) = (
+            a.to_string_lossy().to_string(),
+            b.to_string_lossy().to_string(),
+        );
+
+        let diff = diff_files(a.clone(), b.clone()).expect("diff");
+        assert!(diff.starts_with(&format!("--- {}\n+++ {}\n", a, b)));
+        assert!(diff.contains("@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n"));
+
+        assert_eq!(diff_files(a.clone(), a).expect("same file"), "");
+    }
+
+    #[test]
+    fn test_diff_against_content_and_new_files() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("settings.json");
+        let path_str = path.to_string_lossy().to_string();
+
+        let created = diff_against_content(path_str.clone(), "{}\n".to_string()).expect("diff");
+        assert!(created.starts_with(&format!("--- /dev/null\n+++ {}\n", path_str)));
+        assert!(created.contains("+{}\n"));
+
+        fs::write(&path, "{}\n").expect("write settings");
+        let unchanged = diff_against_content(path_str, "{}\n".to_string()).expect("diff");
+        assert!(unchanged.is_empty());
+
+        assert!(diff_against_content("/etc/hosts".to_string(), String::new()).is_err());
+    }
+}
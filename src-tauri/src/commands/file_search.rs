@@ -0,0 +1,376 @@
+/**
+ * [INPUT]: 依赖 ignore (遵循 .gitignore 的遍历与 glob 覆盖), grep-regex/grep-searcher (内容搜索、二进制检测), serde, crate::security
+ * [OUTPUT]: 对外提供 find_files, grep_project 命令与 GrepOptions, FindFilesResult, GrepMatch, GrepResult
+ * [POS]: commands/ 模块的项目内搜索，供前端快速打开与全项目搜索，无需调用外部 rg
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::sinks::Lossy;
+use grep_searcher::{BinaryDetection, SearcherBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::{Walk, WalkBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GrepOptions {
+    pub case_insensitive: bool,
+    /// Treat the pattern as literal text instead of a regex
+    pub fixed_strings: bool,
+    pub whole_word: bool,
+    /// Only search files matching this glob, e.g. "*.rs" or "src/**"
+    pub glob: Option<String>,
+    /// Also search files excluded by .gitignore/.ignore
+    pub include_ignored: bool,
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FindFilesResult {
+    /// Paths relative to the project, sorted
+    pub files: Vec<String>,
+    /// More files matched than were returned
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrepMatch {
+    /// Path relative to the project
+    pub path: String,
+    pub line_number: u64,
+    /// Matching line without the line terminator, clipped around the match when long
+    pub line: String,
+    /// First match within `line`, in characters
+    pub match_start: usize,
+    pub match_end: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrepResult {
+    pub matches: Vec<GrepMatch>,
+    pub files_searched: usize,
+    /// Search stopped at the result limit
+    pub truncated: bool,
+}
+
+const DEFAULT_MAX_FILES: usize = 1_000;
+const DEFAULT_MAX_MATCHES: usize = 2_000;
+/// Larger files (logs, dumps, bundles) are not searched
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_LINE_CHARS: usize = 500;
+/// Characters kept before the match when a long line is clipped
+const LINE_CONTEXT_CHARS: usize = 100;
+
+/// Never searched, even with include_ignored
+const ALWAYS_SKIPPED: &[&str] = &[".git", ".DS_Store"];
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn project_root(project_path: &str) -> AppResult<PathBuf> {
+    security::validate_decoded_path(project_path)?;
+    let root = PathBuf::from(project_path);
+    if !root.is_dir() {
+        return Err(AppError::invalid_input(format!(
+            "Not a directory: {}",
+            project_path
+        )));
+    }
+    Ok(root)
+}
+
+/// Files under `root` in name order; `glob` uses .gitignore syntax, so "*.rs" matches
+/// at any depth and "src/*.rs" only under src/
+fn walk_project(root: &Path, glob: Option<&str>, include_ignored: bool) -> Result<Walk, String> {
+    let mut builder = WalkBuilder::new(root);
+    if let Some(glob) = glob.filter(|g| !g.trim().is_empty()) {
+        let mut overrides = OverrideBuilder::new(root);
+        overrides
+            .add(glob.trim())
+            .map_err(|e| format!("Invalid glob '{}': {}", glob, e))?;
+        let overrides = overrides
+            .build()
+            .map_err(|e| format!("Invalid glob '{}': {}", glob, e))?;
+        builder.overrides(overrides);
+    }
+    let respect_gitignore = !include_ignored;
+    Ok(builder
+        .hidden(false)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        .require_git(false)
+        .sort_by_file_name(std::cmp::Ord::cmp)
+        .filter_entry(|entry| {
+            !ALWAYS_SKIPPED
+                .iter()
+                .any(|name| entry.file_name() == std::ffi::OsStr::new(name))
+        })
+        .build())
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn find(root: &Path, glob: &str, max_results: usize) -> Result<FindFilesResult, String> {
+    let mut files = Vec::new();
+    let mut truncated = false;
+    for entry in walk_project(root, Some(glob), false)?.flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        if files.len() >= max_results {
+            truncated = true;
+            break;
+        }
+        files.push(relative_path(root, entry.path()));
+    }
+    Ok(FindFilesResult { files, truncated })
+}
+
+/// Clip a long line to a window around the match; returns the text and the match
+/// range in characters within it
+fn clip_line(line: &str, byte_start: usize, byte_end: usize) -> (String, usize, usize) {
+    let char_at = |byte: usize| line.get(..byte).map_or(0, |s| s.chars().count());
+    let (start, end) = (char_at(byte_start), char_at(byte_end));
+    let total = line.chars().count();
+    if total <= MAX_LINE_CHARS {
+        return (line.to_string(), start, end);
+    }
+    let offset = if end <= MAX_LINE_CHARS {
+        0
+    } else {
+        start
+            .saturating_sub(LINE_CONTEXT_CHARS)
+            .min(total - MAX_LINE_CHARS)
+    };
+    let clipped: String = line.chars().skip(offset).take(MAX_LINE_CHARS).collect();
+    let end = (end - offset).min(MAX_LINE_CHARS);
+    (clipped, start - offset, end)
+}
+
+fn build_matcher(pattern: &str, options: &GrepOptions) -> Result<RegexMatcher, String> {
+    RegexMatcherBuilder::new()
+        .case_insensitive(options.case_insensitive)
+        .fixed_strings(options.fixed_strings)
+        .word(options.whole_word)
+        .line_terminator(Some(b'\n'))
+        .build(pattern)
+        .map_err(|e| format!("Invalid pattern: {}", e))
+}
+
+fn grep(root: &Path, pattern: &str, options: &GrepOptions) -> Result<GrepResult, String> {
+    if pattern.is_empty() {
+        return Err("Pattern must not be empty".to_string());
+    }
+    let matcher = build_matcher(pattern, options)?;
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_MATCHES).max(1);
+    let mut searcher = SearcherBuilder::new()
+        // Stop at the first NUL byte: binary files produce no matches
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .line_number(true)
+        .build();
+
+    let mut matches: Vec<GrepMatch> = Vec::new();
+    let mut files_searched = 0;
+    let mut truncated = false;
+    let walker = walk_project(root, options.glob.as_deref(), options.include_ignored)?;
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|t| t.is_file())
+            || entry.metadata().map_or(true, |m| m.len() > MAX_FILE_BYTES)
+        {
+            continue;
+        }
+        files_searched += 1;
+        let path = relative_path(root, entry.path());
+        let sink = Lossy(|line_number, line: &str| {
+            if matches.len() >= max_results {
+                truncated = true;
+                return Ok(false);
+            }
+            let line = line.trim_end_matches(['\n', '\r']);
+            let Some(found) = matcher.find(line.as_bytes()).ok().flatten() else {
+                return Ok(true);
+            };
+            let (line, match_start, match_end) = clip_line(line, found.start(), found.end());
+            matches.push(GrepMatch {
+                path: path.clone(),
+                line_number,
+                line,
+                match_start,
+                match_end,
+            });
+            Ok(true)
+        });
+        // Unreadable files are skipped like ripgrep does
+        if let Err(e) = searcher.search_path(&matcher, entry.path(), sink) {
+            tracing::debug!(path = %entry.path().display(), error = %e, "Skipped file in search");
+        }
+        if truncated {
+            break;
+        }
+    }
+    Ok(GrepResult {
+        matches,
+        files_searched,
+        truncated,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Files in the project matching `glob` (.gitignore syntax), for quick-open
+///
+/// Ignored files and .git are skipped; at most `limit` (default 1000) paths are returned.
+#[tauri::command]
+pub async fn find_files(
+    project_path: String,
+    glob: String,
+    limit: Option<usize>,
+) -> AppResult<FindFilesResult> {
+    let root = project_root(&project_path)?;
+    if glob.trim().is_empty() {
+        return Err(AppError::invalid_input("Glob must not be empty"));
+    }
+    let max_results = limit.unwrap_or(DEFAULT_MAX_FILES).max(1);
+    // Only a bad glob makes find fail
+    tauri::async_runtime::spawn_blocking(move || find(&root, &glob, max_results))
+        .await
+        .map_err(|e| format!("Failed to find files: {}", e))?
+        .map_err(AppError::invalid_input)
+}
+
+/// Lines in project files matching `pattern`, ripgrep-style
+///
+/// Binary files and files over 10 MB are skipped. Search stops after
+/// `options.max_results` (default 2000) matches.
+#[tauri::command]
+pub async fn grep_project(
+    project_path: String,
+    pattern: String,
+    options: Option<GrepOptions>,
+) -> AppResult<GrepResult> {
+    let root = project_root(&project_path)?;
+    let options = options.unwrap_or_default();
+    // Only a bad pattern or glob makes grep fail
+    tauri::async_runtime::spawn_blocking(move || grep(&root, &pattern, &options))
+        .await
+        .map_err(|e| format!("Failed to search project: {}", e))?
+        .map_err(AppError::invalid_input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let temp = TempDir::new().expect("create temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("src/util")).expect("create dirs");
+        fs::create_dir_all(root.join("target")).expect("create target");
+        fs::write(root.join(".gitignore"), "target/\n").expect("write gitignore");
+        fs::write(root.join("src/main.rs"), "fn main() {\n    run();\n}\n").expect("write main");
+        fs::write(
+            root.join("src/util/run.rs"),
+            "pub fn run() {}\n// TODO: Run faster\n",
+        )
+        .expect("write run");
+        fs::write(root.join("target/out.rs"), "fn run() {}\n").expect("write target");
+        fs::write(root.join("README.md"), "Run with cargo run\n").expect("write readme");
+        fs::write(root.join("logo.bin"), b"run\x00\x01\x02run").expect("write binary");
+        temp
+    }
+
+    #[test]
+    fn test_find_files_glob_and_limit() {
+        let temp = project();
+        let root = temp.path();
+
+        let found = find(root, "*.rs", 100).expect("find");
+        assert_eq!(found.files, vec!["src/main.rs", "src/util/run.rs"]);
+        assert!(!found.truncated);
+
+        let anchored = find(root, "src/*.rs", 100).expect("find anchored");
+        assert_eq!(anchored.files, vec!["src/main.rs"]);
+
+        let limited = find(root, "*", 2).expect("find limited");
+        assert_eq!(limited.files.len(), 2);
+        assert!(limited.truncated);
+
+        assert!(find(root, "src/{", 10).is_err());
+    }
+
+    #[test]
+    fn test_grep_options_binary_and_limit() {
+        let temp = project();
+        let root = temp.path();
+
+        let result = grep(root, "run", &GrepOptions::default()).expect("grep");
+        let hits: Vec<(&str, u64)> = result
+            .matches
+            .iter()
+            .map(|m| (m.path.as_str(), m.line_number))
+            .collect();
+        // Binary logo.bin and ignored target/ produce nothing
+        assert_eq!(
+            hits,
+            vec![("README.md", 1), ("src/main.rs", 2), ("src/util/run.rs", 1)]
+        );
+        let readme = &result.matches[0];
+        assert_eq!((readme.match_start, readme.match_end), (15, 18));
+
+        let options = GrepOptions {
+            case_insensitive: true,
+            whole_word: true,
+            glob: Some("*.rs".to_string()),
+            ..GrepOptions::default()
+        };
+        let result = grep(root, "run", &options).expect("grep words");
+        assert_eq!(result.matches.len(), 3);
+        assert_eq!(result.matches[2].line, "// TODO: Run faster");
+
+        let options = GrepOptions {
+            include_ignored: true,
+            max_results: Some(1),
+            ..GrepOptions::default()
+        };
+        let result = grep(root, "fn run", &options).expect("grep limited");
+        assert_eq!(result.matches.len(), 1);
+        assert!(result.truncated);
+
+        assert!(grep(root, "(", &GrepOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_clip_line_keeps_match_visible() {
+        let line = format!("{}needle{}", "é".repeat(800), "x".repeat(1000));
+        let start = "é".len() * 800;
+        let (clipped, s, e) = clip_line(&line, start, start + 6);
+        assert_eq!(clipped.chars().count(), MAX_LINE_CHARS);
+        assert_eq!((s, e), (LINE_CONTEXT_CHARS, LINE_CONTEXT_CHARS + 6));
+        assert_eq!(
+            clipped.chars().skip(s).take(e - s).collect::<String>(),
+            "needle"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+/**
+ * [INPUT]: 依赖 trash (系统回收站), serde_json, crate::security
+ * [OUTPUT]: 对外提供 remove_path 工具函数, trash_path, get_permanent_delete, set_permanent_delete 命令
+ * [POS]: commands/ 模块的文件删除入口，默认移入系统回收站以便恢复，可在设置中改为永久删除
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+struct DeleteSettings {
+    /// Skip the OS trash and delete files for good
+    #[serde(default)]
+    permanent: bool,
+}
+
+static PERMANENT_DELETE: LazyLock<AtomicBool> =
+    LazyLock::new(|| AtomicBool::new(load_settings(&settings_path()).permanent));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn settings_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("file_delete.json")
+}
+
+fn load_settings(path: &Path) -> DeleteSettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(path: &Path, settings: &DeleteSettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content).map_err(|e| e.to_string())
+}
+
+fn remove_path_with(path: &Path, permanent: bool) -> Result<(), String> {
+    if !permanent {
+        return trash::delete(path).map_err(|e| format!("Failed to move to trash: {}", e));
+    }
+    let result = if path.is_dir() && !path.is_symlink() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+/// Refuse to delete an allowed root itself (home, /tmp, a whole project) or anything
+/// containing one, e.g. `/`; only paths inside a root may go
+fn reject_root_paths(target: &Path, roots: &[PathBuf]) -> Result<(), String> {
+    let real = target.canonicalize().unwrap_or_else(|_| target.to_path_buf());
+    let covers_root = roots.iter().any(|root| {
+        let real_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+        [target, real.as_path()]
+            .iter()
+            .any(|t| root.starts_with(t) || real_root.starts_with(t))
+    });
+    if covers_root {
+        return Err(format!("Refusing to delete {}", target.display()));
+    }
+    Ok(())
+}
+
+/// Delete a file or directory the way the user chose: into the OS trash (default)
+/// or permanently. Use this for every deletion of user content.
+pub fn remove_path(path: &Path) -> Result<(), String> {
+    remove_path_with(path, PERMANENT_DELETE.load(Ordering::Relaxed))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Move a file or folder to the OS trash (or delete it if permanent deletion is on)
+#[tauri::command]
+pub fn trash_path(path: String) -> AppResult<()> {
+    let target = security::validate_user_path(&path)?;
+    if target.symlink_metadata().is_err() {
+        return Err(AppError::not_found("Path does not exist").with_context(path));
+    }
+    reject_root_paths(&target, &security::user_path_roots()?)
+        .map_err(|e| AppError::new(ErrorCode::PermissionDenied, e).with_context(&path))?;
+    remove_path(&target).map_err(|e| AppError::new(ErrorCode::Io, e).with_context(path))
+}
+
+#[tauri::command]
+pub fn get_permanent_delete() -> bool {
+    PERMANENT_DELETE.load(Ordering::Relaxed)
+}
+
+#[tauri::command]
+pub fn set_permanent_delete(enabled: bool) -> AppResult<()> {
+    save_settings(&settings_path(), &DeleteSettings { permanent: enabled })
+        .map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    PERMANENT_DELETE.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_permanent_removal_of_files_and_dirs() {
+        let temp = TempDir::new().expect("create temp dir");
+        let file = temp.path().join("logo-old.png");
+        let dir = temp.path().join("assets");
+        fs::write(&file, "png").expect("write file");
+        fs::create_dir_all(dir.join("nested")).expect("create dir");
+        fs::write(dir.join("nested").join("a.txt"), "a").expect("write nested");
+
+        remove_path_with(&file, true).expect("remove file");
+        remove_path_with(&dir, true).expect("remove dir");
+        assert!(!file.exists());
+        assert!(!dir.exists());
+        assert!(remove_path_with(&file, true).is_err());
+    }
+
+    #[test]
+    fn test_allowed_roots_are_not_deletable() {
+        let project = TempDir::new().expect("create temp dir");
+        let home = dirs::home_dir().expect("home dir");
+        let roots = vec![home.clone(), PathBuf::from("/tmp"), project.path().to_path_buf()];
+
+        for target in [home.as_path(), Path::new("/tmp"), project.path(), Path::new("/")] {
+            assert!(
+                reject_root_paths(target, &roots).is_err(),
+                "{} must be rejected",
+                target.display()
+            );
+        }
+        let inside = project.path().join("build");
+        fs::create_dir_all(&inside).expect("create dir");
+        assert!(reject_root_paths(&inside, &roots).is_ok());
+        assert!(reject_root_paths(&home.join("notes.md"), &roots).is_ok());
+    }
+
+    #[test]
+    fn test_delete_settings_roundtrip() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("lovcode").join("file_delete.json");
+        assert_eq!(load_settings(&path), DeleteSettings::default());
+
+        save_settings(&path, &DeleteSettings { permanent: true }).expect("save settings");
+        assert!(load_settings(&path).permanent);
+    }
+}
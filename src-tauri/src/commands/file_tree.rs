@@ -0,0 +1,320 @@
+/**
+ * [INPUT]: 依赖 ignore (遵循 .gitignore 的目录遍历), git2 (工作区状态), serde, crate::security
+ * [OUTPUT]: 对外提供 get_file_tree 命令与 FileTreeNode
+ * [POS]: commands/ 模块的项目文件树，供前端项目浏览器按层懒加载
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use git2::{Repository, Status, StatusOptions};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTreeNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    /// File size in bytes (0 for directories)
+    pub size: u64,
+    /// Git marker: "M" modified, "A" added, "D" deleted, "R" renamed, "?" untracked,
+    /// "U" conflicted. Directories containing changes are marked "M".
+    pub git_status: Option<String>,
+    /// None for directories below the requested depth (load them with another call)
+    pub children: Option<Vec<FileTreeNode>>,
+}
+
+/// Stop walking after this many entries; huge trees are expanded level by level
+const MAX_TREE_ENTRIES: usize = 20_000;
+
+/// Never shown, even with respect_gitignore = false
+const ALWAYS_SKIPPED: &[&str] = &[".git", ".DS_Store"];
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn status_marker(status: Status) -> Option<&'static str> {
+    if status.is_conflicted() {
+        Some("U")
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        Some("R")
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        Some("D")
+    } else if status.contains(Status::INDEX_NEW) {
+        Some("A")
+    } else if status.contains(Status::WT_NEW) {
+        Some("?")
+    } else if status.intersects(
+        Status::INDEX_MODIFIED
+            | Status::WT_MODIFIED
+            | Status::INDEX_TYPECHANGE
+            | Status::WT_TYPECHANGE,
+    ) {
+        Some("M")
+    } else {
+        None
+    }
+}
+
+/// Git markers keyed by absolute path; ancestors of changed paths inside `root` get "M".
+/// Empty when `root` is not inside a repository.
+fn git_markers(root: &Path) -> HashMap<PathBuf, &'static str> {
+    let mut markers = HashMap::new();
+    let Ok(repo) = Repository::discover(root) else {
+        return markers;
+    };
+    let Some(workdir) = repo.workdir().map(Path::to_path_buf) else {
+        return markers;
+    };
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .include_ignored(false)
+        .recurse_untracked_dirs(false);
+    let Ok(statuses) = repo.statuses(Some(&mut opts)) else {
+        return markers;
+    };
+
+    // Repository paths are relative to the workdir; map them onto `root` as given, which
+    // may be spelled differently (symlinks, /tmp vs /private/tmp)
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let workdir = workdir.canonicalize().unwrap_or(workdir);
+    for entry in statuses.iter() {
+        let (Some(rel), Some(marker)) = (entry.path(), status_marker(entry.status())) else {
+            continue;
+        };
+        let absolute = workdir.join(rel.trim_end_matches('/'));
+        let Ok(in_root) = absolute.strip_prefix(&canonical_root) else {
+            continue;
+        };
+        let path = root.join(in_root);
+        for ancestor in path.ancestors().skip(1) {
+            if !ancestor.starts_with(root) {
+                break;
+            }
+            markers.entry(ancestor.to_path_buf()).or_insert("M");
+        }
+        markers.insert(path, marker);
+    }
+    markers
+}
+
+fn sort_nodes(nodes: &mut [FileTreeNode]) {
+    // Directories first, then alphabetically (same order as list_directory)
+    nodes.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+/// Assemble the node for `path` from the walked entries, depth-first
+fn build_node(
+    path: &Path,
+    entries: &HashMap<PathBuf, (bool, u64)>,
+    children: &HashMap<PathBuf, Vec<PathBuf>>,
+    markers: &HashMap<PathBuf, &'static str>,
+    expanded: bool,
+) -> FileTreeNode {
+    let (is_dir, size) = entries.get(path).copied().unwrap_or((true, 0));
+    let child_nodes = (is_dir && expanded).then(|| {
+        let mut nodes: Vec<FileTreeNode> = children
+            .get(path)
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|child| {
+                        let child_expanded = children.contains_key(child);
+                        build_node(child, entries, children, markers, child_expanded)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        sort_nodes(&mut nodes);
+        nodes
+    });
+    FileTreeNode {
+        name: path.file_name().map_or_else(
+            || path.to_string_lossy().to_string(),
+            |n| n.to_string_lossy().to_string(),
+        ),
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        size,
+        git_status: markers.get(path).map(|m| (*m).to_string()),
+        children: child_nodes,
+    }
+}
+
+fn file_tree(root: &Path, depth: usize, respect_gitignore: bool) -> FileTreeNode {
+    let depth = depth.max(1);
+    let walker = WalkBuilder::new(root)
+        .max_depth(Some(depth))
+        .hidden(false)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        // Honour .gitignore even outside a git repository (e.g. fresh projects)
+        .require_git(false)
+        .filter_entry(|entry| {
+            !ALWAYS_SKIPPED
+                .iter()
+                .any(|name| entry.file_name() == std::ffi::OsStr::new(name))
+        })
+        .build();
+
+    let mut entries: HashMap<PathBuf, (bool, u64)> = HashMap::new();
+    let mut children: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    // Directories whose contents were listed (depth below the limit)
+    children.insert(root.to_path_buf(), Vec::new());
+    for entry in walker.flatten() {
+        if entry.depth() == 0 {
+            continue;
+        }
+        if entries.len() >= MAX_TREE_ENTRIES {
+            break;
+        }
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        let size = if is_dir {
+            0
+        } else {
+            entry.metadata().map_or(0, |m| m.len())
+        };
+        let path = entry.into_path();
+        if is_dir {
+            // Listed unless it sits at the depth limit; fixed up below
+            children.entry(path.clone()).or_default();
+        }
+        if let Some(parent) = path.parent() {
+            children
+                .entry(parent.to_path_buf())
+                .or_default()
+                .push(path.clone());
+        }
+        entries.insert(path, (is_dir, size));
+    }
+    // Directories at the depth limit were never descended into
+    children.retain(|dir, _| {
+        dir == root
+            || dir
+                .strip_prefix(root)
+                .is_ok_and(|rel| rel.components().count() < depth)
+    });
+
+    let markers = git_markers(root);
+    build_node(root, &entries, &children, &markers, true)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Nested file tree of `path`, `depth` levels deep (at least 1)
+///
+/// With `respect_gitignore`, .gitignore/.ignore rules apply; .git is always skipped.
+/// Directories below the depth have `children: null` and are loaded by calling this
+/// again with their path.
+#[tauri::command]
+pub async fn get_file_tree(
+    path: String,
+    depth: usize,
+    respect_gitignore: bool,
+) -> AppResult<FileTreeNode> {
+    security::validate_decoded_path(&path)?;
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(AppError::invalid_input(format!("Not a directory: {}", path)));
+    }
+    Ok(tauri::async_runtime::spawn_blocking(move || file_tree(&root, depth, respect_gitignore))
+        .await
+        .map_err(|e| format!("Failed to build file tree: {}", e))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn child<'a>(node: &'a FileTreeNode, name: &str) -> &'a FileTreeNode {
+        node.children
+            .as_ref()
+            .and_then(|c| c.iter().find(|n| n.name == name))
+            .expect("child exists")
+    }
+
+    fn names(node: &FileTreeNode) -> Vec<&str> {
+        node.children
+            .as_ref()
+            .map(|c| c.iter().map(|n| n.name.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_file_tree_depth_and_gitignore() {
+        let temp = TempDir::new().expect("create temp dir");
+        let root = temp.path();
+        fs::create_dir_all(root.join("src/nested")).expect("create dirs");
+        fs::create_dir_all(root.join("target")).expect("create target");
+        fs::write(root.join(".gitignore"), "target/\n*.log\n").expect("write gitignore");
+        fs::write(root.join("src/main.rs"), "fn main() {}").expect("write main");
+        fs::write(root.join("src/nested/deep.rs"), "").expect("write deep");
+        fs::write(root.join("debug.log"), "x").expect("write log");
+        fs::write(root.join("README.md"), "# hi").expect("write readme");
+
+        let tree = file_tree(root, 2, true);
+        assert_eq!(names(&tree), vec!["src", ".gitignore", "README.md"]);
+        let src = child(&tree, "src");
+        assert_eq!(names(src), vec!["nested", "main.rs"]);
+        assert_eq!(child(src, "main.rs").size, 12);
+        // Below the depth limit: not loaded
+        assert!(child(src, "nested").children.is_none());
+
+        let all = file_tree(root, 1, false);
+        assert_eq!(
+            names(&all),
+            vec!["src", "target", ".gitignore", "debug.log", "README.md"]
+        );
+        assert!(child(&all, "src").children.is_none());
+    }
+
+    #[test]
+    fn test_file_tree_git_markers() {
+        let temp = TempDir::new().expect("create temp dir");
+        let root = temp.path();
+        let repo = Repository::init(root).expect("init repo");
+        fs::create_dir_all(root.join("src")).expect("create src");
+        fs::write(root.join("src/lib.rs"), "one").expect("write lib");
+        fs::write(root.join("clean.txt"), "clean").expect("write clean");
+
+        let mut index = repo.index().expect("index");
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .expect("add all");
+        index.write().expect("write index");
+        let tree_id = index.write_tree().expect("write tree");
+        let tree = repo.find_tree(tree_id).expect("find tree");
+        let sig = git2::Signature::now("t", "t@example.com").expect("signature");
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .expect("commit");
+
+        fs::write(root.join("src/lib.rs"), "two").expect("modify lib");
+        fs::write(root.join("new.txt"), "new").expect("write new");
+
+        let tree = file_tree(root, 3, true);
+        assert_eq!(child(&tree, "new.txt").git_status.as_deref(), Some("?"));
+        assert_eq!(child(&tree, "clean.txt").git_status, None);
+        let src = child(&tree, "src");
+        assert_eq!(src.git_status.as_deref(), Some("M"));
+        assert_eq!(child(src, "lib.rs").git_status.as_deref(), Some("M"));
+    }
+}
@@ -1,13 +1,19 @@
 /**
- * [INPUT]: 依赖 std::fs, std::path, base64, serde, crate::security
- * [OUTPUT]: 对外提供 read_file, read_file_base64, list_directory, get_file_metadata, copy_file_to_project_assets 等命令
+ * [INPUT]: 依赖 std::fs, std::path, base64, serde, tokio (进程/流式输出), crate::security, commands::file_trash (删除进回收站)
+ * [OUTPUT]: 对外提供 read_file (路径校验/大小限制/二进制检测), read_file_range/read_file_tail (大文件分块读取), read_file_base64, list_directory, get_file_metadata, copy_file_to_project_assets, exec_shell_stream/cancel_exec 等命令
  * [POS]: commands/ 模块的文件操作命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
+use crate::commands::file_trash::remove_path;
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
 
 // ============================================================================
 // Types
@@ -20,12 +26,42 @@ pub struct DirEntry {
     pub is_dir: bool,
 }
 
+/// Part of a file returned by read_file_range / read_file_tail
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChunk {
+    pub content: String,
+    /// Byte offset where `content` starts
+    pub offset: u64,
+    /// Byte offset just past `content`; pass it as the next `offset` to continue
+    pub next_offset: u64,
+    pub file_size: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct FileMetadata {
     pub size: u64,
     pub modified: Option<u64>,
 }
 
+/// Payload of the "exec-output" event
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecOutputEvent {
+    pub exec_id: String,
+    /// "stdout" | "stderr"
+    pub stream: &'static str,
+    pub data: String,
+}
+
+/// Payload of the "exec-exit" event
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecExitEvent {
+    pub exec_id: String,
+    /// None when the process was killed or could not be waited on
+    pub exit_code: Option<i32>,
+    /// "exited" | "cancelled" | "timed_out" | "failed"
+    pub reason: &'static str,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LogoVersion {
     pub path: String,
@@ -38,49 +74,181 @@ pub struct LogoVersion {
 // File Reading Commands
 // ============================================================================
 
+/// Larger files must be read in pieces
+const MAX_READ_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// A NUL byte in this many leading bytes marks a file as binary
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+fn looks_binary(data: &[u8]) -> bool {
+    data.iter().take(BINARY_SNIFF_BYTES).any(|&b| b == 0)
+}
+
+/// Like readable_file, for files read in one go: refuses anything over MAX_READ_FILE_BYTES
+fn readable_whole_file(path: &str) -> AppResult<PathBuf> {
+    let file_path = readable_file(path)?;
+    let size = fs::metadata(&file_path)
+        .map_err(|e| AppError::io("Failed to read file", &e).with_context(path))?
+        .len();
+    if size > MAX_READ_FILE_BYTES {
+        return Err(AppError::invalid_input(format!(
+            "File too large: {} ({} bytes, limit {})",
+            path, size, MAX_READ_FILE_BYTES
+        ))
+        .with_context(path));
+    }
+    Ok(file_path)
+}
+
 /// Read file contents as string
+///
+/// Only paths under home, /tmp or a workspace project are allowed. Binary files and
+/// files over 10 MB are refused.
 #[tauri::command]
-pub fn read_file(path: String) -> Result<String, String> {
-    let file_path = PathBuf::from(&path);
+pub fn read_file(path: String) -> AppResult<String> {
+    let file_path = readable_whole_file(&path)?;
+    let data = fs::read(&file_path)
+        .map_err(|e| AppError::io("Failed to read file", &e).with_context(&path))?;
+    if looks_binary(&data) {
+        return Err(AppError::invalid_input(format!("Binary file: {}", path)).with_context(path));
+    }
+    String::from_utf8(data).map_err(|e| {
+        AppError::new(ErrorCode::Parse, format!("Failed to read file: {}", e)).with_context(path)
+    })
+}
 
+/// Largest piece returned by read_file_range / read_file_tail
+const MAX_CHUNK_BYTES: u64 = 4 * 1024 * 1024;
+/// read_file_tail scans backwards in blocks of this size
+const TAIL_BLOCK_BYTES: u64 = 64 * 1024;
+
+fn readable_file(path: &str) -> AppResult<PathBuf> {
+    let file_path = security::validate_user_path(path)?;
     if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
+        return Err(
+            AppError::not_found(format!("File does not exist: {}", path)).with_context(path)
+        );
     }
-
     if !file_path.is_file() {
-        return Err(format!("Not a file: {}", path));
+        return Err(AppError::invalid_input(format!("Not a file: {}", path)).with_context(path));
     }
+    Ok(file_path)
+}
 
-    fs::read_to_string(&file_path).map_err(|e| format!("Failed to read file: {}", e))
+/// Read up to `length` bytes at `offset`, moved to whole UTF-8 characters: a start in the
+/// middle of a character skips it, an end in the middle of one leaves it for the next chunk
+fn read_chunk(path: &Path, offset: u64, length: u64) -> std::io::Result<FileChunk> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = fs::File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let offset = offset.min(file_size);
+    let length = length.min(MAX_CHUNK_BYTES).min(file_size - offset);
+
+    let mut buf = Vec::new();
+    file.seek(SeekFrom::Start(offset))?;
+    file.by_ref().take(length).read_to_end(&mut buf)?;
+    // At most 3 continuation bytes (10xxxxxx) precede the next character
+    let skipped = buf
+        .iter()
+        .take(3)
+        .take_while(|&&b| b & 0xC0 == 0x80)
+        .count();
+    buf.drain(..skipped);
+    let start = offset + skipped as u64;
+
+    let read = buf.len() as u64;
+    let (content, next_offset) = if start + read >= file_size {
+        (String::from_utf8_lossy(&buf).into_owned(), file_size)
+    } else {
+        // Leaves the incomplete trailing character in buf
+        let content = take_utf8_prefix(&mut buf);
+        (content, start + read - buf.len() as u64)
+    };
+    Ok(FileChunk {
+        content,
+        offset: start,
+        next_offset,
+        file_size,
+    })
+}
+
+/// Byte offset where the last `lines` lines of the file begin. A final newline ends
+/// the last line rather than starting an empty one.
+fn tail_offset(path: &Path, lines: usize) -> std::io::Result<u64> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = fs::File::open(path)?;
+    let file_size = file.metadata()?.len();
+    if lines == 0 {
+        return Ok(file_size);
+    }
+    let mut newlines = 0;
+    let mut pos = file_size;
+    let mut block = Vec::new();
+    while pos > 0 && file_size - pos < MAX_CHUNK_BYTES {
+        let block_len = pos.min(TAIL_BLOCK_BYTES);
+        pos -= block_len;
+        file.seek(SeekFrom::Start(pos))?;
+        block.clear();
+        file.by_ref().take(block_len).read_to_end(&mut block)?;
+        for (i, &byte) in block.iter().enumerate().rev() {
+            let at = pos + i as u64;
+            if byte != b'\n' || at + 1 == file_size {
+                continue;
+            }
+            newlines += 1;
+            if newlines == lines {
+                return Ok(at + 1);
+            }
+        }
+    }
+    Ok(pos.max(file_size.saturating_sub(MAX_CHUNK_BYTES)))
 }
 
-/// Read file as base64
+/// Read part of a file, for viewing files too big for read_file
+///
+/// At most 4 MB is returned per call; continue from `next_offset`.
 #[tauri::command]
-pub fn read_file_base64(path: String) -> Result<String, String> {
-    use base64::{engine::general_purpose::STANDARD, Engine as _};
+pub fn read_file_range(path: String, offset: u64, length: u64) -> AppResult<FileChunk> {
+    let file_path = readable_file(&path)?;
+    read_chunk(&file_path, offset, length).map_err(|e| AppError::io("Failed to read file", &e))
+}
 
-    let file_path = PathBuf::from(&path);
+/// Read the last `lines` lines of a file (at most its last 4 MB)
+#[tauri::command]
+pub fn read_file_tail(path: String, lines: usize) -> AppResult<FileChunk> {
+    let file_path = readable_file(&path)?;
+    let start =
+        tail_offset(&file_path, lines).map_err(|e| AppError::io("Failed to read file", &e))?;
+    read_chunk(&file_path, start, MAX_CHUNK_BYTES)
+        .map_err(|e| AppError::io("Failed to read file", &e))
+}
 
-    if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
-    }
+/// Read file as base64, with the same path and size limits as read_file
+#[tauri::command]
+pub fn read_file_base64(path: String) -> AppResult<String> {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
 
-    let data = fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let file_path = readable_whole_file(&path)?;
+    let data = fs::read(&file_path)
+        .map_err(|e| AppError::io("Failed to read file", &e).with_context(&path))?;
 
     Ok(STANDARD.encode(&data))
 }
 
 /// Get file metadata (size, modified time)
 #[tauri::command]
-pub fn get_file_metadata(path: String) -> Result<FileMetadata, String> {
+pub fn get_file_metadata(path: String) -> AppResult<FileMetadata> {
     let file_path = PathBuf::from(&path);
 
     if !file_path.exists() {
-        return Err(format!("File does not exist: {}", path));
+        return Err(
+            AppError::not_found(format!("File does not exist: {}", path)).with_context(path)
+        );
     }
 
     let metadata =
-        fs::metadata(&file_path).map_err(|e| format!("Failed to get metadata: {}", e))?;
+        fs::metadata(&file_path).map_err(|e| AppError::io("Failed to get metadata", &e))?;
 
     let modified = metadata
         .modified()
@@ -100,15 +268,19 @@ pub fn get_file_metadata(path: String) -> Result<FileMetadata, String> {
 
 /// List directory contents (non-recursive, respects .gitignore patterns)
 #[tauri::command]
-pub fn list_directory(path: String) -> Result<Vec<DirEntry>, String> {
+pub fn list_directory(path: String) -> AppResult<Vec<DirEntry>> {
     let dir_path = PathBuf::from(&path);
 
     if !dir_path.exists() {
-        return Err(format!("Directory does not exist: {}", path));
+        return Err(
+            AppError::not_found(format!("Directory does not exist: {}", path)).with_context(path)
+        );
     }
 
     if !dir_path.is_dir() {
-        return Err(format!("Not a directory: {}", path));
+        return Err(
+            AppError::invalid_input(format!("Not a directory: {}", path)).with_context(path)
+        );
     }
 
     // Common patterns to ignore
@@ -135,7 +307,7 @@ pub fn list_directory(path: String) -> Result<Vec<DirEntry>, String> {
     let mut entries: Vec<DirEntry> = Vec::new();
 
     let read_dir =
-        fs::read_dir(&dir_path).map_err(|e| format!("Failed to read directory: {}", e))?;
+        fs::read_dir(&dir_path).map_err(|e| AppError::io("Failed to read directory", &e))?;
 
     for entry in read_dir.flatten() {
         let name = entry.file_name().to_string_lossy().to_string();
@@ -290,7 +462,7 @@ pub fn save_project_logo(
     project_path: String,
     base64_data: String,
     filename: String,
-) -> Result<String, String> {
+) -> AppResult<String> {
     use base64::{engine::general_purpose::STANDARD, Engine as _};
 
     let project = PathBuf::from(&project_path);
@@ -298,23 +470,21 @@ pub fn save_project_logo(
 
     // Ensure assets directory exists
     fs::create_dir_all(&assets_dir)
-        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
+        .map_err(|e| AppError::io("Failed to create assets directory", &e))?;
 
     // Decode base64
     let data = STANDARD
         .decode(&base64_data)
-        .map_err(|e| format!("Failed to decode base64: {}", e))?;
+        .map_err(|e| AppError::invalid_input(format!("Failed to decode base64: {}", e)))?;
 
     // Save versioned file (atomic)
     let versioned_path = assets_dir.join(&filename);
-    security::atomic_write(&versioned_path, &data)
-        .map_err(|e| format!("Failed to write logo: {}", e))?;
+    security::atomic_write(&versioned_path, &data)?;
 
     // Also save as logo.png (current)
     let ext = filename.rsplit('.').next().unwrap_or("png");
     let current_path = assets_dir.join(format!("logo.{}", ext));
-    security::atomic_write(&current_path, &data)
-        .map_err(|e| format!("Failed to write current logo: {}", e))?;
+    security::atomic_write(&current_path, &data)?;
 
     Ok(versioned_path.to_string_lossy().to_string())
 }
@@ -325,45 +495,45 @@ pub fn copy_file_to_project_assets(
     source_path: String,
     project_path: String,
     target_filename: String,
-) -> Result<String, String> {
+) -> AppResult<String> {
     let source = PathBuf::from(&source_path);
     let project = PathBuf::from(&project_path);
     let assets_dir = project.join("assets");
 
     // Ensure assets directory exists
     fs::create_dir_all(&assets_dir)
-        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
+        .map_err(|e| AppError::io("Failed to create assets directory", &e))?;
 
     // Copy to target filename
     let target_path = assets_dir.join(&target_filename);
-    fs::copy(&source, &target_path).map_err(|e| format!("Failed to copy file: {}", e))?;
+    fs::copy(&source, &target_path).map_err(|e| AppError::io("Failed to copy file", &e))?;
 
     Ok(target_path.to_string_lossy().to_string())
 }
 
 /// Set a specific logo version as current
 #[tauri::command]
-pub fn set_current_project_logo(project_path: String, logo_path: String) -> Result<(), String> {
+pub fn set_current_project_logo(project_path: String, logo_path: String) -> AppResult<()> {
     let project = PathBuf::from(&project_path);
     let assets_dir = project.join("assets");
     let source = PathBuf::from(&logo_path);
 
     if !source.exists() {
-        return Err("Logo file does not exist".to_string());
+        return Err(AppError::not_found("Logo file does not exist").with_context(logo_path));
     }
 
     let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("png");
 
     // Copy as current logo
     let current_path = assets_dir.join(format!("logo.{}", ext));
-    fs::copy(&source, &current_path).map_err(|e| format!("Failed to set current logo: {}", e))?;
+    fs::copy(&source, &current_path).map_err(|e| AppError::io("Failed to set current logo", &e))?;
 
     Ok(())
 }
 
 /// Delete a logo version
 #[tauri::command]
-pub fn delete_project_logo(_project_path: String, logo_path: String) -> Result<(), String> {
+pub fn delete_project_logo(_project_path: String, logo_path: String) -> AppResult<()> {
     let path = PathBuf::from(&logo_path);
 
     if !path.exists() {
@@ -373,15 +543,17 @@ pub fn delete_project_logo(_project_path: String, logo_path: String) -> Result<(
     // Don't allow deleting the current logo (logo.png/logo.svg)
     if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
         if filename == "logo.png" || filename == "logo.svg" {
-            return Err(
-                "Cannot delete current logo. Set another version as current first.".to_string(),
-            );
+            return Err(AppError::new(
+                ErrorCode::Conflict,
+                "Cannot delete current logo. Set another version as current first.",
+            )
+            .with_context(logo_path));
         }
     }
 
-    fs::remove_file(&path).map_err(|e| format!("Failed to delete logo: {}", e))?;
-
-    Ok(())
+    // Into the OS trash unless the user opted into permanent deletion
+    remove_path(&path)
+        .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to delete logo: {}", e)))
 }
 
 // ============================================================================
@@ -391,6 +563,24 @@ pub fn delete_project_logo(_project_path: String, logo_path: String) -> Result<(
 /// Default timeout for shell commands (30 seconds)
 const SHELL_COMMAND_TIMEOUT_SECS: u64 = 30;
 
+/// How long exec_shell_stream keeps reading after the shell exits; a background job
+/// started by the command (`npm run dev &`) can hold the pipes open indefinitely
+const EXEC_OUTPUT_GRACE_SECS: u64 = 2;
+
+/// Cancel handles of running exec_shell_stream processes, by execution id
+static RUNNING_EXECS: LazyLock<Mutex<HashMap<String, oneshot::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 使用固定的 shell 路径，避免环境变量劫持
+/// macOS 默认使用 zsh，Linux 使用 bash
+const fn login_shell() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "/bin/zsh"
+    } else {
+        "/bin/bash"
+    }
+}
+
 /// Run a shell command in specified directory using login shell (async, non-blocking)
 ///
 /// # Security
@@ -399,45 +589,215 @@ const SHELL_COMMAND_TIMEOUT_SECS: u64 = 30;
 /// - 使用固定的 shell 路径，避免 $SHELL 环境变量劫持
 /// - 命令执行有 30 秒超时，防止无限挂起
 #[tauri::command]
-pub async fn exec_shell_command(command: String, cwd: String) -> Result<String, String> {
+pub async fn exec_shell_command(command: String, cwd: String) -> AppResult<String> {
     use std::time::Duration;
     use tokio::process::Command;
     use tokio::time::timeout;
 
     // 安全验证：验证路径并转义 shell 特殊字符
-    let escaped_cwd = security::validate_and_escape_cwd(&cwd)
-        .map_err(|e| format!("Invalid working directory: {}", e))?;
-
-    // 使用固定的 shell 路径，避免环境变量劫持
-    // macOS 默认使用 zsh，Linux 使用 bash
-    #[cfg(target_os = "macos")]
-    let shell = "/bin/zsh";
-    #[cfg(not(target_os = "macos"))]
-    let shell = "/bin/bash";
+    let escaped_cwd = security::validate_and_escape_cwd(&cwd)?;
 
     // 使用超时机制，防止命令无限挂起
     let output = timeout(
         Duration::from_secs(SHELL_COMMAND_TIMEOUT_SECS),
-        Command::new(shell)
+        Command::new(login_shell())
             .args(["-ilc", &format!("cd {} && {}", escaped_cwd, command)])
             .output(),
     )
     .await
     .map_err(|_| {
-        format!(
-            "Command timed out after {} seconds",
-            SHELL_COMMAND_TIMEOUT_SECS
+        AppError::new(
+            ErrorCode::Timeout,
+            format!("Command timed out after {} seconds", SHELL_COMMAND_TIMEOUT_SECS),
         )
     })?
-    .map_err(|e| format!("Failed to run command: {}", e))?;
+    .map_err(|e| AppError::io("Failed to run command", &e))?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        Err(if stderr.is_empty() { stdout } else { stderr })
+        Err(if stderr.is_empty() { stdout.into() } else { stderr.into() })
+    }
+}
+
+/// Split off the longest valid UTF-8 prefix of `buf`, keeping an incomplete trailing
+/// character for the next chunk; invalid bytes are replaced
+fn take_utf8_prefix(buf: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(buf) {
+        // error_len() is None when the input just ends mid-character
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => buf.len(),
+    };
+    let rest = buf.split_off(valid);
+    let text = String::from_utf8_lossy(buf).into_owned();
+    *buf = rest;
+    text
+}
+
+/// Forward one output pipe as "exec-output" events until it closes
+async fn stream_output<R>(app: AppHandle, exec_id: String, stream: &'static str, mut pipe: R)
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut chunk = vec![0u8; 8192];
+    let mut pending = Vec::new();
+    loop {
+        let n = match pipe.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        pending.extend_from_slice(&chunk[..n]);
+        let data = take_utf8_prefix(&mut pending);
+        if !data.is_empty() {
+            let _ = app.emit(
+                "exec-output",
+                ExecOutputEvent {
+                    exec_id: exec_id.clone(),
+                    stream,
+                    data,
+                },
+            );
+        }
     }
+    if !pending.is_empty() {
+        let data = String::from_utf8_lossy(&pending).into_owned();
+        let _ = app.emit("exec-output", ExecOutputEvent { exec_id, stream, data });
+    }
+}
+
+/// Kill the whole process group, so children of the login shell (build tools, servers)
+/// don't outlive a cancel
+fn kill_exec(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id().and_then(|pid| i32::try_from(pid).ok()) {
+        // SAFETY: killpg only sends a signal; the group was created for this child
+        unsafe {
+            libc::killpg(pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.start_kill();
+}
+
+/// Run a shell command like exec_shell_command, but stream its output
+///
+/// Returns an execution id at once; stdout/stderr chunks arrive as
+/// "exec-output" events and the end as one "exec-exit" event. No timeout unless
+/// `timeout_secs` is given; `cancel_exec` stops the process early. Background jobs
+/// the command leaves behind are not waited for.
+#[tauri::command]
+pub async fn exec_shell_stream(
+    app: AppHandle,
+    command: String,
+    cwd: String,
+    timeout_secs: Option<u64>,
+) -> AppResult<String> {
+    use std::process::Stdio;
+    use std::time::Duration;
+    use tokio::process::Command;
+
+    let escaped_cwd = security::validate_and_escape_cwd(&cwd)?;
+
+    let mut cmd = Command::new(login_shell());
+    cmd.args(["-ilc", &format!("cd {} && {}", escaped_cwd, command)])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AppError::io("Failed to run command", &e))?;
+
+    let exec_id = uuid::Uuid::new_v4().to_string();
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    RUNNING_EXECS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(exec_id.clone(), cancel_tx);
+
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(tauri::async_runtime::spawn(stream_output(
+            app.clone(),
+            exec_id.clone(),
+            "stdout",
+            stdout,
+        )));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(tauri::async_runtime::spawn(stream_output(
+            app.clone(),
+            exec_id.clone(),
+            "stderr",
+            stderr,
+        )));
+    }
+
+    let id = exec_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let deadline = async {
+            match timeout_secs {
+                Some(secs) => tokio::time::sleep(Duration::from_secs(secs)).await,
+                None => std::future::pending().await,
+            }
+        };
+        let (exit_code, reason) = tokio::select! {
+            status = child.wait() => match status {
+                Ok(status) => (status.code(), "exited"),
+                Err(_) => (None, "failed"),
+            },
+            _ = cancel_rx => {
+                kill_exec(&mut child);
+                let _ = child.wait().await;
+                (None, "cancelled")
+            }
+            () = deadline => {
+                kill_exec(&mut child);
+                let _ = child.wait().await;
+                (None, "timed_out")
+            }
+        };
+
+        // Deliver all output before the exit event, but don't wait on pipes a
+        // leftover background job keeps open
+        let grace = tokio::time::Instant::now() + Duration::from_secs(EXEC_OUTPUT_GRACE_SECS);
+        for mut reader in readers {
+            if tokio::time::timeout_at(grace, &mut reader).await.is_err() {
+                reader.abort();
+            }
+        }
+        if let Ok(mut running) = RUNNING_EXECS.lock() {
+            running.remove(&id);
+        }
+        let _ = app.emit(
+            "exec-exit",
+            ExecExitEvent {
+                exec_id: id,
+                exit_code,
+                reason,
+            },
+        );
+    });
+
+    Ok(exec_id)
+}
+
+/// Stop a process started by exec_shell_stream; its "exec-exit" event reports
+/// reason "cancelled"
+#[tauri::command]
+pub fn cancel_exec(exec_id: String) -> AppResult<()> {
+    let cancel = RUNNING_EXECS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&exec_id)
+        .ok_or_else(|| AppError::not_found(format!("Execution '{}' not found", exec_id)))?;
+    let _ = cancel.send(());
+    Ok(())
 }
 
 // ============================================================================
@@ -467,9 +827,13 @@ mod tests {
 
     #[test]
     fn test_read_file_not_exists() {
-        let result = read_file("/nonexistent/path/to/file.txt".to_string());
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let missing = temp_dir.path().join("nonexistent").join("file.txt");
+        let result = read_file(missing.to_string_lossy().to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert_eq!(err.code(), ErrorCode::NotFound);
     }
 
     #[test]
@@ -477,7 +841,9 @@ mod tests {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
         let result = read_file(temp_dir.path().to_string_lossy().to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Not a file"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Not a file"));
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
     }
 
     #[test]
@@ -491,6 +857,72 @@ mod tests {
         assert_eq!(result.unwrap(), "你好世界！🚀");
     }
 
+    #[test]
+    fn test_read_file_rejects_binary_and_outside_paths() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("image.png");
+        fs::write(&file_path, b"\x89PNG\r\n\x1a\n\x00\x00").expect("Failed to write file");
+
+        let result = read_file(file_path.to_string_lossy().to_string());
+        let err = result.expect_err("binary");
+        assert!(err.to_string().contains("Binary file"));
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
+
+        let result = read_file("/etc/hosts".to_string());
+        let err = result.expect_err("outside");
+        assert_eq!(err.code(), ErrorCode::PermissionDenied);
+        let traversal = format!("{}/../etc/hosts", temp_dir.path().display());
+        assert!(read_file(traversal).is_err());
+    }
+
+    // ------------------------------------------------------------------------
+    // read_file_range / read_file_tail tests
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn test_read_file_range_keeps_utf8_characters_whole() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("log.txt");
+        // "é" is 2 bytes: a-é-b = bytes 0, 1..3, 3
+        fs::write(&file_path, "aéb").expect("Failed to write file");
+        let path = file_path.to_string_lossy().to_string();
+
+        // Ends inside "é": left for the next chunk
+        let chunk = read_file_range(path.clone(), 0, 2).expect("range");
+        assert_eq!((chunk.content.as_str(), chunk.next_offset), ("a", 1));
+        let chunk = read_file_range(path.clone(), chunk.next_offset, 2).expect("range");
+        assert_eq!((chunk.content.as_str(), chunk.next_offset), ("é", 3));
+
+        // Starts inside "é": skipped
+        let chunk = read_file_range(path.clone(), 2, 10).expect("range");
+        assert_eq!((chunk.content.as_str(), chunk.offset), ("b", 3));
+        assert_eq!((chunk.next_offset, chunk.file_size), (4, 4));
+
+        let chunk = read_file_range(path, 100, 10).expect("past end");
+        assert_eq!((chunk.content.as_str(), chunk.offset), ("", 4));
+    }
+
+    #[test]
+    fn test_read_file_tail() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("session.jsonl");
+        let lines: Vec<String> = (1..=20_000).map(|i| format!("line {}", i)).collect();
+        fs::write(&file_path, lines.join("\n") + "\n").expect("Failed to write file");
+        let path = file_path.to_string_lossy().to_string();
+
+        let tail = read_file_tail(path.clone(), 2).expect("tail");
+        assert_eq!(tail.content, "line 19999\nline 20000\n");
+        assert_eq!(tail.next_offset, tail.file_size);
+
+        // Spans several backward blocks
+        let tail = read_file_tail(path.clone(), 15_000).expect("long tail");
+        assert!(tail.content.starts_with("line 5001\n"));
+
+        let all = read_file_tail(path.clone(), 50_000).expect("whole file");
+        assert_eq!(all.offset, 0);
+        assert!(read_file_tail(path, 0).expect("no lines").content.is_empty());
+    }
+
     // ------------------------------------------------------------------------
     // read_file_base64 tests
     // ------------------------------------------------------------------------
@@ -508,9 +940,27 @@ mod tests {
 
     #[test]
     fn test_read_file_base64_not_exists() {
-        let result = read_file_base64("/nonexistent/file.bin".to_string());
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let missing = temp_dir.path().join("file.bin");
+        let result = read_file_base64(missing.to_string_lossy().to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert_eq!(err.code(), ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_read_file_base64_rejects_outside_and_large_files() {
+        let err = read_file_base64("/etc/hosts".to_string()).expect_err("outside");
+        assert_eq!(err.code(), ErrorCode::PermissionDenied);
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let file_path = temp_dir.path().join("huge.png");
+        let file = fs::File::create(&file_path).expect("Failed to create file");
+        file.set_len(MAX_READ_FILE_BYTES + 1).expect("Failed to grow file");
+        let err = read_file_base64(file_path.to_string_lossy().to_string()).expect_err("large");
+        assert!(err.to_string().contains("File too large"));
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
     }
 
     // ------------------------------------------------------------------------
@@ -534,7 +984,9 @@ mod tests {
     fn test_get_file_metadata_not_exists() {
         let result = get_file_metadata("/nonexistent/file.txt".to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert_eq!(err.code(), ErrorCode::NotFound);
     }
 
     // ------------------------------------------------------------------------
@@ -587,7 +1039,9 @@ mod tests {
     fn test_list_directory_not_exists() {
         let result = list_directory("/nonexistent/directory".to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert_eq!(err.code(), ErrorCode::NotFound);
     }
 
     #[test]
@@ -598,7 +1052,9 @@ mod tests {
 
         let result = list_directory(file_path.to_string_lossy().to_string());
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Not a directory"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Not a directory"));
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
     }
 
     // ------------------------------------------------------------------------
@@ -635,7 +1091,8 @@ mod tests {
         );
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("decode base64"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("decode base64"));
     }
 
     // ------------------------------------------------------------------------
@@ -695,6 +1152,36 @@ mod tests {
             logo_path.to_string_lossy().to_string(),
         );
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Cannot delete current logo"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Cannot delete current logo"));
+        assert_eq!(err.code(), ErrorCode::Conflict);
+    }
+
+    // ------------------------------------------------------------------------
+    // exec_shell_stream tests
+    // ------------------------------------------------------------------------
+
+    #[test]
+    fn test_take_utf8_prefix_keeps_split_character() {
+        // "é" is 0xC3 0xA9; the chunk ends after its first byte
+        let mut buf = b"caf\xC3".to_vec();
+        assert_eq!(take_utf8_prefix(&mut buf), "caf");
+        assert_eq!(buf, vec![0xC3]);
+
+        buf.extend_from_slice(b"\xA9!");
+        assert_eq!(take_utf8_prefix(&mut buf), "é!");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_take_utf8_prefix_replaces_invalid_bytes() {
+        let mut buf = b"a\xFFb".to_vec();
+        assert_eq!(take_utf8_prefix(&mut buf), "a\u{FFFD}b");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_cancel_exec_unknown_id() {
+        assert!(cancel_exec("missing".to_string()).is_err());
     }
 }
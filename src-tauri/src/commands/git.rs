@@ -1,16 +1,20 @@
 /**
- * [INPUT]: 依赖 std::process::Command, serde, regex, chrono, crate::security
- * [OUTPUT]: 对外提供 git_log, git_get_note, git_set_note, git_revert, git_has_changes, git_auto_commit, git_generate_changelog 命令
- * [POS]: commands/ 模块的 Git 操作命令中心
+ * [INPUT]: 依赖 git2 (读操作), std::process::Command (写操作/推送), serde, regex, chrono, crate::security
+ * [OUTPUT]: 对外提供 current_branch/start_branch/merge_branch (功能分支) 与 git_log, git_get_note, git_set_note, git_revert, git_has_changes, git_auto_commit, git_generate_changelog, git_diff, git_list_branches, git_create_branch, git_switch_branch, git_delete_branch, git_add_worktree, git_list_worktrees, git_remove_worktree, git_show, git_push, git_pull 命令
+ * [POS]: commands/ 模块的 Git 操作命令中心；status/log/diff/branch 走 libgit2，其余调用 git CLI
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
+use git2::{BranchType, DiffFormat, DiffOptions, Oid, Repository, Sort, StatusOptions};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::process::Command;
+use std::io::Read;
+use std::process::{Command, Stdio};
 use std::sync::LazyLock;
+use tauri::{AppHandle, Emitter};
 
 // ============================================================================
 // Types
@@ -24,6 +28,33 @@ pub struct CommitInfo {
     pub timestamp: i64,
     pub author: String,
     pub feat_name: Option<String>, // Parsed from message: feat(xxx): ...
+    /// Parent hashes (empty for a root commit, two or more for merges)
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// Decorations such as "HEAD -> main", "origin/main", "tag: v1.0"
+    #[serde(default)]
+    pub refs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct GitLogOptions {
+    /// Number of commits to skip (for pagination)
+    pub skip: Option<usize>,
+    /// Only commits whose author matches this pattern
+    pub author: Option<String>,
+    /// Unix timestamps bounding the commit date
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Include every branch, not just HEAD
+    pub all: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CommitDetail {
+    pub commit: CommitInfo,
+    pub body: String, // Full commit message
+    /// Changes against the first parent (or the empty tree for a root commit)
+    pub files: Vec<FileDiff>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,6 +65,95 @@ pub struct CommitNote {
     pub override_assoc: bool,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct GitBranch {
+    pub name: String, // "main" or "origin/main" for remote branches
+    pub is_current: bool,
+    pub is_remote: bool,
+    pub commit: String, // Short hash of the branch tip
+    pub timestamp: i64, // Committer date of the branch tip
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct GitWorktree {
+    pub path: String,
+    pub head: String,           // Commit checked out in the worktree
+    pub branch: Option<String>, // None when detached
+    pub is_main: bool,          // The repository's primary working copy
+    pub locked: bool,
+    pub prunable: bool, // Directory is gone; `git worktree prune` would drop it
+}
+
+/// Payload of the "git-progress" event emitted during push/pull
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct GitProgressEvent {
+    pub project_path: String,
+    pub operation: String, // "push" | "pull"
+    pub stage: String,     // e.g. "Counting objects", "Writing objects", "Receiving objects"
+    pub percent: Option<u32>,
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct GitDiffOptions {
+    /// "unstaged" (default) | "staged" | "range"
+    pub mode: Option<String>,
+    /// Range start (required for "range"); `to` defaults to the working tree
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Limit the diff to these paths (relative to the project)
+    pub paths: Option<Vec<String>>,
+    /// Lines of context around each hunk (default 3)
+    pub context_lines: Option<u32>,
+    /// Include untracked files as added files (unstaged mode only)
+    pub include_untracked: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    pub old_path: Option<String>, // Set for renames and copies
+    pub status: String,           // "added" | "modified" | "deleted" | "renamed" | "copied"
+    pub binary: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub header: String, // Function context after the @@ markers
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiffLine {
+    pub kind: String, // "context" | "add" | "delete"
+    pub content: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+}
+
+/// How a finished feature branch is folded back into its base branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// Always create a merge commit (--no-ff)
+    Merge,
+    /// One commit on the base branch with all of the feature's changes
+    Squash,
+    /// Only advance the base branch; fails if it has diverged
+    FastForward,
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -51,65 +171,709 @@ fn parse_feat_from_message(message: &str) -> Option<String> {
         .and_then(|caps| caps.get(1).map(|m| m.as_str().to_string()))
 }
 
-// ============================================================================
-// Git Commands
-// ============================================================================
-
-/// Get git log for a project
-#[tauri::command]
-pub fn git_log(project_path: String, limit: Option<usize>) -> Result<Vec<CommitInfo>, String> {
-    // 安全验证：防止路径遍历攻击
-    security::validate_decoded_path(&project_path)
-        .map_err(|e| format!("Invalid project path: {}", e))?;
+static HUNK_HEADER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@ ?(.*)$")
+        .expect("HUNK_HEADER_RE should compile")
+});
 
-    let limit = limit.unwrap_or(100);
+/// Run git in a project directory and return stdout (untrimmed)
+fn run_git_in(project_path: &str, args: &[&str]) -> Result<String, String> {
     let output = Command::new("git")
-        .args([
-            "-C",
-            &project_path,
-            "log",
-            &format!("-{}", limit),
-            "--format=%H|%h|%s|%at|%an",
-        ])
+        .args(["-C", project_path])
+        .args(args)
         .output()
-        .map_err(|e| format!("Failed to run git log: {}", e))?;
+        .map_err(|e| format!("Failed to run git {}: {}", args.first().unwrap_or(&""), e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git log failed: {}", stderr));
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let commits: Vec<CommitInfo> = stdout
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn strip_diff_prefix(path: &str) -> String {
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// Parse `git diff` unified output into per-file hunks with line numbers
+fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut in_hunk = false;
+    let (mut old_no, mut new_no) = (0u32, 0u32);
+
+    for line in text.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            // Fallback path for diffs without ---/+++ lines (binary, pure rename, mode change)
+            let path = header
+                .rsplit_once(" b/")
+                .map_or_else(|| header.to_string(), |(_, b)| b.to_string());
+            files.push(FileDiff {
+                path,
+                old_path: None,
+                status: "modified".to_string(),
+                binary: false,
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+            });
+            in_hunk = false;
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if let Some(caps) = HUNK_HEADER_RE.captures(line) {
+            let num = |i: usize, default: u32| {
+                caps.get(i)
+                    .and_then(|m| m.as_str().parse().ok())
+                    .unwrap_or(default)
+            };
+            old_no = num(1, 0);
+            new_no = num(3, 0);
+            file.hunks.push(DiffHunk {
+                old_start: old_no,
+                old_lines: num(2, 1),
+                new_start: new_no,
+                new_lines: num(4, 1),
+                header: caps.get(5).map_or("", |m| m.as_str()).to_string(),
+                lines: Vec::new(),
+            });
+            in_hunk = true;
+            continue;
+        }
+
+        if in_hunk {
+            let Some(hunk) = file.hunks.last_mut() else {
+                continue;
+            };
+            let (kind, old_line, new_line) = match line.chars().next() {
+                Some('+') => {
+                    file.additions += 1;
+                    new_no += 1;
+                    ("add", None, Some(new_no - 1))
+                }
+                Some('-') => {
+                    file.deletions += 1;
+                    old_no += 1;
+                    ("delete", Some(old_no - 1), None)
+                }
+                Some(' ') | None => {
+                    old_no += 1;
+                    new_no += 1;
+                    ("context", Some(old_no - 1), Some(new_no - 1))
+                }
+                // "\ No newline at end of file"
+                _ => continue,
+            };
+            hunk.lines.push(DiffLine {
+                kind: kind.to_string(),
+                content: line.get(1..).unwrap_or("").to_string(),
+                old_line,
+                new_line,
+            });
+            continue;
+        }
+
+        if line.starts_with("new file mode") {
+            file.status = "added".to_string();
+        } else if line.starts_with("deleted file mode") {
+            file.status = "deleted".to_string();
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.status = "renamed".to_string();
+            file.old_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = to.to_string();
+        } else if let Some(from) = line.strip_prefix("copy from ") {
+            file.status = "copied".to_string();
+            file.old_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("copy to ") {
+            file.path = to.to_string();
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            if file.status == "deleted" && old != "/dev/null" {
+                file.path = strip_diff_prefix(old);
+            }
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            if new != "/dev/null" {
+                file.path = strip_diff_prefix(new);
+            }
+        }
+    }
+
+    files
+}
+
+/// Parse `git worktree list --porcelain` (blank-line separated records, main worktree first)
+fn parse_worktree_list(text: &str) -> Vec<GitWorktree> {
+    let mut worktrees: Vec<GitWorktree> = Vec::new();
+
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.push(GitWorktree {
+                path: path.to_string(),
+                is_main: worktrees.is_empty(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(wt) = worktrees.last_mut() else {
+            continue;
+        };
+        if let Some(head) = line.strip_prefix("HEAD ") {
+            wt.head = head.to_string();
+        } else if let Some(branch) = line.strip_prefix("branch ") {
+            wt.branch = Some(branch.trim_start_matches("refs/heads/").to_string());
+        } else if line == "locked" || line.starts_with("locked ") {
+            wt.locked = true;
+        } else if line == "prunable" || line.starts_with("prunable ") {
+            wt.prunable = true;
+        }
+    }
+
+    worktrees
+}
+
+/// Reject names git would treat as options or refuse as branch names
+fn validate_branch_name(name: &str) -> Result<(), String> {
+    if name.starts_with('-') || !git2::Branch::name_is_valid(name).unwrap_or(false) {
+        return Err(format!("Invalid branch name: {}", name));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Push / Pull (git CLI)
+// ============================================================================
+
+/// "Writing objects:  50% (3/6), 1.2 KiB | 1.2 MiB/s" or "Enumerating objects: 12, done."
+static PROGRESS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:remote: )?([A-Za-z][A-Za-z ]*?):\s+(?:(\d+)% \((\d+)/(\d+)\)|(\d+))")
+        .expect("PROGRESS_RE should compile")
+});
+
+fn parse_progress_line(line: &str, project_path: &str, operation: &str) -> Option<GitProgressEvent> {
+    let caps = PROGRESS_RE.captures(line)?;
+    let num = |i: usize| caps.get(i).and_then(|m| m.as_str().parse::<u64>().ok());
+    let percent = num(2).and_then(|p| u32::try_from(p).ok());
+
+    Some(GitProgressEvent {
+        project_path: project_path.to_string(),
+        operation: operation.to_string(),
+        stage: caps.get(1)?.as_str().to_string(),
+        percent,
+        current: if percent.is_some() { num(3) } else { num(5) },
+        total: num(4),
+    })
+}
+
+/// Turn common auth/rejection failures into messages that say what to do next
+fn explain_remote_error(operation: &str, remote: &str, stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    let auth_failed = [
+        "authentication failed",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "permission denied (publickey",
+        "access denied",
+        "invalid username or password",
+        "returned error: 403",
+    ]
+    .iter()
+    .any(|p| lower.contains(p));
+
+    if auth_failed {
+        return format!(
+            "Authentication to '{}' failed. Set up a credential helper (git config --global credential.helper) \
+             or an SSH key for this remote, then retry. Details: {}",
+            remote,
+            stderr.trim()
+        );
+    }
+    if lower.contains("host key verification failed") {
+        return format!(
+            "The SSH host key for '{}' is not trusted yet. Connect once from a terminal (ssh -T) to accept it, then retry.",
+            remote
+        );
+    }
+    if lower.contains("could not resolve host") || lower.contains("unable to access") {
+        return format!(
+            "Cannot reach remote '{}'. Check the network and the remote URL (git remote -v). Details: {}",
+            remote,
+            stderr.trim()
+        );
+    }
+    if operation == "push" && (lower.contains("non-fast-forward") || lower.contains("fetch first")) {
+        return "Push rejected: the remote has commits you don't have. Pull first, then push again."
+            .to_string();
+    }
+    if lower.contains("conflict") {
+        return format!(
+            "Pull stopped with merge conflicts. Resolve them in the working tree and commit. Details: {}",
+            stderr.trim()
+        );
+    }
+    format!("git {} failed: {}", operation, stderr.trim())
+}
+
+/// Run a network git command, emitting "git-progress" events parsed from its stderr.
+/// Returns the non-progress output on success.
+fn run_git_with_progress(
+    app: &AppHandle,
+    project_path: &str,
+    operation: &str,
+    remote: &str,
+    args: &[&str],
+) -> Result<String, String> {
+    let mut child = Command::new("git")
+        .args(["-C", project_path])
+        .args(args)
+        // Fail fast instead of waiting for a password prompt nobody can see
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git {}: {}", operation, e))?;
+
+    let mut messages: Vec<String> = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buf = [0u8; 4096];
+        let mut pending: Vec<u8> = Vec::new();
+        let mut last_event: Option<GitProgressEvent> = None;
+
+        loop {
+            let n = stderr.read(&mut buf).unwrap_or(0);
+            if n == 0 {
+                break;
+            }
+            pending.extend_from_slice(buf.get(..n).unwrap_or_default());
+
+            // Progress lines are terminated by \r while updating, \n when done
+            while let Some(pos) = pending.iter().position(|b| *b == b'\r' || *b == b'\n') {
+                let segment: Vec<u8> = pending.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&segment).trim().to_string();
+                if line.is_empty() {
+                    continue;
+                }
+                let Some(event) = parse_progress_line(&line, project_path, operation) else {
+                    messages.push(line);
+                    continue;
+                };
+                if last_event.as_ref() != Some(&event) {
+                    let _ = app.emit("git-progress", event.clone());
+                    last_event = Some(event);
+                }
+            }
+        }
+        if !pending.is_empty() {
+            messages.push(String::from_utf8_lossy(&pending).trim().to_string());
+        }
+    }
+
+    let mut stdout = String::new();
+    if let Some(mut out) = child.stdout.take() {
+        let _ = out.read_to_string(&mut stdout);
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for git {}: {}", operation, e))?;
+
+    let output = stdout
         .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let parts: Vec<&str> = line.splitn(5, '|').collect();
-            let message = parts.get(2).unwrap_or(&"").to_string();
-            let feat_name = parse_feat_from_message(&message);
-
-            CommitInfo {
-                hash: parts.first().unwrap_or(&"").to_string(),
-                short_hash: parts.get(1).unwrap_or(&"").to_string(),
-                message,
-                timestamp: parts.get(3).unwrap_or(&"0").parse().unwrap_or(0),
-                author: parts.get(4).unwrap_or(&"").to_string(),
-                feat_name,
+        .map(str::to_string)
+        .chain(messages)
+        .filter(|l| !l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if status.success() {
+        Ok(output)
+    } else {
+        Err(explain_remote_error(operation, remote, &output))
+    }
+}
+
+// ============================================================================
+// libgit2 Read Helpers
+// ============================================================================
+
+fn git2_err(e: git2::Error) -> String {
+    format!("git error: {}", e.message())
+}
+
+/// Validate the path and open the repository containing it
+fn open_repo(project_path: &str) -> AppResult<Repository> {
+    security::validate_decoded_path(project_path)?;
+    Repository::discover(project_path)
+        .map_err(|e| AppError::not_found(git2_err(e)).with_context(project_path))
+}
+
+fn has_changes_in(repo: &Repository) -> Result<bool, String> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .include_ignored(false)
+        .recurse_untracked_dirs(false);
+    let statuses = repo.statuses(Some(&mut opts)).map_err(git2_err)?;
+    Ok(!statuses.is_empty())
+}
+
+/// Ref labels per commit, in `git log --decorate` style ("HEAD -> main", "tag: v1")
+fn ref_decorations(repo: &Repository) -> HashMap<Oid, Vec<String>> {
+    let mut decorations: HashMap<Oid, Vec<String>> = HashMap::new();
+
+    let head = repo.head().ok();
+    let head_branch = head
+        .as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(String::from));
+    if let Some(oid) = head.as_ref().and_then(git2::Reference::target) {
+        let label = head_branch
+            .as_ref()
+            .map_or_else(|| "HEAD".to_string(), |b| format!("HEAD -> {}", b));
+        decorations.entry(oid).or_default().push(label);
+    }
+
+    let Ok(references) = repo.references() else {
+        return decorations;
+    };
+    for reference in references.flatten() {
+        let Some(short) = reference.shorthand().map(String::from) else {
+            continue;
+        };
+        let label = if reference.is_tag() {
+            format!("tag: {}", short)
+        } else if reference.is_remote()
+            || (reference.is_branch() && head_branch.as_deref() != Some(short.as_str()))
+        {
+            short
+        } else {
+            continue;
+        };
+        if let Ok(commit) = reference.peel_to_commit() {
+            decorations.entry(commit.id()).or_default().push(label);
+        }
+    }
+
+    decorations
+}
+
+fn commit_info(commit: &git2::Commit, decorations: &HashMap<Oid, Vec<String>>) -> CommitInfo {
+    let message = commit.summary().unwrap_or("").to_string();
+    let feat_name = parse_feat_from_message(&message);
+    let hash = commit.id().to_string();
+
+    CommitInfo {
+        short_hash: commit
+            .as_object()
+            .short_id()
+            .ok()
+            .and_then(|b| b.as_str().map(String::from))
+            .unwrap_or_else(|| hash.chars().take(7).collect()),
+        hash,
+        message,
+        timestamp: commit.time().seconds(),
+        author: commit.author().name().unwrap_or("").to_string(),
+        feat_name,
+        parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+        refs: decorations.get(&commit.id()).cloned().unwrap_or_default(),
+    }
+}
+
+fn log_in(
+    repo: &Repository,
+    limit: usize,
+    options: &GitLogOptions,
+) -> Result<Vec<CommitInfo>, String> {
+    let mut walk = repo.revwalk().map_err(git2_err)?;
+    walk.set_sorting(Sort::TIME).map_err(git2_err)?;
+    if options.all.unwrap_or(false) {
+        walk.push_glob("refs/*").map_err(git2_err)?;
+    }
+    if walk.push_head().is_err() && !options.all.unwrap_or(false) {
+        // Unborn HEAD: a repository without commits has no log
+        return Ok(Vec::new());
+    }
+
+    // Like `git log --author`, the pattern is a regex matched against "Name <email>"
+    let author_re = options
+        .author
+        .as_deref()
+        .filter(|a| !a.is_empty())
+        .map(|a| Regex::new(a).or_else(|_| Regex::new(&regex::escape(a))))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    let decorations = ref_decorations(repo);
+    let mut commits = Vec::new();
+    let mut skipped = 0;
+    for oid in walk {
+        let commit = repo.find_commit(oid.map_err(git2_err)?).map_err(git2_err)?;
+        let time = commit.time().seconds();
+        if options.since.is_some_and(|s| time < s) || options.until.is_some_and(|u| time > u) {
+            continue;
+        }
+        if let Some(re) = &author_re {
+            let author = commit.author();
+            let ident = format!(
+                "{} <{}>",
+                author.name().unwrap_or(""),
+                author.email().unwrap_or("")
+            );
+            if !re.is_match(&ident) {
+                continue;
             }
-        })
-        .collect();
+        }
+        if skipped < options.skip.unwrap_or(0) {
+            skipped += 1;
+            continue;
+        }
+        commits.push(commit_info(&commit, &decorations));
+        if commits.len() >= limit {
+            break;
+        }
+    }
 
     Ok(commits)
 }
 
+/// Render a libgit2 diff as unified patch text, then parse it like CLI output
+fn diff_to_files(diff: &mut git2::Diff) -> Result<Vec<FileDiff>, String> {
+    let mut find = git2::DiffFindOptions::new();
+    find.renames(true);
+    diff.find_similar(Some(&mut find)).map_err(git2_err)?;
+
+    let mut text = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        let content = String::from_utf8_lossy(line.content());
+        match line.origin() {
+            origin @ ('+' | '-' | ' ') => {
+                text.push(origin);
+                text.push_str(&content);
+                if !content.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            // "\ No newline at end of file" markers
+            '=' | '>' | '<' => {}
+            _ => text.push_str(&content),
+        }
+        true
+    })
+    .map_err(git2_err)?;
+
+    Ok(parse_unified_diff(&text))
+}
+
+fn diff_in(repo: &Repository, options: &GitDiffOptions) -> AppResult<Vec<FileDiff>> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(options.context_lines.unwrap_or(3));
+    for path in options.paths.iter().flatten() {
+        opts.pathspec(path);
+    }
+
+    let tree_of = |rev: &str| -> AppResult<git2::Tree> {
+        repo.revparse_single(rev)
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(|_| AppError::invalid_input(format!("Invalid revision: {}", rev)))
+    };
+
+    let mut diff = match options.mode.as_deref().unwrap_or("unstaged") {
+        "unstaged" => {
+            if options.include_untracked.unwrap_or(false) {
+                opts.include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
+            }
+            repo.diff_index_to_workdir(None, Some(&mut opts))
+        }
+        "staged" => {
+            // An unborn HEAD diffs the index against the empty tree
+            let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            repo.diff_tree_to_index(head.as_ref(), None, Some(&mut opts))
+        }
+        "range" => {
+            let from = options
+                .from
+                .as_deref()
+                .ok_or_else(|| AppError::invalid_input("'from' is required for range diffs"))?;
+            let from_tree = tree_of(from)?;
+            match options.to.as_deref() {
+                Some(to) => {
+                    let to_tree = tree_of(to)?;
+                    repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), Some(&mut opts))
+                }
+                None => repo.diff_tree_to_workdir_with_index(Some(&from_tree), Some(&mut opts)),
+            }
+        }
+        other => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown diff mode: {}",
+                other
+            )))
+        }
+    }
+    .map_err(git2_err)?;
+
+    Ok(diff_to_files(&mut diff)?)
+}
+
+fn branches_in(repo: &Repository) -> Result<Vec<GitBranch>, String> {
+    let mut branches = Vec::new();
+
+    for entry in repo.branches(None).map_err(git2_err)? {
+        let (branch, kind) = entry.map_err(git2_err)?;
+        let Some(name) = branch.name().ok().flatten().map(String::from) else {
+            continue;
+        };
+        let is_remote = kind == BranchType::Remote;
+        // Skip symbolic refs like origin/HEAD
+        if is_remote && name.ends_with("/HEAD") {
+            continue;
+        }
+        let Ok(tip) = branch.get().peel_to_commit() else {
+            continue;
+        };
+
+        let upstream = branch.upstream().ok();
+        let (ahead, behind) = upstream
+            .as_ref()
+            .and_then(|u| u.get().target())
+            .and_then(|u| repo.graph_ahead_behind(tip.id(), u).ok())
+            .unwrap_or((0, 0));
+
+        branches.push(GitBranch {
+            name,
+            is_current: branch.is_head(),
+            is_remote,
+            commit: commit_info(&tip, &HashMap::new()).short_hash,
+            timestamp: tip.time().seconds(),
+            upstream: upstream.and_then(|u| u.name().ok().flatten().map(String::from)),
+            ahead: u32::try_from(ahead).unwrap_or(u32::MAX),
+            behind: u32::try_from(behind).unwrap_or(u32::MAX),
+        });
+    }
+
+    branches.sort_by(|a, b| {
+        b.is_current
+            .cmp(&a.is_current)
+            .then(a.is_remote.cmp(&b.is_remote))
+            .then(b.timestamp.cmp(&a.timestamp))
+    });
+    Ok(branches)
+}
+
+fn show_in(repo: &Repository, rev: &str) -> AppResult<CommitDetail> {
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|_| AppError::not_found(format!("Commit not found: {}", rev)))?;
+
+    let tree = commit.tree().map_err(git2_err)?;
+    // Merges are shown against their first parent; root commits against the empty tree
+    let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let mut diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+        .map_err(git2_err)?;
+
+    Ok(CommitDetail {
+        commit: commit_info(&commit, &ref_decorations(repo)),
+        body: commit.message().unwrap_or("").trim_end().to_string(),
+        files: diff_to_files(&mut diff)?,
+    })
+}
+
+// ============================================================================
+// Feature Branches (used by workspace features)
+// ============================================================================
+
+/// Name of the checked-out branch (None when detached or not a repository)
+pub fn current_branch(project_path: &str) -> Option<String> {
+    let repo = open_repo(project_path).ok()?;
+    let head = repo.head().ok().filter(git2::Reference::is_branch)?;
+    head.shorthand().map(String::from)
+}
+
+/// Create `name` from HEAD and check it out; an existing branch is just checked out
+pub fn start_branch(project_path: &str, name: &str) -> AppResult<()> {
+    let repo = open_repo(project_path)?;
+    validate_branch_name(name).map_err(AppError::invalid_input)?;
+
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        run_git_in(project_path, &["switch", name])?;
+    } else {
+        run_git_in(project_path, &["switch", "-c", name])?;
+    }
+    Ok(())
+}
+
+/// Check out `into` and merge `branch` with `strategy`. A failed merge is aborted so
+/// the working tree is left clean on `into`.
+pub fn merge_branch(
+    project_path: &str,
+    branch: &str,
+    into: &str,
+    strategy: MergeStrategy,
+    message: &str,
+) -> AppResult<()> {
+    let repo = open_repo(project_path)?;
+    validate_branch_name(branch).map_err(AppError::invalid_input)?;
+    validate_branch_name(into).map_err(AppError::invalid_input)?;
+    if has_changes_in(&repo)? {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "Commit or stash your changes before merging",
+        ));
+    }
+
+    run_git_in(project_path, &["switch", into])?;
+    let result = match strategy {
+        MergeStrategy::Merge => run_git_in(project_path, &["merge", "--no-ff", "-m", message, branch]),
+        MergeStrategy::FastForward => run_git_in(project_path, &["merge", "--ff-only", branch]),
+        MergeStrategy::Squash => run_git_in(project_path, &["merge", "--squash", branch])
+            .and_then(|_| run_git_in(project_path, &["commit", "-m", message])),
+    };
+    if let Err(e) = result {
+        let _ = run_git_in(project_path, &["merge", "--abort"]);
+        let _ = run_git_in(project_path, &["reset", "--merge"]);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Git Commands
+// ============================================================================
+
+/// Get git log for a project, newest first. `limit` + `options.skip` page through history.
+#[tauri::command]
+pub fn git_log(
+    project_path: String,
+    limit: Option<usize>,
+    options: Option<GitLogOptions>,
+) -> AppResult<Vec<CommitInfo>> {
+    // 安全验证：防止路径遍历攻击
+    let repo = open_repo(&project_path)?;
+    Ok(log_in(&repo, limit.unwrap_or(100), &options.unwrap_or_default())?)
+}
+
 /// Get git note for a commit
 #[tauri::command]
 pub fn git_get_note(
     project_path: String,
     commit_hash: String,
-) -> Result<Option<CommitNote>, String> {
-    security::validate_decoded_path(&project_path)
-        .map_err(|e| format!("Invalid project path: {}", e))?;
+) -> AppResult<Option<CommitNote>> {
+    security::validate_decoded_path(&project_path)?;
 
     let output = Command::new("git")
         .args([
@@ -121,7 +885,7 @@ pub fn git_get_note(
             &commit_hash,
         ])
         .output()
-        .map_err(|e| format!("Failed to run git notes: {}", e))?;
+        .map_err(|e| AppError::io("Failed to run git notes", &e))?;
 
     if !output.status.success() {
         // Note doesn't exist
@@ -129,8 +893,9 @@ pub fn git_get_note(
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let note: CommitNote =
-        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse note: {}", e))?;
+    let note: CommitNote = serde_json::from_str(&stdout).map_err(|e| {
+        AppError::new(ErrorCode::Parse, format!("Failed to parse note: {}", e))
+    })?;
 
     Ok(Some(note))
 }
@@ -141,9 +906,8 @@ pub fn git_set_note(
     project_path: String,
     commit_hash: String,
     note: CommitNote,
-) -> Result<(), String> {
-    security::validate_decoded_path(&project_path)
-        .map_err(|e| format!("Invalid project path: {}", e))?;
+) -> AppResult<()> {
+    security::validate_decoded_path(&project_path)?;
 
     let note_json =
         serde_json::to_string(&note).map_err(|e| format!("Failed to serialize note: {}", e))?;
@@ -162,11 +926,11 @@ pub fn git_set_note(
             &commit_hash,
         ])
         .output()
-        .map_err(|e| format!("Failed to run git notes add: {}", e))?;
+        .map_err(|e| AppError::io("Failed to run git notes add", &e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git notes add failed: {}", stderr));
+        return Err(format!("git notes add failed: {}", stderr).into());
     }
 
     Ok(())
@@ -174,25 +938,24 @@ pub fn git_set_note(
 
 /// Revert a commit
 #[tauri::command]
-pub fn git_revert(project_path: String, commit_hash: String) -> Result<String, String> {
-    security::validate_decoded_path(&project_path)
-        .map_err(|e| format!("Invalid project path: {}", e))?;
+pub fn git_revert(project_path: String, commit_hash: String) -> AppResult<String> {
+    security::validate_decoded_path(&project_path)?;
 
     let output = Command::new("git")
         .args(["-C", &project_path, "revert", "--no-edit", &commit_hash])
         .output()
-        .map_err(|e| format!("Failed to run git revert: {}", e))?;
+        .map_err(|e| AppError::io("Failed to run git revert", &e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git revert failed: {}", stderr));
+        return Err(format!("git revert failed: {}", stderr).into());
     }
 
     // Get the new commit hash
     let new_commit = Command::new("git")
         .args(["-C", &project_path, "rev-parse", "HEAD"])
         .output()
-        .map_err(|e| format!("Failed to get new commit: {}", e))?;
+        .map_err(|e| AppError::io("Failed to get new commit", &e))?;
 
     let new_hash = String::from_utf8_lossy(&new_commit.stdout)
         .trim()
@@ -202,22 +965,8 @@ pub fn git_revert(project_path: String, commit_hash: String) -> Result<String, S
 
 /// Check if there are uncommitted changes
 #[tauri::command]
-pub fn git_has_changes(project_path: String) -> Result<bool, String> {
-    security::validate_decoded_path(&project_path)
-        .map_err(|e| format!("Invalid project path: {}", e))?;
-
-    let output = Command::new("git")
-        .args(["-C", &project_path, "status", "--porcelain"])
-        .output()
-        .map_err(|e| format!("Failed to run git status: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git status failed: {}", stderr));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(!stdout.trim().is_empty())
+pub fn git_has_changes(project_path: String) -> AppResult<bool> {
+    Ok(has_changes_in(&open_repo(&project_path)?)?)
 }
 
 /// Auto commit with feat name
@@ -227,7 +976,7 @@ pub fn git_auto_commit(
     feat_name: String,
     feat_id: String,
     message: String,
-) -> Result<Option<String>, String> {
+) -> AppResult<Option<String>> {
     // Check if there are changes
     let has_changes = git_has_changes(project_path.clone())?;
     if !has_changes {
@@ -238,11 +987,11 @@ pub fn git_auto_commit(
     let add_output = Command::new("git")
         .args(["-C", &project_path, "add", "-A"])
         .output()
-        .map_err(|e| format!("Failed to run git add: {}", e))?;
+        .map_err(|e| AppError::io("Failed to run git add", &e))?;
 
     if !add_output.status.success() {
         let stderr = String::from_utf8_lossy(&add_output.stderr);
-        return Err(format!("git add failed: {}", stderr));
+        return Err(format!("git add failed: {}", stderr).into());
     }
 
     // Create commit
@@ -250,18 +999,18 @@ pub fn git_auto_commit(
     let commit_output = Command::new("git")
         .args(["-C", &project_path, "commit", "-m", &commit_message])
         .output()
-        .map_err(|e| format!("Failed to run git commit: {}", e))?;
+        .map_err(|e| AppError::io("Failed to run git commit", &e))?;
 
     if !commit_output.status.success() {
         let stderr = String::from_utf8_lossy(&commit_output.stderr);
-        return Err(format!("git commit failed: {}", stderr));
+        return Err(format!("git commit failed: {}", stderr).into());
     }
 
     // Get commit hash
     let hash_output = Command::new("git")
         .args(["-C", &project_path, "rev-parse", "HEAD"])
         .output()
-        .map_err(|e| format!("Failed to get commit hash: {}", e))?;
+        .map_err(|e| AppError::io("Failed to get commit hash", &e))?;
 
     let hash = String::from_utf8_lossy(&hash_output.stdout)
         .trim()
@@ -284,9 +1033,9 @@ pub fn git_generate_changelog(
     project_path: String,
     feat_names: Vec<String>,
     from_date: Option<i64>,
-) -> Result<String, String> {
+) -> AppResult<String> {
     // git_log 已内置路径验证，无需重复
-    let commits = git_log(project_path.clone(), Some(500))?;
+    let commits = git_log(project_path.clone(), Some(500), None)?;
 
     // Filter commits by feat names and date
     let filtered: Vec<&CommitInfo> = commits
@@ -328,3 +1077,520 @@ pub fn git_generate_changelog(
 
     Ok(md)
 }
+
+/// Structured diff for unstaged changes, staged changes, or a commit range
+#[tauri::command]
+pub fn git_diff(
+    project_path: String,
+    options: Option<GitDiffOptions>,
+) -> AppResult<Vec<FileDiff>> {
+    diff_in(&open_repo(&project_path)?, &options.unwrap_or_default())
+}
+
+/// List local and remote-tracking branches, current branch first
+#[tauri::command]
+pub fn git_list_branches(project_path: String) -> AppResult<Vec<GitBranch>> {
+    Ok(branches_in(&open_repo(&project_path)?)?)
+}
+
+/// Create a branch from `from` (default HEAD) without switching to it
+#[tauri::command]
+pub fn git_create_branch(
+    project_path: String,
+    name: String,
+    from: Option<String>,
+) -> AppResult<()> {
+    security::validate_decoded_path(&project_path)?;
+    validate_branch_name(&name).map_err(AppError::invalid_input)?;
+
+    let mut args = vec!["branch", "--", name.as_str()];
+    if let Some(from) = from.as_deref().filter(|f| !f.is_empty()) {
+        args.push(from);
+    }
+    run_git_in(&project_path, &args)?;
+    Ok(())
+}
+
+/// Check out a branch; a remote-only branch gets a local tracking branch
+#[tauri::command]
+pub fn git_switch_branch(project_path: String, name: String) -> AppResult<()> {
+    security::validate_decoded_path(&project_path)?;
+    validate_branch_name(&name).map_err(AppError::invalid_input)?;
+
+    run_git_in(&project_path, &["switch", &name])?;
+    Ok(())
+}
+
+/// Delete a local branch; unmerged branches require `force`
+#[tauri::command]
+pub fn git_delete_branch(
+    project_path: String,
+    name: String,
+    force: Option<bool>,
+) -> AppResult<()> {
+    security::validate_decoded_path(&project_path)?;
+    validate_branch_name(&name).map_err(AppError::invalid_input)?;
+
+    let repo = open_repo(&project_path)?;
+    let current = repo.head().ok().filter(git2::Reference::is_branch);
+    if current.as_ref().and_then(git2::Reference::shorthand) == Some(name.as_str()) {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("Cannot delete the current branch: {}", name),
+        ));
+    }
+
+    let flag = if force.unwrap_or(false) { "-D" } else { "-d" };
+    run_git_in(&project_path, &["branch", flag, "--", &name])?;
+    Ok(())
+}
+
+/// Check out `branch` into a new worktree at `dest`; the branch is created from HEAD
+/// if it does not exist yet
+#[tauri::command]
+pub fn git_add_worktree(
+    project_path: String,
+    branch: String,
+    dest: String,
+) -> AppResult<GitWorktree> {
+    security::validate_decoded_path(&project_path)?;
+    security::validate_decoded_path(&dest)?;
+    validate_branch_name(&branch).map_err(AppError::invalid_input)?;
+
+    if std::path::Path::new(&dest).exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("Worktree destination already exists: {}", dest),
+        ));
+    }
+
+    let local_ref = format!("refs/heads/{}", branch);
+    let branch_exists =
+        run_git_in(&project_path, &["show-ref", "--verify", "--quiet", &local_ref]).is_ok();
+    if branch_exists {
+        run_git_in(&project_path, &["worktree", "add", "--", &dest, &branch])?;
+    } else {
+        run_git_in(&project_path, &["worktree", "add", "-b", &branch, "--", &dest])?;
+    }
+
+    Ok(git_list_worktrees(project_path)?
+        .into_iter()
+        .find(|wt| wt.branch.as_deref() == Some(branch.as_str()))
+        .ok_or_else(|| format!("Worktree for {} was not created", branch))?)
+}
+
+/// List worktrees of a repository (main working copy first)
+#[tauri::command]
+pub fn git_list_worktrees(project_path: String) -> AppResult<Vec<GitWorktree>> {
+    security::validate_decoded_path(&project_path)?;
+
+    let output = run_git_in(&project_path, &["worktree", "list", "--porcelain"])?;
+    Ok(parse_worktree_list(&output))
+}
+
+/// Remove a linked worktree; a worktree with local changes requires `force`
+#[tauri::command]
+pub fn git_remove_worktree(
+    project_path: String,
+    path: String,
+    force: Option<bool>,
+) -> AppResult<()> {
+    security::validate_decoded_path(&project_path)?;
+
+    // git reports canonical paths (e.g. /private/var on macOS)
+    let canonical = std::fs::canonicalize(&path).ok();
+    let worktrees = git_list_worktrees(project_path.clone())?;
+    let target = worktrees
+        .iter()
+        .find(|wt| {
+            wt.path == path
+                || canonical
+                    .as_ref()
+                    .is_some_and(|c| std::fs::canonicalize(&wt.path).ok().as_ref() == Some(c))
+        })
+        .ok_or_else(|| {
+            AppError::not_found(format!("Not a worktree of this repository: {}", path))
+        })?;
+    if target.is_main {
+        return Err(AppError::invalid_input("Cannot remove the main working tree"));
+    }
+
+    let mut args = vec!["worktree", "remove"];
+    if force.unwrap_or(false) {
+        args.push("--force");
+    }
+    args.extend(["--", target.path.as_str()]);
+    run_git_in(&project_path, &args)?;
+    Ok(())
+}
+
+/// Show a single commit: metadata, full message and per-file diff
+#[tauri::command]
+pub fn git_show(project_path: String, commit: String) -> AppResult<CommitDetail> {
+    show_in(&open_repo(&project_path)?, &commit)
+}
+
+/// Push a branch (default: the current one) and set its upstream.
+/// Progress is reported through "git-progress" events.
+#[tauri::command]
+pub async fn git_push(
+    app: AppHandle,
+    project_path: String,
+    remote: Option<String>,
+    branch: Option<String>,
+) -> AppResult<String> {
+    security::validate_decoded_path(&project_path)?;
+    let remote = remote.unwrap_or_else(|| "origin".to_string());
+    if remote.is_empty() || remote.starts_with('-') {
+        return Err(AppError::invalid_input(format!("Invalid remote: {}", remote)));
+    }
+    if let Some(branch) = &branch {
+        validate_branch_name(branch).map_err(AppError::invalid_input)?;
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let refspec = branch.as_deref().unwrap_or("HEAD");
+        run_git_with_progress(
+            &app,
+            &project_path,
+            "push",
+            &remote,
+            &["push", "--progress", "-u", &remote, refspec],
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Pull (merge, or rebase when `rebase` is set) from a remote branch, or from the
+/// current branch's upstream when neither is given
+#[tauri::command]
+pub async fn git_pull(
+    app: AppHandle,
+    project_path: String,
+    remote: Option<String>,
+    branch: Option<String>,
+    rebase: Option<bool>,
+) -> AppResult<String> {
+    security::validate_decoded_path(&project_path)?;
+    if remote.as_deref().is_some_and(|r| r.is_empty() || r.starts_with('-')) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid remote: {}",
+            remote.unwrap_or_default()
+        )));
+    }
+    if let Some(branch) = &branch {
+        if remote.is_none() {
+            return Err(AppError::invalid_input(
+                "A remote is required when pulling a specific branch",
+            ));
+        }
+        validate_branch_name(branch).map_err(AppError::invalid_input)?;
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let mode = if rebase.unwrap_or(false) {
+            "--rebase"
+        } else {
+            "--no-rebase"
+        };
+        let mut args = vec!["pull", "--progress", mode, "--no-edit"];
+        args.extend(remote.as_deref());
+        args.extend(branch.as_deref());
+        run_git_with_progress(
+            &app,
+            &project_path,
+            "pull",
+            remote.as_deref().unwrap_or("upstream"),
+            &args,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_modified_file() {
+        let diff = r"diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,4 @@ fn main() {
+ line1
+-line2
++line2 changed
++line2b
+ line3
+\ No newline at end of file
+";
+        let files = parse_unified_diff(diff);
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(file.path, "src/main.rs");
+        assert_eq!(file.status, "modified");
+        assert_eq!((file.additions, file.deletions), (2, 1));
+
+        let hunk = &file.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (1, 3, 1, 4));
+        assert_eq!(hunk.header, "fn main() {");
+        let numbers: Vec<(&str, Option<u32>, Option<u32>)> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.kind.as_str(), l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                ("context", Some(1), Some(1)),
+                ("delete", Some(2), None),
+                ("add", None, Some(2)),
+                ("add", None, Some(3)),
+                ("context", Some(3), Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_added_deleted_renamed_binary() {
+        let diff = "diff --git a/new.txt b/new.txt\n\
+new file mode 100644\n\
+--- /dev/null\n\
++++ b/new.txt\n\
+@@ -0,0 +1 @@\n\
++hello\n\
+diff --git a/old.txt b/old.txt\n\
+deleted file mode 100644\n\
+--- a/old.txt\n\
++++ /dev/null\n\
+@@ -1 +0,0 @@\n\
+-bye\n\
+diff --git a/a dir/x.md b/b dir/y.md\n\
+similarity index 100%\n\
+rename from a dir/x.md\n\
+rename to b dir/y.md\n\
+diff --git a/logo.png b/logo.png\n\
+Binary files a/logo.png and b/logo.png differ\n";
+        let files = parse_unified_diff(diff);
+        let summary: Vec<(&str, &str, bool)> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.status.as_str(), f.binary))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("new.txt", "added", false),
+                ("old.txt", "deleted", false),
+                ("b dir/y.md", "renamed", false),
+                ("logo.png", "modified", true),
+            ]
+        );
+        assert_eq!(files[0].hunks[0].lines[0].new_line, Some(1));
+        assert_eq!(files[2].old_path.as_deref(), Some("a dir/x.md"));
+    }
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = "worktree /home/u/app\nHEAD aaa\nbranch refs/heads/main\n\n\
+worktree /home/u/app-wt/feat-x\nHEAD bbb\nbranch refs/heads/feat/x\nlocked\n\n\
+worktree /home/u/app-wt/tmp\nHEAD ccc\ndetached\nprunable gitdir file points to non-existent location\n";
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(worktrees.len(), 3);
+
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("main"));
+
+        assert!(!worktrees[1].is_main && worktrees[1].locked);
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feat/x"));
+        assert_eq!(worktrees[1].path, "/home/u/app-wt/feat-x");
+
+        assert!(worktrees[2].branch.is_none() && worktrees[2].prunable);
+        assert_eq!(worktrees[2].head, "ccc");
+    }
+
+    fn commit_file(repo: &Repository, name: &str, content: &str, message: &str) -> Oid {
+        let workdir = repo.workdir().expect("workdir");
+        std::fs::write(workdir.join(name), content).expect("write file");
+        let mut index = repo.index().expect("index");
+        index.add_path(std::path::Path::new(name)).expect("add path");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("write tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("Ann", "ann@example.com").expect("signature");
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .expect("commit")
+    }
+
+    #[test]
+    fn test_log_and_show_with_libgit2() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let repo = Repository::init(temp.path()).expect("init repo");
+        assert!(log_in(&repo, 10, &GitLogOptions::default())
+            .expect("log of empty repo")
+            .is_empty());
+
+        let first = commit_file(&repo, "a.txt", "one\n", "init");
+        let second = commit_file(&repo, "a.txt", "one\ntwo\n", "feat(auth): add | pipe\n\nBody text");
+        let head = repo.find_commit(second).expect("find commit");
+        repo.tag_lightweight("v1", head.as_object(), false)
+            .expect("tag");
+
+        let commits = log_in(&repo, 10, &GitLogOptions::default()).expect("log");
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].message, "feat(auth): add | pipe");
+        assert_eq!(commits[0].feat_name.as_deref(), Some("auth"));
+        assert_eq!(commits[0].parents, vec![first.to_string()]);
+        assert!(commits[0].refs.iter().any(|r| r.starts_with("HEAD -> ")));
+        assert!(commits[0].refs.contains(&"tag: v1".to_string()));
+        assert!(commits[1].parents.is_empty());
+
+        let paged = GitLogOptions {
+            skip: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(log_in(&repo, 10, &paged).expect("log")[0].hash, first.to_string());
+
+        let by_author = GitLogOptions {
+            author: Some("nobody".to_string()),
+            ..Default::default()
+        };
+        assert!(log_in(&repo, 10, &by_author).expect("log").is_empty());
+
+        let detail = show_in(&repo, &second.to_string()).expect("show");
+        assert_eq!(detail.body, "feat(auth): add | pipe\n\nBody text");
+        assert_eq!(detail.files[0].path, "a.txt");
+        assert_eq!((detail.files[0].additions, detail.files[0].deletions), (1, 0));
+
+        let root = show_in(&repo, &first.to_string()).expect("show root");
+        assert_eq!(root.files[0].status, "added");
+    }
+
+    #[test]
+    fn test_status_diff_and_branches_with_libgit2() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let repo = Repository::init(temp.path()).expect("init repo");
+        commit_file(&repo, "a.txt", "one\ntwo\n", "init");
+        assert!(!has_changes_in(&repo).expect("status"));
+
+        std::fs::write(temp.path().join("a.txt"), "one\nTWO").expect("modify");
+        std::fs::write(temp.path().join("new.txt"), "fresh\n").expect("untracked");
+        assert!(has_changes_in(&repo).expect("status"));
+
+        let unstaged = diff_in(&repo, &GitDiffOptions::default()).expect("diff");
+        assert_eq!(unstaged.len(), 1);
+        let lines: Vec<(&str, &str)> = unstaged[0].hunks[0]
+            .lines
+            .iter()
+            .map(|l| (l.kind.as_str(), l.content.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![("context", "one"), ("delete", "two"), ("add", "TWO")]
+        );
+
+        let with_untracked = GitDiffOptions {
+            include_untracked: Some(true),
+            ..Default::default()
+        };
+        let files = diff_in(&repo, &with_untracked).expect("diff");
+        assert!(files.iter().any(|f| f.path == "new.txt" && f.status == "added"));
+
+        let staged = GitDiffOptions {
+            mode: Some("staged".to_string()),
+            ..Default::default()
+        };
+        assert!(diff_in(&repo, &staged).expect("diff").is_empty());
+
+        let head = repo.head().expect("head").peel_to_commit().expect("commit");
+        repo.branch("feat/x", &head, false).expect("branch");
+        let branches = branches_in(&repo).expect("branches");
+        assert_eq!(branches.len(), 2);
+        assert!(branches[0].is_current);
+        assert_eq!(branches[1].name, "feat/x");
+        assert!(!branches[1].is_current && !branches[1].is_remote);
+    }
+
+    #[test]
+    fn test_parse_progress_line() {
+        let parse = |line: &str| {
+            parse_progress_line(line, "/p", "push").map(|e| (e.stage, e.percent, e.current, e.total))
+        };
+        assert_eq!(
+            parse("Writing objects:  50% (3/6), 1.2 KiB | 1.2 MiB/s"),
+            Some(("Writing objects".to_string(), Some(50), Some(3), Some(6)))
+        );
+        assert_eq!(
+            parse("remote: Counting objects: 100% (12/12), done."),
+            Some(("Counting objects".to_string(), Some(100), Some(12), Some(12)))
+        );
+        assert_eq!(
+            parse("Enumerating objects: 5, done."),
+            Some(("Enumerating objects".to_string(), None, Some(5), None))
+        );
+        assert_eq!(parse("To github.com:me/repo.git"), None);
+    }
+
+    #[test]
+    fn test_explain_remote_error() {
+        let auth = explain_remote_error(
+            "push",
+            "origin",
+            "fatal: could not read Username for 'https://github.com': terminal prompts disabled",
+        );
+        assert!(auth.contains("credential helper"));
+
+        let rejected = explain_remote_error(
+            "push",
+            "origin",
+            "! [rejected] main -> main (fetch first)",
+        );
+        assert!(rejected.contains("Pull first"));
+
+        assert!(explain_remote_error("pull", "origin", "boom").starts_with("git pull failed"));
+    }
+
+    #[test]
+    fn test_feature_branch_start_and_merge() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().to_string_lossy().to_string();
+        let repo = Repository::init(temp.path()).expect("init repo");
+        let mut config = repo.config().expect("config");
+        config.set_str("user.name", "Ann").expect("set name");
+        config.set_str("user.email", "ann@example.com").expect("set email");
+        commit_file(&repo, "a.txt", "one\n", "init");
+        let base = current_branch(&path).expect("base branch");
+
+        start_branch(&path, "feat/login").expect("start branch");
+        assert_eq!(current_branch(&path).as_deref(), Some("feat/login"));
+        commit_file(&repo, "b.txt", "two\n", "add b");
+        commit_file(&repo, "c.txt", "three\n", "add c");
+
+        // Dirty tree refuses to merge
+        std::fs::write(temp.path().join("dirty.txt"), "x").expect("write");
+        assert!(merge_branch(&path, "feat/login", &base, MergeStrategy::Squash, "Login").is_err());
+        std::fs::remove_file(temp.path().join("dirty.txt")).expect("remove");
+
+        merge_branch(&path, "feat/login", &base, MergeStrategy::Squash, "Login").expect("squash");
+        assert_eq!(current_branch(&path).as_deref(), Some(base.as_str()));
+        let commits = log_in(&repo, 10, &GitLogOptions::default()).expect("log");
+        assert_eq!(commits[0].message, "Login");
+        assert_eq!(commits[0].parents.len(), 1);
+        assert!(temp.path().join("c.txt").exists());
+
+        // Re-starting an existing branch just checks it out
+        start_branch(&path, "feat/login").expect("restart branch");
+        assert_eq!(current_branch(&path).as_deref(), Some("feat/login"));
+        assert!(start_branch(&path, "-bad").is_err());
+    }
+}
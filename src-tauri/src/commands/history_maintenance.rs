@@ -0,0 +1,346 @@
+/**
+ * [INPUT]: 依赖 ~/.claude/history.jsonl, serde_json, chrono, crate::security (原子写入, lovstudio 目录)
+ * [OUTPUT]: 对外提供 analyze_history, compact_history 命令与 HistoryAnalysis/HistoryCompaction 类型
+ * [POS]: commands/ 模块的 history.jsonl 体检与修复，报告损坏行与体积，按保留天数去重压缩并先备份原文件
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A line of history.jsonl that is not a JSON object
+#[derive(Debug, Clone, Serialize)]
+pub struct MalformedLine {
+    /// 1-based
+    pub line: usize,
+    pub preview: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HistoryAnalysis {
+    pub path: String,
+    pub exists: bool,
+    pub size_bytes: u64,
+    /// Non-blank lines
+    pub total_lines: usize,
+    pub valid_lines: usize,
+    pub malformed_lines: usize,
+    pub blank_lines: usize,
+    /// Valid lines repeating an earlier entry
+    pub duplicate_lines: usize,
+    /// Valid lines without a numeric `timestamp`; compaction never drops them by age
+    pub undated_lines: usize,
+    /// Unix ms
+    pub oldest: Option<u64>,
+    pub newest: Option<u64>,
+    /// First few malformed lines
+    pub malformed_samples: Vec<MalformedLine>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryCompaction {
+    /// Copy of the file as it was before compaction
+    pub backup_path: String,
+    pub lines_before: usize,
+    pub lines_after: usize,
+    pub removed_malformed: usize,
+    pub removed_duplicates: usize,
+    pub removed_expired: usize,
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+const MAX_MALFORMED_SAMPLES: usize = 20;
+const PREVIEW_CHARS: usize = 120;
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+// ============================================================================
+// Scan
+// ============================================================================
+
+enum Entry<'a> {
+    Blank,
+    Malformed(String),
+    Valid {
+        line: &'a str,
+        /// Compact re-serialization, so entries differing only in whitespace match
+        key: String,
+        timestamp: Option<u64>,
+    },
+}
+
+fn parse_line(line: &str) -> Entry<'_> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Entry::Blank;
+    }
+    match serde_json::from_str::<Value>(line) {
+        Ok(value @ Value::Object(_)) => Entry::Valid {
+            line,
+            timestamp: value.get("timestamp").and_then(Value::as_u64),
+            key: value.to_string(),
+        },
+        Ok(_) => Entry::Malformed("not a JSON object".to_string()),
+        Err(e) => Entry::Malformed(e.to_string()),
+    }
+}
+
+fn preview(line: &str) -> String {
+    let line = line.trim();
+    let mut out: String = line.chars().take(PREVIEW_CHARS).collect();
+    if out.len() < line.len() {
+        out.push('…');
+    }
+    out
+}
+
+fn analyze(content: &str) -> HistoryAnalysis {
+    let mut analysis = HistoryAnalysis::default();
+    let mut seen = HashSet::new();
+
+    for (i, raw) in content.lines().enumerate() {
+        match parse_line(raw) {
+            Entry::Blank => analysis.blank_lines += 1,
+            Entry::Malformed(error) => {
+                analysis.malformed_lines += 1;
+                if analysis.malformed_samples.len() < MAX_MALFORMED_SAMPLES {
+                    analysis.malformed_samples.push(MalformedLine {
+                        line: i + 1,
+                        preview: preview(raw),
+                        error,
+                    });
+                }
+            }
+            Entry::Valid { key, timestamp, .. } => {
+                analysis.valid_lines += 1;
+                if !seen.insert(key) {
+                    analysis.duplicate_lines += 1;
+                }
+                match timestamp {
+                    Some(ts) => {
+                        analysis.oldest = Some(analysis.oldest.map_or(ts, |o| o.min(ts)));
+                        analysis.newest = Some(analysis.newest.map_or(ts, |n| n.max(ts)));
+                    }
+                    None => analysis.undated_lines += 1,
+                }
+            }
+        }
+    }
+    analysis.total_lines = analysis.valid_lines + analysis.malformed_lines;
+    analysis
+}
+
+/// Cleaned file content plus (malformed, duplicates, expired) counts
+///
+/// Order is kept and the first copy of a duplicate wins; entries older than `cutoff_ms`
+/// are dropped.
+fn compact(content: &str, cutoff_ms: Option<u64>) -> (String, usize, usize, usize) {
+    let mut out = String::with_capacity(content.len());
+    let mut seen = HashSet::new();
+    let (mut malformed, mut duplicates, mut expired) = (0, 0, 0);
+
+    for raw in content.lines() {
+        match parse_line(raw) {
+            Entry::Blank => {}
+            Entry::Malformed(_) => malformed += 1,
+            Entry::Valid {
+                line,
+                key,
+                timestamp,
+            } => {
+                if matches!((timestamp, cutoff_ms), (Some(ts), Some(cutoff)) if ts < cutoff) {
+                    expired += 1;
+                } else if !seen.insert(key) {
+                    duplicates += 1;
+                } else {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    (out, malformed, duplicates, expired)
+}
+
+// ============================================================================
+// File operations
+// ============================================================================
+
+fn history_path() -> PathBuf {
+    security::get_claude_dir_or_fallback().join("history.jsonl")
+}
+
+fn backups_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("history-backups")
+}
+
+fn analyze_file(path: &Path) -> AppResult<HistoryAnalysis> {
+    let mut analysis = match fs::read(path) {
+        Ok(bytes) => {
+            let mut analysis = analyze(&String::from_utf8_lossy(&bytes));
+            analysis.exists = true;
+            analysis.size_bytes = bytes.len() as u64;
+            analysis
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => HistoryAnalysis::default(),
+        Err(e) => return Err(AppError::io("Failed to read history.jsonl", &e)),
+    };
+    analysis.path = path.to_string_lossy().to_string();
+    Ok(analysis)
+}
+
+fn compact_file(
+    path: &Path,
+    backups: &Path,
+    cutoff_ms: Option<u64>,
+) -> AppResult<HistoryCompaction> {
+    let bytes = fs::read(path).map_err(|e| AppError::io("Failed to read history.jsonl", &e))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let lines_before = content.lines().filter(|l| !l.trim().is_empty()).count();
+    let (cleaned, removed_malformed, removed_duplicates, removed_expired) =
+        compact(&content, cutoff_ms);
+
+    fs::create_dir_all(backups)
+        .map_err(|e| AppError::io("Failed to create history backup directory", &e))?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut backup_path = backups.join(format!("history-{}.jsonl", stamp));
+    let mut n = 2;
+    while backup_path.exists() {
+        backup_path = backups.join(format!("history-{}-{}.jsonl", stamp, n));
+        n += 1;
+    }
+    fs::write(&backup_path, &bytes)
+        .map_err(|e| AppError::io("Failed to back up history.jsonl", &e))?;
+
+    // Claude Code appends a line per prompt; don't overwrite one that landed meanwhile
+    let current_len = fs::metadata(path).map_or(0, |m| m.len());
+    if current_len != bytes.len() as u64 {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "history.jsonl changed while compacting, try again",
+        ));
+    }
+    security::atomic_write_string(&path.to_path_buf(), &cleaned)?;
+
+    Ok(HistoryCompaction {
+        backup_path: backup_path.to_string_lossy().to_string(),
+        lines_before,
+        lines_after: cleaned.lines().count(),
+        removed_malformed,
+        removed_duplicates,
+        removed_expired,
+        size_before: bytes.len() as u64,
+        size_after: cleaned.len() as u64,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Size, malformed lines and duplicates of ~/.claude/history.jsonl
+#[tauri::command]
+pub async fn analyze_history() -> AppResult<HistoryAnalysis> {
+    tauri::async_runtime::spawn_blocking(|| analyze_file(&history_path()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Rewrite ~/.claude/history.jsonl without malformed lines and duplicates
+///
+/// With `keep_days`, entries older than that many days are dropped too. The original
+/// is copied to ~/.lovstudio/lovcode/history-backups/ first.
+#[tauri::command]
+pub async fn compact_history(keep_days: Option<u32>) -> AppResult<HistoryCompaction> {
+    if keep_days == Some(0) {
+        return Err(AppError::invalid_input("keep_days must be at least 1"));
+    }
+    let cutoff_ms = keep_days.map(|days| {
+        let now_ms = u64::try_from(chrono::Utc::now().timestamp_millis()).unwrap_or(0);
+        now_ms.saturating_sub(u64::from(days) * DAY_MS)
+    });
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = history_path();
+        if !path.exists() {
+            return Err(AppError::not_found("history.jsonl not found"));
+        }
+        compact_file(&path, &backups_dir(), cutoff_ms)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SAMPLE: &str = concat!(
+        "{\"display\":\"a\",\"timestamp\":1000,\"project\":\"/p\"}\n",
+        "\n",
+        "{\"display\":\"a\", \"timestamp\":1000, \"project\":\"/p\"}\n",
+        "{\"display\":\"b\",\"timesta\n",
+        "[1,2]\n",
+        "{\"display\":\"c\",\"timestamp\":5000}\n",
+        "{\"display\":\"undated\"}\n",
+    );
+
+    #[test]
+    fn test_analyze_history() {
+        let analysis = analyze(SAMPLE);
+        assert_eq!(analysis.total_lines, 6);
+        assert_eq!(analysis.valid_lines, 4);
+        assert_eq!(analysis.malformed_lines, 2);
+        assert_eq!(analysis.blank_lines, 1);
+        assert_eq!(analysis.duplicate_lines, 1);
+        assert_eq!(analysis.undated_lines, 1);
+        assert_eq!((analysis.oldest, analysis.newest), (Some(1000), Some(5000)));
+        let lines: Vec<usize> = analysis.malformed_samples.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![4, 5]);
+
+        let (cleaned, malformed, duplicates, expired) = compact(SAMPLE, None);
+        assert_eq!((malformed, duplicates, expired), (2, 1, 0));
+        assert_eq!(cleaned.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_compact_history_file() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("history.jsonl");
+        let backups = temp.path().join("backups");
+        fs::write(&path, SAMPLE).expect("write history");
+
+        let result = compact_file(&path, &backups, Some(2000)).expect("compact");
+        assert_eq!(result.lines_before, 6);
+        assert_eq!(result.lines_after, 2);
+        assert_eq!(result.removed_malformed, 2);
+        // Both copies of "a" are expired, so neither counts as a duplicate
+        assert_eq!(result.removed_duplicates, 0);
+        assert_eq!(result.removed_expired, 2);
+        assert_eq!(
+            fs::read_to_string(&path).expect("read compacted"),
+            "{\"display\":\"c\",\"timestamp\":5000}\n{\"display\":\"undated\"}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&result.backup_path).expect("read backup"),
+            SAMPLE
+        );
+
+        let analysis = analyze_file(&path).expect("analyze");
+        assert_eq!(analysis.malformed_lines + analysis.duplicate_lines, 0);
+    }
+}
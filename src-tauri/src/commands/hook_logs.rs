@@ -0,0 +1,410 @@
+/**
+ * [INPUT]: 依赖 serde_json, chrono (时间过滤), crate::security (lovstudio 目录, shell_escape)
+ * [OUTPUT]: 对外提供 get_hook_log_path, list_hook_events, clear_hook_events 命令, ensure_hook_logger/wrap_hook_config 供模板安装使用, HookEvent/HookEventFilter/HookEventStatus
+ * [POS]: commands/ 模块的 Hook 事件日志，约定日志路径 hook-logs/events.jsonl，市场 Hook 模板经 log-hook.sh 包装后写入，这里解析展示
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EVENTS_FILE: &str = "events.jsonl";
+const LOGGER_FILE: &str = "log-hook.sh";
+const DEFAULT_EVENT_LIMIT: usize = 200;
+const MAX_EVENT_LIMIT: usize = 5000;
+/// The log is cut back to its newest lines once it grows past this
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const KEEP_LINES_AFTER_TRIM: usize = 5000;
+
+/// Runs a hook command with the hook's stdin and appends one JSON line to events.jsonl
+/// next to itself. stdout and the exit code pass through, so blocking hooks still work.
+/// Usage: log-hook.sh <hook name> <command>
+const LOGGER_SCRIPT: &str = r#"#!/bin/sh
+# lovcode hook logger. Each run appends one line to events.jsonl next to this script:
+# {"ts","hook","exit_code","duration_secs","stderr","input"}
+# input is the JSON Claude Code passed to the hook on stdin.
+name="$1"
+cmd="$2"
+log="$(dirname "$0")/events.jsonl"
+input="$(cat)"
+err="$(mktemp "${TMPDIR:-/tmp}/lovcode-hook.XXXXXX")"
+start=$(date +%s)
+printf '%s' "$input" | sh -c "$cmd" 2>"$err"
+code=$?
+end=$(date +%s)
+cat "$err" >&2
+esc() { awk '{ gsub(/\\/, "&&"); gsub(/"/, "\\\""); gsub(/\t/, "\\t"); gsub(/\r/, ""); printf "%s%s", (NR > 1 ? "\\n" : ""), $0 }'; }
+case "$input" in
+  "{"*) input_json=$(printf '%s' "$input" | tr '\n' ' ') ;;
+  *) input_json=null ;;
+esac
+printf '{"ts":"%s","hook":"%s","exit_code":%d,"duration_secs":%d,"stderr":"%s","input":%s}\n' \
+  "$(date -u +%Y-%m-%dT%H:%M:%SZ)" "$(printf '%s' "$name" | esc)" "$code" "$((end - start))" \
+  "$(head -c 4000 "$err" | esc)" "$input_json" >> "$log"
+rm -f "$err"
+exit $code
+"#;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// "ok" (exit 0), "blocked" (exit 2: the hook stopped the action and told Claude why),
+/// "failed" (any other exit code) or "unknown" (logged without an exit code)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEventStatus {
+    Ok,
+    Blocked,
+    Failed,
+    Unknown,
+}
+
+/// One hook run from the hook log
+#[derive(Debug, Clone, Serialize)]
+pub struct HookEvent {
+    /// Line in events.jsonl
+    pub line_number: usize,
+    /// RFC 3339, UTC
+    pub timestamp: Option<String>,
+    /// Template or script name the hook was installed as
+    pub hook: Option<String>,
+    /// e.g. "PreToolUse", "PostToolUse", "Stop", "UserPromptSubmit"
+    pub event: Option<String>,
+    pub session_id: Option<String>,
+    pub cwd: Option<String>,
+    pub tool_name: Option<String>,
+    pub tool_input: Option<Value>,
+    pub exit_code: Option<i32>,
+    pub duration_secs: Option<u64>,
+    pub stderr: Option<String>,
+    pub status: HookEventStatus,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HookEventFilter {
+    pub event: Option<String>,
+    pub hook: Option<String>,
+    pub session_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub status: Option<HookEventStatus>,
+    /// RFC 3339; older events (and events without a timestamp) are left out
+    pub since: Option<String>,
+    /// Default 200
+    pub limit: Option<usize>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn hook_logs_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("hook-logs")
+}
+
+fn events_path(dir: &Path) -> PathBuf {
+    dir.join(EVENTS_FILE)
+}
+
+/// Write log-hook.sh if it's missing or outdated; returns its path
+fn write_logger(dir: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create hook log directory: {}", e))?;
+    let path = dir.join(LOGGER_FILE);
+    if fs::read_to_string(&path).ok().as_deref() != Some(LOGGER_SCRIPT) {
+        fs::write(&path, LOGGER_SCRIPT)
+            .map_err(|e| format!("Failed to write hook logger: {}", e))?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make hook logger executable: {}", e))?;
+    }
+    Ok(path)
+}
+
+/// Install the hook logger script; returns its path
+pub fn ensure_hook_logger() -> Result<PathBuf, String> {
+    write_logger(&hook_logs_dir())
+}
+
+fn wrap_command(logger: &Path, name: &str, command: &str) -> String {
+    format!(
+        "sh {} {} {}",
+        security::shell_escape(&logger.to_string_lossy()),
+        security::shell_escape(name),
+        security::shell_escape(command)
+    )
+}
+
+/// Route every command hook in a hook config ({"PreToolUse": [{"matcher", "hooks": [...]}]})
+/// through the logger, so its runs show up in list_hook_events
+pub fn wrap_hook_config(config: &mut Value, name: &str, logger: &Path) {
+    let logger_str = logger.to_string_lossy();
+    let hooks = config
+        .as_object_mut()
+        .into_iter()
+        .flat_map(|events| events.values_mut())
+        .filter_map(Value::as_array_mut)
+        .flatten()
+        .filter_map(|handler| handler.get_mut("hooks").and_then(Value::as_array_mut))
+        .flatten();
+    for hook in hooks {
+        if hook.get("type").and_then(Value::as_str) != Some("command") {
+            continue;
+        }
+        let Some(command) = hook.get("command").and_then(Value::as_str) else {
+            continue;
+        };
+        if command.contains(logger_str.as_ref()) {
+            continue;
+        }
+        let wrapped = wrap_command(logger, name, command);
+        hook["command"] = Value::String(wrapped);
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(String::from)
+}
+
+/// Parse a logger line, or a raw hook stdin line written with `tee -a`
+fn parse_event_line(line: &str, line_number: usize) -> Option<HookEvent> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    // Logger lines wrap the hook's stdin in "input"; raw lines are the stdin itself
+    let input = value.get("input").unwrap_or(&value);
+    let exit_code = value
+        .get("exit_code")
+        .and_then(Value::as_i64)
+        .and_then(|c| i32::try_from(c).ok());
+    let status = match exit_code {
+        Some(0) => HookEventStatus::Ok,
+        Some(2) => HookEventStatus::Blocked,
+        Some(_) => HookEventStatus::Failed,
+        None => HookEventStatus::Unknown,
+    };
+    Some(HookEvent {
+        line_number,
+        timestamp: str_field(&value, "ts"),
+        hook: str_field(&value, "hook"),
+        event: str_field(input, "hook_event_name"),
+        session_id: str_field(input, "session_id"),
+        cwd: str_field(input, "cwd"),
+        tool_name: str_field(input, "tool_name"),
+        tool_input: input.get("tool_input").cloned(),
+        exit_code,
+        duration_secs: value.get("duration_secs").and_then(Value::as_u64),
+        stderr: str_field(&value, "stderr")
+            .map(|s| s.trim_end().to_string())
+            .filter(|s| !s.is_empty()),
+        status,
+    })
+}
+
+fn matches_filter(event: &HookEvent, filter: &HookEventFilter, since: Option<i64>) -> bool {
+    let eq = |wanted: &Option<String>, actual: &Option<String>| {
+        wanted.is_none() || wanted.as_deref() == actual.as_deref()
+    };
+    let recent_enough = since.is_none_or(|since| {
+        event
+            .timestamp
+            .as_deref()
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .is_some_and(|ts| ts.timestamp() >= since)
+    });
+    eq(&filter.event, &event.event)
+        && eq(&filter.hook, &event.hook)
+        && eq(&filter.session_id, &event.session_id)
+        && eq(&filter.tool_name, &event.tool_name)
+        && filter.status.is_none_or(|status| status == event.status)
+        && recent_enough
+}
+
+/// Newest matching events first
+fn read_events(path: &Path, filter: &HookEventFilter) -> AppResult<Vec<HookEvent>> {
+    let since = match &filter.since {
+        Some(since) => Some(
+            chrono::DateTime::parse_from_rfc3339(since)
+                .map_err(|e| AppError::invalid_input(format!("Invalid since: {}", e)))?
+                .timestamp(),
+        ),
+        None => None,
+    };
+    let limit = filter
+        .limit
+        .unwrap_or(DEFAULT_EVENT_LIMIT)
+        .clamp(1, MAX_EVENT_LIMIT);
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(Vec::new());
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    Ok(lines
+        .iter()
+        .enumerate()
+        .rev()
+        .filter_map(|(idx, line)| parse_event_line(line, idx + 1))
+        .filter(|event| matches_filter(event, filter, since))
+        .take(limit)
+        .collect())
+}
+
+/// Keep the log from growing without bound; hooks only ever append
+fn trim_log(path: &Path) {
+    let too_big = fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_BYTES);
+    if !too_big {
+        return;
+    }
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let kept = lines
+        .get(lines.len().saturating_sub(KEEP_LINES_AFTER_TRIM)..)
+        .unwrap_or_default();
+    let _ = security::atomic_write_string(&path.to_path_buf(), &(kept.join("\n") + "\n"));
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Where hooks log their runs: ~/.lovstudio/lovcode/hook-logs/events.jsonl
+#[tauri::command]
+pub fn get_hook_log_path() -> AppResult<String> {
+    Ok(events_path(&hook_logs_dir()).to_string_lossy().to_string())
+}
+
+/// Hook runs from the hook log, newest first
+#[tauri::command]
+pub fn list_hook_events(filter: Option<HookEventFilter>) -> AppResult<Vec<HookEvent>> {
+    let path = events_path(&hook_logs_dir());
+    trim_log(&path);
+    read_events(&path, &filter.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn clear_hook_events() -> AppResult<()> {
+    let path = events_path(&hook_logs_dir());
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::io("Failed to clear hook log", &e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_wrap_hook_config() {
+        let logger = Path::new("/home/u/.lovstudio/lovcode/hook-logs/log-hook.sh");
+        let mut config = serde_json::json!({
+            "PreToolUse": [{
+                "matcher": "Bash",
+                "hooks": [
+                    { "type": "command", "command": "echo 'checking' >&2" },
+                    { "type": "prompt", "prompt": "Is this safe?" }
+                ]
+            }]
+        });
+        wrap_hook_config(&mut config, "bash-guard", logger);
+        let first = config
+            .pointer("/PreToolUse/0/hooks/0/command")
+            .and_then(Value::as_str);
+        assert_eq!(
+            first,
+            Some(
+                "sh '/home/u/.lovstudio/lovcode/hook-logs/log-hook.sh' 'bash-guard' 'echo '\\''checking'\\'' >&2'"
+            )
+        );
+        // Already wrapped commands and non-command hooks are left alone
+        let before = config.clone();
+        wrap_hook_config(&mut config, "bash-guard", logger);
+        assert_eq!(config, before);
+        assert_eq!(
+            config.pointer("/PreToolUse/0/hooks/1"),
+            Some(&serde_json::json!({ "type": "prompt", "prompt": "Is this safe?" }))
+        );
+    }
+
+    #[test]
+    fn test_logger_records_runs() {
+        let temp = TempDir::new().expect("create temp dir");
+        let logger = write_logger(temp.path()).expect("write logger");
+        let run = |name: &str, command: &str, input: &str| {
+            let mut child = Command::new("sh")
+                .arg(&logger)
+                .args([name, command])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .expect("spawn logger");
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(input.as_bytes()).expect("write stdin");
+            }
+            let output = child.wait_with_output().expect("wait logger");
+            (
+                output.status.code(),
+                String::from_utf8_lossy(&output.stdout).to_string(),
+            )
+        };
+
+        let input = r#"{"session_id":"s1","hook_event_name":"PreToolUse","tool_name":"Bash","tool_input":{"command":"rm -rf /"}}"#;
+        assert_eq!(
+            run("guard", "echo 'Blocked: \"rm -rf\"' >&2; exit 2", input),
+            (Some(2), String::new())
+        );
+        let stop = r#"{"session_id":"s1","hook_event_name":"Stop"}"#;
+        assert_eq!(
+            run("notify", "cat >/dev/null; echo done", stop),
+            (Some(0), "done\n".to_string())
+        );
+        // A raw `tee -a` line without the logger's fields
+        let mut log = fs::OpenOptions::new()
+            .append(true)
+            .open(events_path(temp.path()))
+            .expect("open log");
+        writeln!(log, "{}", stop).expect("append raw line");
+
+        let path = events_path(temp.path());
+        let events = read_events(&path, &HookEventFilter::default()).expect("read events");
+        let summary: Vec<(Option<String>, HookEventStatus)> =
+            events.iter().map(|e| (e.hook.clone(), e.status)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (None, HookEventStatus::Unknown),
+                (Some("notify".to_string()), HookEventStatus::Ok),
+                (Some("guard".to_string()), HookEventStatus::Blocked),
+            ]
+        );
+        let blocked = events.last().expect("guard event");
+        assert_eq!(blocked.tool_name.as_deref(), Some("Bash"));
+        assert_eq!(
+            blocked.tool_input,
+            Some(serde_json::json!({ "command": "rm -rf /" }))
+        );
+        assert_eq!(blocked.stderr.as_deref(), Some("Blocked: \"rm -rf\""));
+
+        let filter = HookEventFilter {
+            status: Some(HookEventStatus::Blocked),
+            ..HookEventFilter::default()
+        };
+        assert_eq!(read_events(&path, &filter).expect("read events").len(), 1);
+        let filter = HookEventFilter {
+            since: Some("2000-01-01T00:00:00Z".to_string()),
+            event: Some("Stop".to_string()),
+            ..HookEventFilter::default()
+        };
+        assert_eq!(read_events(&path, &filter).expect("read events").len(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+/**
+ * [INPUT]: 依赖 chrono (本地时间转换), crate::commands::projects::encode_project_path, super (ImportedSession, message)
+ * [OUTPUT]: 对外提供 parse_path, parse_history
+ * [POS]: commands/importers/ 的 Aider 导入器，解析项目目录下的 .aider.chat.history.md，每个 "# aider chat started at" 段落为一个会话
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use super::{message, ImportSource, ImportedSession, Parsed};
+use crate::commands::projects::encode_project_path;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use std::fs;
+use std::path::Path;
+
+const HISTORY_FILE: &str = ".aider.chat.history.md";
+const SESSION_MARKER: &str = "# aider chat started at ";
+
+/// Aider writes the start time in local time, without an offset
+fn local_rfc3339(stamp: &str) -> Option<(String, u64)> {
+    let naive = NaiveDateTime::parse_from_str(stamp.trim(), "%Y-%m-%d %H:%M:%S").ok()?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    Some((local.to_rfc3339(), u64::try_from(local.timestamp()).ok()?))
+}
+
+/// Accumulates consecutive lines of the same kind into one message
+struct Builder {
+    session: ImportedSession,
+    timestamp: String,
+    role: &'static str,
+    is_tool: bool,
+    lines: Vec<String>,
+}
+
+impl Builder {
+    fn push(&mut self, role: &'static str, is_tool: bool, line: &str) {
+        if (role, is_tool) != (self.role, self.is_tool) {
+            self.flush();
+            self.role = role;
+            self.is_tool = is_tool;
+        }
+        self.lines.push(line.to_string());
+    }
+
+    fn flush(&mut self) {
+        let content = self.lines.join("\n").trim().to_string();
+        self.lines.clear();
+        if content.is_empty() {
+            return;
+        }
+        let index = self.session.messages.len();
+        let mut entry = message(&self.session.id, index, self.role, content, &self.timestamp);
+        entry.is_tool = self.is_tool;
+        self.session.messages.push(entry);
+    }
+
+    fn finish(mut self) -> ImportedSession {
+        self.flush();
+        self.session
+    }
+}
+
+/// Split a chat history file into sessions. "#### " lines are the user's input,
+/// "> " lines are Aider's own output (edits applied, commands run), everything else
+/// is the model's reply.
+pub fn parse_history(content: &str, project_path: &str) -> Vec<ImportedSession> {
+    let prefix = format!("aider{}", encode_project_path(project_path));
+    let mut sessions = Vec::new();
+    let mut current: Option<Builder> = None;
+
+    for line in content.lines() {
+        if let Some(stamp) = line.strip_prefix(SESSION_MARKER) {
+            sessions.extend(current.take().map(Builder::finish));
+            let (timestamp, secs) = local_rfc3339(stamp).unwrap_or_default();
+            let compact: String = stamp.chars().filter(char::is_ascii_digit).collect();
+            current = Some(Builder {
+                session: ImportedSession {
+                    id: format!("{}-{}", prefix, compact),
+                    source: ImportSource::Aider,
+                    project_path: Some(project_path.to_string()),
+                    summary: None,
+                    created_at: secs,
+                    last_modified: secs,
+                    messages: Vec::new(),
+                },
+                timestamp,
+                role: "assistant",
+                is_tool: false,
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(builder) = current.as_mut() else {
+            continue;
+        };
+        if let Some(input) = line.strip_prefix("#### ") {
+            builder.push("user", false, input);
+        } else if line == ">" || line.starts_with("> ") {
+            builder.push("assistant", true, line.trim_start_matches('>').trim_start());
+        } else if builder.is_tool && line.trim().is_empty() {
+            // Blank lines separate tool output from the reply that follows
+            builder.flush();
+            builder.is_tool = false;
+            builder.role = "assistant";
+        } else if builder.role == "user" && line.trim().is_empty() {
+            builder.push("user", false, line);
+        } else {
+            builder.push("assistant", false, line);
+        }
+    }
+    sessions.extend(current.map(Builder::finish));
+    sessions
+}
+
+/// A project directory (its .aider.chat.history.md) or the history file itself
+pub fn parse_path(path: &Path) -> Parsed {
+    let file = if path.is_dir() {
+        path.join(HISTORY_FILE)
+    } else {
+        path.to_path_buf()
+    };
+    let project = file
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut parsed = Parsed::default();
+    match fs::read_to_string(&file) {
+        Ok(content) => parsed.sessions = parse_history(&content, &project),
+        Err(e) => parsed.errors.push(format!("{}: {}", file.display(), e)),
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_sessions_and_roles() {
+        let content = "\
+# aider chat started at 2025-03-04 09:15:00
+
+> Aider v0.75.0
+> Main model: sonnet
+
+#### Rename foo to bar
+#### in utils.py
+
+I'll rename it.
+
+utils.py
+```python
+def bar(): pass
+```
+
+> Applied edit to utils.py
+> Commit 1a2b3c4 refactor: rename foo
+
+# aider chat started at 2025-03-05 18:00:00
+
+#### /help
+";
+        let sessions = parse_history(content, "/work/app");
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "aider-work-app-20250304091500");
+
+        let shape: Vec<(&str, bool)> = sessions[0]
+            .messages
+            .iter()
+            .map(|m| (m.role.as_str(), m.is_tool))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                ("assistant", true),
+                ("user", false),
+                ("assistant", false),
+                ("assistant", true),
+            ]
+        );
+        assert_eq!(
+            sessions[0].messages[1].content,
+            "Rename foo to bar\nin utils.py"
+        );
+        assert!(sessions[0].messages[2].content.contains("def bar()"));
+        assert!(sessions[0].created_at > 0);
+        assert_eq!(sessions[1].messages.len(), 1);
+    }
+}
@@ -0,0 +1,244 @@
+/**
+ * [INPUT]: 依赖 serde_json, ignore::WalkBuilder (递归查找 rollout 文件), super (ImportedSession, message)
+ * [OUTPUT]: 对外提供 default_dir, parse_dir, parse_rollout
+ * [POS]: commands/importers/ 的 OpenAI Codex CLI 导入器，解析 ~/.codex/sessions 下的 rollout-*.jsonl (新旧两种行格式)
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use super::{message, rfc3339_secs, ImportSource, ImportedSession, Parsed};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Function outputs can be whole files; keep the start
+const MAX_TOOL_OUTPUT_CHARS: usize = 4000;
+
+/// $CODEX_HOME/sessions, or ~/.codex/sessions
+pub fn default_dir() -> Option<PathBuf> {
+    std::env::var_os("CODEX_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".codex")))
+        .map(|codex| codex.join("sessions"))
+}
+
+fn text_of(content: &Value) -> String {
+    content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() > MAX_TOOL_OUTPUT_CHARS {
+        format!(
+            "{}\n…",
+            text.chars().take(MAX_TOOL_OUTPUT_CHARS).collect::<String>()
+        )
+    } else {
+        text.to_string()
+    }
+}
+
+/// Parse one rollout file. Newer files wrap every record as
+/// `{"timestamp", "type": "session_meta" | "response_item" | ..., "payload"}`; older ones
+/// start with a bare `{"id", "timestamp"}` line followed by bare response items.
+pub fn parse_rollout(content: &str, fallback_id: &str) -> ImportedSession {
+    let mut session = ImportedSession {
+        id: fallback_id.to_string(),
+        source: ImportSource::Codex,
+        project_path: None,
+        summary: None,
+        created_at: 0,
+        last_modified: 0,
+        messages: Vec::new(),
+    };
+    let mut last_timestamp = String::new();
+
+    for line in content.lines() {
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if let Some(ts) = record.get("timestamp").and_then(Value::as_str) {
+            last_timestamp = ts.to_string();
+        }
+        let (kind, item) = match record.get("payload") {
+            Some(payload) => (record.get("type").and_then(Value::as_str), payload),
+            None => (None, &record),
+        };
+        let is_meta_record = kind == Some("session_meta")
+            || (kind.is_none() && record.get("type").is_none() && record.get("id").is_some());
+        if is_meta_record {
+            if let Some(id) = item.get("id").and_then(Value::as_str) {
+                session.id = id.to_string();
+            }
+            session.project_path = item
+                .get("cwd")
+                .and_then(Value::as_str)
+                .map(String::from)
+                .or(session.project_path);
+            if let Some(created) = item
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .and_then(rfc3339_secs)
+            {
+                session.created_at = created;
+            }
+            continue;
+        }
+        // event_msg records repeat the response items for the TUI
+        if kind.is_some() && kind != Some("response_item") {
+            continue;
+        }
+
+        let index = session.messages.len();
+        let entry = match item.get("type").and_then(Value::as_str) {
+            Some("message") => {
+                let role = item.get("role").and_then(Value::as_str).unwrap_or("user");
+                if role != "user" && role != "assistant" {
+                    continue;
+                }
+                let text = text_of(item.get("content").unwrap_or(&Value::Null));
+                let mut entry = message(&session.id, index, role, text, &last_timestamp);
+                // Codex sends the environment and AGENTS.md as user messages
+                entry.is_meta = role == "user"
+                    && ["<environment_context>", "<user_instructions>"]
+                        .iter()
+                        .any(|tag| entry.content.trim_start().starts_with(tag));
+                entry
+            }
+            Some("function_call" | "custom_tool_call" | "local_shell_call") => {
+                let name = item.get("name").and_then(Value::as_str).unwrap_or("shell");
+                let args = item
+                    .get("arguments")
+                    .or_else(|| item.get("input"))
+                    .or_else(|| item.get("action"))
+                    .map(|a| a.as_str().map_or_else(|| a.to_string(), String::from))
+                    .unwrap_or_default();
+                let mut entry = message(
+                    &session.id,
+                    index,
+                    "assistant",
+                    format!("{}: {}", name, args),
+                    &last_timestamp,
+                );
+                entry.is_tool = true;
+                entry
+            }
+            Some("function_call_output" | "custom_tool_call_output") => {
+                let output = item.get("output").map_or_else(String::new, |o| {
+                    o.as_str().map_or_else(|| o.to_string(), String::from)
+                });
+                let mut entry = message(
+                    &session.id,
+                    index,
+                    "user",
+                    truncate(&output),
+                    &last_timestamp,
+                );
+                entry.is_tool = true;
+                entry
+            }
+            _ => continue,
+        };
+        if !entry.content.trim().is_empty() {
+            session.messages.push(entry);
+        }
+    }
+
+    session.last_modified = rfc3339_secs(&last_timestamp).unwrap_or(session.created_at);
+    if session.created_at == 0 {
+        session.created_at = session
+            .messages
+            .first()
+            .and_then(|m| rfc3339_secs(&m.timestamp))
+            .unwrap_or(session.last_modified);
+    }
+    session.id = format!("codex-{}", session.id);
+    session
+}
+
+/// Every rollout-*.jsonl under `dir` (sessions are filed by date), or a single file
+pub fn parse_dir(dir: &Path) -> Parsed {
+    let files: Vec<PathBuf> = if dir.is_file() {
+        vec![dir.to_path_buf()]
+    } else {
+        ignore::WalkBuilder::new(dir)
+            .standard_filters(false)
+            .build()
+            .flatten()
+            .map(ignore::DirEntry::into_path)
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "jsonl")
+                    && path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with("rollout-"))
+            })
+            .collect()
+    };
+
+    let mut parsed = Parsed::default();
+    for file in files {
+        match fs::read_to_string(&file) {
+            Ok(content) => {
+                let stem = file
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                parsed.sessions.push(parse_rollout(&content, &stem));
+            }
+            Err(e) => parsed.errors.push(format!("{}: {}", file.display(), e)),
+        }
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rollout_formats() {
+        let current = [
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"s-1","timestamp":"2025-09-01T10:00:00Z","cwd":"/work/app"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>cwd</environment_context>"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:02Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Add a test"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:02Z","type":"event_msg","payload":{"type":"user_message","message":"Add a test"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:03Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"c1"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:04Z","type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":"src"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:05Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Done."}]}}"#,
+        ];
+        let session = parse_rollout(&current.join("\n"), "rollout-x");
+        assert_eq!(session.id, "codex-s-1");
+        assert_eq!(session.project_path.as_deref(), Some("/work/app"));
+        let shape: Vec<(&str, bool, bool)> = session
+            .messages
+            .iter()
+            .map(|m| (m.role.as_str(), m.is_meta, m.is_tool))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                ("user", true, false),
+                ("user", false, false),
+                ("assistant", false, true),
+                ("user", false, true),
+                ("assistant", false, false),
+            ]
+        );
+        assert_eq!(session.messages[2].content, r#"shell: {"command":["ls"]}"#);
+        assert_eq!(session.last_modified, session.created_at + 5);
+
+        let legacy = [
+            r#"{"id":"old-1","timestamp":"2025-05-01T08:00:00Z","instructions":null}"#,
+            r#"{"type":"message","role":"user","content":[{"type":"input_text","text":"hi"}]}"#,
+            r#"{"record_type":"state"}"#,
+        ];
+        let session = parse_rollout(&legacy.join("\n"), "rollout-y");
+        assert_eq!(session.id, "codex-old-1");
+        assert_eq!(session.messages.len(), 1);
+        assert_eq!(session.created_at, 1_746_086_400);
+    }
+}
@@ -0,0 +1,371 @@
+/**
+ * [INPUT]: 依赖 rusqlite (只读打开 state.vscdb), serde_json, crate::services::pty_output::percent_decode, super (ImportedSession, message)
+ * [OUTPUT]: 对外提供 default_dir, parse_dir, chat_tab_sessions, composer_session
+ * [POS]: commands/importers/ 的 Cursor 导入器，读取 workspaceStorage 中的旧版 AI chat 标签页与 globalStorage 中的 Composer 对话
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use super::{message, millis_rfc3339, ImportSource, ImportedSession, Parsed};
+use crate::services::pty_output::percent_decode;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Legacy chat panel tabs, per workspace
+const CHAT_DATA_KEY: &str = "workbench.panel.aichat.view.aichat.chatdata";
+/// Composers opened in a workspace (older versions also keep the conversation here)
+const COMPOSER_DATA_KEY: &str = "composer.composerData";
+
+/// Cursor's user data directory, e.g. ~/Library/Application Support/Cursor/User
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|config| config.join("Cursor").join("User"))
+}
+
+// ============================================================================
+// Database Access
+// ============================================================================
+
+/// Cursor keeps the database open; read-only access doesn't get in its way
+fn open(path: &Path) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
+/// Values are TEXT in some versions and BLOB in others
+fn json_column(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Option<Value>> {
+    Ok(row
+        .get_ref(index)?
+        .as_bytes_or_null()
+        .ok()
+        .flatten()
+        .and_then(|bytes| serde_json::from_slice(bytes).ok()))
+}
+
+/// JSON values of `table` whose key matches the LIKE `pattern`
+fn read_values(
+    conn: &Connection,
+    table: &str,
+    pattern: &str,
+) -> rusqlite::Result<Vec<(String, Value)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT key, value FROM {} WHERE key LIKE ?1",
+        table
+    ))?;
+    let rows = stmt.query_map([pattern], |row| Ok((row.get(0)?, json_column(row, 1)?)))?;
+    Ok(rows
+        .flatten()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect())
+}
+
+fn read_value(conn: &Connection, table: &str, key: &str) -> Option<Value> {
+    conn.query_row(
+        &format!("SELECT value FROM {} WHERE key = ?1", table),
+        [key],
+        |row| json_column(row, 0),
+    )
+    .ok()
+    .flatten()
+}
+
+// ============================================================================
+// Conversion
+// ============================================================================
+
+/// Project folder from workspace.json, e.g. {"folder": "file:///Users/me/app"}
+fn workspace_folder(workspace_json: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(workspace_json).ok()?;
+    let folder = value.get("folder")?.as_str()?;
+    Some(percent_decode(folder.strip_prefix("file://")?))
+}
+
+fn str_field<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .filter_map(|key| value.get(*key).and_then(Value::as_str))
+        .find(|s| !s.trim().is_empty())
+}
+
+/// Role, tool flag and text of a chat bubble. Legacy tabs use "user"/"ai",
+/// composers use 1 (user) and 2 (assistant).
+fn bubble_content(bubble: &Value) -> Option<(&'static str, bool, String)> {
+    let role = match bubble.get("type") {
+        Some(Value::String(t)) if t == "user" => "user",
+        Some(Value::Number(n)) if n.as_u64() == Some(1) => "user",
+        _ => "assistant",
+    };
+    if let Some(text) = str_field(bubble, &["text", "rawText"]) {
+        return Some((role, false, text.to_string()));
+    }
+    let tool = bubble.get("toolFormerData")?;
+    let name = str_field(tool, &["name", "tool"]).unwrap_or("tool");
+    let args = str_field(tool, &["rawArgs", "params"]).unwrap_or_default();
+    Some((role, true, format!("{}: {}", name, args)))
+}
+
+fn build_session(
+    id: String,
+    project: Option<&str>,
+    summary: Option<&str>,
+    created_ms: i64,
+    updated_ms: i64,
+    bubbles: &[&Value],
+) -> ImportedSession {
+    let timestamp = millis_rfc3339(created_ms);
+    let mut messages = Vec::new();
+    for bubble in bubbles {
+        if let Some((role, is_tool, content)) = bubble_content(bubble) {
+            let mut entry = message(&id, messages.len(), role, content, &timestamp);
+            entry.is_tool = is_tool;
+            messages.push(entry);
+        }
+    }
+    let secs = |ms: i64| u64::try_from(ms / 1000).unwrap_or(0);
+    ImportedSession {
+        source: ImportSource::Cursor,
+        project_path: project.map(String::from),
+        summary: summary.map(String::from),
+        created_at: secs(created_ms),
+        last_modified: secs(updated_ms.max(created_ms)),
+        messages,
+        id,
+    }
+}
+
+/// Sessions from a workspace's legacy chat data:
+/// {"tabs": [{"tabId", "chatTitle", "lastSendTime", "bubbles": [...]}]}
+pub fn chat_tab_sessions(chat_data: &Value, project: Option<&str>) -> Vec<ImportedSession> {
+    chat_data
+        .get("tabs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tab| {
+            let id = tab.get("tabId")?.as_str()?;
+            let last_send = tab.get("lastSendTime").and_then(Value::as_i64).unwrap_or(0);
+            let bubbles: Vec<&Value> = tab
+                .get("bubbles")
+                .and_then(Value::as_array)
+                .map(|b| b.iter().collect())
+                .unwrap_or_default();
+            Some(build_session(
+                format!("cursor-chat-{}", id),
+                project,
+                str_field(tab, &["chatTitle"]),
+                last_send,
+                last_send,
+                &bubbles,
+            ))
+        })
+        .collect()
+}
+
+/// A composer conversation: {"composerId", "name", "createdAt", "lastUpdatedAt"} plus either
+/// the bubbles inline ("conversation") or their ids ("fullConversationHeadersOnly") to look
+/// up in `bubbles`
+pub fn composer_session(
+    composer: &Value,
+    bubbles: &HashMap<String, Value>,
+    project: Option<&str>,
+) -> Option<ImportedSession> {
+    let id = composer.get("composerId")?.as_str()?;
+    let conversation: Vec<&Value> = match composer.get("conversation").and_then(Value::as_array) {
+        Some(inline) if !inline.is_empty() => inline.iter().collect(),
+        _ => composer
+            .get("fullConversationHeadersOnly")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|header| bubbles.get(header.get("bubbleId")?.as_str()?))
+            .collect(),
+    };
+    let created = composer
+        .get("createdAt")
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    let updated = composer
+        .get("lastUpdatedAt")
+        .and_then(Value::as_i64)
+        .unwrap_or(created);
+    Some(build_session(
+        format!("cursor-composer-{}", id),
+        project,
+        str_field(composer, &["name"]),
+        created,
+        updated,
+        &conversation,
+    ))
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Read every workspace under workspaceStorage: legacy chat tabs become sessions
+/// directly, composers are mapped to their project for the global pass
+fn read_workspaces(
+    user_dir: &Path,
+    parsed: &mut Parsed,
+    composer_projects: &mut HashMap<String, String>,
+) {
+    let Ok(entries) = fs::read_dir(user_dir.join("workspaceStorage")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let db_path = entry.path().join("state.vscdb");
+        if !db_path.is_file() {
+            continue;
+        }
+        let project = fs::read_to_string(entry.path().join("workspace.json"))
+            .ok()
+            .and_then(|json| workspace_folder(&json));
+        let conn = match open(&db_path) {
+            Ok(conn) => conn,
+            Err(e) => {
+                parsed.errors.push(format!("{}: {}", db_path.display(), e));
+                continue;
+            }
+        };
+
+        if let Some(chat_data) = read_value(&conn, "ItemTable", CHAT_DATA_KEY) {
+            parsed
+                .sessions
+                .extend(chat_tab_sessions(&chat_data, project.as_deref()));
+        }
+        let composers = read_value(&conn, "ItemTable", COMPOSER_DATA_KEY);
+        for composer in composers
+            .as_ref()
+            .and_then(|data| data.get("allComposers"))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let Some(id) = composer.get("composerId").and_then(Value::as_str) else {
+                continue;
+            };
+            if let Some(project) = &project {
+                composer_projects.insert(id.to_string(), project.clone());
+            }
+            let has_inline = composer
+                .get("conversation")
+                .and_then(Value::as_array)
+                .is_some_and(|c| !c.is_empty());
+            if has_inline {
+                parsed.sessions.extend(composer_session(
+                    composer,
+                    &HashMap::new(),
+                    project.as_deref(),
+                ));
+            }
+        }
+    }
+}
+
+/// Composers in globalStorage (cursorDiskKV), where current versions keep every
+/// conversation and its bubbles
+fn read_global(user_dir: &Path, parsed: &mut Parsed, composer_projects: &HashMap<String, String>) {
+    let db_path = user_dir.join("globalStorage").join("state.vscdb");
+    if !db_path.is_file() {
+        return;
+    }
+    let result = open(&db_path).and_then(|conn| {
+        let composers = read_values(&conn, "cursorDiskKV", "composerData:%")?;
+        let mut sessions = Vec::new();
+        for (_, composer) in composers {
+            let Some(id) = composer.get("composerId").and_then(Value::as_str) else {
+                continue;
+            };
+            let bubbles: HashMap<String, Value> =
+                read_values(&conn, "cursorDiskKV", &format!("bubbleId:{}:%", id))?
+                    .into_iter()
+                    .filter_map(|(key, value)| Some((key.rsplit(':').next()?.to_string(), value)))
+                    .collect();
+            let project = composer_projects.get(id).map(String::as_str);
+            sessions.extend(composer_session(&composer, &bubbles, project));
+        }
+        Ok(sessions)
+    });
+    match result {
+        Ok(sessions) => parsed.sessions.extend(sessions),
+        Err(e) => parsed.errors.push(format!("{}: {}", db_path.display(), e)),
+    }
+}
+
+/// Import from Cursor's user data directory (the one holding workspaceStorage and
+/// globalStorage). A composer found in both places is taken from globalStorage.
+pub fn parse_dir(user_dir: &Path) -> Parsed {
+    let mut parsed = Parsed::default();
+    let mut composer_projects = HashMap::new();
+    read_workspaces(user_dir, &mut parsed, &mut composer_projects);
+    read_global(user_dir, &mut parsed, &composer_projects);
+
+    // Later entries win: keep the last session for each id
+    let mut seen = HashSet::new();
+    let mut sessions: Vec<ImportedSession> = parsed
+        .sessions
+        .into_iter()
+        .rev()
+        .filter(|s| s.messages.is_empty() || seen.insert(s.id.clone()))
+        .collect();
+    sessions.reverse();
+    parsed.sessions = sessions;
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_chat_tabs_and_composers() {
+        assert_eq!(
+            workspace_folder(r#"{"folder":"file:///Users/me/my%20app"}"#).as_deref(),
+            Some("/Users/me/my app")
+        );
+
+        let chat_data = json!({"tabs": [{
+            "tabId": "t1",
+            "chatTitle": "Explain the parser",
+            "lastSendTime": 1_735_689_600_000_i64,
+            "bubbles": [
+                {"type": "user", "text": "What does parse() do?"},
+                {"type": "ai", "rawText": "It tokenizes the input."}
+            ]
+        }]});
+        let tabs = chat_tab_sessions(&chat_data, Some("/Users/me/app"));
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(tabs[0].id, "cursor-chat-t1");
+        assert_eq!(tabs[0].summary.as_deref(), Some("Explain the parser"));
+        assert_eq!(tabs[0].created_at, 1_735_689_600);
+        let roles: Vec<&str> = tabs[0].messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant"]);
+
+        let composer = json!({
+            "composerId": "c1",
+            "createdAt": 1_735_689_600_000_i64,
+            "lastUpdatedAt": 1_735_689_660_000_i64,
+            "fullConversationHeadersOnly": [
+                {"bubbleId": "b1", "type": 1},
+                {"bubbleId": "b2", "type": 2},
+                {"bubbleId": "missing", "type": 2}
+            ]
+        });
+        let bubbles = HashMap::from([
+            ("b1".to_string(), json!({"type": 1, "text": "Add logging"})),
+            (
+                "b2".to_string(),
+                json!({"type": 2, "text": "", "toolFormerData": {"name": "edit_file", "rawArgs": "{}"}}),
+            ),
+        ]);
+        let session = composer_session(&composer, &bubbles, None).expect("composer");
+        assert_eq!(session.id, "cursor-composer-c1");
+        assert_eq!(session.last_modified, 1_735_689_660);
+        assert_eq!(session.messages.len(), 2);
+        assert!(session.messages[1].is_tool);
+        assert_eq!(session.messages[1].content, "edit_file: {}");
+    }
+}
@@ -0,0 +1,416 @@
+/**
+ * [INPUT]: 依赖 codex, aider, cursor 子模块, crate::types::{Message, Session}, crate::security, crate::services::search (删除时同步移出索引)
+ * [OUTPUT]: 对外提供 import_chat_history, list_imported_sessions, delete_imported_sessions 命令, read_imported_messages/load_imported_sessions 供会话读取与搜索索引复用, ImportSource/ImportedSession/ImportReport 类型
+ * [POS]: commands/importers/ 模块入口，把其他 AI 编程工具的历史转换为内部会话格式，存放在 ~/.lovstudio/lovcode/imported/ (不写入 ~/.claude)
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+mod aider;
+mod codex;
+mod cursor;
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::services::search;
+use crate::types::{Message, Session};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    Cursor,
+    Codex,
+    Aider,
+}
+
+impl ImportSource {
+    const ALL: [Self; 3] = [Self::Cursor, Self::Codex, Self::Aider];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Cursor => "cursor",
+            Self::Codex => "codex",
+            Self::Aider => "aider",
+        }
+    }
+}
+
+/// A conversation from another tool, converted to the app's message format
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedSession {
+    /// Stable across re-imports, so importing again updates instead of duplicating
+    pub id: String,
+    pub source: ImportSource,
+    pub project_path: Option<String>,
+    pub summary: Option<String>,
+    /// Unix seconds
+    pub created_at: u64,
+    /// Unix seconds
+    pub last_modified: u64,
+    pub messages: Vec<Message>,
+}
+
+impl ImportedSession {
+    /// The project id it is listed and searched under
+    pub fn project_id(&self) -> String {
+        format!("{}{}", IMPORTED_PROJECT_PREFIX, self.source.as_str())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    /// Where history was read from
+    pub path: String,
+    /// New or updated sessions
+    pub imported: usize,
+    /// Conversations without any message
+    pub skipped: usize,
+    /// Files or records that couldn't be read, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Sessions read by one importer, plus what went wrong along the way
+#[derive(Debug, Default)]
+struct Parsed {
+    sessions: Vec<ImportedSession>,
+    errors: Vec<String>,
+}
+
+/// Prefix of the project id imported sessions are listed and searched under,
+/// e.g. "import:codex"; read_session_messages resolves it here
+pub const IMPORTED_PROJECT_PREFIX: &str = "import:";
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn imported_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("imported")
+}
+
+fn source_dir(root: &Path, source: ImportSource) -> PathBuf {
+    root.join(source.as_str())
+}
+
+/// Ids end up as file names
+fn check_id(id: &str) -> AppResult<()> {
+    if id.is_empty() || id.starts_with('.') || id.contains(['/', '\\', '\0']) {
+        return Err(AppError::invalid_input("Invalid imported session id").with_context(id));
+    }
+    Ok(())
+}
+
+/// Keep ids file-name safe: anything but ASCII letters, digits, '-' and '_' becomes '-'.
+/// A changed id gets a short hash of the original, so "a/b" and "a:b" don't share a file.
+fn sanitize_id(raw: &str) -> String {
+    let safe: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if safe == raw {
+        return safe;
+    }
+    format!("{}-{:08x}", safe, stable_hash(raw) >> 32)
+}
+
+/// FNV-1a; unlike std's DefaultHasher it stays the same across builds, so a re-import
+/// lands in the same file
+fn stable_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// First line of the first real user message, shortened
+fn summarize(messages: &[Message]) -> Option<String> {
+    let first = messages
+        .iter()
+        .find(|m| m.role == "user" && !m.is_meta && !m.is_tool)?;
+    let line = first.content.lines().find(|l| !l.trim().is_empty())?.trim();
+    Some(if line.chars().count() > 80 {
+        format!("{}…", line.chars().take(80).collect::<String>())
+    } else {
+        line.to_string()
+    })
+}
+
+/// Unix seconds of an RFC 3339 timestamp
+fn rfc3339_secs(timestamp: &str) -> Option<u64> {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .and_then(|t| u64::try_from(t.timestamp()).ok())
+}
+
+/// Unix milliseconds to RFC 3339; empty when unknown (0)
+fn millis_rfc3339(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .filter(|_| millis > 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// A plain message; `index` is its position in the conversation
+fn message(
+    session_id: &str,
+    index: usize,
+    role: &str,
+    content: String,
+    timestamp: &str,
+) -> Message {
+    Message {
+        uuid: format!("{}-{}", session_id, index + 1),
+        role: role.to_string(),
+        content,
+        timestamp: timestamp.to_string(),
+        is_meta: false,
+        is_tool: false,
+        line_number: index + 1,
+        thinking: None,
+    }
+}
+
+fn write_session(root: &Path, session: &ImportedSession) -> Result<(), String> {
+    let dir = source_dir(root, session.source);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let content = serde_json::to_string(session).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&dir.join(format!("{}.json", session.id)), &content)
+        .map_err(|e| e.to_string())
+}
+
+fn read_session(path: &Path) -> Option<ImportedSession> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn load_from(root: &Path, source: Option<ImportSource>) -> Vec<ImportedSession> {
+    let sources = source.map_or(ImportSource::ALL.to_vec(), |s| vec![s]);
+    sources
+        .into_iter()
+        .filter_map(|s| fs::read_dir(source_dir(root, s)).ok())
+        .flat_map(Iterator::flatten)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| read_session(&e.path()))
+        .collect()
+}
+
+fn store(root: &Path, source: ImportSource, path: &Path, parsed: Parsed) -> ImportReport {
+    let mut report = ImportReport {
+        source,
+        path: path.to_string_lossy().to_string(),
+        imported: 0,
+        skipped: 0,
+        errors: parsed.errors,
+    };
+    for mut session in parsed.sessions {
+        if session.messages.is_empty() {
+            report.skipped += 1;
+            continue;
+        }
+        session.id = sanitize_id(&session.id);
+        if session.summary.is_none() {
+            session.summary = summarize(&session.messages);
+        }
+        match write_session(root, &session) {
+            Ok(()) => report.imported += 1,
+            Err(e) => report.errors.push(e),
+        }
+    }
+    report
+}
+
+fn to_session(session: ImportedSession) -> Session {
+    Session {
+        project_id: session.project_id(),
+        project_path: session.project_path,
+        summary: session.summary,
+        message_count: session.messages.len(),
+        last_modified: session.last_modified,
+        id: session.id,
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Every imported session, for the search index
+pub fn load_imported_sessions() -> Vec<ImportedSession> {
+    load_from(&imported_dir(), None)
+}
+
+/// Messages of an imported session; `project_id` is "import:<source>"
+pub fn read_imported_messages(project_id: &str, session_id: &str) -> AppResult<Vec<Message>> {
+    let source = project_id.strip_prefix(IMPORTED_PROJECT_PREFIX).ok_or_else(|| {
+        AppError::invalid_input(format!("Not an imported project: {}", project_id))
+    })?;
+    check_id(source)?;
+    check_id(session_id)?;
+    let path = imported_dir()
+        .join(source)
+        .join(format!("{}.json", session_id));
+    read_session(&path)
+        .map(|session| session.messages)
+        .ok_or_else(|| {
+            AppError::not_found("Session not found")
+                .with_context(format!("{}/{}", project_id, session_id))
+        })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Import history from another tool. `path` defaults to the tool's own location
+/// (~/.codex/sessions, Cursor's user data); Aider needs the project directory or its
+/// .aider.chat.history.md.
+#[tauri::command]
+pub async fn import_chat_history(
+    source: ImportSource,
+    path: Option<String>,
+) -> AppResult<ImportReport> {
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(security::validate_decoded_path(&path)?),
+        None => match source {
+            ImportSource::Codex => codex::default_dir(),
+            ImportSource::Cursor => cursor::default_dir(),
+            ImportSource::Aider => None,
+        }
+        .ok_or_else(|| {
+            AppError::invalid_input(format!(
+                "No default location for {} history",
+                source.as_str()
+            ))
+        })?,
+    };
+    if !path.exists() {
+        return Err(AppError::not_found("History not found")
+            .with_context(path.to_string_lossy().to_string()));
+    }
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let parsed = match source {
+            ImportSource::Codex => codex::parse_dir(&path),
+            ImportSource::Aider => aider::parse_path(&path),
+            ImportSource::Cursor => cursor::parse_dir(&path),
+        };
+        store(&imported_dir(), source, &path, parsed)
+    })
+    .await
+    .map_err(|e| e.to_string())?)
+}
+
+/// Imported sessions as regular sessions under the project id "import:<source>",
+/// most recent first
+#[tauri::command]
+pub async fn list_imported_sessions(source: Option<ImportSource>) -> AppResult<Vec<Session>> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let mut sessions: Vec<Session> = load_from(&imported_dir(), source)
+            .into_iter()
+            .map(to_session)
+            .collect();
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.last_modified));
+        sessions
+    })
+    .await
+    .map_err(|e| e.to_string())?)
+}
+
+/// Remove imported sessions of one source, or all of them, including their messages in
+/// the search index; returns how many were removed
+#[tauri::command]
+pub fn delete_imported_sessions(source: Option<ImportSource>) -> AppResult<usize> {
+    let root = imported_dir();
+    let sources = source.map_or(ImportSource::ALL.to_vec(), |s| vec![s]);
+    let mut removed = 0;
+    for source in sources {
+        let dir = source_dir(&root, source);
+        if !dir.exists() {
+            continue;
+        }
+        removed += fs::read_dir(&dir).map_or(0, |entries| entries.flatten().count());
+        fs::remove_dir_all(&dir)
+            .map_err(|e| AppError::io("Failed to remove imported sessions", &e))?;
+        let project_id = format!("{}{}", IMPORTED_PROJECT_PREFIX, source.as_str());
+        if let Err(e) = search::remove_project_documents(&project_id) {
+            tracing::warn!("Failed to remove {} from the search index: {}", project_id, e);
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_load_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let parsed = Parsed {
+            sessions: vec![
+                ImportedSession {
+                    id: "abc/def".to_string(),
+                    source: ImportSource::Codex,
+                    project_path: Some("/tmp/app".to_string()),
+                    summary: None,
+                    created_at: 1,
+                    last_modified: 2,
+                    messages: vec![message(
+                        "s",
+                        0,
+                        "user",
+                        "Fix the login bug\nplease".to_string(),
+                        "",
+                    )],
+                },
+                ImportedSession {
+                    id: "empty".to_string(),
+                    source: ImportSource::Codex,
+                    project_path: None,
+                    summary: None,
+                    created_at: 1,
+                    last_modified: 1,
+                    messages: Vec::new(),
+                },
+            ],
+            errors: vec!["bad.jsonl: unreadable".to_string()],
+        };
+        let report = store(temp.path(), ImportSource::Codex, temp.path(), parsed);
+        assert_eq!(
+            (report.imported, report.skipped, report.errors.len()),
+            (1, 1, 1)
+        );
+
+        let loaded = load_from(temp.path(), Some(ImportSource::Codex));
+        assert_eq!(loaded.len(), 1);
+        let session = to_session(loaded.into_iter().next().expect("session"));
+        assert_eq!(session.id, sanitize_id("abc/def"));
+        assert_eq!(session.project_id, "import:codex");
+        assert_eq!(session.summary.as_deref(), Some("Fix the login bug"));
+        assert!(load_from(temp.path(), Some(ImportSource::Aider)).is_empty());
+        assert!(check_id("../x").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_id_keeps_distinct_ids_apart() {
+        assert_eq!(sanitize_id("rollout-2025_01"), "rollout-2025_01");
+
+        let slash = sanitize_id("abc/def");
+        let colon = sanitize_id("abc:def");
+        assert!(slash.starts_with("abc-def-"));
+        assert_ne!(slash, colon);
+        assert_ne!(slash, sanitize_id("abc-def"));
+        assert_eq!(slash, sanitize_id("abc/def"));
+        assert!(check_id(&slash).is_ok());
+    }
+}
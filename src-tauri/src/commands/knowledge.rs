@@ -1,18 +1,24 @@
 /**
- * [INPUT]: 依赖 std::fs, std::path, serde, serde_json, chrono, tauri, crate::types, crate::commands::projects
- * [OUTPUT]: 对外提供 list_distill_documents, list_reference_sources, list_reference_docs, find_session_project, get_distill_watch_enabled, set_distill_watch_enabled 命令
+ * [INPUT]: 依赖 std::fs, std::path, serde, serde_json, chrono, tauri, reqwest, crate::types, crate::commands::projects, crate::commands::file_trash (删除进回收站), crate::services::distill
+ * [OUTPUT]: 对外提供 list_distill_documents, list_reference_sources, list_reference_docs, find_session_project, get_distill_watch_enabled, set_distill_watch_enabled, create/update/delete_distill_document, list/rename/delete_distill_tag, add_reference_source, refresh_reference_sources, distill_session, distill_sessions 命令, start_reference_refresher 定时刷新, read_distill_index/append_distill_index 索引读写
  * [POS]: commands/ 模块的知识库管理命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
 use crate::commands::decode_project_path;
+use crate::commands::file_trash;
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
+use crate::services::distill::{self, DistillProgress, DEFAULT_DISTILL_TEMPLATE};
 use crate::types::{RawLine, Session};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::sync::{LazyLock, Mutex};
 use tauri::Manager;
 
 // ============================================================================
@@ -22,6 +28,17 @@ use tauri::Manager;
 // Distill watch state
 pub static DISTILL_WATCH_ENABLED: AtomicBool = AtomicBool::new(true);
 
+// Serializes read-modify-write of distill/index.jsonl
+pub static DISTILL_INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+// Serializes read-modify-write of reference _sources.json sidecars
+static REFERENCE_SOURCES_LOCK: Mutex<()> = Mutex::new(());
+
+/// Sidecar in a reference source dir recording where fetched docs came from
+const REFERENCE_SOURCES_FILE: &str = "_sources.json";
+const DEFAULT_REFERENCE_SOURCE: &str = "web";
+const MAX_REFERENCE_BYTES: usize = 5 * 1024 * 1024;
+
 // ============================================================================
 // Path Helper Functions
 // ============================================================================
@@ -53,6 +70,13 @@ pub struct DistillDocument {
     pub session: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DistillTag {
+    pub name: String,
+    /// Number of documents carrying the tag
+    pub count: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReferenceSource {
     pub name: String,
@@ -65,6 +89,20 @@ pub struct ReferenceDoc {
     pub name: String,
     pub path: String,
     pub group: Option<String>,
+    /// Origin URL for docs ingested via add_reference_source
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Entry of a source's _sources.json, keyed by doc name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReferenceOrigin {
+    pub url: String,
+    pub title: Option<String>,
+    /// Unix ms of the last successful fetch
+    pub fetched_at: i64,
+    /// Re-fetch interval; None disables periodic refresh
+    pub refresh_hours: Option<u32>,
 }
 
 // ============================================================================
@@ -171,12 +209,583 @@ fn find_reference_source_dir(app_handle: &tauri::AppHandle, source: &str) -> Opt
     None
 }
 
+/// Raw entries of distill/index.jsonl (unparseable lines are skipped)
+pub fn read_distill_index(distill_dir: &Path) -> Vec<DistillDocument> {
+    fs::read_to_string(distill_dir.join("index.jsonl"))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Append one entry to distill/index.jsonl (caller holds DISTILL_INDEX_LOCK)
+pub fn append_distill_index(distill_dir: &Path, doc: &DistillDocument) -> Result<(), String> {
+    use std::io::Write;
+
+    fs::create_dir_all(distill_dir).map_err(|e| e.to_string())?;
+    let line = serde_json::to_string(doc).map_err(|e| e.to_string())?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(distill_dir.join("index.jsonl"))
+        .map_err(|e| format!("Failed to open distill index: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write distill index: {}", e))
+}
+
+/// Rewrite distill/index.jsonl with `docs` (caller holds DISTILL_INDEX_LOCK)
+pub fn write_distill_index(distill_dir: &Path, docs: &[DistillDocument]) -> Result<(), String> {
+    let mut content = String::new();
+    for doc in docs {
+        content.push_str(&serde_json::to_string(doc).map_err(|e| e.to_string())?);
+        content.push('\n');
+    }
+    security::atomic_write_string(&distill_dir.join("index.jsonl"), &content)
+        .map_err(|e| format!("Failed to write distill index: {}", e))
+}
+
+/// Distill files are addressed by bare name; reject anything that could leave the directory
+fn validate_distill_file(file: &str) -> Result<(), String> {
+    if file.is_empty()
+        || file.contains('/')
+        || file.contains('\\')
+        || file.starts_with('.')
+        || !file.ends_with(".md")
+    {
+        return Err(format!("Invalid distill document name: {}", file));
+    }
+    Ok(())
+}
+
+/// Trim, drop empties and duplicates while keeping the given order
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+fn title_slug(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= 40 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "note".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn create_distill_document_in(
+    distill_dir: &Path,
+    title: &str,
+    content: &str,
+    tags: Vec<String>,
+    session: Option<String>,
+) -> AppResult<DistillDocument> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(AppError::invalid_input("Title is required"));
+    }
+    fs::create_dir_all(distill_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
+
+    let _guard = DISTILL_INDEX_LOCK
+        .lock()
+        .map_err(|_| "Distill index lock poisoned")?;
+    let now = chrono::Local::now();
+    let base = format!("{}-{}", now.format("%Y-%m-%d"), title_slug(title));
+    let mut file = format!("{}.md", base);
+    let mut n = 2;
+    while distill_dir.join(&file).exists() {
+        file = format!("{}-{}.md", base, n);
+        n += 1;
+    }
+
+    security::atomic_write_string(&distill_dir.join(&file), content)?;
+    let doc = DistillDocument {
+        date: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        file,
+        title: title.to_string(),
+        tags: normalize_tags(tags),
+        session,
+    };
+    append_distill_index(distill_dir, &doc).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    Ok(doc)
+}
+
+fn update_distill_document_in(
+    distill_dir: &Path,
+    file: &str,
+    title: Option<String>,
+    content: Option<String>,
+    tags: Option<Vec<String>>,
+) -> AppResult<DistillDocument> {
+    validate_distill_file(file).map_err(AppError::invalid_input)?;
+    let _guard = DISTILL_INDEX_LOCK
+        .lock()
+        .map_err(|_| "Distill index lock poisoned")?;
+    let mut docs = read_distill_index(distill_dir);
+    let doc = docs
+        .iter_mut()
+        .find(|d| d.file == file)
+        .ok_or_else(|| AppError::not_found(format!("Distill document not found: {}", file)))?;
+
+    if let Some(title) = title {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(AppError::invalid_input("Title is required"));
+        }
+        doc.title = title.to_string();
+    }
+    if let Some(tags) = tags {
+        doc.tags = normalize_tags(tags);
+    }
+    if let Some(content) = content {
+        security::atomic_write_string(&distill_dir.join(file), &content)?;
+    }
+    let updated = doc.clone();
+    write_distill_index(distill_dir, &docs).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    Ok(updated)
+}
+
+fn delete_distill_document_in(distill_dir: &Path, file: &str) -> AppResult<()> {
+    validate_distill_file(file).map_err(AppError::invalid_input)?;
+    let _guard = DISTILL_INDEX_LOCK
+        .lock()
+        .map_err(|_| "Distill index lock poisoned")?;
+    let mut docs = read_distill_index(distill_dir);
+    let before = docs.len();
+    docs.retain(|d| d.file != file);
+    let path = distill_dir.join(file);
+    if docs.len() == before && !path.exists() {
+        return Err(AppError::not_found(format!("Distill document not found: {}", file)));
+    }
+
+    if path.exists() {
+        file_trash::remove_path(&path).map_err(|e| {
+            AppError::new(ErrorCode::Io, format!("Failed to delete distill document: {}", e))
+        })?;
+    }
+    write_distill_index(distill_dir, &docs).map_err(|e| AppError::new(ErrorCode::Io, e))
+}
+
+/// Apply `edit` to every document's tag list; returns how many documents changed
+fn edit_distill_tags_in(
+    distill_dir: &Path,
+    edit: impl Fn(&mut Vec<String>),
+) -> AppResult<usize> {
+    let _guard = DISTILL_INDEX_LOCK
+        .lock()
+        .map_err(|_| "Distill index lock poisoned")?;
+    let mut docs = read_distill_index(distill_dir);
+    let mut changed = 0;
+    for doc in &mut docs {
+        let before = doc.tags.clone();
+        edit(&mut doc.tags);
+        doc.tags = normalize_tags(std::mem::take(&mut doc.tags));
+        if doc.tags != before {
+            changed += 1;
+        }
+    }
+    if changed > 0 {
+        write_distill_index(distill_dir, &docs).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    }
+    Ok(changed)
+}
+
+fn count_distill_tags(docs: &[DistillDocument]) -> Vec<DistillTag> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in docs.iter().flat_map(|d| &d.tags) {
+        *counts.entry(tag.as_str()).or_insert(0) += 1;
+    }
+    let mut tags: Vec<DistillTag> = counts
+        .into_iter()
+        .map(|(name, count)| DistillTag {
+            name: name.to_string(),
+            count,
+        })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    tags
+}
+
+// ============================================================================
+// Reference Ingestion
+// ============================================================================
+
+static HTML_TITLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("HTML_TITLE_RE should compile")
+});
+static HTML_BODY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<body[^>]*>(.*)</body>").expect("HTML_BODY_RE should compile")
+});
+static HTML_NOISE_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        "script", "style", "noscript", "head", "nav", "footer", "svg", "form",
+    ]
+    .iter()
+    .map(|tag| {
+        Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}>")).expect("noise regex should compile")
+    })
+    .collect()
+});
+static HTML_COMMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").expect("HTML_COMMENT_RE should compile"));
+static HTML_PRE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<pre[^>]*>(.*?)</pre>").expect("HTML_PRE_RE should compile")
+});
+static HTML_HEADING_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    (1..=6)
+        .map(|n| {
+            Regex::new(&format!(r"(?is)<h{n}\b[^>]*>(.*?)</h{n}>"))
+                .expect("heading regex should compile")
+        })
+        .collect()
+});
+static HTML_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<a\b[^>]*?href\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a>"#)
+        .expect("HTML_LINK_RE should compile")
+});
+static HTML_STRONG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<(?:strong|b)\b[^>]*>(.*?)</(?:strong|b)>")
+        .expect("HTML_STRONG_RE should compile")
+});
+static HTML_EM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<(?:em|i)\b[^>]*>(.*?)</(?:em|i)>").expect("HTML_EM_RE should compile")
+});
+static HTML_CODE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<code\b[^>]*>(.*?)</code>").expect("HTML_CODE_RE should compile")
+});
+static HTML_LI_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<li\b[^>]*>").expect("HTML_LI_RE should compile"));
+static HTML_BR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<br\s*/?>").expect("HTML_BR_RE should compile"));
+static HTML_BLOCK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)</?(?:p|div|section|article|main|header|ul|ol|table|tr|blockquote|dl|dt|dd)\b[^>]*>",
+    )
+    .expect("HTML_BLOCK_RE should compile")
+});
+static HTML_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<[^>]+>").expect("HTML_TAG_RE should compile"));
+static HTML_ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("HTML_ENTITY_RE should compile")
+});
+
+fn decode_html_entities(text: &str) -> String {
+    HTML_ENTITY_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .to_string()
+}
+
+/// Tag-stripped, entity-decoded text with whitespace collapsed to single spaces
+fn inline_text(html: &str) -> String {
+    decode_html_entities(&HTML_TAG_RE.replace_all(html, ""))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Best-effort HTML to Markdown for documentation pages. Returns (title, markdown).
+fn html_to_markdown(html: &str) -> (Option<String>, String) {
+    let title = HTML_TITLE_RE
+        .captures(html)
+        .map(|c| inline_text(&c[1]))
+        .filter(|t| !t.is_empty());
+    let mut text = HTML_BODY_RE
+        .captures(html)
+        .map_or_else(|| html.to_string(), |c| c[1].to_string());
+
+    text = HTML_COMMENT_RE.replace_all(&text, "").to_string();
+    for re in HTML_NOISE_RES.iter() {
+        text = re.replace_all(&text, "").to_string();
+    }
+
+    // Code blocks keep their whitespace: park them behind placeholders until the end
+    let mut blocks: Vec<String> = Vec::new();
+    text = HTML_PRE_RE
+        .replace_all(&text, |caps: &regex::Captures| {
+            let code = decode_html_entities(&HTML_TAG_RE.replace_all(&caps[1], ""));
+            blocks.push(format!("```\n{}\n```", code.trim_matches('\n')));
+            format!("<p>\u{0}{}\u{0}</p>", blocks.len() - 1)
+        })
+        .to_string();
+    // Outside code blocks, source line breaks are just whitespace
+    text = text.replace(['\r', '\n'], " ");
+
+    for (level, re) in HTML_HEADING_RES.iter().enumerate() {
+        let hashes = "#".repeat(level + 1);
+        text = re
+            .replace_all(&text, |caps: &regex::Captures| {
+                format!("\n\n{} {}\n\n", hashes, inline_text(&caps[1]))
+            })
+            .to_string();
+    }
+    text = HTML_STRONG_RE.replace_all(&text, "**$1**").to_string();
+    text = HTML_EM_RE.replace_all(&text, "*$1*").to_string();
+    text = HTML_CODE_RE.replace_all(&text, "`$1`").to_string();
+    text = HTML_LINK_RE
+        .replace_all(&text, |caps: &regex::Captures| {
+            format!("[{}]({})", inline_text(&caps[2]), &caps[1])
+        })
+        .to_string();
+    text = HTML_LI_RE.replace_all(&text, "\n- ").to_string();
+    text = HTML_BR_RE.replace_all(&text, "\n").to_string();
+    text = HTML_BLOCK_RE.replace_all(&text, "\n\n").to_string();
+    text = decode_html_entities(&HTML_TAG_RE.replace_all(&text, ""));
+
+    let mut markdown = String::new();
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        if !markdown.is_empty() {
+            markdown.push_str(if blank_run > 0 { "\n\n" } else { "\n" });
+        }
+        blank_run = 0;
+        let restored = line
+            .strip_prefix('\u{0}')
+            .and_then(|rest| rest.strip_suffix('\u{0}'))
+            .and_then(|idx| idx.parse::<usize>().ok())
+            .and_then(|idx| blocks.get(idx));
+        markdown.push_str(restored.map_or(line.as_str(), String::as_str));
+    }
+    markdown.push('\n');
+    (title, markdown)
+}
+
+/// Source and doc names are used as path components
+fn validate_reference_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.contains('/')
+        || name.contains('\\')
+        || name.starts_with('.')
+        || name.starts_with('_')
+    {
+        return Err(format!("Invalid reference name: {}", name));
+    }
+    Ok(())
+}
+
+fn load_reference_origins(source_dir: &Path) -> BTreeMap<String, ReferenceOrigin> {
+    fs::read_to_string(source_dir.join(REFERENCE_SOURCES_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_reference_origins(
+    source_dir: &Path,
+    origins: &BTreeMap<String, ReferenceOrigin>,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(origins).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&source_dir.join(REFERENCE_SOURCES_FILE), &content)
+        .map_err(|e| format!("Failed to write reference metadata: {}", e))
+}
+
+/// Doc name for a URL: reuses the existing entry for the same URL, otherwise the
+/// last path segment (or host) slugged, suffixed on collision
+fn reference_doc_name(url: &reqwest::Url, origins: &BTreeMap<String, ReferenceOrigin>) -> String {
+    if let Some((name, _)) = origins.iter().find(|(_, o)| o.url == url.as_str()) {
+        return name.clone();
+    }
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .map(|s| {
+            s.trim_end_matches(".md")
+                .trim_end_matches(".html")
+                .trim_end_matches(".htm")
+                .to_string()
+        })
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| url.host_str().unwrap_or("page").to_string());
+    let base = title_slug(&segment);
+    let mut name = base.clone();
+    let mut n = 2;
+    while origins.contains_key(&name) {
+        name = format!("{}-{}", base, n);
+        n += 1;
+    }
+    name
+}
+
+/// HTTPS-only fetch; HTML is converted, markdown/plain text stored as-is.
+/// Returns (title, markdown).
+async fn fetch_reference(url: &reqwest::Url) -> AppResult<(Option<String>, String)> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(20))
+        .https_only(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| AppError::new(ErrorCode::Network, format!("Failed to fetch {}: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::new(
+            ErrorCode::Network,
+            format!("Failed to fetch {}: HTTP {}", url, response.status()),
+        ));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_REFERENCE_BYTES as u64)
+    {
+        return Err(AppError::invalid_input(format!("Document too large: {}", url)));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_lowercase();
+    // Content-Length may be missing or wrong: stop reading once past the cap
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::new(ErrorCode::Network, format!("Failed to read {}: {}", url, e)))?
+    {
+        if bytes.len() + chunk.len() > MAX_REFERENCE_BYTES {
+            return Err(AppError::invalid_input(format!("Document too large: {}", url)));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    let body = String::from_utf8_lossy(&bytes).into_owned();
+
+    let is_markdown = content_type.starts_with("text/markdown")
+        || content_type.starts_with("text/plain")
+        || url.path().ends_with(".md");
+    if is_markdown {
+        let title = body
+            .lines()
+            .find_map(|l| l.strip_prefix("# "))
+            .map(|t| t.trim().to_string());
+        Ok((title, body))
+    } else {
+        Ok(html_to_markdown(&body))
+    }
+}
+
+/// Write a fetched doc and record its origin; returns the doc path
+fn store_reference_doc(
+    source_dir: &Path,
+    name: &str,
+    origin: ReferenceOrigin,
+    markdown: &str,
+) -> AppResult<PathBuf> {
+    let _guard = REFERENCE_SOURCES_LOCK
+        .lock()
+        .map_err(|_| "Reference sources lock poisoned")?;
+    fs::create_dir_all(source_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    let path = source_dir.join(format!("{}.md", name));
+    security::atomic_write_string(&path, markdown)?;
+    let mut origins = load_reference_origins(source_dir);
+    origins.insert(name.to_string(), origin);
+    save_reference_origins(source_dir, &origins).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    Ok(path)
+}
+
+/// Docs whose refresh interval has elapsed (or every URL doc when `force`)
+fn stale_reference_docs(
+    origins: &BTreeMap<String, ReferenceOrigin>,
+    now_ms: i64,
+    force: bool,
+) -> Vec<(String, ReferenceOrigin)> {
+    origins
+        .iter()
+        .filter(|(_, o)| {
+            force
+                || o.refresh_hours
+                    .is_some_and(|h| now_ms - o.fetched_at >= i64::from(h) * 3_600_000)
+        })
+        .map(|(name, o)| (name.clone(), o.clone()))
+        .collect()
+}
+
+/// Re-fetch stale URL docs across user reference sources; returns how many were updated
+async fn refresh_reference_docs(force: bool) -> AppResult<usize> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut refreshed = 0;
+    for source in scan_reference_dir(&get_reference_dir()) {
+        let source_dir = PathBuf::from(&source.path);
+        for (name, origin) in
+            stale_reference_docs(&load_reference_origins(&source_dir), now_ms, force)
+        {
+            let Ok(url) = reqwest::Url::parse(&origin.url) else {
+                continue;
+            };
+            match fetch_reference(&url).await {
+                Ok((title, markdown)) => {
+                    let origin = ReferenceOrigin {
+                        title: title.or(origin.title),
+                        fetched_at: chrono::Utc::now().timestamp_millis(),
+                        ..origin
+                    };
+                    store_reference_doc(&source_dir, &name, origin, &markdown)?;
+                    refreshed += 1;
+                }
+                Err(e) => tracing::warn!("Failed to refresh reference {}: {}", name, e),
+            }
+        }
+    }
+    Ok(refreshed)
+}
+
+/// Hourly background check for reference docs due a refresh
+pub fn start_reference_refresher() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+            if let Err(e) = refresh_reference_docs(false).await {
+                tracing::warn!("Reference refresh failed: {}", e);
+            }
+        }
+    });
+}
+
 // ============================================================================
 // Commands
 // ============================================================================
 
 #[tauri::command]
-pub fn list_reference_sources(app_handle: tauri::AppHandle) -> Result<Vec<ReferenceSource>, String> {
+pub fn list_reference_sources(app_handle: tauri::AppHandle) -> AppResult<Vec<ReferenceSource>> {
     let mut sources = Vec::new();
     let mut seen_names = std::collections::HashSet::new();
 
@@ -216,7 +825,7 @@ pub fn list_reference_sources(app_handle: tauri::AppHandle) -> Result<Vec<Refere
 }
 
 #[tauri::command]
-pub fn list_reference_docs(app_handle: tauri::AppHandle, source: String) -> Result<Vec<ReferenceDoc>, String> {
+pub fn list_reference_docs(app_handle: tauri::AppHandle, source: String) -> AppResult<Vec<ReferenceDoc>> {
     let source_dir = match find_reference_source_dir(&app_handle, &source) {
         Some(dir) => dir,
         None => return Ok(vec![]),
@@ -251,9 +860,12 @@ pub fn list_reference_docs(app_handle: tauri::AppHandle, source: String) -> Resu
         }
     }
 
+    let origins = load_reference_origins(&source_dir);
     let mut docs = Vec::new();
-    for entry in fs::read_dir(&source_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
+    let entries = fs::read_dir(&source_dir)
+        .map_err(|e| AppError::io("Failed to read directory", &e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
         let path = entry.path();
 
         if path.extension().map(|e| e == "md").unwrap_or(false) {
@@ -264,10 +876,12 @@ pub fn list_reference_docs(app_handle: tauri::AppHandle, source: String) -> Resu
 
             let group = order_map.get(&name).and_then(|(_, g)| g.clone());
 
+            let url = origins.get(&name).map(|o| o.url.clone());
             docs.push(ReferenceDoc {
                 name,
                 path: path.to_string_lossy().to_string(),
                 group,
+                url,
             });
         }
     }
@@ -292,8 +906,96 @@ pub fn list_reference_docs(app_handle: tauri::AppHandle, source: String) -> Resu
     Ok(docs)
 }
 
+/// Distill one session through `claude -p`. `template` overrides the default prompt
+/// (placeholders: {{transcript}}, {{project}}, {{session_id}}, {{date}}).
+#[tauri::command]
+pub async fn distill_session(
+    project_id: String,
+    session_id: String,
+    template: Option<String>,
+) -> AppResult<DistillDocument> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let session_path = distill::session_transcript_path(&project_id, &session_id)?;
+        let template = template.unwrap_or_else(|| DEFAULT_DISTILL_TEMPLATE.to_string());
+        distill::distill_with_claude(&session_path, &get_distill_dir(), &template)
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Batch mode: distill every session modified within [start, end) (Unix ms), emitting
+/// "distill-progress" per session. Already distilled sessions are skipped unless `force`.
+#[tauri::command]
+pub async fn distill_sessions(
+    app_handle: tauri::AppHandle,
+    start: i64,
+    end: i64,
+    template: Option<String>,
+    force: Option<bool>,
+) -> AppResult<Vec<DistillProgress>> {
+    use tauri::Emitter;
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+        let sessions = distill::sessions_in_range(&projects_dir, start, end);
+        let template = template.unwrap_or_else(|| DEFAULT_DISTILL_TEMPLATE.to_string());
+        distill::distill_sessions_with_claude(
+            &sessions,
+            &get_distill_dir(),
+            &template,
+            force.unwrap_or(false),
+            &|progress| {
+                let _ = app_handle.emit("distill-progress", progress);
+            },
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?)
+}
+
+/// Fetch a page or raw markdown over HTTPS into a user reference source
+/// (default "web"). Re-adding the same URL overwrites its doc.
+#[tauri::command]
+pub async fn add_reference_source(
+    url: String,
+    source: Option<String>,
+    refresh_hours: Option<u32>,
+) -> AppResult<ReferenceDoc> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::invalid_input(format!("Invalid URL: {}", e)))?;
+    if parsed.scheme() != "https" {
+        return Err(AppError::invalid_input("Only https:// URLs are supported"));
+    }
+    let source = source.unwrap_or_else(|| DEFAULT_REFERENCE_SOURCE.to_string());
+    validate_reference_name(&source).map_err(AppError::invalid_input)?;
+    let source_dir = get_reference_dir().join(&source);
+
+    let (title, markdown) = fetch_reference(&parsed).await?;
+    let name = reference_doc_name(&parsed, &load_reference_origins(&source_dir));
+    let origin = ReferenceOrigin {
+        url: parsed.to_string(),
+        title,
+        fetched_at: chrono::Utc::now().timestamp_millis(),
+        refresh_hours,
+    };
+    let path = store_reference_doc(&source_dir, &name, origin, &markdown)?;
+
+    Ok(ReferenceDoc {
+        name,
+        path: path.to_string_lossy().to_string(),
+        group: None,
+        url: Some(parsed.to_string()),
+    })
+}
+
+/// Re-fetch URL-backed reference docs that are due (all of them when `force`)
 #[tauri::command]
-pub fn list_distill_documents() -> Result<Vec<DistillDocument>, String> {
+pub async fn refresh_reference_sources(force: Option<bool>) -> AppResult<usize> {
+    refresh_reference_docs(force.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub fn list_distill_documents() -> AppResult<Vec<DistillDocument>> {
     let distill_dir = get_distill_dir();
     let index_path = distill_dir.join("index.jsonl");
 
@@ -301,7 +1003,8 @@ pub fn list_distill_documents() -> Result<Vec<DistillDocument>, String> {
         return Ok(vec![]);
     }
 
-    let content = fs::read_to_string(&index_path).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&index_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
     let mut docs: Vec<DistillDocument> = content
         .lines()
         .filter(|line| !line.trim().is_empty())
@@ -325,14 +1028,69 @@ pub fn list_distill_documents() -> Result<Vec<DistillDocument>, String> {
 }
 
 #[tauri::command]
-pub fn find_session_project(session_id: String) -> Result<Option<Session>, String> {
+pub fn create_distill_document(
+    title: String,
+    content: String,
+    tags: Vec<String>,
+    session: Option<String>,
+) -> AppResult<DistillDocument> {
+    create_distill_document_in(&get_distill_dir(), &title, &content, tags, session)
+}
+
+/// Fields left as None are kept unchanged
+#[tauri::command]
+pub fn update_distill_document(
+    file: String,
+    title: Option<String>,
+    content: Option<String>,
+    tags: Option<Vec<String>>,
+) -> AppResult<DistillDocument> {
+    update_distill_document_in(&get_distill_dir(), &file, title, content, tags)
+}
+
+#[tauri::command]
+pub fn delete_distill_document(file: String) -> AppResult<()> {
+    delete_distill_document_in(&get_distill_dir(), &file)
+}
+
+#[tauri::command]
+pub fn list_distill_tags() -> AppResult<Vec<DistillTag>> {
+    Ok(count_distill_tags(&read_distill_index(&get_distill_dir())))
+}
+
+/// Rename a tag across all documents (merges into `to` if it already exists)
+#[tauri::command]
+pub fn rename_distill_tag(from: String, to: String) -> AppResult<usize> {
+    let to = to.trim().to_string();
+    if to.is_empty() {
+        return Err(AppError::invalid_input("Tag name is required"));
+    }
+    edit_distill_tags_in(&get_distill_dir(), |tags| {
+        for tag in tags.iter_mut() {
+            if *tag == from {
+                *tag = to.clone();
+            }
+        }
+    })
+}
+
+#[tauri::command]
+pub fn delete_distill_tag(tag: String) -> AppResult<usize> {
+    edit_distill_tags_in(&get_distill_dir(), |tags| tags.retain(|t| *t != tag))
+}
+
+#[tauri::command]
+pub fn find_session_project(session_id: String) -> AppResult<Option<Session>> {
     let projects_dir = security::get_claude_dir_or_fallback().join("projects");
     if !projects_dir.exists() {
         return Ok(None);
     }
 
-    for project_entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-        let project_entry = project_entry.map_err(|e| e.to_string())?;
+    let project_entries = fs::read_dir(&projects_dir)
+        .map_err(|e| AppError::io("Failed to read directory", &e))?;
+    for project_entry in project_entries {
+        let project_entry =
+            project_entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
         let project_path = project_entry.path();
 
         if !project_path.is_dir() {
@@ -380,3 +1138,162 @@ pub fn get_distill_watch_enabled() -> bool {
 pub fn set_distill_watch_enabled(enabled: bool) {
     DISTILL_WATCH_ENABLED.store(enabled, std::sync::atomic::Ordering::Relaxed);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distill_document_crud() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let dir = temp.path();
+
+        let doc = create_distill_document_in(
+            dir,
+            "Tauri: IPC notes!",
+            "# IPC",
+            vec!["tauri".into(), " ipc ".into(), "tauri".into(), "".into()],
+            None,
+        )
+        .expect("create");
+        assert!(doc.file.ends_with("-tauri-ipc-notes.md"));
+        assert_eq!(doc.tags, vec!["tauri", "ipc"]);
+        let second = create_distill_document_in(dir, "Tauri: IPC notes!", "", vec![], None)
+            .expect("create duplicate title");
+        assert!(second.file.ends_with("-tauri-ipc-notes-2.md"));
+
+        let updated = update_distill_document_in(
+            dir,
+            &doc.file,
+            Some("IPC".into()),
+            Some("# IPC v2".into()),
+            None,
+        )
+        .expect("update");
+        assert_eq!(updated.title, "IPC");
+        assert_eq!(updated.tags, vec!["tauri", "ipc"]);
+        assert_eq!(
+            fs::read_to_string(dir.join(&doc.file)).expect("read"),
+            "# IPC v2"
+        );
+
+        delete_distill_document_in(dir, &second.file).expect("delete");
+        assert!(!dir.join(&second.file).exists());
+        let index = read_distill_index(dir);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].title, "IPC");
+        assert!(delete_distill_document_in(dir, &second.file).is_err());
+        assert!(update_distill_document_in(dir, "../index.md", None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_distill_tag_management() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let dir = temp.path();
+        create_distill_document_in(dir, "a", "", vec!["rust".into(), "Rust".into()], None)
+            .expect("create a");
+        create_distill_document_in(dir, "b", "", vec!["rust".into()], None).expect("create b");
+        create_distill_document_in(dir, "c", "", vec!["auto".into()], None).expect("create c");
+
+        let renamed = edit_distill_tags_in(dir, |tags| {
+            for tag in tags.iter_mut() {
+                if *tag == "Rust" {
+                    *tag = "rust".to_string();
+                }
+            }
+        })
+        .expect("rename");
+        assert_eq!(renamed, 1);
+        assert_eq!(
+            count_distill_tags(&read_distill_index(dir)),
+            vec![
+                DistillTag {
+                    name: "rust".into(),
+                    count: 2
+                },
+                DistillTag {
+                    name: "auto".into(),
+                    count: 1
+                },
+            ]
+        );
+
+        let removed = edit_distill_tags_in(dir, |tags| tags.retain(|t| t.as_str() != "rust"))
+            .expect("delete");
+        assert_eq!(removed, 2);
+        assert_eq!(count_distill_tags(&read_distill_index(dir)).len(), 1);
+    }
+
+    #[test]
+    fn test_html_to_markdown() {
+        let html = [
+            "<html><head><title>Hooks &amp; Events</title><style>p{}</style></head><body>",
+            "<nav><a href=\"/\">Home</a></nav>",
+            "<h1>Hooks</h1><p>Run <code>claude</code> with <strong>care</strong>, see",
+            "<a href=\"https://example.com/x\">the <em>docs</em></a>.</p>",
+            "<ul><li>one</li><li>two &lt;3</li></ul>",
+            "<pre><code>fn main() {\n    println!(\"hi\");\n}</code></pre>",
+            "<script>alert(1)</script></body></html>",
+        ]
+        .join("\n");
+        let (title, markdown) = html_to_markdown(&html);
+        assert_eq!(title.as_deref(), Some("Hooks & Events"));
+        assert_eq!(
+            markdown,
+            [
+                "# Hooks",
+                "",
+                "Run `claude` with **care**, see [the *docs*](https://example.com/x).",
+                "",
+                "- one",
+                "- two <3",
+                "",
+                "```\nfn main() {\n    println!(\"hi\");\n}\n```",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_reference_doc_naming_and_refresh() {
+        let url = reqwest::Url::parse("https://docs.example.com/guide/hooks.md").expect("url");
+        let mut origins = BTreeMap::new();
+        assert_eq!(reference_doc_name(&url, &origins), "hooks");
+
+        origins.insert(
+            "hooks".to_string(),
+            ReferenceOrigin {
+                url: "https://other.example.com/hooks".to_string(),
+                title: None,
+                fetched_at: 0,
+                refresh_hours: Some(24),
+            },
+        );
+        assert_eq!(reference_doc_name(&url, &origins), "hooks-2");
+        let other = reqwest::Url::parse("https://other.example.com/hooks").expect("url");
+        assert_eq!(reference_doc_name(&other, &origins), "hooks");
+        let root = reqwest::Url::parse("https://example.com/").expect("url");
+        assert_eq!(reference_doc_name(&root, &origins), "example-com");
+
+        origins.insert(
+            "pinned".to_string(),
+            ReferenceOrigin {
+                url: root.to_string(),
+                title: None,
+                fetched_at: 0,
+                refresh_hours: None,
+            },
+        );
+        let hour = 3_600_000;
+        assert!(stale_reference_docs(&origins, 23 * hour, false).is_empty());
+        let due = stale_reference_docs(&origins, 24 * hour, false);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, "hooks");
+        assert_eq!(stale_reference_docs(&origins, 0, true).len(), 2);
+
+        assert!(validate_reference_name("web").is_ok());
+        assert!(validate_reference_name("../etc").is_err());
+        assert!(validate_reference_name("_sources.json").is_err());
+    }
+}
@@ -1,22 +1,39 @@
 /**
- * [INPUT]: 依赖 std::fs, std::path, std::collections::HashMap, crate::types::LocalCommand, crate::security
- * [OUTPUT]: 对外提供 list_local_commands, rename_command, deprecate_command, archive_command, restore_command, update_command_aliases 命令
+ * [INPUT]: 依赖 std::fs, std::path, std::collections::HashMap, crate::types::LocalCommand, crate::security, crate::commands::report, crate::commands::file_diff (unified_diff), chrono
+ * [OUTPUT]: 对外提供 list_local_commands, list_project_commands, rename_command, move_command_namespace, deprecate_command, archive_command, restore_command, update_command_aliases, update_command_content, list_command_versions, diff_command_versions 命令
  * [POS]: commands/ 模块的本地命令管理中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use crate::commands::file_diff::unified_diff;
+use crate::commands::report::{load_command_usage, normalize_command_key};
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
 use crate::types::LocalCommand;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+// ============================================================================
+// Types
+// ============================================================================
+
+/// An archived snapshot of a command (or the live file when `is_current`)
+#[derive(Debug, Serialize, Clone)]
+pub struct CommandVersion {
+    pub version: String,
+    pub path: String,
+    pub last_modified: u64,
+    pub is_current: bool,
+}
+
 // ============================================================================
 // Main Commands
 // ============================================================================
 
 #[tauri::command]
-pub fn list_local_commands() -> Result<Vec<LocalCommand>, String> {
+pub fn list_local_commands() -> AppResult<Vec<LocalCommand>> {
     let claude_dir = security::get_claude_dir_or_fallback();
     let commands_dir = claude_dir.join("commands");
     let dot_commands_dir = claude_dir.join(".commands");
@@ -37,14 +54,61 @@ pub fn list_local_commands() -> Result<Vec<LocalCommand>, String> {
 
     // Collect active commands from commands/
     if commands_dir.exists() {
-        collect_commands_from_dir(&commands_dir, &commands_dir, &mut commands, "active")?;
+        collect_commands_from_dir(
+            &commands_dir,
+            &commands_dir,
+            &mut commands,
+            "active",
+            "user",
+        )?;
     }
 
     // Collect deprecated commands from .commands/archived/
     if archived_dir.exists() {
-        collect_commands_from_dir(&archived_dir, &archived_dir, &mut commands, "deprecated")?;
+        collect_commands_from_dir(
+            &archived_dir,
+            &archived_dir,
+            &mut commands,
+            "deprecated",
+            "user",
+        )?;
+    }
+
+    attach_usage_stats(&mut commands);
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(commands)
+}
+
+/// List commands defined inside a project's .claude/commands (scope = "project")
+#[tauri::command]
+pub fn list_project_commands(project_path: String) -> AppResult<Vec<LocalCommand>> {
+    security::validate_decoded_path(&project_path)?;
+
+    let claude_dir = PathBuf::from(&project_path).join(".claude");
+    let commands_dir = claude_dir.join("commands");
+    let archived_dir = claude_dir.join(".commands").join("archived");
+
+    let mut commands = Vec::new();
+    if commands_dir.exists() {
+        collect_commands_from_dir(
+            &commands_dir,
+            &commands_dir,
+            &mut commands,
+            "active",
+            "project",
+        )?;
+    }
+    if archived_dir.exists() {
+        collect_commands_from_dir(
+            &archived_dir,
+            &archived_dir,
+            &mut commands,
+            "deprecated",
+            "project",
+        )?;
     }
 
+    attach_usage_stats(&mut commands);
     commands.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(commands)
 }
@@ -55,24 +119,24 @@ pub fn rename_command(
     path: String,
     new_name: String,
     create_dir: Option<bool>,
-) -> Result<String, String> {
+) -> AppResult<String> {
     let src = PathBuf::from(&path);
     if !src.exists() {
-        return Err(format!("Command file not found: {}", path));
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
     }
 
     if !path.ends_with(".md") {
-        return Err("Can only rename .md commands".to_string());
+        return Err(AppError::invalid_input("Can only rename .md commands"));
     }
 
     // Parse new_name as a command path (e.g., /lovstudio/repo/takeover)
     let name = new_name.trim().trim_start_matches('/');
     if name.is_empty() {
-        return Err("New name cannot be empty".to_string());
+        return Err(AppError::invalid_input("New name cannot be empty"));
     }
 
-    // Build destination path from command name
-    let commands_dir = security::get_claude_dir_or_fallback().join("commands");
+    // Build destination path from command name (within the same scope as the source)
+    let commands_dir = resolve_claude_dir_for(&src).join("commands");
     let new_filename = if name.ends_with(".md") {
         name.to_string()
     } else {
@@ -85,18 +149,21 @@ pub fn rename_command(
         if !dest_parent.exists() {
             if create_dir.unwrap_or(false) {
                 fs::create_dir_all(dest_parent)
-                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+                    .map_err(|e| AppError::io("Failed to create directory", &e))?;
             } else {
                 // Return special error for frontend to show confirmation
-                return Err(format!("DIR_NOT_EXIST:{}", dest_parent.to_string_lossy()));
+                return Err(AppError::not_found(format!(
+                    "DIR_NOT_EXIST:{}",
+                    dest_parent.to_string_lossy()
+                )));
             }
         }
     }
 
     if dest.exists() && dest != src {
-        return Err(format!(
-            "A command with name '{}' already exists",
-            new_filename
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("A command with name '{}' already exists", new_filename),
         ));
     }
 
@@ -134,13 +201,14 @@ pub fn rename_command(
             };
 
         // Update aliases: add old name, remove new name if it was an alias
-        let content = fs::read_to_string(&src).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&src)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
         let updated = update_aliases_on_rename(&content, &old_name, &new_name);
         if updated != content {
-            security::atomic_write_string(&src, &updated).map_err(|e| e.to_string())?;
+            security::atomic_write_string(&src, &updated)?;
         }
 
-        fs::rename(&src, &dest).map_err(|e| e.to_string())?;
+        fs::rename(&src, &dest).map_err(|e| AppError::io("Failed to move file", &e))?;
 
         // Also rename associated .changelog file if exists
         let changelog_src = src.with_extension("changelog");
@@ -160,27 +228,28 @@ pub fn deprecate_command(
     path: String,
     replaced_by: Option<String>,
     note: Option<String>,
-) -> Result<String, String> {
+) -> AppResult<String> {
     let src = PathBuf::from(&path);
     if !src.exists() {
-        return Err(format!("Command file not found: {}", path));
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
     }
 
-    let commands_dir = security::get_claude_dir_or_fallback().join("commands");
-    let archived_dir = security::get_claude_dir_or_fallback().join(".commands").join("archived");
+    let claude_dir = resolve_claude_dir_for(&src);
+    let commands_dir = claude_dir.join("commands");
+    let archived_dir = claude_dir.join(".commands").join("archived");
 
     // Only allow deprecating active .md files from commands directory
     if !path.ends_with(".md") {
-        return Err("Can only deprecate .md commands".to_string());
+        return Err(AppError::invalid_input("Can only deprecate .md commands"));
     }
 
     // Check if already archived
     if src.starts_with(&archived_dir) {
-        return Err("Command is already archived".to_string());
+        return Err(AppError::new(ErrorCode::Conflict, "Command is already archived"));
     }
 
     // Update frontmatter with replaced_by and/or note
-    let content = fs::read_to_string(&src).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&src).map_err(|e| AppError::io("Failed to read file", &e))?;
     let mut updated = content.clone();
     if let Some(replacement) = &replaced_by {
         updated = add_frontmatter_field(&updated, "replaced-by", replacement);
@@ -189,21 +258,22 @@ pub fn deprecate_command(
         updated = add_frontmatter_field(&updated, "deprecation-note", n);
     }
     if updated != content {
-        security::atomic_write_string(&src, &updated).map_err(|e| e.to_string())?;
+        security::atomic_write_string(&src, &updated)?;
     }
 
     // Calculate relative path from commands directory
     let relative = src
         .strip_prefix(&commands_dir)
-        .map_err(|_| "Command is not in commands directory")?;
+        .map_err(|_| AppError::invalid_input("Command is not in commands directory"))?;
 
     // Create destination path in archived directory (preserving subdirectory structure)
     let dest = archived_dir.join(relative);
     if let Some(dest_parent) = dest.parent() {
-        fs::create_dir_all(dest_parent).map_err(|e| e.to_string())?;
+        fs::create_dir_all(dest_parent)
+            .map_err(|e| AppError::io("Failed to create directory", &e))?;
     }
 
-    fs::rename(&src, &dest).map_err(|e| e.to_string())?;
+    fs::rename(&src, &dest).map_err(|e| AppError::io("Failed to move file", &e))?;
 
     // Also move associated .changelog file if exists
     let base_name = src.with_extension("");
@@ -221,16 +291,17 @@ pub fn deprecate_command(
 
 /// Archive a command by moving it to versions/ directory with version suffix
 #[tauri::command]
-pub fn archive_command(path: String, version: String) -> Result<String, String> {
+pub fn archive_command(path: String, version: String) -> AppResult<String> {
     let src = PathBuf::from(&path);
     if !src.exists() {
-        return Err(format!("Command file not found: {}", path));
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
     }
 
     // Get the commands directory and create versions/ if needed
     let commands_dir = src.parent().unwrap_or(&src);
     let versions_dir = commands_dir.join("versions");
-    fs::create_dir_all(&versions_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&versions_dir)
+        .map_err(|e| AppError::io("Failed to create directory", &e))?;
 
     // Get base name and create versioned filename
     let filename = src.file_name().unwrap_or_default().to_string_lossy();
@@ -238,21 +309,22 @@ pub fn archive_command(path: String, version: String) -> Result<String, String>
     let versioned_name = format!("{}.v{}.md.archived", base_name, version);
     let dest = versions_dir.join(versioned_name);
 
-    fs::rename(&src, &dest).map_err(|e| e.to_string())?;
+    fs::rename(&src, &dest).map_err(|e| AppError::io("Failed to move file", &e))?;
 
     Ok(dest.to_string_lossy().to_string())
 }
 
 /// Restore a deprecated or archived command to active status
 #[tauri::command]
-pub fn restore_command(path: String) -> Result<String, String> {
+pub fn restore_command(path: String) -> AppResult<String> {
     let src = PathBuf::from(&path);
     if !src.exists() {
-        return Err(format!("Command file not found: {}", path));
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
     }
 
-    let commands_dir = security::get_claude_dir_or_fallback().join("commands");
-    let archived_dir = security::get_claude_dir_or_fallback().join(".commands").join("archived");
+    let claude_dir = resolve_claude_dir_for(&src);
+    let commands_dir = claude_dir.join("commands");
+    let archived_dir = claude_dir.join(".commands").join("archived");
     let path_str = src.to_string_lossy();
 
     // Determine source type and calculate destination
@@ -280,20 +352,24 @@ pub fn restore_command(path: String) -> Result<String, String> {
         let base = file_name.split(".v").next().unwrap_or(&file_name);
         parent.join(format!("{}.md", base))
     } else {
-        return Err("File is not deprecated or archived".to_string());
+        return Err(AppError::invalid_input("File is not deprecated or archived"));
     };
 
     // Check if destination already exists
     if dest.exists() {
-        return Err(format!("Cannot restore: {} already exists", dest.display()));
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("Cannot restore: {} already exists", dest.display()),
+        ));
     }
 
     // Create destination directory if needed
     if let Some(dest_parent) = dest.parent() {
-        fs::create_dir_all(dest_parent).map_err(|e| e.to_string())?;
+        fs::create_dir_all(dest_parent)
+            .map_err(|e| AppError::io("Failed to create directory", &e))?;
     }
 
-    fs::rename(&src, &dest).map_err(|e| e.to_string())?;
+    fs::rename(&src, &dest).map_err(|e| AppError::io("Failed to move file", &e))?;
 
     // Also restore associated .changelog file if exists
     if src.starts_with(&archived_dir) {
@@ -311,24 +387,443 @@ pub fn restore_command(path: String) -> Result<String, String> {
     Ok(dest.to_string_lossy().to_string())
 }
 
+/// Move every command under /old_prefix/* to /new_prefix/*, keeping aliases,
+/// changelogs and versions/ in step. Returns the new command file paths.
+#[tauri::command]
+pub fn move_command_namespace(
+    old_prefix: String,
+    new_prefix: String,
+) -> AppResult<Vec<String>> {
+    let commands_dir = security::get_claude_dir_or_fallback().join("commands");
+    move_namespace_in(&commands_dir, &old_prefix, &new_prefix)
+}
+
+fn move_namespace_in(
+    commands_dir: &Path,
+    old_prefix: &str,
+    new_prefix: &str,
+) -> AppResult<Vec<String>> {
+    let old_ns = old_prefix.trim().trim_matches('/').to_string();
+    let new_ns = new_prefix.trim().trim_matches('/').to_string();
+
+    if old_ns.is_empty() || new_ns.is_empty() {
+        return Err(AppError::invalid_input("Namespace cannot be empty"));
+    }
+    if old_ns.contains("..") || new_ns.contains("..") {
+        return Err(AppError::invalid_input("Namespace cannot contain '..'"));
+    }
+    if old_ns == new_ns {
+        return Ok(Vec::new());
+    }
+    if new_ns.starts_with(&format!("{}/", old_ns)) {
+        return Err(AppError::invalid_input("Cannot move a namespace into itself"));
+    }
+
+    let old_dir = commands_dir.join(&old_ns);
+    let new_dir = commands_dir.join(&new_ns);
+    if !old_dir.is_dir() {
+        return Err(AppError::not_found(format!("Namespace not found: /{}", old_ns)));
+    }
+
+    let mut files = Vec::new();
+    collect_namespace_files(&old_dir, &mut files)
+        .map_err(|e| AppError::io("Failed to read namespace", &e))?;
+
+    // Refuse up front if anything would be overwritten
+    let conflicts: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.strip_prefix(&old_dir).ok())
+        .filter(|rel| new_dir.join(rel).exists())
+        .map(|rel| rel.to_string_lossy().to_string())
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("Cannot move: /{} already contains {}", new_ns, conflicts.join(", ")),
+        ));
+    }
+
+    let mut moved = Vec::new();
+    for src in files {
+        let relative = src
+            .strip_prefix(&old_dir)
+            .map_err(|_| "Cannot get relative path")?
+            .to_path_buf();
+        let dest = new_dir.join(&relative);
+
+        let is_command = src.extension().is_some_and(|e| e == "md")
+            && !relative.starts_with("versions");
+        if is_command {
+            let command_rel = relative.with_extension("");
+            let command_rel = command_rel.to_string_lossy().replace('\\', "/");
+            let old_name = format!("/{}/{}", old_ns, command_rel);
+            let new_name = format!("/{}/{}", new_ns, command_rel);
+
+            let content =
+                fs::read_to_string(&src).map_err(|e| AppError::io("Failed to read file", &e))?;
+            let updated = update_aliases_on_rename(&content, &old_name, &new_name);
+            if updated != content {
+                security::atomic_write_string(&src, &updated)?;
+            }
+        }
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("Failed to create directory", &e))?;
+        }
+        fs::rename(&src, &dest).map_err(|e| AppError::io("Failed to move file", &e))?;
+
+        if is_command {
+            moved.push(dest.to_string_lossy().to_string());
+        }
+    }
+
+    remove_empty_dirs(&old_dir);
+
+    moved.sort();
+    Ok(moved)
+}
+
 /// Update aliases for a command
 #[tauri::command]
-pub fn update_command_aliases(path: String, aliases: Vec<String>) -> Result<(), String> {
+pub fn update_command_aliases(path: String, aliases: Vec<String>) -> AppResult<()> {
     let file_path = PathBuf::from(&path);
     if !file_path.exists() {
-        return Err(format!("Command file not found: {}", path));
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
     }
 
-    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
 
     // Format aliases as comma-separated string
     let aliases_value = aliases.join(", ");
     let updated_content = update_frontmatter_field(&content, "aliases", &aliases_value);
 
-    security::atomic_write_string(&file_path, &updated_content).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&file_path, &updated_content)?;
+    Ok(())
+}
+
+/// Rewrite a command's frontmatter and body, recording the edit in its .changelog
+#[tauri::command]
+pub fn update_command_content(
+    path: String,
+    frontmatter: Option<String>,
+    body: String,
+) -> AppResult<()> {
+    let file_path = PathBuf::from(&path);
+    if !file_path.exists() {
+        return Err(AppError::not_found(format!("Command file not found: {}", path)));
+    }
+
+    if !path.ends_with(".md") {
+        return Err(AppError::invalid_input("Can only edit .md commands"));
+    }
+
+    let raw_frontmatter = frontmatter
+        .as_deref()
+        .map(str::trim)
+        .filter(|fm| !fm.is_empty());
+    if raw_frontmatter.is_some_and(|fm| fm.contains("---")) {
+        return Err(AppError::invalid_input(
+            "Frontmatter cannot contain '---' delimiters",
+        ));
+    }
+
+    let new_content = match raw_frontmatter {
+        Some(fm) => format!("---\n{}\n---\n\n{}", fm, body.trim_start()),
+        None => body,
+    };
+
+    let (new_fields, _, new_body) = parse_frontmatter(&new_content);
+    validate_command_frontmatter(&new_fields).map_err(AppError::invalid_input)?;
+
+    let old_content = fs::read_to_string(&file_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
+    if old_content == new_content {
+        return Ok(());
+    }
+    let (old_fields, _, old_body) = parse_frontmatter(&old_content);
+
+    security::atomic_write_string(&file_path, &new_content)?;
+
+    // Append a changelog entry describing what changed
+    let summary = describe_content_changes(&old_fields, &new_fields, &old_body, &new_body);
+    let changelog_path = file_path.with_extension("changelog");
+    let mut changelog = fs::read_to_string(&changelog_path).unwrap_or_default();
+    if !changelog.is_empty() && !changelog.ends_with('\n') {
+        changelog.push('\n');
+    }
+    changelog.push_str(&format!(
+        "## {}\n\n{}\n\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        summary
+    ));
+    security::atomic_write_string(&changelog_path, &changelog)?;
+
     Ok(())
 }
 
+/// List archived versions of a command (from versions/), oldest first, plus the current file
+#[tauri::command]
+pub fn list_command_versions(path: String) -> AppResult<Vec<CommandVersion>> {
+    let (dir, base) = resolve_command_base(Path::new(&path)).map_err(AppError::invalid_input)?;
+    let versions_dir = dir.join("versions");
+    let prefix = format!("{}.v", base);
+
+    let mut versions = Vec::new();
+    if versions_dir.exists() {
+        let entries = fs::read_dir(&versions_dir)
+            .map_err(|e| AppError::io("Failed to read directory", &e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(version) = file_name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".md.archived"))
+            else {
+                continue;
+            };
+
+            versions.push(CommandVersion {
+                version: version.to_string(),
+                path: entry.path().to_string_lossy().to_string(),
+                last_modified: modified_secs(&entry.path()),
+                is_current: false,
+            });
+        }
+    }
+
+    versions.sort_by(|a, b| {
+        let parse = |s: &str| -> Vec<u32> { s.split('.').filter_map(|p| p.parse().ok()).collect() };
+        parse(&a.version)
+            .cmp(&parse(&b.version))
+            .then_with(|| a.version.cmp(&b.version))
+    });
+
+    let current = dir.join(format!("{}.md", base));
+    if current.exists() {
+        versions.push(CommandVersion {
+            version: "current".to_string(),
+            path: current.to_string_lossy().to_string(),
+            last_modified: modified_secs(&current),
+            is_current: true,
+        });
+    }
+
+    Ok(versions)
+}
+
+/// Unified diff between two versions of a command ("current" refers to the live file)
+#[tauri::command]
+pub fn diff_command_versions(path: String, v1: String, v2: String) -> AppResult<String> {
+    let (dir, base) = resolve_command_base(Path::new(&path)).map_err(AppError::invalid_input)?;
+
+    let read_version = |version: &str| -> AppResult<(String, String)> {
+        let file = if version == "current" {
+            dir.join(format!("{}.md", base))
+        } else {
+            if version.is_empty() || version.contains(['/', '\\']) || version.contains("..") {
+                return Err(AppError::invalid_input(format!("Invalid version: {}", version)));
+            }
+            dir.join("versions")
+                .join(format!("{}.v{}.md.archived", base, version))
+        };
+        let content = fs::read_to_string(&file).map_err(|_| {
+            AppError::not_found(format!("Version '{}' of {} not found", version, base))
+        })?;
+        let label = if version == "current" {
+            format!("{}.md", base)
+        } else {
+            format!("{}.v{}.md", base, version)
+        };
+        Ok((label, content))
+    };
+
+    let (old_label, old_content) = read_version(&v1)?;
+    let (new_label, new_content) = read_version(&v2)?;
+
+    Ok(unified_diff(&old_content, &new_content, &old_label, &new_label))
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Built-in Claude Code tools that may appear in allowed-tools
+const KNOWN_TOOLS: &[&str] = &[
+    "Agent",
+    "AskUserQuestion",
+    "Bash",
+    "BashOutput",
+    "Edit",
+    "ExitPlanMode",
+    "Glob",
+    "Grep",
+    "KillShell",
+    "LS",
+    "MultiEdit",
+    "NotebookEdit",
+    "NotebookRead",
+    "Read",
+    "SlashCommand",
+    "Skill",
+    "Task",
+    "TodoRead",
+    "TodoWrite",
+    "WebFetch",
+    "WebSearch",
+    "Write",
+];
+
+/// Validate the well-known frontmatter fields of a command
+pub fn validate_command_frontmatter(fields: &HashMap<String, String>) -> Result<(), String> {
+    if let Some(tools) = fields.get("allowed-tools") {
+        validate_allowed_tools(tools)?;
+    }
+
+    if let Some(description) = fields.get("description") {
+        let description = description.trim();
+        if description.is_empty() {
+            return Err("description cannot be empty".to_string());
+        }
+        if description == "|" || description == ">" {
+            return Err("description must be a single line".to_string());
+        }
+        if description.chars().count() > 300 {
+            return Err("description must be at most 300 characters".to_string());
+        }
+    }
+
+    if let Some(hint) = fields.get("argument-hint") {
+        validate_argument_hint(hint)?;
+    }
+
+    Ok(())
+}
+
+/// Check each allowed-tools entry, e.g. `Read, Bash(git diff:*), mcp__github__get_issue`
+pub fn validate_allowed_tools(value: &str) -> Result<(), String> {
+    let entries = split_allowed_tools(value);
+    if entries.is_empty() {
+        return Err("allowed-tools cannot be empty".to_string());
+    }
+
+    for entry in entries {
+        let tool_name = match entry.find('(') {
+            Some(idx) => {
+                if !entry.ends_with(')') {
+                    return Err(format!(
+                        "Malformed tool pattern in allowed-tools: {}",
+                        entry
+                    ));
+                }
+                &entry[..idx]
+            }
+            None => entry.as_str(),
+        };
+
+        let is_mcp = tool_name.starts_with("mcp__") && tool_name.len() > "mcp__".len();
+        if !is_mcp && !KNOWN_TOOLS.contains(&tool_name) {
+            return Err(format!("Unknown tool in allowed-tools: {}", tool_name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Split allowed-tools on top-level commas, keeping patterns like `Bash(a, b)` intact
+pub fn split_allowed_tools(value: &str) -> Vec<String> {
+    let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                entries.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    entries.push(current);
+
+    entries
+        .into_iter()
+        .map(|e| e.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// argument-hint must be a single line with balanced [] and <> groups
+fn validate_argument_hint(hint: &str) -> Result<(), String> {
+    if hint.trim() == "|" || hint.trim() == ">" {
+        return Err("argument-hint must be a single line".to_string());
+    }
+
+    let mut stack = Vec::new();
+    for c in hint.chars() {
+        match c {
+            '[' | '<' => stack.push(c),
+            ']' | '>' => {
+                let expected = if c == ']' { '[' } else { '<' };
+                if stack.pop() != Some(expected) {
+                    return Err(format!("Unbalanced brackets in argument-hint: {}", hint));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !stack.is_empty() {
+        return Err(format!("Unbalanced brackets in argument-hint: {}", hint));
+    }
+
+    Ok(())
+}
+
+/// Summarize frontmatter/body differences as changelog bullet points
+fn describe_content_changes(
+    old_fields: &HashMap<String, String>,
+    new_fields: &HashMap<String, String>,
+    old_body: &str,
+    new_body: &str,
+) -> String {
+    let mut keys: Vec<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut lines = Vec::new();
+    for key in keys {
+        match (old_fields.get(key), new_fields.get(key)) {
+            (None, Some(v)) => lines.push(format!("- Added `{}`: {}", key, v)),
+            (Some(_), None) => lines.push(format!("- Removed `{}`", key)),
+            (Some(old), Some(new)) if old != new => {
+                lines.push(format!("- Changed `{}`: {} -> {}", key, old, new));
+            }
+            _ => {}
+        }
+    }
+
+    if old_body.trim() != new_body.trim() {
+        let old_lines = old_body.lines().count();
+        let new_lines = new_body.lines().count();
+        lines.push(format!(
+            "- Edited prompt body ({} -> {} lines)",
+            old_lines, new_lines
+        ));
+    }
+
+    if lines.is_empty() {
+        "- Reformatted content".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
 // ============================================================================
 // Migration Functions
 // ============================================================================
@@ -496,12 +991,114 @@ fn migrate_orphan_changelogs_recursive(
 // Helper Functions
 // ============================================================================
 
+/// Recursively collect every file in a namespace directory (skipping hidden dirs)
+fn collect_namespace_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let hidden = path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+            if !hidden {
+                collect_namespace_files(&path, files)?;
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Remove a directory tree bottom-up, leaving any directory that still has content
+fn remove_empty_dirs(dir: &Path) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                remove_empty_dirs(&entry.path());
+            }
+        }
+    }
+    let _ = fs::remove_dir(dir);
+}
+
+/// Fill usage_count/last_used from the command-stats index, counting the
+/// command's own name plus every alias it was previously known by
+fn attach_usage_stats(commands: &mut [LocalCommand]) {
+    let usage = load_command_usage();
+    if !usage.is_empty() {
+        add_usage(commands, &usage);
+    }
+}
+
+fn add_usage(commands: &mut [LocalCommand], usage: &HashMap<String, (usize, Option<i64>)>) {
+    for cmd in commands.iter_mut() {
+        let mut keys: Vec<String> = std::iter::once(&cmd.name)
+            .chain(cmd.aliases.iter())
+            .map(|n| normalize_command_key(n))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        for key in keys {
+            if let Some((count, last_used)) = usage.get(&key) {
+                cmd.usage_count += count;
+                cmd.last_used = cmd.last_used.max(*last_used);
+            }
+        }
+    }
+}
+
+/// Find the .claude directory that owns a command file: ~/.claude for user commands,
+/// or the nearest `<project>/.claude` ancestor for project-scoped commands
+pub fn resolve_claude_dir_for(path: &Path) -> PathBuf {
+    let user_dir = security::get_claude_dir_or_fallback();
+    if path.starts_with(&user_dir) {
+        return user_dir;
+    }
+
+    path.ancestors()
+        .find(|p| p.file_name().is_some_and(|n| n == ".claude"))
+        .map_or(user_dir, Path::to_path_buf)
+}
+
+/// Resolve a command file or one of its archived versions to (directory, base name)
+fn resolve_command_base(path: &Path) -> Result<(PathBuf, String), String> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or("Cannot get filename")?;
+
+    if let Some(stem) = file_name.strip_suffix(".md.archived") {
+        // versions/<base>.v<version>.md.archived
+        let base = stem.rfind(".v").map_or(stem, |idx| &stem[..idx]);
+        let dir = path
+            .parent()
+            .and_then(|p| p.parent())
+            .ok_or("Cannot get command directory")?;
+        Ok((dir.to_path_buf(), base.to_string()))
+    } else if let Some(base) = file_name.strip_suffix(".md") {
+        let dir = path.parent().ok_or("Cannot get command directory")?;
+        Ok((dir.to_path_buf(), base.to_string()))
+    } else {
+        Err(format!("Not a command file: {}", path.display()))
+    }
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs())
+}
+
 /// Collect commands from a directory with a given status
 fn collect_commands_from_dir(
     base_dir: &PathBuf,
     current_dir: &PathBuf,
     commands: &mut Vec<LocalCommand>,
     status: &str,
+    scope: &str,
 ) -> Result<(), String> {
     for entry in fs::read_dir(current_dir).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
@@ -511,7 +1108,7 @@ fn collect_commands_from_dir(
             // Skip hidden directories
             let name = path.file_name().unwrap_or_default().to_string_lossy();
             if !name.starts_with('.') {
-                collect_commands_from_dir(base_dir, &path, commands, status)?;
+                collect_commands_from_dir(base_dir, &path, commands, status, scope)?;
             }
         } else {
             let filename = path.file_name().unwrap_or_default().to_string_lossy();
@@ -581,6 +1178,9 @@ fn collect_commands_from_dir(
                     changelog,
                     aliases,
                     frontmatter: raw_frontmatter,
+                    scope: scope.to_string(),
+                    usage_count: 0,
+                    last_used: None,
                 });
             }
         }
@@ -691,7 +1291,7 @@ fn update_aliases_on_rename(content: &str, old_name: &str, new_name: &str) -> St
             })
             .collect();
 
-        format!("---{}---{}", new_frontmatter.join("\n"), body)
+        format!("---{}\n---{}", new_frontmatter.join("\n"), body)
     } else if !new_aliases.is_empty() {
         // No aliases field, add it
         let new_frontmatter = format!(
@@ -699,7 +1299,7 @@ fn update_aliases_on_rename(content: &str, old_name: &str, new_name: &str) -> St
             frontmatter.trim_end(),
             new_aliases.join(", ")
         );
-        format!("---{}---{}", new_frontmatter, body)
+        format!("---{}\n---{}", new_frontmatter, body)
     } else {
         content.to_string()
     }
@@ -765,3 +1365,174 @@ pub fn update_frontmatter_field(content: &str, key: &str, value: &str) -> String
         format!("---\n{}: {}\n---\n\n{}", key, value, content)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str, aliases: &[&str]) -> LocalCommand {
+        LocalCommand {
+            name: name.to_string(),
+            path: String::new(),
+            description: None,
+            allowed_tools: None,
+            argument_hint: None,
+            content: String::new(),
+            version: None,
+            status: "active".to_string(),
+            deprecated_by: None,
+            changelog: None,
+            aliases: aliases.iter().map(ToString::to_string).collect(),
+            frontmatter: None,
+            scope: "user".to_string(),
+            usage_count: 0,
+            last_used: None,
+        }
+    }
+
+    #[test]
+    fn test_add_usage_counts_aliases_once() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("command-stats.json");
+        let stats = serde_json::json!({
+            "_schema_version": 1,
+            "commands": {
+                "git:commit": { "2025-W01": 2 },
+                "git/commit": { "2025-W02": 3 },
+                "old-commit": { "2025-W01": 5 },
+            },
+            "last_used": { "git:commit": 100, "git/commit": 400, "old-commit": 250 },
+        });
+        fs::write(&path, stats.to_string()).expect("write stats");
+        let usage = crate::commands::report::load_command_usage_from(&path);
+
+        // "/git/commit" and "git:commit" are the command's own key again
+        let mut commands = vec![
+            command("/git:commit", &["/git/commit", "git:commit", "/old-commit", "old-commit"]),
+            command("/review", &[]),
+        ];
+        add_usage(&mut commands, &usage);
+        assert_eq!(commands[0].usage_count, 10);
+        assert_eq!(commands[0].last_used, Some(400));
+        assert_eq!(commands[1].usage_count, 0);
+        assert_eq!(commands[1].last_used, None);
+    }
+
+    #[test]
+    fn test_validate_allowed_tools() {
+        assert!(validate_allowed_tools("Read, Write, Bash(git diff:*)").is_ok());
+        assert!(validate_allowed_tools("[Read, Bash(git add, git commit)]").is_ok());
+        assert!(validate_allowed_tools("mcp__github__get_issue").is_ok());
+        assert!(validate_allowed_tools("Read, Fly").is_err());
+        assert!(validate_allowed_tools("Bash(git diff").is_err());
+        assert!(validate_allowed_tools("mcp__").is_err());
+        assert!(validate_allowed_tools("").is_err());
+    }
+
+    #[test]
+    fn test_validate_argument_hint() {
+        assert!(validate_argument_hint("[message] <file>").is_ok());
+        assert!(validate_argument_hint("[add|remove] [tag-id]").is_ok());
+        assert!(validate_argument_hint("[message").is_err());
+        assert!(validate_argument_hint("<file]").is_err());
+        assert!(validate_argument_hint("|").is_err());
+    }
+
+    #[test]
+    fn test_describe_content_changes() {
+        let old = HashMap::from([("description".to_string(), "old".to_string())]);
+        let new = HashMap::from([
+            ("description".to_string(), "new".to_string()),
+            ("version".to_string(), "2".to_string()),
+        ]);
+
+        let summary = describe_content_changes(&old, &new, "a", "a\nb");
+        assert!(summary.contains("- Changed `description`: old -> new"));
+        assert!(summary.contains("- Added `version`: 2"));
+        assert!(summary.contains("- Edited prompt body (1 -> 2 lines)"));
+    }
+
+    #[test]
+    fn test_command_versions_and_diff() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let cmd = temp.path().join("review.md");
+        let versions = temp.path().join("versions");
+        fs::create_dir_all(&versions).expect("create versions dir");
+        fs::write(versions.join("review.v1.md.archived"), "line a\nline b\n").expect("write v1");
+        fs::write(versions.join("review.v10.md.archived"), "line a\n").expect("write v10");
+        fs::write(versions.join("review.v2.md.archived"), "line a\nline c\n").expect("write v2");
+        fs::write(versions.join("other.v1.md.archived"), "x\n").expect("write other");
+        fs::write(&cmd, "line a\nline d\n").expect("write current");
+
+        let path = cmd.to_string_lossy().to_string();
+        let listed = list_command_versions(path.clone()).expect("list versions");
+        let labels: Vec<&str> = listed.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(labels, vec!["1", "2", "10", "current"]);
+        assert!(listed[3].is_current);
+
+        let archived = versions.join("review.v2.md.archived");
+        let (dir, base) = resolve_command_base(&archived).expect("resolve archived");
+        assert_eq!(dir, temp.path());
+        assert_eq!(base, "review");
+
+        let diff = diff_command_versions(path.clone(), "1".into(), "current".into())
+            .expect("diff versions");
+        assert!(diff.contains("--- review.v1.md"));
+        assert!(diff.contains("+++ review.md"));
+        assert!(diff.contains("-line b"));
+        assert!(diff.contains("+line d"));
+
+        assert!(diff_command_versions(path.clone(), "3".into(), "current".into()).is_err());
+        assert!(diff_command_versions(path, "../x".into(), "current".into()).is_err());
+    }
+
+    #[test]
+    fn test_project_command_deprecate_and_restore() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let claude_dir = temp.path().join("proj").join(".claude");
+        let commands_dir = claude_dir.join("commands").join("ops");
+        fs::create_dir_all(&commands_dir).expect("create commands dir");
+        let cmd = commands_dir.join("deploy.md");
+        fs::write(&cmd, "---\ndescription: Deploy\n---\n\nRun it").expect("write command");
+
+        assert_eq!(resolve_claude_dir_for(&cmd), claude_dir);
+
+        let deprecated = deprecate_command(cmd.to_string_lossy().to_string(), None, None)
+            .expect("deprecate project command");
+        let expected = claude_dir.join(".commands/archived/ops/deploy.md");
+        assert_eq!(PathBuf::from(&deprecated), expected);
+        assert!(!cmd.exists());
+
+        let restored = restore_command(deprecated).expect("restore project command");
+        assert_eq!(PathBuf::from(restored), cmd);
+        assert!(cmd.exists());
+    }
+
+    #[test]
+    fn test_move_command_namespace() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let commands_dir = temp.path().join("commands");
+        let old_dir = commands_dir.join("lovstudio").join("repo");
+        fs::create_dir_all(old_dir.join("versions")).expect("create dirs");
+        fs::write(old_dir.join("takeover.md"), "---\ndescription: Take over\n---\n\nBody")
+            .expect("write command");
+        fs::write(old_dir.join("takeover.changelog"), "## v1\n").expect("write changelog");
+        fs::write(old_dir.join("versions/takeover.v1.md.archived"), "old").expect("write version");
+
+        let moved = move_namespace_in(&commands_dir, "/lovstudio", "/studio").expect("move");
+        let new_cmd = commands_dir.join("studio/repo/takeover.md");
+        assert_eq!(moved, vec![new_cmd.to_string_lossy().to_string()]);
+
+        let content = fs::read_to_string(&new_cmd).expect("read moved command");
+        assert!(content.contains("aliases: \"/lovstudio/repo/takeover\"\n---"));
+        assert!(commands_dir.join("studio/repo/takeover.changelog").exists());
+        assert!(commands_dir.join("studio/repo/versions/takeover.v1.md.archived").exists());
+        assert!(!commands_dir.join("lovstudio").exists());
+
+        // Moving back onto an occupied namespace is refused
+        fs::create_dir_all(commands_dir.join("lovstudio/repo")).expect("recreate dir");
+        fs::write(commands_dir.join("lovstudio/repo/takeover.md"), "x").expect("write clash");
+        assert!(move_namespace_in(&commands_dir, "studio", "lovstudio").is_err());
+        assert!(move_namespace_in(&commands_dir, "studio", "studio/sub").is_err());
+    }
+}
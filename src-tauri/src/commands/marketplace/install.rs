@@ -1,11 +1,14 @@
 /**
- * [INPUT]: 依赖 std::fs, serde_json, crate::security
+ * [INPUT]: 依赖 std::fs, serde_json, crate::security, commands::hook_logs (Hook 日志包装), services::claude_json_history (标注 MCP 安装来源)
  * [OUTPUT]: 对外提供 install_command_template, install_mcp_template, uninstall_mcp_template, check_mcp_installed, install_hook_template, install_setting_template 命令
  * [POS]: marketplace/ 模块的安装/卸载命令
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use crate::commands::hook_logs::{ensure_hook_logger, wrap_hook_config};
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
+use crate::services::claude_json_history;
 use std::fs;
 
 // ============================================================================
@@ -13,12 +16,12 @@ use std::fs;
 // ============================================================================
 
 #[tauri::command]
-pub fn install_command_template(name: String, content: String) -> Result<String, String> {
+pub fn install_command_template(name: String, content: String) -> AppResult<String> {
     let commands_dir = security::get_claude_dir_or_fallback().join("commands");
-    fs::create_dir_all(&commands_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&commands_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
 
     let file_path = commands_dir.join(format!("{}.md", name));
-    fs::write(&file_path, content).map_err(|e| e.to_string())?;
+    fs::write(&file_path, content).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     Ok(file_path.to_string_lossy().to_string())
 }
@@ -28,13 +31,13 @@ pub fn install_command_template(name: String, content: String) -> Result<String,
 // ============================================================================
 
 #[tauri::command]
-pub fn install_mcp_template(name: String, config: String) -> Result<String, String> {
+pub fn install_mcp_template(name: String, config: String) -> AppResult<String> {
     // MCP servers are stored in ~/.claude.json (not ~/.claude/settings.json)
     let claude_json_path = security::get_claude_json_path_or_fallback();
 
     // Parse the MCP config
-    let mcp_config: serde_json::Value =
-        serde_json::from_str(&config).map_err(|e| e.to_string())?;
+    let mcp_config: serde_json::Value = serde_json::from_str(&config)
+        .map_err(|e| AppError::invalid_input(format!("Invalid MCP config: {}", e)))?;
 
     // Helper to check if a value looks like an actual MCP server config
     // (has type, url, or command field)
@@ -74,7 +77,8 @@ pub fn install_mcp_template(name: String, config: String) -> Result<String, Stri
 
     // Read existing ~/.claude.json or create new
     let mut claude_json: serde_json::Value = if claude_json_path.exists() {
-        let content = fs::read_to_string(&claude_json_path).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&claude_json_path)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
         serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
     } else {
         serde_json::json!({})
@@ -109,36 +113,40 @@ pub fn install_mcp_template(name: String, config: String) -> Result<String, Stri
 
     // Write back
     let output = serde_json::to_string_pretty(&claude_json).map_err(|e| e.to_string())?;
-    fs::write(&claude_json_path, output).map_err(|e| e.to_string())?;
+    fs::write(&claude_json_path, output).map_err(|e| AppError::io("Failed to write file", &e))?;
+    claude_json_history::record_lovcode_change(format!("Installed MCP server {}", name));
 
     Ok(format!("Installed MCP: {}", name))
 }
 
 #[tauri::command]
-pub fn uninstall_mcp_template(name: String) -> Result<String, String> {
+pub fn uninstall_mcp_template(name: String) -> AppResult<String> {
     let claude_json_path = security::get_claude_json_path_or_fallback();
 
     if !claude_json_path.exists() {
-        return Err("No MCP configuration found".to_string());
+        return Err(AppError::not_found("No MCP configuration found"));
     }
 
-    let content = fs::read_to_string(&claude_json_path).map_err(|e| e.to_string())?;
-    let mut claude_json: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&claude_json_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
+    let mut claude_json: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+        AppError::new(ErrorCode::Parse, format!("Failed to parse ~/.claude.json: {}", e))
+    })?;
 
     if let Some(mcp_servers) = claude_json
         .get_mut("mcpServers")
         .and_then(|v| v.as_object_mut())
     {
         if mcp_servers.remove(&name).is_none() {
-            return Err(format!("MCP '{}' not found", name));
+            return Err(AppError::not_found(format!("MCP '{}' not found", name)));
         }
     } else {
-        return Err("No mcpServers found".to_string());
+        return Err(AppError::not_found("No mcpServers found"));
     }
 
     let output = serde_json::to_string_pretty(&claude_json).map_err(|e| e.to_string())?;
-    fs::write(&claude_json_path, output).map_err(|e| e.to_string())?;
+    fs::write(&claude_json_path, output).map_err(|e| AppError::io("Failed to write file", &e))?;
+    claude_json_history::record_lovcode_change(format!("Uninstalled MCP server {}", name));
 
     Ok(format!("Uninstalled MCP: {}", name))
 }
@@ -171,15 +179,20 @@ pub fn check_mcp_installed(name: String) -> bool {
 // ============================================================================
 
 #[tauri::command]
-pub fn install_hook_template(name: String, config: String) -> Result<String, String> {
+pub fn install_hook_template(name: String, config: String) -> AppResult<String> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
 
     // Parse the hook config (should be an object with event type as key)
-    let hook_config: serde_json::Value =
-        serde_json::from_str(&config).map_err(|e| e.to_string())?;
+    let mut hook_config: serde_json::Value = serde_json::from_str(&config)
+        .map_err(|e| AppError::invalid_input(format!("Invalid hook config: {}", e)))?;
+
+    // Log every run to the hook log (see list_hook_events)
+    let logger = ensure_hook_logger().map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    wrap_hook_config(&mut hook_config, &name, &logger);
 
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
         serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
     } else {
         serde_json::json!({})
@@ -210,7 +223,7 @@ pub fn install_hook_template(name: String, config: String) -> Result<String, Str
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     Ok(format!("Installed hook: {}", name))
 }
@@ -220,15 +233,16 @@ pub fn install_hook_template(name: String, config: String) -> Result<String, Str
 // ============================================================================
 
 #[tauri::command]
-pub fn install_setting_template(config: String) -> Result<String, String> {
+pub fn install_setting_template(config: String) -> AppResult<String> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
 
     // Parse the setting config
-    let new_settings: serde_json::Value =
-        serde_json::from_str(&config).map_err(|e| e.to_string())?;
+    let new_settings: serde_json::Value = serde_json::from_str(&config)
+        .map_err(|e| AppError::invalid_input(format!("Invalid setting config: {}", e)))?;
 
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
         serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
     } else {
         serde_json::json!({})
@@ -244,7 +258,7 @@ pub fn install_setting_template(config: String) -> Result<String, String> {
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     Ok("Settings updated".to_string())
 }
@@ -22,6 +22,7 @@ pub use statusline::{
 };
 pub use types::{SourceInfo, TemplateComponent, TemplatesCatalog};
 
+use crate::errors::AppResult;
 use loader::{
     load_community_catalog, load_personal_statuslines, load_plugin_directory, load_single_plugin,
 };
@@ -33,7 +34,7 @@ use types::PLUGIN_SOURCES;
 // ============================================================================
 
 #[tauri::command]
-pub fn get_templates_catalog(app_handle: tauri::AppHandle) -> Result<TemplatesCatalog, String> {
+pub fn get_templates_catalog(app_handle: tauri::AppHandle) -> AppResult<TemplatesCatalog> {
     let mut all_components: Vec<TemplateComponent> = Vec::new();
     let mut source_counts: HashMap<String, usize> = HashMap::new();
 
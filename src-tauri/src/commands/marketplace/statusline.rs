@@ -5,6 +5,7 @@
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
 use std::fs;
 
@@ -13,11 +14,14 @@ use std::fs;
 // ============================================================================
 
 #[tauri::command]
-pub fn update_settings_statusline(statusline: serde_json::Value) -> Result<(), String> {
+pub fn update_settings_statusline(statusline: serde_json::Value) -> AppResult<()> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&content).map_err(|e| e.to_string())?
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            AppError::new(ErrorCode::Parse, format!("Failed to parse settings.json: {}", e))
+        })?
     } else {
         serde_json::json!({})
     };
@@ -25,27 +29,29 @@ pub fn update_settings_statusline(statusline: serde_json::Value) -> Result<(), S
     settings["statusLine"] = statusline;
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output).map_err(|e| AppError::io("Failed to write file", &e))?;
     Ok(())
 }
 
 #[tauri::command]
-pub fn remove_settings_statusline() -> Result<(), String> {
+pub fn remove_settings_statusline() -> AppResult<()> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     if !settings_path.exists() {
         return Ok(());
     }
 
-    let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
-    let mut settings: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&settings_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
+    let mut settings: serde_json::Value = serde_json::from_str(&content).map_err(|e| {
+        AppError::new(ErrorCode::Parse, format!("Failed to parse settings.json: {}", e))
+    })?;
 
     if let Some(obj) = settings.as_object_mut() {
         obj.remove("statusLine");
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output).map_err(|e| AppError::io("Failed to write file", &e))?;
     Ok(())
 }
 
@@ -54,19 +60,20 @@ pub fn remove_settings_statusline() -> Result<(), String> {
 // ============================================================================
 
 #[tauri::command]
-pub fn write_statusline_script(content: String) -> Result<String, String> {
+pub fn write_statusline_script(content: String) -> AppResult<String> {
     let script_path = security::get_claude_dir_or_fallback().join("statusline.sh");
-    fs::write(&script_path, &content).map_err(|e| e.to_string())?;
+    fs::write(&script_path, &content).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     // Make executable on Unix
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let mut perms = fs::metadata(&script_path)
-            .map_err(|e| e.to_string())?
+            .map_err(|e| AppError::io("Failed to read file", &e))?
             .permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&script_path, perms).map_err(|e| e.to_string())?;
+        fs::set_permissions(&script_path, perms)
+            .map_err(|e| AppError::io("Failed to set permissions", &e))?;
     }
 
     Ok(script_path.to_string_lossy().to_string())
@@ -78,22 +85,24 @@ pub fn write_statusline_script(content: String) -> Result<String, String> {
 
 /// Install statusline template to ~/.lovstudio/lovcode/statusline/{name}.sh
 #[tauri::command]
-pub fn install_statusline_template(name: String, content: String) -> Result<String, String> {
+pub fn install_statusline_template(name: String, content: String) -> AppResult<String> {
     let statusline_dir = security::get_lovstudio_dir_or_fallback().join("statusline");
-    fs::create_dir_all(&statusline_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&statusline_dir)
+        .map_err(|e| AppError::io("Failed to create directory", &e))?;
 
     let script_path = statusline_dir.join(format!("{}.sh", name));
-    fs::write(&script_path, &content).map_err(|e| e.to_string())?;
+    fs::write(&script_path, &content).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     // Make executable on Unix
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let mut perms = fs::metadata(&script_path)
-            .map_err(|e| e.to_string())?
+            .map_err(|e| AppError::io("Failed to read file", &e))?
             .permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&script_path, perms).map_err(|e| e.to_string())?;
+        fs::set_permissions(&script_path, perms)
+            .map_err(|e| AppError::io("Failed to set permissions", &e))?;
     }
 
     Ok(script_path.to_string_lossy().to_string())
@@ -102,41 +111,45 @@ pub fn install_statusline_template(name: String, content: String) -> Result<Stri
 /// Apply statusline: copy from ~/.lovstudio/lovcode/statusline/{name}.sh to ~/.claude/statusline.sh
 /// If ~/.claude/statusline.sh exists and is not already installed, backup to ~/.lovstudio/lovcode/statusline/_previous.sh
 #[tauri::command]
-pub fn apply_statusline(name: String) -> Result<String, String> {
+pub fn apply_statusline(name: String) -> AppResult<String> {
     let source_path = security::get_lovstudio_dir_or_fallback()
         .join("statusline")
         .join(format!("{}.sh", name));
     if !source_path.exists() {
-        return Err(format!("Statusline template not found: {}", name));
+        return Err(AppError::not_found(format!("Statusline template not found: {}", name)));
     }
 
     let target_path = security::get_claude_dir_or_fallback().join("statusline.sh");
     let backup_dir = security::get_lovstudio_dir_or_fallback().join("statusline");
-    fs::create_dir_all(&backup_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&backup_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
 
     // Backup existing statusline.sh if it exists and differs from source
     if target_path.exists() {
         let existing_content = fs::read_to_string(&target_path).unwrap_or_default();
-        let new_content = fs::read_to_string(&source_path).map_err(|e| e.to_string())?;
+        let new_content = fs::read_to_string(&source_path)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
 
         if existing_content != new_content {
             let backup_path = backup_dir.join("_previous.sh");
-            fs::copy(&target_path, &backup_path).map_err(|e| e.to_string())?;
+            fs::copy(&target_path, &backup_path)
+                .map_err(|e| AppError::io("Failed to copy file", &e))?;
         }
     }
 
-    let content = fs::read_to_string(&source_path).map_err(|e| e.to_string())?;
-    fs::write(&target_path, &content).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&source_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
+    fs::write(&target_path, &content).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     // Make executable on Unix
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let mut perms = fs::metadata(&target_path)
-            .map_err(|e| e.to_string())?
+            .map_err(|e| AppError::io("Failed to read file", &e))?
             .permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&target_path, perms).map_err(|e| e.to_string())?;
+        fs::set_permissions(&target_path, perms)
+            .map_err(|e| AppError::io("Failed to set permissions", &e))?;
     }
 
     Ok(target_path.to_string_lossy().to_string())
@@ -144,27 +157,29 @@ pub fn apply_statusline(name: String) -> Result<String, String> {
 
 /// Restore previous statusline from backup
 #[tauri::command]
-pub fn restore_previous_statusline() -> Result<String, String> {
+pub fn restore_previous_statusline() -> AppResult<String> {
     let backup_path = security::get_lovstudio_dir_or_fallback()
         .join("statusline")
         .join("_previous.sh");
     if !backup_path.exists() {
-        return Err("No previous statusline to restore".to_string());
+        return Err(AppError::not_found("No previous statusline to restore"));
     }
 
-    let content = fs::read_to_string(&backup_path).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&backup_path)
+        .map_err(|e| AppError::io("Failed to read file", &e))?;
     let target_path = security::get_claude_dir_or_fallback().join("statusline.sh");
-    fs::write(&target_path, &content).map_err(|e| e.to_string())?;
+    fs::write(&target_path, &content).map_err(|e| AppError::io("Failed to write file", &e))?;
 
     // Make executable on Unix
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let mut perms = fs::metadata(&target_path)
-            .map_err(|e| e.to_string())?
+            .map_err(|e| AppError::io("Failed to read file", &e))?
             .permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&target_path, perms).map_err(|e| e.to_string())?;
+        fs::set_permissions(&target_path, perms)
+            .map_err(|e| AppError::io("Failed to set permissions", &e))?;
     }
 
     // Remove backup after restore
@@ -184,12 +199,12 @@ pub fn has_previous_statusline() -> bool {
 
 /// Remove installed statusline template
 #[tauri::command]
-pub fn remove_statusline_template(name: String) -> Result<(), String> {
+pub fn remove_statusline_template(name: String) -> AppResult<()> {
     let script_path = security::get_lovstudio_dir_or_fallback()
         .join("statusline")
         .join(format!("{}.sh", name));
     if script_path.exists() {
-        fs::remove_file(&script_path).map_err(|e| e.to_string())?;
+        fs::remove_file(&script_path).map_err(|e| AppError::io("Failed to delete file", &e))?;
     }
     Ok(())
 }
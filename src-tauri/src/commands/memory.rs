@@ -0,0 +1,304 @@
+/**
+ * [INPUT]: 依赖 std::fs, std::path, serde, chrono, crate::security, crate::commands::projects::encode_project_path
+ * [OUTPUT]: 对外提供 get_memory, update_memory, append_memory_entry 命令
+ * [POS]: commands/ 模块的记忆文件 (CLAUDE.md / CLAUDE.local.md) 编辑中心
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::projects::encode_project_path;
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemoryFile {
+    pub scope: String, // "user" | "project" | "local"
+    pub path: String,
+    pub exists: bool,
+    pub content: String,
+    pub last_modified: u64,
+    /// Backup written before the last modification, if any
+    pub backup_path: Option<String>,
+}
+
+/// Backups kept per memory file
+const MAX_MEMORY_BACKUPS: usize = 20;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// user    -> ~/.claude/CLAUDE.md
+/// project -> <project>/CLAUDE.md
+/// local   -> <project>/CLAUDE.local.md
+fn resolve_memory_path(scope: &str, project_path: Option<&str>) -> AppResult<PathBuf> {
+    if scope == "user" {
+        let claude_dir = security::get_claude_dir()?;
+        return Ok(claude_dir.join("CLAUDE.md"));
+    }
+
+    let project = project_path.ok_or_else(|| {
+        AppError::invalid_input(format!("project_path is required for scope '{}'", scope))
+    })?;
+    security::validate_decoded_path(project)?;
+
+    match scope {
+        "project" => Ok(Path::new(project).join("CLAUDE.md")),
+        "local" => Ok(Path::new(project).join("CLAUDE.local.md")),
+        _ => Err(AppError::invalid_input(format!("Unknown memory scope: {}", scope))),
+    }
+}
+
+/// ~/.lovstudio/lovcode/memory-backups/<scope>[-<encoded project>]/
+fn backup_dir_for(scope: &str, project_path: Option<&str>) -> PathBuf {
+    let key = match project_path {
+        Some(p) if scope != "user" => format!("{}{}", scope, encode_project_path(p)),
+        _ => scope.to_string(),
+    };
+    security::get_lovstudio_dir_or_fallback()
+        .join("memory-backups")
+        .join(key)
+}
+
+/// Copy the current file into `backup_dir` and prune old backups
+fn backup_memory_file(file: &Path, backup_dir: &Path) -> Result<Option<PathBuf>, String> {
+    if !file.exists() {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(backup_dir).map_err(|e| e.to_string())?;
+    let backup = backup_dir.join(format!(
+        "{}.md",
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    fs::copy(file, &backup).map_err(|e| format!("Failed to back up memory file: {}", e))?;
+
+    // Timestamped names sort chronologically
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_MEMORY_BACKUPS);
+    for old in backups.into_iter().take(excess) {
+        let _ = fs::remove_file(old);
+    }
+
+    Ok(Some(backup))
+}
+
+fn read_memory_file(scope: &str, path: &Path, backup_path: Option<PathBuf>) -> MemoryFile {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let last_modified = fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+
+    MemoryFile {
+        scope: scope.to_string(),
+        path: path.to_string_lossy().to_string(),
+        exists: path.exists(),
+        content,
+        last_modified,
+        backup_path: backup_path.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+fn write_memory(
+    scope: &str,
+    project_path: Option<&str>,
+    path: &Path,
+    content: &str,
+) -> AppResult<MemoryFile> {
+    let backup = backup_memory_file(path, &backup_dir_for(scope, project_path))
+        .map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    security::atomic_write_string(&path.to_path_buf(), content)?;
+    Ok(read_memory_file(scope, path, backup))
+}
+
+fn heading_level(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 || !line[level..].starts_with(' ') {
+        return None;
+    }
+    Some((level, line[level..].trim()))
+}
+
+/// Add a bullet to `content`, at the end of `section` (created as "## section" if missing)
+/// or at the end of the file
+fn append_entry(content: &str, entry: &str, section: Option<&str>) -> String {
+    let entry = entry.trim();
+    let bullet = if entry.starts_with("- ") || entry.starts_with("* ") {
+        entry.to_string()
+    } else {
+        format!("- {}", entry)
+    };
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    let Some(section) = section.map(str::trim).filter(|s| !s.is_empty()) else {
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.push(bullet);
+        return lines.join("\n") + "\n";
+    };
+
+    let found = lines.iter().enumerate().find_map(|(i, l)| {
+        heading_level(l)
+            .filter(|(_, title)| title.eq_ignore_ascii_case(section))
+            .map(|(level, _)| (i, level))
+    });
+
+    match found {
+        Some((start, level)) => {
+            // Section ends at the next heading of the same or higher level
+            let end = lines[start + 1..]
+                .iter()
+                .position(|l| heading_level(l).is_some_and(|(lv, _)| lv <= level))
+                .map_or(lines.len(), |offset| start + 1 + offset);
+            let insert_at = (start + 1..end)
+                .rev()
+                .find(|&i| !lines[i].trim().is_empty())
+                .map_or(start + 1, |i| i + 1);
+            if insert_at == start + 1 {
+                lines.insert(insert_at, String::new());
+                lines.insert(insert_at + 1, bullet);
+            } else {
+                lines.insert(insert_at, bullet);
+            }
+        }
+        None => {
+            while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                lines.pop();
+            }
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!("## {}", section));
+            lines.push(String::new());
+            lines.push(bullet);
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Read a memory file; a missing file is returned with `exists: false`
+#[tauri::command]
+pub fn get_memory(scope: String, project_path: Option<String>) -> AppResult<MemoryFile> {
+    let path = resolve_memory_path(&scope, project_path.as_deref())?;
+    Ok(read_memory_file(&scope, &path, None))
+}
+
+/// Replace a memory file's content (the previous version is backed up first)
+#[tauri::command]
+pub fn update_memory(
+    scope: String,
+    project_path: Option<String>,
+    content: String,
+) -> AppResult<MemoryFile> {
+    let path = resolve_memory_path(&scope, project_path.as_deref())?;
+    write_memory(&scope, project_path.as_deref(), &path, &content)
+}
+
+/// Append a bullet entry, optionally under a "## section" heading
+#[tauri::command]
+pub fn append_memory_entry(
+    scope: String,
+    project_path: Option<String>,
+    entry: String,
+    section: Option<String>,
+) -> AppResult<MemoryFile> {
+    if entry.trim().is_empty() {
+        return Err(AppError::invalid_input("Memory entry cannot be empty"));
+    }
+
+    let path = resolve_memory_path(&scope, project_path.as_deref())?;
+    let current = fs::read_to_string(&path).unwrap_or_default();
+    let updated = append_entry(&current, &entry, section.as_deref());
+    write_memory(&scope, project_path.as_deref(), &path, &updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_entry_without_section() {
+        assert_eq!(append_entry("", "Use pnpm", None), "- Use pnpm\n");
+        assert_eq!(
+            append_entry("# Notes\n\n- a\n\n\n", "- b", None),
+            "# Notes\n\n- a\n- b\n"
+        );
+    }
+
+    #[test]
+    fn test_append_entry_into_existing_section() {
+        let content = "# Project\n\n## Style\n\n- tabs\n\n## Commands\n\n- make\n";
+        assert_eq!(
+            append_entry(content, "no semicolons", Some("style")),
+            "# Project\n\n## Style\n\n- tabs\n- no semicolons\n\n## Commands\n\n- make\n"
+        );
+
+        // Nested headings stay inside the section
+        let nested = "## Style\n\n### JS\n\n- x\n## Other\n";
+        assert_eq!(
+            append_entry(nested, "y", Some("Style")),
+            "## Style\n\n### JS\n\n- x\n- y\n## Other\n"
+        );
+    }
+
+    #[test]
+    fn test_append_entry_creates_section() {
+        assert_eq!(
+            append_entry("# Project\n", "run tests", Some("Workflow")),
+            "# Project\n\n## Workflow\n\n- run tests\n"
+        );
+        assert_eq!(
+            append_entry("## Empty\n", "first", Some("Empty")),
+            "## Empty\n\n- first\n"
+        );
+    }
+
+    #[test]
+    fn test_backup_pruning() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let file = temp.path().join("CLAUDE.md");
+        let backups = temp.path().join("backups");
+
+        assert!(backup_memory_file(&file, &backups).expect("backup").is_none());
+
+        fs::write(&file, "memory").expect("write memory");
+        fs::create_dir_all(&backups).expect("create backups");
+        for i in 0..MAX_MEMORY_BACKUPS + 3 {
+            fs::write(backups.join(format!("20200101-0000{:02}.000.md", i)), "old")
+                .expect("write old backup");
+        }
+        let backup = backup_memory_file(&file, &backups)
+            .expect("backup")
+            .expect("backup path");
+        assert_eq!(fs::read_to_string(&backup).expect("read backup"), "memory");
+        assert_eq!(
+            fs::read_dir(&backups).expect("read backups").count(),
+            MAX_MEMORY_BACKUPS
+        );
+        assert!(backup.exists());
+    }
+}
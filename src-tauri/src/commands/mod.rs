@@ -7,21 +7,49 @@
 // ============================================================================
 // 子模块声明
 // ============================================================================
+pub mod achievements; // 成就徽章
+pub mod activity_calendar; // 活跃度日历导出 (iCal)
 pub mod agents; // Agent 和 Skill 管理
+pub mod bookmarks; // 消息书签
+pub mod claude_binary; // Claude CLI 多安装探测
+pub mod claude_md_gen; // CLAUDE.md 草稿生成
+pub mod code_blocks; // 会话代码片段提取
+pub mod command_lint; // 命令质量检查
 pub mod context; // 上下文文件管理
+pub mod copy_history; // 剪贴板历史
 pub mod diagnostics; // 项目诊断分析
 pub mod executors; // Executor Profile 管理
+pub mod file_diff; // 通用文本 diff
+pub mod file_search; // 项目内文件名/内容搜索
+pub mod file_trash; // 删除进系统回收站
+pub mod file_tree; // 项目文件树
 pub mod files; // 文件操作
 pub mod git; // Git 操作
+pub mod history_maintenance; // history.jsonl 体检与压缩
+pub mod hook_logs; // Hook 事件日志
 pub mod hooks; // Hook 监控
+pub mod importers; // 其他 AI 工具历史导入
 pub mod knowledge; // 知识库管理
 pub mod local_commands; // 本地命令管理
 pub mod marketplace; // 模板市场
+pub mod memory; // CLAUDE.md 记忆管理
+pub mod project_setup; // 项目 Claude Code 就绪检查与一键初始化
 pub mod projects; // 项目和会话管理
+pub mod prompts; // 提示词库
 pub mod pty; // PTY 终端管理
 pub mod report; // 报告和统计
+pub mod report_export; // 报告导出
+pub mod saved_searches; // 保存的搜索与搜索历史
+pub mod session_chain; // 会话续接链 (压缩/恢复)
+pub mod session_export; // 会话导出 (笔记库 / HTML 分享页)
+pub mod session_metrics; // 会话时长与回合分析
+pub mod session_trash; // 会话回收站
 pub mod sessions; // 会话消息
 pub mod settings; // 设置管理
+pub mod settings_validation; // settings.json 校验
+pub mod sync; // 命令 Git 同步
+pub mod todos; // 会话待办/计划提取
+pub mod tool_calls; // 会话工具调用审计
 pub mod version; // Claude Code 版本管理
 pub mod workspace; // 工作区管理
 
@@ -29,26 +57,56 @@ pub mod workspace; // 工作区管理
 // 重导出所有命令
 // ============================================================================
 
-pub use agents::{get_coding_agent_info, list_coding_agents, list_local_agents, list_local_skills};
-pub use context::{get_context_files, get_project_context};
+pub use achievements::get_achievements;
+pub use activity_calendar::export_activity_ical;
+pub use agents::{
+    archive_local_agent, archive_skill, create_local_agent, create_skill, delete_local_agent,
+    delete_skill, get_coding_agent_info, list_coding_agents, list_local_agents,
+    list_local_skills, update_local_agent, validate_agent, validate_skill, AssetProblem,
+    SkillFile,
+};
+pub use claude_binary::{
+    detect_claude_installations, set_preferred_claude_binary, ClaudeInstallMethod,
+    ClaudeInstallation,
+};
+pub use claude_md_gen::{
+    generate_claude_md, ClaudeMdDraft, LanguageStat, LayoutEntry, ProjectCommand, RepoScan,
+};
+pub use command_lint::{lint_all_commands, lint_command, CommandLintWarning};
+pub use context::{get_context_files, get_expanded_context, get_project_context, ContextNode};
+pub use memory::{append_memory_entry, get_memory, update_memory, MemoryFile};
 pub use executors::{get_agent_profiles, list_executor_profiles, list_supported_agents};
+pub use file_diff::{diff_against_content, diff_files};
+pub use file_search::{
+    find_files, grep_project, FindFilesResult, GrepMatch, GrepOptions, GrepResult,
+};
+pub use file_trash::{get_permanent_delete, set_permanent_delete, trash_path};
+pub use file_tree::{get_file_tree, FileTreeNode};
 pub use files::{
-    copy_file_to_project_assets, delete_project_logo, exec_shell_command, get_file_metadata,
-    get_project_logo, list_directory, list_project_logos, read_file, read_file_base64,
-    save_project_logo, set_current_project_logo,
+    cancel_exec, copy_file_to_project_assets, delete_project_logo, exec_shell_command,
+    exec_shell_stream, get_file_metadata, get_project_logo, list_directory, list_project_logos,
+    read_file, read_file_base64, read_file_range, read_file_tail, save_project_logo,
+    set_current_project_logo, FileChunk,
 };
 pub use git::{
-    git_auto_commit, git_generate_changelog, git_get_note, git_has_changes, git_log, git_revert,
-    git_set_note,
+    git_add_worktree, git_auto_commit, git_create_branch, git_delete_branch, git_diff,
+    git_generate_changelog, git_get_note, git_has_changes, git_list_branches, git_list_worktrees,
+    git_log, git_pull, git_push, git_remove_worktree, git_revert, git_set_note, git_show,
+    git_switch_branch,
 };
 pub use knowledge::{
-    find_session_project, get_distill_dir, get_distill_watch_enabled, list_distill_documents,
-    list_reference_docs, list_reference_sources, set_distill_watch_enabled, DISTILL_WATCH_ENABLED,
+    add_reference_source, create_distill_document, delete_distill_document, delete_distill_tag,
+    distill_session, distill_sessions, find_session_project, get_distill_dir,
+    get_distill_watch_enabled, list_distill_documents,
+    list_distill_tags, list_reference_docs, list_reference_sources, refresh_reference_sources,
+    rename_distill_tag, set_distill_watch_enabled, start_reference_refresher,
+    update_distill_document, DISTILL_WATCH_ENABLED,
 };
 pub use local_commands::{
-    add_frontmatter_field, archive_command, deprecate_command, list_local_commands,
+    add_frontmatter_field, archive_command, deprecate_command, diff_command_versions,
+    list_command_versions, list_local_commands, list_project_commands, move_command_namespace,
     parse_frontmatter, rename_command, restore_command, update_command_aliases,
-    update_frontmatter_field,
+    update_command_content, update_frontmatter_field, CommandVersion,
 };
 pub use marketplace::{
     apply_statusline, check_mcp_installed, get_templates_catalog, has_previous_statusline,
@@ -62,8 +120,13 @@ pub use projects::{
     read_session_head,
 };
 pub use report::{
-    get_activity_stats, get_annual_report_2025, get_command_stats, get_command_weekly_stats,
+    get_activity_stats, get_annual_report, get_api_error_stats, get_command_stats,
+    get_command_weekly_stats, get_cost_report, get_model_usage_stats, get_period_report,
+    get_price_table, get_project_stats, get_report_period, get_session_metrics_summary,
+    get_tool_usage_stats, prune_command_stats, recount_command_stats, reset_command_stats,
+    update_price_table,
 };
+pub use report_export::export_report;
 pub use settings::{
     copy_to_clipboard, delete_settings_env, disable_settings_env, enable_settings_env,
     get_home_dir, get_mcp_config_path, get_session_file_path, get_session_summary, get_settings,
@@ -72,8 +135,13 @@ pub use settings::{
     test_openai_connection, update_disabled_settings_env, update_mcp_env, update_settings_env,
     write_binary_file, write_file,
 };
+pub use settings_validation::{validate_settings, SettingsDiagnostic};
+pub use sync::{init_commands_repo, sync_commands_pull, sync_commands_push, SyncPullResult};
 pub use version::{
-    get_claude_code_version_info, install_claude_code_version, set_claude_code_autoupdater,
+    get_claude_code_changelog, get_claude_code_version_info, install_claude_code_version,
+    list_claude_code_versions, pin_claude_code_version, set_claude_code_autoupdater,
+    unpin_claude_code_version, ClaudeCodeChangelogEntry, ClaudeCodePin, ClaudeCodeRelease,
+    ClaudeCodeVersionList,
 };
 
 // 新增模块导出
@@ -81,19 +149,66 @@ pub use diagnostics::{
     diagnostics_add_missing_keys, diagnostics_check_env, diagnostics_detect_stack,
     diagnostics_scan_file_lines,
 };
+pub use project_setup::{
+    analyze_project_setup, scaffold_project_setup, ProjectScaffoldOptions, ProjectScaffoldResult,
+    ProjectSetup, RecommendedHook,
+};
+pub use hook_logs::{
+    clear_hook_events, get_hook_log_path, list_hook_events, HookEvent, HookEventFilter,
+    HookEventStatus,
+};
 pub use hooks::{
     hook_get_monitored, hook_is_monitoring, hook_notify_complete, hook_start_monitoring,
     hook_stop_monitoring,
 };
+pub use history_maintenance::{
+    analyze_history, compact_history, HistoryAnalysis, HistoryCompaction, MalformedLine,
+};
+pub use importers::{
+    delete_imported_sessions, import_chat_history, list_imported_sessions, ImportReport,
+    ImportSource, ImportedSession,
+};
 pub use pty::{
-    pty_create, pty_exists, pty_flush_scrollback, pty_kill, pty_list, pty_purge_scrollback,
-    pty_read, pty_resize, pty_scrollback, pty_write,
+    pty_create, pty_create_ssh, pty_exists, pty_flush_scrollback, pty_get_batch_settings,
+    pty_get_exit_status, pty_get_info, pty_get_state, pty_is_alive, pty_kill, pty_list,
+    pty_list_sessions, pty_purge_scrollback, pty_read, pty_resize, pty_respawn, pty_scrollback,
+    pty_set_batch_settings, pty_write,
+};
+pub use session_export::{
+    export_session_html, export_sessions_to_vault, VaultExportFilter, VaultExportReport,
+};
+pub use session_metrics::{get_session_metrics, SessionMetrics};
+pub use prompts::{
+    delete_prompt, list_prompts, save_prompt, suggest_prompts_from_history, use_prompt,
+    PromptSuggestion, SavedPrompt, SuggestionReason,
 };
+pub use bookmarks::{bookmark_message, list_bookmarks, remove_bookmark, Bookmark, BookmarkFilter};
+pub use session_trash::{list_trashed_sessions, restore_session, trash_session, TrashedSession};
 pub use sessions::get_session_messages;
+pub use todos::{
+    get_project_open_todos, get_session_todos, OpenTodos, SessionPlan, SessionTodos, TodoItem,
+};
+pub use tool_calls::{get_session_tool_calls, ToolCall, ToolCallFilter};
+pub use code_blocks::{extract_code_blocks, CodeBlock};
+pub use saved_searches::{
+    delete_saved_search, get_search_history, list_saved_searches, save_search, SavedSearch,
+    SearchFilters, SearchHistoryEntry,
+};
+pub use session_chain::{get_session_chain, ChainLink, ChainedSession, SessionChain};
+pub use copy_history::{
+    clear_copy_history, get_copy_history_enabled, list_copy_history, set_copy_history_enabled,
+    CopyHistoryItem, CopySource,
+};
 pub use workspace::{
-    workspace_add_panel, workspace_add_project, workspace_create_feature, workspace_delete_feature,
-    workspace_get_pending_reviews, workspace_list_projects, workspace_load, workspace_remove_panel,
-    workspace_remove_project, workspace_rename_feature, workspace_save,
-    workspace_set_active_feature, workspace_set_active_project, workspace_toggle_panel_shared,
-    workspace_update_feature_status,
+    workspace_add_panel, workspace_add_project, workspace_complete_feature,
+    workspace_create_feature, workspace_delete, workspace_delete_feature, workspace_export,
+    workspace_import, workspace_list, workspace_switch,
+    workspace_get_pending_reviews, workspace_link_feature_session, workspace_list_projects,
+    workspace_list_trash, workspace_restore_deleted_feature, workspace_undo_last_change,
+    workspace_load, workspace_move_feature_status, workspace_remove_panel, workspace_remove_project,
+    workspace_rename_feature, workspace_reorder_features, workspace_save,
+    workspace_set_active_feature, workspace_set_active_project, workspace_set_feature_worktree,
+    workspace_start_feature_branch,
+    workspace_set_panel_env, workspace_toggle_panel_shared, workspace_unlink_feature_session,
+    workspace_update_feature_status, workspace_update_layout_ratio,
 };
@@ -0,0 +1,458 @@
+/**
+ * [INPUT]: 依赖 git2 (git init), serde_json, crate::diagnostics (技术栈检测), commands::claude_md_gen (CLAUDE.md 模板), crate::security (原子写入)
+ * [OUTPUT]: 对外提供 analyze_project_setup, scaffold_project_setup 命令与 ProjectSetup/RecommendedHook/ProjectScaffoldOptions/ProjectScaffoldResult 类型
+ * [POS]: commands/ 模块的项目 Claude Code 就绪检查，报告 CLAUDE.md / .claude/settings.json / git / 推荐 hook / .mcp.json 是否齐全，并一键补齐缺失项
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::claude_md_gen;
+use crate::diagnostics::{self, TechStack};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSetup {
+    pub path: String,
+    pub git_repo: bool,
+    /// CLAUDE.md or .claude/CLAUDE.md, relative to the project
+    pub claude_md: Option<String>,
+    /// .claude/settings.json exists
+    pub settings: bool,
+    /// .mcp.json exists
+    pub mcp_config: bool,
+    /// Hooks suggested for the detected stack
+    pub recommended_hooks: Vec<RecommendedHook>,
+    /// Missing pieces: "git", "claude_md", "settings", "hooks", "mcp_config"
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendedHook {
+    pub id: String,
+    pub event: String,
+    pub matcher: String,
+    pub command: String,
+    pub description: String,
+    /// A hook in the project settings already runs this tool
+    pub installed: bool,
+}
+
+/// Pieces to create; existing files are never overwritten
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectScaffoldOptions {
+    #[serde(default)]
+    pub git: bool,
+    #[serde(default)]
+    pub claude_md: bool,
+    #[serde(default)]
+    pub settings: bool,
+    /// Add the recommended hooks that aren't installed to .claude/settings.json
+    #[serde(default)]
+    pub hooks: bool,
+    #[serde(default)]
+    pub mcp_config: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectScaffoldResult {
+    /// Project-relative paths created
+    pub created: Vec<String>,
+    /// Existing files that were changed (hooks merged into settings)
+    pub updated: Vec<String>,
+    /// The project after scaffolding
+    pub setup: ProjectSetup,
+}
+
+/// Edits that trigger the formatter hooks
+const EDIT_MATCHER: &str = "Edit|MultiEdit|Write";
+
+const SETTINGS_FILE: &str = ".claude/settings.json";
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Run `tool` on the file Claude just edited (read from the hook's stdin JSON)
+fn format_edited_file(tool: &str) -> String {
+    format!(
+        r#"f=$(jq -r '.tool_input.file_path // empty'); if [ -n "$f" ]; then {} "$f"; fi"#,
+        tool
+    )
+}
+
+fn has_dependency(root: &Path, file: &str, name: &str) -> bool {
+    fs::read_to_string(root.join(file)).is_ok_and(|content| content.contains(name))
+}
+
+/// Formatter hooks for the detected stack, with the text that marks one as installed
+fn hooks_for(root: &Path, stack: &TechStack) -> Vec<(RecommendedHook, &'static str)> {
+    let mut hooks = Vec::new();
+    let mut add = |id: &str, command: String, description: &str, marker: &'static str| {
+        hooks.push((
+            RecommendedHook {
+                id: id.to_string(),
+                event: "PostToolUse".to_string(),
+                matcher: EDIT_MATCHER.to_string(),
+                command,
+                description: description.to_string(),
+                installed: false,
+            },
+            marker,
+        ));
+    };
+
+    if stack.runtime.contains("rust") {
+        add(
+            "rustfmt",
+            "cargo fmt --quiet".to_string(),
+            "Run cargo fmt after Claude edits a file",
+            "cargo fmt",
+        );
+    }
+    if stack.runtime.contains("node") && has_dependency(root, "package.json", "\"prettier\"") {
+        add(
+            "prettier",
+            format_edited_file("npx prettier --write --ignore-unknown"),
+            "Format each edited file with Prettier",
+            "prettier",
+        );
+    }
+    if stack.runtime.contains("python") {
+        let deps = ["pyproject.toml", "requirements.txt"];
+        if deps.iter().any(|f| has_dependency(root, f, "ruff")) {
+            add(
+                "ruff",
+                format_edited_file("ruff format --quiet"),
+                "Format each edited file with Ruff",
+                "ruff format",
+            );
+        } else if deps.iter().any(|f| has_dependency(root, f, "black")) {
+            add(
+                "black",
+                format_edited_file("black --quiet"),
+                "Format each edited file with Black",
+                "black",
+            );
+        }
+    }
+    hooks
+}
+
+/// Commands of every hook in the project's shared and local settings
+fn installed_hook_commands(root: &Path) -> Vec<String> {
+    [SETTINGS_FILE, ".claude/settings.local.json"]
+        .iter()
+        .filter_map(|file| fs::read_to_string(root.join(file)).ok())
+        .filter_map(|content| serde_json::from_str::<Value>(&content).ok())
+        .filter_map(|settings| settings.get("hooks").and_then(Value::as_object).cloned())
+        .flat_map(|events| events.into_iter().map(|(_, handlers)| handlers))
+        .filter_map(|handlers| handlers.as_array().cloned())
+        .flatten()
+        .filter_map(|handler| handler.get("hooks").and_then(Value::as_array).cloned())
+        .flatten()
+        .filter_map(|hook| {
+            hook.get("command")
+                .and_then(Value::as_str)
+                .map(String::from)
+        })
+        .collect()
+}
+
+/// (label, script) for the common package.json scripts the project defines
+fn package_scripts(root: &Path) -> Vec<(&'static str, &'static str)> {
+    let Some(scripts) = fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|json| json.get("scripts").and_then(Value::as_object).cloned())
+    else {
+        return Vec::new();
+    };
+    [
+        ("Dev", "dev"),
+        ("Build", "build"),
+        ("Test", "test"),
+        ("Lint", "lint"),
+    ]
+    .into_iter()
+    .filter(|(_, script)| scripts.contains_key(*script))
+    .collect()
+}
+
+/// (label, command) pairs for the build/test commands of the detected stack
+pub fn stack_commands(root: &Path, stack: &TechStack) -> Vec<(String, String)> {
+    let mut commands = Vec::new();
+    if stack.runtime.contains("rust") {
+        commands.push(("Build".to_string(), "cargo build".to_string()));
+        commands.push(("Test".to_string(), "cargo test".to_string()));
+        commands.push(("Lint".to_string(), "cargo clippy".to_string()));
+    }
+    if stack.runtime.contains("node") {
+        let pm = stack.package_manager.as_deref().unwrap_or("npm");
+        for (label, script) in package_scripts(root) {
+            commands.push((label.to_string(), format!("{} run {}", pm, script)));
+        }
+    }
+    if stack.runtime.contains("python") {
+        let runner = match stack.package_manager.as_deref() {
+            Some("uv") => "uv run ",
+            Some("poetry") => "poetry run ",
+            _ => "",
+        };
+        commands.push(("Test".to_string(), format!("{}pytest", runner)));
+    }
+    commands
+}
+
+/// Project settings that let Claude run the stack's build/test commands and git
+/// read-only commands without asking, and keep .env files out of reach
+fn settings_template(root: &Path, stack: &TechStack) -> Value {
+    let mut allow: Vec<String> = stack_commands(root, stack)
+        .into_iter()
+        .map(|(_, command)| format!("Bash({}:*)", command))
+        .collect();
+    allow.extend(
+        ["git status", "git diff", "git log"]
+            .iter()
+            .map(|command| format!("Bash({}:*)", command)),
+    );
+    json!({
+        "permissions": {
+            "allow": allow,
+            "deny": ["Read(./.env)", "Read(./.env.*)"],
+        }
+    })
+}
+
+fn write_new(root: &Path, rel: &str, content: &str) -> AppResult<()> {
+    let path = root.join(rel);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::io("Failed to create directory", &e).with_context(rel))?;
+    }
+    security::atomic_write_string(&path, content)
+}
+
+fn project_root(path: &str) -> AppResult<PathBuf> {
+    let root = PathBuf::from(path);
+    if !root.is_absolute() {
+        return Err(AppError::invalid_input("Project path must be absolute").with_context(path));
+    }
+    if !root.is_dir() {
+        return Err(AppError::not_found("Project directory not found").with_context(path));
+    }
+    Ok(root)
+}
+
+// ============================================================================
+// Analysis and Scaffolding
+// ============================================================================
+
+fn analyze(root: &Path) -> AppResult<ProjectSetup> {
+    let stack = diagnostics::detect_tech_stack(&root.to_string_lossy())?;
+    let installed = installed_hook_commands(root);
+    let recommended_hooks: Vec<RecommendedHook> = hooks_for(root, &stack)
+        .into_iter()
+        .map(|(mut hook, marker)| {
+            hook.installed = installed.iter().any(|command| command.contains(marker));
+            hook
+        })
+        .collect();
+
+    let git_repo = git2::Repository::discover(root).is_ok();
+    let claude_md = ["CLAUDE.md", ".claude/CLAUDE.md"]
+        .into_iter()
+        .find(|rel| root.join(rel).is_file())
+        .map(String::from);
+    let settings = root.join(SETTINGS_FILE).is_file();
+    let mcp_config = root.join(".mcp.json").is_file();
+
+    let missing = [
+        ("git", git_repo),
+        ("claude_md", claude_md.is_some()),
+        ("settings", settings),
+        ("hooks", recommended_hooks.iter().all(|h| h.installed)),
+        ("mcp_config", mcp_config),
+    ]
+    .into_iter()
+    .filter(|(_, present)| !present)
+    .map(|(key, _)| key.to_string())
+    .collect();
+
+    Ok(ProjectSetup {
+        path: root.to_string_lossy().to_string(),
+        git_repo,
+        claude_md,
+        settings,
+        mcp_config,
+        recommended_hooks,
+        missing,
+    })
+}
+
+fn scaffold(
+    root: &Path,
+    options: &ProjectScaffoldOptions,
+) -> AppResult<ProjectScaffoldResult> {
+    let before = analyze(root)?;
+    let stack = diagnostics::detect_tech_stack(&root.to_string_lossy())?;
+    let mut created = Vec::new();
+    let mut updated = Vec::new();
+
+    if options.git && !before.git_repo {
+        git2::Repository::init(root).map_err(|e| {
+            AppError::new(ErrorCode::Io, format!("Failed to init git repo: {}", e.message()))
+        })?;
+        created.push(".git".to_string());
+    }
+    if options.claude_md && before.claude_md.is_none() {
+        let scan = claude_md_gen::scan_repository(root)?;
+        write_new(root, "CLAUDE.md", &claude_md_gen::render_template(&scan))?;
+        created.push("CLAUDE.md".to_string());
+    }
+    if options.mcp_config && !before.mcp_config {
+        write_new(root, ".mcp.json", "{\n  \"mcpServers\": {}\n}\n")?;
+        created.push(".mcp.json".to_string());
+    }
+
+    let new_hooks: Vec<&RecommendedHook> = before
+        .recommended_hooks
+        .iter()
+        .filter(|hook| options.hooks && !hook.installed)
+        .collect();
+    let create_settings = options.settings && !before.settings;
+    if create_settings || !new_hooks.is_empty() {
+        let mut settings = if before.settings {
+            let content = fs::read_to_string(root.join(SETTINGS_FILE))
+                .map_err(|e| AppError::io("Failed to read file", &e).with_context(SETTINGS_FILE))?;
+            serde_json::from_str(&content).map_err(|e| {
+                AppError::new(ErrorCode::Parse, format!("Failed to parse {}: {}", SETTINGS_FILE, e))
+            })?
+        } else if create_settings {
+            settings_template(root, &stack)
+        } else {
+            json!({})
+        };
+        // Plain commands rather than lovcode's hook logger: the settings file is shared
+        // through git, and the logger script only exists on this machine
+        if !new_hooks.is_empty() {
+            let invalid = || {
+                AppError::new(
+                    ErrorCode::Parse,
+                    format!("{} has an invalid hooks section", SETTINGS_FILE),
+                )
+            };
+            let events = settings
+                .as_object_mut()
+                .ok_or_else(invalid)?
+                .entry("hooks")
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+                .ok_or_else(invalid)?;
+            for hook in &new_hooks {
+                events
+                    .entry(hook.event.clone())
+                    .or_insert_with(|| json!([]))
+                    .as_array_mut()
+                    .ok_or_else(invalid)?
+                    .push(json!({
+                        "matcher": hook.matcher,
+                        "hooks": [{"type": "command", "command": hook.command}],
+                    }));
+            }
+        }
+        let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+        write_new(root, SETTINGS_FILE, &format!("{}\n", content))?;
+        if before.settings {
+            updated.push(SETTINGS_FILE.to_string());
+        } else {
+            created.push(SETTINGS_FILE.to_string());
+        }
+    }
+
+    Ok(ProjectScaffoldResult {
+        created,
+        updated,
+        setup: analyze(root)?,
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Report which Claude Code setup pieces a project has
+#[tauri::command]
+pub fn analyze_project_setup(path: String) -> AppResult<ProjectSetup> {
+    analyze(&project_root(&path)?)
+}
+
+/// Create the chosen missing setup pieces ("prepare this repo for Claude Code")
+#[tauri::command]
+pub fn scaffold_project_setup(
+    path: String,
+    options: ProjectScaffoldOptions,
+) -> AppResult<ProjectScaffoldResult> {
+    scaffold(&project_root(&path)?, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::settings_validation::validate_settings_json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_analyze_and_scaffold_project() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = temp.path().join("demo");
+        fs::create_dir_all(&root).expect("project dir");
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"demo\"\n").expect("manifest");
+
+        let setup = analyze(&root).expect("analyze");
+        assert_eq!(
+            setup.missing,
+            ["git", "claude_md", "settings", "hooks", "mcp_config"]
+        );
+        assert_eq!(setup.recommended_hooks.len(), 1);
+        assert_eq!(setup.recommended_hooks[0].id, "rustfmt");
+
+        let options = ProjectScaffoldOptions {
+            git: true,
+            claude_md: true,
+            settings: true,
+            hooks: true,
+            mcp_config: true,
+        };
+        let result = scaffold(&root, &options).expect("scaffold");
+        assert_eq!(
+            result.created,
+            [".git", "CLAUDE.md", ".mcp.json", ".claude/settings.json"]
+        );
+        assert!(result.setup.missing.is_empty());
+        assert!(result.setup.recommended_hooks[0].installed);
+
+        let claude_md = fs::read_to_string(root.join("CLAUDE.md")).expect("CLAUDE.md");
+        assert!(claude_md.starts_with("# demo\n"));
+        assert!(claude_md.contains("- Test: `cargo test`"));
+        let settings = fs::read_to_string(root.join(SETTINGS_FILE)).expect("settings");
+        assert!(settings.contains("Bash(cargo test:*)"));
+        assert!(validate_settings_json(&settings).is_empty());
+
+        // Nothing left to create, and existing files are untouched
+        fs::write(root.join("CLAUDE.md"), "custom").expect("edit");
+        let again = scaffold(&root, &options).expect("scaffold again");
+        assert!(again.created.is_empty() && again.updated.is_empty());
+        assert_eq!(
+            fs::read_to_string(root.join("CLAUDE.md")).expect("read"),
+            "custom"
+        );
+    }
+}
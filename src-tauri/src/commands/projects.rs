@@ -1,14 +1,17 @@
 /**
- * [INPUT]: 依赖 types (Project, Session, ChatMessage), security (get_claude_dir, validate_decoded_path)
- * [OUTPUT]: 对外提供 list_projects, list_sessions, list_all_sessions, list_all_chats 命令
+ * [INPUT]: 依赖 types (Project, Session, SessionsResponse, ChatsResponse), crate::jsonl (流式读取会话与 history.jsonl), security (get_claude_dir, validate_decoded_path), services::chat_cache (list_all_sessions / list_all_chats 分页)
+ * [OUTPUT]: 对外提供 list_projects, list_sessions, list_all_sessions, list_all_chats 命令, read_session_head / restore_slash_command / build_session_index_from_history 供会话缓存复用
  * [POS]: commands/ 模块的项目和会话管理中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
+use crate::errors::{AppError, AppResult};
+use crate::jsonl;
 use crate::security::{get_claude_dir, validate_decoded_path};
-use crate::types::{ChatMessage, ChatsResponse, HistoryEntry, Project, RawLine, Session};
+use crate::services::chat_cache::{self, SessionSort};
+use crate::types::{ChatsResponse, HistoryEntry, Project, RawLine, Session, SessionsResponse};
 use regex::Regex;
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
@@ -99,23 +102,15 @@ fn try_merge_segments(prefix: &str, rest: &str) -> Option<String> {
 
 /// Read only the first N lines of a session file to get summary (much faster than reading entire file)
 pub fn read_session_head(path: &Path, max_lines: usize) -> (Option<String>, usize) {
-    use std::io::{BufRead, BufReader};
-
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return (None, 0),
+    let Ok(lines) = jsonl::open(path) else {
+        return (None, 0);
     };
 
-    let reader = BufReader::new(file);
     let mut summary = None;
     let mut first_user_message: Option<String> = None;
     let mut message_count = 0;
 
-    for line in reader.lines().take(max_lines) {
-        let line = match line {
-            Ok(l) => l,
-            Err(_) => continue,
-        };
+    for (_, line) in lines.take(max_lines) {
         if let Ok(parsed) = serde_json::from_str::<RawLine>(&line) {
             if parsed.line_type.as_deref() == Some("summary") {
                 summary = parsed.summary;
@@ -165,7 +160,7 @@ pub fn read_session_head(path: &Path, max_lines: usize) -> (Option<String>, usiz
 
 /// Convert <command-message>...</command-message><command-name>/cmd</command-name> to /cmd format
 #[allow(clippy::unwrap_used)] // Static regex patterns are compile-time validated
-fn restore_slash_command(content: &str) -> String {
+pub fn restore_slash_command(content: &str) -> String {
     static NAME_RE: LazyLock<Regex> =
         LazyLock::new(|| Regex::new(r"<command-name>(/[^<]+)</command-name>").unwrap());
     static ARGS_RE: LazyLock<Regex> =
@@ -199,74 +194,39 @@ fn restore_slash_command(content: &str) -> String {
     }
 }
 
-/// Build session index from history.jsonl (fast: only reads one file)
-fn build_session_index_from_history() -> HashMap<(String, String), (u64, Option<String>)> {
-    use std::io::{BufRead, BufReader};
+/// (project id, session id) -> (latest prompt timestamp ms, its display text)
+pub type HistoryIndex = HashMap<(String, String), (u64, Option<String>)>;
 
+/// Build session index from history.jsonl (fast: only reads one file)
+pub fn build_session_index_from_history() -> HistoryIndex {
     let history_path = get_claude_dir()
         .map(|d| d.join("history.jsonl"))
         .unwrap_or_else(|_| PathBuf::from("./.claude/history.jsonl"));
 
-    let mut index: HashMap<(String, String), (u64, Option<String>)> = HashMap::new();
+    let mut index: HistoryIndex = HashMap::new();
 
-    let file = match fs::File::open(&history_path) {
-        Ok(f) => f,
-        Err(_) => return index,
+    let Ok(lines) = jsonl::open(&history_path) else {
+        return index;
     };
 
-    let reader = BufReader::new(file);
-    for line in reader.lines().map_while(Result::ok) {
-        if let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line) {
-            if let (Some(session_id), Some(project), Some(timestamp)) =
-                (entry.session_id, entry.project, entry.timestamp)
-            {
-                let project_id = encode_project_path(&project);
-                index
-                    .entry((project_id, session_id))
-                    .and_modify(|(ts, disp)| {
-                        if timestamp > *ts {
-                            *ts = timestamp;
-                            *disp = entry.display.clone();
-                        }
-                    })
-                    .or_insert((timestamp, entry.display));
-            }
-        }
-    }
-
-    index
-}
-
-/// Extract text content from message content field
-fn extract_content_with_meta(value: &Option<Value>) -> (String, bool) {
-    match value {
-        Some(Value::String(s)) => (s.clone(), false),
-        Some(Value::Array(arr)) => {
-            let has_tool = arr.iter().any(|item| {
-                if let Some(obj) = item.as_object() {
-                    let t = obj.get("type").and_then(|v| v.as_str());
-                    return t == Some("tool_use") || t == Some("tool_result");
-                }
-                false
-            });
-
-            let text = arr
-                .iter()
-                .filter_map(|item| {
-                    if let Some(obj) = item.as_object() {
-                        if obj.get("type").and_then(|v| v.as_str()) == Some("text") {
-                            return obj.get("text").and_then(|v| v.as_str()).map(String::from);
-                        }
+    for (_, entry) in lines.records::<HistoryEntry>() {
+        if let (Some(session_id), Some(project), Some(timestamp)) =
+            (entry.session_id, entry.project, entry.timestamp)
+        {
+            let project_id = encode_project_path(&project);
+            index
+                .entry((project_id, session_id))
+                .and_modify(|(ts, disp)| {
+                    if timestamp > *ts {
+                        *ts = timestamp;
+                        *disp = entry.display.clone();
                     }
-                    None
                 })
-                .collect::<Vec<_>>()
-                .join("\n");
-
-            (text, has_tool)
+                .or_insert((timestamp, entry.display));
         }
-        _ => (String::new(), false),
     }
+
+    index
 }
 
 // ============================================================================
@@ -274,11 +234,9 @@ fn extract_content_with_meta(value: &Option<Value>) -> (String, bool) {
 // ============================================================================
 
 #[tauri::command]
-pub async fn list_projects() -> Result<Vec<Project>, String> {
+pub async fn list_projects() -> AppResult<Vec<Project>> {
     tauri::async_runtime::spawn_blocking(|| {
-        let projects_dir = get_claude_dir()
-            .map(|d| d.join("projects"))
-            .map_err(|e| e.to_string())?;
+        let projects_dir = get_claude_dir()?.join("projects");
 
         if !projects_dir.exists() {
             return Ok(vec![]);
@@ -286,8 +244,10 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
 
         let mut projects = Vec::new();
 
-        for entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
+        let entries = fs::read_dir(&projects_dir)
+            .map_err(|e| AppError::io("Failed to read directory", &e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
             let path = entry.path();
 
             if path.is_dir() {
@@ -335,20 +295,20 @@ pub async fn list_projects() -> Result<Vec<Project>, String> {
 }
 
 #[tauri::command]
-pub async fn list_sessions(project_id: String) -> Result<Vec<Session>, String> {
+pub async fn list_sessions(project_id: String) -> AppResult<Vec<Session>> {
     tauri::async_runtime::spawn_blocking(move || {
-        let project_dir = get_claude_dir()
-            .map(|d| d.join("projects").join(&project_id))
-            .map_err(|e| e.to_string())?;
+        let project_dir = get_claude_dir()?.join("projects").join(&project_id);
 
         if !project_dir.exists() {
-            return Err("Project not found".to_string());
+            return Err(AppError::not_found("Project not found").with_context(project_id));
         }
 
         let mut sessions = Vec::new();
 
-        for entry in fs::read_dir(&project_dir).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
+        let entries = fs::read_dir(&project_dir)
+            .map_err(|e| AppError::io("Failed to read directory", &e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| AppError::io("Failed to read directory", &e))?;
             let path = entry.path();
             let name = path
                 .file_name()
@@ -384,240 +344,36 @@ pub async fn list_sessions(project_id: String) -> Result<Vec<Session>, String> {
     .map_err(|e| e.to_string())?
 }
 
+/// Sessions across all projects, served from the chat cache.
+/// `sort_by` is "last_modified" (default), "message_count" or "project"; every session
+/// is returned when `limit` is omitted.
 #[tauri::command]
-pub async fn list_all_sessions() -> Result<Vec<Session>, String> {
-    tauri::async_runtime::spawn_blocking(|| {
-        let projects_dir = get_claude_dir()
-            .map(|d| d.join("projects"))
-            .map_err(|e| e.to_string())?;
-
-        if !projects_dir.exists() {
-            return Ok(vec![]);
-        }
-
-        let history_index = build_session_index_from_history();
-        let mut all_sessions = Vec::new();
-        let mut seen_sessions: HashSet<(String, String)> = HashSet::new();
-
-        // First pass: use history index
-        for ((project_id, session_id), (timestamp, display)) in &history_index {
-            let session_path = projects_dir
-                .join(project_id)
-                .join(format!("{}.jsonl", session_id));
-
-            if !session_path.exists() {
-                continue;
-            }
-
-            seen_sessions.insert((project_id.clone(), session_id.clone()));
-
-            let (summary, head_msg_count) = read_session_head(&session_path, 20);
-            let final_summary =
-                summary.or_else(|| display.clone().map(|d| restore_slash_command(&d)));
-
-            let metadata = fs::metadata(&session_path).ok();
-            let last_modified = metadata
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs())
-                .unwrap_or(*timestamp / 1000);
-
-            let display_path = decode_project_path(project_id);
-
-            all_sessions.push(Session {
-                id: session_id.clone(),
-                project_id: project_id.clone(),
-                project_path: Some(display_path),
-                summary: final_summary,
-                message_count: head_msg_count,
-                last_modified,
-            });
-        }
-
-        // Second pass: scan for sessions not in history
-        for project_entry in fs::read_dir(&projects_dir).into_iter().flatten().flatten() {
-            let project_path = project_entry.path();
-            if !project_path.is_dir() {
-                continue;
-            }
-
-            let project_id = project_path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-            let display_path = decode_project_path(&project_id);
-
-            for entry in fs::read_dir(&project_path).into_iter().flatten().flatten() {
-                let path = entry.path();
-                let name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-
-                if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                    let session_id = name.trim_end_matches(".jsonl").to_string();
-
-                    if seen_sessions.contains(&(project_id.clone(), session_id.clone())) {
-                        continue;
-                    }
-
-                    let (summary, head_msg_count) = read_session_head(&path, 20);
-
-                    let metadata = fs::metadata(&path).ok();
-                    let last_modified = metadata
-                        .and_then(|m| m.modified().ok())
-                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0);
-
-                    all_sessions.push(Session {
-                        id: session_id,
-                        project_id: project_id.clone(),
-                        project_path: Some(display_path.clone()),
-                        summary,
-                        message_count: head_msg_count,
-                        last_modified,
-                    });
-                }
-            }
-        }
-
-        all_sessions.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
-        Ok(all_sessions)
+pub async fn list_all_sessions(
+    limit: Option<usize>,
+    offset: Option<usize>,
+    sort_by: Option<String>,
+) -> AppResult<SessionsResponse> {
+    let sort = SessionSort::parse(sort_by.as_deref().unwrap_or("last_modified"))
+        .map_err(AppError::invalid_input)?;
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        chat_cache::page_sessions(limit, offset.unwrap_or(0), sort)
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())??)
 }
 
+/// Chat messages across all projects, newest first, served from the chat cache.
+/// A message copied into a resumed session is listed once, under its original session.
 #[tauri::command]
 pub async fn list_all_chats(
     limit: Option<usize>,
     offset: Option<usize>,
-) -> Result<ChatsResponse, String> {
-    tauri::async_runtime::spawn_blocking(move || {
-        let projects_dir = get_claude_dir()
-            .map(|d| d.join("projects"))
-            .map_err(|e| e.to_string())?;
-
-        let max_messages = limit.unwrap_or(50);
-        let skip = offset.unwrap_or(0);
-
-        if !projects_dir.exists() {
-            return Ok(ChatsResponse {
-                items: vec![],
-                total: 0,
-            });
-        }
-
-        // Collect all session files with metadata
-        let mut session_files: Vec<(PathBuf, String, String, u64)> = Vec::new();
-
-        for project_entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let project_entry = project_entry.map_err(|e| e.to_string())?;
-            let project_path = project_entry.path();
-
-            if !project_path.is_dir() {
-                continue;
-            }
-
-            let project_id = project_path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-            let display_path = decode_project_path(&project_id);
-
-            for entry in fs::read_dir(&project_path).map_err(|e| e.to_string())? {
-                let entry = entry.map_err(|e| e.to_string())?;
-                let path = entry.path();
-                let name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-
-                if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                    let last_modified = entry
-                        .metadata()
-                        .ok()
-                        .and_then(|m| m.modified().ok())
-                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0);
-
-                    session_files.push((
-                        path,
-                        project_id.clone(),
-                        display_path.clone(),
-                        last_modified,
-                    ));
-                }
-            }
-        }
-
-        session_files.sort_by(|a, b| b.3.cmp(&a.3));
-
-        let mut all_chats: Vec<ChatMessage> = Vec::new();
-
-        for (path, project_id, project_path, _) in session_files {
-            let session_id = path
-                .file_stem()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default();
-            let content = fs::read_to_string(&path).unwrap_or_default();
-
-            let mut session_summary: Option<String> = None;
-            let mut session_messages: Vec<ChatMessage> = Vec::new();
-
-            for line in content.lines() {
-                if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                    let line_type = parsed.line_type.as_deref();
-
-                    if line_type == Some("summary") {
-                        session_summary = parsed.summary;
-                    }
-
-                    if line_type == Some("user") || line_type == Some("assistant") {
-                        if let Some(msg) = &parsed.message {
-                            let role = msg.role.clone().unwrap_or_default();
-                            let (text_content, _is_tool) = extract_content_with_meta(&msg.content);
-                            let is_meta = parsed.is_meta.unwrap_or(false);
-
-                            if !is_meta && !text_content.is_empty() {
-                                session_messages.push(ChatMessage {
-                                    uuid: parsed.uuid.unwrap_or_default(),
-                                    role,
-                                    content: text_content,
-                                    timestamp: parsed.timestamp.unwrap_or_default(),
-                                    project_id: project_id.clone(),
-                                    project_path: project_path.clone(),
-                                    session_id: session_id.clone(),
-                                    session_summary: None,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-
-            for msg in &mut session_messages {
-                msg.session_summary = session_summary.clone();
-            }
-
-            all_chats.extend(session_messages);
-        }
-
-        all_chats.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-        let total = all_chats.len();
-        let items: Vec<ChatMessage> = all_chats
-            .into_iter()
-            .skip(skip)
-            .take(max_messages)
-            .collect();
-
-        Ok(ChatsResponse { items, total })
+) -> AppResult<ChatsResponse> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        chat_cache::page_chats(limit.unwrap_or(50), offset.unwrap_or(0))
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())??)
 }
 
 // ============================================================================
@@ -627,6 +383,7 @@ pub async fn list_all_chats(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::{extract_content_with_meta, extract_thinking};
     use tempfile::TempDir;
 
     // ------------------------------------------------------------------------
@@ -847,4 +604,20 @@ mod tests {
         assert_eq!(text, "");
         assert!(!is_tool);
     }
+
+    #[test]
+    fn test_extract_thinking() {
+        let content = Some(serde_json::json!([
+            {"type": "thinking", "thinking": "Check the lock order first.", "signature": "sig"},
+            {"type": "redacted_thinking", "data": "opaque"},
+            {"type": "thinking", "thinking": "  "},
+            {"type": "text", "text": "Swap the two locks."}
+        ]));
+        assert_eq!(
+            extract_thinking(content.as_ref()).as_deref(),
+            Some("Check the lock order first.")
+        );
+        assert_eq!(extract_content_with_meta(&content).0, "Swap the two locks.");
+        assert_eq!(extract_thinking(Some(&Value::String("hi".to_string()))), None);
+    }
 }
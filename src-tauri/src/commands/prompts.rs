@@ -0,0 +1,470 @@
+/**
+ * [INPUT]: 依赖 crate::store (prompts.json 预写日志存储), crate::commands::sessions (read_session_messages), crate::security (shell_escape), uuid
+ * [OUTPUT]: 对外提供 save_prompt, list_prompts, delete_prompt, use_prompt, suggest_prompts_from_history 命令与 SavedPrompt/PromptSuggestion 类型
+ * [POS]: commands/ 模块的提示词库，保存带标签的常用提示词，从项目历史会话中挖掘高频/长篇用户消息作为候选，并生成可直接交给 pty_create 的 claude 命令
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::sessions::read_session_messages;
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::store::{self, Schema};
+use crate::types::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPrompt {
+    pub id: String,
+    pub text: String,
+    /// Lowercase, without duplicates
+    pub tags: Vec<String>,
+    /// Unix seconds
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+    pub use_count: u32,
+}
+
+/// Why a past message was suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionReason {
+    /// Sent at least MIN_REPEATS times
+    Frequent,
+    /// A detailed instruction, at least LONG_PROMPT_CHARS long
+    Long,
+}
+
+/// A past user message worth saving to the library
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptSuggestion {
+    /// Latest wording of the message
+    pub text: String,
+    pub reason: SuggestionReason,
+    pub occurrences: usize,
+    pub sessions: usize,
+    /// Timestamp of the latest occurrence
+    pub last_used: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PromptStore {
+    /// Oldest first
+    prompts: Vec<SavedPrompt>,
+}
+
+const PROMPTS_SCHEMA: Schema = Schema::new(1);
+/// Most recently modified sessions mined per project
+const MAX_SESSIONS: usize = 200;
+const MIN_PROMPT_CHARS: usize = 20;
+const LONG_PROMPT_CHARS: usize = 280;
+const MIN_REPEATS: usize = 2;
+const MAX_SUGGESTIONS: usize = 30;
+
+/// Serializes read-modify-write of prompts.json
+static PROMPTS_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn prompts_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("prompts.json")
+}
+
+fn load_prompts(path: &Path) -> Result<PromptStore, String> {
+    Ok(store::load_as(path, &PROMPTS_SCHEMA)?.unwrap_or_default())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty() && seen.insert(t.clone()))
+        .collect()
+}
+
+/// Save a prompt; saving the same text again adds the new tags to the existing entry
+fn save_prompt_in(path: &Path, text: &str, tags: Vec<String>) -> AppResult<SavedPrompt> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::invalid_input("Prompt text cannot be empty"));
+    }
+    let _guard = PROMPTS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut data = load_prompts(path)?;
+    let tags = normalize_tags(tags);
+
+    let saved = if let Some(existing) = data.prompts.iter_mut().find(|p| p.text == text) {
+        for tag in tags {
+            if !existing.tags.contains(&tag) {
+                existing.tags.push(tag);
+            }
+        }
+        existing.clone()
+    } else {
+        let prompt = SavedPrompt {
+            id: uuid::Uuid::new_v4().to_string(),
+            text: text.to_string(),
+            tags,
+            created_at: now_secs(),
+            last_used_at: None,
+            use_count: 0,
+        };
+        data.prompts.push(prompt.clone());
+        prompt
+    };
+    store::save(path, &PROMPTS_SCHEMA, &data)?;
+    Ok(saved)
+}
+
+/// Most recently used or saved first
+fn list_prompts_in(path: &Path, tag: Option<&str>) -> Result<Vec<SavedPrompt>, String> {
+    let tag = tag.map(|t| t.trim().to_lowercase());
+    let mut prompts: Vec<SavedPrompt> = load_prompts(path)?
+        .prompts
+        .into_iter()
+        .filter(|p| tag.as_ref().is_none_or(|t| p.tags.contains(t)))
+        .collect();
+    prompts.sort_by_key(|p| std::cmp::Reverse(p.last_used_at.unwrap_or(p.created_at)));
+    Ok(prompts)
+}
+
+/// Apply `f` to the prompt with this id and save
+fn update_prompt<T>(
+    path: &Path,
+    id: &str,
+    f: impl FnOnce(&mut Vec<SavedPrompt>, usize) -> T,
+) -> AppResult<T> {
+    let _guard = PROMPTS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut data = load_prompts(path)?;
+    let index = data
+        .prompts
+        .iter()
+        .position(|p| p.id == id)
+        .ok_or_else(|| AppError::not_found("Prompt not found").with_context(id.to_string()))?;
+    let result = f(&mut data.prompts, index);
+    store::save(path, &PROMPTS_SCHEMA, &data)?;
+    Ok(result)
+}
+
+/// Count a use and build `claude '<text>'`
+fn use_prompt_in(path: &Path, id: &str) -> AppResult<String> {
+    update_prompt(path, id, |prompts, index| {
+        let prompt = &mut prompts[index];
+        prompt.use_count += 1;
+        prompt.last_used_at = Some(now_secs());
+        format!("claude {}", security::shell_escape(&prompt.text))
+    })
+}
+
+// ============================================================================
+// History mining
+// ============================================================================
+
+/// Whitespace-collapsed, lowercase form used to spot repeats
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Typed prompts only: slash commands, command output and interruption notices are skipped
+fn is_candidate(message: &Message) -> bool {
+    let text = message.content.trim();
+    message.role == "user"
+        && !message.is_meta
+        && !message.is_tool
+        && text.chars().count() >= MIN_PROMPT_CHARS
+        && !text.starts_with('/')
+        && !text.starts_with('<')
+        && !text.starts_with('[')
+}
+
+/// Frequent and long user messages across `sessions`, skipping texts already saved
+fn mine_suggestions(
+    sessions: &[(String, Vec<Message>)],
+    saved: &HashSet<String>,
+) -> Vec<PromptSuggestion> {
+    struct Seen {
+        text: String,
+        occurrences: usize,
+        sessions: HashSet<String>,
+        last_used: Option<String>,
+    }
+    let mut by_text: HashMap<String, Seen> = HashMap::new();
+    // A resumed session starts with a copy of its parent's messages
+    let mut uuids = HashSet::new();
+
+    for (session_id, messages) in sessions {
+        for message in messages.iter().filter(|m| is_candidate(m)) {
+            if !message.uuid.is_empty() && !uuids.insert(message.uuid.as_str()) {
+                continue;
+            }
+            let key = normalize_text(&message.content);
+            if saved.contains(&key) {
+                continue;
+            }
+            let seen = by_text.entry(key).or_insert_with(|| Seen {
+                text: String::new(),
+                occurrences: 0,
+                sessions: HashSet::new(),
+                last_used: None,
+            });
+            seen.occurrences += 1;
+            seen.sessions.insert(session_id.clone());
+            let timestamp = Some(message.timestamp.clone()).filter(|t| !t.is_empty());
+            if seen.text.is_empty() || timestamp >= seen.last_used {
+                seen.text = message.content.trim().to_string();
+                seen.last_used = timestamp;
+            }
+        }
+    }
+
+    let mut suggestions: Vec<PromptSuggestion> = by_text
+        .into_values()
+        .filter_map(|seen| {
+            let reason = if seen.occurrences >= MIN_REPEATS {
+                SuggestionReason::Frequent
+            } else if seen.text.chars().count() >= LONG_PROMPT_CHARS {
+                SuggestionReason::Long
+            } else {
+                return None;
+            };
+            Some(PromptSuggestion {
+                text: seen.text,
+                reason,
+                occurrences: seen.occurrences,
+                sessions: seen.sessions.len(),
+                last_used: seen.last_used,
+            })
+        })
+        .collect();
+    // Repeats first, then longer texts; newest breaks ties
+    suggestions.sort_by(|a, b| {
+        b.occurrences
+            .cmp(&a.occurrences)
+            .then_with(|| b.text.len().cmp(&a.text.len()))
+            .then_with(|| b.last_used.cmp(&a.last_used))
+    });
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Session ids of a project, most recently modified first
+fn recent_session_ids(project_dir: &Path) -> Vec<String> {
+    let mut sessions: Vec<(u64, String)> = fs::read_dir(project_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".jsonl")?;
+            if id.starts_with("agent-") {
+                return None;
+            }
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            Some((modified, id.to_string()))
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.cmp(a));
+    sessions
+        .into_iter()
+        .take(MAX_SESSIONS)
+        .map(|(_, id)| id)
+        .collect()
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn save_prompt(text: String, tags: Option<Vec<String>>) -> AppResult<SavedPrompt> {
+    tauri::async_runtime::spawn_blocking(move || {
+        save_prompt_in(&prompts_path(), &text, tags.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Saved prompts, optionally only those with `tag`
+#[tauri::command]
+pub async fn list_prompts(tag: Option<String>) -> AppResult<Vec<SavedPrompt>> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        list_prompts_in(&prompts_path(), tag.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[tauri::command]
+pub async fn delete_prompt(id: String) -> AppResult<()> {
+    tauri::async_runtime::spawn_blocking(move || {
+        update_prompt(&prompts_path(), &id, |prompts, index| {
+            prompts.remove(index);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Record a use of the prompt and return the shell command that starts Claude Code with
+/// it, ready to pass to `pty_create` as `command`
+#[tauri::command]
+pub async fn use_prompt(id: String) -> AppResult<String> {
+    tauri::async_runtime::spawn_blocking(move || use_prompt_in(&prompts_path(), &id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Candidates for the library from a project's recent sessions: messages sent more than
+/// once and long, detailed instructions. Prompts already saved are left out.
+#[tauri::command]
+pub async fn suggest_prompts_from_history(project_id: String) -> AppResult<Vec<PromptSuggestion>> {
+    let project_dir = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(&project_id);
+    if project_id.is_empty()
+        || project_id.contains(['/', '\\'])
+        || project_id.contains("..")
+        || !project_dir.is_dir()
+    {
+        return Err(AppError::not_found("Project not found").with_context(project_id));
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let sessions: Vec<(String, Vec<Message>)> = recent_session_ids(&project_dir)
+            .into_iter()
+            .filter_map(|id| {
+                let messages = read_session_messages(&project_id, &id).ok()?;
+                Some((id, messages))
+            })
+            .collect();
+        let saved: HashSet<String> = load_prompts(&prompts_path())?
+            .prompts
+            .iter()
+            .map(|p| normalize_text(&p.text))
+            .collect();
+        Ok::<_, String>(mine_suggestions(&sessions, &saved))
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn user(content: &str, timestamp: &str) -> Message {
+        Message {
+            uuid: String::new(),
+            role: "user".to_string(),
+            content: content.to_string(),
+            timestamp: timestamp.to_string(),
+            is_meta: false,
+            is_tool: false,
+            line_number: 1,
+            thinking: None,
+        }
+    }
+
+    #[test]
+    fn test_save_list_and_use_prompts() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("prompts.json");
+
+        let review = save_prompt_in(&path, " Review this diff ", vec!["Review".to_string()])
+            .expect("save review");
+        assert_eq!(review.text, "Review this diff");
+        save_prompt_in(&path, "Write tests", vec![]).expect("save tests");
+        let merged = save_prompt_in(
+            &path,
+            "Review this diff",
+            vec!["git".into(), "REVIEW".into()],
+        )
+        .expect("save again");
+        assert_eq!(merged.id, review.id);
+        assert_eq!(merged.tags, vec!["review", "git"]);
+        assert!(save_prompt_in(&path, "  ", vec![]).is_err());
+
+        let tagged = list_prompts_in(&path, Some("Git")).expect("list by tag");
+        assert_eq!(tagged.len(), 1);
+
+        let command = use_prompt_in(&path, &review.id).expect("use");
+        assert_eq!(command, "claude 'Review this diff'");
+        let used = list_prompts_in(&path, None)
+            .expect("list")
+            .into_iter()
+            .find(|p| p.id == review.id)
+            .expect("review prompt");
+        assert_eq!(used.use_count, 1);
+        assert!(use_prompt_in(&path, "missing").is_err());
+    }
+
+    #[test]
+    fn test_mine_suggestions() {
+        let long = format!(
+            "Refactor the parser so that {}",
+            "each rule is testable. ".repeat(15)
+        );
+        let sessions = vec![
+            (
+                "s1".to_string(),
+                vec![
+                    user("Run the tests and fix failures", "2026-10-01T00:00:00Z"),
+                    user(&long, "2026-10-01T00:01:00Z"),
+                    user("/review", "2026-10-01T00:02:00Z"),
+                    user("short", "2026-10-01T00:03:00Z"),
+                ],
+            ),
+            (
+                "s2".to_string(),
+                vec![
+                    user("run the tests  and fix failures", "2026-10-02T00:00:00Z"),
+                    user("Explain this function once", "2026-10-02T00:01:00Z"),
+                    user("Already saved prompt text", "2026-10-02T00:02:00Z"),
+                ],
+            ),
+        ];
+        let saved = HashSet::from([normalize_text("Already saved prompt text")]);
+
+        let suggestions = mine_suggestions(&sessions, &saved);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].reason, SuggestionReason::Frequent);
+        assert_eq!(suggestions[0].text, "run the tests  and fix failures");
+        assert_eq!(
+            (suggestions[0].occurrences, suggestions[0].sessions),
+            (2, 2)
+        );
+        assert_eq!(
+            suggestions[0].last_used.as_deref(),
+            Some("2026-10-02T00:00:00Z")
+        );
+        assert_eq!(suggestions[1].reason, SuggestionReason::Long);
+        assert_eq!(suggestions[1].text, long.trim());
+    }
+}
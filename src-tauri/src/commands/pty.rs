@@ -1,10 +1,14 @@
 /**
- * [INPUT]: 依赖 pty_manager 模块的会话管理功能
+ * [INPUT]: 依赖 pty_manager 模块的会话管理功能 (含 Claude 状态检测, OSC 目录/标题跟踪, pty-data 批量窗口)
  * [OUTPUT]: 对外提供 PTY 终端相关的 Tauri 命令
  * [POS]: commands/ 模块成员，处理伪终端会话的创建、读写、调整大小等
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
-use crate::pty_manager;
+use crate::errors::AppResult;
+use crate::pty_manager::{
+    self, PtyBatchSettings, PtyExitStatus, PtyInfo, PtySessionInfo, PtyState,
+};
+use std::collections::BTreeMap;
 
 // ============================================================================
 // PTY Terminal Commands
@@ -16,31 +20,52 @@ pub fn pty_create(
     cwd: String,
     shell: Option<String>,
     command: Option<String>,
-) -> Result<String, String> {
-    pty_manager::create_session(id.clone(), cwd, shell, command)?;
+    env: Option<BTreeMap<String, String>>,
+) -> AppResult<String> {
+    pty_manager::create_session(id.clone(), cwd, shell, command, env.unwrap_or_default())?;
     Ok(id)
 }
 
+/// Open a terminal on a remote host via the local `ssh` binary; `cwd` and `command`
+/// apply on the remote side
 #[tauri::command]
-pub fn pty_write(id: String, data: Vec<u8>) -> Result<(), String> {
+pub fn pty_create_ssh(
+    id: String,
+    host_alias: String,
+    cwd: Option<String>,
+    command: Option<String>,
+) -> AppResult<String> {
+    pty_manager::create_ssh_session(id.clone(), host_alias, cwd, command)?;
+    Ok(id)
+}
+
+/// Restart a session that died with the app, with its original cwd/shell/command
+#[tauri::command]
+pub fn pty_respawn(id: String) -> AppResult<String> {
+    pty_manager::respawn_session(&id)?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn pty_write(id: String, data: Vec<u8>) -> AppResult<()> {
     pty_manager::write_to_session(&id, &data)
 }
 
 #[tauri::command]
 #[allow(deprecated)]
-pub fn pty_read(id: String) -> Result<Vec<u8>, String> {
+pub fn pty_read(id: String) -> AppResult<Vec<u8>> {
     // Legacy - data now comes via pty-data events
-    pty_manager::read_from_session(&id)
+    Ok(pty_manager::read_from_session(&id)?)
 }
 
 #[tauri::command]
-pub fn pty_resize(id: String, cols: u16, rows: u16) -> Result<(), String> {
+pub fn pty_resize(id: String, cols: u16, rows: u16) -> AppResult<()> {
     pty_manager::resize_session(&id, cols, rows)
 }
 
 #[tauri::command]
-pub fn pty_kill(id: String) -> Result<(), String> {
-    pty_manager::kill_session(&id)
+pub fn pty_kill(id: String) -> AppResult<()> {
+    Ok(pty_manager::kill_session(&id)?)
 }
 
 #[tauri::command]
@@ -53,6 +78,35 @@ pub fn pty_exists(id: String) -> bool {
     pty_manager::session_exists(&id)
 }
 
+/// Session inventory, including exited sessions that were not killed yet
+#[tauri::command]
+pub fn pty_list_sessions() -> Vec<PtySessionInfo> {
+    pty_manager::list_session_info()
+}
+
+/// Claude Code state detected from the session's output (also pushed as `pty-claude-state`)
+#[tauri::command]
+pub fn pty_get_state(id: String) -> AppResult<PtyState> {
+    pty_manager::session_state(&id)
+}
+
+/// Current directory and title reported by the shell (also pushed as `pty-info-changed`)
+#[tauri::command]
+pub fn pty_get_info(id: String) -> AppResult<PtyInfo> {
+    pty_manager::get_pty_info(&id)
+}
+
+/// How the session's process ended (the `pty-exit` payload); null while it runs
+#[tauri::command]
+pub fn pty_get_exit_status(id: String) -> AppResult<Option<PtyExitStatus>> {
+    pty_manager::get_pty_exit_status(&id)
+}
+
+#[tauri::command]
+pub fn pty_is_alive(id: String) -> bool {
+    pty_manager::session_alive(&id)
+}
+
 #[tauri::command]
 pub fn pty_scrollback(id: String) -> Vec<u8> {
     pty_manager::get_scrollback(&id)
@@ -67,3 +121,15 @@ pub fn pty_purge_scrollback(id: String) {
 pub fn pty_flush_scrollback() {
     pty_manager::flush_all_scrollback()
 }
+
+/// How long terminal output is coalesced before it is sent as one pty-data event
+#[tauri::command]
+pub fn pty_get_batch_settings() -> PtyBatchSettings {
+    pty_manager::batch_settings()
+}
+
+/// Change the pty-data batch window (0-50 ms, 0 disables batching); applies to running sessions
+#[tauri::command]
+pub fn pty_set_batch_settings(settings: PtyBatchSettings) -> AppResult<()> {
+    pty_manager::set_batch_settings(settings)
+}
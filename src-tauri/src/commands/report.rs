@@ -1,17 +1,23 @@
 /**
- * [INPUT]: 依赖 std::fs, std::collections, serde, serde_json, chrono, regex, tauri
- * [OUTPUT]: 对外提供 get_activity_stats, get_annual_report_2025, get_command_stats, get_command_weekly_stats 命令
+ * [INPUT]: 依赖 std::fs, std::collections, serde, serde_json, chrono, regex, tauri, rayon + crate::scan (并行刷新报告缓存), crate::jsonl (流式读取会话), crate::store (command-stats.json)
+ * [OUTPUT]: 对外提供 get_activity_stats, get_period_report, get_report_period, get_annual_report, get_model_usage_stats, get_tool_usage_stats, get_api_error_stats, get_session_metrics_summary, get_project_stats, get_cost_report, get_price_table, update_price_table, get_command_stats, get_command_weekly_stats, reset_command_stats, prune_command_stats, recount_command_stats 命令, load_command_usage 用量聚合, save_command_stats 写入周索引, aggregate_activity 与 longest_streak 活跃度工具
  * [POS]: commands/ 模块的报告和统计命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
 use crate::commands::list_local_commands;
+use crate::commands::session_metrics::{median, MetricsCollector, SessionMetrics};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::jsonl;
+use crate::scan;
 use crate::security;
+use crate::store::{self, Schema};
+use rayon::prelude::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::sync::Mutex;
 
@@ -20,6 +26,15 @@ static COMMAND_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"<command-name>(/[^<]+)</command-name>").expect("COMMAND_NAME_RE should compile")
 });
 
+/// HTTP status in API error text: "API Error: 529 {...}" or "Request rejected (429)"
+static API_ERROR_STATUS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:API Error: |\()(\d{3})\b").expect("API_ERROR_STATUS_RE should compile")
+});
+
+/// Week keys in the command-stats index ("2025-W07")
+static WEEK_KEY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d{4}-W\d{2}$").expect("WEEK_KEY_RE should compile"));
+
 // ============================================================================
 // Path Helper Functions
 // ============================================================================
@@ -31,6 +46,86 @@ fn get_command_stats_path() -> PathBuf {
         .join("command-stats.json")
 }
 
+/// Schema of command-stats.json
+const COMMAND_STATS_SCHEMA: Schema = Schema::new(1);
+
+/// The command-stats index, or None if it is missing or unreadable
+fn read_command_stats(stats_path: &Path) -> Option<serde_json::Value> {
+    store::load(stats_path, &COMMAND_STATS_SCHEMA).unwrap_or_else(|e| {
+        tracing::warn!("{}", e);
+        None
+    })
+}
+
+/// Replace the command-stats index with weekly counts and last-used timestamps
+pub fn save_command_stats(
+    weekly: &HashMap<String, HashMap<String, usize>>,
+    last_used: &HashMap<String, i64>,
+) -> Result<(), String> {
+    let stats_json = serde_json::json!({
+        "updated_at": chrono::Utc::now().timestamp(),
+        "commands": weekly,
+        "last_used": last_used,
+    });
+    store::save(
+        &get_command_stats_path(),
+        &COMMAND_STATS_SCHEMA,
+        &stats_json,
+    )
+}
+
+fn get_price_table_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("pricing.json")
+}
+
+fn get_report_cache_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("lovcode")
+        .join("report-cache.json")
+}
+
+/// Total usage and last-used timestamp per command from the command-stats index.
+/// Keys are normalized to slash form without the leading "/" (e.g. "git/commit").
+pub fn load_command_usage() -> HashMap<String, (usize, Option<i64>)> {
+    load_command_usage_from(&get_command_stats_path())
+}
+
+pub fn load_command_usage_from(stats_path: &Path) -> HashMap<String, (usize, Option<i64>)> {
+    let Some(parsed) = read_command_stats(stats_path) else {
+        return HashMap::new();
+    };
+
+    let last_used = parsed.get("last_used").and_then(|v| v.as_object());
+    let mut usage: HashMap<String, (usize, Option<i64>)> = HashMap::new();
+
+    if let Some(commands) = parsed.get("commands").and_then(|v| v.as_object()) {
+        for (cmd_name, week_data) in commands {
+            let total: usize = week_data.as_object().map_or(0, |weeks| {
+                weeks
+                    .values()
+                    .filter_map(serde_json::Value::as_u64)
+                    .map(|n| n as usize)
+                    .sum()
+            });
+            let ts = last_used
+                .and_then(|m| m.get(cmd_name))
+                .and_then(serde_json::Value::as_i64);
+
+            let entry = usage.entry(normalize_command_key(cmd_name)).or_insert((0, None));
+            entry.0 += total;
+            entry.1 = entry.1.max(ts);
+        }
+    }
+
+    usage
+}
+
+/// "/git:commit" and "git/commit" both refer to commands/git/commit.md
+pub fn normalize_command_key(name: &str) -> String {
+    name.trim_start_matches('/').replace(':', "/")
+}
+
 // ============================================================================
 // Global State
 // ============================================================================
@@ -43,6 +138,61 @@ static COMMAND_STATS_CACHE: LazyLock<Mutex<CommandStatsCache>> =
 struct CommandStatsCache {
     stats: HashMap<String, usize>,
     scanned: HashMap<String, u64>,
+    /// Bumped by reset/recount so an in-flight scan doesn't write stale counts back
+    generation: u64,
+}
+
+// Per-session-file report aggregates, mirrored to report-cache.json
+static REPORT_CACHE: LazyLock<Mutex<Option<ReportCache>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Bumped whenever SessionAggregate changes shape so stale caches are rebuilt
+const REPORT_CACHE_VERSION: u32 = 5;
+const HOUR_MS: u64 = 3600 * 1000;
+/// Days in the project stats sparkline
+const SPARKLINE_DAYS: usize = 30;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReportCache {
+    version: u32,
+    /// Session file path -> aggregate
+    files: HashMap<String, SessionAggregate>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionAggregate {
+    project_id: String,
+    mtime: u64,
+    size: u64,
+    /// UTC hour index (Unix ms / 3600000) -> activity in that hour
+    hours: BTreeMap<u64, HourBucket>,
+    #[serde(default)]
+    metrics: SessionMetrics,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HourBucket {
+    messages: usize,
+    commands: HashMap<String, usize>,
+    /// Assistant replies per model id
+    #[serde(default)]
+    models: HashMap<String, usize>,
+    /// tool_use blocks per tool name
+    #[serde(default)]
+    tools: HashMap<String, usize>,
+    /// Token usage per model id
+    #[serde(default)]
+    tokens: HashMap<String, TokenUsage>,
+    /// Failed API requests: provider -> error type -> count
+    #[serde(default)]
+    api_errors: HashMap<String, HashMap<String, usize>>,
+}
+
+impl SessionAggregate {
+    fn hours_in(&self, period: ReportPeriod) -> impl Iterator<Item = &HourBucket> {
+        self.hours
+            .range(period.start / HOUR_MS..period.end.div_ceil(HOUR_MS))
+            .map(|(_, bucket)| bucket)
+    }
 }
 
 // ============================================================================
@@ -57,6 +207,12 @@ pub struct ActivityStats {
     pub hourly: HashMap<u32, usize>,
     /// Map of "date:hour" (YYYY-MM-DD:HH) to count for detailed heatmap
     pub detailed: HashMap<String, usize>,
+    /// 7x24 counts: rows are weekdays (0 = Sunday), columns are hours
+    #[serde(default)]
+    pub weekday_hour: Vec<Vec<usize>>,
+    /// Offset all buckets were computed in (minutes east of UTC)
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,8 +229,28 @@ pub struct TopCommand {
     pub count: usize,
 }
 
+/// Half-open time range [start, end) in Unix milliseconds (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportPeriod {
+    pub start: u64,
+    pub end: u64,
+}
+
+const ALL_TIME: ReportPeriod = ReportPeriod {
+    start: 0,
+    end: u64::MAX,
+};
+
+impl ReportPeriod {
+    fn contains(&self, ts_ms: u64) -> bool {
+        ts_ms >= self.start && ts_ms < self.end
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AnnualReport2025 {
+pub struct PeriodReport {
+    pub period_start: u64,
+    pub period_end: u64,
     pub total_sessions: usize,
     pub total_messages: usize,
     pub total_commands: usize,
@@ -92,6 +268,152 @@ pub struct AnnualReport2025 {
     pub hourly_distribution: HashMap<u32, usize>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageShare {
+    pub name: String,
+    pub count: usize,
+    /// Fraction of the total (0.0 - 1.0)
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// Full model id, e.g. "claude-opus-4-1-20250805"
+    pub model: String,
+    /// "opus" | "sonnet" | "haiku" | "other"
+    pub family: String,
+    pub count: usize,
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+impl TokenUsage {
+    fn from_json(usage: &serde_json::Value) -> Self {
+        let field = |name: &str| {
+            usage
+                .get(name)
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0)
+        };
+        Self {
+            input_tokens: field("input_tokens"),
+            output_tokens: field("output_tokens"),
+            cache_creation_input_tokens: field("cache_creation_input_tokens"),
+            cache_read_input_tokens: field("cache_read_input_tokens"),
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+
+    fn cost(&self, price: &ModelPrice) -> f64 {
+        let per_token = |tokens: u64, per_million: f64| tokens as f64 * per_million / 1_000_000.0;
+        per_token(self.input_tokens, price.input)
+            + per_token(self.output_tokens, price.output)
+            + per_token(self.cache_creation_input_tokens, price.cache_write)
+            + per_token(self.cache_read_input_tokens, price.cache_read)
+    }
+}
+
+/// USD per million tokens for models whose id contains `pattern`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub pattern: String,
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CostGroup {
+    /// Project id, ISO week ("2026-W03") or model id
+    pub key: String,
+    /// Human readable key (project path for project grouping)
+    pub label: String,
+    pub cost: f64,
+    pub tokens: TokenUsage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CostReport {
+    pub period_start: u64,
+    pub period_end: u64,
+    pub group_by: String,
+    pub total_cost: f64,
+    pub total_tokens: TokenUsage,
+    /// Sorted by cost descending (chronologically for week grouping)
+    pub groups: Vec<CostGroup>,
+    /// Models with usage but no matching price entry (counted as zero cost)
+    pub unpriced_models: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiErrorStats {
+    pub period_start: u64,
+    pub period_end: u64,
+    pub total: usize,
+    /// Errors per assistant reply attempt (errors + successful replies)
+    pub error_rate: f64,
+    /// "overloaded" | "rate_limit" | "server_error" | "timeout" | "connection" | "auth" |
+    /// "invalid_request" | "other"
+    pub by_type: Vec<UsageShare>,
+    /// Inferred from the model the session was using, e.g. "anthropic", "deepseek"
+    pub by_provider: Vec<UsageShare>,
+    /// 24 counts by local hour of day
+    pub by_hour: Vec<usize>,
+    /// Local date (YYYY-MM-DD) -> count
+    pub daily: BTreeMap<String, usize>,
+}
+
+/// Medians over the sessions that started in the period and have at least one prompt
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionMetricsSummary {
+    pub period_start: u64,
+    pub period_end: u64,
+    pub sessions: usize,
+    pub median_duration_ms: Option<u64>,
+    pub median_user_turns: Option<u64>,
+    pub median_assistant_turns: Option<u64>,
+    /// Median of each session's median response latency
+    pub median_response_ms: Option<u64>,
+    pub median_longest_gap_ms: Option<u64>,
+}
+
+/// Mini dashboard for one project (all time, buckets in local time)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectStats {
+    pub project_id: String,
+    pub path: String,
+    pub total_sessions: usize,
+    pub total_messages: usize,
+    /// Unix ms of the first and last hour with activity
+    pub first_active: Option<u64>,
+    pub last_active: Option<u64>,
+    /// Sessions by the ISO week ("2026-W03") they started in
+    pub sessions_by_week: BTreeMap<String, usize>,
+    pub top_commands: Vec<TopCommand>,
+    pub tokens: TokenUsage,
+    pub cost: f64,
+    /// Models with usage but no matching price entry (counted as zero cost)
+    pub unpriced_models: Vec<String>,
+    /// 24 message counts by local hour of day
+    pub active_hours: Vec<usize>,
+    /// Messages per local day over the last 30 days, oldest first, ending today
+    pub last_30_days: Vec<usize>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CommandStats {
     pub name: String,
@@ -99,339 +421,1241 @@ pub struct CommandStats {
 }
 
 // ============================================================================
-// Commands
+// Period Report Helpers
 // ============================================================================
 
-#[tauri::command]
-pub async fn get_activity_stats() -> Result<ActivityStats, String> {
-    tauri::async_runtime::spawn_blocking(|| {
-        let history_path = security::get_claude_dir_or_fallback().join("history.jsonl");
-        let mut daily: HashMap<String, usize> = HashMap::new();
-        let mut hourly: HashMap<u32, usize> = HashMap::new();
-        let mut detailed: HashMap<String, usize> = HashMap::new();
-
-        if !history_path.exists() {
-            return Ok(ActivityStats {
-                daily,
-                hourly,
-                detailed,
-            });
+fn date_start_ms(date: chrono::NaiveDate) -> u64 {
+    date.and_hms_opt(0, 0, 0)
+        .map_or(0, |dt| dt.and_utc().timestamp_millis().max(0) as u64)
+}
+
+/// year | quarter | month | week (ISO, Monday-based) containing `anchor`
+fn period_for_preset(preset: &str, anchor: chrono::NaiveDate) -> Result<ReportPeriod, String> {
+    use chrono::{Datelike, Days, Months, NaiveDate};
+
+    let (first, last_exclusive) = match preset {
+        "year" => {
+            let first = NaiveDate::from_ymd_opt(anchor.year(), 1, 1);
+            (
+                first,
+                first.and_then(|d| d.checked_add_months(Months::new(12))),
+            )
+        }
+        "quarter" => {
+            let month = (anchor.month0() / 3) * 3 + 1;
+            let first = NaiveDate::from_ymd_opt(anchor.year(), month, 1);
+            (
+                first,
+                first.and_then(|d| d.checked_add_months(Months::new(3))),
+            )
+        }
+        "month" => {
+            let first = anchor.with_day(1);
+            (
+                first,
+                first.and_then(|d| d.checked_add_months(Months::new(1))),
+            )
         }
+        "week" => {
+            let first = anchor.checked_sub_days(Days::new(u64::from(
+                anchor.weekday().num_days_from_monday(),
+            )));
+            (first, first.and_then(|d| d.checked_add_days(Days::new(7))))
+        }
+        _ => return Err(format!("Unknown report period preset: {}", preset)),
+    };
 
-        if let Ok(content) = fs::read_to_string(&history_path) {
-            for line in content.lines() {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
-                    if let Some(ts_ms) = parsed.get("timestamp").and_then(|v| v.as_u64()) {
-                        let ts_secs = ts_ms / 1000;
-                        if let Some(dt) = chrono::DateTime::from_timestamp(ts_secs as i64, 0) {
-                            // Daily count
-                            let date = dt.format("%Y-%m-%d").to_string();
-                            *daily.entry(date.clone()).or_insert(0) += 1;
+    match (first, last_exclusive) {
+        (Some(first), Some(end)) => Ok(ReportPeriod {
+            start: date_start_ms(first),
+            end: date_start_ms(end),
+        }),
+        _ => Err(format!("Report period out of range for {}", anchor)),
+    }
+}
 
-                            // Hourly count (0-23)
-                            let hour = dt.format("%H").to_string().parse::<u32>().unwrap_or(0);
-                            *hourly.entry(hour).or_insert(0) += 1;
+/// Whether an index week key ("2025-W07") overlaps the period
+fn week_key_in_period(key: &str, period: ReportPeriod) -> bool {
+    let Some((year, week)) = key.split_once("-W") else {
+        return false;
+    };
+    let (Ok(year), Ok(week)) = (year.parse::<i32>(), week.parse::<u32>()) else {
+        return false;
+    };
+    let Some(monday) = chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon) else {
+        return false;
+    };
+    let start = date_start_ms(monday);
+    start < period.end && start + 7 * 24 * 3600 * 1000 > period.start
+}
 
-                            // Detailed: date + hour
-                            let date_hour = format!("{}:{:02}", date, hour);
-                            *detailed.entry(date_hour).or_insert(0) += 1;
-                        }
-                    }
+/// Longest run of consecutive YYYY-MM-DD keys
+pub fn longest_streak(daily: &HashMap<String, usize>) -> usize {
+    let mut dates: Vec<chrono::NaiveDate> = daily
+        .keys()
+        .filter_map(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .collect();
+    dates.sort();
+
+    let mut longest = 0usize;
+    let mut current = 0usize;
+    let mut prev: Option<chrono::NaiveDate> = None;
+    for date in dates {
+        current = match prev {
+            Some(p) if date.signed_duration_since(p).num_days() == 1 => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        prev = Some(date);
+    }
+    longest
+}
+
+fn top_n(counts: HashMap<String, usize>, n: usize) -> Vec<TopCommand> {
+    let mut sorted: Vec<_> = counts.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sorted
+        .into_iter()
+        .take(n)
+        .map(|(name, count)| TopCommand { name, count })
+        .collect()
+}
+
+/// Command usage within the period from the weekly command-stats index
+fn indexed_command_counts(stats_path: &Path, period: ReportPeriod) -> HashMap<String, usize> {
+    let mut aggregated: HashMap<String, usize> = HashMap::new();
+    let Some(parsed) = read_command_stats(stats_path) else {
+        return aggregated;
+    };
+
+    if let Some(commands) = parsed.get("commands").and_then(|v| v.as_object()) {
+        for (cmd_name, week_data) in commands {
+            let Some(weeks) = week_data.as_object() else {
+                continue;
+            };
+            let total: usize = weeks
+                .iter()
+                .filter(|(week, _)| week_key_in_period(week, period))
+                .filter_map(|(_, v)| v.as_u64())
+                .map(|n| n as usize)
+                .sum();
+            if total > 0 {
+                *aggregated.entry(cmd_name.clone()).or_insert(0) += total;
+            }
+        }
+    }
+    aggregated
+}
+
+/// Real project path from project.json, falling back to the encoded directory name
+fn project_actual_path(projects_dir: &std::path::Path, project_id: &str) -> String {
+    fs::read_to_string(projects_dir.join(project_id).join("project.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|v| v.get("path").and_then(|p| p.as_str()).map(String::from))
+        .unwrap_or_else(|| project_id.to_string())
+}
+
+fn file_signature(path: &std::path::Path) -> Option<(u64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis() as u64;
+    Some((mtime, meta.len()))
+}
+
+/// Bucket a session file's messages and commands by UTC hour
+fn aggregate_session_file(path: &std::path::Path, project_id: &str) -> SessionAggregate {
+    let mut aggregate = SessionAggregate {
+        project_id: project_id.to_string(),
+        ..SessionAggregate::default()
+    };
+    let Ok(lines) = jsonl::open(path) else {
+        return aggregate;
+    };
+    let mut metrics = MetricsCollector::default();
+    let mut seen_message_ids: std::collections::HashSet<String> = std::collections::HashSet::new();
+    // Errors are attributed to the provider of the last real reply before them
+    let mut last_model: Option<String> = None;
+
+    for (_, parsed) in lines.records::<serde_json::Value>() {
+        metrics.add_line(&parsed);
+        let Some(ts_ms) = parsed
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .map(|dt| dt.timestamp_millis().max(0) as u64)
+        else {
+            continue;
+        };
+        let bucket = aggregate.hours.entry(ts_ms / HOUR_MS).or_default();
+
+        // Count non-meta messages
+        if parsed.get("type").and_then(|t| t.as_str()) != Some("meta") {
+            bucket.messages += 1;
+        }
+        if let Some(kind) = api_error_kind(&parsed) {
+            let provider = last_model.as_deref().map_or("unknown", model_provider);
+            *bucket
+                .api_errors
+                .entry(provider.to_string())
+                .or_default()
+                .entry(kind.to_string())
+                .or_insert(0) += 1;
+        }
+        let Some(message) = parsed.get("message") else {
+            continue;
+        };
+        // Extract commands from messages (for fallback stats)
+        if let Some(text) = message.get("content").and_then(|c| c.as_str()) {
+            for cap in COMMAND_NAME_RE.captures_iter(text) {
+                if let Some(cmd_match) = cap.get(1) {
+                    let cmd = cmd_match.as_str().trim_start_matches('/').to_string();
+                    *bucket.commands.entry(cmd).or_insert(0) += 1;
+                }
+            }
+        }
+
+        // Each content block of an assistant reply is logged as its own line with the
+        // same message id, so the model is counted once per reply
+        let first_block = message
+            .get("id")
+            .and_then(|v| v.as_str())
+            .is_none_or(|id| seen_message_ids.insert(id.to_string()));
+        if let Some(model) = message.get("model").and_then(|v| v.as_str()) {
+            if model != "<synthetic>" {
+                last_model = Some(model.to_string());
+            }
+            if first_block && model != "<synthetic>" {
+                *bucket.models.entry(model.to_string()).or_insert(0) += 1;
+                if let Some(usage) = message.get("usage") {
+                    bucket
+                        .tokens
+                        .entry(model.to_string())
+                        .or_default()
+                        .add(&TokenUsage::from_json(usage));
+                }
+            }
+        }
+        for block in message
+            .get("content")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if block.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
+                if let Some(name) = block.get("name").and_then(|n| n.as_str()) {
+                    *bucket.tools.entry(name.to_string()).or_insert(0) += 1;
                 }
             }
         }
+    }
+    aggregate.metrics = metrics.finish();
+    aggregate
+}
 
-        Ok(ActivityStats {
-            daily,
-            hourly,
-            detailed,
+/// Reparse session files whose mtime/size changed and drop deleted ones.
+/// Stale files are parsed in parallel on the scan pool.
+/// Returns whether the cache was modified.
+fn refresh_report_cache(cache: &mut ReportCache, projects_dir: &std::path::Path) -> bool {
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut stale: Vec<(String, PathBuf, String, (u64, u64))> = Vec::new();
+
+    for project in scan::project_dirs(projects_dir, true) {
+        for session_path in project.sessions {
+            let Some(signature) = file_signature(&session_path) else {
+                continue;
+            };
+            let key = session_path.to_string_lossy().to_string();
+            seen.insert(key.clone());
+
+            let fresh = cache
+                .files
+                .get(&key)
+                .is_some_and(|f| (f.mtime, f.size) == signature);
+            if !fresh {
+                stale.push((key, session_path, project.id.clone(), signature));
+            }
+        }
+    }
+
+    let changed = !stale.is_empty();
+    let parsed: Vec<(String, SessionAggregate)> = scan::install(|| {
+        stale
+            .into_par_iter()
+            .map(|(key, path, project_id, (mtime, size))| {
+                let mut aggregate = aggregate_session_file(&path, &project_id);
+                aggregate.mtime = mtime;
+                aggregate.size = size;
+                (key, aggregate)
+            })
+            .collect()
+    });
+    cache.files.extend(parsed);
+
+    let before = cache.files.len();
+    cache.files.retain(|key, _| seen.contains(key));
+    changed || cache.files.len() != before
+}
+
+fn load_report_cache(path: &PathBuf) -> ReportCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<ReportCache>(&content).ok())
+        .filter(|cache| cache.version == REPORT_CACHE_VERSION)
+        .unwrap_or_else(|| ReportCache {
+            version: REPORT_CACHE_VERSION,
+            files: HashMap::new(),
         })
-    })
-    .await
-    .map_err(|e| e.to_string())?
 }
 
-#[tauri::command]
-pub async fn get_annual_report_2025() -> Result<AnnualReport2025, String> {
-    tauri::async_runtime::spawn_blocking(|| {
-        use chrono::{Datelike, Timelike};
+fn save_report_cache(path: &PathBuf, cache: &ReportCache) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string(cache).map_err(|e| e.to_string())?;
+    security::atomic_write_string(path, &content).map_err(|e| e.to_string())
+}
 
-        // 2025 year bounds (UTC)
-        let start_2025: u64 = 1735689600000; // 2025-01-01 00:00:00 UTC in ms
-        let end_2025: u64 = 1767225600000; // 2026-01-01 00:00:00 UTC in ms
+/// Bring the in-memory report cache up to date (loading it from disk on first use)
+/// and run `f` against it
+fn with_report_cache<T>(f: impl FnOnce(&ReportCache) -> T) -> Result<T, String> {
+    let mut guard = REPORT_CACHE
+        .lock()
+        .map_err(|_| "Report cache lock poisoned")?;
+    let path = get_report_cache_path();
+    let cache = guard.get_or_insert_with(|| load_report_cache(&path));
+
+    let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+    if refresh_report_cache(cache, &projects_dir) {
+        if let Err(e) = save_report_cache(&path, cache) {
+            tracing::warn!("Failed to save report cache: {}", e);
+        }
+    }
+    Ok(f(cache))
+}
 
-        let history_path = security::get_claude_dir_or_fallback().join("history.jsonl");
-        let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+fn model_family(model: &str) -> &'static str {
+    let lower = model.to_lowercase();
+    ["opus", "sonnet", "haiku"]
+        .into_iter()
+        .find(|family| lower.contains(family))
+        .unwrap_or("other")
+}
 
-        let mut daily_activity: HashMap<String, usize> = HashMap::new();
-        let mut hourly_distribution: HashMap<u32, usize> = HashMap::new();
-        let mut weekday_counts: HashMap<u32, usize> = HashMap::new();
-        let mut first_date: Option<String> = None;
-        let mut last_date: Option<String> = None;
-
-        // Parse history.jsonl for 2025 data
-        if history_path.exists() {
-            if let Ok(content) = fs::read_to_string(&history_path) {
-                for line in content.lines() {
-                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
-                        if let Some(ts_ms) = parsed.get("timestamp").and_then(|v| v.as_u64()) {
-                            // Filter for 2025 only
-                            if ts_ms >= start_2025 && ts_ms < end_2025 {
-                                let ts_secs = ts_ms / 1000;
-                                if let Some(dt) =
-                                    chrono::DateTime::from_timestamp(ts_secs as i64, 0)
-                                {
-                                    let date = dt.format("%Y-%m-%d").to_string();
-                                    *daily_activity.entry(date.clone()).or_insert(0) += 1;
-
-                                    let hour = dt.hour();
-                                    *hourly_distribution.entry(hour).or_insert(0) += 1;
-
-                                    let weekday = dt.weekday().num_days_from_sunday();
-                                    *weekday_counts.entry(weekday).or_insert(0) += 1;
-
-                                    // Track first and last dates
-                                    if first_date.as_ref().map_or(true, |d| &date < d) {
-                                        first_date = Some(date.clone());
-                                    }
-                                    if last_date.as_ref().map_or(true, |d| &date > d) {
-                                        last_date = Some(date.clone());
-                                    }
-                                }
-                            }
-                        }
-                    }
+/// Provider family of a model id; relays serving Claude models count as "anthropic"
+fn model_provider(model: &str) -> &'static str {
+    let lower = model.to_lowercase();
+    [
+        (&["claude", "opus", "sonnet", "haiku"][..], "anthropic"),
+        (&["deepseek"], "deepseek"),
+        (&["glm", "zhipu"], "zhipu"),
+        (&["kimi", "moonshot"], "moonshot"),
+        (&["qwen"], "qwen"),
+        (&["minimax"], "minimax"),
+        (&["doubao"], "doubao"),
+        (&["gemini"], "google"),
+        (&["gpt", "o1", "o3", "o4"], "openai"),
+    ]
+    .into_iter()
+    .find(|(needles, _)| needles.iter().any(|n| lower.contains(n)))
+    .map_or("other", |(_, provider)| provider)
+}
+
+/// Error type of a failed API request recorded in a session line, if it is one.
+/// Claude Code logs each retried attempt as a `system` line with subtype `api_error`,
+/// and a request that finally failed as a synthetic assistant message
+/// (`isApiErrorMessage`) reading "API Error: ...".
+fn api_error_kind(line: &serde_json::Value) -> Option<&'static str> {
+    let (status, detail) = if line.get("subtype").and_then(|s| s.as_str()) == Some("api_error") {
+        let error = line.get("error");
+        let status = error
+            .and_then(|e| e.get("status"))
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|s| u16::try_from(s).ok());
+        let detail = [error, line.get("cause")]
+            .into_iter()
+            .flatten()
+            .map(serde_json::Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        (status, detail)
+    } else if line.get("isApiErrorMessage") == Some(&serde_json::Value::Bool(true)) {
+        let content = line.get("message").and_then(|m| m.get("content"));
+        let detail = match content {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(serde_json::Value::Array(blocks)) => blocks
+                .iter()
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        };
+        let status = API_ERROR_STATUS_RE
+            .captures(&detail)
+            .and_then(|c| c.get(1))
+            .and_then(|m| m.as_str().parse().ok());
+        (status, detail)
+    } else {
+        return None;
+    };
+    classify_api_error(status, &detail)
+}
+
+/// None for requests the user interrupted, which aren't failures
+fn classify_api_error(status: Option<u16>, detail: &str) -> Option<&'static str> {
+    let lower = detail.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+    let kind = match status {
+        Some(529) => "overloaded",
+        Some(429) => "rate_limit",
+        Some(401 | 403) => "auth",
+        Some(408 | 504) => "timeout",
+        Some(500..=599) if has(&["overloaded"]) => "overloaded",
+        Some(500..=599) => "server_error",
+        Some(400..=499) => "invalid_request",
+        _ if has(&["aborted", "interrupted"]) => return None,
+        _ if has(&["overloaded"]) => "overloaded",
+        _ if has(&["rate_limit", "rate limit", "too many requests"]) => "rate_limit",
+        _ if has(&["timeout", "timed out", "etimedout"]) => "timeout",
+        _ if has(&["connection", "econnreset", "econnrefused", "fetch failed"]) => "connection",
+        _ if has(&["authentication", "api key", "unauthorized", "oauth"]) => "auth",
+        _ if has(&["invalid_request", "prompt is too long"]) => "invalid_request",
+        _ if has(&["api_error", "internal server error"]) => "server_error",
+        _ => "other",
+    };
+    Some(kind)
+}
+
+fn build_api_error_stats(
+    cache: &ReportCache,
+    period: ReportPeriod,
+    offset: Option<chrono::FixedOffset>,
+) -> ApiErrorStats {
+    use chrono::Timelike;
+
+    let mut by_type: HashMap<String, usize> = HashMap::new();
+    let mut by_provider: HashMap<String, usize> = HashMap::new();
+    let mut by_hour = vec![0usize; 24];
+    let mut daily: BTreeMap<String, usize> = BTreeMap::new();
+    let mut replies = 0usize;
+
+    for file in cache.files.values() {
+        for (hour, bucket) in file
+            .hours
+            .range(period.start / HOUR_MS..period.end.div_ceil(HOUR_MS))
+        {
+            replies += bucket.models.values().sum::<usize>();
+            let count: usize = bucket.api_errors.values().flat_map(|k| k.values()).sum();
+            if count == 0 {
+                continue;
+            }
+            for (provider, kinds) in &bucket.api_errors {
+                for (kind, n) in kinds {
+                    *by_type.entry(kind.clone()).or_insert(0) += n;
+                    *by_provider.entry(provider.clone()).or_insert(0) += n;
                 }
             }
+            let Some(utc) = chrono::DateTime::from_timestamp_millis((hour * HOUR_MS) as i64) else {
+                continue;
+            };
+            let local = match offset {
+                Some(offset) => utc.with_timezone(&offset).naive_local(),
+                None => utc.with_timezone(&chrono::Local).naive_local(),
+            };
+            by_hour[local.hour() as usize] += count;
+            *daily
+                .entry(local.format("%Y-%m-%d").to_string())
+                .or_insert(0) += count;
         }
+    }
 
-        // Calculate peak hour
-        let (peak_hour, peak_hour_count) = hourly_distribution
-            .iter()
-            .max_by_key(|(_, count)| *count)
-            .map(|(h, c)| (*h, *c))
-            .unwrap_or((0, 0));
+    let total: usize = by_type.values().sum();
+    ApiErrorStats {
+        period_start: period.start,
+        period_end: period.end,
+        total,
+        error_rate: if total == 0 {
+            0.0
+        } else {
+            total as f64 / (total + replies) as f64
+        },
+        by_type: usage_shares(by_type),
+        by_provider: usage_shares(by_provider),
+        by_hour,
+        daily,
+    }
+}
 
-        // Calculate peak weekday
-        let peak_weekday = weekday_counts
-            .iter()
-            .max_by_key(|(_, count)| *count)
-            .map(|(d, _)| *d)
-            .unwrap_or(0);
-
-        // Calculate longest streak
-        let mut dates: Vec<&String> = daily_activity.keys().collect();
-        dates.sort();
-        let mut longest_streak = 0usize;
-        let mut current_streak = 1usize;
-        for i in 1..dates.len() {
-            if let (Ok(prev), Ok(curr)) = (
-                chrono::NaiveDate::parse_from_str(dates[i - 1], "%Y-%m-%d"),
-                chrono::NaiveDate::parse_from_str(dates[i], "%Y-%m-%d"),
-            ) {
-                if curr.signed_duration_since(prev).num_days() == 1 {
-                    current_streak += 1;
-                } else {
-                    longest_streak = longest_streak.max(current_streak);
-                    current_streak = 1;
-                }
+fn build_session_metrics_summary(
+    cache: &ReportCache,
+    period: ReportPeriod,
+) -> SessionMetricsSummary {
+    let sessions: Vec<&SessionMetrics> = cache
+        .files
+        .values()
+        .map(|file| &file.metrics)
+        .filter(|m| m.user_turns > 0)
+        .filter(|m| {
+            m.started_at
+                .as_deref()
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+                .is_some_and(|dt| period.contains(dt.timestamp_millis().max(0) as u64))
+        })
+        .collect();
+    let median_of = |value: fn(&SessionMetrics) -> Option<u64>| {
+        median(&mut sessions.iter().filter_map(|m| value(m)).collect::<Vec<_>>())
+    };
+
+    SessionMetricsSummary {
+        period_start: period.start,
+        period_end: period.end,
+        sessions: sessions.len(),
+        median_duration_ms: median_of(|m| Some(m.duration_ms)),
+        median_user_turns: median_of(|m| Some(m.user_turns as u64)),
+        median_assistant_turns: median_of(|m| Some(m.assistant_turns as u64)),
+        median_response_ms: median_of(|m| m.median_response_ms),
+        median_longest_gap_ms: median_of(|m| Some(m.longest_gap_ms)),
+    }
+}
+
+/// Sum one per-hour counter map over every cached session in the period
+fn sum_bucket_counts(
+    cache: &ReportCache,
+    period: ReportPeriod,
+    counts_of: impl Fn(&HourBucket) -> &HashMap<String, usize>,
+) -> HashMap<String, usize> {
+    let mut totals: HashMap<String, usize> = HashMap::new();
+    for file in cache.files.values() {
+        for bucket in file.hours_in(period) {
+            for (name, count) in counts_of(bucket) {
+                *totals.entry(name.clone()).or_insert(0) += count;
             }
         }
-        longest_streak = longest_streak.max(current_streak);
-
-        // Scan projects for session/message counts in 2025
-        let mut total_sessions = 0usize;
-        let mut total_messages = 0usize;
-        let mut project_stats: HashMap<String, (String, usize, usize)> = HashMap::new(); // id -> (path, sessions, messages)
-        let mut command_counts: HashMap<String, usize> = HashMap::new(); // command -> count (fallback)
+    }
+    totals
+}
 
-        if projects_dir.exists() {
-            if let Ok(entries) = fs::read_dir(&projects_dir) {
-                for entry in entries.flatten() {
-                    let project_path = entry.path();
-                    if !project_path.is_dir() {
-                        continue;
-                    }
+/// Sorted by count descending, ties by name
+fn usage_shares(counts: HashMap<String, usize>) -> Vec<UsageShare> {
+    let total: usize = counts.values().sum();
+    let mut shares: Vec<UsageShare> = counts
+        .into_iter()
+        .map(|(name, count)| UsageShare {
+            name,
+            count,
+            share: if total == 0 {
+                0.0
+            } else {
+                count as f64 / total as f64
+            },
+        })
+        .collect();
+    shares.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    shares
+}
 
-                    let project_id = project_path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("")
-                        .to_string();
-
-                    // Read project.json for actual path
-                    let project_json_path = project_path.join("project.json");
-                    let actual_path = if project_json_path.exists() {
-                        fs::read_to_string(&project_json_path)
-                            .ok()
-                            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
-                            .and_then(|v| {
-                                v.get("path").and_then(|p| p.as_str()).map(String::from)
-                            })
-                            .unwrap_or_else(|| project_id.clone())
-                    } else {
-                        project_id.clone()
-                    };
+/// Anthropic list prices; the first entry whose pattern matches wins, so specific
+/// versions come before their family
+fn default_price_table() -> Vec<ModelPrice> {
+    let price = |pattern: &str, input: f64, output: f64| ModelPrice {
+        pattern: pattern.to_string(),
+        input,
+        output,
+        cache_write: input * 1.25,
+        cache_read: input * 0.1,
+    };
+    vec![
+        price("opus-4-5", 5.0, 25.0),
+        price("opus", 15.0, 75.0),
+        price("sonnet", 3.0, 15.0),
+        price("haiku-4-5", 1.0, 5.0),
+        price("haiku", 0.8, 4.0),
+    ]
+}
 
-                    let mut proj_sessions = 0usize;
-                    let mut proj_messages = 0usize;
+fn load_price_table(path: &PathBuf) -> Vec<ModelPrice> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(default_price_table)
+}
 
-                    // Scan session files
-                    if let Ok(session_entries) = fs::read_dir(&project_path) {
-                        for session_entry in session_entries.flatten() {
-                            let session_path = session_entry.path();
-                            if session_path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-                                continue;
-                            }
+fn find_price<'a>(table: &'a [ModelPrice], model: &str) -> Option<&'a ModelPrice> {
+    let model = model.to_lowercase();
+    table
+        .iter()
+        .find(|p| !p.pattern.is_empty() && model.contains(&p.pattern.to_lowercase()))
+}
 
-                            // Check if session has 2025 activity by reading first line
-                            if let Ok(content) = fs::read_to_string(&session_path) {
-                                let mut has_2025_activity = false;
-                                let mut msg_count = 0usize;
-
-                                for line in content.lines() {
-                                    if let Ok(parsed) =
-                                        serde_json::from_str::<serde_json::Value>(line)
-                                    {
-                                        // Check timestamp if available
-                                        if let Some(ts) =
-                                            parsed.get("timestamp").and_then(|v| v.as_str())
-                                        {
-                                            if ts.starts_with("2025-") {
-                                                has_2025_activity = true;
-                                            }
-                                        }
-                                        // Count non-meta messages
-                                        if parsed.get("type").and_then(|t| t.as_str())
-                                            != Some("meta")
-                                        {
-                                            msg_count += 1;
-                                        }
-                                        // Extract commands from assistant messages (for fallback stats)
-                                        if let Some(text) = parsed.get("message").and_then(|m| {
-                                            m.get("content").and_then(|c| c.as_str())
-                                        }) {
-                                            for cap in COMMAND_NAME_RE.captures_iter(text) {
-                                                if let Some(cmd_match) = cap.get(1) {
-                                                    let cmd = cmd_match
-                                                        .as_str()
-                                                        .trim_start_matches('/')
-                                                        .to_string();
-                                                    *command_counts.entry(cmd).or_insert(0) += 1;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
+fn build_cost_report(
+    cache: &ReportCache,
+    projects_dir: &std::path::Path,
+    prices: &[ModelPrice],
+    period: ReportPeriod,
+    group_by: &str,
+) -> Result<CostReport, String> {
+    if !matches!(group_by, "project" | "week" | "model") {
+        return Err(format!("Unknown cost grouping: {}", group_by));
+    }
 
-                                if has_2025_activity {
-                                    proj_sessions += 1;
-                                    proj_messages += msg_count;
-                                }
-                            }
-                        }
+    let mut groups: HashMap<String, CostGroup> = HashMap::new();
+    let mut total_tokens = TokenUsage::default();
+    let mut total_cost = 0.0;
+    let mut unpriced: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+
+    for file in cache.files.values() {
+        for (hour, bucket) in file
+            .hours
+            .range(period.start / HOUR_MS..period.end.div_ceil(HOUR_MS))
+        {
+            for (model, tokens) in &bucket.tokens {
+                let cost = match find_price(prices, model) {
+                    Some(price) => tokens.cost(price),
+                    None => {
+                        unpriced.insert(model.clone());
+                        0.0
                     }
+                };
+                let key = match group_by {
+                    "project" => file.project_id.clone(),
+                    "model" => model.clone(),
+                    _ => chrono::DateTime::from_timestamp_millis((hour * HOUR_MS) as i64)
+                        .map(|dt| dt.format("%G-W%V").to_string())
+                        .unwrap_or_default(),
+                };
+                let group = groups.entry(key.clone()).or_insert_with(|| CostGroup {
+                    label: if group_by == "project" {
+                        project_actual_path(projects_dir, &key)
+                    } else {
+                        key.clone()
+                    },
+                    key,
+                    cost: 0.0,
+                    tokens: TokenUsage::default(),
+                });
+                group.cost += cost;
+                group.tokens.add(tokens);
+                total_cost += cost;
+                total_tokens.add(tokens);
+            }
+        }
+    }
 
-                    if proj_sessions > 0 {
-                        total_sessions += proj_sessions;
-                        total_messages += proj_messages;
-                        project_stats
-                            .insert(project_id, (actual_path, proj_sessions, proj_messages));
+    let mut groups: Vec<CostGroup> = groups.into_values().collect();
+    if group_by == "week" {
+        groups.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        groups.sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.key.cmp(&b.key)));
+    }
+
+    Ok(CostReport {
+        period_start: period.start,
+        period_end: period.end,
+        group_by: group_by.to_string(),
+        total_cost,
+        total_tokens,
+        groups,
+        unpriced_models: unpriced.into_iter().collect(),
+    })
+}
+
+fn build_project_stats(
+    cache: &ReportCache,
+    projects_dir: &std::path::Path,
+    prices: &[ModelPrice],
+    project_id: &str,
+    offset: Option<chrono::FixedOffset>,
+    today: chrono::NaiveDate,
+) -> ProjectStats {
+    use chrono::Timelike;
+
+    let to_local = |hour: u64| {
+        let utc = chrono::DateTime::from_timestamp_millis((hour * HOUR_MS) as i64)?;
+        Some(match offset {
+            Some(offset) => utc.with_timezone(&offset).naive_local(),
+            None => utc.with_timezone(&chrono::Local).naive_local(),
+        })
+    };
+    let sparkline_start = today - chrono::Days::new(SPARKLINE_DAYS as u64 - 1);
+
+    let mut stats = ProjectStats {
+        project_id: project_id.to_string(),
+        path: project_actual_path(projects_dir, project_id),
+        total_sessions: 0,
+        total_messages: 0,
+        first_active: None,
+        last_active: None,
+        sessions_by_week: BTreeMap::new(),
+        top_commands: Vec::new(),
+        tokens: TokenUsage::default(),
+        cost: 0.0,
+        unpriced_models: Vec::new(),
+        active_hours: vec![0; 24],
+        last_30_days: vec![0; SPARKLINE_DAYS],
+    };
+    let mut commands: HashMap<String, usize> = HashMap::new();
+    let mut unpriced: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+
+    for file in cache.files.values().filter(|f| f.project_id == project_id) {
+        let (Some(first), Some(last)) = (file.hours.keys().next(), file.hours.keys().next_back())
+        else {
+            continue;
+        };
+        let (start, end) = (first * HOUR_MS, last * HOUR_MS);
+        stats.total_sessions += 1;
+        stats.first_active = Some(stats.first_active.map_or(start, |t| t.min(start)));
+        stats.last_active = Some(stats.last_active.map_or(end, |t| t.max(end)));
+        if let Some(started) = to_local(*first) {
+            *stats
+                .sessions_by_week
+                .entry(started.format("%G-W%V").to_string())
+                .or_insert(0) += 1;
+        }
+
+        for (hour, bucket) in &file.hours {
+            stats.total_messages += bucket.messages;
+            for (cmd, count) in &bucket.commands {
+                *commands.entry(cmd.clone()).or_insert(0) += count;
+            }
+            for (model, tokens) in &bucket.tokens {
+                match find_price(prices, model) {
+                    Some(price) => stats.cost += tokens.cost(price),
+                    None => {
+                        unpriced.insert(model.clone());
                     }
                 }
+                stats.tokens.add(tokens);
+            }
+
+            let Some(local) = to_local(*hour) else {
+                continue;
+            };
+            stats.active_hours[local.hour() as usize] += bucket.messages;
+            let day = (local.date() - sparkline_start).num_days();
+            if let Some(slot) = usize::try_from(day)
+                .ok()
+                .and_then(|day| stats.last_30_days.get_mut(day))
+            {
+                *slot += bucket.messages;
             }
         }
+    }
 
-        // Find favorite project
-        let favorite_project = project_stats
-            .iter()
-            .max_by_key(|(_, (_, sessions, _))| sessions)
-            .map(|(id, (path, sessions, messages))| FavoriteProject {
-                id: id.clone(),
-                path: path.clone(),
-                session_count: *sessions,
-                message_count: *messages,
-            });
+    stats.top_commands = top_n(commands, 5);
+    stats.unpriced_models = unpriced.into_iter().collect();
+    stats
+}
 
-        // Get top commands from command-stats index (aggregate weekly data) or fallback to extracted
-        let mut top_commands: Vec<TopCommand> = Vec::new();
-        let stats_path = get_command_stats_path();
-        let mut use_fallback = true;
-
-        if stats_path.exists() {
-            if let Ok(content) = fs::read_to_string(&stats_path) {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(commands) = parsed.get("commands").and_then(|v| v.as_object()) {
-                        let mut aggregated: HashMap<String, usize> = HashMap::new();
-                        for (cmd_name, week_data) in commands {
-                            if let Some(weeks) = week_data.as_object() {
-                                let total: usize = weeks
-                                    .values()
-                                    .filter_map(|v| v.as_u64())
-                                    .map(|n| n as usize)
-                                    .sum();
-                                aggregated.insert(cmd_name.clone(), total);
-                            }
-                        }
-                        if !aggregated.is_empty() {
-                            let mut sorted: Vec<_> = aggregated.into_iter().collect();
-                            sorted.sort_by(|a, b| b.1.cmp(&a.1));
-                            top_commands = sorted
-                                .into_iter()
-                                .take(5)
-                                .map(|(name, count)| TopCommand { name, count })
-                                .collect();
-                            use_fallback = false;
-                        }
-                    }
-                }
+/// Bucket history.jsonl content in `offset` (system timezone when None)
+pub fn aggregate_activity(history: &str, offset: Option<chrono::FixedOffset>) -> ActivityStats {
+    use chrono::{Datelike, Offset, Timelike};
+
+    let mut daily: HashMap<String, usize> = HashMap::new();
+    let mut hourly: HashMap<u32, usize> = HashMap::new();
+    let mut detailed: HashMap<String, usize> = HashMap::new();
+    let mut weekday_hour = vec![vec![0usize; 24]; 7];
+
+    for line in history.lines() {
+        let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let Some(ts_ms) = parsed.get("timestamp").and_then(serde_json::Value::as_u64) else {
+            continue;
+        };
+        let Some(utc) = chrono::DateTime::from_timestamp_millis(ts_ms as i64) else {
+            continue;
+        };
+        // The system timezone is resolved per timestamp so DST changes land correctly
+        let local = match offset {
+            Some(offset) => utc.with_timezone(&offset).naive_local(),
+            None => utc.with_timezone(&chrono::Local).naive_local(),
+        };
+
+        let date = local.format("%Y-%m-%d").to_string();
+        let hour = local.hour();
+        *daily.entry(date.clone()).or_insert(0) += 1;
+        *hourly.entry(hour).or_insert(0) += 1;
+        *detailed.entry(format!("{}:{:02}", date, hour)).or_insert(0) += 1;
+        if let Some(cell) = weekday_hour
+            .get_mut(local.weekday().num_days_from_sunday() as usize)
+            .and_then(|row| row.get_mut(hour as usize))
+        {
+            *cell += 1;
+        }
+    }
+
+    let offset = offset.unwrap_or_else(|| chrono::Local::now().offset().fix());
+    ActivityStats {
+        daily,
+        hourly,
+        detailed,
+        weekday_hour,
+        utc_offset_minutes: offset.local_minus_utc() / 60,
+    }
+}
+
+fn build_period_report(
+    claude_dir: &std::path::Path,
+    cache: &ReportCache,
+    stats_path: &Path,
+    period: ReportPeriod,
+    total_commands: usize,
+) -> PeriodReport {
+    use chrono::{Datelike, Timelike};
+
+    let history_path = claude_dir.join("history.jsonl");
+    let projects_dir = claude_dir.join("projects");
+
+    let mut daily_activity: HashMap<String, usize> = HashMap::new();
+    let mut hourly_distribution: HashMap<u32, usize> = HashMap::new();
+    let mut weekday_counts: HashMap<u32, usize> = HashMap::new();
+
+    // Parse history.jsonl for activity inside the period
+    if let Ok(content) = fs::read_to_string(&history_path) {
+        for line in content.lines() {
+            let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+            let Some(ts_ms) = parsed.get("timestamp").and_then(serde_json::Value::as_u64) else {
+                continue;
+            };
+            if !period.contains(ts_ms) {
+                continue;
+            }
+            if let Some(dt) = chrono::DateTime::from_timestamp((ts_ms / 1000) as i64, 0) {
+                let date = dt.format("%Y-%m-%d").to_string();
+                *daily_activity.entry(date).or_insert(0) += 1;
+                *hourly_distribution.entry(dt.hour()).or_insert(0) += 1;
+                *weekday_counts
+                    .entry(dt.weekday().num_days_from_sunday())
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    // YYYY-MM-DD keys sort chronologically
+    let first_chat_date = daily_activity.keys().min().cloned();
+    let last_chat_date = daily_activity.keys().max().cloned();
+
+    let (peak_hour, peak_hour_count) = hourly_distribution
+        .iter()
+        .max_by_key(|(hour, count)| (**count, std::cmp::Reverse(**hour)))
+        .map(|(h, c)| (*h, *c))
+        .unwrap_or((0, 0));
+
+    let peak_weekday = weekday_counts
+        .iter()
+        .max_by_key(|(day, count)| (**count, std::cmp::Reverse(**day)))
+        .map(|(d, _)| *d)
+        .unwrap_or(0);
+
+    // Session/message counts in the period from cached per-file aggregates
+    let mut total_sessions = 0usize;
+    let mut total_messages = 0usize;
+    let mut project_stats: HashMap<String, (String, usize, usize)> = HashMap::new(); // id -> (path, sessions, messages)
+    let mut command_counts: HashMap<String, usize> = HashMap::new(); // command -> count (fallback)
+
+    for file in cache.files.values() {
+        let mut msg_count = 0usize;
+        for bucket in file.hours_in(period) {
+            msg_count += bucket.messages;
+            for (cmd, count) in &bucket.commands {
+                *command_counts.entry(cmd.clone()).or_insert(0) += count;
             }
         }
+        if msg_count == 0 {
+            continue;
+        }
 
-        // Fallback: use command counts extracted from session files
-        if use_fallback && !command_counts.is_empty() {
-            let mut sorted: Vec<_> = command_counts.into_iter().collect();
-            sorted.sort_by(|a, b| b.1.cmp(&a.1));
-            top_commands = sorted
-                .into_iter()
-                .take(5)
-                .map(|(name, count)| TopCommand { name, count })
-                .collect();
+        total_sessions += 1;
+        total_messages += msg_count;
+        let stats = project_stats
+            .entry(file.project_id.clone())
+            .or_insert_with(|| (project_actual_path(&projects_dir, &file.project_id), 0, 0));
+        stats.1 += 1;
+        stats.2 += msg_count;
+    }
+
+    let favorite_project = project_stats
+        .iter()
+        .max_by_key(|(id, (_, sessions, _))| (*sessions, std::cmp::Reverse(*id)))
+        .map(|(id, (path, sessions, messages))| FavoriteProject {
+            id: id.clone(),
+            path: path.clone(),
+            session_count: *sessions,
+            message_count: *messages,
+        });
+
+    // Prefer the command-stats index, fall back to commands extracted from sessions
+    let indexed = indexed_command_counts(stats_path, period);
+    let top_commands = if indexed.is_empty() {
+        top_n(command_counts, 5)
+    } else {
+        top_n(indexed, 5)
+    };
+
+    PeriodReport {
+        period_start: period.start,
+        period_end: period.end,
+        total_sessions,
+        total_messages,
+        total_commands,
+        active_days: daily_activity.len(),
+        first_chat_date,
+        last_chat_date,
+        peak_hour,
+        peak_hour_count,
+        peak_weekday,
+        total_projects: project_stats.len(),
+        favorite_project,
+        top_commands,
+        longest_streak: longest_streak(&daily_activity),
+        daily_activity,
+        hourly_distribution,
+    }
+}
+
+// ============================================================================
+// Command Stats Maintenance
+// ============================================================================
+
+/// Previous names (normalized) -> current command name without the leading "/"
+fn command_alias_map() -> HashMap<String, String> {
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    for cmd in list_local_commands().unwrap_or_default() {
+        let canonical = cmd.name.trim_start_matches('/').to_string();
+        for alias in &cmd.aliases {
+            aliases.insert(normalize_command_key(alias), canonical.clone());
+        }
+    }
+    aliases
+}
+
+fn merge_aliases(
+    stats: HashMap<String, usize>,
+    aliases: &HashMap<String, String>,
+) -> HashMap<String, usize> {
+    let mut merged: HashMap<String, usize> = HashMap::new();
+    for (name, count) in stats {
+        let name = aliases
+            .get(&normalize_command_key(&name))
+            .cloned()
+            .unwrap_or(name);
+        *merged.entry(name).or_insert(0) += count;
+    }
+    merged
+}
+
+/// Remove week entries older than `before_week` (keys sort chronologically),
+/// dropping commands left without any weeks. Returns the number of entries removed.
+fn prune_weekly_stats(stats: &mut serde_json::Value, before_week: &str) -> usize {
+    let mut removed = 0usize;
+    let mut emptied: Vec<String> = Vec::new();
+
+    if let Some(commands) = stats.get_mut("commands").and_then(|v| v.as_object_mut()) {
+        for (name, weeks) in commands.iter_mut() {
+            let Some(weeks) = weeks.as_object_mut() else {
+                continue;
+            };
+            let before = weeks.len();
+            weeks.retain(|week, _| week.as_str() >= before_week);
+            removed += before - weeks.len();
+            if weeks.is_empty() {
+                emptied.push(name.clone());
+            }
+        }
+        for name in &emptied {
+            commands.remove(name);
         }
+    }
+    if let Some(last_used) = stats.get_mut("last_used").and_then(|v| v.as_object_mut()) {
+        for name in &emptied {
+            last_used.remove(name);
+        }
+    }
+    removed
+}
 
-        // Count local commands
+/// Weekly usage (same key format as the search index build) and last-used seconds
+/// per command, scanned from every main session file
+fn count_weekly_command_usage(
+    projects_dir: &std::path::Path,
+    aliases: &HashMap<String, String>,
+) -> (
+    HashMap<String, HashMap<String, usize>>,
+    HashMap<String, i64>,
+) {
+    let mut weekly: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    let mut last_used: HashMap<String, i64> = HashMap::new();
+
+    for project in fs::read_dir(projects_dir).into_iter().flatten().flatten() {
+        for session in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let name = session.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".jsonl") || name.starts_with("agent-") {
+                continue;
+            }
+            let Ok(lines) = jsonl::open(&session.path()) else {
+                continue;
+            };
+
+            for (_, line) in lines {
+                if !line.contains("<command-name>") || line.contains("\"type\":\"queue-operation\"")
+                {
+                    continue;
+                }
+                let Some(ts) = serde_json::from_str::<serde_json::Value>(&line)
+                    .ok()
+                    .and_then(|v| v.get("timestamp")?.as_str().map(String::from))
+                    .and_then(|ts| chrono::DateTime::parse_from_rfc3339(&ts).ok())
+                else {
+                    continue;
+                };
+                let week_key = ts.format("%Y-W%V").to_string();
+                for cap in COMMAND_NAME_RE.captures_iter(&line) {
+                    let Some(cmd_match) = cap.get(1) else {
+                        continue;
+                    };
+                    let raw = cmd_match.as_str().trim_start_matches('/').to_string();
+                    let cmd = aliases
+                        .get(&normalize_command_key(&raw))
+                        .cloned()
+                        .unwrap_or(raw);
+                    let last = last_used.entry(cmd.clone()).or_insert(0);
+                    *last = (*last).max(ts.timestamp());
+                    *weekly
+                        .entry(cmd)
+                        .or_default()
+                        .entry(week_key.clone())
+                        .or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    (weekly, last_used)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+fn parse_utc_offset(minutes: Option<i32>) -> Result<Option<chrono::FixedOffset>, String> {
+    minutes
+        .map(|minutes| {
+            chrono::FixedOffset::east_opt(minutes.saturating_mul(60))
+                .ok_or_else(|| format!("Invalid UTC offset: {} minutes", minutes))
+        })
+        .transpose()
+}
+
+/// Activity buckets from history.jsonl, in the given UTC offset (minutes east of UTC)
+/// or the system local timezone when omitted
+#[tauri::command]
+pub async fn get_activity_stats(utc_offset_minutes: Option<i32>) -> AppResult<ActivityStats> {
+    let offset = parse_utc_offset(utc_offset_minutes).map_err(AppError::invalid_input)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let history_path = security::get_claude_dir_or_fallback().join("history.jsonl");
+        let content = fs::read_to_string(&history_path).unwrap_or_default();
+        Ok(aggregate_activity(&content, offset))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Report for the half-open range [start, end) in Unix milliseconds
+#[tauri::command]
+pub async fn get_period_report(start: u64, end: u64) -> AppResult<PeriodReport> {
+    if start >= end {
+        return Err(AppError::invalid_input(
+            "Report period start must be before end",
+        ));
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
         let total_commands = list_local_commands().map(|cmds| cmds.len()).unwrap_or(0);
+        with_report_cache(|cache| {
+            build_period_report(
+                &security::get_claude_dir_or_fallback(),
+                cache,
+                &get_command_stats_path(),
+                ReportPeriod { start, end },
+                total_commands,
+            )
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Bounds of the year/quarter/month/week containing `date` (YYYY-MM-DD, default today)
+#[tauri::command]
+pub fn get_report_period(preset: String, date: Option<String>) -> AppResult<ReportPeriod> {
+    let anchor = match date {
+        Some(d) => chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+            .map_err(|e| AppError::invalid_input(format!("Invalid date '{}': {}", d, e)))?,
+        None => chrono::Utc::now().date_naive(),
+    };
+    period_for_preset(&preset, anchor).map_err(AppError::invalid_input)
+}
 
-        Ok(AnnualReport2025 {
-            total_sessions,
-            total_messages,
-            total_commands,
-            active_days: daily_activity.len(),
-            first_chat_date: first_date,
-            last_chat_date: last_date,
-            peak_hour,
-            peak_hour_count,
-            peak_weekday,
-            total_projects: project_stats.len(),
-            favorite_project,
-            top_commands,
-            longest_streak,
-            daily_activity,
-            hourly_distribution,
+#[tauri::command]
+pub async fn get_annual_report(year: i32) -> AppResult<PeriodReport> {
+    let anchor = chrono::NaiveDate::from_ymd_opt(year, 1, 1)
+        .ok_or_else(|| AppError::invalid_input(format!("Invalid year: {}", year)))?;
+    let period = period_for_preset("year", anchor).map_err(AppError::invalid_input)?;
+    get_period_report(period.start, period.end).await
+}
+
+/// Assistant replies per model in the period (all time when omitted)
+#[tauri::command]
+pub async fn get_model_usage_stats(
+    period: Option<ReportPeriod>,
+) -> AppResult<Vec<ModelUsage>> {
+    let period = period.unwrap_or(ALL_TIME);
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        with_report_cache(|cache| {
+            usage_shares(sum_bucket_counts(cache, period, |b| &b.models))
+                .into_iter()
+                .map(|u| ModelUsage {
+                    family: model_family(&u.name).to_string(),
+                    model: u.name,
+                    count: u.count,
+                    share: u.share,
+                })
+                .collect()
         })
     })
     .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Estimated spend grouped by "project", "week" or "model" (all time when period omitted)
+#[tauri::command]
+pub async fn get_cost_report(
+    period: Option<ReportPeriod>,
+    group_by: String,
+) -> AppResult<CostReport> {
+    let period = period.unwrap_or(ALL_TIME);
+    tauri::async_runtime::spawn_blocking(move || {
+        let prices = load_price_table(&get_price_table_path());
+        let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+        // An unknown grouping is the only way build_cost_report fails
+        with_report_cache(|cache| {
+            build_cost_report(cache, &projects_dir, &prices, period, &group_by)
+                .map_err(AppError::invalid_input)
+        })?
+    })
+    .await
     .map_err(|e| e.to_string())?
 }
 
+/// Price table used by get_cost_report (defaults until the user saves their own)
 #[tauri::command]
-pub async fn get_command_stats() -> Result<HashMap<String, usize>, String> {
+pub fn get_price_table() -> AppResult<Vec<ModelPrice>> {
+    Ok(load_price_table(&get_price_table_path()))
+}
+
+#[tauri::command]
+pub fn update_price_table(prices: Vec<ModelPrice>) -> AppResult<()> {
+    if let Some(bad) = prices.iter().find(|p| {
+        [p.input, p.output, p.cache_write, p.cache_read]
+            .iter()
+            .any(|v| !v.is_finite() || *v < 0.0)
+    }) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid price for '{}': rates must be non-negative",
+            bad.pattern
+        )));
+    }
+
+    let path = get_price_table_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let content = serde_json::to_string_pretty(&prices).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path, &content)
+}
+
+/// tool_use calls per tool in the period (all time when omitted)
+#[tauri::command]
+pub async fn get_tool_usage_stats(period: Option<ReportPeriod>) -> AppResult<Vec<UsageShare>> {
+    let period = period.unwrap_or(ALL_TIME);
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        with_report_cache(|cache| usage_shares(sum_bucket_counts(cache, period, |b| &b.tools)))
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Failed API requests (overloaded, rate limited, 5xx, ...) in the period (all time when
+/// omitted), by type, local hour and provider
+#[tauri::command]
+pub async fn get_api_error_stats(period: Option<ReportPeriod>) -> AppResult<ApiErrorStats> {
+    let period = period.unwrap_or(ALL_TIME);
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        with_report_cache(|cache| build_api_error_stats(cache, period, None))
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Median session duration, turns, response latency and longest gap for sessions that
+/// started in the period (all time when omitted)
+#[tauri::command]
+pub async fn get_session_metrics_summary(
+    period: Option<ReportPeriod>,
+) -> AppResult<SessionMetricsSummary> {
+    let period = period.unwrap_or(ALL_TIME);
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        with_report_cache(|cache| build_session_metrics_summary(cache, period))
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Sessions, messages, top commands, spend, active hours and a 30-day sparkline for one
+/// project, bucketed in the given UTC offset or the system local timezone when omitted
+#[tauri::command]
+pub async fn get_project_stats(
+    project_id: String,
+    utc_offset_minutes: Option<i32>,
+) -> AppResult<ProjectStats> {
+    let offset = parse_utc_offset(utc_offset_minutes).map_err(AppError::invalid_input)?;
+    let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+    if project_id.is_empty()
+        || project_id.contains(['/', '\\'])
+        || project_id.contains("..")
+        || !projects_dir.join(&project_id).is_dir()
+    {
+        return Err(AppError::not_found("Project not found").with_context(project_id));
+    }
+
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let prices = load_price_table(&get_price_table_path());
+        let today = match offset {
+            Some(offset) => chrono::Utc::now().with_timezone(&offset).date_naive(),
+            None => chrono::Local::now().date_naive(),
+        };
+        with_report_cache(|cache| {
+            build_project_stats(cache, &projects_dir, &prices, &project_id, offset, today)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[tauri::command]
+pub async fn get_command_stats() -> AppResult<HashMap<String, usize>> {
     // Get current cache state
-    let (cached_stats, cached_scanned) = {
+    let (cached_stats, cached_scanned, generation) = {
         let cache = COMMAND_STATS_CACHE
             .lock()
             .map_err(|_| "Cache lock poisoned")?;
-        (cache.stats.clone(), cache.scanned.clone())
+        (cache.stats.clone(), cache.scanned.clone(), cache.generation)
     };
 
     // Incremental update in background
@@ -475,22 +1699,19 @@ pub async fn get_command_stats() -> Result<HashMap<String, usize>, String> {
 
                 // Read only new content (from prev_size offset)
                 if let Ok(mut file) = std::fs::File::open(&session_path) {
-                    use std::io::{Read, Seek, SeekFrom};
+                    use std::io::{Seek, SeekFrom};
                     if file.seek(SeekFrom::Start(prev_size)).is_ok() {
-                        let mut new_content = String::new();
-                        if file.read_to_string(&mut new_content).is_ok() {
-                            // Process line by line to filter out queue-operation entries
-                            for line in new_content.lines() {
-                                if line.contains("\"type\":\"queue-operation\"") {
-                                    continue;
-                                }
-                                for cap in COMMAND_NAME_RE.captures_iter(line) {
-                                    if let Some(cmd_name) = cap.get(1) {
-                                        // Remove leading "/" to match cmd.name format
-                                        let name =
-                                            cmd_name.as_str().trim_start_matches('/').to_string();
-                                        *stats.entry(name).or_insert(0) += 1;
-                                    }
+                        // Process line by line to filter out queue-operation entries
+                        for (_, line) in jsonl::from_reader(file) {
+                            if line.contains("\"type\":\"queue-operation\"") {
+                                continue;
+                            }
+                            for cap in COMMAND_NAME_RE.captures_iter(&line) {
+                                if let Some(cmd_name) = cap.get(1) {
+                                    // Remove leading "/" to match cmd.name format
+                                    let name =
+                                        cmd_name.as_str().trim_start_matches('/').to_string();
+                                    *stats.entry(name).or_insert(0) += 1;
                                 }
                             }
                         }
@@ -510,11 +1731,83 @@ pub async fn get_command_stats() -> Result<HashMap<String, usize>, String> {
         let mut cache = COMMAND_STATS_CACHE
             .lock()
             .map_err(|_| "Cache lock poisoned")?;
-        cache.stats = new_stats.clone();
-        cache.scanned = new_scanned;
+        if cache.generation == generation {
+            cache.stats = new_stats.clone();
+            cache.scanned = new_scanned;
+        }
+    }
+
+    // Fold usage recorded under old names into the command's current name
+    Ok(tauri::async_runtime::spawn_blocking(move || merge_aliases(new_stats, &command_alias_map()))
+        .await
+        .map_err(|e| e.to_string())?)
+}
+
+/// Delete the weekly index and forget all incrementally scanned counts
+#[tauri::command]
+pub fn reset_command_stats() -> AppResult<()> {
+    let mut cache = COMMAND_STATS_CACHE
+        .lock()
+        .map_err(|_| "Cache lock poisoned")?;
+    store::remove(&get_command_stats_path()).map_err(|e| {
+        AppError::new(ErrorCode::Io, format!("Failed to reset command stats: {}", e))
+    })?;
+    cache.stats.clear();
+    cache.scanned.clear();
+    cache.generation += 1;
+    Ok(())
+}
+
+/// Drop weekly index entries older than `before_week` ("YYYY-Www"); returns entries removed
+#[tauri::command]
+pub fn prune_command_stats(before_week: String) -> AppResult<usize> {
+    if !WEEK_KEY_RE.is_match(&before_week) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid week '{}', expected YYYY-Www",
+            before_week
+        )));
     }
 
-    Ok(new_stats)
+    let _cache = COMMAND_STATS_CACHE
+        .lock()
+        .map_err(|_| "Cache lock poisoned")?;
+    let stats_path = get_command_stats_path();
+    let Some(mut parsed) = store::load(&stats_path, &COMMAND_STATS_SCHEMA)? else {
+        return Ok(0);
+    };
+
+    let removed = prune_weekly_stats(&mut parsed, &before_week);
+    if removed > 0 {
+        store::save(&stats_path, &COMMAND_STATS_SCHEMA, &parsed)?;
+    }
+    Ok(removed)
+}
+
+/// Rebuild the weekly index from session files (aliases folded into current names)
+/// and restart incremental counting; returns per-command totals
+#[tauri::command]
+pub async fn recount_command_stats() -> AppResult<HashMap<String, usize>> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let aliases = command_alias_map();
+        let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+        let (weekly, last_used) = count_weekly_command_usage(&projects_dir, &aliases);
+
+        let mut cache = COMMAND_STATS_CACHE
+            .lock()
+            .map_err(|_| "Cache lock poisoned")?;
+        save_command_stats(&weekly, &last_used)?;
+
+        cache.stats.clear();
+        cache.scanned.clear();
+        cache.generation += 1;
+
+        Ok(weekly
+            .into_iter()
+            .map(|(name, weeks)| (name, weeks.values().sum()))
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// Returns command usage counts grouped by week (from pre-built index)
@@ -522,22 +1815,17 @@ pub async fn get_command_stats() -> Result<HashMap<String, usize>, String> {
 #[tauri::command]
 pub fn get_command_weekly_stats(
     _weeks: Option<usize>,
-) -> Result<HashMap<String, HashMap<String, usize>>, String> {
+) -> AppResult<HashMap<String, HashMap<String, usize>>> {
     // Read from pre-built index (created by build_search_index)
-    let stats_path = get_command_stats_path();
-
-    if !stats_path.exists() {
+    let Some(parsed) = store::load(&get_command_stats_path(), &COMMAND_STATS_SCHEMA)? else {
         return Ok(HashMap::new());
-    }
-
-    let content = fs::read_to_string(&stats_path).map_err(|e| e.to_string())?;
-    let parsed: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    };
 
     // Extract commands map
     let commands = parsed
         .get("commands")
         .and_then(|v| v.as_object())
-        .ok_or("Invalid command stats format")?;
+        .ok_or_else(|| AppError::new(ErrorCode::Parse, "Invalid command stats format"))?;
 
     let mut stats: HashMap<String, HashMap<String, usize>> = HashMap::new();
     for (cmd_name, week_data) in commands {
@@ -554,3 +1842,548 @@ pub fn get_command_weekly_stats(
 
     Ok(stats)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> chrono::NaiveDate {
+        chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").expect("valid date")
+    }
+
+    #[test]
+    fn test_load_command_usage_merges_key_forms() {
+        assert_eq!(normalize_command_key("/git:commit"), "git/commit");
+        assert_eq!(normalize_command_key("git/commit"), "git/commit");
+
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("command-stats.json");
+        let stats = serde_json::json!({
+            "updated_at": 0,
+            "commands": {
+                "git:commit": { "2025-W01": 2, "2025-W02": 1 },
+                "git/commit": { "2025-W02": 4 },
+                "review": { "2025-W03": 1 },
+            },
+            "last_used": { "git:commit": 300, "git/commit": 100 },
+        });
+        store::save(&path, &COMMAND_STATS_SCHEMA, &stats).expect("write stats");
+
+        let usage = load_command_usage_from(&path);
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage.get("git/commit"), Some(&(7, Some(300))));
+        assert_eq!(usage.get("review"), Some(&(1, None)));
+        assert!(load_command_usage_from(&temp.path().join("missing.json")).is_empty());
+    }
+
+    #[test]
+    fn test_period_presets() {
+        let year = period_for_preset("year", date("2025-06-15")).expect("year");
+        assert_eq!(
+            year,
+            ReportPeriod {
+                start: 1735689600000,
+                end: 1767225600000
+            }
+        );
+
+        let quarter = period_for_preset("quarter", date("2026-05-20")).expect("quarter");
+        assert_eq!(quarter.start, date_start_ms(date("2026-04-01")));
+        assert_eq!(quarter.end, date_start_ms(date("2026-07-01")));
+
+        let month = period_for_preset("month", date("2025-12-31")).expect("month");
+        assert_eq!(month.start, date_start_ms(date("2025-12-01")));
+        assert_eq!(month.end, date_start_ms(date("2026-01-01")));
+
+        // 2026-01-01 is a Thursday
+        let week = period_for_preset("week", date("2026-01-01")).expect("week");
+        assert_eq!(week.start, date_start_ms(date("2025-12-29")));
+        assert_eq!(week.end, date_start_ms(date("2026-01-05")));
+
+        assert!(period_for_preset("decade", date("2025-01-01")).is_err());
+    }
+
+    #[test]
+    fn test_week_key_in_period() {
+        let january = period_for_preset("month", date("2026-01-10")).expect("month");
+        assert!(week_key_in_period("2026-W01", january));
+        assert!(week_key_in_period("2026-W05", january)); // Jan 26 - Feb 1
+        assert!(!week_key_in_period("2026-W06", january));
+        assert!(!week_key_in_period("garbage", january));
+    }
+
+    #[test]
+    fn test_longest_streak() {
+        let daily: HashMap<String, usize> =
+            ["2025-01-01", "2025-01-02", "2025-01-03", "2025-01-10"]
+                .iter()
+                .map(|d| (d.to_string(), 1))
+                .collect();
+        assert_eq!(longest_streak(&daily), 3);
+        assert_eq!(longest_streak(&HashMap::new()), 0);
+    }
+
+    #[test]
+    fn test_build_period_report_filters_by_period() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let claude_dir = temp.path();
+        fs::write(
+            claude_dir.join("history.jsonl"),
+            [
+                r#"{"timestamp":1767258000000}"#, // 2026-01-01 09:00 UTC
+                r#"{"timestamp":1767344400000}"#, // 2026-01-02 09:00 UTC
+                r#"{"timestamp":1735722000000}"#, // 2025-01-01 09:00 UTC
+            ]
+            .join("\n"),
+        )
+        .expect("write history");
+
+        let project = claude_dir.join("projects").join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        fs::write(
+            project.join("a.jsonl"),
+            [
+                r#"{"type":"user","timestamp":"2026-01-01T09:00:00Z","message":{"content":"<command-name>/commit</command-name>"}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:00:05Z"}"#,
+            ]
+            .join("\n"),
+        )
+        .expect("write session");
+        fs::write(
+            project.join("b.jsonl"),
+            r#"{"type":"user","timestamp":"2025-03-01T09:00:00Z"}"#,
+        )
+        .expect("write old session");
+
+        let mut cache = ReportCache::default();
+        assert!(refresh_report_cache(
+            &mut cache,
+            &claude_dir.join("projects")
+        ));
+        let period = period_for_preset("month", date("2026-01-15")).expect("month");
+        let report = build_period_report(
+            claude_dir,
+            &cache,
+            &claude_dir.join("missing.json"),
+            period,
+            7,
+        );
+
+        assert_eq!(report.period_start, period.start);
+        assert_eq!(report.active_days, 2);
+        assert_eq!(report.first_chat_date.as_deref(), Some("2026-01-01"));
+        assert_eq!(report.last_chat_date.as_deref(), Some("2026-01-02"));
+        assert_eq!(report.longest_streak, 2);
+        assert_eq!(report.peak_hour, 9);
+        assert_eq!(report.total_sessions, 1);
+        assert_eq!(report.total_messages, 2);
+        assert_eq!(report.total_commands, 7);
+        assert_eq!(report.top_commands.len(), 1);
+        assert_eq!(report.top_commands[0].name, "commit");
+        assert_eq!(
+            report.favorite_project.map(|p| p.id).as_deref(),
+            Some("-tmp-demo")
+        );
+    }
+
+    #[test]
+    fn test_report_cache_reprocesses_only_changed_files() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let projects_dir = temp.path().join("projects");
+        let project = projects_dir.join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        let session = project.join("a.jsonl");
+        fs::write(
+            &session,
+            r#"{"type":"user","timestamp":"2026-01-01T09:00:00Z"}"#,
+        )
+        .expect("write session");
+
+        let mut cache = ReportCache::default();
+        assert!(refresh_report_cache(&mut cache, &projects_dir));
+        assert!(!refresh_report_cache(&mut cache, &projects_dir));
+
+        let key = session.to_string_lossy().to_string();
+        let hour = 1767258000000 / HOUR_MS;
+        assert_eq!(cache.files[&key].hours[&hour].messages, 1);
+
+        // Unchanged files keep their cached aggregate
+        cache.files.get_mut(&key).expect("cached").project_id = "marker".to_string();
+        assert!(!refresh_report_cache(&mut cache, &projects_dir));
+        assert_eq!(cache.files[&key].project_id, "marker");
+
+        fs::write(
+            &session,
+            [
+                r#"{"type":"user","timestamp":"2026-01-01T09:00:00Z"}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:10:00Z"}"#,
+            ]
+            .join("\n"),
+        )
+        .expect("rewrite session");
+        assert!(refresh_report_cache(&mut cache, &projects_dir));
+        assert_eq!(cache.files[&key].project_id, "-tmp-demo");
+        assert_eq!(cache.files[&key].hours[&hour].messages, 2);
+
+        fs::remove_file(&session).expect("remove session");
+        assert!(refresh_report_cache(&mut cache, &projects_dir));
+        assert!(cache.files.is_empty());
+
+        // Round-trips through disk
+        let cache_path = temp.path().join("cache").join("report-cache.json");
+        cache.version = REPORT_CACHE_VERSION;
+        save_report_cache(&cache_path, &cache).expect("save cache");
+        assert_eq!(load_report_cache(&cache_path).version, REPORT_CACHE_VERSION);
+    }
+
+    #[test]
+    fn test_model_and_tool_usage() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let project = temp.path().join("projects").join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        fs::write(
+            project.join("a.jsonl"),
+            [
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:00:00Z","message":{"id":"m1","model":"claude-opus-4-1","content":[{"type":"text","text":"hi"}]}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:00:01Z","message":{"id":"m1","model":"claude-opus-4-1","content":[{"type":"tool_use","name":"Bash"}]}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:01:00Z","message":{"id":"m2","model":"claude-sonnet-4-5","content":[{"type":"tool_use","name":"Edit"},{"type":"tool_use","name":"Bash"}]}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:02:00Z","message":{"id":"m3","model":"<synthetic>","content":[]}}"#,
+                r#"{"type":"assistant","timestamp":"2025-06-01T09:00:00Z","message":{"id":"m4","model":"claude-haiku-4-5","content":[]}}"#,
+            ]
+            .join("\n"),
+        )
+        .expect("write session");
+
+        let mut cache = ReportCache::default();
+        refresh_report_cache(&mut cache, &temp.path().join("projects"));
+        let period = period_for_preset("year", date("2026-03-01")).expect("year");
+
+        let models = usage_shares(sum_bucket_counts(&cache, period, |b| &b.models));
+        assert_eq!(
+            models
+                .iter()
+                .map(|u| (u.name.as_str(), u.count))
+                .collect::<Vec<_>>(),
+            vec![("claude-opus-4-1", 1), ("claude-sonnet-4-5", 1)]
+        );
+        assert!((models[0].share - 0.5).abs() < f64::EPSILON);
+        assert_eq!(model_family(&models[1].name), "sonnet");
+
+        let tools = usage_shares(sum_bucket_counts(&cache, period, |b| &b.tools));
+        assert_eq!(tools[0].name, "Bash");
+        assert_eq!(tools[0].count, 2);
+        assert_eq!(tools[1].name, "Edit");
+
+        let all_models = sum_bucket_counts(&cache, ALL_TIME, |b| &b.models);
+        assert_eq!(all_models.get("claude-haiku-4-5"), Some(&1));
+    }
+
+    #[test]
+    fn test_api_error_stats() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let project = temp.path().join("projects").join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        fs::write(
+            project.join("a.jsonl"),
+            [
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:00:00Z","message":{"id":"m1","model":"deepseek-chat","content":[]}}"#,
+                r#"{"type":"system","subtype":"api_error","level":"error","timestamp":"2026-01-01T09:01:00Z","error":{"status":529,"error":{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}},"retryAttempt":1}"#,
+                r#"{"type":"system","subtype":"api_error","level":"error","timestamp":"2026-01-01T09:02:00Z","error":{},"cause":{"code":"ECONNRESET"},"retryAttempt":2}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:03:00Z","isApiErrorMessage":true,"message":{"id":"m2","model":"<synthetic>","content":[{"type":"text","text":"API Error: Request rejected (429) · rate limited"}]}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T09:04:00Z","isApiErrorMessage":true,"message":{"id":"m3","model":"<synthetic>","content":[{"type":"text","text":"API Error: Request was aborted."}]}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T23:30:00Z","message":{"id":"m4","model":"claude-sonnet-4-5","content":[]}}"#,
+                r#"{"type":"assistant","timestamp":"2026-01-01T23:31:00Z","isApiErrorMessage":true,"message":{"id":"m5","model":"<synthetic>","content":[{"type":"text","text":"API Error: 500 {\"type\":\"error\",\"error\":{\"type\":\"api_error\"}}"}]}}"#,
+            ]
+            .join("\n"),
+        )
+        .expect("write session");
+
+        let mut cache = ReportCache::default();
+        refresh_report_cache(&mut cache, &temp.path().join("projects"));
+        let stats = build_api_error_stats(&cache, ALL_TIME, chrono::FixedOffset::east_opt(3600));
+
+        assert_eq!(stats.total, 4);
+        assert!((stats.error_rate - 4.0 / 6.0).abs() < 1e-9);
+        let by_type: HashMap<&str, usize> = stats
+            .by_type
+            .iter()
+            .map(|u| (u.name.as_str(), u.count))
+            .collect();
+        assert_eq!(
+            by_type,
+            HashMap::from([
+                ("overloaded", 1),
+                ("connection", 1),
+                ("rate_limit", 1),
+                ("server_error", 1)
+            ])
+        );
+        assert_eq!(stats.by_provider[0].name, "deepseek");
+        assert_eq!(stats.by_provider[0].count, 3);
+        assert_eq!(stats.by_provider[1].name, "anthropic");
+        // UTC+1: 09:xx -> 10:00, 23:31 -> 00:00 the next day
+        assert_eq!(stats.by_hour[10], 3);
+        assert_eq!(stats.by_hour[0], 1);
+        assert_eq!(stats.daily.get("2026-01-02"), Some(&1));
+    }
+
+    #[test]
+    fn test_session_metrics_summary() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let project = temp.path().join("projects").join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        for (name, day, prompts) in [("a", "01", 1), ("b", "02", 3), ("c", "03", 2)] {
+            let lines: Vec<String> = (0..prompts)
+                .flat_map(|i| {
+                    [
+                        format!(r#"{{"type":"user","timestamp":"2026-01-{}T09:0{}:00Z","message":{{"content":"hi"}}}}"#, day, i * 2),
+                        format!(r#"{{"type":"assistant","timestamp":"2026-01-{}T09:0{}:10Z","message":{{"id":"{}{}","model":"claude-sonnet-4-5","content":[]}}}}"#, day, i * 2, name, i),
+                    ]
+                })
+                .collect();
+            fs::write(project.join(format!("{}.jsonl", name)), lines.join("\n"))
+                .expect("write session");
+        }
+        fs::write(
+            project.join("empty.jsonl"),
+            r#"{"type":"summary","summary":"No prompts"}"#,
+        )
+        .expect("write session");
+
+        let mut cache = ReportCache::default();
+        refresh_report_cache(&mut cache, &temp.path().join("projects"));
+        let summary = build_session_metrics_summary(&cache, ALL_TIME);
+        assert_eq!(summary.sessions, 3);
+        assert_eq!(summary.median_user_turns, Some(2));
+        assert_eq!(summary.median_assistant_turns, Some(2));
+        // a: 10 s, b: 4 min 10 s, c: 2 min 10 s
+        assert_eq!(summary.median_duration_ms, Some(130_000));
+        assert_eq!(summary.median_response_ms, Some(10_000));
+
+        let january_2nd = ReportPeriod {
+            start: 1_767_312_000_000,
+            end: 1_767_398_400_000,
+        };
+        let summary = build_session_metrics_summary(&cache, january_2nd);
+        assert_eq!(summary.sessions, 1);
+        assert_eq!(summary.median_user_turns, Some(3));
+    }
+
+    #[test]
+    fn test_cost_report_grouping() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let projects_dir = temp.path().join("projects");
+        for (project, line) in [
+            (
+                "-tmp-a",
+                r#"{"type":"assistant","timestamp":"2026-01-05T09:00:00Z","message":{"id":"m1","model":"claude-sonnet-4-5","usage":{"input_tokens":1000000,"output_tokens":100000}}}"#,
+            ),
+            (
+                "-tmp-b",
+                r#"{"type":"assistant","timestamp":"2026-01-12T09:00:00Z","message":{"id":"m2","model":"claude-opus-4-1","usage":{"input_tokens":0,"output_tokens":0,"cache_read_input_tokens":1000000}}}"#,
+            ),
+            (
+                "-tmp-b",
+                r#"{"type":"assistant","timestamp":"2026-01-12T10:00:00Z","message":{"id":"m3","model":"relay-model","usage":{"input_tokens":5}}}"#,
+            ),
+        ] {
+            let dir = projects_dir.join(project);
+            fs::create_dir_all(&dir).expect("create project");
+            let file = dir.join(format!("{}.jsonl", line.len()));
+            fs::write(file, line).expect("write session");
+        }
+
+        let mut cache = ReportCache::default();
+        refresh_report_cache(&mut cache, &projects_dir);
+        let prices = default_price_table();
+
+        let by_project = build_cost_report(&cache, &projects_dir, &prices, ALL_TIME, "project")
+            .expect("project report");
+        // sonnet: 1M input * $3 + 0.1M output * $15; opus: 1M cache reads * $1.5
+        assert!((by_project.total_cost - 6.0).abs() < 1e-9);
+        assert_eq!(by_project.groups[0].key, "-tmp-a");
+        assert!((by_project.groups[0].cost - 4.5).abs() < 1e-9);
+        assert_eq!(by_project.unpriced_models, vec!["relay-model".to_string()]);
+        assert_eq!(by_project.total_tokens.input_tokens, 1_000_005);
+
+        let by_week = build_cost_report(&cache, &projects_dir, &prices, ALL_TIME, "week")
+            .expect("week report");
+        let weeks: Vec<&str> = by_week.groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(weeks, vec!["2026-W02", "2026-W03"]);
+
+        let custom = vec![ModelPrice {
+            pattern: "relay".to_string(),
+            input: 1_000_000.0,
+            output: 0.0,
+            cache_write: 0.0,
+            cache_read: 0.0,
+        }];
+        let by_model = build_cost_report(&cache, &projects_dir, &custom, ALL_TIME, "model")
+            .expect("model report");
+        assert_eq!(by_model.groups[0].key, "relay-model");
+        assert!((by_model.total_cost - 5.0).abs() < 1e-9);
+
+        assert!(build_cost_report(&cache, &projects_dir, &prices, ALL_TIME, "day").is_err());
+    }
+
+    #[test]
+    fn test_project_stats() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let projects_dir = temp.path().join("projects");
+        for (project, name, lines) in [
+            (
+                "-tmp-a",
+                "s1",
+                vec![
+                    r#"{"type":"user","timestamp":"2025-12-01T08:00:00Z","message":{"content":"<command-name>/review</command-name>"}}"#,
+                    r#"{"type":"assistant","timestamp":"2025-12-01T08:01:00Z","message":{"id":"m1","model":"claude-sonnet-4-5","usage":{"input_tokens":1000000}}}"#,
+                ],
+            ),
+            (
+                "-tmp-a",
+                "s2",
+                vec![
+                    r#"{"type":"user","timestamp":"2026-01-12T22:30:00Z","message":{"content":"<command-name>/review</command-name>"}}"#,
+                    r#"{"type":"user","timestamp":"2026-01-12T22:40:00Z","message":{"content":"<command-name>/test</command-name>"}}"#,
+                ],
+            ),
+            (
+                "-tmp-b",
+                "s3",
+                vec![
+                    r#"{"type":"user","timestamp":"2026-01-12T09:00:00Z","message":{"content":"hi"}}"#,
+                ],
+            ),
+        ] {
+            let dir = projects_dir.join(project);
+            fs::create_dir_all(&dir).expect("create project");
+            fs::write(dir.join(format!("{}.jsonl", name)), lines.join("\n"))
+                .expect("write session");
+        }
+
+        let mut cache = ReportCache::default();
+        refresh_report_cache(&mut cache, &projects_dir);
+        let stats = build_project_stats(
+            &cache,
+            &projects_dir,
+            &default_price_table(),
+            "-tmp-a",
+            chrono::FixedOffset::east_opt(3600),
+            date("2026-01-13"),
+        );
+
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.total_messages, 4);
+        assert_eq!(stats.first_active, Some(1_764_576_000_000));
+        let weeks: Vec<&str> = stats.sessions_by_week.keys().map(String::as_str).collect();
+        // UTC+1: 2026-01-12 22:30 is 23:30 on Monday of W03
+        assert_eq!(weeks, ["2025-W49", "2026-W03"]);
+        assert_eq!(stats.top_commands[0].name, "review");
+        assert_eq!(stats.top_commands[0].count, 2);
+        assert!((stats.cost - 3.0).abs() < 1e-9);
+        assert_eq!(stats.active_hours[9], 2);
+        assert_eq!(stats.active_hours[23], 2);
+        // Only the January session falls in the last 30 days, on the day before "today"
+        assert_eq!(stats.last_30_days.len(), SPARKLINE_DAYS);
+        assert_eq!(stats.last_30_days.iter().sum::<usize>(), 2);
+        assert_eq!(stats.last_30_days[SPARKLINE_DAYS - 2], 2);
+    }
+
+    #[test]
+    fn test_aggregate_activity_with_offset() {
+        let history = [
+            r#"{"timestamp":1767222000000}"#, // 2025-12-31 23:00 UTC (Wednesday)
+            r#"{"timestamp":1767225600000}"#, // 2026-01-01 00:00 UTC (Thursday)
+            "not json",
+        ]
+        .join("\n");
+
+        let utc = aggregate_activity(&history, chrono::FixedOffset::east_opt(0));
+        assert_eq!(utc.daily.get("2025-12-31"), Some(&1));
+        assert_eq!(utc.weekday_hour[3][23], 1);
+        assert_eq!(utc.utc_offset_minutes, 0);
+
+        // UTC+8: both land on Thursday morning
+        let shanghai = aggregate_activity(&history, chrono::FixedOffset::east_opt(8 * 3600));
+        assert_eq!(shanghai.daily.get("2026-01-01"), Some(&2));
+        assert_eq!(shanghai.detailed.get("2026-01-01:07"), Some(&1));
+        assert_eq!(shanghai.detailed.get("2026-01-01:08"), Some(&1));
+        assert_eq!(shanghai.weekday_hour[4][7], 1);
+        assert_eq!(shanghai.weekday_hour[4][8], 1);
+        assert_eq!(shanghai.utc_offset_minutes, 480);
+
+        // UTC-5: still New Year's Eve
+        let new_york = aggregate_activity(&history, chrono::FixedOffset::west_opt(5 * 3600));
+        assert_eq!(new_york.daily.get("2025-12-31"), Some(&2));
+        assert_eq!(new_york.hourly.get(&18), Some(&1));
+        assert_eq!(new_york.weekday_hour.iter().flatten().sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn test_merge_aliases() {
+        let aliases = HashMap::from([
+            ("old/commit".to_string(), "git:commit".to_string()),
+            ("ship".to_string(), "release:ship".to_string()),
+        ]);
+        let stats = HashMap::from([
+            ("old:commit".to_string(), 3),
+            ("git:commit".to_string(), 2),
+            ("ship".to_string(), 1),
+            ("other".to_string(), 4),
+        ]);
+        let merged = merge_aliases(stats, &aliases);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged["git:commit"], 5);
+        assert_eq!(merged["release:ship"], 1);
+        assert_eq!(merged["other"], 4);
+    }
+
+    #[test]
+    fn test_prune_weekly_stats() {
+        let mut stats = serde_json::json!({
+            "commands": {
+                "commit": { "2024-W50": 2, "2025-W02": 1 },
+                "legacy": { "2024-W01": 5 },
+            },
+            "last_used": { "commit": 100, "legacy": 50 },
+        });
+        assert_eq!(prune_weekly_stats(&mut stats, "2025-W01"), 2);
+        assert_eq!(
+            stats,
+            serde_json::json!({
+                "commands": { "commit": { "2025-W02": 1 } },
+                "last_used": { "commit": 100 },
+            })
+        );
+        assert!(WEEK_KEY_RE.is_match("2025-W01"));
+        assert!(!WEEK_KEY_RE.is_match("2025-1"));
+    }
+
+    #[test]
+    fn test_count_weekly_command_usage() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let project = temp.path().join("-tmp-demo");
+        fs::create_dir_all(&project).expect("create project");
+        fs::write(
+            project.join("a.jsonl"),
+            [
+                r#"{"type":"user","timestamp":"2025-01-07T10:00:00Z","message":{"content":"<command-name>/old-commit</command-name>"}}"#,
+                r#"{"type":"user","timestamp":"2025-01-08T10:00:00Z","message":{"content":"<command-name>/commit</command-name>"}}"#,
+                r#"{"type":"queue-operation","timestamp":"2025-01-08T10:00:00Z","content":"<command-name>/commit</command-name>"}"#,
+            ]
+            .join("\n"),
+        )
+        .expect("write session");
+        fs::write(
+            project.join("agent-1.jsonl"),
+            r#"{"type":"user","timestamp":"2025-01-08T10:00:00Z","message":{"content":"<command-name>/commit</command-name>"}}"#,
+        )
+        .expect("write agent session");
+
+        let aliases = HashMap::from([("old-commit".to_string(), "commit".to_string())]);
+        let (weekly, last_used) = count_weekly_command_usage(temp.path(), &aliases);
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly["commit"]["2025-W02"], 2);
+        assert_eq!(last_used["commit"], 1736330400);
+    }
+}
@@ -0,0 +1,504 @@
+/**
+ * [INPUT]: 依赖 crate::commands::report 的统计结果, chrono, crate::security, services::redact (可选脱敏)
+ * [OUTPUT]: 对外提供 export_report 命令
+ * [POS]: commands/ 模块的报告导出器，把统计渲染成 Markdown 或内联 SVG 图表的独立 HTML
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::report::{
+    get_activity_stats, get_period_report, ActivityStats, PeriodReport, ReportPeriod,
+};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+/// Heatmaps show at most this many weeks (the most recent ones)
+const MAX_HEATMAP_WEEKS: i64 = 53;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Renderer-neutral view of either report kind
+struct ExportData {
+    title: String,
+    subtitle: String,
+    /// (label, value) rows for the summary table
+    summary: Vec<(String, String)>,
+    daily: HashMap<String, usize>,
+    hourly: HashMap<u32, usize>,
+    /// (name, count) rows, already sorted
+    top_commands: Vec<(String, usize)>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn date_of_ms(ms: u64) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp_millis(i64::try_from(ms).ok()?).map(|dt| dt.date_naive())
+}
+
+fn period_label(start: u64, end: u64) -> String {
+    match (date_of_ms(start), date_of_ms(end.saturating_sub(1))) {
+        (Some(first), Some(last)) => format!("{} – {}", first, last),
+        _ => "All time".to_string(),
+    }
+}
+
+fn from_period_report(report: &PeriodReport) -> ExportData {
+    let mut summary = vec![
+        ("Sessions".to_string(), report.total_sessions.to_string()),
+        ("Messages".to_string(), report.total_messages.to_string()),
+        ("Active days".to_string(), report.active_days.to_string()),
+        (
+            "Longest streak".to_string(),
+            format!("{} days", report.longest_streak),
+        ),
+        ("Projects".to_string(), report.total_projects.to_string()),
+        (
+            "Peak hour".to_string(),
+            format!(
+                "{:02}:00 ({} prompts)",
+                report.peak_hour, report.peak_hour_count
+            ),
+        ),
+        (
+            "Busiest weekday".to_string(),
+            WEEKDAYS
+                .get(report.peak_weekday as usize)
+                .copied()
+                .unwrap_or("-")
+                .to_string(),
+        ),
+        (
+            "Local commands".to_string(),
+            report.total_commands.to_string(),
+        ),
+    ];
+    if let Some(project) = &report.favorite_project {
+        summary.push((
+            "Favorite project".to_string(),
+            format!("{} ({} sessions)", project.path, project.session_count),
+        ));
+    }
+    if let (Some(first), Some(last)) = (&report.first_chat_date, &report.last_chat_date) {
+        summary.push((
+            "First / last chat".to_string(),
+            format!("{} / {}", first, last),
+        ));
+    }
+
+    ExportData {
+        title: "Lovcode Report".to_string(),
+        subtitle: period_label(report.period_start, report.period_end),
+        summary,
+        daily: report.daily_activity.clone(),
+        hourly: report.hourly_distribution.clone(),
+        top_commands: report
+            .top_commands
+            .iter()
+            .map(|c| (c.name.clone(), c.count))
+            .collect(),
+    }
+}
+
+/// ActivityStats is all-time; narrow it to the period using its date/date:hour keys
+fn from_activity_stats(stats: &ActivityStats, period: Option<ReportPeriod>) -> ExportData {
+    let bounds = period.map(|p| (date_of_ms(p.start), date_of_ms(p.end.saturating_sub(1))));
+    let in_period = |date: &str| {
+        let Some((first, last)) = bounds else {
+            return true;
+        };
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return false;
+        };
+        first.is_none_or(|f| date >= f) && last.is_none_or(|l| date <= l)
+    };
+
+    let daily: HashMap<String, usize> = stats
+        .daily
+        .iter()
+        .filter(|(date, _)| in_period(date))
+        .map(|(date, count)| (date.clone(), *count))
+        .collect();
+    let mut hourly: HashMap<u32, usize> = HashMap::new();
+    for (key, count) in &stats.detailed {
+        let Some((date, hour)) = key.rsplit_once(':') else {
+            continue;
+        };
+        if let (true, Ok(hour)) = (in_period(date), hour.parse::<u32>()) {
+            *hourly.entry(hour).or_insert(0) += count;
+        }
+    }
+
+    let total: usize = daily.values().sum();
+    ExportData {
+        title: "Lovcode Activity".to_string(),
+        subtitle: period.map_or_else(|| "All time".to_string(), |p| period_label(p.start, p.end)),
+        summary: vec![
+            ("Prompts".to_string(), total.to_string()),
+            ("Active days".to_string(), daily.len().to_string()),
+        ],
+        daily,
+        hourly,
+        top_commands: Vec::new(),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// 24 vertical bars, one per hour of the day
+fn svg_hourly_chart(hourly: &HashMap<u32, usize>) -> String {
+    const BAR: u32 = 16;
+    const GAP: u32 = 4;
+    const HEIGHT: u32 = 120;
+    let max = hourly.values().copied().max().unwrap_or(0).max(1);
+    let width = 24 * (BAR + GAP);
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" role="img" aria-label="Activity by hour">"#,
+        width,
+        HEIGHT + 20,
+        width,
+        HEIGHT + 20
+    );
+    for hour in 0..24u32 {
+        let count = hourly.get(&hour).copied().unwrap_or(0);
+        let bar_height = (count * HEIGHT as usize / max) as u32;
+        let x = hour * (BAR + GAP);
+        let _ = write!(
+            svg,
+            r##"<rect x="{}" y="{}" width="{}" height="{}" rx="2" fill="#CC785C"><title>{:02}:00 · {}</title></rect>"##,
+            x,
+            HEIGHT - bar_height,
+            BAR,
+            bar_height,
+            hour,
+            count
+        );
+        if hour % 3 == 0 {
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" font-size="10" fill="#87867F">{}</text>"##,
+                x,
+                HEIGHT + 14,
+                hour
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// GitHub-style calendar: one column per week, one row per weekday (Sunday first)
+fn svg_daily_heatmap(daily: &HashMap<String, usize>) -> String {
+    const CELL: i64 = 11;
+    const STEP: i64 = 13;
+    const COLORS: [&str; 5] = ["#EBEDF0", "#F3D9CC", "#E6B299", "#D98C66", "#CC785C"];
+
+    let counts: HashMap<NaiveDate, usize> = daily
+        .iter()
+        .filter_map(|(d, c)| Some((NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()?, *c)))
+        .collect();
+    let (Some(first), Some(last)) = (counts.keys().min(), counts.keys().max()) else {
+        return String::new();
+    };
+    let last = *last;
+    let earliest = last - chrono::Duration::weeks(MAX_HEATMAP_WEEKS - 1);
+    let first = (*first).max(earliest);
+    let start = first - chrono::Duration::days(i64::from(first.weekday().num_days_from_sunday()));
+    let max = counts.values().copied().max().unwrap_or(0).max(1);
+    let weeks = (last - start).num_days() / 7 + 1;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" role="img" aria-label="Daily activity">"#,
+        weeks * STEP,
+        7 * STEP,
+        weeks * STEP,
+        7 * STEP
+    );
+    let mut day = start;
+    while day <= last {
+        let count = if day < first {
+            0
+        } else {
+            counts.get(&day).copied().unwrap_or(0)
+        };
+        let level = if count == 0 {
+            0
+        } else {
+            (count * 4).div_ceil(max).clamp(1, 4)
+        };
+        let _ = write!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="2" fill="{}"><title>{} · {}</title></rect>"#,
+            (day - start).num_days() / 7 * STEP,
+            i64::from(day.weekday().num_days_from_sunday()) * STEP,
+            CELL,
+            CELL,
+            COLORS[level],
+            day,
+            count
+        );
+        day += chrono::Duration::days(1);
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn render_markdown(data: &ExportData) -> String {
+    let mut md = format!(
+        "# {}\n\n_{}_\n\n| | |\n|---|---|\n",
+        data.title, data.subtitle
+    );
+    for (label, value) in &data.summary {
+        let _ = writeln!(md, "| {} | {} |", label, escape_markdown_cell(value));
+    }
+
+    if !data.top_commands.is_empty() {
+        md.push_str("\n## Top Commands\n\n| Command | Uses |\n|---|---|\n");
+        for (name, count) in &data.top_commands {
+            let _ = writeln!(md, "| `/{}` | {} |", escape_markdown_cell(name), count);
+        }
+    }
+
+    md.push_str("\n## Activity by Hour\n\n```\n");
+    let max = data.hourly.values().copied().max().unwrap_or(0).max(1);
+    for hour in 0..24u32 {
+        let count = data.hourly.get(&hour).copied().unwrap_or(0);
+        let _ = writeln!(
+            md,
+            "{:02}:00 {:<20} {}",
+            hour,
+            "█".repeat(count * 20 / max),
+            count
+        );
+    }
+    md.push_str("```\n");
+
+    let mut days: Vec<(&String, &usize)> = data.daily.iter().collect();
+    days.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    if !days.is_empty() {
+        md.push_str("\n## Busiest Days\n\n| Date | Prompts |\n|---|---|\n");
+        for (date, count) in days.into_iter().take(5) {
+            let _ = writeln!(md, "| {} | {} |", date, count);
+        }
+    }
+
+    md.push_str("\n---\n\nGenerated by Lovcode\n");
+    md
+}
+
+fn render_html(data: &ExportData) -> String {
+    let mut rows = String::new();
+    for (label, value) in &data.summary {
+        let _ = write!(
+            rows,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape_html(label),
+            escape_html(value)
+        );
+    }
+
+    let mut commands = String::new();
+    if !data.top_commands.is_empty() {
+        commands.push_str("<h2>Top Commands</h2><table>");
+        for (name, count) in &data.top_commands {
+            let _ = write!(
+                commands,
+                "<tr><th><code>/{}</code></th><td>{}</td></tr>",
+                escape_html(name),
+                count
+            );
+        }
+        commands.push_str("</table>");
+    }
+
+    let heatmap = svg_daily_heatmap(&data.daily);
+    let daily_section = if heatmap.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<h2>Daily Activity</h2><div class=\"chart\">{}</div>",
+            heatmap
+        )
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #F9F9F7; color: #181818; max-width: 760px; margin: 40px auto; padding: 0 20px; }}
+h1 {{ font-family: Georgia, serif; margin-bottom: 4px; }}
+.subtitle {{ color: #87867F; margin-top: 0; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 24px; }}
+th, td {{ text-align: left; padding: 6px 8px; border-bottom: 1px solid #E8E6DC; }}
+th {{ font-weight: 500; color: #5E5D59; width: 40%; }}
+.chart {{ overflow-x: auto; margin-bottom: 24px; }}
+footer {{ color: #87867F; font-size: 12px; margin-top: 40px; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="subtitle">{subtitle}</p>
+<table>{rows}</table>
+{commands}
+<h2>Activity by Hour</h2>
+<div class="chart">{hourly}</div>
+{daily_section}
+<footer>Generated by Lovcode</footer>
+</body>
+</html>
+"#,
+        title = escape_html(&data.title),
+        subtitle = escape_html(&data.subtitle),
+        rows = rows,
+        commands = commands,
+        hourly = svg_hourly_chart(&data.hourly),
+        daily_section = daily_section,
+    )
+}
+
+fn write_export(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    security::atomic_write_string(&path.to_path_buf(), content)
+        .map_err(|e| format!("Failed to write report: {}", e))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Render a report to `path`.
+///
+/// kind: "period" (requires period; use a year range for the annual wrap-up) | "activity"
+/// format: "markdown" | "html"
+/// redact: replace API keys, tokens and passwords with `[REDACTED:<kind>]`
+#[tauri::command]
+pub async fn export_report(
+    kind: String,
+    period: Option<ReportPeriod>,
+    format: String,
+    path: String,
+    redact: Option<bool>,
+) -> AppResult<String> {
+    security::validate_user_path(&path)?;
+    if !matches!(format.as_str(), "markdown" | "html") {
+        return Err(AppError::invalid_input(format!("Unknown export format: {}", format)));
+    }
+
+    let data = match kind.as_str() {
+        "period" => {
+            let period = period.ok_or_else(|| {
+                AppError::invalid_input("A period is required for period reports")
+            })?;
+            from_period_report(&get_period_report(period.start, period.end).await?)
+        }
+        "activity" => from_activity_stats(&get_activity_stats(None).await?, period),
+        _ => return Err(AppError::invalid_input(format!("Unknown report kind: {}", kind))),
+    };
+
+    let content = if format == "html" {
+        render_html(&data)
+    } else {
+        render_markdown(&data)
+    };
+    let content = if redact.unwrap_or(false) {
+        crate::services::redact::redact(&content)
+    } else {
+        content
+    };
+    write_export(Path::new(&path), &content).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ExportData {
+        ExportData {
+            title: "Lovcode Report".to_string(),
+            subtitle: "2025-01-01 – 2025-12-31".to_string(),
+            summary: vec![("Favorite project".to_string(), "<a|b>".to_string())],
+            daily: HashMap::from([("2025-03-02".to_string(), 4), ("2025-03-04".to_string(), 1)]),
+            hourly: HashMap::from([(9, 10), (22, 5)]),
+            top_commands: vec![("commit".to_string(), 12)],
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render_markdown(&sample());
+        assert!(md.starts_with("# Lovcode Report\n\n_2025-01-01 – 2025-12-31_\n"));
+        assert!(md.contains("| Favorite project | <a\\|b> |"));
+        assert!(md.contains("| `/commit` | 12 |"));
+        assert!(md.contains(&format!("09:00 {} 10", "█".repeat(20))));
+        assert!(md.contains(&format!("22:00 {:<20} 5", "█".repeat(10))));
+        assert!(md.contains("| 2025-03-02 | 4 |"));
+    }
+
+    #[test]
+    fn test_render_html_escapes_and_embeds_svg() {
+        let html = render_html(&sample());
+        assert!(html.contains("<td>&lt;a|b&gt;</td>"));
+        assert!(!html.contains("<a|b>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.contains("<title>09:00 · 10</title>"));
+        assert!(html.contains("<title>2025-03-02 · 4</title>"));
+    }
+
+    #[test]
+    fn test_daily_heatmap_layout() {
+        assert!(svg_daily_heatmap(&HashMap::new()).is_empty());
+
+        // 2025-03-02 is a Sunday, so both days fall in the first column
+        let svg = svg_daily_heatmap(&sample().daily);
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains(r##"<rect x="0" y="26" width="11" height="11" rx="2" fill="#F3D9CC"><title>2025-03-04 · 1</title>"##));
+    }
+
+    #[test]
+    fn test_activity_stats_filtered_to_period() {
+        let stats = ActivityStats {
+            daily: HashMap::from([("2025-12-31".to_string(), 2), ("2026-01-01".to_string(), 3)]),
+            hourly: HashMap::new(),
+            detailed: HashMap::from([
+                ("2025-12-31:23".to_string(), 2),
+                ("2026-01-01:08".to_string(), 3),
+            ]),
+            weekday_hour: Vec::new(),
+            utc_offset_minutes: 0,
+        };
+        let period = ReportPeriod {
+            start: 1767225600000, // 2026-01-01
+            end: 1798761600000,   // 2027-01-01
+        };
+        let data = from_activity_stats(&stats, Some(period));
+        assert_eq!(data.daily.len(), 1);
+        assert_eq!(data.hourly, HashMap::from([(8, 3)]));
+        assert_eq!(data.subtitle, "2026-01-01 – 2026-12-31");
+        assert_eq!(from_activity_stats(&stats, None).daily.len(), 2);
+    }
+}
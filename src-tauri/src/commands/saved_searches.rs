@@ -0,0 +1,291 @@
+/**
+ * [INPUT]: 依赖 crate::store (searches.json 预写日志存储), crate::security, uuid
+ * [OUTPUT]: 对外提供 save_search, list_saved_searches, delete_saved_search, get_search_history 命令, record_search 工具函数 (供 search_chats 调用) 与 SavedSearch/SearchFilters/SearchHistoryEntry 类型
+ * [POS]: commands/ 模块的保存的搜索与搜索历史，常用排查查询一键重跑，search_chats 带 record 提交的查询记入历史
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::store::{self, Schema};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// The search_chats parameters besides the query
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub project_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    pub query: String,
+    pub filters: SearchFilters,
+    /// Unix seconds
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub filters: SearchFilters,
+    /// Unix seconds of the latest run
+    pub searched_at: u64,
+    /// Results of the latest run
+    pub result_count: usize,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SearchStore {
+    /// Oldest first
+    #[serde(default)]
+    saved: Vec<SavedSearch>,
+    /// Newest first
+    #[serde(default)]
+    history: Vec<SearchHistoryEntry>,
+}
+
+const SEARCHES_SCHEMA: Schema = Schema::new(1);
+/// Queries kept in the history (oldest are dropped first)
+const MAX_HISTORY: usize = 100;
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Serializes read-modify-write of searches.json
+static SEARCHES_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn searches_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("searches.json")
+}
+
+fn load_searches(path: &Path) -> Result<SearchStore, String> {
+    Ok(store::load_as(path, &SEARCHES_SCHEMA)?.unwrap_or_default())
+}
+
+fn update_searches<T>(path: &Path, f: impl FnOnce(&mut SearchStore) -> T) -> Result<T, String> {
+    let _guard = SEARCHES_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut data = load_searches(path)?;
+    let result = f(&mut data);
+    store::save(path, &SEARCHES_SCHEMA, &data)?;
+    Ok(result)
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Saving under an existing name replaces that search's query and filters
+fn save_search_in(
+    path: &Path,
+    name: &str,
+    query: &str,
+    filters: SearchFilters,
+    now: u64,
+) -> AppResult<SavedSearch> {
+    let (name, query) = (name.trim(), query.trim());
+    if name.is_empty() || query.is_empty() {
+        return Err(AppError::invalid_input(
+            "Saved search needs a name and a query",
+        ));
+    }
+    Ok(update_searches(path, |data| {
+        if let Some(existing) = data.saved.iter_mut().find(|s| s.name == name) {
+            existing.query = query.to_string();
+            existing.filters = filters;
+            existing.updated_at = now;
+            return existing.clone();
+        }
+        let saved = SavedSearch {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            query: query.to_string(),
+            filters,
+            created_at: now,
+            updated_at: now,
+        };
+        data.saved.push(saved.clone());
+        saved
+    })?)
+}
+
+/// Running the same query with the same filters again moves it to the top
+fn push_history(
+    data: &mut SearchStore,
+    query: &str,
+    filters: SearchFilters,
+    result_count: usize,
+    now: u64,
+) {
+    data.history
+        .retain(|entry| entry.query != query || entry.filters != filters);
+    data.history.insert(
+        0,
+        SearchHistoryEntry {
+            query: query.to_string(),
+            filters,
+            searched_at: now,
+            result_count,
+        },
+    );
+    data.history.truncate(MAX_HISTORY);
+}
+
+/// Add a submitted search_chats query to the history. Failures are logged, never returned: the
+/// search itself already succeeded.
+pub fn record_search(query: &str, filters: SearchFilters, result_count: usize) {
+    let query = query.trim();
+    if query.is_empty() {
+        return;
+    }
+    let now = now_secs();
+    if let Err(e) = update_searches(&searches_path(), |data| {
+        push_history(data, query, filters, result_count, now);
+    }) {
+        tracing::warn!("Failed to record search history: {}", e);
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn save_search(
+    name: String,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> AppResult<SavedSearch> {
+    tauri::async_runtime::spawn_blocking(move || {
+        save_search_in(
+            &searches_path(),
+            &name,
+            &query,
+            filters.unwrap_or_default(),
+            now_secs(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Saved searches, most recently saved or updated first
+#[tauri::command]
+pub async fn list_saved_searches() -> AppResult<Vec<SavedSearch>> {
+    Ok(tauri::async_runtime::spawn_blocking(|| {
+        load_searches(&searches_path()).map(|data| {
+            let mut saved = data.saved;
+            saved.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+            saved
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[tauri::command]
+pub async fn delete_saved_search(id: String) -> AppResult<()> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let removed = update_searches(&searches_path(), |data| {
+            let before = data.saved.len();
+            data.saved.retain(|s| s.id != id);
+            data.saved.len() != before
+        })?;
+        if removed {
+            Ok(())
+        } else {
+            Err(AppError::not_found("Saved search not found").with_context(id))
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Recent search_chats queries, newest first
+#[tauri::command]
+pub async fn get_search_history(limit: Option<usize>) -> AppResult<Vec<SearchHistoryEntry>> {
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        load_searches(&searches_path()).map(|data| {
+            let mut history = data.history;
+            history.truncate(limit.unwrap_or(DEFAULT_HISTORY_LIMIT));
+            history
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_search_upserts_by_name() {
+        let temp = TempDir::new().expect("create temp dir");
+        let path = temp.path().join("searches.json");
+        let scoped = SearchFilters {
+            project_id: Some("p1".to_string()),
+            limit: None,
+        };
+
+        let first =
+            save_search_in(&path, " Panics ", "panic", SearchFilters::default(), 1).expect("save");
+        let updated =
+            save_search_in(&path, "Panics", "panic OR abort", scoped.clone(), 2).expect("update");
+        assert_eq!(first.id, updated.id);
+        assert_eq!((updated.created_at, updated.updated_at), (1, 2));
+
+        let data = load_searches(&path).expect("load");
+        assert_eq!(data.saved.len(), 1);
+        assert_eq!(data.saved[0].query, "panic OR abort");
+        assert_eq!(data.saved[0].filters, scoped);
+
+        let err = save_search_in(&path, "Empty", "  ", SearchFilters::default(), 3)
+            .expect_err("empty query");
+        assert_eq!(err.code(), crate::errors::ErrorCode::InvalidInput);
+    }
+
+    #[test]
+    fn test_history_dedupes_by_query_and_filters() {
+        let mut data = SearchStore::default();
+        let scoped = SearchFilters {
+            project_id: Some("p1".to_string()),
+            limit: None,
+        };
+        push_history(&mut data, "tokio", SearchFilters::default(), 3, 1);
+        push_history(&mut data, "tokio", scoped.clone(), 1, 2);
+        push_history(&mut data, "tokio", SearchFilters::default(), 5, 3);
+
+        let runs: Vec<(u64, usize)> = data
+            .history
+            .iter()
+            .map(|e| (e.searched_at, e.result_count))
+            .collect();
+        assert_eq!(runs, vec![(3, 5), (2, 1)]);
+        assert_eq!(data.history[1].filters, scoped);
+
+        for n in 0..MAX_HISTORY {
+            push_history(&mut data, &n.to_string(), SearchFilters::default(), 0, 4);
+        }
+        assert_eq!(data.history.len(), MAX_HISTORY);
+    }
+}
@@ -0,0 +1,376 @@
+/**
+ * [INPUT]: 依赖 ~/.claude/projects/<project_id>/<session_id>.jsonl, crate::jsonl (流式读取), crate::commands::projects (read_session_head, decode_project_path), crate::security
+ * [OUTPUT]: 对外提供 get_session_chain 命令与 SessionChain/ChainedSession/ChainLink 类型
+ * [POS]: commands/ 模块的会话续接链，识别压缩/恢复后新开的会话文件与前一会话的关联 (summary leafUuid、跨文件 parentUuid、复制的消息)，供 UI 拼接成连续对话
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::projects::{decode_project_path, read_session_head};
+use crate::errors::{AppError, AppResult};
+use crate::jsonl;
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How a session was found to continue the one before it, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainLink {
+    /// A summary line's `leafUuid` names a message of the previous session
+    Summary,
+    /// A `parentUuid` / `logicalParentUuid` points into the previous session
+    Parent,
+    /// The session starts with messages copied from the previous one
+    Copied,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainedSession {
+    pub session_id: String,
+    pub summary: Option<String>,
+    pub message_count: usize,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// Unix seconds
+    pub last_modified: u64,
+    /// How it links to the previous session; None for the first one
+    pub linked_by: Option<ChainLink>,
+    /// Contains a compaction summary
+    pub compacted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionChain {
+    pub project_id: String,
+    pub project_path: String,
+    /// Oldest first; always contains the requested session
+    pub sessions: Vec<ChainedSession>,
+}
+
+/// The fields of a session line that carry links
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainLine {
+    #[serde(rename = "type")]
+    line_type: Option<String>,
+    uuid: Option<String>,
+    parent_uuid: Option<String>,
+    logical_parent_uuid: Option<String>,
+    leaf_uuid: Option<String>,
+    timestamp: Option<String>,
+    is_sidechain: Option<bool>,
+    is_compact_summary: Option<bool>,
+}
+
+/// One session file, reduced to what linking needs
+struct SessionScan {
+    session_id: String,
+    /// Unix ms; a session only links to older ones
+    mtime: u64,
+    uuids: HashSet<String>,
+    /// uuids of other sessions this one refers to
+    refs: Vec<(ChainLink, String)>,
+    message_count: usize,
+    started_at: Option<String>,
+    ended_at: Option<String>,
+    compacted: bool,
+}
+
+/// Lines read for the summary, as list_sessions does
+const SESSION_HEAD_LINES: usize = 20;
+
+// ============================================================================
+// Scanning
+// ============================================================================
+
+fn scan_lines(session_id: &str, mtime: u64, lines: impl Iterator<Item = ChainLine>) -> SessionScan {
+    let mut scan = SessionScan {
+        session_id: session_id.to_string(),
+        mtime,
+        uuids: HashSet::new(),
+        refs: Vec::new(),
+        message_count: 0,
+        started_at: None,
+        ended_at: None,
+        compacted: false,
+    };
+
+    for line in lines {
+        if line.line_type.as_deref() == Some("summary") {
+            if let Some(leaf) = line.leaf_uuid {
+                scan.refs.push((ChainLink::Summary, leaf));
+            }
+            continue;
+        }
+        if line.is_sidechain == Some(true) {
+            continue;
+        }
+        let Some(uuid) = line.uuid else {
+            continue;
+        };
+        // Parents come before their children, so a parent not seen yet is in another file
+        for parent in [line.parent_uuid, line.logical_parent_uuid]
+            .into_iter()
+            .flatten()
+        {
+            if !scan.uuids.contains(&parent) {
+                scan.refs.push((ChainLink::Parent, parent));
+            }
+        }
+        if scan.uuids.is_empty() {
+            scan.refs.push((ChainLink::Copied, uuid.clone()));
+        }
+        scan.uuids.insert(uuid);
+
+        if matches!(line.line_type.as_deref(), Some("user" | "assistant")) {
+            scan.message_count += 1;
+        }
+        scan.compacted |= line.is_compact_summary == Some(true);
+        if let Some(timestamp) = line.timestamp {
+            if scan.started_at.is_none() {
+                scan.started_at = Some(timestamp.clone());
+            }
+            scan.ended_at = Some(timestamp);
+        }
+    }
+    scan.refs.sort_by_key(|(link, _)| *link);
+    scan
+}
+
+fn scan_session(path: &Path, session_id: &str) -> Option<SessionScan> {
+    let mtime = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_millis();
+    let lines = jsonl::open(path).ok()?;
+    Some(scan_lines(
+        session_id,
+        u64::try_from(mtime).unwrap_or(u64::MAX),
+        lines.records::<ChainLine>().map(|(_, line)| line),
+    ))
+}
+
+// ============================================================================
+// Linking
+// ============================================================================
+
+/// Previous session of each scan. A reference resolves to the most recently modified
+/// older session holding the uuid: with A → B → C, C's copy of A's first message is
+/// also in B, and B is its direct predecessor.
+fn link_previous(scans: &[SessionScan]) -> Vec<Option<(usize, ChainLink)>> {
+    let key = |i: usize| (scans[i].mtime, scans[i].session_id.as_str());
+    let mut owners: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, scan) in scans.iter().enumerate() {
+        for uuid in &scan.uuids {
+            owners.entry(uuid.as_str()).or_default().push(i);
+        }
+    }
+
+    (0..scans.len())
+        .map(|i| {
+            scans[i].refs.iter().find_map(|(link, uuid)| {
+                owners
+                    .get(uuid.as_str())?
+                    .iter()
+                    .copied()
+                    .filter(|&j| key(j) < key(i))
+                    .max_by_key(|&j| key(j))
+                    .map(|j| (j, *link))
+            })
+        })
+        .collect()
+}
+
+/// Indexes of the chain through `target`, oldest first, with each one's link. Past
+/// `target`, a session continued more than once follows the most recently modified
+/// continuation.
+fn chain_through(scans: &[SessionScan], target: usize) -> Vec<(usize, Option<ChainLink>)> {
+    let previous = link_previous(scans);
+
+    let mut chain = vec![(target, previous[target].map(|(_, link)| link))];
+    let mut current = target;
+    while let Some((prev, _)) = previous[current] {
+        chain.push((prev, previous[prev].map(|(_, link)| link)));
+        current = prev;
+    }
+    chain.reverse();
+
+    current = target;
+    loop {
+        let next = (0..scans.len())
+            .filter_map(|j| match previous[j] {
+                Some((prev, link)) if prev == current => Some((j, link)),
+                _ => None,
+            })
+            .max_by_key(|&(j, _)| (scans[j].mtime, scans[j].session_id.as_str()));
+        let Some((next, link)) = next else {
+            break;
+        };
+        chain.push((next, Some(link)));
+        current = next;
+    }
+    chain
+}
+
+fn find_project(projects: &Path, session_id: &str) -> Option<String> {
+    fs::read_dir(projects)
+        .ok()?
+        .flatten()
+        .find(|entry| entry.path().join(format!("{}.jsonl", session_id)).is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+}
+
+fn session_chain_in(projects: &Path, session_id: &str) -> AppResult<SessionChain> {
+    if session_id.is_empty() || session_id.contains(['/', '\\']) || session_id.contains("..") {
+        return Err(AppError::invalid_input(format!(
+            "Invalid session id: {}",
+            session_id
+        )));
+    }
+    let project_id = find_project(projects, session_id)
+        .ok_or_else(|| AppError::not_found("Session not found").with_context(session_id))?;
+    let project_dir = projects.join(&project_id);
+
+    let scans: Vec<SessionScan> = fs::read_dir(&project_dir)
+        .map_err(|e| AppError::io("read project directory", &e))?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".jsonl")?;
+            if id.starts_with("agent-") {
+                return None;
+            }
+            scan_session(&entry.path(), id)
+        })
+        .collect();
+    let target = scans
+        .iter()
+        .position(|s| s.session_id == session_id)
+        .ok_or_else(|| AppError::not_found("Session not found").with_context(session_id))?;
+
+    let sessions = chain_through(&scans, target)
+        .into_iter()
+        .map(|(i, linked_by)| {
+            let scan = &scans[i];
+            let path = project_dir.join(format!("{}.jsonl", scan.session_id));
+            ChainedSession {
+                session_id: scan.session_id.clone(),
+                summary: read_session_head(&path, SESSION_HEAD_LINES).0,
+                message_count: scan.message_count,
+                started_at: scan.started_at.clone(),
+                ended_at: scan.ended_at.clone(),
+                last_modified: scan.mtime / 1000,
+                linked_by,
+                compacted: scan.compacted,
+            }
+        })
+        .collect();
+
+    Ok(SessionChain {
+        project_path: decode_project_path(&project_id),
+        project_id,
+        sessions,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// The sessions a conversation spans across compactions and resumes, oldest first
+#[tauri::command]
+pub async fn get_session_chain(session_id: String) -> AppResult<SessionChain> {
+    tauri::async_runtime::spawn_blocking(move || {
+        session_chain_in(
+            &security::get_claude_dir_or_fallback().join("projects"),
+            &session_id,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(session_id: &str, mtime: u64, jsonl_text: &str) -> SessionScan {
+        let lines = jsonl::from_reader(jsonl_text.as_bytes())
+            .records::<ChainLine>()
+            .map(|(_, line)| line);
+        scan_lines(session_id, mtime, lines)
+    }
+
+    #[test]
+    fn test_chain_links_summary_parent_and_copies() {
+        let scans = vec![
+            // Original conversation
+            scan(
+                "a",
+                1,
+                r#"{"type":"user","uuid":"a1","parentUuid":null,"timestamp":"t1"}
+{"type":"assistant","uuid":"a2","parentUuid":"a1","timestamp":"t2"}"#,
+            ),
+            // Resumed: copies a's messages, then goes on
+            scan(
+                "b",
+                2,
+                r#"{"type":"user","uuid":"a1","parentUuid":null}
+{"type":"assistant","uuid":"a2","parentUuid":"a1"}
+{"type":"user","uuid":"b1","parentUuid":"a2"}"#,
+            ),
+            // Compacted continuation of b, named by its summary line
+            scan(
+                "c",
+                3,
+                r#"{"type":"summary","summary":"Fix","leafUuid":"b1"}
+{"type":"user","uuid":"c1","parentUuid":null,"isCompactSummary":true}
+{"type":"assistant","uuid":"c2","parentUuid":"c1","isSidechain":true,"logicalParentUuid":"zz"}"#,
+            ),
+            // Continues c through a cross-file parent
+            scan(
+                "d",
+                4,
+                r#"{"type":"system","uuid":"d1","logicalParentUuid":"c1"}"#,
+            ),
+            // Unrelated, and a session older than the message it points at
+            scan("x", 5, r#"{"type":"user","uuid":"x1"}"#),
+            scan("f", 0, r#"{"type":"user","uuid":"f1","parentUuid":"a2"}"#),
+        ];
+
+        let chain = chain_through(&scans, 2);
+        let ids: Vec<(&str, Option<ChainLink>)> = chain
+            .iter()
+            .map(|&(i, link)| (scans[i].session_id.as_str(), link))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("a", None),
+                ("b", Some(ChainLink::Copied)),
+                ("c", Some(ChainLink::Summary)),
+                ("d", Some(ChainLink::Parent)),
+            ]
+        );
+        assert!(scans[2].compacted);
+        assert_eq!(scans[2].message_count, 1);
+        assert_eq!(scans[0].started_at.as_deref(), Some("t1"));
+
+        // f is older than a, so it can't continue it
+        assert_eq!(chain_through(&scans, 5), vec![(5, None)]);
+        assert_eq!(chain_through(&scans, 4), vec![(4, None)]);
+    }
+}
@@ -0,0 +1,724 @@
+/**
+ * [INPUT]: 依赖 commands::{projects (会话列表), sessions (read_session_messages), importers (导入会话)}, crate::security, services::redact (脱敏), chrono, pulldown-cmark + syntect (HTML 分享页)
+ * [OUTPUT]: 对外提供 export_sessions_to_vault, export_session_html 命令, VaultExportFilter/VaultExportReport
+ * [POS]: commands/ 模块的会话导出器，把会话写成 Obsidian/Logseq 笔记库 (YAML frontmatter + wiki-link) 或可分享的独立 HTML 页面
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::importers::list_imported_sessions;
+use crate::commands::projects::{decode_project_path, list_all_sessions, read_session_head};
+use crate::commands::sessions::read_session_messages;
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::types::{Message, Session};
+use chrono::{DateTime, Local};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+/// First frontmatter line of every exported note; only files starting with it are overwritten
+const GENERATOR_LINE: &str = "---\ngenerator: lovcode\n";
+const SESSIONS_DIR: &str = "Sessions";
+const PROJECTS_DIR: &str = "Projects";
+/// Titles longer than this are cut in note names
+const MAX_TITLE_CHARS: usize = 60;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct VaultExportFilter {
+    /// Only these projects; every project when empty
+    pub project_ids: Vec<String>,
+    /// Unix seconds; sessions last active earlier are left out
+    pub since: Option<u64>,
+    /// Unix seconds; sessions last active later are left out
+    pub until: Option<u64>,
+    /// Also export sessions imported from other tools
+    pub include_imported: bool,
+    /// Include tool calls and their output, as folded callouts
+    pub include_tools: bool,
+    /// Replace API keys, tokens and passwords with `[REDACTED:<kind>]`
+    pub redact: bool,
+}
+
+impl VaultExportFilter {
+    fn matches(&self, session: &Session) -> bool {
+        (self.project_ids.is_empty() || self.project_ids.contains(&session.project_id))
+            && self
+                .since
+                .is_none_or(|since| session.last_modified >= since)
+            && self
+                .until
+                .is_none_or(|until| session.last_modified <= until)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VaultExportReport {
+    pub dest: String,
+    /// Session notes written
+    pub sessions: usize,
+    /// Project notes written
+    pub projects: usize,
+    /// Sessions that couldn't be read and notes that weren't overwritten, with the reason
+    pub errors: Vec<String>,
+}
+
+/// A session with the names it gets in the vault
+struct SessionNote {
+    session: Session,
+    /// Local time of the first message
+    started: DateTime<Local>,
+    /// Note name without ".md", also the wiki-link target
+    name: String,
+    title: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// A double-quoted YAML scalar; JSON strings are valid YAML
+fn yaml_str(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Lowercase letters, digits and '-', usable as a tag segment
+fn tag_slug(text: &str) -> String {
+    let slug: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    slug.split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Characters that break file names or wiki-links become spaces
+fn note_name(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| {
+            if c.is_control() || "/\\:*?\"<>|#^[]".contains(c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let name = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    let name: String = name.chars().take(MAX_TITLE_CHARS).collect();
+    name.trim().trim_end_matches('.').trim().to_string()
+}
+
+fn local_time(timestamp: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Local))
+}
+
+/// Last path component of the project, or its id
+fn project_title(session: &Session) -> String {
+    session
+        .project_path
+        .as_deref()
+        .and_then(|p| Path::new(p).file_name())
+        .map_or_else(
+            || session.project_id.clone(),
+            |n| n.to_string_lossy().to_string(),
+        )
+}
+
+/// "claude-code" for Claude sessions, the tool name for imported ones
+fn source_tag(session: &Session) -> String {
+    session
+        .project_id
+        .strip_prefix(crate::commands::importers::IMPORTED_PROJECT_PREFIX)
+        .map_or_else(|| "claude-code".to_string(), tag_slug)
+}
+
+fn visible(message: &Message, include_tools: bool) -> bool {
+    !message.is_meta && (include_tools || !message.is_tool) && !message.content.trim().is_empty()
+}
+
+fn render_session_note(
+    note: &SessionNote,
+    project_note: &str,
+    previous: Option<&str>,
+    next: Option<&str>,
+    messages: &[Message],
+    include_tools: bool,
+) -> String {
+    let session = &note.session;
+    let tags = [
+        "lovcode".to_string(),
+        source_tag(session),
+        format!("project/{}", tag_slug(project_note)),
+    ];
+    let mut md = String::from(GENERATOR_LINE);
+    let _ = writeln!(md, "type: session");
+    let _ = writeln!(
+        md,
+        "project: {}",
+        yaml_str(&format!("[[{}]]", project_note))
+    );
+    if let Some(path) = &session.project_path {
+        let _ = writeln!(md, "project_path: {}", yaml_str(path));
+    }
+    let _ = writeln!(md, "session_id: {}", yaml_str(&session.id));
+    let _ = writeln!(md, "date: {}", note.started.format("%Y-%m-%d"));
+    let _ = writeln!(md, "started: {}", note.started.to_rfc3339());
+    let _ = writeln!(md, "tags: [{}]", tags.join(", "));
+    let _ = writeln!(md, "summary: {}", yaml_str(&note.title));
+    let _ = writeln!(
+        md,
+        "messages: {}",
+        messages.iter().filter(|m| visible(m, false)).count()
+    );
+    md.push_str("---\n\n");
+
+    let _ = writeln!(md, "# {}\n", note.title);
+    let mut nav = vec![format!("Project: [[{}]]", project_note)];
+    if let Some(previous) = previous {
+        nav.push(format!("Previous: [[{}]]", previous));
+    }
+    if let Some(next) = next {
+        nav.push(format!("Next: [[{}]]", next));
+    }
+    let _ = writeln!(md, "{}\n", nav.join(" · "));
+
+    for message in messages.iter().filter(|m| visible(m, include_tools)) {
+        let time = local_time(&message.timestamp)
+            .map(|t| format!(" · {}", t.format("%H:%M")))
+            .unwrap_or_default();
+        if message.is_tool {
+            let _ = writeln!(md, "> [!example]- Tool{}", time);
+            for line in message.content.trim().lines() {
+                let _ = writeln!(md, "> {}", line);
+            }
+            md.push('\n');
+        } else {
+            let speaker = if message.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            let _ = writeln!(md, "## {}{}\n\n{}\n", speaker, time, message.content.trim());
+        }
+    }
+    md
+}
+
+/// Project note linking to its sessions, newest first
+fn render_project_note(name: &str, path: Option<&str>, notes: &[&SessionNote]) -> String {
+    let mut md = String::from(GENERATOR_LINE);
+    let _ = writeln!(md, "type: project");
+    if let Some(path) = path {
+        let _ = writeln!(md, "path: {}", yaml_str(path));
+    }
+    let _ = writeln!(md, "sessions: {}", notes.len());
+    let _ = writeln!(md, "tags: [lovcode, project/{}]", tag_slug(name));
+    md.push_str("---\n\n");
+    let _ = writeln!(md, "# {}\n", name);
+    if let Some(path) = path {
+        let _ = writeln!(md, "`{}`\n", path);
+    }
+    md.push_str("## Sessions\n\n");
+    for note in notes.iter().rev() {
+        let _ = writeln!(
+            md,
+            "- [[{}|{}]] · {}",
+            note.name,
+            note.title,
+            note.started.format("%Y-%m-%d")
+        );
+    }
+    md
+}
+
+/// Write a note unless a file the export didn't create is in the way
+fn write_note(path: &Path, content: &str) -> Result<(), String> {
+    if path.exists()
+        && !fs::read_to_string(path).is_ok_and(|existing| existing.starts_with(GENERATOR_LINE))
+    {
+        return Err(format!(
+            "{}: not created by Lovcode, left as is",
+            path.display()
+        ));
+    }
+    security::atomic_write_string(&path.to_path_buf(), content)
+        .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_vault(
+    dest: &Path,
+    sessions: Vec<Session>,
+    filter: &VaultExportFilter,
+) -> VaultExportReport {
+    let mut report = VaultExportReport {
+        dest: dest.to_string_lossy().to_string(),
+        sessions: 0,
+        projects: 0,
+        errors: Vec::new(),
+    };
+    let finish = |content: String| {
+        if filter.redact {
+            crate::services::redact::redact(&content)
+        } else {
+            content
+        }
+    };
+
+    // Names depend on when each session started, so read them all once up front
+    let mut notes = Vec::new();
+    for session in sessions {
+        let messages = match read_session_messages(&session.project_id, &session.id) {
+            Ok(messages) => messages,
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("{}/{}: {}", session.project_id, session.id, e));
+                continue;
+            }
+        };
+        let Some(first) = messages.iter().find(|m| visible(m, false)) else {
+            continue;
+        };
+        let started = local_time(&first.timestamp)
+            .or_else(|| {
+                i64::try_from(session.last_modified)
+                    .ok()
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                    .map(Into::into)
+            })
+            .unwrap_or_default();
+        let title = session
+            .summary
+            .clone()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| first.content.lines().next().unwrap_or_default().to_string());
+        let title = note_name(&title);
+        let short_id: String = session.id.chars().take(8).collect();
+        notes.push(SessionNote {
+            name: note_name(&format!(
+                "{} {} ({})",
+                started.format("%Y-%m-%d"),
+                title,
+                short_id
+            )),
+            title,
+            started,
+            session,
+        });
+    }
+
+    // Group by project, oldest session first; project note names must be unique
+    let mut by_project: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, note) in notes.iter().enumerate() {
+        by_project
+            .entry(note.session.project_id.clone())
+            .or_default()
+            .push(index);
+    }
+    let mut taken = HashSet::new();
+    let sessions_dir = dest.join(SESSIONS_DIR);
+    let projects_dir = dest.join(PROJECTS_DIR);
+    for dir in [&sessions_dir, &projects_dir] {
+        if let Err(e) = fs::create_dir_all(dir) {
+            report.errors.push(format!("{}: {}", dir.display(), e));
+            return report;
+        }
+    }
+
+    for indices in by_project.values_mut() {
+        indices.sort_by_key(|&i| notes.get(i).map(|n| n.started));
+        let project_notes: Vec<&SessionNote> =
+            indices.iter().filter_map(|&i| notes.get(i)).collect();
+        let Some(first) = project_notes.first() else {
+            continue;
+        };
+        let base = note_name(&project_title(&first.session));
+        let mut project_name = base.clone();
+        let mut n = 2;
+        while !taken.insert(project_name.to_lowercase()) {
+            project_name = format!("{} {}", base, n);
+            n += 1;
+        }
+
+        for (position, note) in project_notes.iter().enumerate() {
+            let previous = position
+                .checked_sub(1)
+                .and_then(|p| project_notes.get(p))
+                .map(|n| n.name.as_str());
+            let next = project_notes.get(position + 1).map(|n| n.name.as_str());
+            let messages = match read_session_messages(&note.session.project_id, &note.session.id) {
+                Ok(messages) => messages,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", note.name, e));
+                    continue;
+                }
+            };
+            let content = render_session_note(
+                note,
+                &project_name,
+                previous,
+                next,
+                &messages,
+                filter.include_tools,
+            );
+            let path = sessions_dir.join(format!("{}.md", note.name));
+            match write_note(&path, &finish(content)) {
+                Ok(()) => report.sessions += 1,
+                Err(e) => report.errors.push(e),
+            }
+        }
+
+        let content = render_project_note(
+            &project_name,
+            first.session.project_path.as_deref(),
+            &project_notes,
+        );
+        let path = projects_dir.join(format!("{}.md", project_name));
+        match write_note(&path, &finish(content)) {
+            Ok(()) => report.projects += 1,
+            Err(e) => report.errors.push(e),
+        }
+    }
+    report
+}
+
+// ============================================================================
+// HTML Share Page
+// ============================================================================
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static CODE_THEME: LazyLock<Option<Theme>> =
+    LazyLock::new(|| ThemeSet::load_defaults().themes.remove("InspiredGitHub"));
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A code block with inline styles, so the page needs no stylesheet or script for it
+fn highlight_code(code: &str, lang: &str) -> String {
+    let syntax = SYNTAXES
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+    CODE_THEME
+        .as_ref()
+        .and_then(|theme| highlighted_html_for_string(code, &SYNTAXES, syntax, theme).ok())
+        .unwrap_or_else(|| format!("<pre><code>{}</code></pre>", escape_html(code)))
+}
+
+/// Markdown to HTML. Raw HTML in a message is shown as text, never rendered.
+fn render_markdown(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut events = Vec::new();
+    let mut code: Option<(String, String)> = None;
+    for event in Parser::new_ext(text, options) {
+        match (event, code.as_mut()) {
+            (Event::Start(Tag::CodeBlock(kind)), _) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((lang, String::new()));
+            }
+            (Event::Text(chunk), Some((_, body))) => body.push_str(&chunk),
+            (Event::End(TagEnd::CodeBlock), Some((lang, body))) => {
+                events.push(Event::Html(highlight_code(body, lang).into()));
+                code = None;
+            }
+            (Event::Html(raw) | Event::InlineHtml(raw), _) => events.push(Event::Text(raw)),
+            (event, _) => events.push(event),
+        }
+    }
+    let mut out = String::new();
+    pulldown_cmark::html::push_html(&mut out, events.into_iter());
+    out
+}
+
+fn render_share_page(title: &str, subtitle: &str, messages: &[Message]) -> String {
+    let mut body = String::new();
+    for message in messages.iter().filter(|m| visible(m, true)) {
+        let time = local_time(&message.timestamp)
+            .map(|t| format!(" <time>{}</time>", t.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        if message.is_tool {
+            let first_line = message.content.trim().lines().next().unwrap_or_default();
+            let preview: String = first_line.chars().take(80).collect();
+            let _ = writeln!(
+                body,
+                "<details class=\"tool\"><summary>{}</summary>{}</details>",
+                escape_html(&preview),
+                highlight_code(message.content.trim(), "")
+            );
+            continue;
+        }
+        let (class, speaker) = if message.role == "user" {
+            ("user", "User")
+        } else {
+            ("assistant", "Assistant")
+        };
+        let _ = writeln!(
+            body,
+            "<div class=\"msg {}\"><div class=\"meta\">{}{}</div><div class=\"bubble\">{}</div></div>",
+            class,
+            speaker,
+            time,
+            render_markdown(&message.content)
+        );
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #F9F9F7; color: #181818; max-width: 820px; margin: 40px auto; padding: 0 20px; line-height: 1.55; }}
+h1 {{ font-family: Georgia, serif; margin-bottom: 4px; }}
+.subtitle {{ color: #87867F; margin-top: 0; margin-bottom: 32px; }}
+.msg {{ display: flex; flex-direction: column; margin: 16px 0; }}
+.msg.user {{ align-items: flex-end; }}
+.meta {{ font-size: 12px; color: #87867F; margin: 0 6px 4px; }}
+.meta time {{ margin-left: 4px; }}
+.bubble {{ max-width: 85%; padding: 10px 14px; border-radius: 14px; overflow-x: auto; }}
+.msg.user .bubble {{ background: #CC785C; color: #FFFFFF; border-bottom-right-radius: 4px; }}
+.msg.user .bubble a {{ color: #FFFFFF; }}
+.msg.assistant .bubble {{ background: #FFFFFF; border: 1px solid #E8E6DC; border-bottom-left-radius: 4px; }}
+.bubble > :first-child {{ margin-top: 0; }}
+.bubble > :last-child {{ margin-bottom: 0; }}
+pre {{ padding: 10px 12px; border-radius: 8px; overflow-x: auto; font-size: 13px; border: 1px solid #E8E6DC; }}
+code {{ font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 13px; }}
+:not(pre) > code {{ background: rgba(0, 0, 0, 0.06); padding: 1px 4px; border-radius: 4px; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #E8E6DC; padding: 4px 8px; }}
+details.tool {{ margin: 8px 0; font-size: 13px; color: #5E5D59; }}
+details.tool summary {{ cursor: pointer; font-family: ui-monospace, SFMono-Regular, Menlo, monospace; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; }}
+footer {{ color: #87867F; font-size: 12px; margin-top: 40px; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="subtitle">{subtitle}</p>
+{body}
+<footer>Exported from Lovcode</footer>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        subtitle = escape_html(subtitle),
+        body = body,
+    )
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Write sessions as markdown notes into `dest`, e.g. an Obsidian vault or Logseq graph.
+///
+/// One note per session goes under Sessions/ and one per project under Projects/, linked
+/// with [[wiki-links]]. Exporting again updates the notes; files the export didn't create
+/// are never overwritten.
+#[tauri::command]
+pub async fn export_sessions_to_vault(
+    dest: String,
+    filter: Option<VaultExportFilter>,
+) -> AppResult<VaultExportReport> {
+    let dest = PathBuf::from(security::validate_decoded_path(&dest)?);
+    let filter = filter.unwrap_or_default();
+    let mut sessions = list_all_sessions(None, None, None).await?.items;
+    if filter.include_imported {
+        sessions.extend(list_imported_sessions(None).await?);
+    }
+    sessions.retain(|s| filter.matches(s));
+
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || write_vault(&dest, sessions, &filter))
+            .await
+            .map_err(|e| e.to_string())?,
+    )
+}
+
+/// Render one session as a self-contained HTML page for sharing: chat bubbles, tool
+/// calls folded, code highlighted, no external assets.
+///
+/// Written to `path`, or to ~/.lovstudio/lovcode/exports/ when omitted; returns the path.
+/// Secrets are redacted unless `redact` is false.
+#[tauri::command]
+pub async fn export_session_html(
+    project_id: String,
+    session_id: String,
+    path: Option<String>,
+    redact: Option<bool>,
+) -> AppResult<String> {
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(security::validate_decoded_path(&path)?),
+        None => security::get_lovstudio_dir_or_fallback()
+            .join("exports")
+            .join(format!("session-{}.html", note_name(&session_id))),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut messages = read_session_messages(&project_id, &session_id)?;
+        if redact.unwrap_or(true) {
+            for message in &mut messages {
+                message.content = crate::services::redact::redact(&message.content);
+            }
+        }
+
+        let session_file = security::get_claude_dir_or_fallback()
+            .join("projects")
+            .join(&project_id)
+            .join(format!("{}.jsonl", session_id));
+        let first_prompt = messages
+            .iter()
+            .find(|m| m.role == "user" && visible(m, false))
+            .and_then(|m| m.content.lines().next())
+            .map(|line| line.chars().take(80).collect::<String>());
+        let title = read_session_head(&session_file, 20)
+            .0
+            .or(first_prompt)
+            .unwrap_or_else(|| session_id.clone());
+        let started = messages
+            .iter()
+            .find_map(|m| local_time(&m.timestamp))
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string());
+        let subtitle = [Some(decode_project_path(&project_id)), started]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("Failed to create directory", &e))?;
+        }
+        security::atomic_write_string(&path, &render_share_page(&title, &subtitle, &messages))?;
+        Ok(path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str, is_tool: bool) -> Message {
+        Message {
+            uuid: String::new(),
+            role: role.to_string(),
+            content: content.to_string(),
+            timestamp: String::new(),
+            is_meta: false,
+            is_tool,
+            line_number: 0,
+            thinking: None,
+        }
+    }
+
+    #[test]
+    fn test_render_session_note() {
+        let note = SessionNote {
+            session: Session {
+                id: "abc12345-6789".to_string(),
+                project_id: "-Users-me-my-app".to_string(),
+                project_path: Some("/Users/me/my app".to_string()),
+                summary: Some("Fix \"login\" bug".to_string()),
+                message_count: 0,
+                last_modified: 0,
+            },
+            started: DateTime::parse_from_rfc3339("2025-03-04T10:00:00+00:00")
+                .expect("timestamp")
+                .with_timezone(&Local),
+            name: "2025-03-04 Fix login bug (abc12345)".to_string(),
+            title: "Fix login bug".to_string(),
+        };
+        let messages = [
+            message("user", "It fails", false),
+            message("assistant", "Bash: ls", true),
+            message("assistant", "Fixed.", false),
+        ];
+        let md = render_session_note(&note, "my app", None, Some("Next one"), &messages, false);
+        assert!(md.starts_with(GENERATOR_LINE));
+        assert!(md.contains("project: \"[[my app]]\"\n"));
+        assert!(md.contains("tags: [lovcode, claude-code, project/my-app]\n"));
+        assert!(md.contains("Project: [[my app]] · Next: [[Next one]]\n"));
+        assert!(md.contains("## User\n\nIt fails\n"));
+        assert!(!md.contains("Bash: ls"));
+
+        let with_tools = render_session_note(&note, "my app", None, None, &messages, true);
+        assert!(with_tools.contains("> [!example]- Tool\n> Bash: ls\n"));
+
+        let project = render_project_note("my app", Some("/Users/me/my app"), &[&note]);
+        assert!(project.contains("- [[2025-03-04 Fix login bug (abc12345)|Fix login bug]] · "));
+    }
+
+    #[test]
+    fn test_note_names_and_overwrite_guard() {
+        assert_eq!(note_name("fix: a/b [wip] #12?"), "fix a b wip 12");
+        assert_eq!(tag_slug("My App_v2"), "my-app-v2");
+        assert_eq!(yaml_str("a \"b\"\n"), r#""a \"b\"\n""#);
+
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let mine = temp.path().join("mine.md");
+        let theirs = temp.path().join("theirs.md");
+        fs::write(&theirs, "# My own note\n").expect("write note");
+        assert!(write_note(&mine, &format!("{}---\n", GENERATOR_LINE)).is_ok());
+        assert!(write_note(&mine, &format!("{}---\nagain", GENERATOR_LINE)).is_ok());
+        assert!(write_note(&theirs, GENERATOR_LINE).is_err());
+        assert_eq!(
+            fs::read_to_string(&theirs).expect("read note"),
+            "# My own note\n"
+        );
+    }
+
+    #[test]
+    fn test_share_page_escapes_and_highlights() {
+        let messages = [
+            message("user", "Why does <script>alert(1)</script> run?", false),
+            message(
+                "assistant",
+                "Use this:\n\n```rust\nfn main() {}\n```",
+                false,
+            ),
+            message("assistant", "Read: src/main.rs\n<contents>", true),
+        ];
+        let html = render_share_page("A <b> title", "/tmp/app", &messages);
+        assert!(html.contains("<title>A &lt;b&gt; title</title>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("<div class=\"msg user\">"));
+        // syntect styles tokens inline; the keyword starts its own span
+        assert!(html.contains("<pre style=\"background-color:"));
+        assert!(html.contains("\">fn "));
+        assert!(html.contains("<details class=\"tool\"><summary>Read: src/main.rs</summary>"));
+        assert!(!html.contains("<contents>"));
+    }
+}
@@ -0,0 +1,237 @@
+/**
+ * [INPUT]: 依赖 serde_json, chrono (时间戳解析), crate::jsonl (流式读取), crate::security
+ * [OUTPUT]: 对外提供 get_session_metrics 命令, MetricsCollector (逐行累积) 供 report 缓存汇总中位数, compute_session_metrics, SessionMetrics 类型
+ * [POS]: commands/ 模块的会话时长与回合分析，从会话 JSONL 时间戳计算总时长、用户/助手回合数、响应延迟与最长间隔
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::jsonl;
+use crate::security;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMetrics {
+    /// First and last timestamp in the session
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// Wall-clock time from first to last line
+    pub duration_ms: u64,
+    /// Prompts the user typed (tool results and meta lines aren't turns)
+    pub user_turns: usize,
+    /// Model replies; a reply split over several lines counts once
+    pub assistant_turns: usize,
+    /// Time from a prompt to the first reply line after it
+    pub avg_response_ms: Option<u64>,
+    pub median_response_ms: Option<u64>,
+    /// Longest pause between consecutive lines, and when it started
+    pub longest_gap_ms: u64,
+    pub longest_gap_at: Option<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn timestamp_ms(line: &Value) -> Option<(i64, &str)> {
+    let text = line.get("timestamp")?.as_str()?;
+    let parsed = chrono::DateTime::parse_from_rfc3339(text).ok()?;
+    Some((parsed.timestamp_millis(), text))
+}
+
+/// Whether a user line is a prompt rather than a tool result or an interruption notice
+fn is_prompt(message: &Value) -> bool {
+    let text = match message.get("content") {
+        Some(Value::String(text)) => text.as_str(),
+        Some(Value::Array(blocks)) => {
+            if blocks
+                .iter()
+                .any(|b| b.get("type").and_then(Value::as_str) == Some("tool_result"))
+            {
+                return false;
+            }
+            blocks
+                .iter()
+                .find_map(|b| b.get("text").and_then(Value::as_str))
+                .unwrap_or_default()
+        }
+        _ => "",
+    };
+    let text = text.trim_start();
+    !text.is_empty()
+        && !text.starts_with("[Request interrupted")
+        && !text.starts_with("<local-command-stdout>")
+}
+
+/// Middle value (mean of the two middle values for even counts)
+pub fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        values[mid - 1] + (values[mid] - values[mid - 1]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+/// Builds SessionMetrics one parsed JSONL line at a time, so callers can stream
+/// a session file and share the pass with other aggregation.
+/// Subagent (sidechain) lines only count toward the duration and gaps; turns and
+/// latency are the main conversation's.
+#[derive(Default)]
+pub struct MetricsCollector {
+    metrics: SessionMetrics,
+    first: Option<i64>,
+    previous: Option<(i64, String)>,
+    reply_ids: HashSet<String>,
+    anonymous_replies: usize,
+    waiting_since: Option<i64>,
+    latencies: Vec<u64>,
+}
+
+impl MetricsCollector {
+    pub fn add_line(&mut self, parsed: &Value) {
+        let Some((ts, text)) = timestamp_ms(parsed) else {
+            return;
+        };
+        let metrics = &mut self.metrics;
+        let first = *self.first.get_or_insert(ts);
+        if let Some((prev, prev_text)) = &self.previous {
+            let gap = u64::try_from(ts - prev).unwrap_or(0);
+            if gap > metrics.longest_gap_ms {
+                metrics.longest_gap_ms = gap;
+                metrics.longest_gap_at = Some(prev_text.clone());
+            }
+        }
+        if metrics.started_at.is_none() {
+            metrics.started_at = Some(text.to_string());
+        }
+        metrics.ended_at = Some(text.to_string());
+        metrics.duration_ms = u64::try_from(ts - first).unwrap_or(0);
+        self.previous = Some((ts, text.to_string()));
+
+        let sidechain = parsed.get("isSidechain").and_then(Value::as_bool) == Some(true);
+        let meta = parsed.get("isMeta").and_then(Value::as_bool) == Some(true)
+            || parsed.get("isCompactSummary").and_then(Value::as_bool) == Some(true);
+        let Some(message) = parsed.get("message").filter(|_| !sidechain && !meta) else {
+            return;
+        };
+        match parsed.get("type").and_then(Value::as_str) {
+            Some("user") if is_prompt(message) => {
+                metrics.user_turns += 1;
+                self.waiting_since = Some(ts);
+            }
+            Some("assistant") => {
+                if message.get("model").and_then(Value::as_str) == Some("<synthetic>") {
+                    return;
+                }
+                match message.get("id").and_then(Value::as_str) {
+                    Some(id) => {
+                        self.reply_ids.insert(id.to_string());
+                    }
+                    None => self.anonymous_replies += 1,
+                }
+                if let Some(since) = self.waiting_since.take() {
+                    self.latencies.push(u64::try_from(ts - since).unwrap_or(0));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn finish(mut self) -> SessionMetrics {
+        let mut metrics = self.metrics;
+        metrics.assistant_turns = self.reply_ids.len() + self.anonymous_replies;
+        if !self.latencies.is_empty() {
+            metrics.avg_response_ms =
+                Some(self.latencies.iter().sum::<u64>() / self.latencies.len() as u64);
+            metrics.median_response_ms = median(&mut self.latencies);
+        }
+        metrics
+    }
+}
+
+/// Metrics of one session from its parsed JSONL lines
+pub fn compute_session_metrics(lines: impl Iterator<Item = Value>) -> SessionMetrics {
+    let mut collector = MetricsCollector::default();
+    for line in lines {
+        collector.add_line(&line);
+    }
+    collector.finish()
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Duration, turn counts, response latency and longest gap of one session
+#[tauri::command]
+pub async fn get_session_metrics(
+    project_id: String,
+    session_id: String,
+) -> AppResult<SessionMetrics> {
+    let path = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(&project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !path.exists() {
+        return Err(AppError::not_found("Session not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
+    }
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        jsonl::open(&path)
+            .map(|lines| compute_session_metrics(lines.records().map(|(_, line)| line)))
+            .map_err(|e| format!("Failed to read session: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_session_metrics() {
+        let content = [
+            r#"{"type":"summary","summary":"Demo"}"#,
+            r#"{"type":"user","timestamp":"2025-03-04T10:00:00Z","isMeta":true,"message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","timestamp":"2025-03-04T10:00:01Z","message":{"role":"user","content":"Fix the bug"}}"#,
+            r#"{"type":"assistant","timestamp":"2025-03-04T10:00:04Z","message":{"id":"m1","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Looking"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-03-04T10:00:05Z","message":{"id":"m1","model":"claude-sonnet-4-5","content":[{"type":"tool_use","id":"t1","name":"Read","input":{}}]}}"#,
+            r#"{"type":"user","timestamp":"2025-03-04T10:00:06Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-03-04T10:00:07Z","message":{"id":"m2","model":"claude-sonnet-4-5","content":[{"type":"text","text":"Fixed"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-03-04T10:00:08Z","isSidechain":true,"message":{"id":"s1","model":"claude-haiku-4-5","content":[]}}"#,
+            r#"{"type":"user","timestamp":"2025-03-04T10:20:08Z","message":{"role":"user","content":[{"type":"text","text":"Thanks, now add a test"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-03-04T10:20:18Z","message":{"id":"m3","model":"claude-sonnet-4-5","content":[]}}"#,
+        ]
+        .join("\n");
+        let lines = jsonl::from_reader(content.as_bytes()).records();
+        let metrics = compute_session_metrics(lines.map(|(_, line)| line));
+
+        assert_eq!(metrics.started_at.as_deref(), Some("2025-03-04T10:00:00Z"));
+        assert_eq!(metrics.duration_ms, 1_218_000);
+        assert_eq!(metrics.user_turns, 2);
+        assert_eq!(metrics.assistant_turns, 3);
+        assert_eq!(metrics.avg_response_ms, Some(6_500));
+        assert_eq!(metrics.median_response_ms, Some(6_500));
+        assert_eq!(metrics.longest_gap_ms, 1_200_000);
+        assert_eq!(
+            metrics.longest_gap_at.as_deref(),
+            Some("2025-03-04T10:00:08Z")
+        );
+
+        assert_eq!(median(&mut [5, 1, 3]), Some(3));
+        assert_eq!(median(&mut [4, 1, 3, 2]), Some(2));
+        assert_eq!(median(&mut []), None);
+    }
+}
@@ -0,0 +1,415 @@
+/**
+ * [INPUT]: 依赖 ~/.claude/projects/<project_id>/<session_id>.jsonl, crate::commands::projects (read_session_head, decode_project_path), crate::services::search (reindex_session), crate::security
+ * [OUTPUT]: 对外提供 trash_session, list_trashed_sessions, restore_session 命令与 TrashedSession 类型
+ * [POS]: commands/ 模块的会话回收站，删除的会话移入 ~/.lovstudio/lovcode/trash/<project_id>/ 并保留原元数据，可原样放回
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::projects::{decode_project_path, read_session_head};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use crate::services::search;
+use crate::types::Session;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A session in the trash, stored as trash/<project_id>/<session_id>.jsonl with this
+/// record next to it as <session_id>.json
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashedSession {
+    /// "<project_id>/<session_id>", passed to restore_session
+    pub trash_id: String,
+    /// Metadata as it was when the session was deleted
+    #[serde(flatten)]
+    pub session: Session,
+    /// Unix seconds
+    pub deleted_at: u64,
+    pub original_path: String,
+}
+
+/// Lines read for the summary and message count, as list_sessions does
+const SESSION_HEAD_LINES: usize = 20;
+
+// ============================================================================
+// Paths
+// ============================================================================
+
+fn trash_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("trash")
+}
+
+fn projects_dir() -> PathBuf {
+    security::get_claude_dir_or_fallback().join("projects")
+}
+
+/// Project and session ids are single path segments
+fn validate_segment(kind: &str, value: &str) -> AppResult<()> {
+    if value.is_empty() || value.contains(['/', '\\']) || value.contains("..") {
+        return Err(AppError::invalid_input(format!(
+            "Invalid {}: {}",
+            kind, value
+        )));
+    }
+    Ok(())
+}
+
+fn parse_trash_id(trash_id: &str) -> AppResult<(&str, &str)> {
+    let (project_id, session_id) = trash_id
+        .split_once('/')
+        .ok_or_else(|| AppError::invalid_input(format!("Invalid trash id: {}", trash_id)))?;
+    validate_segment("project id", project_id)?;
+    validate_segment("session id", session_id)?;
+    Ok((project_id, session_id))
+}
+
+fn record_path(trash: &Path, project_id: &str, session_id: &str) -> PathBuf {
+    trash.join(project_id).join(format!("{}.json", session_id))
+}
+
+fn trashed_file(trash: &Path, project_id: &str, session_id: &str) -> PathBuf {
+    trash.join(project_id).join(format!("{}.jsonl", session_id))
+}
+
+/// Rename, falling back to copy + remove when the trash is on another filesystem
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+/// Rename a directory, falling back to copy + remove like move_file
+fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_dir(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(e);
+    }
+    fs::remove_dir_all(from)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Trash operations
+// ============================================================================
+
+/// Move a session file, and the <session_id>/ directory Claude Code keeps next to it for
+/// subagents and tool results, into the trash
+fn trash_session_in(
+    projects: &Path,
+    trash: &Path,
+    project_id: &str,
+    session_id: &str,
+) -> AppResult<TrashedSession> {
+    validate_segment("project id", project_id)?;
+    validate_segment("session id", session_id)?;
+
+    let source = projects
+        .join(project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !source.is_file() {
+        return Err(AppError::not_found("Session not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
+    }
+    let record = record_path(trash, project_id, session_id);
+    // A record without its file is left over from an interrupted trash and is replaced
+    if record.exists() && trashed_file(trash, project_id, session_id).exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "A session with this id is already in the trash",
+        )
+        .with_context(format!("{}/{}", project_id, session_id)));
+    }
+
+    let (summary, message_count) = read_session_head(&source, SESSION_HEAD_LINES);
+    let last_modified = fs::metadata(&source)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let trashed = TrashedSession {
+        trash_id: format!("{}/{}", project_id, session_id),
+        session: Session {
+            id: session_id.to_string(),
+            project_id: project_id.to_string(),
+            project_path: Some(decode_project_path(project_id)),
+            summary,
+            message_count,
+            last_modified,
+        },
+        deleted_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        original_path: source.to_string_lossy().to_string(),
+    };
+
+    let target_dir = trash.join(project_id);
+    fs::create_dir_all(&target_dir).map_err(|e| AppError::io("Failed to create trash", &e))?;
+    // The record goes first, so nothing is ever in the trash without one
+    let json = serde_json::to_string_pretty(&trashed).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&record, &json)?;
+
+    let side_dir = projects.join(project_id).join(session_id);
+    let trashed_side_dir = target_dir.join(session_id);
+    let moved_side_dir = side_dir.is_dir();
+    if moved_side_dir {
+        if let Err(e) = move_dir(&side_dir, &trashed_side_dir) {
+            let _ = fs::remove_file(&record);
+            return Err(AppError::io("Failed to move session directory to trash", &e));
+        }
+    }
+    if let Err(e) = move_file(&source, &trashed_file(trash, project_id, session_id)) {
+        // Leave the session whole where it was
+        if moved_side_dir {
+            let _ = move_dir(&trashed_side_dir, &side_dir);
+        }
+        let _ = fs::remove_file(&record);
+        return Err(AppError::io("Failed to move session to trash", &e));
+    }
+    Ok(trashed)
+}
+
+fn list_trashed_in(trash: &Path) -> Vec<TrashedSession> {
+    let mut sessions: Vec<TrashedSession> = fs::read_dir(trash)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .flat_map(|entry| fs::read_dir(entry.path()).into_iter().flatten().flatten())
+        .filter(|entry| entry.path().extension().is_some_and(|e| e == "json"))
+        // Skip records left over from an interrupted trash
+        .filter(|entry| entry.path().with_extension("jsonl").is_file())
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str(&content).ok()
+        })
+        .collect();
+    sessions.sort_by(|a, b| {
+        b.deleted_at
+            .cmp(&a.deleted_at)
+            .then_with(|| a.trash_id.cmp(&b.trash_id))
+    });
+    sessions
+}
+
+/// Put a trashed session back where it was deleted from
+fn restore_session_in(projects: &Path, trash: &Path, trash_id: &str) -> AppResult<TrashedSession> {
+    let (project_id, session_id) = parse_trash_id(trash_id)?;
+    let record = record_path(trash, project_id, session_id);
+    let content = fs::read_to_string(&record).map_err(|_| {
+        AppError::not_found("Session not found in trash").with_context(trash_id.to_string())
+    })?;
+    let trashed: TrashedSession = serde_json::from_str(&content)
+        .map_err(|e| {
+            AppError::new(
+                ErrorCode::Parse,
+                format!("Failed to parse trash record {}: {}", trash_id, e),
+            )
+        })?;
+
+    let project_dir = projects.join(project_id);
+    let target = project_dir.join(format!("{}.jsonl", session_id));
+    if target.exists() {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "A session with this id already exists in the project",
+        )
+        .with_context(trash_id.to_string()));
+    }
+
+    let source_dir = trash.join(project_id);
+    fs::create_dir_all(&project_dir)
+        .map_err(|e| AppError::io("Failed to create project directory", &e))?;
+    move_file(&trashed_file(trash, project_id, session_id), &target)
+        .map_err(|e| AppError::io("Failed to restore session", &e))?;
+    let side_dir = source_dir.join(session_id);
+    if side_dir.is_dir() && !project_dir.join(session_id).exists() {
+        move_dir(&side_dir, &project_dir.join(session_id))
+            .map_err(|e| AppError::io("Failed to restore session directory", &e))?;
+    }
+    fs::remove_file(&record).map_err(|e| AppError::io("Failed to remove trash record", &e))?;
+    // Only succeeds once the project's trash folder is empty
+    let _ = fs::remove_dir(&source_dir);
+    Ok(trashed)
+}
+
+/// Keep search in step with the file; lists rescan on their own
+fn sync_search_index(project_id: &str, session_id: &str, path: Option<&Path>) {
+    if let Err(e) = search::reindex_session(project_id, session_id, path) {
+        tracing::warn!(
+            "Failed to update search index for session {}: {}",
+            session_id,
+            e
+        );
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Move a session to ~/.lovstudio/lovcode/trash/<project_id>/
+#[tauri::command]
+pub async fn trash_session(project_id: String, session_id: String) -> AppResult<TrashedSession> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let trashed = trash_session_in(&projects_dir(), &trash_dir(), &project_id, &session_id)?;
+        sync_search_index(&project_id, &session_id, None);
+        Ok(trashed)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Sessions in the trash, most recently deleted first
+#[tauri::command]
+pub async fn list_trashed_sessions() -> AppResult<Vec<TrashedSession>> {
+    Ok(
+        tauri::async_runtime::spawn_blocking(|| list_trashed_in(&trash_dir()))
+            .await
+            .map_err(|e| e.to_string())?,
+    )
+}
+
+/// Move a trashed session back to its project
+#[tauri::command]
+pub async fn restore_session(id: String) -> AppResult<TrashedSession> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let projects = projects_dir();
+        let restored = restore_session_in(&projects, &trash_dir(), &id)?;
+        let path = projects
+            .join(&restored.session.project_id)
+            .join(format!("{}.jsonl", restored.session.id));
+        sync_search_index(
+            &restored.session.project_id,
+            &restored.session.id,
+            Some(&path),
+        );
+        Ok(restored)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trash_and_restore_session() {
+        let temp = TempDir::new().expect("create temp dir");
+        let projects = temp.path().join("projects");
+        let trash = temp.path().join("trash");
+        let project_dir = projects.join("-Users-me-app");
+        fs::create_dir_all(project_dir.join("abc").join("subagents")).expect("create dirs");
+        let line = r#"{"type":"user","message":{"role":"user","content":"fix the build"}}"#;
+        fs::write(project_dir.join("abc.jsonl"), format!("{}\n", line)).expect("write session");
+
+        let trashed = trash_session_in(&projects, &trash, "-Users-me-app", "abc").expect("trash");
+        assert_eq!(trashed.trash_id, "-Users-me-app/abc");
+        assert_eq!(trashed.session.message_count, 1);
+        assert!(!project_dir.join("abc.jsonl").exists());
+        assert!(!project_dir.join("abc").exists());
+        assert!(trash
+            .join("-Users-me-app")
+            .join("abc")
+            .join("subagents")
+            .is_dir());
+
+        let listed = list_trashed_in(&trash);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].session.summary.as_deref(), Some("fix the build"));
+
+        let restored = restore_session_in(&projects, &trash, &listed[0].trash_id).expect("restore");
+        assert_eq!(restored.session.id, "abc");
+        assert_eq!(
+            fs::read_to_string(project_dir.join("abc.jsonl")).expect("read restored"),
+            format!("{}\n", line)
+        );
+        assert!(project_dir.join("abc").join("subagents").is_dir());
+        assert!(list_trashed_in(&trash).is_empty());
+        assert!(!trash.join("-Users-me-app").exists());
+    }
+
+    #[test]
+    fn test_trash_rejects_bad_ids_and_conflicts() {
+        let temp = TempDir::new().expect("create temp dir");
+        let projects = temp.path().join("projects");
+        let trash = temp.path().join("trash");
+        fs::create_dir_all(projects.join("p")).expect("create project");
+        fs::write(projects.join("p").join("s.jsonl"), "").expect("write session");
+
+        let err = trash_session_in(&projects, &trash, "..", "s").expect_err("bad project id");
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
+        let err = trash_session_in(&projects, &trash, "p", "missing").expect_err("missing");
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        trash_session_in(&projects, &trash, "p", "s").expect("trash");
+        fs::write(projects.join("p").join("s.jsonl"), "").expect("recreate session");
+        let err = restore_session_in(&projects, &trash, "p/s").expect_err("conflict");
+        assert_eq!(err.code(), ErrorCode::Conflict);
+        assert!(parse_trash_id("p/../s").is_err());
+    }
+
+    #[test]
+    fn test_trash_replaces_record_left_by_interrupted_trash() {
+        let temp = TempDir::new().expect("create temp dir");
+        let projects = temp.path().join("projects");
+        let trash = temp.path().join("trash");
+        fs::create_dir_all(projects.join("p").join("s").join("subagents")).expect("create dirs");
+        fs::write(projects.join("p").join("s.jsonl"), "").expect("write session");
+
+        // Stopped after the record and the directory move, before the session file moved
+        trash_session_in(&projects, &trash, "p", "s").expect("trash");
+        fs::rename(trashed_file(&trash, "p", "s"), projects.join("p").join("s.jsonl"))
+            .expect("undo file move");
+        assert!(list_trashed_in(&trash).is_empty());
+
+        let trashed = trash_session_in(&projects, &trash, "p", "s").expect("trash again");
+        assert_eq!(list_trashed_in(&trash).len(), 1);
+        restore_session_in(&projects, &trash, &trashed.trash_id).expect("restore");
+        assert!(projects.join("p").join("s").join("subagents").is_dir());
+        assert!(projects.join("p").join("s.jsonl").is_file());
+    }
+
+    #[test]
+    fn test_copy_dir_copies_nested_files() {
+        let temp = TempDir::new().expect("create temp dir");
+        let from = temp.path().join("from");
+        fs::create_dir_all(from.join("subagents")).expect("create dirs");
+        fs::write(from.join("subagents").join("agent.jsonl"), "{}\n").expect("write file");
+
+        let to = temp.path().join("to");
+        copy_dir(&from, &to).expect("copy");
+        assert_eq!(
+            fs::read_to_string(to.join("subagents").join("agent.jsonl")).expect("read copy"),
+            "{}\n"
+        );
+    }
+}
@@ -1,62 +1,90 @@
 /**
- * [INPUT]: 依赖 types, services, security 模块
- * [OUTPUT]: 对外提供会话消息相关的 Tauri 命令
+ * [INPUT]: 依赖 types, services, security, jsonl (流式读取) 模块, commands::importers ("import:" 项目的会话)
+ * [OUTPUT]: 对外提供会话消息相关的 Tauri 命令 (可选附带 thinking 块) + read_session_messages 供导出/扫描复用
  * [POS]: commands/ 模块成员，处理会话消息的读取和解析
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
+use crate::commands::importers::{read_imported_messages, IMPORTED_PROJECT_PREFIX};
+use crate::errors::{AppError, AppResult};
+use crate::jsonl;
 use crate::security;
-use crate::services::extract_content_with_meta;
+use crate::services::{extract_content_with_meta, extract_thinking};
 use crate::types::{Message, RawLine};
-use std::fs;
 
 // ============================================================================
 // Session Messages Command
 // ============================================================================
 
-#[tauri::command]
-pub async fn get_session_messages(
-    project_id: String,
-    session_id: String,
-) -> Result<Vec<Message>, String> {
-    tauri::async_runtime::spawn_blocking(move || {
-        let session_path = security::get_claude_dir_or_fallback()
-            .join("projects")
-            .join(&project_id)
-            .join(format!("{}.jsonl", session_id));
+/// Visible user/assistant messages of a session, in file order
+pub fn read_session_messages(project_id: &str, session_id: &str) -> AppResult<Vec<Message>> {
+    read_messages(project_id, session_id, false)
+}
 
-        if !session_path.exists() {
-            return Err("Session not found".to_string());
-        }
+/// With `include_thinking`, thinking blocks fill `Message::thinking` and assistant
+/// entries holding only thinking are kept (with empty `content`)
+fn read_messages(
+    project_id: &str,
+    session_id: &str,
+    include_thinking: bool,
+) -> AppResult<Vec<Message>> {
+    if project_id.starts_with(IMPORTED_PROJECT_PREFIX) {
+        return read_imported_messages(project_id, session_id);
+    }
+    let session_path = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(project_id)
+        .join(format!("{}.jsonl", session_id));
+
+    if !session_path.exists() {
+        return Err(AppError::not_found("Session not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
+    }
+
+    let lines = jsonl::open(&session_path)
+        .map_err(|e| AppError::io("Failed to read session", &e))?;
+    let mut messages = Vec::new();
 
-        let content = fs::read_to_string(&session_path).map_err(|e| e.to_string())?;
-        let mut messages = Vec::new();
-
-        for (idx, line) in content.lines().enumerate() {
-            if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                let line_type = parsed.line_type.as_deref();
-                if line_type == Some("user") || line_type == Some("assistant") {
-                    if let Some(msg) = &parsed.message {
-                        let role = msg.role.clone().unwrap_or_default();
-                        let (content, is_tool) = extract_content_with_meta(&msg.content);
-                        let is_meta = parsed.is_meta.unwrap_or(false);
-
-                        if !content.is_empty() {
-                            messages.push(Message {
-                                uuid: parsed.uuid.unwrap_or_default(),
-                                role,
-                                content,
-                                timestamp: parsed.timestamp.unwrap_or_default(),
-                                is_meta,
-                                is_tool,
-                                line_number: idx + 1,
-                            });
-                        }
-                    }
+    for (line_number, parsed) in lines.records::<RawLine>() {
+        let line_type = parsed.line_type.as_deref();
+        if line_type == Some("user") || line_type == Some("assistant") {
+            if let Some(msg) = &parsed.message {
+                let role = msg.role.clone().unwrap_or_default();
+                let (content, is_tool) = extract_content_with_meta(&msg.content);
+                let is_meta = parsed.is_meta.unwrap_or(false);
+                let thinking = if include_thinking {
+                    extract_thinking(msg.content.as_ref())
+                } else {
+                    None
+                };
+
+                if !content.is_empty() || thinking.is_some() {
+                    messages.push(Message {
+                        uuid: parsed.uuid.unwrap_or_default(),
+                        role,
+                        content,
+                        timestamp: parsed.timestamp.unwrap_or_default(),
+                        is_meta,
+                        is_tool,
+                        line_number,
+                        thinking,
+                    });
                 }
             }
         }
+    }
+
+    Ok(messages)
+}
 
-        Ok(messages)
+/// `include_thinking` adds the model's extended-thinking text to assistant messages
+#[tauri::command]
+pub async fn get_session_messages(
+    project_id: String,
+    session_id: String,
+    include_thinking: Option<bool>,
+) -> AppResult<Vec<Message>> {
+    tauri::async_runtime::spawn_blocking(move || {
+        read_messages(&project_id, &session_id, include_thinking.unwrap_or(false))
     })
     .await
     .map_err(|e| e.to_string())?
@@ -1,12 +1,17 @@
 /**
- * [INPUT]: 依赖 std::fs, std::time::Duration, serde, serde_json, reqwest, arboard, crate::security, crate::types
+ * [INPUT]: 依赖 std::fs, std::time::Duration, serde, serde_json, reqwest, arboard, crate::security, crate::store (disabled_env.json), crate::commands::copy_history (记录复制历史), services::claude_json_history (标注 MCP env 修改), crate::types
  * [OUTPUT]: 对外提供 get_settings, update_mcp_env, update_settings_env, delete_settings_env, disable_settings_env, enable_settings_env, test_anthropic_connection, test_openai_connection, test_claude_cli 等命令
  * [POS]: commands/ 模块的设置管理命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use crate::commands::claude_binary::claude_program;
+use crate::commands::copy_history::{self, CopySource};
 use crate::commands::read_session_head;
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security;
+use crate::services::claude_json_history;
+use crate::store::{self, Schema};
 use crate::types::{ClaudeSettings, McpServer};
 use serde::Serialize;
 use serde_json::Value;
@@ -23,25 +28,22 @@ fn get_disabled_env_path() -> PathBuf {
     security::get_lovstudio_dir_or_fallback().join("disabled_env.json")
 }
 
+/// Schema of disabled_env.json
+const DISABLED_ENV_SCHEMA: Schema = Schema::new(1);
+
 fn load_disabled_env() -> Result<serde_json::Map<String, Value>, String> {
-    let path = get_disabled_env_path();
-    if !path.exists() {
-        return Ok(serde_json::Map::new());
-    }
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let value: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-    Ok(value.as_object().cloned().unwrap_or_default())
+    let value = store::load(&get_disabled_env_path(), &DISABLED_ENV_SCHEMA)?;
+    Ok(value
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default())
 }
 
 fn save_disabled_env(disabled: &serde_json::Map<String, Value>) -> Result<(), String> {
-    let path = get_disabled_env_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let output = serde_json::to_string_pretty(&Value::Object(disabled.clone()))
-        .map_err(|e| e.to_string())?;
-    fs::write(&path, output).map_err(|e| e.to_string())?;
-    Ok(())
+    store::save(&get_disabled_env_path(), &DISABLED_ENV_SCHEMA, disabled)
+}
+
+fn settings_parse_error(e: serde_json::Error) -> AppError {
+    AppError::new(ErrorCode::Parse, format!("Failed to parse settings.json: {}", e))
 }
 
 fn get_session_path(project_id: &str, session_id: &str) -> PathBuf {
@@ -56,14 +58,15 @@ fn get_session_path(project_id: &str, session_id: &str) -> PathBuf {
 // ============================================================================
 
 #[tauri::command]
-pub fn get_settings() -> Result<ClaudeSettings, String> {
+pub fn get_settings() -> AppResult<ClaudeSettings> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     let claude_json_path = security::get_claude_json_path_or_fallback();
 
     // Read ~/.claude/settings.json for permissions, hooks, etc.
     let (mut raw, permissions, hooks) = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
-        let raw: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read settings.json", &e))?;
+        let raw: Value = serde_json::from_str(&content).map_err(settings_parse_error)?;
         let permissions = raw.get("permissions").cloned();
         let hooks = raw.get("hooks").cloned();
         (raw, permissions, hooks)
@@ -174,39 +177,43 @@ pub fn get_settings() -> Result<ClaudeSettings, String> {
 // ============================================================================
 
 #[tauri::command]
-pub fn open_session_in_editor(project_id: String, session_id: String) -> Result<(), String> {
+pub fn open_session_in_editor(project_id: String, session_id: String) -> AppResult<()> {
     let path = get_session_path(&project_id, &session_id);
     if !path.exists() {
-        return Err("Session file not found".to_string());
+        return Err(AppError::not_found("Session file not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
     }
     open_in_editor(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub fn get_session_file_path(project_id: String, session_id: String) -> Result<String, String> {
+pub fn get_session_file_path(project_id: String, session_id: String) -> AppResult<String> {
     let path = get_session_path(&project_id, &session_id);
     if !path.exists() {
-        return Err("Session file not found".to_string());
+        return Err(AppError::not_found("Session file not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
     }
     Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub fn get_session_summary(project_id: String, session_id: String) -> Result<Option<String>, String> {
+pub fn get_session_summary(project_id: String, session_id: String) -> AppResult<Option<String>> {
     let path = get_session_path(&project_id, &session_id);
     if !path.exists() {
-        return Err("Session file not found".to_string());
+        return Err(AppError::not_found("Session file not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
     }
     let (summary, _) = read_session_head(&path, 20);
     Ok(summary)
 }
 
 #[tauri::command]
-pub fn reveal_session_file(project_id: String, session_id: String) -> Result<(), String> {
+pub fn reveal_session_file(project_id: String, session_id: String) -> AppResult<()> {
     let session_path = get_session_path(&project_id, &session_id);
 
     if !session_path.exists() {
-        return Err("Session file not found".to_string());
+        return Err(AppError::not_found("Session file not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
     }
 
     let path = session_path.to_string_lossy().to_string();
@@ -216,21 +223,21 @@ pub fn reveal_session_file(project_id: String, session_id: String) -> Result<(),
         std::process::Command::new("open")
             .args(["-R", &path])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to reveal session file", &e))?;
     }
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
             .args(["/select,", &path])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to reveal session file", &e))?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(session_path.parent().unwrap_or(&session_path))
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to reveal session file", &e))?;
     }
     Ok(())
 }
@@ -239,10 +246,13 @@ pub fn reveal_session_file(project_id: String, session_id: String) -> Result<(),
 // Clipboard Commands
 // ============================================================================
 
+/// `source` tags the entry in the copy history (when enabled)
 #[tauri::command]
-pub fn copy_to_clipboard(text: String) -> Result<(), String> {
+pub fn copy_to_clipboard(text: String, source: Option<CopySource>) -> AppResult<()> {
     let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())
+    clipboard.set_text(text.as_str()).map_err(|e| e.to_string())?;
+    copy_history::record_copy(&text, source);
+    Ok(())
 }
 
 // ============================================================================
@@ -250,16 +260,16 @@ pub fn copy_to_clipboard(text: String) -> Result<(), String> {
 // ============================================================================
 
 #[tauri::command]
-pub fn reveal_path(path: String) -> Result<(), String> {
+pub fn reveal_path(path: String) -> AppResult<()> {
     let expanded = if path.starts_with("~") {
-        let home = dirs::home_dir().ok_or("Cannot get home dir")?;
+        let home = security::safe_home_dir()?;
         home.join(&path[2..])
     } else {
         std::path::PathBuf::from(&path)
     };
 
     if !expanded.exists() {
-        return Err(format!("Path not found: {}", path));
+        return Err(AppError::not_found(format!("Path not found: {}", path)).with_context(path));
     }
 
     let path_str = expanded.to_string_lossy().to_string();
@@ -269,36 +279,36 @@ pub fn reveal_path(path: String) -> Result<(), String> {
         std::process::Command::new("open")
             .args(["-R", &path_str])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to reveal path", &e))?;
     }
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
             .args(["/select,", &path_str])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to reveal path", &e))?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(expanded.parent().unwrap_or(&expanded))
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to reveal path", &e))?;
     }
     Ok(())
 }
 
 #[tauri::command]
-pub fn open_path(path: String) -> Result<(), String> {
+pub fn open_path(path: String) -> AppResult<()> {
     let expanded = if path.starts_with("~") {
-        let home = dirs::home_dir().ok_or("Cannot get home dir")?;
+        let home = security::safe_home_dir()?;
         home.join(&path[2..])
     } else {
         std::path::PathBuf::from(&path)
     };
 
     if !expanded.exists() {
-        return Err(format!("Path not found: {}", path));
+        return Err(AppError::not_found(format!("Path not found: {}", path)).with_context(path));
     }
 
     let path_str = expanded.to_string_lossy().to_string();
@@ -308,53 +318,53 @@ pub fn open_path(path: String) -> Result<(), String> {
         std::process::Command::new("open")
             .arg(&path_str)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open path", &e))?;
     }
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("cmd")
             .args(["/C", "start", "", &path_str])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open path", &e))?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(&path_str)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open path", &e))?;
     }
     Ok(())
 }
 
 #[tauri::command]
-pub fn open_in_editor(path: String) -> Result<(), String> {
+pub fn open_in_editor(path: String) -> AppResult<()> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open editor", &e))?;
     }
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("cmd")
             .args(["/C", "start", "", &path])
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open editor", &e))?;
     }
     #[cfg(target_os = "linux")]
     {
         std::process::Command::new("xdg-open")
             .arg(&path)
             .spawn()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::io("Failed to open editor", &e))?;
     }
     Ok(())
 }
 
 #[tauri::command]
-pub fn open_file_at_line(path: String, line: usize) -> Result<(), String> {
+pub fn open_file_at_line(path: String, line: usize) -> AppResult<()> {
     // 尝试用 cursor，失败则用 code (VSCode)
     let editors = ["cursor", "code", "zed"];
 
@@ -398,13 +408,15 @@ pub fn get_home_dir() -> String {
 // ============================================================================
 
 #[tauri::command]
-pub fn write_file(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, content).map_err(|e| e.to_string())
+pub fn write_file(path: String, content: String) -> AppResult<()> {
+    let path = security::validate_user_path(&path)?;
+    fs::write(&path, content).map_err(|e| AppError::io("Failed to write file", &e))
 }
 
 #[tauri::command]
-pub fn write_binary_file(path: String, data: Vec<u8>) -> Result<(), String> {
-    fs::write(&path, data).map_err(|e| e.to_string())
+pub fn write_binary_file(path: String, data: Vec<u8>) -> AppResult<()> {
+    let path = security::validate_user_path(&path)?;
+    fs::write(&path, data).map_err(|e| AppError::io("Failed to write file", &e))
 }
 
 // ============================================================================
@@ -412,20 +424,23 @@ pub fn write_binary_file(path: String, data: Vec<u8>) -> Result<(), String> {
 // ============================================================================
 
 #[tauri::command]
-pub fn update_mcp_env(server_name: String, env_key: String, env_value: String) -> Result<(), String> {
+pub fn update_mcp_env(server_name: String, env_key: String, env_value: String) -> AppResult<()> {
     let claude_json_path = security::get_claude_json_path_or_fallback();
 
     let mut claude_json: serde_json::Value = if claude_json_path.exists() {
-        let content = fs::read_to_string(&claude_json_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&content).map_err(|e| e.to_string())?
+        let content = fs::read_to_string(&claude_json_path)
+            .map_err(|e| AppError::io("Failed to read ~/.claude.json", &e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            AppError::new(ErrorCode::Parse, format!("Failed to parse ~/.claude.json: {}", e))
+        })?
     } else {
-        return Err("~/.claude.json not found".to_string());
+        return Err(AppError::not_found("~/.claude.json not found"));
     };
 
     let server = claude_json
         .get_mut("mcpServers")
         .and_then(|s| s.get_mut(&server_name))
-        .ok_or_else(|| format!("MCP server '{}' not found", server_name))?;
+        .ok_or_else(|| AppError::not_found(format!("MCP server '{}' not found", server_name)))?;
 
     if !server.get("env").is_some() {
         server["env"] = serde_json::json!({});
@@ -433,7 +448,12 @@ pub fn update_mcp_env(server_name: String, env_key: String, env_value: String) -
     server["env"][&env_key] = serde_json::Value::String(env_value);
 
     let output = serde_json::to_string_pretty(&claude_json).map_err(|e| e.to_string())?;
-    fs::write(&claude_json_path, output).map_err(|e| e.to_string())?;
+    fs::write(&claude_json_path, output)
+        .map_err(|e| AppError::io("Failed to write ~/.claude.json", &e))?;
+    claude_json_history::record_lovcode_change(format!(
+        "Set {} for MCP server {}",
+        env_key, server_name
+    ));
 
     Ok(())
 }
@@ -443,11 +463,12 @@ pub fn update_settings_env(
     env_key: String,
     env_value: String,
     is_new: Option<bool>,
-) -> Result<(), String> {
+) -> AppResult<()> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&content).map_err(|e| e.to_string())?
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read settings.json", &e))?;
+        serde_json::from_str(&content).map_err(settings_parse_error)?
     } else {
         serde_json::json!({})
     };
@@ -477,17 +498,19 @@ pub fn update_settings_env(
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output)
+        .map_err(|e| AppError::io("Failed to write settings.json", &e))?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_settings_env(env_key: String) -> Result<(), String> {
+pub fn delete_settings_env(env_key: String) -> AppResult<()> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&content).map_err(|e| e.to_string())?
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read settings.json", &e))?;
+        serde_json::from_str(&content).map_err(settings_parse_error)?
     } else {
         serde_json::json!({})
     };
@@ -517,7 +540,8 @@ pub fn delete_settings_env(env_key: String) -> Result<(), String> {
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output)
+        .map_err(|e| AppError::io("Failed to write settings.json", &e))?;
 
     let mut disabled_env = load_disabled_env()?;
     disabled_env.remove(&env_key);
@@ -527,14 +551,15 @@ pub fn delete_settings_env(env_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn disable_settings_env(env_key: String) -> Result<(), String> {
+pub fn disable_settings_env(env_key: String) -> AppResult<()> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     if !settings_path.exists() {
         return Ok(());
     }
-    let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(&settings_path)
+        .map_err(|e| AppError::io("Failed to read settings.json", &e))?;
     let mut settings: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        serde_json::from_str(&content).map_err(settings_parse_error)?;
 
     // Get current value before removing
     let current_value = settings
@@ -554,7 +579,8 @@ pub fn disable_settings_env(env_key: String) -> Result<(), String> {
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output)
+        .map_err(|e| AppError::io("Failed to write settings.json", &e))?;
 
     let mut disabled_env = load_disabled_env()?;
     disabled_env.insert(env_key, serde_json::Value::String(current_value));
@@ -564,11 +590,12 @@ pub fn disable_settings_env(env_key: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn enable_settings_env(env_key: String) -> Result<(), String> {
+pub fn enable_settings_env(env_key: String) -> AppResult<()> {
     let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
-        serde_json::from_str(&content).map_err(|e| e.to_string())?
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read settings.json", &e))?;
+        serde_json::from_str(&content).map_err(settings_parse_error)?
     } else {
         serde_json::json!({})
     };
@@ -594,13 +621,14 @@ pub fn enable_settings_env(env_key: String) -> Result<(), String> {
     }
 
     let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, output).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, output)
+        .map_err(|e| AppError::io("Failed to write settings.json", &e))?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn update_disabled_settings_env(env_key: String, env_value: String) -> Result<(), String> {
+pub fn update_disabled_settings_env(env_key: String, env_value: String) -> AppResult<()> {
     let mut disabled_env = load_disabled_env()?;
     disabled_env.insert(env_key, serde_json::Value::String(env_value));
     save_disabled_env(&disabled_env)?;
@@ -624,9 +652,9 @@ pub async fn test_anthropic_connection(
     base_url: String,
     auth_token: String,
     model: String,
-) -> Result<ConnectionTestResult, String> {
+) -> AppResult<ConnectionTestResult> {
     if auth_token.trim().is_empty() {
-        return Err("ANTHROPIC_AUTH_TOKEN is empty".to_string());
+        return Err(AppError::invalid_input("ANTHROPIC_AUTH_TOKEN is empty"));
     }
 
     let base = base_url.trim_end_matches('/');
@@ -656,7 +684,7 @@ pub async fn test_anthropic_connection(
         .json(&payload)
         .send()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| AppError::new(ErrorCode::Network, e.to_string()))?;
 
     let status = response.status();
     let body = response.text().await.unwrap_or_default();
@@ -677,9 +705,9 @@ pub async fn test_anthropic_connection(
 pub async fn test_openai_connection(
     base_url: String,
     api_key: String,
-) -> Result<ConnectionTestResult, String> {
+) -> AppResult<ConnectionTestResult> {
     if api_key.trim().is_empty() {
-        return Err("API key is empty".to_string());
+        return Err(AppError::invalid_input("API key is empty"));
     }
 
     let base = base_url.trim_end_matches('/');
@@ -694,7 +722,7 @@ pub async fn test_openai_connection(
         .header("Authorization", format!("Bearer {}", api_key))
         .send()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| AppError::new(ErrorCode::Network, e.to_string()))?;
 
     let status = response.status();
     let body = response.text().await.unwrap_or_default();
@@ -721,16 +749,16 @@ const CLI_TEST_TIMEOUT_SECS: u64 = 15;
 pub async fn test_claude_cli(
     base_url: String,
     auth_token: String,
-) -> Result<ClaudeCliTestResult, String> {
+) -> AppResult<ClaudeCliTestResult> {
     use tokio::time::timeout;
 
     if auth_token.trim().is_empty() {
-        return Err("ANTHROPIC_AUTH_TOKEN is empty".to_string());
+        return Err(AppError::invalid_input("ANTHROPIC_AUTH_TOKEN is empty"));
     }
 
     let output = timeout(
         Duration::from_secs(CLI_TEST_TIMEOUT_SECS),
-        tokio::process::Command::new("claude")
+        tokio::process::Command::new(claude_program())
             .arg("--print")
             .arg("reply 1")
             .env("ANTHROPIC_BASE_URL", &base_url)
@@ -738,8 +766,13 @@ pub async fn test_claude_cli(
             .output(),
     )
     .await
-    .map_err(|_| format!("Claude CLI test timed out after {} seconds", CLI_TEST_TIMEOUT_SECS))?
-    .map_err(|e| format!("Failed to execute claude CLI: {}", e))?;
+    .map_err(|_| {
+        AppError::new(
+            ErrorCode::Timeout,
+            format!("Claude CLI test timed out after {} seconds", CLI_TEST_TIMEOUT_SECS),
+        )
+    })?
+    .map_err(|e| AppError::io("Failed to execute claude CLI", &e))?;
 
     let code = output.status.code().unwrap_or(-1);
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -0,0 +1,229 @@
+/**
+ * [INPUT]: 依赖 serde_json, crate::security, 内置 settings_schema.json (已知 Claude Code 设置键)
+ * [OUTPUT]: 对外提供 validate_settings 命令, validate_settings_json 校验函数, SettingsDiagnostic 类型
+ * [POS]: commands/ 模块的 settings.json 校验器，按内置 JSON Schema 子集标出未知键、类型错误、非法取值与废弃选项
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::sync::LazyLock;
+
+/// Known settings.json keys, bundled with the app
+static SETTINGS_SCHEMA: LazyLock<Value> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../settings_schema.json"))
+        .expect("Invalid settings_schema.json")
+});
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct SettingsDiagnostic {
+    /// JSON Pointer to the offending value, e.g. "/permissions/defaultMode" ("" = whole file)
+    pub path: String,
+    /// "invalid-json" | "unknown-key" | "wrong-type" | "invalid-value" | "deprecated"
+    pub rule: String,
+    pub severity: String, // "error" | "warning"
+    pub message: String,
+}
+
+impl SettingsDiagnostic {
+    fn new(path: &str, rule: &str, severity: &str, message: String) -> Self {
+        Self {
+            path: path.to_string(),
+            rule: rule.to_string(),
+            severity: severity.to_string(),
+            message,
+        }
+    }
+}
+
+// ============================================================================
+// Schema Walk
+// ============================================================================
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    let actual = type_name(value);
+    actual == expected || (expected == "number" && actual == "integer")
+}
+
+/// Follow a local `$ref` ("#/definitions/name") to the schema it names
+fn resolve(schema: &Value) -> &Value {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix("#/definitions/"))
+        .and_then(|name| SETTINGS_SCHEMA.get("definitions")?.get(name))
+        .unwrap_or(schema)
+}
+
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn check(value: &Value, schema: &Value, pointer: &str, out: &mut Vec<SettingsDiagnostic>) {
+    if schema.get("deprecated").and_then(Value::as_bool) == Some(true) {
+        let hint = schema
+            .get("deprecationMessage")
+            .and_then(Value::as_str)
+            .map(|m| format!(": {}", m))
+            .unwrap_or_default();
+        out.push(SettingsDiagnostic::new(
+            pointer,
+            "deprecated",
+            "warning",
+            format!(
+                "'{}' is deprecated{}",
+                pointer.trim_start_matches('/'),
+                hint
+            ),
+        ));
+    }
+    let schema = resolve(schema);
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let options: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            out.push(SettingsDiagnostic::new(
+                pointer,
+                "invalid-value",
+                "error",
+                format!("Expected one of {}, found {}", options.join(", "), value),
+            ));
+        }
+        return;
+    }
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        if !matches_type(value, expected) {
+            out.push(SettingsDiagnostic::new(
+                pointer,
+                "wrong-type",
+                "error",
+                format!("Expected {}, found {}", expected, type_name(value)),
+            ));
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, child) in object {
+                let path = child_pointer(pointer, key);
+                match (
+                    properties.and_then(|p| p.get(key)),
+                    schema.get("additionalProperties"),
+                ) {
+                    (Some(child_schema), _) => check(child, child_schema, &path, out),
+                    (None, Some(Value::Bool(false))) => out.push(SettingsDiagnostic::new(
+                        &path,
+                        "unknown-key",
+                        "warning",
+                        format!("Unknown key '{}'", key),
+                    )),
+                    (None, Some(extra @ Value::Object(_))) => check(child, extra, &path, out),
+                    (None, _) => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item, item_schema, &format!("{}/{}", pointer, i), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check settings.json content against the bundled schema
+pub fn validate_settings_json(content: &str) -> Vec<SettingsDiagnostic> {
+    let value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => {
+            return vec![SettingsDiagnostic::new(
+                "",
+                "invalid-json",
+                "error",
+                format!("Invalid JSON: {}", e),
+            )]
+        }
+    };
+    let mut diagnostics = Vec::new();
+    check(&value, &SETTINGS_SCHEMA, "", &mut diagnostics);
+    diagnostics
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Validate ~/.claude/settings.json (no diagnostics if the file doesn't exist)
+#[tauri::command]
+pub fn validate_settings() -> AppResult<Vec<SettingsDiagnostic>> {
+    let path = security::get_claude_dir_or_fallback().join("settings.json");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::io("Failed to read file", &e).with_context("settings.json"))?;
+    Ok(validate_settings_json(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_settings_json() {
+        let content = r#"{
+            "env": {"DISABLE_AUTOUPDATER": "1", "MAX_TOKENS": 8000},
+            "permissions": {"allow": ["Bash(git:*)"], "defaultMode": "yolo", "alow": []},
+            "hooks": {"PreToolUse": [{"matcher": "Bash", "hooks": [{"type": "command", "command": "true", "timeout": 5}]}]},
+            "allowedTools": ["Read", 3],
+            "statusLine": {"type": "command", "command": "~/bin/status"},
+            "model": 4,
+            "theme": "dark"
+        }"#;
+        let found: Vec<(String, String)> = validate_settings_json(content)
+            .into_iter()
+            .map(|d| (d.path, d.rule))
+            .collect();
+        let expected = [
+            ("/allowedTools", "deprecated"),
+            ("/allowedTools/1", "wrong-type"),
+            ("/env/MAX_TOKENS", "wrong-type"),
+            ("/model", "wrong-type"),
+            ("/permissions/alow", "unknown-key"),
+            ("/permissions/defaultMode", "invalid-value"),
+            ("/theme", "unknown-key"),
+        ];
+        assert_eq!(
+            found,
+            expected.map(|(path, rule)| (path.to_string(), rule.to_string()))
+        );
+
+        assert!(validate_settings_json("{}").is_empty());
+        let invalid = validate_settings_json("{\"env\": ");
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].rule, "invalid-json");
+    }
+}
@@ -0,0 +1,326 @@
+/**
+ * [INPUT]: 依赖 std::process::Command, std::fs, serde, chrono, crate::security
+ * [OUTPUT]: 对外提供 init_commands_repo, sync_commands_push, sync_commands_pull 命令
+ * [POS]: commands/ 模块的命令同步中心，将 ~/.claude 下的 commands/agents/skills 托管到 git 仓库
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SyncPullResult {
+    /// Files changed by the pull, relative to ~/.claude
+    pub updated: Vec<String>,
+    /// Files with merge conflicts; non-empty means the merge was aborted
+    pub conflicts: Vec<String>,
+}
+
+/// Marks a ~/.claude repository as created by lovcode (stored in .git/config)
+const SYNC_MARKER_KEY: &str = "lovcode.sync";
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        // Fail fast instead of waiting for a password prompt nobody can see
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run git {}: {}", args.first().unwrap_or(&""), e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whitelist .gitignore: ignore everything in ~/.claude except the synced directories
+fn build_gitignore(include_agents: bool, include_skills: bool) -> String {
+    let mut lines = vec![
+        "# Managed by lovcode: only shared slash commands are synced".to_string(),
+        "/*".to_string(),
+        "!/.gitignore".to_string(),
+        "!/commands/".to_string(),
+    ];
+    if include_agents {
+        lines.push("!/agents/".to_string());
+    }
+    if include_skills {
+        lines.push("!/skills/".to_string());
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Returns ~/.claude after checking it is a lovcode-managed sync repository
+fn get_sync_repo() -> AppResult<PathBuf> {
+    check_sync_repo(security::get_claude_dir()?)
+}
+
+fn check_sync_repo(claude_dir: PathBuf) -> AppResult<PathBuf> {
+    if !claude_dir.join(".git").exists() {
+        return Err(AppError::not_found("Command sync is not initialized"));
+    }
+    let marker = run_git(&claude_dir, &["config", "--get", SYNC_MARKER_KEY]).unwrap_or_default();
+    if marker != "true" {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("{} is a git repository not managed by lovcode", claude_dir.display()),
+        ));
+    }
+    Ok(claude_dir)
+}
+
+/// Commit any local changes; returns true if a commit was created
+fn commit_local_changes(repo: &Path, message: &str) -> Result<bool, String> {
+    run_git(repo, &["add", "-A"])?;
+    let status = run_git(repo, &["status", "--porcelain"])?;
+    if status.is_empty() {
+        return Ok(false);
+    }
+    run_git(repo, &["commit", "-m", message])?;
+    Ok(true)
+}
+
+fn current_branch(repo: &Path) -> Result<String, String> {
+    run_git(repo, &["rev-parse", "--abbrev-ref", "HEAD"])
+}
+
+fn default_commit_message() -> String {
+    format!(
+        "Sync commands ({})",
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    )
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Turn ~/.claude into a git repository that tracks commands/ (and optionally
+/// agents/ and skills/), pointing `origin` at the given remote
+#[tauri::command]
+pub fn init_commands_repo(
+    remote: String,
+    include_agents: Option<bool>,
+    include_skills: Option<bool>,
+) -> AppResult<()> {
+    let remote = remote.trim();
+    if remote.is_empty() || remote.starts_with('-') {
+        return Err(AppError::invalid_input(format!("Invalid remote: {}", remote)));
+    }
+    init_in(
+        &security::get_claude_dir()?,
+        remote,
+        include_agents.unwrap_or(false),
+        include_skills.unwrap_or(false),
+    )
+}
+
+fn init_in(
+    claude_dir: &Path,
+    remote: &str,
+    include_agents: bool,
+    include_skills: bool,
+) -> AppResult<()> {
+    fs::create_dir_all(claude_dir.join("commands"))
+        .map_err(|e| AppError::io("Failed to create directory", &e))?;
+
+    if claude_dir.join(".git").exists() {
+        // Re-initializing our own repo is fine (e.g. to change the remote)
+        check_sync_repo(claude_dir.to_path_buf())?;
+    } else {
+        run_git(claude_dir, &["init"])?;
+        run_git(claude_dir, &["config", SYNC_MARKER_KEY, "true"])?;
+    }
+
+    let gitignore = build_gitignore(include_agents, include_skills);
+    security::atomic_write_string(&claude_dir.join(".gitignore"), &gitignore)?;
+
+    let remotes = run_git(claude_dir, &["remote"])?;
+    if remotes.lines().any(|r| r == "origin") {
+        run_git(claude_dir, &["remote", "set-url", "origin", remote])?;
+    } else {
+        run_git(claude_dir, &["remote", "add", "origin", remote])?;
+    }
+
+    commit_local_changes(claude_dir, "Initial command sync")?;
+    Ok(())
+}
+
+/// Commit local changes and push them to origin. Returns the pushed commit hash.
+#[tauri::command]
+pub async fn sync_commands_push(message: Option<String>) -> AppResult<String> {
+    let repo = get_sync_repo()?;
+    tauri::async_runtime::spawn_blocking(move || push_in(&repo, message))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn push_in(repo: &Path, message: Option<String>) -> AppResult<String> {
+    let message = message
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(default_commit_message);
+
+    commit_local_changes(repo, &message)?;
+
+    let branch = current_branch(repo)?;
+    run_git(repo, &["push", "-u", "origin", &branch])?;
+    Ok(run_git(repo, &["rev-parse", "HEAD"])?)
+}
+
+/// Pull from origin. Local edits are committed first; if the merge conflicts it is
+/// aborted and the conflicting files are reported instead of being left half-merged.
+#[tauri::command]
+pub async fn sync_commands_pull() -> AppResult<SyncPullResult> {
+    let repo = get_sync_repo()?;
+    tauri::async_runtime::spawn_blocking(move || pull_in(&repo))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn pull_in(repo: &Path) -> AppResult<SyncPullResult> {
+    commit_local_changes(repo, &default_commit_message())?;
+
+    let branch = current_branch(repo)?;
+    let before = run_git(repo, &["rev-parse", "HEAD"]).ok();
+
+    run_git(repo, &["fetch", "origin"])?;
+    let remote_ref = format!("origin/{}", branch);
+    if run_git(repo, &["rev-parse", "--verify", "--quiet", &remote_ref]).is_err() {
+        // Nothing has been pushed to this branch yet
+        return Ok(SyncPullResult {
+            updated: Vec::new(),
+            conflicts: Vec::new(),
+        });
+    }
+
+    if let Err(merge_err) = run_git(
+        repo,
+        &["merge", "--no-edit", "--allow-unrelated-histories", &remote_ref],
+    ) {
+        let conflicts: Vec<String> =
+            run_git(repo, &["diff", "--name-only", "--diff-filter=U"])
+                .unwrap_or_default()
+                .lines()
+                .map(String::from)
+                .collect();
+        if conflicts.is_empty() {
+            return Err(merge_err.into());
+        }
+        let _ = run_git(repo, &["merge", "--abort"]);
+        return Ok(SyncPullResult {
+            updated: Vec::new(),
+            conflicts,
+        });
+    }
+
+    let updated = match before {
+        Some(before) => run_git(repo, &["diff", "--name-only", &before, "HEAD"])?
+            .lines()
+            .map(String::from)
+            .collect(),
+        None => run_git(repo, &["ls-files"])?
+            .lines()
+            .map(String::from)
+            .collect(),
+    };
+
+    Ok(SyncPullResult {
+        updated,
+        conflicts: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A sync repo pointing at `remote`, with a local identity so commits work anywhere
+    fn sync_dir(root: &Path, name: &str, remote: &Path) -> PathBuf {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).expect("create dir");
+        run_git(&dir, &["init", "-q", "-b", "main"]).expect("git init");
+        for (key, value) in [
+            ("user.name", "Test"),
+            ("user.email", "test@example.com"),
+            (SYNC_MARKER_KEY, "true"),
+        ] {
+            run_git(&dir, &["config", key, value]).expect("git config");
+        }
+        init_in(&dir, &remote.to_string_lossy(), false, false).expect("init sync repo");
+        dir
+    }
+
+    #[test]
+    fn test_build_gitignore() {
+        let commands_only = build_gitignore(false, false);
+        assert!(commands_only.contains("\n/*\n"));
+        assert!(commands_only.contains("!/commands/"));
+        assert!(!commands_only.contains("!/agents/"));
+        assert!(!commands_only.contains("!/skills/"));
+        assert!(commands_only.ends_with('\n'));
+
+        let everything = build_gitignore(true, true);
+        assert!(everything.contains("!/agents/\n"));
+        assert!(everything.contains("!/skills/\n"));
+    }
+
+    #[test]
+    fn test_push_pull_roundtrip_and_conflict() {
+        let temp = TempDir::new().expect("temp dir");
+        let remote = temp.path().join("remote.git");
+        fs::create_dir_all(&remote).expect("create remote");
+        run_git(&remote, &["init", "-q", "--bare", "-b", "main"]).expect("init bare");
+        let a = sync_dir(temp.path(), "a", &remote);
+        let b = sync_dir(temp.path(), "b", &remote);
+        let command = Path::new("commands").join("hello.md");
+
+        // Not pushed yet: nothing to pull
+        let empty = pull_in(&b).expect("pull before push");
+        assert!(empty.updated.is_empty() && empty.conflicts.is_empty());
+
+        fs::write(a.join(&command), "v1\n").expect("write command");
+        // Files outside the synced directories stay local
+        fs::write(a.join("settings.json"), "{}").expect("write settings");
+        let pushed = push_in(&a, Some("add hello".to_string())).expect("push");
+        assert_eq!(run_git(&remote, &["rev-parse", "main"]).expect("remote head"), pushed);
+
+        let pulled = pull_in(&b).expect("pull");
+        assert_eq!(pulled.updated, vec!["commands/hello.md".to_string()]);
+        assert!(pulled.conflicts.is_empty());
+        assert_eq!(fs::read_to_string(b.join(&command)).expect("read"), "v1\n");
+        assert!(!b.join("settings.json").exists());
+
+        // Both sides edit the same command: the merge is aborted and reported
+        fs::write(a.join(&command), "from a\n").expect("edit a");
+        push_in(&a, None).expect("push a");
+        fs::write(b.join(&command), "from b\n").expect("edit b");
+        let conflicted = pull_in(&b).expect("pull with conflict");
+        assert_eq!(conflicted.conflicts, vec!["commands/hello.md".to_string()]);
+        assert!(conflicted.updated.is_empty());
+        assert_eq!(fs::read_to_string(b.join(&command)).expect("read"), "from b\n");
+        assert!(!b.join(".git").join("MERGE_HEAD").exists());
+    }
+}
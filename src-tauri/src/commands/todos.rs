@@ -0,0 +1,306 @@
+/**
+ * [INPUT]: 依赖 serde_json, crate::jsonl (流式读取会话), crate::security, crate::commands::projects (encode_project_path, read_session_head)
+ * [OUTPUT]: 对外提供 get_session_todos, get_project_open_todos 命令与 TodoItem/SessionPlan/SessionTodos/OpenTodos 类型
+ * [POS]: commands/ 模块的会话待办提取，解析 JSONL 中的 TodoWrite 与 ExitPlanMode 工具调用，支撑跨会话任务总览
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::projects::{encode_project_path, read_session_head};
+use crate::errors::{AppError, AppResult};
+use crate::jsonl;
+use crate::security;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TodoItem {
+    pub content: String,
+    /// "pending", "in_progress" or "completed"
+    pub status: String,
+    /// Present-tense label Claude shows while the item is in progress
+    pub active_form: Option<String>,
+}
+
+/// A plan Claude proposed in plan mode (ExitPlanMode)
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionPlan {
+    /// Markdown
+    pub plan: String,
+    pub timestamp: Option<String>,
+    /// Line in the session JSONL
+    pub line_number: usize,
+    /// Whether the user accepted it; None when the session has no answer
+    pub approved: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionTodos {
+    pub session_id: String,
+    /// The latest TodoWrite list; every call replaces the whole list
+    pub todos: Vec<TodoItem>,
+    /// Timestamp of the latest TodoWrite call
+    pub updated_at: Option<String>,
+    /// Number of TodoWrite calls
+    pub updates: usize,
+    pub plans: Vec<SessionPlan>,
+}
+
+/// A session whose latest todo list still has pending or in-progress items
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenTodos {
+    pub session_id: String,
+    pub summary: Option<String>,
+    pub updated_at: Option<String>,
+    /// Only the items that aren't completed
+    pub todos: Vec<TodoItem>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn parse_todo_list(input: &Value) -> Vec<TodoItem> {
+    input
+        .get("todos")
+        .and_then(|t| t.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|todo| {
+            Some(TodoItem {
+                content: str_field(todo, "content")?,
+                status: str_field(todo, "status").unwrap_or_else(|| "pending".to_string()),
+                active_form: str_field(todo, "activeForm"),
+            })
+        })
+        .collect()
+}
+
+/// Collect TodoWrite lists and ExitPlanMode plans from a session JSONL
+fn parse_session_todos(reader: impl Read, session_id: &str) -> SessionTodos {
+    let mut result = SessionTodos {
+        session_id: session_id.to_string(),
+        todos: Vec::new(),
+        updated_at: None,
+        updates: 0,
+        plans: Vec::new(),
+    };
+    // ExitPlanMode tool_use id -> index in result.plans, until its tool_result shows up
+    let mut pending_plans: HashMap<String, usize> = HashMap::new();
+
+    for (line_number, line) in jsonl::from_reader(reader) {
+        // Cheap filter: most lines are neither todos, plans nor answers to a plan
+        let answers_plan = !pending_plans.is_empty() && line.contains("tool_result");
+        if !answers_plan && !line.contains("\"TodoWrite\"") && !line.contains("\"ExitPlanMode\"") {
+            continue;
+        }
+        let Ok(parsed) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        // Subagent todos belong to the subagent, not the session
+        if parsed.get("isSidechain").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        let timestamp = str_field(&parsed, "timestamp");
+        let blocks = parsed
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array());
+
+        for block in blocks.into_iter().flatten() {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("tool_use") => {
+                    let input = block.get("input").unwrap_or(&Value::Null);
+                    match block.get("name").and_then(|n| n.as_str()) {
+                        Some("TodoWrite") => {
+                            result.todos = parse_todo_list(input);
+                            result.updated_at.clone_from(&timestamp);
+                            result.updates += 1;
+                        }
+                        Some("ExitPlanMode") => {
+                            let Some(plan) = str_field(input, "plan") else {
+                                continue;
+                            };
+                            if let Some(id) = str_field(block, "id") {
+                                pending_plans.insert(id, result.plans.len());
+                            }
+                            result.plans.push(SessionPlan {
+                                plan,
+                                timestamp: timestamp.clone(),
+                                line_number,
+                                approved: None,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+                Some("tool_result") => {
+                    let index =
+                        str_field(block, "tool_use_id").and_then(|id| pending_plans.remove(&id));
+                    if let Some(plan) = index.and_then(|i| result.plans.get_mut(i)) {
+                        // Rejecting a plan comes back as an error result
+                        let rejected = block.get("is_error").and_then(Value::as_bool);
+                        plan.approved = Some(rejected != Some(true));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    result
+}
+
+fn read_session_todos(path: &Path, session_id: &str) -> Result<SessionTodos, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open session: {}", e))?;
+    Ok(parse_session_todos(file, session_id))
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// The session's latest todo list and the plans proposed in it
+#[tauri::command]
+pub async fn get_session_todos(project_id: String, session_id: String) -> AppResult<SessionTodos> {
+    let path = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(&project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !path.exists() {
+        return Err(AppError::not_found("Session not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
+    }
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || read_session_todos(&path, &session_id))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
+}
+
+/// Unfinished todos across a project's sessions, most recently updated first
+#[tauri::command]
+pub async fn get_project_open_todos(project_path: String) -> AppResult<Vec<OpenTodos>> {
+    let project_dir = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(encode_project_path(&project_path));
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let Ok(entries) = fs::read_dir(&project_dir) else {
+            return Vec::new();
+        };
+        let mut open: Vec<OpenTodos> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter_map(|path| {
+                let name = path.file_name()?.to_str()?;
+                if !name.ends_with(".jsonl") || name.starts_with("agent-") {
+                    return None;
+                }
+                let session_id = name.trim_end_matches(".jsonl");
+                let todos = read_session_todos(&path, session_id).ok()?;
+                let pending: Vec<TodoItem> = todos
+                    .todos
+                    .into_iter()
+                    .filter(|t| t.status != "completed")
+                    .collect();
+                if pending.is_empty() {
+                    return None;
+                }
+                Some(OpenTodos {
+                    session_id: session_id.to_string(),
+                    summary: read_session_head(&path, 20).0,
+                    updated_at: todos.updated_at,
+                    todos: pending,
+                })
+            })
+            .collect();
+        open.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        open
+    })
+    .await
+    .map_err(|e| e.to_string())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo_write(timestamp: &str, todos: &[(&str, &str)]) -> String {
+        let todos: Vec<Value> = todos
+            .iter()
+            .map(|(content, status)| {
+                serde_json::json!({ "content": content, "status": status, "activeForm": content })
+            })
+            .collect();
+        serde_json::json!({
+            "type": "assistant",
+            "timestamp": timestamp,
+            "message": { "role": "assistant", "content": [
+                { "type": "tool_use", "id": "t1", "name": "TodoWrite", "input": { "todos": todos } }
+            ]}
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_latest_todo_list_wins() {
+        let lines = [
+            todo_write(
+                "2026-10-16T09:00:00Z",
+                &[("Write parser", "in_progress"), ("Add tests", "pending")],
+            ),
+            r#"{"type":"user","message":{"role":"user","content":"keep going"}}"#.to_string(),
+            // Subagent lists don't replace the session's own
+            r#"{"type":"assistant","isSidechain":true,"message":{"content":[{"type":"tool_use","name":"TodoWrite","input":{"todos":[]}}]}}"#.to_string(),
+            todo_write(
+                "2026-10-16T09:05:00Z",
+                &[("Write parser", "completed"), ("Add tests", "in_progress")],
+            ),
+        ];
+        let todos = parse_session_todos(lines.join("\n").as_bytes(), "s1");
+        assert_eq!(todos.updates, 2);
+        assert_eq!(todos.updated_at.as_deref(), Some("2026-10-16T09:05:00Z"));
+        assert_eq!(
+            todos.todos,
+            vec![
+                TodoItem {
+                    content: "Write parser".to_string(),
+                    status: "completed".to_string(),
+                    active_form: Some("Write parser".to_string()),
+                },
+                TodoItem {
+                    content: "Add tests".to_string(),
+                    status: "in_progress".to_string(),
+                    active_form: Some("Add tests".to_string()),
+                },
+            ]
+        );
+        assert!(todos.plans.is_empty());
+    }
+
+    #[test]
+    fn test_plans_track_approval() {
+        let lines = [
+            r#"{"type":"assistant","timestamp":"2026-10-16T10:00:00Z","message":{"content":[{"type":"tool_use","id":"p1","name":"ExitPlanMode","input":{"plan":"1. Refactor\n2. Test"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"p1","is_error":true,"content":"The user doesn't want to proceed"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2026-10-16T10:02:00Z","message":{"content":[{"type":"tool_use","id":"p2","name":"ExitPlanMode","input":{"plan":"1. Test first"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"p2","content":"User has approved your plan"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"p3","name":"ExitPlanMode","input":{"plan":"3"}}]}}"#,
+        ];
+        let todos = parse_session_todos(lines.join("\n").as_bytes(), "s1");
+        let approvals: Vec<Option<bool>> = todos.plans.iter().map(|p| p.approved).collect();
+        assert_eq!(approvals, vec![Some(false), Some(true), None]);
+        assert_eq!(todos.plans.first().map(|p| p.line_number), Some(1));
+        assert_eq!(todos.updates, 0);
+    }
+}
@@ -0,0 +1,349 @@
+/**
+ * [INPUT]: 依赖 serde_json, crate::jsonl (流式读取会话), chrono (时间戳差计算耗时), crate::security
+ * [OUTPUT]: 对外提供 get_session_tool_calls 命令与 ToolCall/ToolCallFilter 类型
+ * [POS]: commands/ 模块的工具调用审计，把会话 JSONL 中的 tool_use 与 tool_result 按 id 配对，列出每次 Bash/编辑/MCP 调用的输入、输出大小、耗时与是否出错
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::jsonl;
+use crate::security;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+
+/// Characters kept from each result for the preview
+const OUTPUT_PREVIEW_CHARS: usize = 500;
+const SUMMARY_CHARS: usize = 200;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ToolCallFilter {
+    /// Tool names to keep (case-insensitive); every tool when empty
+    pub tools: Vec<String>,
+    /// Case-insensitive substring of the tool name or input
+    pub query: Option<String>,
+    /// Only calls whose result was an error
+    pub errors_only: bool,
+    /// Only MCP tools (mcp__<server>__<tool>)
+    pub mcp_only: bool,
+}
+
+/// One tool_use block and the tool_result that answered it
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCall {
+    /// tool_use id
+    pub id: String,
+    pub name: String,
+    /// Server name for MCP tools
+    pub mcp_server: Option<String>,
+    pub input: Value,
+    /// The command, file path, pattern or URL the call was about
+    pub summary: String,
+    /// Bytes of text (and base64 image data) in the result
+    pub output_bytes: usize,
+    pub output_preview: Option<String>,
+    pub is_error: bool,
+    pub started_at: Option<String>,
+    /// None when the call never got a result (interrupted, or still running)
+    pub finished_at: Option<String>,
+    /// Time between the tool_use and tool_result lines
+    pub duration_ms: Option<u64>,
+    /// Made by a subagent (Task tool)
+    pub is_sidechain: bool,
+    /// Line of the tool_use in the session JSONL
+    pub line_number: usize,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let mut chars = text.chars();
+    let head: String = chars.by_ref().take(max_chars).collect();
+    if chars.next().is_some() {
+        format!("{}…", head)
+    } else {
+        head
+    }
+}
+
+/// "mcp__github__create_issue" -> "github"
+fn mcp_server(name: &str) -> Option<String> {
+    let rest = name.strip_prefix("mcp__")?;
+    rest.split_once("__").map(|(server, _)| server.to_string())
+}
+
+/// The field that says what a call did, for the common tools; the first string
+/// input otherwise
+fn summarize_input(name: &str, input: &Value) -> String {
+    let field = |key: &str| input.get(key).and_then(Value::as_str);
+    let summary = match name {
+        "Bash" => field("command"),
+        "Read" | "Write" | "Edit" | "MultiEdit" => field("file_path"),
+        "NotebookEdit" => field("notebook_path"),
+        "Grep" | "Glob" => field("pattern"),
+        "WebFetch" => field("url"),
+        "WebSearch" => field("query"),
+        "Task" => field("description"),
+        _ => None,
+    }
+    .or_else(|| input.as_object()?.values().find_map(Value::as_str))
+    .unwrap_or_default();
+    truncate(summary.lines().next().unwrap_or_default(), SUMMARY_CHARS)
+}
+
+/// Text of a tool_result `content`: a string, or text and image blocks
+fn result_text(content: &Value) -> (String, usize) {
+    match content {
+        Value::String(text) => (text.clone(), text.len()),
+        Value::Array(blocks) => {
+            let mut text = String::new();
+            let mut bytes = 0;
+            for block in blocks {
+                if let Some(t) = block.get("text").and_then(Value::as_str) {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(t);
+                    bytes += t.len();
+                } else if let Some(data) = block
+                    .get("source")
+                    .and_then(|s| s.get("data"))
+                    .and_then(Value::as_str)
+                {
+                    bytes += data.len();
+                }
+            }
+            (text, bytes)
+        }
+        _ => (String::new(), 0),
+    }
+}
+
+fn millis_between(start: Option<&str>, end: &str) -> Option<u64> {
+    let start = chrono::DateTime::parse_from_rfc3339(start?).ok()?;
+    let end = chrono::DateTime::parse_from_rfc3339(end).ok()?;
+    u64::try_from((end - start).num_milliseconds()).ok()
+}
+
+/// Pair every tool_use with its tool_result, in call order
+fn parse_tool_calls(reader: impl Read) -> Vec<ToolCall> {
+    let mut calls: Vec<ToolCall> = Vec::new();
+    // tool_use id -> index in calls
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+
+    for (line_number, line) in jsonl::from_reader(reader) {
+        if !line.contains("\"tool_use\"") && !line.contains("\"tool_result\"") {
+            continue;
+        }
+        let Ok(parsed) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let timestamp = parsed
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .map(String::from);
+        let is_sidechain = parsed.get("isSidechain").and_then(Value::as_bool) == Some(true);
+        let blocks = parsed
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(Value::as_array);
+
+        for block in blocks.into_iter().flatten() {
+            match block.get("type").and_then(Value::as_str) {
+                Some("tool_use") => {
+                    let name = block
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    let input = block.get("input").cloned().unwrap_or(Value::Null);
+                    let id = block
+                        .get("id")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    by_id.insert(id.clone(), calls.len());
+                    calls.push(ToolCall {
+                        id,
+                        mcp_server: mcp_server(&name),
+                        summary: summarize_input(&name, &input),
+                        name,
+                        input,
+                        output_bytes: 0,
+                        output_preview: None,
+                        is_error: false,
+                        started_at: timestamp.clone(),
+                        finished_at: None,
+                        duration_ms: None,
+                        is_sidechain,
+                        line_number,
+                    });
+                }
+                Some("tool_result") => {
+                    let call = block
+                        .get("tool_use_id")
+                        .and_then(Value::as_str)
+                        .and_then(|id| by_id.get(id))
+                        .and_then(|&i| calls.get_mut(i));
+                    let Some(call) = call else {
+                        continue;
+                    };
+                    let (text, bytes) = result_text(block.get("content").unwrap_or(&Value::Null));
+                    call.output_bytes = bytes;
+                    call.output_preview =
+                        (!text.is_empty()).then(|| truncate(&text, OUTPUT_PREVIEW_CHARS));
+                    call.is_error = block.get("is_error").and_then(Value::as_bool) == Some(true);
+                    if let Some(finished) = &timestamp {
+                        call.duration_ms = millis_between(call.started_at.as_deref(), finished);
+                    }
+                    call.finished_at.clone_from(&timestamp);
+                }
+                _ => {}
+            }
+        }
+    }
+    calls
+}
+
+fn matches_filter(call: &ToolCall, filter: &ToolCallFilter) -> bool {
+    if filter.errors_only && !call.is_error {
+        return false;
+    }
+    if filter.mcp_only && call.mcp_server.is_none() {
+        return false;
+    }
+    if !filter.tools.is_empty()
+        && !filter
+            .tools
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&call.name))
+    {
+        return false;
+    }
+    match filter.query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => {
+            let query = query.to_lowercase();
+            call.name.to_lowercase().contains(&query)
+                || call.input.to_string().to_lowercase().contains(&query)
+        }
+        _ => true,
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Every tool call in a session with its input, output size, duration and error flag,
+/// in call order. Imported sessions have no tool calls.
+#[tauri::command]
+pub async fn get_session_tool_calls(
+    project_id: String,
+    session_id: String,
+    filter: Option<ToolCallFilter>,
+) -> AppResult<Vec<ToolCall>> {
+    if project_id.starts_with(crate::commands::importers::IMPORTED_PROJECT_PREFIX) {
+        return Ok(Vec::new());
+    }
+    let path = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(&project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !path.exists() {
+        return Err(AppError::not_found("Session not found")
+            .with_context(format!("{}/{}", project_id, session_id)));
+    }
+    let filter = filter.unwrap_or_default();
+    Ok(tauri::async_runtime::spawn_blocking(move || {
+        let file = fs::File::open(&path).map_err(|e| format!("Failed to open session: {}", e))?;
+        let mut calls = parse_tool_calls(file);
+        calls.retain(|call| matches_filter(call, &filter));
+        Ok::<_, String>(calls)
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = r#"{"type":"assistant","timestamp":"2025-03-04T10:00:00.000Z","message":{"content":[{"type":"text","text":"Running tests"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test\necho done","description":"Run tests"}}]}}
+{"type":"user","timestamp":"2025-03-04T10:00:02.500Z","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"error: 1 test failed","is_error":true}]}}
+{"type":"assistant","timestamp":"2025-03-04T10:00:03.000Z","isSidechain":true,"message":{"content":[{"type":"tool_use","id":"t2","name":"mcp__github__get_issue","input":{"repo":"a/b","number":7}}]}}
+{"type":"user","timestamp":"2025-03-04T10:00:04.000Z","isSidechain":true,"message":{"content":[{"type":"tool_result","tool_use_id":"t2","content":[{"type":"text","text":"Issue #7"},{"type":"image","source":{"type":"base64","data":"AAAA"}}]}]}}
+{"type":"assistant","timestamp":"2025-03-04T10:00:05.000Z","message":{"content":[{"type":"tool_use","id":"t3","name":"Edit","input":{"file_path":"/src/main.rs","old_string":"a","new_string":"b"}}]}}"#;
+
+    #[test]
+    fn test_parse_tool_calls_pairs_results() {
+        let calls = parse_tool_calls(SESSION.as_bytes());
+        assert_eq!(calls.len(), 3);
+
+        assert_eq!(calls[0].name, "Bash");
+        assert_eq!(calls[0].summary, "cargo test");
+        assert!(calls[0].is_error);
+        assert_eq!(calls[0].duration_ms, Some(2500));
+        assert_eq!(calls[0].output_bytes, 20);
+        assert_eq!(calls[0].line_number, 1);
+
+        assert_eq!(calls[1].mcp_server.as_deref(), Some("github"));
+        assert_eq!(calls[1].summary, "a/b");
+        assert_eq!(calls[1].output_bytes, 12);
+        assert_eq!(calls[1].output_preview.as_deref(), Some("Issue #7"));
+        assert!(calls[1].is_sidechain && !calls[1].is_error);
+
+        assert_eq!(calls[2].summary, "/src/main.rs");
+        assert_eq!(calls[2].finished_at, None);
+        assert_eq!(calls[2].duration_ms, None);
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        let calls = parse_tool_calls(SESSION.as_bytes());
+        let names = |filter: ToolCallFilter| -> Vec<String> {
+            calls
+                .iter()
+                .filter(|c| matches_filter(c, &filter))
+                .map(|c| c.name.clone())
+                .collect()
+        };
+        assert_eq!(names(ToolCallFilter::default()).len(), 3);
+        assert_eq!(
+            names(ToolCallFilter {
+                tools: vec!["edit".to_string()],
+                ..ToolCallFilter::default()
+            }),
+            ["Edit"]
+        );
+        assert_eq!(
+            names(ToolCallFilter {
+                errors_only: true,
+                ..ToolCallFilter::default()
+            }),
+            ["Bash"]
+        );
+        assert_eq!(
+            names(ToolCallFilter {
+                mcp_only: true,
+                ..ToolCallFilter::default()
+            }),
+            ["mcp__github__get_issue"]
+        );
+        assert_eq!(
+            names(ToolCallFilter {
+                query: Some("MAIN.RS".to_string()),
+                ..ToolCallFilter::default()
+            }),
+            ["Edit"]
+        );
+    }
+}
@@ -1,20 +1,24 @@
 /**
- * [INPUT]: 依赖 std::fs, serde, serde_json, reqwest, crate::security
- * [OUTPUT]: 对外提供 get_claude_code_version_info, install_claude_code_version, set_claude_code_autoupdater 命令
+ * [INPUT]: 依赖 std::fs, serde, serde_json, reqwest (npm registry / GitHub CHANGELOG), crate::security
+ * [OUTPUT]: 对外提供 run_shell_command 工具函数, get_claude_code_version_info, list_claude_code_versions, get_claude_code_changelog, install_claude_code_version, pin/unpin_claude_code_version, set_claude_code_autoupdater 命令
  * [POS]: commands/ 模块的 Claude Code 版本管理命令中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use crate::errors::{AppError, AppResult, ErrorCode};
 use crate::security::{self, validate_version};
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 // ============================================================================
 // Types
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ClaudeCodeInstallType {
     Native,
@@ -28,20 +32,310 @@ pub struct VersionWithDownloads {
     pub downloads: u64,
 }
 
+/// A published version from the npm registry
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ClaudeCodeRelease {
+    pub version: String,
+    /// ISO 8601 publish time
+    pub published_at: Option<String>,
+    /// Has a pre-release suffix, e.g. 1.0.0-beta.1
+    pub prerelease: bool,
+    /// Dist-tags pointing at this version ("latest", "next", ...)
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeCodeVersionList {
+    /// Dist-tag -> version
+    pub dist_tags: BTreeMap<String, String>,
+    /// Newest first
+    pub versions: Vec<ClaudeCodeRelease>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ClaudeCodeVersionInfo {
     pub install_type: ClaudeCodeInstallType,
     pub current_version: Option<String>,
     pub available_versions: Vec<VersionWithDownloads>,
     pub autoupdater_disabled: bool,
+    /// Set while a version is pinned with pin_claude_code_version
+    pub pinned: Option<ClaudeCodePin>,
+}
+
+/// A version the user pinned; recorded in ~/.lovstudio/lovcode/claude_code_pin.json
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClaudeCodePin {
+    pub version: String,
+    pub install_type: ClaudeCodeInstallType,
+    /// Unix seconds
+    pub pinned_at: u64,
+    /// Autoupdater setting before the pin, restored by unpin_claude_code_version
+    #[serde(default)]
+    pub autoupdater_was_disabled: bool,
+}
+
+/// One `## <version>` section of the Claude Code CHANGELOG
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ClaudeCodeChangelogEntry {
+    pub version: String,
+    /// Markdown body of the section (bullet list)
+    pub notes: String,
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+const NPM_PACKAGE_URL: &str = "https://registry.npmjs.org/@anthropic-ai/claude-code";
+const DEFAULT_VERSION_LIST_LIMIT: usize = 50;
+const CHANGELOG_URL: &str =
+    "https://raw.githubusercontent.com/anthropics/claude-code/main/CHANGELOG.md";
+/// A cached CHANGELOG is reused for this long...
+const CHANGELOG_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// ...but refetched sooner when it lacks the requested version, at most this often
+const CHANGELOG_MIN_REFETCH: Duration = Duration::from_secs(10 * 60);
+
+fn pin_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("claude_code_pin.json")
+}
+
+fn claude_settings_path() -> PathBuf {
+    security::get_claude_dir_or_fallback().join("settings.json")
+}
+
+/// DISABLE_AUTOUPDATER from the env block of a Claude settings.json
+fn autoupdater_disabled_in(settings_path: &Path) -> bool {
+    fs::read_to_string(settings_path)
+        .ok()
+        .and_then(|content| {
+            let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+            json.get("env")?
+                .get("DISABLE_AUTOUPDATER")?
+                .as_str()
+                .map(|s| s == "true" || s == "1")
+        })
+        .unwrap_or(false)
+}
+
+fn load_pin(path: &Path) -> Option<ClaudeCodePin> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_pin(path: &Path, pin: &ClaudeCodePin) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(pin).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content).map_err(|e| e.to_string())
+}
+
+/// Install `version` ("latest" or semver, already validated) the given way.
+/// Blocking; returns the installer's stdout.
+fn run_install(version: &str, install_type: &str) -> Result<String, String> {
+    let cmd = if install_type == "npm" {
+        // NPM installation (--force to overwrite existing native install)
+        // version 已经通过 validate_version 验证，安全
+        format!("npm install -g --force @anthropic-ai/claude-code@{}", version)
+    } else {
+        // Native installation (default)
+        // version 已经通过 validate_version 验证，安全
+        let version_arg = if version == "latest" { "" } else { version };
+        format!("curl -fsSL https://claude.ai/install.sh | bash -s {}", version_arg)
+    };
+
+    // Use user's interactive login shell to get proper PATH (nvm, etc.)
+    let output = run_shell_command(&cmd)
+        .map_err(|e| format!("Failed to run install command: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// Compare versions like semver: numeric parts first, then a release sorts above its
+/// pre-releases (1.0.0-beta < 1.0.0)
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |s: &str| -> (Vec<u32>, Option<String>) {
+        let (core, pre) = s.split_once('-').map_or((s, None), |(c, p)| (c, Some(p.to_string())));
+        (core.split('.').filter_map(|p| p.parse().ok()).collect(), pre)
+    };
+    let (a_core, a_pre) = parse(a);
+    let (b_core, b_pre) = parse(b);
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(&b),
+    })
+}
+
+/// Build the version list from an npm packument (`versions`, `time`, `dist-tags`)
+fn parse_registry_versions(packument: &serde_json::Value, limit: usize) -> ClaudeCodeVersionList {
+    let dist_tags: BTreeMap<String, String> = packument
+        .get("dist-tags")
+        .and_then(|t| t.as_object())
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(tag, v)| Some((tag.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let times = packument.get("time").and_then(|t| t.as_object());
+
+    let mut versions: Vec<ClaudeCodeRelease> = packument
+        .get("versions")
+        .and_then(|v| v.as_object())
+        .map(|obj| {
+            obj.keys()
+                .map(|version| ClaudeCodeRelease {
+                    published_at: times
+                        .and_then(|t| t.get(version))
+                        .and_then(|t| t.as_str())
+                        .map(str::to_string),
+                    prerelease: version.contains('-'),
+                    tags: dist_tags
+                        .iter()
+                        .filter(|(_, v)| *v == version)
+                        .map(|(tag, _)| tag.clone())
+                        .collect(),
+                    version: version.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Newest first by publish time (ISO strings sort chronologically), then by version
+    versions.sort_by(|a, b| {
+        b.published_at
+            .cmp(&a.published_at)
+            .then_with(|| compare_versions(&b.version, &a.version))
+    });
+    versions.truncate(limit);
+
+    ClaudeCodeVersionList {
+        dist_tags,
+        versions,
+    }
+}
+
+fn changelog_cache_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("claude_code_changelog.md")
+}
+
+/// Cached CHANGELOG text and its age
+fn read_cached_changelog(path: &Path) -> Option<(String, Duration)> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+    Some((fs::read_to_string(path).ok()?, age))
+}
+
+/// Split the CHANGELOG into `## <version>` sections, in file order (newest first)
+fn parse_changelog(markdown: &str) -> Vec<ClaudeCodeChangelogEntry> {
+    let mut entries: Vec<ClaudeCodeChangelogEntry> = Vec::new();
+    for line in markdown.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            let version = heading
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .trim_matches(|c| c == '[' || c == ']')
+                .trim_start_matches('v');
+            entries.push(ClaudeCodeChangelogEntry {
+                version: version.to_string(),
+                notes: String::new(),
+            });
+        } else if let Some(entry) = entries.last_mut() {
+            entry.notes.push_str(line);
+            entry.notes.push('\n');
+        }
+    }
+    for entry in &mut entries {
+        entry.notes = entry.notes.trim().to_string();
+    }
+    entries
+}
+
+/// Entries for `version` ("latest" = newest section), or every version after `since`
+/// up to and including `version`
+fn select_changelog_entries(
+    entries: Vec<ClaudeCodeChangelogEntry>,
+    version: &str,
+    since: Option<&str>,
+) -> Vec<ClaudeCodeChangelogEntry> {
+    let upper = if version == "latest" {
+        match entries.first() {
+            Some(entry) => entry.version.clone(),
+            None => return entries,
+        }
+    } else {
+        version.to_string()
+    };
+    entries
+        .into_iter()
+        .filter(|entry| match since {
+            Some(since) => {
+                compare_versions(&entry.version, since) == Ordering::Greater
+                    && compare_versions(&entry.version, &upper) != Ordering::Greater
+            }
+            None => entry.version == upper,
+        })
+        .collect()
+}
+
+/// CHANGELOG text, from the cache when it is recent enough to know `version`.
+/// Falls back to a stale cache when GitHub can't be reached.
+async fn changelog_text(version: &str) -> Result<String, String> {
+    let path = changelog_cache_path();
+    let cached = read_cached_changelog(&path);
+    if let Some((text, age)) = &cached {
+        let knows_version = version == "latest"
+            || parse_changelog(text).iter().any(|e| e.version == version);
+        if *age < CHANGELOG_MIN_REFETCH || (*age < CHANGELOG_TTL && knows_version) {
+            return Ok(text.clone());
+        }
+    }
+
+    let fetched = async {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        client
+            .get(CHANGELOG_URL)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| format!("Failed to fetch changelog: {}", e))?
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read changelog: {}", e))
+    }
+    .await;
+
+    match fetched {
+        Ok(text) => {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Err(e) = security::atomic_write_string(&path, &text) {
+                tracing::warn!(error = %e, "Failed to cache Claude Code changelog");
+            }
+            Ok(text)
+        }
+        Err(e) => match cached {
+            Some((text, _)) => {
+                tracing::warn!(error = %e, "Using stale Claude Code changelog");
+                Ok(text)
+            }
+            None => Err(e),
+        },
+    }
+}
+
 /// Run a command in user's interactive login shell (to get proper PATH with nvm, etc.)
-fn run_shell_command(cmd: &str) -> std::io::Result<std::process::Output> {
+pub fn run_shell_command(cmd: &str) -> std::io::Result<std::process::Output> {
     // Use user's default shell from $SHELL, fallback to /bin/zsh (macOS default)
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
     std::process::Command::new(&shell)
@@ -108,7 +402,7 @@ fn detect_claude_code_install_type() -> (ClaudeCodeInstallType, Option<String>)
 // ============================================================================
 
 #[tauri::command]
-pub async fn get_claude_code_version_info() -> Result<ClaudeCodeVersionInfo, String> {
+pub async fn get_claude_code_version_info() -> AppResult<ClaudeCodeVersionInfo> {
     // Detect installation type and current version
     let (install_type, current_version) = tauri::async_runtime::spawn_blocking(detect_claude_code_install_type)
         .await
@@ -122,7 +416,7 @@ pub async fn get_claude_code_version_info() -> Result<ClaudeCodeVersionInfo, Str
 
     // Get versions list from npm registry
     let versions: Vec<String> = match client
-        .get("https://registry.npmjs.org/@anthropic-ai/claude-code")
+        .get(NPM_PACKAGE_URL)
         .send()
         .await
     {
@@ -133,13 +427,7 @@ pub async fn get_claude_code_version_info() -> Result<ClaudeCodeVersionInfo, Str
             .and_then(|json| {
                 json.get("versions")?.as_object().map(|obj| {
                     let mut versions: Vec<String> = obj.keys().cloned().collect();
-                    // Sort by semver (simple string sort works for most cases)
-                    versions.sort_by(|a, b| {
-                        let parse = |s: &str| -> Vec<u32> {
-                            s.split('.').filter_map(|p| p.parse().ok()).collect()
-                        };
-                        parse(b).cmp(&parse(a))
-                    });
+                    versions.sort_by(|a, b| compare_versions(b, a));
                     versions.into_iter().take(20).collect()
                 })
             })
@@ -177,74 +465,87 @@ pub async fn get_claude_code_version_info() -> Result<ClaudeCodeVersionInfo, Str
         })
         .collect();
 
-    // Check autoupdater setting
-    let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
-    let autoupdater_disabled = fs::read_to_string(&settings_path)
-        .ok()
-        .and_then(|content| {
-            let json: serde_json::Value = serde_json::from_str(&content).ok()?;
-            json.get("env")?
-                .get("DISABLE_AUTOUPDATER")?
-                .as_str()
-                .map(|s| s == "true" || s == "1")
-        })
-        .unwrap_or(false);
-
     Ok(ClaudeCodeVersionInfo {
         install_type,
         current_version,
         available_versions,
-        autoupdater_disabled,
+        autoupdater_disabled: autoupdater_disabled_in(&claude_settings_path()),
+        pinned: load_pin(&pin_path()),
     })
 }
 
+/// Published versions from the npm registry, newest first, including pre-releases,
+/// with dist-tags and publish dates
 #[tauri::command]
-pub async fn install_claude_code_version(version: String, install_type: Option<String>) -> Result<String, String> {
+pub async fn list_claude_code_versions(limit: Option<usize>) -> AppResult<ClaudeCodeVersionList> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let packument: serde_json::Value = client
+        .get(NPM_PACKAGE_URL)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| {
+            AppError::new(ErrorCode::Network, format!("Failed to fetch npm registry: {}", e))
+        })?
+        .json()
+        .await
+        .map_err(|e| {
+            AppError::new(
+                ErrorCode::Parse,
+                format!("Failed to parse npm registry response: {}", e),
+            )
+        })?;
+    Ok(parse_registry_versions(
+        &packument,
+        limit.unwrap_or(DEFAULT_VERSION_LIST_LIMIT),
+    ))
+}
+
+/// Release notes for `version` ("latest" or semver), read from the Claude Code
+/// CHANGELOG on GitHub and cached locally. With `since` (e.g. the installed version),
+/// returns every newer entry up to `version`, newest first.
+#[tauri::command]
+pub async fn get_claude_code_changelog(
+    version: String,
+    since: Option<String>,
+) -> AppResult<Vec<ClaudeCodeChangelogEntry>> {
+    validate_version(&version)?;
+    if let Some(since) = &since {
+        validate_version(since)?;
+    }
+    // Only fetching from GitHub can fail (with no cached copy to fall back on)
+    let text = changelog_text(&version)
+        .await
+        .map_err(|e| AppError::new(ErrorCode::Network, e))?;
+    Ok(select_changelog_entries(
+        parse_changelog(&text),
+        &version,
+        since.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub async fn install_claude_code_version(version: String, install_type: Option<String>) -> AppResult<String> {
     // ========================================================================
     // 安全验证: 防止命令注入
     // ========================================================================
-    validate_version(&version).map_err(|e| e.to_string())?;
+    validate_version(&version)?;
 
     let is_specific_version = version != "latest";
     let install_type_str = install_type.unwrap_or_else(|| "native".to_string());
 
     // 验证 install_type 只能是允许的值
     if install_type_str != "native" && install_type_str != "npm" {
-        return Err(format!("无效的安装类型: {}", install_type_str));
+        return Err(AppError::invalid_input(format!("无效的安装类型: {}", install_type_str)));
     }
 
-    let result = tauri::async_runtime::spawn_blocking(move || {
-        let cmd = if install_type_str == "npm" {
-            // NPM installation (--force to overwrite existing native install)
-            // version 已经通过 validate_version 验证，安全
-            let package = if version == "latest" {
-                "@anthropic-ai/claude-code@latest".to_string()
-            } else {
-                format!("@anthropic-ai/claude-code@{}", version)
-            };
-            format!("npm install -g --force {}", package)
-        } else {
-            // Native installation (default)
-            // version 已经通过 validate_version 验证，安全
-            let version_arg = if version == "latest" { "".to_string() } else { version };
-            format!("curl -fsSL https://claude.ai/install.sh | bash -s {}", version_arg)
-        };
-
-        // Use user's interactive login shell to get proper PATH (nvm, etc.)
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-        let output = std::process::Command::new(&shell)
-            .args(["-ilc", &cmd])
-            .output()
-            .map_err(|e| format!("Failed to run install command: {}", e))?;
-
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
-        }
-    })
-    .await
-    .map_err(|e| e.to_string())??;
+    let result =
+        tauri::async_runtime::spawn_blocking(move || run_install(&version, &install_type_str))
+            .await
+            .map_err(|e| e.to_string())??;
 
     // Auto-disable autoupdater when installing a specific version
     if is_specific_version {
@@ -254,13 +555,67 @@ pub async fn install_claude_code_version(version: String, install_type: Option<S
     Ok(result)
 }
 
+/// Install a specific (usually older) version, turn the autoupdater off and record the
+/// pin, e.g. when a new release breaks a workflow. Uses the current install method.
 #[tauri::command]
-pub fn set_claude_code_autoupdater(disabled: bool) -> Result<(), String> {
-    let settings_path = security::get_claude_dir_or_fallback().join("settings.json");
+pub async fn pin_claude_code_version(version: String) -> AppResult<ClaudeCodePin> {
+    validate_version(&version)?;
+    if version == "latest" {
+        return Err(AppError::invalid_input("Pin a specific version, not \"latest\""));
+    }
+
+    // Re-pinning keeps the setting from before the first pin
+    let autoupdater_was_disabled = load_pin(&pin_path()).map_or_else(
+        || autoupdater_disabled_in(&claude_settings_path()),
+        |pin| pin.autoupdater_was_disabled,
+    );
+    let pin = tauri::async_runtime::spawn_blocking(move || {
+        let install_type = match detect_claude_code_install_type().0 {
+            ClaudeCodeInstallType::Npm => ClaudeCodeInstallType::Npm,
+            ClaudeCodeInstallType::Native | ClaudeCodeInstallType::None => {
+                ClaudeCodeInstallType::Native
+            }
+        };
+        let method = if install_type == ClaudeCodeInstallType::Npm { "npm" } else { "native" };
+        run_install(&version, method)?;
+        Ok::<_, String>(ClaudeCodePin {
+            version,
+            install_type,
+            pinned_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            autoupdater_was_disabled,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // Keep the CLI from updating itself past the pin
+    set_claude_code_autoupdater(true)?;
+    save_pin(&pin_path(), &pin).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    Ok(pin)
+}
+
+/// Forget the pin and put the autoupdater setting back to what it was before pinning;
+/// the installed version stays until the next update
+#[tauri::command]
+pub fn unpin_claude_code_version() -> AppResult<()> {
+    let path = pin_path();
+    let Some(pin) = load_pin(&path) else {
+        return Ok(());
+    };
+    fs::remove_file(&path).map_err(|e| AppError::io("Failed to remove pin", &e))?;
+    set_claude_code_autoupdater(pin.autoupdater_was_disabled)
+}
+
+#[tauri::command]
+pub fn set_claude_code_autoupdater(disabled: bool) -> AppResult<()> {
+    let settings_path = claude_settings_path();
 
     // Read existing settings or create empty object
     let mut settings: serde_json::Value = if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&settings_path)
+            .map_err(|e| AppError::io("Failed to read file", &e))?;
         serde_json::from_str(&content).unwrap_or(serde_json::json!({}))
     } else {
         serde_json::json!({})
@@ -278,7 +633,101 @@ pub fn set_claude_code_autoupdater(disabled: bool) -> Result<(), String> {
 
     // Write back (atomic)
     let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    security::atomic_write_string(&settings_path, &content).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&settings_path, &content)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("2.0.10", "2.0.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0-beta.1", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0-beta.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2.3", "1.2.3"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_pin_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("lovcode").join("claude_code_pin.json");
+        assert_eq!(load_pin(&path), None);
+
+        let pin = ClaudeCodePin {
+            version: "2.0.50".to_string(),
+            install_type: ClaudeCodeInstallType::Npm,
+            pinned_at: 1_750_000_000,
+            autoupdater_was_disabled: true,
+        };
+        save_pin(&path, &pin).expect("save pin");
+        assert_eq!(load_pin(&path), Some(pin));
+        let raw = fs::read_to_string(&path).expect("read pin");
+        assert!(raw.contains("\"install_type\": \"npm\""));
+
+        // Pins saved before the setting was recorded restore auto-updates
+        fs::write(
+            &path,
+            r#"{"version":"2.0.50","install_type":"native","pinned_at":1}"#,
+        )
+        .expect("write old pin");
+        assert_eq!(load_pin(&path).map(|p| p.autoupdater_was_disabled), Some(false));
+
+        let settings = temp.path().join("settings.json");
+        assert!(!autoupdater_disabled_in(&settings));
+        fs::write(&settings, r#"{"env":{"DISABLE_AUTOUPDATER":"1"}}"#).expect("write settings");
+        assert!(autoupdater_disabled_in(&settings));
+    }
+
+    #[test]
+    fn test_parse_registry_versions() {
+        let packument = serde_json::json!({
+            "dist-tags": { "latest": "2.0.1", "next": "2.1.0-beta.1", "stable": "2.0.1" },
+            "versions": { "2.0.0": {}, "2.0.1": {}, "2.1.0-beta.1": {}, "1.9.9": {} },
+            "time": {
+                "created": "2025-01-01T00:00:00.000Z",
+                "1.9.9": "2025-05-01T00:00:00.000Z",
+                "2.0.0": "2025-06-01T00:00:00.000Z",
+                "2.1.0-beta.1": "2025-06-10T00:00:00.000Z",
+                "2.0.1": "2025-06-05T00:00:00.000Z"
+            }
+        });
+        let list = parse_registry_versions(&packument, 3);
+        assert_eq!(list.dist_tags.get("next").map(String::as_str), Some("2.1.0-beta.1"));
+
+        let versions: Vec<&str> = list.versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.1.0-beta.1", "2.0.1", "2.0.0"]);
+        assert!(list.versions[0].prerelease);
+        assert_eq!(list.versions[0].tags, vec!["next"]);
+        assert_eq!(list.versions[1].tags, vec!["latest", "stable"]);
+        assert_eq!(
+            list.versions[1].published_at.as_deref(),
+            Some("2025-06-05T00:00:00.000Z")
+        );
+    }
+
+    #[test]
+    fn test_parse_and_select_changelog() {
+        let markdown = "# Changelog\n\n## 2.0.3\n\n- Faster startup\n- Fix: resume picker\n\n\
+                        ## 2.0.2\n\n- Add /context\n\n## 2.0.1\n\n- Bugfixes\n";
+        let entries = parse_changelog(markdown);
+        let versions: Vec<&str> = entries.iter().map(|e| e.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.3", "2.0.2", "2.0.1"]);
+        assert_eq!(entries[0].notes, "- Faster startup\n- Fix: resume picker");
+
+        let one = select_changelog_entries(entries.clone(), "2.0.2", None);
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].notes, "- Add /context");
+
+        let latest = select_changelog_entries(entries.clone(), "latest", None);
+        assert_eq!(latest[0].version, "2.0.3");
+
+        let upgrade = select_changelog_entries(entries.clone(), "latest", Some("2.0.1"));
+        let versions: Vec<&str> = upgrade.iter().map(|e| e.version.as_str()).collect();
+        assert_eq!(versions, vec!["2.0.3", "2.0.2"]);
+
+        assert!(select_changelog_entries(entries, "9.9.9", None).is_empty());
+    }
+}
@@ -1,44 +1,87 @@
 /**
- * [INPUT]: 依赖 workspace_store 模块的工作区数据管理功能
+ * [INPUT]: 依赖 workspace_store 模块的工作区数据管理功能, crate::security (导入导出路径校验), pty_manager (面板环境变量校验), services::checkpoint (功能启动时自动快照), services::notifications (待审核通知), services::webhooks (状态变更推送), commands::git (功能分支创建/合并)
  * [OUTPUT]: 对外提供工作区相关的 Tauri 命令
  * [POS]: commands/ 模块成员，处理项目、功能、面板的 CRUD 操作
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
+use crate::commands::git::{self, MergeStrategy};
+use crate::errors::{AppError, AppResult};
+use crate::pty_manager;
+use crate::security;
+use crate::services::checkpoint;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::webhooks::{self, WebhookEvent};
 use crate::workspace_store::{
-    self, Feature, FeatureStatus, PanelState, WorkspaceData, WorkspaceProject,
+    self, Feature, FeatureStatus, PanelState, TrashEntry, WorkspaceData, WorkspaceImportSummary,
+    WorkspaceProfile, WorkspaceProject,
 };
+use std::collections::BTreeMap;
+use std::path::Path;
 
 // ============================================================================
 // Workspace Commands
 // ============================================================================
 
 #[tauri::command]
-pub fn workspace_load() -> Result<WorkspaceData, String> {
+pub fn workspace_load() -> AppResult<WorkspaceData> {
     workspace_store::load_workspace()
 }
 
+/// Save the whole workspace; returns the new generation to send with the next save
 #[tauri::command]
-pub fn workspace_save(data: WorkspaceData) -> Result<(), String> {
+pub fn workspace_save(data: WorkspaceData) -> AppResult<u64> {
     workspace_store::save_workspace(&data)
 }
 
+/// Named workspaces, e.g. separate boards for work and personal projects
 #[tauri::command]
-pub fn workspace_add_project(path: String) -> Result<WorkspaceProject, String> {
+pub fn workspace_list() -> AppResult<Vec<WorkspaceProfile>> {
+    workspace_store::list_workspaces()
+}
+
+/// Switch to (or create) the named workspace and return its data
+#[tauri::command]
+pub fn workspace_switch(name: String) -> AppResult<WorkspaceData> {
+    workspace_store::switch_workspace(&name)
+}
+
+#[tauri::command]
+pub fn workspace_delete(name: String) -> AppResult<()> {
+    workspace_store::delete_workspace(&name)
+}
+
+/// Write the active workspace to `path` as workspace.json-formatted JSON
+#[tauri::command]
+pub fn workspace_export(path: String) -> AppResult<()> {
+    security::validate_decoded_path(&path)?;
+    workspace_store::export_workspace(Path::new(&path))
+}
+
+/// Import an exported workspace. With `merge`, projects (by path) and features (by id)
+/// missing from the active workspace are added; otherwise the active workspace is replaced.
+#[tauri::command]
+pub fn workspace_import(path: String, merge: bool) -> AppResult<WorkspaceImportSummary> {
+    security::validate_decoded_path(&path)?;
+    workspace_store::import_workspace(Path::new(&path), merge)
+}
+
+#[tauri::command]
+pub fn workspace_add_project(path: String) -> AppResult<WorkspaceProject> {
     workspace_store::add_project(path)
 }
 
 #[tauri::command]
-pub fn workspace_list_projects() -> Result<Vec<WorkspaceProject>, String> {
+pub fn workspace_list_projects() -> AppResult<Vec<WorkspaceProject>> {
     workspace_store::load_workspace().map(|d| d.projects)
 }
 
 #[tauri::command]
-pub fn workspace_remove_project(id: String) -> Result<(), String> {
+pub fn workspace_remove_project(id: String) -> AppResult<()> {
     workspace_store::remove_project(&id)
 }
 
 #[tauri::command]
-pub fn workspace_set_active_project(id: String) -> Result<(), String> {
+pub fn workspace_set_active_project(id: String) -> AppResult<()> {
     workspace_store::set_active_project(&id)
 }
 
@@ -47,12 +90,16 @@ pub fn workspace_create_feature(
     project_id: String,
     name: String,
     description: Option<String>,
-) -> Result<Feature, String> {
-    workspace_store::create_feature(&project_id, name, description)
+) -> AppResult<Feature> {
+    workspace_store::create_feature(
+        &project_id,
+        name,
+        description,
+    )
 }
 
 #[tauri::command]
-pub fn workspace_rename_feature(feature_id: String, name: String) -> Result<(), String> {
+pub fn workspace_rename_feature(feature_id: String, name: String) -> AppResult<()> {
     workspace_store::rename_feature(&feature_id, name)
 }
 
@@ -61,18 +108,265 @@ pub fn workspace_update_feature_status(
     project_id: String,
     feature_id: String,
     status: FeatureStatus,
-) -> Result<(), String> {
-    workspace_store::update_feature_status(&project_id, &feature_id, status)
+) -> AppResult<()> {
+    if status == FeatureStatus::Running {
+        auto_checkpoint_feature(&project_id, &feature_id);
+    }
+    let change = status_change(&feature_id, &status);
+    workspace_store::update_feature_status(&project_id, &feature_id, status)?;
+    announce_status_change(change);
+    Ok(())
+}
+
+/// A feature moving from one status to another, looked up before the change is saved
+struct StatusChange {
+    project_id: String,
+    project_name: String,
+    feature_id: String,
+    feature_name: String,
+    from: FeatureStatus,
+    to: FeatureStatus,
+}
+
+/// None when the feature doesn't exist or already has this status
+fn status_change(feature_id: &str, status: &FeatureStatus) -> Option<StatusChange> {
+    let data = workspace_store::load_workspace().ok()?;
+    data.projects.iter().find_map(|project| {
+        project
+            .features
+            .iter()
+            .find(|f| f.id == feature_id && f.status != *status)
+            .map(|f| StatusChange {
+                project_id: project.id.clone(),
+                project_name: project.name.clone(),
+                feature_id: f.id.clone(),
+                feature_name: f.name.clone(),
+                from: f.status.clone(),
+                to: status.clone(),
+            })
+    })
+}
+
+/// Notify when a feature moves into review, and send the change to webhooks
+fn announce_status_change(change: Option<StatusChange>) {
+    let Some(change) = change else {
+        return;
+    };
+    let label = format!("{}: {}", change.project_name, change.feature_name);
+    if change.to == FeatureStatus::NeedsReview {
+        notifications::notify(
+            "Feature needs review",
+            &label,
+            NotificationKind::FeatureReview,
+        );
+    }
+    let status = |s: &FeatureStatus| serde_json::to_value(s).unwrap_or_default();
+    webhooks::emit(
+        WebhookEvent::FeatureStatusChanged,
+        "Feature status changed",
+        &format!(
+            "{} ({} → {})",
+            label,
+            status(&change.from).as_str().unwrap_or_default(),
+            status(&change.to).as_str().unwrap_or_default()
+        ),
+        serde_json::json!({
+            "project_id": change.project_id,
+            "project_name": change.project_name,
+            "feature_id": change.feature_id,
+            "feature_name": change.feature_name,
+            "from": change.from,
+            "to": change.to,
+        }),
+    );
+}
+
+/// Checkpoint the project before a feature with auto_checkpoint starts running.
+/// Failures (e.g. not a git repository) are logged and never block the status change.
+fn auto_checkpoint_feature(project_id: &str, feature_id: &str) {
+    let Ok(data) = workspace_store::load_workspace() else {
+        return;
+    };
+    let Some(project) = data.projects.iter().find(|p| p.id == project_id) else {
+        return;
+    };
+    let Some(feature) = project.features.iter().find(|f| f.id == feature_id) else {
+        return;
+    };
+    if !feature.auto_checkpoint.unwrap_or(false) || feature.status == FeatureStatus::Running {
+        return;
+    }
+
+    let path = feature.worktree_path.as_deref().unwrap_or(&project.path);
+    let label = format!("Start: {}", feature.name);
+    if let Err(e) = checkpoint::checkpoint_project(path, &label, Some(feature_id.to_string())) {
+        tracing::warn!(feature_id = %feature_id, error = %e, "Auto checkpoint failed");
+    }
+}
+
+#[tauri::command]
+pub fn workspace_reorder_features(project_id: String, ordered_ids: Vec<String>) -> AppResult<()> {
+    workspace_store::reorder_features(&project_id, ordered_ids)
+}
+
+#[tauri::command]
+pub fn workspace_move_feature_status(
+    feature_id: String,
+    status: FeatureStatus,
+    position: usize,
+) -> AppResult<()> {
+    if status == FeatureStatus::Running {
+        let project_id = workspace_store::load_workspace()?
+            .projects
+            .into_iter()
+            .find(|p| p.features.iter().any(|f| f.id == feature_id))
+            .map(|p| p.id);
+        if let Some(project_id) = project_id {
+            auto_checkpoint_feature(&project_id, &feature_id);
+        }
+    }
+    let change = status_change(&feature_id, &status);
+    workspace_store::move_feature_status(&feature_id, status, position)?;
+    announce_status_change(change);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn workspace_link_feature_session(feature_id: String, session_id: String) -> AppResult<()> {
+    workspace_store::link_feature_session(
+        &feature_id,
+        &session_id,
+    )
+}
+
+#[tauri::command]
+pub fn workspace_unlink_feature_session(feature_id: String, session_id: String) -> AppResult<()> {
+    workspace_store::unlink_feature_session(
+        &feature_id,
+        &session_id,
+    )
+}
+
+/// Create and check out a branch for a feature (in its worktree if it has one)
+#[tauri::command]
+pub fn workspace_start_feature_branch(
+    project_id: String,
+    feature_id: String,
+    name: String,
+) -> AppResult<()> {
+    let data = workspace_store::load_workspace()?;
+    let project = data
+        .projects
+        .iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
+    let feature = project
+        .features
+        .iter()
+        .find(|f| f.id == feature_id)
+        .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
+
+    let path = feature.worktree_path.as_deref().unwrap_or(&project.path);
+    let base_branch = git::current_branch(path).filter(|b| *b != name);
+    git::start_branch(path, &name)?;
+    workspace_store::set_feature_branch(
+        &feature_id,
+        name,
+        base_branch,
+    )
+}
+
+/// Mark a feature completed, first merging its branch into the base branch when a
+/// strategy is given and optionally deleting the branch afterwards
+#[tauri::command]
+pub fn workspace_complete_feature(
+    feature_id: String,
+    merge_strategy: Option<MergeStrategy>,
+    delete_branch: Option<bool>,
+) -> AppResult<()> {
+    let data = workspace_store::load_workspace()?;
+    let (project, feature) = data
+        .projects
+        .iter()
+        .find_map(|p| {
+            p.features
+                .iter()
+                .find(|f| f.id == feature_id)
+                .map(|f| (p, f))
+        })
+        .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
+
+    if let Some(strategy) = merge_strategy {
+        let branch = feature
+            .git_branch
+            .as_deref()
+            .ok_or_else(|| {
+                AppError::invalid_input(format!("Feature '{}' has no branch", feature.name))
+            })?;
+        let base = feature.base_branch.as_deref().ok_or_else(|| {
+            AppError::invalid_input(format!(
+                "Feature '{}' has no base branch to merge into",
+                feature.name
+            ))
+        })?;
+        git::merge_branch(&project.path, branch, base, strategy, &feature.name)?;
+
+        if delete_branch.unwrap_or(false) {
+            // Squashed commits are not ancestors of the base branch, so -d would refuse
+            let force = strategy == MergeStrategy::Squash;
+            git::git_delete_branch(project.path.clone(), branch.to_string(), Some(force))?;
+        }
+    }
+
+    let change = status_change(&feature_id, &FeatureStatus::Completed);
+    workspace_store::update_feature_status(&project.id, &feature_id, FeatureStatus::Completed)?;
+    announce_status_change(change);
+    Ok(())
 }
 
 #[tauri::command]
-pub fn workspace_delete_feature(project_id: String, feature_id: String) -> Result<(), String> {
+pub fn workspace_delete_feature(project_id: String, feature_id: String) -> AppResult<()> {
     workspace_store::delete_feature(&project_id, &feature_id)
 }
 
+/// Projects and features deleted from the active workspace, most recent first
 #[tauri::command]
-pub fn workspace_set_active_feature(project_id: String, feature_id: String) -> Result<(), String> {
-    workspace_store::set_active_feature(&project_id, &feature_id)
+pub fn workspace_list_trash() -> AppResult<Vec<TrashEntry>> {
+    workspace_store::list_trash()
+}
+
+/// Restore the most recently deleted project or feature
+#[tauri::command]
+pub fn workspace_undo_last_change() -> AppResult<TrashEntry> {
+    workspace_store::undo_last_change()
+}
+
+#[tauri::command]
+pub fn workspace_restore_deleted_feature(feature_id: String) -> AppResult<TrashEntry> {
+    workspace_store::restore_deleted_feature(&feature_id)
+}
+
+#[tauri::command]
+pub fn workspace_set_active_feature(project_id: String, feature_id: String) -> AppResult<()> {
+    workspace_store::set_active_feature(
+        &project_id,
+        &feature_id,
+    )
+}
+
+#[tauri::command]
+pub fn workspace_set_feature_worktree(
+    project_id: String,
+    feature_id: String,
+    worktree_path: Option<String>,
+    git_branch: Option<String>,
+) -> AppResult<()> {
+    workspace_store::set_feature_worktree(
+        &project_id,
+        &feature_id,
+        worktree_path,
+        git_branch,
+    )
 }
 
 #[tauri::command]
@@ -80,8 +374,12 @@ pub fn workspace_add_panel(
     project_id: String,
     feature_id: String,
     panel: PanelState,
-) -> Result<(), String> {
-    workspace_store::add_panel_to_feature(&project_id, &feature_id, panel)
+) -> AppResult<()> {
+    workspace_store::add_panel_to_feature(
+        &project_id,
+        &feature_id,
+        panel,
+    )
 }
 
 #[tauri::command]
@@ -89,16 +387,52 @@ pub fn workspace_remove_panel(
     project_id: String,
     feature_id: String,
     panel_id: String,
-) -> Result<(), String> {
-    workspace_store::remove_panel_from_feature(&project_id, &feature_id, &panel_id)
+) -> AppResult<()> {
+    workspace_store::remove_panel_from_feature(
+        &project_id,
+        &feature_id,
+        &panel_id,
+    )
 }
 
+/// Replace a panel's environment variables; terminals started in the panel afterwards
+/// get them merged into their environment
 #[tauri::command]
-pub fn workspace_toggle_panel_shared(project_id: String, panel_id: String) -> Result<bool, String> {
-    workspace_store::toggle_panel_shared(&project_id, &panel_id)
+pub fn workspace_set_panel_env(
+    project_id: String,
+    panel_id: String,
+    env: BTreeMap<String, String>,
+) -> AppResult<()> {
+    pty_manager::validate_env(&env).map_err(AppError::invalid_input)?;
+    workspace_store::set_panel_env(&project_id, &panel_id, env)
+}
+
+#[tauri::command]
+pub fn workspace_toggle_panel_shared(project_id: String, panel_id: String) -> AppResult<bool> {
+    workspace_store::toggle_panel_shared(
+        &project_id,
+        &panel_id,
+    )
+}
+
+/// Persist the ratio of a resized split; `node_path` walks from the root layout node
+/// (0 = first child, 1 = second child)
+#[tauri::command]
+pub fn workspace_update_layout_ratio(
+    project_id: String,
+    feature_id: String,
+    node_path: Vec<u8>,
+    ratio: f64,
+) -> AppResult<()> {
+    workspace_store::update_layout_ratio(
+        &project_id,
+        &feature_id,
+        &node_path,
+        ratio,
+    )
 }
 
 #[tauri::command]
-pub fn workspace_get_pending_reviews() -> Result<Vec<(String, String, String)>, String> {
+pub fn workspace_get_pending_reviews() -> AppResult<Vec<(String, String, String)>> {
     workspace_store::get_pending_reviews()
 }
@@ -1,12 +1,32 @@
 /**
- * [INPUT]: 依赖 thiserror 的 Error derive
- * [OUTPUT]: 对外提供 AppError 统一错误类型、Result 别名
- * [POS]: src-tauri/src 的错误处理核心，被所有模块消费
+ * [INPUT]: 依赖 thiserror 的 Error derive, serde
+ * [OUTPUT]: 对外提供 AppError 统一错误类型、ErrorCode 错误分类、Result 别名
+ * [POS]: src-tauri/src 的错误处理核心，被所有模块消费；所有 Tauri 命令返回 AppResult，前端收到 { code, message, context }
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+use serde::Serialize;
 use thiserror::Error;
 
+// ============================================================================
+// 错误分类 (前端据此分支)
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NotFound,
+    PermissionDenied,
+    InvalidInput,
+    Parse,
+    Io,
+    Network,
+    Timeout,
+    Conflict,
+    Unavailable,
+    Internal,
+}
+
 // ============================================================================
 // 统一错误类型
 // ============================================================================
@@ -41,7 +61,10 @@ pub enum AppError {
     ProjectNotFound { path: String },
 
     #[error("会话不存在: {project_id}/{session_id}")]
-    SessionNotFound { project_id: String, session_id: String },
+    SessionNotFound {
+        project_id: String,
+        session_id: String,
+    },
 
     #[error("文件不存在: {path}")]
     FileNotFound { path: String },
@@ -54,6 +77,95 @@ pub enum AppError {
 
     #[error("操作失败: {0}")]
     Operation(String),
+
+    // --------------------------------------------------------------------
+    // 命令错误: 消息原样透传，附带分类与上下文 (通常是路径或 id)
+    // --------------------------------------------------------------------
+    #[error("{message}")]
+    Coded {
+        code: ErrorCode,
+        message: String,
+        context: Option<String>,
+    },
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Coded {
+            code,
+            message: message.into(),
+            context: None,
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    /// `"{action}: {err}"`, classified by the io error kind
+    pub fn io(action: &str, err: &std::io::Error) -> Self {
+        Self::new(io_error_code(err), format!("{}: {}", action, err))
+    }
+
+    /// Attach what the error is about, e.g. the path or id the command was given
+    pub fn with_context(self, context: impl Into<String>) -> Self {
+        Self::Coded {
+            code: self.code(),
+            message: match self {
+                Self::Coded { message, .. } => message,
+                other => other.to_string(),
+            },
+            context: Some(context.into()),
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::HomeDirNotFound
+            | Self::ProjectNotFound { .. }
+            | Self::SessionNotFound { .. }
+            | Self::FileNotFound { .. } => ErrorCode::NotFound,
+            Self::Io(e) => io_error_code(e),
+            Self::Json(_) => ErrorCode::Parse,
+            Self::PathTraversal { .. } => ErrorCode::PermissionDenied,
+            Self::InvalidVersion { .. } => ErrorCode::InvalidInput,
+            Self::FileWrite { .. } => ErrorCode::Io,
+            Self::SearchIndexNotReady => ErrorCode::Unavailable,
+            Self::Operation(_) => ErrorCode::Internal,
+            Self::Coded { code, .. } => *code,
+        }
+    }
+
+    pub fn context(&self) -> Option<String> {
+        match self {
+            Self::ProjectNotFound { path }
+            | Self::FileNotFound { path }
+            | Self::FileWrite { path, .. } => Some(path.clone()),
+            Self::SessionNotFound {
+                project_id,
+                session_id,
+            } => Some(format!("{}/{}", project_id, session_id)),
+            Self::InvalidVersion { version } => Some(version.clone()),
+            Self::Coded { context, .. } => context.clone(),
+            _ => None,
+        }
+    }
+}
+
+fn io_error_code(err: &std::io::Error) -> ErrorCode {
+    use std::io::ErrorKind;
+    match err.kind() {
+        ErrorKind::NotFound => ErrorCode::NotFound,
+        ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+        ErrorKind::AlreadyExists => ErrorCode::Conflict,
+        ErrorKind::TimedOut => ErrorCode::Timeout,
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => ErrorCode::InvalidInput,
+        _ => ErrorCode::Io,
+    }
 }
 
 // ============================================================================
@@ -72,12 +184,83 @@ impl From<AppError> for String {
     }
 }
 
-// 允许 AppError 作为 Tauri command 的返回类型
-impl serde::Serialize for AppError {
+// 允许 `?` 把 Result<_, String> 带入返回 AppResult 的命令；
+// 消息不带类别，一律为 internal，能确定类别的调用点须显式给出
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+// 作为 Tauri command 的错误返回: { code, message, context }
+impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_shape() {
+        let err = AppError::FileNotFound {
+            path: "/tmp/a.md".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&err).expect("serialize"),
+            serde_json::json!({
+                "code": "not_found",
+                "message": "文件不存在: /tmp/a.md",
+                "context": "/tmp/a.md"
+            })
+        );
+
+        let err = AppError::invalid_input("Path must be absolute").with_context("bin/claude");
+        assert_eq!(
+            serde_json::to_value(&err).expect("serialize"),
+            serde_json::json!({
+                "code": "invalid_input",
+                "message": "Path must be absolute",
+                "context": "bin/claude"
+            })
+        );
+    }
+
+    #[test]
+    fn test_plain_messages_are_internal() {
+        // No guessing from the wording: call sites pick the code explicitly
+        let err = AppError::from("Session not found: abc");
+        assert_eq!(err.code(), ErrorCode::Internal);
+        assert_eq!(err.to_string(), "Session not found: abc");
+        assert_eq!(
+            AppError::from(format!("Invalid path: {}", "/x")).code(),
+            ErrorCode::Internal
+        );
+    }
+
+    #[test]
+    fn test_io_error_codes() {
+        let err = AppError::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert_eq!(err.code(), ErrorCode::PermissionDenied);
+        assert_eq!(
+            err.with_context("/etc/x").context().as_deref(),
+            Some("/etc/x")
+        );
     }
 }
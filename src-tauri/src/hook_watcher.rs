@@ -2,6 +2,7 @@ use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::collections::HashSet;
 use tauri::{AppHandle, Emitter};
+use crate::services::webhooks::{self, WebhookEvent};
 
 /// Tracks which features are currently being monitored for completion
 static MONITORED_FEATURES: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| {
@@ -63,6 +64,17 @@ pub fn notify_feature_complete(
     if let Err(e) = app_handle.emit("feature-complete", event) {
         tracing::error!(error = %e, "Failed to emit feature-complete event");
     }
+
+    webhooks::emit(
+        WebhookEvent::SessionFinished,
+        "Claude finished",
+        feature_name,
+        serde_json::json!({
+            "project_id": project_id,
+            "feature_id": feature_id,
+            "feature_name": feature_name,
+        }),
+    );
 }
 
 /// Get list of currently monitored features
@@ -0,0 +1,181 @@
+/**
+ * [INPUT]: 依赖 std::io (BufReader), serde (逐行反序列化)
+ * [OUTPUT]: 对外提供 open / from_reader 流式行迭代器 JsonlLines (records 逐行解析), MAX_LINE_BYTES
+ * [POS]: src-tauri/src 的 JSONL 流式解析基础设施，避免把数百 MB 的会话文件整体读入内存；被 sessions / chat_cache / search / report 共用
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Lines longer than this are skipped instead of buffered. Claude Code logs whole tool
+/// results (pasted images included) on one line, so the limit is generous.
+pub const MAX_LINE_BYTES: usize = 32 * 1024 * 1024;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Lines of a JSONL stream with their 1-based line numbers.
+///
+/// - A line over the length limit is skipped but still counted, so later line numbers
+///   match the file.
+/// - Invalid UTF-8 is replaced rather than ending the stream.
+/// - An unterminated last line (a session still being written) is yielded as-is; it
+///   usually fails to parse, which `records` skips.
+/// - A read error ends the stream after logging it.
+pub struct JsonlLines<R> {
+    reader: R,
+    max_line_bytes: usize,
+    line_number: usize,
+    done: bool,
+}
+
+enum LineRead {
+    Line(Vec<u8>),
+    Oversized,
+    End,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Stream the lines of a JSONL file
+pub fn open(path: &Path) -> io::Result<JsonlLines<BufReader<File>>> {
+    Ok(from_reader(File::open(path)?))
+}
+
+/// Stream the lines of any reader
+pub fn from_reader<R: Read>(reader: R) -> JsonlLines<BufReader<R>> {
+    with_line_limit(reader, MAX_LINE_BYTES)
+}
+
+fn with_line_limit<R: Read>(reader: R, max_line_bytes: usize) -> JsonlLines<BufReader<R>> {
+    JsonlLines {
+        reader: BufReader::new(reader),
+        max_line_bytes,
+        line_number: 0,
+        done: false,
+    }
+}
+
+impl<R: BufRead> JsonlLines<R> {
+    /// Lines that deserialize as `T`, with their line numbers; other lines are skipped
+    pub fn records<T: DeserializeOwned>(self) -> impl Iterator<Item = (usize, T)> {
+        self.filter_map(|(number, line)| {
+            serde_json::from_str::<T>(&line)
+                .ok()
+                .map(|record| (number, record))
+        })
+    }
+
+    /// Consume the next line and its newline; the newline is not returned
+    fn read_line(&mut self) -> LineRead {
+        let mut buf: Vec<u8> = Vec::new();
+        let mut oversized = false;
+        let mut read_any = false;
+
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    tracing::warn!(
+                        "Stopped reading JSONL at line {}: {}",
+                        self.line_number + 1,
+                        e
+                    );
+                    self.done = true;
+                    break;
+                }
+            };
+            if available.is_empty() {
+                self.done = true;
+                break;
+            }
+            read_any = true;
+
+            let newline = available.iter().position(|&b| b == b'\n');
+            let content_len = newline.unwrap_or(available.len());
+            if !oversized {
+                if buf.len() + content_len > self.max_line_bytes {
+                    oversized = true;
+                    buf = Vec::new();
+                } else {
+                    buf.extend_from_slice(available.get(..content_len).unwrap_or_default());
+                }
+            }
+            self.reader.consume(newline.map_or(content_len, |i| i + 1));
+            if newline.is_some() {
+                break;
+            }
+        }
+
+        match (read_any, oversized) {
+            (false, _) => LineRead::End,
+            (true, true) => LineRead::Oversized,
+            (true, false) => LineRead::Line(buf),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for JsonlLines<R> {
+    type Item = (usize, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let mut bytes = match self.read_line() {
+                LineRead::Line(bytes) => bytes,
+                LineRead::End => return None,
+                LineRead::Oversized => {
+                    self.line_number += 1;
+                    tracing::warn!(
+                        "Skipped JSONL line {} longer than {} bytes",
+                        self.line_number,
+                        self.max_line_bytes
+                    );
+                    continue;
+                }
+            };
+            self.line_number += 1;
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+            let text = String::from_utf8(bytes)
+                .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
+            return Some((self.line_number, text));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_lines_skip_oversized_and_keep_numbering() {
+        let input = b"{\"a\":1}\r\n\n{\"long\":\"xxxxxxxxxxxxxxxxxxxx\"}\n\xff{\"b\":2}\n{\"c\":";
+        let lines: Vec<(usize, String)> = with_line_limit(&input[..], 16).collect();
+
+        assert_eq!(
+            lines,
+            [
+                (1, "{\"a\":1}".to_string()),
+                (2, String::new()),
+                (4, "\u{fffd}{\"b\":2}".to_string()),
+                (5, "{\"c\":".to_string()),
+            ]
+        );
+
+        let records: Vec<(usize, Value)> = from_reader(&input[..]).records().collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], (1, serde_json::json!({"a": 1})));
+        assert_eq!(records[1].0, 3);
+        assert!(from_reader(&b""[..]).next().is_none());
+    }
+}
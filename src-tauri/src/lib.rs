@@ -8,11 +8,14 @@ mod commands;
 mod diagnostics;
 mod errors;
 mod hook_watcher;
+mod jsonl;
 mod logging;
 mod logs;
 mod pty_manager;
+mod scan;
 mod security;
 mod services;
+mod store;
 mod types;
 mod workspace_store;
 
@@ -33,23 +36,84 @@ use objc::*;
 // Project Commands
 pub use commands::{list_all_chats, list_all_sessions, list_projects, list_sessions};
 // Search
-pub use services::{build_search_index, search_chats};
+pub use services::{
+    build_search_index, get_search_index_stats, optimize_search_index, remove_project_from_index,
+    search_chats, ProjectDocCount, SearchIndexStats,
+};
+// Checkpoints
+pub use services::{
+    create_checkpoint, delete_checkpoint, list_checkpoints, restore_checkpoint, Checkpoint,
+};
+// Claude config backups
+pub use services::{
+    create_claude_backup, delete_claude_backup, list_claude_backups, restore_claude_backup,
+    BackupChange, BackupChangeKind, BackupRestore, ClaudeBackup,
+};
+// ~/.claude.json history
+pub use services::{
+    diff_claude_json_history, list_claude_json_history, ClaudeJsonDiff, ClaudeJsonSnapshot,
+    JsonChange, JsonChangeKind,
+};
+// Secret redaction
+pub use services::{redact_secrets, scan_session_secrets, SecretFinding};
+// Claude task queue
+pub use services::{
+    cancel_claude_task, enqueue_claude_task, get_claude_task, list_claude_tasks,
+    remove_claude_task, ClaudeTask, ClaudeTaskOptions, ClaudeTaskProgress, ClaudeTaskStatus,
+};
+pub use services::{
+    delete_scheduled_task, list_scheduled_tasks, schedule_task, set_scheduled_task_enabled,
+    ScheduledTask, ScheduledTaskSpec,
+};
+// Notifications
+pub use services::{
+    get_notification_settings, send_notification, set_notification_settings, AppNotification,
+    NotificationKind, NotificationSettings, QuietHours,
+};
+// Local REST API
+pub use services::{
+    get_api_server_status, regenerate_api_server_token, set_api_server_settings, ApiServerSettings,
+    ApiServerStatus,
+};
+// Deep links
+pub use services::{open_deep_link, take_pending_deep_link, DeepLink};
+// Webhooks
+pub use services::{
+    add_webhook, list_webhook_deliveries, list_webhooks, remove_webhook, set_webhook_enabled,
+    test_webhook, Webhook, WebhookDelivery, WebhookEvent, WebhookKind,
+};
+// Terminal notifications
+pub use services::{unwatch_directory, watch_directory, FsChangedEvent};
+pub use services::{get_pty_notify_settings, set_pty_notify_settings, PtyNotifySettings};
+// Logs
+pub use logs::{get_recent_logs, set_log_level, LogEntry};
 // Commands
 pub use commands::{
-    add_frontmatter_field, archive_command, deprecate_command, list_local_commands,
+    add_frontmatter_field, archive_command, deprecate_command, diff_command_versions,
+    list_command_versions, list_local_commands, list_project_commands, move_command_namespace,
     parse_frontmatter, rename_command, restore_command, update_command_aliases,
-    update_frontmatter_field,
+    update_command_content, update_frontmatter_field, CommandVersion,
 };
+pub use commands::{lint_all_commands, lint_command, CommandLintWarning};
+// Command Sync
+pub use commands::{init_commands_repo, sync_commands_pull, sync_commands_push, SyncPullResult};
 // Agents & Skills
 pub use commands::{
-    get_coding_agent_info, list_coding_agents, list_local_agents, list_local_skills,
+    archive_local_agent, archive_skill, create_local_agent, create_skill, delete_local_agent,
+    delete_skill, get_coding_agent_info, list_coding_agents, list_local_agents,
+    list_local_skills, update_local_agent, validate_agent, validate_skill, AssetProblem,
+    SkillFile,
 };
 // Executor Profiles
 pub use commands::{get_agent_profiles, list_executor_profiles, list_supported_agents};
 // Knowledge Base
 pub use commands::{
-    find_session_project, get_distill_dir, get_distill_watch_enabled, list_distill_documents,
-    list_reference_docs, list_reference_sources, set_distill_watch_enabled, DISTILL_WATCH_ENABLED,
+    add_reference_source, create_distill_document, delete_distill_document, delete_distill_tag,
+    distill_session, distill_sessions, find_session_project, get_distill_dir,
+    get_distill_watch_enabled, list_distill_documents,
+    list_distill_tags, list_reference_docs, list_reference_sources, refresh_reference_sources,
+    rename_distill_tag, set_distill_watch_enabled, start_reference_refresher,
+    update_distill_document, DISTILL_WATCH_ENABLED,
 };
 // Marketplace
 pub use commands::{
@@ -60,11 +124,23 @@ pub use commands::{
     update_settings_statusline, write_statusline_script,
 };
 // Context
-pub use commands::{get_context_files, get_project_context};
+pub use commands::{get_context_files, get_expanded_context, get_project_context, ContextNode};
+// Memory
+pub use commands::{append_memory_entry, get_memory, update_memory, MemoryFile};
+pub use commands::{
+    generate_claude_md, ClaudeMdDraft, LanguageStat, LayoutEntry, ProjectCommand, RepoScan,
+};
 // Report
 pub use commands::{
-    get_activity_stats, get_annual_report_2025, get_command_stats, get_command_weekly_stats,
+    get_activity_stats, get_annual_report, get_api_error_stats, get_command_stats,
+    get_command_weekly_stats, get_cost_report, get_model_usage_stats, get_period_report,
+    get_price_table, get_project_stats, get_report_period, get_session_metrics_summary,
+    get_tool_usage_stats, prune_command_stats, recount_command_stats, reset_command_stats,
+    update_price_table,
 };
+pub use commands::{export_activity_ical, export_report};
+// Achievements
+pub use commands::get_achievements;
 // Settings
 pub use commands::{
     copy_to_clipboard, delete_settings_env, disable_settings_env, enable_settings_env,
@@ -74,46 +150,115 @@ pub use commands::{
     test_openai_connection, update_disabled_settings_env, update_mcp_env, update_settings_env,
     write_binary_file, write_file,
 };
+pub use commands::{validate_settings, SettingsDiagnostic};
 // Version
 pub use commands::{
-    get_claude_code_version_info, install_claude_code_version, set_claude_code_autoupdater,
+    detect_claude_installations, get_claude_code_changelog, get_claude_code_version_info,
+    install_claude_code_version, list_claude_code_versions, pin_claude_code_version,
+    set_claude_code_autoupdater, set_preferred_claude_binary, unpin_claude_code_version,
+    ClaudeCodeChangelogEntry, ClaudeCodePin, ClaudeCodeRelease, ClaudeCodeVersionList,
+    ClaudeInstallMethod, ClaudeInstallation,
 };
 // Files
 pub use commands::{
-    copy_file_to_project_assets, delete_project_logo, exec_shell_command, get_file_metadata,
-    get_project_logo, list_directory, list_project_logos, read_file, read_file_base64,
-    save_project_logo, set_current_project_logo,
+    find_files, grep_project, FindFilesResult, GrepMatch, GrepOptions, GrepResult,
+};
+pub use commands::{diff_against_content, diff_files};
+pub use commands::{get_file_tree, FileTreeNode};
+pub use commands::{get_permanent_delete, set_permanent_delete, trash_path};
+pub use commands::{
+    cancel_exec, copy_file_to_project_assets, delete_project_logo, exec_shell_command,
+    exec_shell_stream, get_file_metadata, get_project_logo, list_directory, list_project_logos,
+    read_file, read_file_base64, read_file_range, read_file_tail, save_project_logo,
+    set_current_project_logo, FileChunk,
 };
 // Git
 pub use commands::{
-    git_auto_commit, git_generate_changelog, git_get_note, git_has_changes, git_log, git_revert,
-    git_set_note,
+    git_add_worktree, git_auto_commit, git_create_branch, git_delete_branch, git_diff,
+    git_generate_changelog, git_get_note, git_has_changes, git_list_branches, git_list_worktrees,
+    git_log, git_pull, git_push, git_remove_worktree, git_revert, git_set_note, git_show,
+    git_switch_branch,
 };
 // PTY
 pub use commands::{
-    pty_create, pty_exists, pty_flush_scrollback, pty_kill, pty_list, pty_purge_scrollback,
-    pty_read, pty_resize, pty_scrollback, pty_write,
+    pty_create, pty_create_ssh, pty_exists, pty_flush_scrollback, pty_get_batch_settings,
+    pty_get_exit_status, pty_get_info, pty_get_state, pty_is_alive, pty_kill, pty_list,
+    pty_list_sessions, pty_purge_scrollback, pty_read, pty_resize, pty_respawn, pty_scrollback,
+    pty_set_batch_settings, pty_write,
 };
 // Workspace
 pub use commands::{
-    workspace_add_panel, workspace_add_project, workspace_create_feature, workspace_delete_feature,
-    workspace_get_pending_reviews, workspace_list_projects, workspace_load, workspace_remove_panel,
-    workspace_remove_project, workspace_rename_feature, workspace_save,
-    workspace_set_active_feature, workspace_set_active_project, workspace_toggle_panel_shared,
-    workspace_update_feature_status,
+    workspace_add_panel, workspace_add_project, workspace_complete_feature,
+    workspace_create_feature, workspace_delete, workspace_delete_feature, workspace_export,
+    workspace_import, workspace_list, workspace_switch,
+    workspace_get_pending_reviews, workspace_link_feature_session, workspace_list_projects,
+    workspace_list_trash, workspace_restore_deleted_feature, workspace_undo_last_change,
+    workspace_load, workspace_move_feature_status, workspace_remove_panel, workspace_remove_project,
+    workspace_rename_feature, workspace_reorder_features, workspace_save,
+    workspace_set_active_feature, workspace_set_active_project, workspace_set_feature_worktree,
+    workspace_start_feature_branch,
+    workspace_set_panel_env, workspace_toggle_panel_shared, workspace_unlink_feature_session,
+    workspace_update_feature_status, workspace_update_layout_ratio,
 };
 // Hooks
 pub use commands::{
     hook_get_monitored, hook_is_monitoring, hook_notify_complete, hook_start_monitoring,
     hook_stop_monitoring,
 };
+pub use commands::{
+    clear_hook_events, get_hook_log_path, list_hook_events, HookEvent, HookEventFilter,
+    HookEventStatus,
+};
 // Diagnostics
 pub use commands::{
     diagnostics_add_missing_keys, diagnostics_check_env, diagnostics_detect_stack,
     diagnostics_scan_file_lines,
 };
+pub use commands::{
+    analyze_project_setup, scaffold_project_setup, ProjectScaffoldOptions, ProjectScaffoldResult,
+    ProjectSetup, RecommendedHook,
+};
 // Sessions
 pub use commands::get_session_messages;
+pub use commands::{
+    get_project_open_todos, get_session_todos, OpenTodos, SessionPlan, SessionTodos, TodoItem,
+};
+pub use commands::{get_session_metrics, SessionMetrics};
+pub use commands::{get_session_tool_calls, ToolCall, ToolCallFilter};
+pub use commands::{extract_code_blocks, CodeBlock};
+pub use commands::{get_session_chain, ChainLink, ChainedSession, SessionChain};
+// History maintenance
+pub use commands::{
+    analyze_history, compact_history, HistoryAnalysis, HistoryCompaction, MalformedLine,
+};
+// History import
+pub use commands::{
+    delete_imported_sessions, import_chat_history, list_imported_sessions, ImportReport,
+    ImportSource, ImportedSession,
+};
+// Session export
+pub use commands::{
+    export_session_html, export_sessions_to_vault, VaultExportFilter, VaultExportReport,
+};
+// Session trash
+pub use commands::{list_trashed_sessions, restore_session, trash_session, TrashedSession};
+// Bookmarks
+pub use commands::{bookmark_message, list_bookmarks, remove_bookmark, Bookmark, BookmarkFilter};
+// Copy history
+pub use commands::{
+    clear_copy_history, get_copy_history_enabled, list_copy_history, set_copy_history_enabled,
+    CopyHistoryItem, CopySource,
+};
+// Saved searches
+pub use commands::{
+    delete_saved_search, get_search_history, list_saved_searches, save_search, SavedSearch,
+    SearchFilters, SearchHistoryEntry,
+};
+// Prompt library
+pub use commands::{
+    delete_prompt, list_prompts, save_prompt, suggest_prompts_from_history, use_prompt,
+    PromptSuggestion, SavedPrompt, SuggestionReason,
+};
 
 // ============================================================================
 // macOS Window Configuration
@@ -165,6 +310,17 @@ fn activate_and_focus_window(window: &tauri::WebviewWindow) {
     }
 }
 
+/// Show and focus the main window; None when it was closed
+fn focus_main_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    let window = app.get_webview_window("main")?;
+    let _ = window.show();
+    #[cfg(target_os = "macos")]
+    activate_and_focus_window(&window);
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.set_focus();
+    Some(window)
+}
+
 // ============================================================================
 // Application Entry Point
 // ============================================================================
@@ -175,14 +331,65 @@ pub fn run() {
     logging::init_logging();
 
     tauri::Builder::default()
+        // Must come first: a second launch (e.g. from a lovcode:// link on Windows/Linux)
+        // hands its link to this instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            focus_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
-            use tauri::menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder};
+            use tauri::menu::{
+                CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, PredefinedMenuItem,
+                SubmenuBuilder,
+            };
+            use tauri::tray::TrayIconBuilder;
+            use tauri_plugin_deep_link::DeepLinkExt;
 
             // Initialize PTY manager with app handle for event emission
             pty_manager::init(app.handle().clone());
 
+            // Native notifications for long-running work (index builds, Claude tasks, reviews)
+            services::notifications::init(app.handle().clone());
+
+            // lovcode:// links: session/<project>/<id> and feature/<id>
+            #[cfg(any(windows, target_os = "linux"))]
+            app.deep_link().register_all()?;
+            let app_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                focus_main_window(&app_handle);
+                for url in event.urls() {
+                    services::deep_link::dispatch(&app_handle, url.as_str());
+                }
+            });
+            // The link the app was launched with
+            for url in app.deep_link().get_current()?.unwrap_or_default() {
+                services::deep_link::dispatch(app.handle(), url.as_str());
+            }
+
+            // Distill finished sessions in the background (gated by the distill watch toggle)
+            services::distill::start_session_watcher(app.handle().clone());
+
+            // Notify when busy terminals go idle or print a watched pattern
+            services::pty_notify::start_pty_notifier(app.handle().clone());
+
+            // Snapshot ~/.claude.json whenever Claude Code, an installer or lovcode changes it
+            services::claude_json_history::start_claude_json_watcher();
+
+            // Resume queued headless claude -p tasks
+            services::task_queue::start_task_queue(app.handle().clone());
+
+            // Queue scheduled claude -p tasks when their cron time comes
+            services::scheduler::start_scheduler();
+
+            // Local read-only REST API, when the user turned it on
+            services::api_server::start_api_server();
+
+            // Re-fetch URL-backed reference docs when their refresh interval elapses
+            start_reference_refresher();
+
             // Start watching distill directory for changes
             let app_handle = app.handle().clone();
             std::thread::spawn(move || {
@@ -279,6 +486,52 @@ pub fn run() {
 
             app.set_menu(menu)?;
 
+            // System tray: live stats and quick actions
+            let stats = services::tray::collect_stats();
+            let [terminals_label, reviews_label, tasks_label] = services::tray::stat_labels(&stats);
+            let tray_terminals = MenuItemBuilder::with_id("tray_terminals", terminals_label)
+                .enabled(false)
+                .build(app)?;
+            let tray_reviews = MenuItemBuilder::with_id("tray_reviews", reviews_label)
+                .enabled(false)
+                .build(app)?;
+            let tray_tasks = MenuItemBuilder::with_id("tray_tasks", tasks_label)
+                .enabled(false)
+                .build(app)?;
+            let tray_pause = CheckMenuItemBuilder::with_id("tray_pause_watchers", "Pause Watchers")
+                .checked(stats.watchers_paused)
+                .build(app)?;
+            let tray_menu = MenuBuilder::new(app)
+                .item(&tray_terminals)
+                .item(&tray_reviews)
+                .item(&tray_tasks)
+                .separator()
+                .item(
+                    &MenuItemBuilder::with_id("tray_open_workspace", "Open Workspace")
+                        .build(app)?,
+                )
+                .item(&tray_pause)
+                .separator()
+                .item(&MenuItemBuilder::with_id("tray_quit", "Quit Lovcode").build(app)?)
+                .build()?;
+
+            let mut tray_builder = TrayIconBuilder::with_id("main")
+                .menu(&tray_menu)
+                .tooltip(services::tray::tooltip(&stats));
+            if let Some(icon) = app.default_window_icon() {
+                tray_builder = tray_builder.icon(icon.clone());
+            }
+            let tray = tray_builder.build(app)?;
+
+            services::tray::start_refresher(app.handle(), move |stats| {
+                let [terminals, reviews, tasks] = services::tray::stat_labels(stats);
+                let _ = tray_terminals.set_text(terminals);
+                let _ = tray_reviews.set_text(reviews);
+                let _ = tray_tasks.set_text(tasks);
+                let _ = tray_pause.set_checked(stats.watchers_paused);
+                let _ = tray.set_tooltip(Some(services::tray::tooltip(stats)));
+            });
+
             Ok(())
         })
         .on_menu_event(|app, event| {
@@ -335,6 +588,15 @@ pub fn run() {
                         }
                     }
                 }
+                "tray_open_workspace" => {
+                    if let Some(window) = focus_main_window(app) {
+                        let _ = window.emit("menu-open-workspace", ());
+                    }
+                }
+                "tray_pause_watchers" => {
+                    services::tray::set_watchers_paused(app, !services::tray::watchers_paused());
+                }
+                "tray_quit" => app.exit(0),
                 _ => {}
             }
         })
@@ -344,11 +606,30 @@ pub fn run() {
             list_all_sessions,
             list_all_chats,
             get_session_messages,
+            get_session_todos,
+            get_project_open_todos,
+            get_session_tool_calls,
+            extract_code_blocks,
+            get_session_chain,
+            get_session_metrics,
             build_search_index,
             search_chats,
+            remove_project_from_index,
+            get_search_index_stats,
+            optimize_search_index,
             list_local_commands,
+            list_project_commands,
             list_local_agents,
+            create_local_agent,
+            update_local_agent,
+            delete_local_agent,
+            archive_local_agent,
             list_local_skills,
+            create_skill,
+            delete_skill,
+            archive_skill,
+            validate_agent,
+            validate_skill,
             list_coding_agents,
             get_coding_agent_info,
             list_executor_profiles,
@@ -356,18 +637,49 @@ pub fn run() {
             list_supported_agents,
             get_context_files,
             get_project_context,
+            get_expanded_context,
+            get_memory,
+            update_memory,
+            append_memory_entry,
+            generate_claude_md,
             get_settings,
+            validate_settings,
             get_command_stats,
             get_command_weekly_stats,
+            reset_command_stats,
+            prune_command_stats,
+            recount_command_stats,
             get_activity_stats,
-            get_annual_report_2025,
+            get_annual_report,
+            get_period_report,
+            get_report_period,
+            get_model_usage_stats,
+            get_tool_usage_stats,
+            get_api_error_stats,
+            get_session_metrics_summary,
+            get_project_stats,
+            get_cost_report,
+            get_price_table,
+            update_price_table,
+            export_report,
+            export_activity_ical,
+            get_achievements,
             get_templates_catalog,
             install_command_template,
             rename_command,
+            move_command_namespace,
             deprecate_command,
             archive_command,
             restore_command,
             update_command_aliases,
+            update_command_content,
+            list_command_versions,
+            diff_command_versions,
+            lint_command,
+            lint_all_commands,
+            init_commands_repo,
+            sync_commands_push,
+            sync_commands_pull,
             install_mcp_template,
             uninstall_mcp_template,
             check_mcp_installed,
@@ -405,28 +717,61 @@ pub fn run() {
             test_openai_connection,
             test_claude_cli,
             list_distill_documents,
+            create_distill_document,
+            update_distill_document,
+            delete_distill_document,
+            list_distill_tags,
+            rename_distill_tag,
+            delete_distill_tag,
+            distill_session,
+            distill_sessions,
             find_session_project,
             get_distill_watch_enabled,
             set_distill_watch_enabled,
             list_reference_sources,
             list_reference_docs,
+            add_reference_source,
+            refresh_reference_sources,
             get_claude_code_version_info,
+            list_claude_code_versions,
+            get_claude_code_changelog,
             install_claude_code_version,
+            pin_claude_code_version,
+            unpin_claude_code_version,
             set_claude_code_autoupdater,
+            detect_claude_installations,
+            set_preferred_claude_binary,
+            // Logs
+            get_recent_logs,
+            set_log_level,
             // PTY commands
             pty_create,
+            pty_create_ssh,
+            pty_respawn,
             pty_write,
             pty_read,
             pty_resize,
             pty_kill,
             pty_list,
             pty_exists,
+            pty_list_sessions,
+            pty_is_alive,
+            pty_get_state,
+            pty_get_info,
+            pty_get_exit_status,
             pty_scrollback,
             pty_purge_scrollback,
             pty_flush_scrollback,
+            pty_get_batch_settings,
+            pty_set_batch_settings,
             // Workspace commands
             workspace_load,
             workspace_save,
+            workspace_list,
+            workspace_switch,
+            workspace_delete,
+            workspace_export,
+            workspace_import,
             workspace_add_project,
             workspace_list_projects,
             workspace_remove_project,
@@ -434,11 +779,23 @@ pub fn run() {
             workspace_create_feature,
             workspace_rename_feature,
             workspace_update_feature_status,
+            workspace_reorder_features,
+            workspace_move_feature_status,
+            workspace_link_feature_session,
+            workspace_unlink_feature_session,
             workspace_delete_feature,
+            workspace_list_trash,
+            workspace_undo_last_change,
+            workspace_restore_deleted_feature,
             workspace_set_active_feature,
+            workspace_set_feature_worktree,
+            workspace_start_feature_branch,
+            workspace_complete_feature,
             workspace_add_panel,
             workspace_remove_panel,
+            workspace_set_panel_env,
             workspace_toggle_panel_shared,
+            workspace_update_layout_ratio,
             workspace_get_pending_reviews,
             // Hook watcher commands
             hook_start_monitoring,
@@ -452,26 +809,138 @@ pub fn run() {
             set_current_project_logo,
             delete_project_logo,
             read_file_base64,
+            read_file_range,
+            read_file_tail,
+            diff_files,
+            diff_against_content,
+            trash_path,
+            get_permanent_delete,
+            set_permanent_delete,
+            get_file_tree,
+            find_files,
+            grep_project,
+            watch_directory,
+            unwatch_directory,
             exec_shell_command,
+            exec_shell_stream,
+            cancel_exec,
             hook_get_monitored,
             hook_notify_complete,
+            // Hook event log
+            get_hook_log_path,
+            list_hook_events,
+            clear_hook_events,
             // File system
             get_file_metadata,
             read_file,
             list_directory,
             // Git commands
             git_log,
+            git_show,
+            git_push,
+            git_pull,
+            // Checkpoints
+            create_checkpoint,
+            list_checkpoints,
+            restore_checkpoint,
+            delete_checkpoint,
+            // Claude config backups
+            create_claude_backup,
+            list_claude_backups,
+            restore_claude_backup,
+            delete_claude_backup,
+            // ~/.claude.json history
+            list_claude_json_history,
+            diff_claude_json_history,
+            // Secret redaction
+            scan_session_secrets,
+            redact_secrets,
+            // Claude task queue
+            enqueue_claude_task,
+            list_claude_tasks,
+            get_claude_task,
+            cancel_claude_task,
+            remove_claude_task,
+            schedule_task,
+            list_scheduled_tasks,
+            set_scheduled_task_enabled,
+            delete_scheduled_task,
+            // Notifications
+            send_notification,
+            get_notification_settings,
+            set_notification_settings,
+            // Local REST API
+            get_api_server_status,
+            set_api_server_settings,
+            regenerate_api_server_token,
+            // Deep links
+            open_deep_link,
+            take_pending_deep_link,
+            // Webhooks
+            add_webhook,
+            list_webhooks,
+            remove_webhook,
+            set_webhook_enabled,
+            test_webhook,
+            list_webhook_deliveries,
+            // History maintenance
+            analyze_history,
+            compact_history,
+            // History import
+            import_chat_history,
+            list_imported_sessions,
+            delete_imported_sessions,
+            // Session export
+            export_sessions_to_vault,
+            export_session_html,
+            // Session trash
+            trash_session,
+            list_trashed_sessions,
+            restore_session,
+            // Bookmarks
+            bookmark_message,
+            remove_bookmark,
+            list_bookmarks,
+            // Copy history
+            list_copy_history,
+            clear_copy_history,
+            get_copy_history_enabled,
+            set_copy_history_enabled,
+            // Saved searches
+            save_search,
+            list_saved_searches,
+            delete_saved_search,
+            get_search_history,
+            // Prompt library
+            save_prompt,
+            list_prompts,
+            delete_prompt,
+            use_prompt,
+            suggest_prompts_from_history,
+            // Terminal notifications
+            get_pty_notify_settings,
+            set_pty_notify_settings,
             git_get_note,
             git_set_note,
             git_revert,
             git_has_changes,
             git_auto_commit,
             git_generate_changelog,
+            git_diff,
+            git_list_branches,
+            git_create_branch,
+            git_switch_branch,
+            git_delete_branch,
+            git_add_worktree,
+            git_list_worktrees,
+            git_remove_worktree,
             // Diagnostics commands
             diagnostics_detect_stack,
             diagnostics_check_env,
             diagnostics_add_missing_keys,
-            diagnostics_scan_file_lines
+            diagnostics_scan_file_lines,
+            analyze_project_setup,
+            scaffold_project_setup
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
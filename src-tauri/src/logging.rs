@@ -1,11 +1,55 @@
 /**
- * [INPUT]: 依赖 tracing, tracing-subscriber
- * [OUTPUT]: 对外提供 init_logging() 初始化函数
- * [POS]: 日志模块，提供结构化日志支持
+ * [INPUT]: 依赖 tracing, tracing-subscriber (reload), tracing-appender (按天滚动文件), crate::security
+ * [OUTPUT]: 对外提供 init_logging() 初始化函数, logs_dir, set_level 运行时调整日志级别
+ * [POS]: 日志模块，提供结构化日志支持；终端输出 + ~/.lovstudio/lovcode/logs 下的 JSON 行日志文件
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use crate::security;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+};
+
+/// Log files are named `lovcode.<date>.log`
+pub const LOG_FILE_PREFIX: &str = "lovcode";
+/// Daily files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+/// Flushes the background file writer on drop, so it must live for the whole app
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+pub fn logs_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("logs")
+}
+
+/// Filter for lovcode's own logs at `level`, other crates stay at the build default
+fn level_filter(level: &str) -> EnvFilter {
+    let others = if cfg!(debug_assertions) {
+        "info"
+    } else {
+        "warn"
+    };
+    EnvFilter::new(format!("lovcode={},{}", level, others))
+}
+
+fn file_writer() -> Option<NonBlocking> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(logs_dir())
+        .map_err(|e| eprintln!("Failed to open log directory: {}", e))
+        .ok()?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let _ = FILE_GUARD.set(guard);
+    Some(writer)
+}
 
 /// Initialize structured logging with tracing.
 ///
@@ -13,17 +57,19 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, Env
 /// - RUST_LOG=debug (for debug output)
 /// - RUST_LOG=lovcode=debug,info (for module-specific levels)
 ///
-/// Default level is INFO in release, DEBUG in debug builds.
+/// Default level is INFO in release, DEBUG in debug builds. Besides the terminal,
+/// logs go to daily JSON-lines files in ~/.lovstudio/lovcode/logs (last 7 days kept).
 pub fn init_logging() {
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
         if cfg!(debug_assertions) {
             // Debug builds: more verbose
-            EnvFilter::new("lovcode=debug,info")
+            level_filter("debug")
         } else {
             // Release builds: less verbose
-            EnvFilter::new("lovcode=info,warn")
+            level_filter("info")
         }
     });
+    let (filter_layer, filter_handle) = reload::Layer::new(env_filter);
 
     let fmt_layer = fmt::layer()
         .with_target(true)
@@ -32,10 +78,21 @@ pub fn init_logging() {
         .with_line_number(true)
         .compact();
 
+    let file_layer = file_writer().map(|writer| {
+        fmt::layer()
+            .json()
+            .with_ansi(false)
+            .with_file(true)
+            .with_line_number(true)
+            .with_writer(writer)
+    });
+
     tracing_subscriber::registry()
-        .with(env_filter)
+        .with(filter_layer)
         .with(fmt_layer)
+        .with(file_layer)
         .init();
+    let _ = FILTER_HANDLE.set(filter_handle);
 
     tracing::info!(
         version = env!("CARGO_PKG_VERSION"),
@@ -43,6 +100,14 @@ pub fn init_logging() {
     );
 }
 
+/// Change lovcode's log level until the app restarts ("error" ... "trace")
+pub fn set_level(level: &str) -> Result<(), String> {
+    let handle = FILTER_HANDLE.get().ok_or("Logging is not initialized")?;
+    handle
+        .reload(level_filter(level))
+        .map_err(|e| format!("Failed to change log level: {}", e))
+}
+
 // Re-export tracing macros for convenience (used by other modules)
 #[allow(unused_imports)]
 pub use tracing::{debug, error, info, instrument, trace, warn};
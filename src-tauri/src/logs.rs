@@ -0,0 +1,215 @@
+/**
+ * [INPUT]: 依赖 serde_json, crate::logging (日志目录/运行时级别), crate::errors
+ * [OUTPUT]: 对外提供 get_recent_logs, set_log_level 命令与 LogEntry
+ * [POS]: 日志读取入口，让用户在设置页查看/导出最近日志以附到 bug 报告，无需从终端启动
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::logging;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LogEntry {
+    /// RFC 3339
+    pub timestamp: String,
+    /// "ERROR", "WARN", "INFO", "DEBUG" or "TRACE"
+    pub level: String,
+    /// Module that logged it, e.g. lovcode_lib::pty_manager
+    pub target: String,
+    pub message: String,
+    /// Structured fields besides the message (session ids, paths, errors, ...)
+    pub fields: BTreeMap<String, serde_json::Value>,
+}
+
+/// Most severe first; a level includes everything before it
+const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+const DEFAULT_LOG_LIMIT: usize = 200;
+const MAX_LOG_LIMIT: usize = 5000;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn level_rank(level: &str) -> Option<usize> {
+    let level = level.to_ascii_lowercase();
+    LOG_LEVELS.iter().position(|l| *l == level)
+}
+
+fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let text = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut fields: BTreeMap<String, serde_json::Value> = value
+        .get("fields")
+        .and_then(|f| f.as_object())
+        .map(|f| f.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default();
+    let message = match fields.remove("message") {
+        Some(serde_json::Value::String(message)) => message,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp: text("timestamp"),
+        level: text("level"),
+        target: text("target"),
+        message,
+        fields,
+    })
+}
+
+/// Newest `limit` entries at `max_rank` or more severe, oldest first
+fn recent_entries(dir: &Path, max_rank: usize, limit: usize) -> Vec<LogEntry> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                        n.starts_with(logging::LOG_FILE_PREFIX) && n.ends_with(".log")
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Dated names sort chronologically
+    files.sort();
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for file in files.iter().rev() {
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+        for entry in content.lines().rev().filter_map(parse_log_line) {
+            if level_rank(&entry.level).is_some_and(|rank| rank <= max_rank) {
+                entries.push(entry);
+                if entries.len() == limit {
+                    entries.reverse();
+                    return entries;
+                }
+            }
+        }
+    }
+    entries.reverse();
+    entries
+}
+
+fn parse_level(level: &str) -> AppResult<usize> {
+    level_rank(level).ok_or_else(|| {
+        AppError::invalid_input(format!(
+            "Invalid log level: {} (expected one of {})",
+            level,
+            LOG_LEVELS.join(", ")
+        ))
+    })
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Latest log entries from ~/.lovstudio/lovcode/logs, oldest first. `level` is the
+/// least severe level included (default "info"); `limit` defaults to 200.
+#[tauri::command]
+pub fn get_recent_logs(level: Option<String>, limit: Option<usize>) -> AppResult<Vec<LogEntry>> {
+    let max_rank = parse_level(level.as_deref().unwrap_or("info"))?;
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+    Ok(recent_entries(&logging::logs_dir(), max_rank, limit))
+}
+
+/// Change lovcode's log level until restart, e.g. "debug" while reproducing a bug
+#[tauri::command]
+pub fn set_log_level(level: String) -> AppResult<()> {
+    parse_level(&level)?;
+    logging::set_level(&level.to_ascii_lowercase())?;
+    tracing::info!(level = %level, "Log level changed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn line(timestamp: &str, level: &str, message: &str) -> String {
+        serde_json::json!({
+            "timestamp": timestamp,
+            "level": level,
+            "fields": { "message": message, "session_id": "abc" },
+            "target": "lovcode_lib::pty_manager",
+            "filename": "src/pty_manager.rs",
+            "line_number": 42
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_log_line() {
+        let entry =
+            parse_log_line(&line("2026-10-15T08:00:00Z", "WARN", "PTY exited")).expect("parse");
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.message, "PTY exited");
+        assert_eq!(entry.target, "lovcode_lib::pty_manager");
+        assert_eq!(
+            entry.fields.get("session_id"),
+            Some(&serde_json::json!("abc"))
+        );
+        assert!(parse_log_line("not json").is_none());
+    }
+
+    #[test]
+    fn test_recent_entries_filters_level_across_files() {
+        let temp = TempDir::new().expect("create temp dir");
+        let older = [
+            line("2026-10-14T09:00:00Z", "ERROR", "old error"),
+            line("2026-10-14T10:00:00Z", "DEBUG", "old debug"),
+        ];
+        let newer = [
+            line("2026-10-15T09:00:00Z", "INFO", "started"),
+            line("2026-10-15T10:00:00Z", "WARN", "slow scan"),
+            line("2026-10-15T11:00:00Z", "ERROR", "crashed"),
+        ];
+        fs::write(temp.path().join("lovcode.2026-10-14.log"), older.join("\n")).expect("write");
+        fs::write(temp.path().join("lovcode.2026-10-15.log"), newer.join("\n")).expect("write");
+        fs::write(
+            temp.path().join("notes.txt"),
+            line("2026-10-15T12:00:00Z", "ERROR", "x"),
+        )
+        .expect("write");
+
+        let messages = |entries: Vec<LogEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(
+            messages(recent_entries(
+                temp.path(),
+                parse_level("warn").expect("level"),
+                10
+            )),
+            vec!["old error", "slow scan", "crashed"]
+        );
+        assert_eq!(
+            messages(recent_entries(
+                temp.path(),
+                parse_level("trace").expect("level"),
+                2
+            )),
+            vec!["slow scan", "crashed"]
+        );
+        assert!(parse_level("verbose").is_err());
+    }
+}
@@ -1,15 +1,22 @@
 //! PTY session management for terminal panels
 //!
 //! Event-driven architecture: data pushed via Tauri events instead of polling.
-//! Scrollback buffers are persisted to disk for recovery after app restart.
-
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
-use serde::Serialize;
-use std::collections::{HashMap, HashSet, VecDeque};
+//! Scrollback buffers are persisted to disk for recovery after app restart, together
+//! with the cwd/shell/command each session was started with so it can be respawned.
+//! Remote sessions run the user's `ssh` binary through the same pipeline.
+
+use crate::commands::claude_binary::with_preferred_claude;
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::services::pty_notify;
+use crate::services::pty_output::{ClaudeState, ClaudeStateDetector, OscParser, OscUpdate};
+use portable_pty::{native_pty_system, Child, ChildKiller, CommandBuilder, PtySize};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs;
 use std::io::{Read, Write};
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver};
 use std::sync::{Arc, LazyLock, Mutex, OnceLock};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -21,6 +28,19 @@ const SCROLLBACK_MAX_BYTES: usize = 256 * 1024;
 /// Minimum interval between disk writes (debounce)
 const SCROLLBACK_SAVE_INTERVAL_MS: u64 = 2000;
 
+/// Default window for coalescing output chunks into one pty-data event
+const DEFAULT_BATCH_MS: u64 = 4;
+
+/// Longest configurable batch window; beyond this, typing echo feels laggy
+pub const MAX_BATCH_MS: u64 = 50;
+
+/// A batch is sent early once it holds this much output
+const MAX_BATCH_BYTES: usize = 256 * 1024;
+
+/// Current batch window in ms (0 sends every read as its own event)
+static PTY_BATCH_MS: LazyLock<AtomicU64> =
+    LazyLock::new(|| AtomicU64::new(load_batch_settings().batch_ms.min(MAX_BATCH_MS)));
+
 /// Global AppHandle for emitting events
 static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
 
@@ -34,13 +54,17 @@ fn get_scrollback_dir() -> PathBuf {
 }
 
 /// Get scrollback file path for a session
-fn get_scrollback_path(id: &str) -> PathBuf {
-    get_scrollback_dir().join(format!("{}.bin", id))
+fn get_scrollback_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.bin", id))
 }
 
 /// Load scrollback from disk
 fn load_scrollback_from_disk(id: &str) -> Option<VecDeque<u8>> {
-    let path = get_scrollback_path(id);
+    load_scrollback_in(&get_scrollback_dir(), id)
+}
+
+fn load_scrollback_in(dir: &Path, id: &str) -> Option<VecDeque<u8>> {
+    let path = get_scrollback_path(dir, id);
     if path.exists() {
         match fs::read(&path) {
             Ok(data) => Some(VecDeque::from(data)),
@@ -53,12 +77,15 @@ fn load_scrollback_from_disk(id: &str) -> Option<VecDeque<u8>> {
 
 /// Save scrollback to disk
 fn save_scrollback_to_disk(id: &str, data: &VecDeque<u8>) -> Result<(), String> {
+    save_scrollback_in(&get_scrollback_dir(), id, data)
+}
+
+fn save_scrollback_in(dir: &Path, id: &str, data: &VecDeque<u8>) -> Result<(), String> {
     use crate::security;
 
-    let dir = get_scrollback_dir();
-    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create scrollback dir: {}", e))?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create scrollback dir: {}", e))?;
 
-    let path = get_scrollback_path(id);
+    let path = get_scrollback_path(dir, id);
     let bytes: Vec<u8> = data.iter().copied().collect();
 
     // 使用原子化写入，防止崩溃时数据损坏
@@ -69,8 +96,229 @@ fn save_scrollback_to_disk(id: &str, data: &VecDeque<u8>) -> Result<(), String>
 
 /// Delete scrollback file
 fn delete_scrollback_from_disk(id: &str) {
-    let path = get_scrollback_path(id);
-    let _ = fs::remove_file(path);
+    let dir = get_scrollback_dir();
+    let _ = fs::remove_file(get_scrollback_path(&dir, id));
+    let _ = crate::store::remove(&get_spec_path(&dir, id));
+}
+
+/// How a session was started, saved next to its scrollback for respawning
+#[derive(Serialize, Deserialize)]
+struct SessionSpec {
+    cwd: String,
+    shell: String,
+    command: Option<String>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    /// Set for sessions that run on a remote host through the local `ssh` binary;
+    /// `command` then runs on the remote side
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ssh: Option<SshTarget>,
+}
+
+/// Remote end of an SSH session
+#[derive(Clone, Serialize, Deserialize)]
+struct SshTarget {
+    /// Host alias from ~/.ssh/config, or `user@host`
+    host: String,
+    /// Passed as `ssh -p`; None uses the config or the default port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    /// Remote working directory (None = login directory)
+    cwd: Option<String>,
+}
+
+impl SessionSpec {
+    /// Directory shown for the session until the shell reports its own
+    fn display_cwd(&self) -> String {
+        match &self.ssh {
+            Some(ssh) => format!("{}:{}", ssh.host, ssh.cwd.as_deref().unwrap_or("~")),
+            None => self.cwd.clone(),
+        }
+    }
+
+    fn title(&self) -> String {
+        match (&self.ssh, &self.command) {
+            (Some(ssh), _) => format!("ssh {}", ssh.host),
+            (None, Some(command)) => command.clone(),
+            (None, None) => self.shell.rsplit('/').next().unwrap_or(&self.shell).to_string(),
+        }
+    }
+
+    fn command_builder(&self) -> CommandBuilder {
+        let mut cmd = if let Some(ssh) = &self.ssh {
+            // -t: allocate a remote TTY even though a command is given
+            let mut c = CommandBuilder::new("ssh");
+            c.arg("-t");
+            if let Some(port) = ssh.port {
+                c.args(["-p", &port.to_string()]);
+            }
+            c.args(["--", &ssh.host]);
+            c.arg(remote_script(ssh.cwd.as_deref(), self.command.as_deref()));
+            c
+        } else if let Some(command) = &self.command {
+            // Run the custom command via shell -c, resolving `claude` at spawn time so a
+            // changed preferred binary applies on respawn too
+            let mut c = CommandBuilder::new(&self.shell);
+            c.arg("-c");
+            c.arg(with_preferred_claude(command));
+            c
+        } else {
+            CommandBuilder::new(&self.shell)
+        };
+        cmd.cwd(&self.cwd);
+        cmd
+    }
+}
+
+/// Script handed to the remote login shell: cd into `cwd`, then run `command` or an
+/// interactive login shell
+fn remote_script(cwd: Option<&str>, command: Option<&str>) -> String {
+    let cd = cwd.map(|cwd| match cwd.strip_prefix("~/") {
+        // Leave the tilde outside the quotes so the remote shell expands it
+        Some(rest) => format!("cd ~/{} && ", crate::security::shell_escape(rest)),
+        None if cwd == "~" => "cd && ".to_string(),
+        None => format!("cd {} && ", crate::security::shell_escape(cwd)),
+    });
+    let run = command.map_or_else(
+        || "exec \"${SHELL:-/bin/sh}\" -l".to_string(),
+        str::to_string,
+    );
+    format!("{}{}", cd.unwrap_or_default(), run)
+}
+
+/// Accept ssh config aliases and `[user@]host[:port]` names (`[addr]:port` for IPv6),
+/// nothing ssh could read as an option or a shell could interpret. ssh doesn't parse
+/// `host:port` itself, so the port is split off to be passed as `-p`.
+fn parse_ssh_host(host: &str) -> Result<(String, Option<u16>), String> {
+    let invalid = || format!("Invalid SSH host '{}'", host);
+    let valid = !host.is_empty()
+        && !host.starts_with('-')
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@' | ':' | '[' | ']'));
+    if !valid {
+        return Err(invalid());
+    }
+
+    let (user, rest) = match host.rsplit_once('@') {
+        Some((user, rest)) => (Some(user), rest),
+        None => (None, host),
+    };
+    let (name, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+        let (addr, after) = bracketed.split_once(']').ok_or_else(invalid)?;
+        match after {
+            "" => (addr, None),
+            _ => (addr, Some(after.strip_prefix(':').ok_or_else(invalid)?)),
+        }
+    } else {
+        match rest.split_once(':') {
+            // More than one colon is a bare IPv6 address
+            Some((name, port)) if !port.contains(':') => (name, Some(port)),
+            _ => (rest, None),
+        }
+    };
+    let bad_part = |part: &str| part.is_empty() || part.contains(['[', ']']);
+    if bad_part(name) || user.is_some_and(|u| bad_part(u) || u.contains(':')) {
+        return Err(invalid());
+    }
+    let port = port
+        .map(|p| p.parse::<u16>().ok().filter(|p| *p > 0).ok_or_else(invalid))
+        .transpose()?;
+    let host = user.map_or_else(|| name.to_string(), |user| format!("{}@{}", user, name));
+    Ok((host, port))
+}
+
+/// Schema of the session spec files
+const SPEC_SCHEMA: crate::store::Schema = crate::store::Schema::new(1);
+
+fn get_spec_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+fn save_spec_to_disk(id: &str, spec: &SessionSpec) -> Result<(), String> {
+    save_spec_in(&get_scrollback_dir(), id, spec)
+}
+
+fn save_spec_in(dir: &Path, id: &str, spec: &SessionSpec) -> Result<(), String> {
+    crate::store::save(&get_spec_path(dir, id), &SPEC_SCHEMA, spec)
+        .map_err(|e| format!("Failed to write session spec: {}", e))
+}
+
+fn load_spec_from_disk(id: &str) -> Option<SessionSpec> {
+    load_spec_in(&get_scrollback_dir(), id)
+}
+
+/// None when nothing was recorded or the file can't be read (logged)
+fn load_spec_in(dir: &Path, id: &str) -> Option<SessionSpec> {
+    crate::store::load_as(&get_spec_path(dir, id), &SPEC_SCHEMA).unwrap_or_else(|e| {
+        tracing::warn!("{}", e);
+        None
+    })
+}
+
+/// Dimmed line appended to a respawned session's scrollback
+fn restore_marker(at: chrono::DateTime<chrono::Local>) -> String {
+    format!(
+        "\r\n\x1b[2m── session restored {} ──\x1b[0m\r\n",
+        at.format("%Y-%m-%d %H:%M")
+    )
+}
+
+/// Append `banner` to a scrollback buffer, dropping the oldest bytes past the cap
+fn append_banner(buffer: &mut VecDeque<u8>, banner: &[u8]) {
+    let overflow = (buffer.len() + banner.len()).saturating_sub(SCROLLBACK_MAX_BYTES);
+    buffer.drain(..overflow.min(buffer.len()));
+    buffer.extend(banner);
+}
+
+/// Persisted pty-data batching settings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PtyBatchSettings {
+    /// Chunks read within this many ms of the first are sent as one event
+    pub batch_ms: u64,
+}
+
+impl Default for PtyBatchSettings {
+    fn default() -> Self {
+        Self {
+            batch_ms: DEFAULT_BATCH_MS,
+        }
+    }
+}
+
+fn get_batch_settings_path() -> PathBuf {
+    crate::security::get_lovstudio_dir_or_fallback().join("pty_batch.json")
+}
+
+fn load_batch_settings() -> PtyBatchSettings {
+    fs::read_to_string(get_batch_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn batch_settings() -> PtyBatchSettings {
+    PtyBatchSettings {
+        batch_ms: PTY_BATCH_MS.load(Ordering::Relaxed),
+    }
+}
+
+/// Apply a new batch window to every session and persist it
+pub fn set_batch_settings(settings: PtyBatchSettings) -> AppResult<()> {
+    if settings.batch_ms > MAX_BATCH_MS {
+        return Err(AppError::invalid_input(format!(
+            "Batch window must be at most {} ms",
+            MAX_BATCH_MS
+        )));
+    }
+    let path = get_batch_settings_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    crate::security::atomic_write_string(&path, &content)?;
+    PTY_BATCH_MS.store(settings.batch_ms, Ordering::Relaxed);
+    Ok(())
 }
 
 /// Initialize PTY manager with AppHandle
@@ -85,10 +333,38 @@ pub struct PtyDataEvent {
     pub data: Vec<u8>,
 }
 
+/// How a session's process ended
+#[derive(Debug, Clone, Serialize)]
+pub struct PtyExitStatus {
+    /// None if the process could not be waited on
+    pub exit_code: Option<u32>,
+    /// Signal name (e.g. "Killed") when the process was terminated by a signal
+    pub signal: Option<String>,
+}
+
+impl From<&portable_pty::ExitStatus> for PtyExitStatus {
+    fn from(status: &portable_pty::ExitStatus) -> Self {
+        Self {
+            exit_code: Some(status.exit_code()),
+            signal: status.signal().map(str::to_string),
+        }
+    }
+}
+
 /// PTY exit event payload
 #[derive(Clone, Serialize)]
 pub struct PtyExitEvent {
     pub id: String,
+    #[serde(flatten)]
+    pub status: PtyExitStatus,
+}
+
+/// PTY restored event payload (session respawned after an app restart)
+#[derive(Clone, Serialize)]
+pub struct PtyRestoredEvent {
+    pub id: String,
+    pub cwd: String,
+    pub command: Option<String>,
 }
 
 /// Session I/O handles
@@ -99,6 +375,68 @@ struct SessionIO {
 /// Session control
 struct SessionControl {
     running: Arc<AtomicBool>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+}
+
+/// Inventory metadata; kept after the process exits until the session is killed
+struct SessionMeta {
+    /// Start directory, replaced by the shell's OSC 7 reports as it moves
+    cwd: String,
+    /// Command or shell name, replaced by OSC 0/2 titles
+    title: String,
+    osc: OscParser,
+    running: bool,
+    /// Set once the reader thread has reaped the process
+    exit: Option<PtyExitStatus>,
+    /// Unix ms of the last input or output
+    last_activity: i64,
+    claude: ClaudeStateDetector,
+    /// Unix ms of the last Claude state change
+    claude_since: i64,
+}
+
+impl SessionMeta {
+    fn new(cwd: String, title: String) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
+        Self {
+            cwd,
+            title,
+            osc: OscParser::default(),
+            running: true,
+            exit: None,
+            last_activity: now,
+            claude: ClaudeStateDetector::default(),
+            claude_since: now,
+        }
+    }
+}
+
+/// PTY session inventory entry (for reconciling persisted panels with live PTYs)
+#[derive(Clone, Serialize)]
+pub struct PtySessionInfo {
+    pub id: String,
+    pub cwd: String,
+    pub running: bool,
+    pub title: String,
+    pub last_activity: i64,
+    pub claude_state: ClaudeState,
+}
+
+/// Current directory and title of a session, as last reported by the program
+#[derive(Clone, Serialize)]
+pub struct PtyInfo {
+    pub id: String,
+    pub cwd: String,
+    pub title: String,
+}
+
+/// Claude Code state of a session, detected from its output
+#[derive(Clone, Serialize)]
+pub struct PtyState {
+    pub id: String,
+    pub claude_state: ClaudeState,
+    /// Unix ms since the session is in this state
+    pub since: i64,
 }
 
 /// Global storages
@@ -123,13 +461,151 @@ static PTY_SCROLLBACK_LAST_SAVE: LazyLock<Mutex<HashMap<String, Instant>>> =
 static PTY_SCROLLBACK_DIRTY: LazyLock<Mutex<HashSet<String>>> =
     LazyLock::new(|| Mutex::new(HashSet::new()));
 
-/// Create a new PTY session with background reader thread
+static PTY_META: LazyLock<Mutex<HashMap<String, SessionMeta>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Record input/output on a session
+fn touch_session(id: &str) {
+    if let Ok(mut meta) = PTY_META.lock() {
+        if let Some(entry) = meta.get_mut(id) {
+            entry.last_activity = chrono::Utc::now().timestamp_millis();
+        }
+    }
+}
+
+/// Record output and run it through the OSC parser and the Claude state detector;
+/// returns the new cwd/title and Claude state when they changed
+fn record_output(id: &str, data: &[u8]) -> (Option<PtyInfo>, Option<PtyState>) {
+    let Ok(mut meta) = PTY_META.lock() else {
+        return (None, None);
+    };
+    let Some(entry) = meta.get_mut(id) else {
+        return (None, None);
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    entry.last_activity = now;
+
+    let mut info_changed = false;
+    for update in entry.osc.feed(data) {
+        let (field, value) = match update {
+            OscUpdate::Cwd(cwd) => (&mut entry.cwd, cwd),
+            OscUpdate::Title(title) => (&mut entry.title, title),
+        };
+        if *field != value {
+            *field = value;
+            info_changed = true;
+        }
+    }
+    let info = info_changed.then(|| PtyInfo {
+        id: id.to_string(),
+        cwd: entry.cwd.clone(),
+        title: entry.title.clone(),
+    });
+
+    let state = entry.claude.feed(data).map(|claude_state| {
+        entry.claude_since = now;
+        PtyState {
+            id: id.to_string(),
+            claude_state,
+            since: now,
+        }
+    });
+    (info, state)
+}
+
+/// Reject variable names the OS can't hold and the ones lovcode sets itself
+pub fn validate_env(env: &BTreeMap<String, String>) -> Result<(), String> {
+    for (key, value) in env {
+        if key.is_empty() || key.contains(['=', '\0']) || value.contains('\0') {
+            return Err(format!("Invalid environment variable '{}'", key));
+        }
+        if key == "LOVCODE_TERMINAL" {
+            return Err(format!("Environment variable '{}' is reserved", key));
+        }
+    }
+    Ok(())
+}
+
+/// Create a new PTY session with background reader thread; `env` is merged over the
+/// inherited environment
 pub fn create_session(
     id: String,
     cwd: String,
     shell: Option<String>,
     command: Option<String>,
-) -> Result<(), String> {
+    env: BTreeMap<String, String>,
+) -> AppResult<()> {
+    validate_env(&env).map_err(AppError::invalid_input)?;
+    let shell = shell.unwrap_or_else(|| {
+        std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string())
+    });
+    Ok(spawn_session(
+        id,
+        SessionSpec {
+            cwd,
+            shell,
+            command,
+            env,
+            ssh: None,
+        },
+        None,
+    )?)
+}
+
+/// Create a session on a remote host through the user's `ssh` binary, so keys, agent
+/// and ~/.ssh/config apply. Output goes through the same event/scrollback pipeline as
+/// local sessions, and the session can be respawned after an app restart.
+pub fn create_ssh_session(
+    id: String,
+    host_alias: String,
+    cwd: Option<String>,
+    command: Option<String>,
+) -> AppResult<()> {
+    let (host, port) = parse_ssh_host(&host_alias).map_err(AppError::invalid_input)?;
+    let local_cwd = crate::security::safe_home_dir()?.to_string_lossy().to_string();
+    Ok(spawn_session(
+        id,
+        SessionSpec {
+            cwd: local_cwd,
+            shell: "ssh".to_string(),
+            command,
+            env: BTreeMap::new(),
+            ssh: Some(SshTarget { host, port, cwd }),
+        },
+        None,
+    )?)
+}
+
+/// Recreate a session that died with the app, using the cwd/shell/command/env recorded
+/// when it was created. The saved scrollback is kept and followed by a
+/// "session restored" marker, so replaying it shows where the new process starts.
+pub fn respawn_session(id: &str) -> AppResult<()> {
+    if session_alive(id) {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            format!("PTY session '{}' is still running", id),
+        ));
+    }
+    let spec = load_spec_from_disk(id).ok_or_else(|| {
+        AppError::not_found(format!("No saved start parameters for PTY session '{}'", id))
+    })?;
+
+    let marker = restore_marker(chrono::Local::now());
+    let event = PtyRestoredEvent {
+        id: id.to_string(),
+        cwd: spec.display_cwd(),
+        command: spec.command.clone(),
+    };
+    spawn_session(id.to_string(), spec, Some(marker.as_bytes()))?;
+
+    if let Some(app_handle) = APP_HANDLE.get() {
+        let _ = app_handle.emit("pty-restored", event);
+    }
+    Ok(())
+}
+
+/// `banner` is appended to the restored scrollback before the process produces output
+fn spawn_session(id: String, spec: SessionSpec, banner: Option<&[u8]>) -> Result<(), String> {
     let app_handle = APP_HANDLE
         .get()
         .ok_or_else(|| "PTY manager not initialized".to_string())?
@@ -147,32 +623,28 @@ pub fn create_session(
         })
         .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
-    // Determine shell
-    let shell_cmd = shell.unwrap_or_else(|| {
-        std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string())
-    });
-
-    // Build command: either run custom command via shell -c, or just start shell
-    let mut cmd = if let Some(ref command_str) = command {
-        let mut c = CommandBuilder::new(&shell_cmd);
-        c.arg("-c");
-        c.arg(command_str);
-        c
-    } else {
-        CommandBuilder::new(&shell_cmd)
-    };
-    cmd.cwd(&cwd);
+    let title = spec.title();
+    let cwd = spec.display_cwd();
+    let mut cmd = spec.command_builder();
 
     // Set proper TERM for xterm.js
     cmd.env("TERM", "xterm-256color");
+    // Panel-declared variables (model, ports, ...) may override TERM
+    for (key, value) in &spec.env {
+        cmd.env(key, value);
+    }
     // Mark as lovcode terminal (similar to ITERM_SESSION_ID for iTerm)
     cmd.env("LOVCODE_TERMINAL", "1");
 
-    let _child = pair
+    let child = pair
         .slave
         .spawn_command(cmd)
         .map_err(|e| format!("Failed to spawn shell: {}", e))?;
 
+    if let Err(e) = save_spec_to_disk(&id, &spec) {
+        tracing::warn!(pty_id = %id, error = %e, "Failed to save PTY session spec");
+    }
+
     // Get reader and writer
     let reader = pair
         .master
@@ -201,14 +673,23 @@ pub fn create_session(
     let running = Arc::new(AtomicBool::new(true));
     {
         let mut controls = PTY_CONTROLS.lock().map_err(|e| e.to_string())?;
-        controls.insert(id.clone(), SessionControl { running: running.clone() });
+        controls.insert(
+            id.clone(),
+            SessionControl {
+                running: running.clone(),
+                killer: child.clone_killer(),
+            },
+        );
     }
 
     // Initialize scrollback buffer - load from disk if exists (for app restart recovery)
     {
         let mut scrollback = PTY_SCROLLBACK.lock().map_err(|e| e.to_string())?;
-        let buffer = load_scrollback_from_disk(&id)
+        let mut buffer = load_scrollback_from_disk(&id)
             .unwrap_or_else(|| VecDeque::with_capacity(SCROLLBACK_MAX_BYTES));
+        if let Some(banner) = banner {
+            append_banner(&mut buffer, banner);
+        }
         scrollback.insert(id.clone(), buffer);
     }
     // Initialize last save timestamp
@@ -216,32 +697,83 @@ pub fn create_session(
         let mut last_save = PTY_SCROLLBACK_LAST_SAVE.lock().map_err(|e| e.to_string())?;
         last_save.insert(id.clone(), Instant::now());
     }
+    {
+        let mut meta = PTY_META.lock().map_err(|e| e.to_string())?;
+        meta.insert(id.clone(), SessionMeta::new(cwd, title));
+    }
 
     // Spawn background reader thread
     let session_id = id.clone();
     let running_flag = running;
 
     thread::spawn(move || {
-        read_loop(session_id, reader, running_flag, app_handle);
+        read_loop(session_id, reader, child, running_flag, app_handle);
     });
 
     Ok(())
 }
 
+/// Wait for the next chunk, then collect chunks arriving within `window` of it (up to
+/// MAX_BATCH_BYTES) into one batch. Returns None once the reader has hung up and
+/// everything it sent was returned.
+fn next_batch(chunks: &Receiver<Vec<u8>>, window: Duration) -> Option<Vec<u8>> {
+    let mut batch = chunks.recv().ok()?;
+    let deadline = Instant::now() + window;
+    while batch.len() < MAX_BATCH_BYTES {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match chunks.recv_timeout(remaining) {
+            Ok(chunk) => batch.extend_from_slice(&chunk),
+            // On disconnect the batch is still sent; the next call returns None
+            Err(_) => break,
+        }
+    }
+    Some(batch)
+}
+
+/// Per-session emitter - coalesces read_loop output into pty-data events, in order
+fn emit_loop(id: String, chunks: Receiver<Vec<u8>>, app_handle: AppHandle) {
+    loop {
+        let window = Duration::from_millis(PTY_BATCH_MS.load(Ordering::Relaxed));
+        let Some(data) = next_batch(&chunks, window) else {
+            break;
+        };
+        let _ = app_handle.emit(
+            "pty-data",
+            PtyDataEvent {
+                id: id.clone(),
+                data,
+            },
+        );
+    }
+}
+
 /// Background reader loop - runs in dedicated thread per session
 fn read_loop(
     id: String,
     mut reader: Box<dyn Read + Send>,
+    mut child: Box<dyn Child + Send + Sync>,
     running: Arc<AtomicBool>,
     app_handle: AppHandle,
 ) {
     let mut buffer = vec![0u8; 16384]; // 16KB buffer
+    // Killed sessions are reaped without an exit event
+    let mut report_exit = false;
+    // Output goes to the frontend through a batching emitter thread
+    let (chunks, batches) = mpsc::channel::<Vec<u8>>();
+    let emitter = {
+        let id = id.clone();
+        let app_handle = app_handle.clone();
+        thread::spawn(move || emit_loop(id, batches, app_handle))
+    };
 
     while running.load(Ordering::Relaxed) {
         match reader.read(&mut buffer) {
             Ok(0) => {
                 // EOF - session ended
-                let _ = app_handle.emit("pty-exit", PtyExitEvent { id: id.clone() });
+                report_exit = true;
                 break;
             }
             Ok(n) => {
@@ -299,22 +831,54 @@ fn read_loop(
                 if let Some(buf) = should_save {
                     let _ = save_scrollback_to_disk(&id, &buf);
                 }
+                let (info, state) = record_output(&id, &data);
+                if let Some(info) = info {
+                    let _ = app_handle.emit("pty-info-changed", info);
+                }
+                if let Some(state) = state {
+                    let _ = app_handle.emit("pty-claude-state", state);
+                }
+                pty_notify::record_output(&id, &data);
 
-                let _ = app_handle.emit("pty-data", PtyDataEvent { id: id.clone(), data });
+                let _ = chunks.send(data);
             }
             Err(e) => {
                 // Check if we should still be running
+                // Linux reports EIO on the master once the child has exited
                 if running.load(Ordering::Relaxed) {
-                    tracing::warn!(pty_id = %id, error = %e, "PTY read error");
-                    let _ = app_handle.emit("pty-exit", PtyExitEvent { id: id.clone() });
+                    tracing::debug!(pty_id = %id, error = %e, "PTY read ended");
+                    report_exit = true;
                 }
                 break;
             }
         }
     }
 
+    // Flush pending output before the exit event
+    drop(chunks);
+    let _ = emitter.join();
+
     // Cleanup on exit
     cleanup_session(&id);
+
+    let status = match child.wait() {
+        Ok(status) => PtyExitStatus::from(&status),
+        Err(e) => {
+            tracing::warn!(pty_id = %id, error = %e, "Failed to wait for PTY process");
+            PtyExitStatus {
+                exit_code: None,
+                signal: None,
+            }
+        }
+    };
+    if let Ok(mut meta) = PTY_META.lock() {
+        if let Some(entry) = meta.get_mut(&id) {
+            entry.exit = Some(status.clone());
+        }
+    }
+    if report_exit {
+        let _ = app_handle.emit("pty-exit", PtyExitEvent { id, status });
+    }
 }
 
 /// Internal cleanup (called from reader thread)
@@ -351,38 +915,47 @@ fn cleanup_session(id: &str) {
     if let Ok(mut dirty) = PTY_SCROLLBACK_DIRTY.lock() {
         dirty.remove(id);
     }
+    if let Ok(mut meta) = PTY_META.lock() {
+        if let Some(entry) = meta.get_mut(id) {
+            entry.running = false;
+        }
+    }
 }
 
 /// Write data to a PTY session
-pub fn write_to_session(id: &str, data: &[u8]) -> Result<(), String> {
+pub fn write_to_session(id: &str, data: &[u8]) -> AppResult<()> {
     let sessions = PTY_SESSIONS.lock().map_err(|e| e.to_string())?;
 
     let io = sessions
         .get(id)
-        .ok_or_else(|| format!("PTY session '{}' not found", id))?;
+        .ok_or_else(|| AppError::not_found(format!("PTY session '{}' not found", id)))?;
 
     let mut io_guard = io.lock().map_err(|e| e.to_string())?;
 
     io_guard
         .writer
         .write_all(data)
-        .map_err(|e| format!("Failed to write: {}", e))?;
+        .map_err(|e| AppError::io("Failed to write", &e))?;
 
     io_guard
         .writer
         .flush()
-        .map_err(|e| format!("Failed to flush: {}", e))?;
+        .map_err(|e| AppError::io("Failed to flush", &e))?;
 
+    drop(io_guard);
+    drop(sessions);
+    touch_session(id);
+    pty_notify::record_input(id);
     Ok(())
 }
 
 /// Resize a PTY session
-pub fn resize_session(id: &str, cols: u16, rows: u16) -> Result<(), String> {
+pub fn resize_session(id: &str, cols: u16, rows: u16) -> AppResult<()> {
     let mut masters = PTY_MASTERS.lock().map_err(|e| e.to_string())?;
 
     let master = masters
         .get_mut(id)
-        .ok_or_else(|| format!("PTY session '{}' not found", id))?;
+        .ok_or_else(|| AppError::not_found(format!("PTY session '{}' not found", id)))?;
 
     master
         .resize(PtySize {
@@ -398,15 +971,20 @@ pub fn resize_session(id: &str, cols: u16, rows: u16) -> Result<(), String> {
 
 /// Kill a PTY session
 pub fn kill_session(id: &str) -> Result<(), String> {
-    // Signal reader thread to stop
-    if let Ok(controls) = PTY_CONTROLS.lock() {
-        if let Some(ctrl) = controls.get(id) {
+    // Signal reader thread to stop, then end the process so the thread can reap it
+    if let Ok(mut controls) = PTY_CONTROLS.lock() {
+        if let Some(ctrl) = controls.get_mut(id) {
             ctrl.running.store(false, Ordering::Relaxed);
+            let _ = ctrl.killer.kill();
         }
     }
 
     // Cleanup will happen in reader thread, but also do immediate cleanup
     cleanup_session(id);
+    if let Ok(mut meta) = PTY_META.lock() {
+        meta.remove(id);
+    }
+    pty_notify::forget_session(id);
 
     Ok(())
 }
@@ -419,6 +997,72 @@ pub fn list_sessions() -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// Inventory of sessions, most recently active first; exited sessions stay listed
+/// (running = false) until killed
+pub fn list_session_info() -> Vec<PtySessionInfo> {
+    let mut sessions: Vec<PtySessionInfo> = PTY_META
+        .lock()
+        .map(|meta| {
+            meta.iter()
+                .map(|(id, entry)| PtySessionInfo {
+                    id: id.clone(),
+                    cwd: entry.cwd.clone(),
+                    running: entry.running,
+                    title: entry.title.clone(),
+                    last_activity: entry.last_activity,
+                    claude_state: entry.claude.state(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+    sessions
+}
+
+/// Claude Code state of a session (sessions that exited keep their last state)
+pub fn session_state(id: &str) -> AppResult<PtyState> {
+    let meta = PTY_META.lock().map_err(|e| e.to_string())?;
+    let entry = meta
+        .get(id)
+        .ok_or_else(|| AppError::not_found(format!("PTY session '{}' not found", id)))?;
+    Ok(PtyState {
+        id: id.to_string(),
+        claude_state: entry.claude.state(),
+        since: entry.claude_since,
+    })
+}
+
+/// Current directory and title of a session (from OSC 7 and OSC 0/2, falling back
+/// to the start directory and command)
+pub fn get_pty_info(id: &str) -> AppResult<PtyInfo> {
+    let meta = PTY_META.lock().map_err(|e| e.to_string())?;
+    let entry = meta
+        .get(id)
+        .ok_or_else(|| AppError::not_found(format!("PTY session '{}' not found", id)))?;
+    Ok(PtyInfo {
+        id: id.to_string(),
+        cwd: entry.cwd.clone(),
+        title: entry.title.clone(),
+    })
+}
+
+/// Exit code and signal of a session's process; None while it is still running
+pub fn get_pty_exit_status(id: &str) -> AppResult<Option<PtyExitStatus>> {
+    let meta = PTY_META.lock().map_err(|e| e.to_string())?;
+    let entry = meta
+        .get(id)
+        .ok_or_else(|| AppError::not_found(format!("PTY session '{}' not found", id)))?;
+    Ok(entry.exit.clone())
+}
+
+/// Check if a session's process is still running
+pub fn session_alive(id: &str) -> bool {
+    session_exists(id)
+        && PTY_META
+            .lock()
+            .is_ok_and(|meta| meta.get(id).is_some_and(|entry| entry.running))
+}
+
 /// Check if a session exists
 pub fn session_exists(id: &str) -> bool {
     PTY_SESSIONS
@@ -474,3 +1118,217 @@ pub fn read_from_session(_id: &str) -> Result<Vec<u8>, String> {
     // Return empty - data now comes via events
     Ok(Vec::new())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_script() {
+        assert_eq!(remote_script(None, None), "exec \"${SHELL:-/bin/sh}\" -l");
+        assert_eq!(
+            remote_script(Some("/srv/my app"), Some("claude")),
+            "cd '/srv/my app' && claude"
+        );
+        assert_eq!(
+            remote_script(Some("~/code/it's"), None),
+            "cd ~/'code/it'\\''s' && exec \"${SHELL:-/bin/sh}\" -l"
+        );
+    }
+
+    #[test]
+    fn test_parse_ssh_host() {
+        let parsed = |host: &str| parse_ssh_host(host).expect("valid host");
+        assert_eq!(parsed("devbox"), ("devbox".to_string(), None));
+        assert_eq!(parsed("me@10.0.0.5"), ("me@10.0.0.5".to_string(), None));
+        assert_eq!(parsed("me@devbox:2222"), ("me@devbox".to_string(), Some(2222)));
+        assert_eq!(parsed("[fe80::1]:22"), ("fe80::1".to_string(), Some(22)));
+        assert_eq!(parsed("me@[::1]"), ("me@::1".to_string(), None));
+        assert_eq!(parsed("fe80::1"), ("fe80::1".to_string(), None));
+
+        assert!(parse_ssh_host("-oProxyCommand=sh").is_err());
+        assert!(parse_ssh_host("host; rm -rf ~").is_err());
+        assert!(parse_ssh_host("").is_err());
+        assert!(parse_ssh_host("devbox:ssh").is_err());
+        assert!(parse_ssh_host("devbox:0").is_err());
+        assert!(parse_ssh_host("[::1").is_err());
+        assert!(parse_ssh_host("[::1]22").is_err());
+        assert!(parse_ssh_host("me:x@devbox").is_err());
+    }
+
+    #[test]
+    fn test_old_session_spec_is_local() {
+        let spec: SessionSpec =
+            serde_json::from_str(r#"{"cwd":"/tmp/p","shell":"/bin/zsh","command":null}"#)
+                .expect("parse spec");
+        assert!(spec.ssh.is_none());
+        assert_eq!(spec.title(), "zsh");
+        assert_eq!(spec.display_cwd(), "/tmp/p");
+    }
+
+    #[test]
+    fn test_session_spec_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let local = SessionSpec {
+            cwd: "/tmp/p".to_string(),
+            shell: "/bin/zsh".to_string(),
+            command: Some("claude".to_string()),
+            env: BTreeMap::from([("PORT".to_string(), "3000".to_string())]),
+            ssh: None,
+        };
+        save_spec_in(temp.path(), "local", &local).expect("save local");
+        let loaded = load_spec_in(temp.path(), "local").expect("load local");
+        assert_eq!(loaded.cwd, "/tmp/p");
+        assert_eq!(loaded.command.as_deref(), Some("claude"));
+        assert_eq!(loaded.env, local.env);
+        assert!(loaded.ssh.is_none());
+
+        let remote = SessionSpec {
+            ssh: Some(SshTarget {
+                host: "devbox".to_string(),
+                port: Some(2222),
+                cwd: Some("~/app".to_string()),
+            }),
+            ..local
+        };
+        save_spec_in(temp.path(), "remote", &remote).expect("save remote");
+        let loaded = load_spec_in(temp.path(), "remote").expect("load remote");
+        assert_eq!(loaded.display_cwd(), "devbox:~/app");
+        assert_eq!(loaded.ssh.as_ref().and_then(|ssh| ssh.port), Some(2222));
+        assert_eq!(loaded.title(), "ssh devbox");
+
+        // Nothing recorded, or an unreadable file: nothing to respawn from
+        assert!(load_spec_in(temp.path(), "missing").is_none());
+        fs::write(get_spec_path(temp.path(), "corrupt"), "{not json").expect("write corrupt");
+        assert!(load_spec_in(temp.path(), "corrupt").is_none());
+    }
+
+    #[test]
+    fn test_scrollback_replay_with_restore_marker() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        save_scrollback_in(temp.path(), "s1", &VecDeque::from(b"$ ls\r\n".to_vec()))
+            .expect("save scrollback");
+        let mut buffer = load_scrollback_in(temp.path(), "s1").expect("load scrollback");
+        let marker = restore_marker(chrono::Local::now());
+        append_banner(&mut buffer, marker.as_bytes());
+        let replay: Vec<u8> = buffer.into_iter().collect();
+        assert!(replay.starts_with(b"$ ls\r\n"));
+        assert!(replay.ends_with(marker.as_bytes()));
+        assert!(marker.contains("session restored"));
+        assert!(load_scrollback_in(temp.path(), "missing").is_none());
+
+        // A full buffer keeps the marker and drops the oldest output
+        let mut full = VecDeque::from(vec![b'x'; SCROLLBACK_MAX_BYTES]);
+        append_banner(&mut full, marker.as_bytes());
+        assert_eq!(full.len(), SCROLLBACK_MAX_BYTES);
+        let replay: Vec<u8> = full.into_iter().collect();
+        assert!(replay.ends_with(marker.as_bytes()));
+    }
+
+    #[test]
+    fn test_exit_status() {
+        let exited = PtyExitStatus::from(&portable_pty::ExitStatus::with_exit_code(1));
+        assert_eq!((exited.exit_code, exited.signal.as_deref()), (Some(1), None));
+        let killed = PtyExitStatus::from(&portable_pty::ExitStatus::with_signal("Killed"));
+        assert_eq!(killed.signal.as_deref(), Some("Killed"));
+        assert_eq!(
+            serde_json::to_value(PtyExitStatus::from(
+                &portable_pty::ExitStatus::with_exit_code(0)
+            ))
+            .expect("serialize"),
+            serde_json::json!({ "exit_code": 0, "signal": null })
+        );
+
+        let id = "test-exit-status";
+        PTY_META
+            .lock()
+            .expect("meta lock")
+            .insert(id.to_string(), SessionMeta::new("/tmp".to_string(), "sh".to_string()));
+        assert!(get_pty_exit_status(id).expect("running").is_none());
+        if let Some(entry) = PTY_META.lock().expect("meta lock").get_mut(id) {
+            entry.exit = Some(exited);
+        }
+        let status = get_pty_exit_status(id).expect("exited").expect("status");
+        assert_eq!(status.exit_code, Some(1));
+        kill_session(id).expect("kill");
+
+        let err = get_pty_exit_status("test-exit-status-unknown").expect_err("unknown id");
+        assert_eq!(err.code(), ErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_session_inventory() {
+        let (older, newer) = ("test-inventory-older", "test-inventory-newer");
+        {
+            let mut meta = PTY_META.lock().expect("meta lock");
+            let mut entry = SessionMeta::new("/tmp/a".to_string(), "zsh".to_string());
+            entry.last_activity = 1_000;
+            entry.running = false;
+            meta.insert(older.to_string(), entry);
+            let mut entry = SessionMeta::new("/tmp/b".to_string(), "claude".to_string());
+            entry.last_activity = 2_000;
+            meta.insert(newer.to_string(), entry);
+        }
+        PTY_SESSIONS.lock().expect("sessions lock").insert(
+            newer.to_string(),
+            Arc::new(Mutex::new(SessionIO {
+                writer: Box::new(std::io::sink()),
+            })),
+        );
+
+        let listed: Vec<PtySessionInfo> = list_session_info()
+            .into_iter()
+            .filter(|s| s.id == older || s.id == newer)
+            .collect();
+        let fields: Vec<(&str, &str, bool, &str, i64)> = listed
+            .iter()
+            .map(|s| {
+                (
+                    s.id.as_str(),
+                    s.cwd.as_str(),
+                    s.running,
+                    s.title.as_str(),
+                    s.last_activity,
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (newer, "/tmp/b", true, "claude", 2_000),
+                (older, "/tmp/a", false, "zsh", 1_000),
+            ]
+        );
+        assert!(session_alive(newer));
+        assert!(!session_alive(older));
+        assert!(!session_alive("test-inventory-missing"));
+
+        kill_session(older).expect("kill older");
+        kill_session(newer).expect("kill newer");
+        assert!(!session_alive(newer));
+        assert!(list_session_info().iter().all(|s| s.id != older && s.id != newer));
+    }
+
+    #[test]
+    fn test_next_batch_coalesces_in_order() {
+        let (tx, rx) = mpsc::channel();
+        for chunk in [&b"ab"[..], b"cd", b"ef"] {
+            tx.send(chunk.to_vec()).expect("send chunk");
+        }
+        let window = Duration::from_millis(20);
+        assert_eq!(next_batch(&rx, window).as_deref(), Some(&b"abcdef"[..]));
+
+        // No window: each chunk is its own batch
+        tx.send(b"g".to_vec()).expect("send chunk");
+        tx.send(b"h".to_vec()).expect("send chunk");
+        assert_eq!(next_batch(&rx, Duration::ZERO).as_deref(), Some(&b"g"[..]));
+
+        // Full batches go out early; the rest follows after the reader hangs up
+        tx.send(vec![0; MAX_BATCH_BYTES]).expect("send chunk");
+        tx.send(b"i".to_vec()).expect("send chunk");
+        drop(tx);
+        assert_eq!(next_batch(&rx, window).map(|b| b.len()), Some(1 + MAX_BATCH_BYTES));
+        assert_eq!(next_batch(&rx, window).as_deref(), Some(&b"i"[..]));
+        assert_eq!(next_batch(&rx, window), None);
+    }
+}
@@ -0,0 +1,152 @@
+/**
+ * [INPUT]: 依赖 rayon (有界线程池)
+ * [OUTPUT]: 对外提供 install (在扫描线程池中运行), project_dirs (并行遍历 ~/.claude/projects), ProjectDir
+ * [POS]: src-tauri/src 的并行扫描基础设施，被 build_search_index / 报告缓存刷新共用
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Upper bound on scan threads, so a cold scan doesn't starve the UI and PTY threads
+const MAX_SCAN_THREADS: usize = 8;
+
+/// Shared by every scan instead of rayon's global pool, which sizes itself to all cores
+static SCAN_POOL: LazyLock<Option<ThreadPool>> = LazyLock::new(|| {
+    let threads = std::thread::available_parallelism()
+        .map_or(4, std::num::NonZeroUsize::get)
+        .min(MAX_SCAN_THREADS);
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("lovcode-scan-{}", i))
+        .build()
+    {
+        Ok(pool) => Some(pool),
+        Err(e) => {
+            tracing::warn!("Failed to start scan thread pool: {}", e);
+            None
+        }
+    }
+});
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// One directory under ~/.claude/projects and its session files
+pub struct ProjectDir {
+    /// Encoded project path (the directory name)
+    pub id: String,
+    /// Top-level *.jsonl files, sorted by path
+    pub sessions: Vec<PathBuf>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Run `op` on the scan pool so its rayon iterators use at most MAX_SCAN_THREADS.
+/// Runs on the calling thread (still parallel, on rayon's global pool) if the pool
+/// couldn't be created.
+pub fn install<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    match SCAN_POOL.as_ref() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// List project directories and their session files, reading directories in parallel.
+/// Subagent transcripts (`agent-*.jsonl`) are skipped unless `include_agents` is set.
+/// Projects are sorted by id.
+pub fn project_dirs(projects_dir: &Path, include_agents: bool) -> Vec<ProjectDir> {
+    let Ok(entries) = fs::read_dir(projects_dir) else {
+        return Vec::new();
+    };
+    let dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+
+    let mut projects: Vec<ProjectDir> = install(|| {
+        dirs.par_iter()
+            .map(|dir| {
+                let mut sessions: Vec<PathBuf> = fs::read_dir(dir)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        let name = path
+                            .file_name()
+                            .map(|n| n.to_string_lossy())
+                            .unwrap_or_default();
+                        name.ends_with(".jsonl")
+                            && (include_agents || !name.starts_with("agent-"))
+                            && path.is_file()
+                    })
+                    .collect();
+                sessions.sort();
+                ProjectDir {
+                    id: dir
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    sessions,
+                }
+            })
+            .collect()
+    });
+    projects.sort_by(|a, b| a.id.cmp(&b.id));
+    projects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_project_dirs_lists_sessions() {
+        let temp = TempDir::new().expect("temp dir");
+        for (project, file) in [
+            ("-work-b", "s2.jsonl"),
+            ("-work-b", "s1.jsonl"),
+            ("-work-b", "agent-1.jsonl"),
+            ("-work-b", "notes.txt"),
+            ("-work-a", "s3.jsonl"),
+        ] {
+            let dir = temp.path().join(project);
+            fs::create_dir_all(&dir).expect("project dir");
+            fs::write(dir.join(file), "").expect("session file");
+        }
+        fs::write(temp.path().join("stray.jsonl"), "").expect("stray file");
+
+        let names = |include_agents| {
+            project_dirs(temp.path(), include_agents)
+                .into_iter()
+                .map(|p| {
+                    let files: Vec<String> = p
+                        .sessions
+                        .iter()
+                        .filter_map(|s| s.file_name())
+                        .map(|n| n.to_string_lossy().to_string())
+                        .collect();
+                    (p.id, files.join(","))
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(false),
+            [
+                ("-work-a".to_string(), "s3.jsonl".to_string()),
+                ("-work-b".to_string(), "s1.jsonl,s2.jsonl".to_string()),
+            ]
+        );
+        assert_eq!(names(true)[1].1, "agent-1.jsonl,s1.jsonl,s2.jsonl");
+        assert!(project_dirs(&temp.path().join("missing"), false).is_empty());
+    }
+}
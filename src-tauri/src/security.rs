@@ -1,13 +1,13 @@
 /**
- * [INPUT]: 依赖 std::path, regex
- * [OUTPUT]: 对外提供 validate_path, validate_version, safe_home_dir
+ * [INPUT]: 依赖 std::path, regex, crate::workspace_store (项目根目录)
+ * [OUTPUT]: 对外提供 validate_path, validate_user_path, user_path_roots, validate_version, safe_home_dir, create_private_dir/make_private (仅本人可访问)
  * [POS]: src-tauri/src 的安全验证核心，防止路径遍历和命令注入
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
 use crate::errors::{AppError, AppResult};
 use regex::Regex;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::LazyLock;
 
 // ============================================================================
@@ -136,6 +136,62 @@ pub fn validate_decoded_path(path: &str) -> AppResult<String> {
     Ok(path.to_string())
 }
 
+/// 解析路径的真实位置 (跟随符号链接)
+/// 目标不存在时 (如待写入的新文件)，规范化最近的已存在祖先目录再拼接剩余部分
+fn resolve_real_path(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(real) = ancestor.canonicalize() {
+            let rest = path.strip_prefix(ancestor).unwrap_or_else(|_| Path::new(""));
+            return real.join(rest);
+        }
+    }
+    path.to_path_buf()
+}
+
+/// 验证路径位于给定根目录之一内 (validate_user_path 的核心，便于测试)
+///
+/// # 安全检查
+/// 1. 必须是绝对路径，且不含 ".." 组件
+/// 2. 跟随符号链接后的真实位置必须仍在某个根目录内
+pub fn validate_path_within(path: &str, roots: &[PathBuf]) -> AppResult<PathBuf> {
+    let raw = Path::new(path);
+    if !raw.is_absolute() {
+        return Err(AppError::PathTraversal {
+            reason: "路径必须是绝对路径".to_string(),
+        });
+    }
+    if raw.components().any(|c| c == Component::ParentDir) {
+        return Err(AppError::PathTraversal {
+            reason: "路径包含 '..' 组件".to_string(),
+        });
+    }
+
+    let real = resolve_real_path(raw);
+    let is_allowed = roots.iter().any(|root| {
+        real.starts_with(root) || root.canonicalize().is_ok_and(|r| real.starts_with(r))
+    });
+    if !is_allowed {
+        return Err(AppError::PathTraversal {
+            reason: format!("路径不在允许的目录范围内: {}", path),
+        });
+    }
+    Ok(raw.to_path_buf())
+}
+
+/// 验证前端传入的读写路径 (read_file / write_file / write_binary_file)
+///
+/// 允许范围: 用户主目录、/tmp (及系统临时目录)、工作区中登记的项目根目录
+pub fn validate_user_path(path: &str) -> AppResult<PathBuf> {
+    validate_path_within(path, &user_path_roots()?)
+}
+
+/// validate_user_path 的允许根目录: 用户主目录、/tmp、系统临时目录、工作区项目根目录
+pub fn user_path_roots() -> AppResult<Vec<PathBuf>> {
+    let mut roots = vec![safe_home_dir()?, PathBuf::from("/tmp"), std::env::temp_dir()];
+    roots.extend(crate::workspace_store::project_roots());
+    Ok(roots)
+}
+
 // ============================================================================
 // Shell 命令安全 (防止命令注入)
 // ============================================================================
@@ -231,6 +287,34 @@ pub fn atomic_write_string(path: &PathBuf, content: &str) -> AppResult<()> {
     atomic_write(path, content.as_bytes())
 }
 
+// ============================================================================
+// 私有文件权限 (保护含密钥的副本)
+// ============================================================================
+
+/// 创建仅当前用户可访问的目录 (0700)，已存在的目录也会被收紧
+/// 用于存放 ~/.claude.json 快照、备份等可能含 API key 的副本
+pub fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// 把文件收紧为仅当前用户可读写 (0600)
+pub fn make_private(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
 // ============================================================================
 // 单元测试
 // ============================================================================
@@ -525,6 +609,40 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_path_within_roots() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let allowed = temp.path().join("project");
+        fs::create_dir_all(allowed.join("src")).expect("create project");
+        let roots = vec![allowed.clone()];
+        let inside = allowed.join("src").join("main.rs");
+
+        assert!(validate_path_within(&inside.to_string_lossy(), &roots).is_ok());
+        // 尚不存在的写入目标
+        let new_file = allowed.join("new").join("file.txt");
+        assert!(validate_path_within(&new_file.to_string_lossy(), &roots).is_ok());
+
+        let outside = temp.path().join("other.txt");
+        assert!(validate_path_within(&outside.to_string_lossy(), &roots).is_err());
+        let traversal = format!("{}/src/../../other.txt", allowed.display());
+        assert!(validate_path_within(&traversal, &roots).is_err());
+        assert!(validate_path_within("relative/path.txt", &roots).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_path_within_rejects_symlink_escape() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let allowed = temp.path().join("project");
+        let secret = temp.path().join("secret");
+        fs::create_dir_all(&allowed).expect("create project");
+        fs::create_dir_all(&secret).expect("create secret");
+        std::os::unix::fs::symlink(&secret, allowed.join("link")).expect("create symlink");
+
+        let escaped = allowed.join("link").join("key.pem");
+        assert!(validate_path_within(&escaped.to_string_lossy(), &[allowed]).is_err());
+    }
+
     #[test]
     fn test_validate_version_edge_cases() {
         // 边界版本号
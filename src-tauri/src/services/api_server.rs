@@ -0,0 +1,511 @@
+/**
+ * [INPUT]: 依赖 axum (HTTP 路由), tokio (监听与优雅关闭), crate::commands (projects/sessions/report), crate::services::search, crate::workspace_store, crate::security
+ * [OUTPUT]: 对外提供 start_api_server 启动入口, get_api_server_status, set_api_server_settings, regenerate_api_server_token 命令与 ApiServerSettings/ApiServerStatus
+ * [POS]: services/ 模块的本地 REST API，可选开启，仅监听 127.0.0.1 并校验 Bearer token，只读暴露会话、搜索、统计与工作区数据供脚本调用
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::report::{ActivityStats, ModelUsage, ReportPeriod};
+use crate::commands::{
+    get_activity_stats, get_model_usage_stats, get_session_messages, list_all_sessions,
+    list_projects, list_sessions,
+};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use crate::services::search_chats;
+use crate::types::{Message, Project, SearchResult, Session};
+use crate::workspace_store::{self, WorkspaceData};
+use axum::extract::{Path as UrlPath, Query, Request};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::oneshot;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token every request except /api/health must carry; generated on first enable
+    pub token: String,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    /// e.g. "http://127.0.0.1:51217/api"; None while stopped
+    pub url: Option<String>,
+    pub token: String,
+    /// Why the server isn't running although enabled, e.g. the port is taken
+    pub error: Option<String>,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    running: Option<RunningServer>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<usize>,
+    project_id: Option<String>,
+    record: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionsQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    sort_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesQuery {
+    include_thinking: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActivityQuery {
+    utc_offset_minutes: Option<i32>,
+}
+
+/// Unix milliseconds; either end may be left open
+#[derive(Debug, Deserialize)]
+struct PeriodQuery {
+    start: Option<u64>,
+    end: Option<u64>,
+}
+
+/// An AppError answered with a matching HTTP status and the usual { code, message, context } body
+struct ApiError(AppError);
+
+impl From<AppError> for ApiError {
+    fn from(err: AppError) -> Self {
+        Self(err)
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(AppError::from(message))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (status_for(self.0.code()), Json(self.0)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Above the dev server's 51216
+const DEFAULT_PORT: u16 = 51217;
+
+static SETTINGS: LazyLock<Mutex<ApiServerSettings>> =
+    LazyLock::new(|| Mutex::new(load_settings(&settings_path())));
+
+static SERVER: LazyLock<Mutex<ServerState>> = LazyLock::new(|| Mutex::new(ServerState::default()));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn settings_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("api_server.json")
+}
+
+fn load_settings(path: &Path) -> ApiServerSettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The file holds the token, so it and its directory are kept owner-only
+fn save_settings(path: &Path, settings: &ApiServerSettings) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        security::create_private_dir(parent)
+            .map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content)?;
+    security::make_private(path).map_err(|e| AppError::io("Failed to restrict settings", &e))
+}
+
+fn new_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn status_for(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::NotFound => StatusCode::NOT_FOUND,
+        ErrorCode::PermissionDenied => StatusCode::FORBIDDEN,
+        ErrorCode::InvalidInput | ErrorCode::Parse => StatusCode::BAD_REQUEST,
+        ErrorCode::Conflict => StatusCode::CONFLICT,
+        ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Io | ErrorCode::Network | ErrorCode::Internal => {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Ids from the URL become path components under ~/.claude/projects
+fn check_id(value: &str) -> AppResult<()> {
+    if value.is_empty() || value == "." || value == ".." || value.contains(['/', '\\', '\0']) {
+        return Err(AppError::invalid_input("Invalid id").with_context(value));
+    }
+    Ok(())
+}
+
+/// Whether the request carries `Authorization: Bearer <token>`; compares in constant time
+fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    let Some(given) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// 401 with a Bearer challenge (RFC 6750) for a missing or wrong token
+fn unauthorized() -> Response {
+    let error = AppError::new(ErrorCode::PermissionDenied, "Missing or invalid bearer token");
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(error),
+    )
+        .into_response()
+}
+
+/// Reads the current token per request, so a new one applies without a restart
+async fn require_token(request: Request, next: Next) -> Response {
+    let allowed = SETTINGS
+        .lock()
+        .is_ok_and(|settings| authorized(request.headers(), &settings.token));
+    if allowed {
+        next.run(request).await
+    } else {
+        unauthorized()
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+async fn projects() -> ApiResult<Vec<Project>> {
+    Ok(Json(list_projects().await?))
+}
+
+async fn project_sessions(UrlPath(project_id): UrlPath<String>) -> ApiResult<Vec<Session>> {
+    check_id(&project_id)?;
+    Ok(Json(list_sessions(project_id).await?))
+}
+
+async fn all_sessions(Query(query): Query<SessionsQuery>) -> ApiResult<Vec<Session>> {
+    let page = list_all_sessions(query.limit, query.offset, query.sort_by).await?;
+    Ok(Json(page.items))
+}
+
+async fn session_messages(
+    UrlPath((project_id, session_id)): UrlPath<(String, String)>,
+    Query(query): Query<MessagesQuery>,
+) -> ApiResult<Vec<Message>> {
+    check_id(&project_id)?;
+    check_id(&session_id)?;
+    Ok(Json(
+        get_session_messages(project_id, session_id, query.include_thinking).await?,
+    ))
+}
+
+async fn search(Query(query): Query<SearchQuery>) -> ApiResult<Vec<SearchResult>> {
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        search_chats(query.q, query.limit, query.project_id, query.record)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    Ok(Json(results))
+}
+
+async fn activity_stats(Query(query): Query<ActivityQuery>) -> ApiResult<ActivityStats> {
+    Ok(Json(get_activity_stats(query.utc_offset_minutes).await?))
+}
+
+async fn model_stats(Query(query): Query<PeriodQuery>) -> ApiResult<Vec<ModelUsage>> {
+    let period = (query.start.is_some() || query.end.is_some()).then(|| ReportPeriod {
+        start: query.start.unwrap_or(0),
+        end: query.end.unwrap_or(u64::MAX),
+    });
+    Ok(Json(get_model_usage_stats(period).await?))
+}
+
+async fn workspace() -> ApiResult<WorkspaceData> {
+    let data = tauri::async_runtime::spawn_blocking(workspace_store::load_workspace)
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(Json(data))
+}
+
+fn router() -> Router {
+    Router::new()
+        .route("/api/projects", get(projects))
+        .route("/api/projects/{project_id}/sessions", get(project_sessions))
+        .route("/api/sessions", get(all_sessions))
+        .route(
+            "/api/sessions/{project_id}/{session_id}/messages",
+            get(session_messages),
+        )
+        .route("/api/search", get(search))
+        .route("/api/stats/activity", get(activity_stats))
+        .route("/api/stats/models", get(model_stats))
+        .route("/api/workspace", get(workspace))
+        .route_layer(middleware::from_fn(require_token))
+        // Registered after the layer, so it answers without a token
+        .route("/api/health", get(health))
+}
+
+// ============================================================================
+// Server Lifecycle
+// ============================================================================
+
+fn stop(state: &mut ServerState) {
+    if let Some(server) = state.running.take() {
+        let _ = server.shutdown.send(());
+        tracing::info!(port = server.port, "API server stopped");
+    }
+}
+
+/// Bind synchronously so a taken port is reported to the caller, then serve in the background
+fn start(state: &mut ServerState, settings: &ApiServerSettings) -> Result<(), String> {
+    stop(state);
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, settings.port)))
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+        .map_err(|e| format!("Failed to listen on 127.0.0.1:{}: {}", settings.port, e))?;
+    let app = router();
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    let port = settings.port;
+    tauri::async_runtime::spawn(async move {
+        let served = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                })
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = served {
+            tracing::warn!(port, error = %e, "API server stopped with an error");
+        }
+    });
+    state.running = Some(RunningServer { port, shutdown });
+    tracing::info!(port, "API server listening on 127.0.0.1");
+    Ok(())
+}
+
+/// Start, restart or stop the server to match `settings`
+fn apply(settings: &ApiServerSettings) -> Result<(), String> {
+    let mut state = SERVER.lock().map_err(|_| "API server lock poisoned")?;
+    state.error = None;
+    if !settings.enabled {
+        stop(&mut state);
+        return Ok(());
+    }
+    if state
+        .running
+        .as_ref()
+        .is_some_and(|server| server.port == settings.port)
+    {
+        return Ok(());
+    }
+    let started = start(&mut state, settings);
+    if let Err(e) = &started {
+        state.error = Some(e.clone());
+    }
+    started
+}
+
+fn status(settings: &ApiServerSettings) -> ApiServerStatus {
+    let (port, error) = SERVER
+        .lock()
+        .map(|state| (state.running.as_ref().map(|s| s.port), state.error.clone()))
+        .unwrap_or_default();
+    ApiServerStatus {
+        enabled: settings.enabled,
+        running: port.is_some(),
+        port: settings.port,
+        url: port.map(|port| format!("http://127.0.0.1:{}/api", port)),
+        token: settings.token.clone(),
+        error,
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Start the server if the user turned it on; called once at startup
+pub fn start_api_server() {
+    let settings = match SETTINGS.lock() {
+        Ok(settings) => settings.clone(),
+        Err(_) => return,
+    };
+    if let Err(e) = apply(&settings) {
+        tracing::warn!(error = %e, "Failed to start API server");
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_api_server_status() -> AppResult<ApiServerStatus> {
+    let settings = SETTINGS.lock().map_err(|_| "API server lock poisoned")?;
+    Ok(status(&settings))
+}
+
+/// Turn the server on or off, optionally on another port. A port that can't be bound
+/// fails the call; the setting is kept and the error shows in the status.
+#[tauri::command]
+pub fn set_api_server_settings(enabled: bool, port: Option<u16>) -> AppResult<ApiServerStatus> {
+    if port.is_some_and(|port| port < 1024) {
+        return Err(AppError::invalid_input("Port must be 1024 or higher"));
+    }
+    let mut settings = SETTINGS.lock().map_err(|_| "API server lock poisoned")?;
+    let mut updated = settings.clone();
+    updated.enabled = enabled;
+    updated.port = port.unwrap_or(updated.port);
+    if updated.token.is_empty() {
+        updated.token = new_token();
+    }
+    save_settings(&settings_path(), &updated)?;
+    *settings = updated;
+    apply(&settings).map_err(|e| AppError::new(ErrorCode::Unavailable, e))?;
+    Ok(status(&settings))
+}
+
+/// Replace the token; clients using the old one are rejected from the next request
+#[tauri::command]
+pub fn regenerate_api_server_token() -> AppResult<ApiServerStatus> {
+    let mut settings = SETTINGS.lock().map_err(|_| "API server lock poisoned")?;
+    let mut updated = settings.clone();
+    updated.token = new_token();
+    save_settings(&settings_path(), &updated)?;
+    *settings = updated;
+    Ok(status(&settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_authorized_requires_exact_bearer_token_and_safe_ids() {
+        let token = "abc123";
+        let with = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::AUTHORIZATION,
+                HeaderValue::from_str(value).expect("header"),
+            );
+            headers
+        };
+        assert!(authorized(&with("Bearer abc123"), token));
+        assert!(!authorized(&with("Bearer abc124"), token));
+        assert!(!authorized(&with("Bearer abc"), token));
+        assert!(!authorized(&with("abc123"), token));
+        assert!(!authorized(&HeaderMap::new(), token));
+        // A server without a token accepts nothing
+        assert!(!authorized(&with("Bearer "), ""));
+
+        assert!(check_id("-Users-me-app").is_ok());
+        assert!(check_id("..").is_err());
+        assert!(check_id("../../etc").is_err());
+    }
+
+    #[test]
+    fn test_settings_and_status_codes() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("api_server.json");
+        assert_eq!(load_settings(&path), ApiServerSettings::default());
+        let settings = ApiServerSettings {
+            enabled: true,
+            port: 6000,
+            token: new_token(),
+        };
+        save_settings(&path, &settings).expect("save settings");
+        assert_eq!(load_settings(&path), settings);
+        assert_eq!(settings.token.len(), 64);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).expect("stat settings").permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert_eq!(status_for(ErrorCode::NotFound), StatusCode::NOT_FOUND);
+        assert_eq!(status_for(ErrorCode::InvalidInput), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            status_for(ErrorCode::PermissionDenied),
+            StatusCode::FORBIDDEN
+        );
+
+        let response = unauthorized();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers().get(header::WWW_AUTHENTICATE).and_then(|v| v.to_str().ok()),
+            Some("Bearer")
+        );
+    }
+}
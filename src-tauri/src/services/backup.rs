@@ -0,0 +1,686 @@
+/**
+ * [INPUT]: 依赖 tar + flate2 (tar.gz 归档), chrono, serde, commands::file_diff (恢复预览), commands::file_trash (删除多余文件), services::claude_json_history (标注恢复来源), crate::security
+ * [OUTPUT]: 对外提供 create_claude_backup, list_claude_backups, restore_claude_backup, delete_claude_backup 命令与 ClaudeBackup/BackupRestore 类型
+ * [POS]: services/ 模块的 Claude 配置备份服务，整体快照 ~/.claude 的 settings/commands/agents/skills/hooks 与 ~/.claude.json，恢复前可预览 diff
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::file_diff::unified_diff;
+use crate::commands::file_trash;
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use crate::services::claude_json_history;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A snapshot of the user's Claude configuration, stored as
+/// ~/.lovstudio/lovcode/backups/<id>.tar.gz with an <id>.json manifest next to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeBackup {
+    pub id: String,
+    pub label: String,
+    pub created_at: u64,
+    pub include_projects: bool,
+    /// Home-relative paths covered by the backup; restoring makes them match the archive
+    pub roots: Vec<String>,
+    pub file_count: usize,
+    /// Archive size in bytes
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupChangeKind {
+    /// In the backup but missing on disk
+    Added,
+    Modified,
+    /// On disk under a covered path but not in the backup
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupChange {
+    /// Home-relative, e.g. .claude/commands/review.md
+    pub path: String,
+    pub kind: BackupChangeKind,
+    /// Unified diff from the current file to the backup's (small text files only)
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupRestore {
+    pub backup: ClaudeBackup,
+    /// False for a dry run (or when nothing differs)
+    pub applied: bool,
+    pub changes: Vec<BackupChange>,
+    pub unchanged: usize,
+    /// Backup of the state just before the restore, so it can be undone
+    pub safety_backup_id: Option<String>,
+}
+
+/// Home-relative files and directories that make up the Claude configuration
+const BACKUP_ROOTS: [&str; 10] = [
+    ".claude/settings.json",
+    ".claude/settings.local.json",
+    ".claude/CLAUDE.md",
+    ".claude/commands",
+    ".claude/agents",
+    ".claude/skills",
+    ".claude/hooks",
+    ".claude/output-styles",
+    ".claude/statusline.sh",
+    ".claude.json",
+];
+
+/// Session transcripts; usually far larger than everything else combined
+const PROJECTS_ROOT: &str = ".claude/projects";
+
+/// Larger files are listed as changed without a diff
+const MAX_DIFF_BYTES: usize = 256 * 1024;
+
+/// Serializes backup creation, restore and deletion
+static BACKUP_LOCK: Mutex<()> = Mutex::new(());
+
+/// File content as archived: regular file bytes or a symlink target
+#[derive(PartialEq)]
+enum Content {
+    File(Vec<u8>),
+    Link(PathBuf),
+}
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn backups_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("backups")
+}
+
+fn archive_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.tar.gz", id))
+}
+
+fn manifest_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+/// Ids are generated timestamps; anything else could escape the backups directory
+fn validate_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid backup id: {}", id));
+    }
+    Ok(())
+}
+
+/// Local timestamp, suffixed when two backups land in the same second
+fn next_id(dir: &Path) -> String {
+    let base = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut id = base.clone();
+    let mut n = 2;
+    while archive_path(dir, &id).exists() || manifest_path(dir, &id).exists() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+fn load_backup(dir: &Path, id: &str) -> AppResult<ClaudeBackup> {
+    validate_id(id).map_err(AppError::invalid_input)?;
+    let content = fs::read_to_string(manifest_path(dir, id))
+        .map_err(|_| AppError::not_found(format!("Backup '{}' not found", id)))?;
+    serde_json::from_str(&content).map_err(|e| {
+        AppError::new(
+            ErrorCode::Parse,
+            format!("Failed to parse backup manifest {}: {}", id, e),
+        )
+    })
+}
+
+fn list_in(dir: &Path) -> Vec<ClaudeBackup> {
+    let mut backups: Vec<ClaudeBackup> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|p| fs::read_to_string(p).ok())
+                .filter_map(|s| serde_json::from_str::<ClaudeBackup>(&s).ok())
+                .filter(|b| archive_path(dir, &b.id).exists())
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    backups
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+// ============================================================================
+// Archive
+// ============================================================================
+
+/// Files and symlinks at or below `rel`, home-relative with `/` separators
+fn collect_files(home: &Path, rel: &str, out: &mut Vec<String>) {
+    let path = home.join(rel);
+    let Ok(meta) = fs::symlink_metadata(&path) else {
+        return;
+    };
+    if !meta.is_dir() {
+        out.push(rel.to_string());
+        return;
+    }
+    let Ok(entries) = fs::read_dir(&path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = format!("{}/{}", rel, entry.file_name().to_string_lossy());
+        collect_files(home, &child, out);
+    }
+}
+
+fn files_under(home: &Path, roots: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
+    for root in roots {
+        collect_files(home, root, &mut files);
+    }
+    files.sort();
+    files
+}
+
+fn is_covered(path: &str, roots: &[String]) -> bool {
+    roots.iter().any(|root| {
+        path == root
+            || path
+                .strip_prefix(root.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Write `files` into a tar.gz at `archive`; returns how many were archived
+fn write_archive(home: &Path, archive: &Path, files: &[String]) -> Result<usize, String> {
+    let file = fs::File::create(archive)
+        .map_err(|e| format!("Failed to create {}: {}", archive.display(), e))?;
+    // Restricted before anything is written: the archive holds credentials and transcripts
+    security::make_private(archive)
+        .map_err(|e| format!("Failed to restrict {}: {}", archive.display(), e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder.follow_symlinks(false);
+
+    let mut count = 0;
+    for rel in files {
+        let path = home.join(rel);
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue; // deleted since the scan
+        };
+        let result = if meta.file_type().is_symlink() {
+            builder.append_path_with_name(&path, rel)
+        } else {
+            // Read up front: a transcript growing mid-copy would corrupt the tar entry
+            let Ok(data) = fs::read(&path) else {
+                continue;
+            };
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata(&meta);
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, rel, data.as_slice())
+        };
+        result.map_err(|e| format!("Failed to archive {}: {}", rel, e))?;
+        count += 1;
+    }
+
+    builder
+        .into_inner()
+        .and_then(GzEncoder::finish)
+        .map_err(|e| format!("Failed to write {}: {}", archive.display(), e))?;
+    Ok(count)
+}
+
+fn open_archive(archive: &Path) -> Result<tar::Archive<GzDecoder<fs::File>>, String> {
+    let file = fs::File::open(archive)
+        .map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+fn read_current(path: &Path) -> Option<Content> {
+    let meta = fs::symlink_metadata(path).ok()?;
+    if meta.file_type().is_symlink() {
+        fs::read_link(path).ok().map(Content::Link)
+    } else if meta.is_file() {
+        fs::read(path).ok().map(Content::File)
+    } else {
+        None
+    }
+}
+
+fn diffable_text(content: Option<&Content>) -> Option<&str> {
+    match content {
+        None => Some(""),
+        Some(Content::File(data)) if data.len() <= MAX_DIFF_BYTES => std::str::from_utf8(data).ok(),
+        Some(_) => None,
+    }
+}
+
+/// Diff for text files small enough to show; `None` on either side means missing
+fn content_diff(rel: &str, current: Option<&Content>, backup: Option<&Content>) -> Option<String> {
+    Some(unified_diff(
+        diffable_text(current)?,
+        diffable_text(backup)?,
+        &format!("current/{}", rel),
+        &format!("backup/{}", rel),
+    ))
+}
+
+// ============================================================================
+// Backup & Restore
+// ============================================================================
+
+fn create_in(
+    home: &Path,
+    dir: &Path,
+    label: &str,
+    roots: Vec<String>,
+) -> Result<ClaudeBackup, String> {
+    security::create_private_dir(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let files = files_under(home, &roots);
+    let id = next_id(dir);
+    let archive = archive_path(dir, &id);
+    let file_count = write_archive(home, &archive, &files).inspect_err(|_| {
+        let _ = fs::remove_file(&archive);
+    })?;
+
+    let backup = ClaudeBackup {
+        id,
+        label: label.trim().to_string(),
+        created_at: now_secs(),
+        include_projects: roots.iter().any(|r| r == PROJECTS_ROOT),
+        roots,
+        file_count,
+        size: fs::metadata(&archive).map_or(0, |m| m.len()),
+    };
+    let json = serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&manifest_path(dir, &backup.id), &json)
+        .map_err(|e| e.to_string())?;
+    Ok(backup)
+}
+
+/// Compare the archive with what's on disk, without touching anything
+fn plan_restore(
+    home: &Path,
+    archive: &Path,
+    roots: &[String],
+) -> Result<(Vec<BackupChange>, usize), String> {
+    let mut archive = open_archive(archive)?;
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    let mut archived = HashSet::new();
+
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read backup: {}", e))?;
+        let rel = entry
+            .path()
+            .map_err(|e| format!("Failed to read backup: {}", e))?
+            .to_string_lossy()
+            .to_string();
+        if !is_covered(&rel, roots) {
+            continue;
+        }
+        let backup = if entry.header().entry_type().is_symlink() {
+            Content::Link(
+                entry
+                    .link_name()
+                    .ok()
+                    .flatten()
+                    .map(std::borrow::Cow::into_owned)
+                    .unwrap_or_default(),
+            )
+        } else {
+            let mut data = Vec::new();
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Failed to read {} from backup: {}", rel, e))?;
+            Content::File(data)
+        };
+
+        match read_current(&home.join(&rel)) {
+            Some(current) if current == backup => unchanged += 1,
+            current => changes.push(BackupChange {
+                diff: content_diff(&rel, current.as_ref(), Some(&backup)),
+                kind: if current.is_some() {
+                    BackupChangeKind::Modified
+                } else {
+                    BackupChangeKind::Added
+                },
+                path: rel.clone(),
+            }),
+        }
+        archived.insert(rel);
+    }
+
+    for rel in files_under(home, roots) {
+        if archived.contains(&rel) {
+            continue;
+        }
+        let path = home.join(&rel);
+        let small = fs::symlink_metadata(&path).is_ok_and(|m| m.len() <= MAX_DIFF_BYTES as u64);
+        let current = if small { read_current(&path) } else { None };
+        changes.push(BackupChange {
+            diff: current
+                .as_ref()
+                .and_then(|c| content_diff(&rel, Some(c), None)),
+            kind: BackupChangeKind::Removed,
+            path: rel,
+        });
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((changes, unchanged))
+}
+
+/// Write the added/modified entries back and remove files the backup didn't have
+fn apply_restore(
+    home: &Path,
+    archive: &Path,
+    changes: &[BackupChange],
+    remove: &dyn Fn(&Path) -> Result<(), String>,
+) -> Result<(), String> {
+    let to_write: HashSet<&str> = changes
+        .iter()
+        .filter(|c| c.kind != BackupChangeKind::Removed)
+        .map(|c| c.path.as_str())
+        .collect();
+
+    let mut archive = open_archive(archive)?;
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read backup: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read backup: {}", e))?;
+        let rel = entry
+            .path()
+            .map_err(|e| format!("Failed to read backup: {}", e))?
+            .to_string_lossy()
+            .to_string();
+        if !to_write.contains(rel.as_str()) {
+            continue;
+        }
+        // unpack_in refuses paths that would land outside `home`
+        entry
+            .unpack_in(home)
+            .map_err(|e| format!("Failed to restore {}: {}", rel, e))?;
+    }
+
+    for change in changes {
+        if change.kind == BackupChangeKind::Removed {
+            remove(&home.join(&change.path))?;
+        }
+    }
+    Ok(())
+}
+
+fn restore_in(
+    home: &Path,
+    dir: &Path,
+    id: &str,
+    dry_run: bool,
+    remove: &dyn Fn(&Path) -> Result<(), String>,
+) -> AppResult<BackupRestore> {
+    let backup = load_backup(dir, id)?;
+    let archive = archive_path(dir, id);
+    if !archive.exists() {
+        return Err(AppError::not_found(format!(
+            "Backup archive not found: {}",
+            archive.display()
+        )));
+    }
+
+    let (changes, unchanged) = plan_restore(home, &archive, &backup.roots)
+        .map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    if dry_run || changes.is_empty() {
+        return Ok(BackupRestore {
+            backup,
+            applied: false,
+            changes,
+            unchanged,
+            safety_backup_id: None,
+        });
+    }
+
+    // Snapshot the same paths first so the restore itself can be undone
+    let safety = create_in(
+        home,
+        dir,
+        &format!("Before restoring {}", backup.id),
+        backup.roots.clone(),
+    )
+    .map_err(|e| AppError::new(ErrorCode::Io, e))?;
+    apply_restore(home, &archive, &changes, remove).map_err(|e| AppError::new(ErrorCode::Io, e))?;
+
+    Ok(BackupRestore {
+        backup,
+        applied: true,
+        changes,
+        unchanged,
+        safety_backup_id: Some(safety.id),
+    })
+}
+
+fn default_roots(include_projects: bool) -> Vec<String> {
+    let mut roots: Vec<String> = BACKUP_ROOTS.iter().map(|r| (*r).to_string()).collect();
+    if include_projects {
+        roots.push(PROJECTS_ROOT.to_string());
+    }
+    roots
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Archive ~/.claude settings, commands, agents, skills, hooks and ~/.claude.json.
+/// Session transcripts (~/.claude/projects) are only included when asked for.
+#[tauri::command]
+pub async fn create_claude_backup(
+    label: String,
+    include_projects: Option<bool>,
+) -> AppResult<ClaudeBackup> {
+    let home = security::safe_home_dir()?;
+    let roots = default_roots(include_projects.unwrap_or(false));
+    tauri::async_runtime::spawn_blocking(move || {
+        let _guard = BACKUP_LOCK.lock().map_err(|_| "Backup lock poisoned")?;
+        // Creating a backup only fails on file system errors
+        create_in(&home, &backups_dir(), &label, roots).map_err(|e| AppError::new(ErrorCode::Io, e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Backups, newest first
+#[tauri::command]
+pub fn list_claude_backups() -> AppResult<Vec<ClaudeBackup>> {
+    Ok(list_in(&backups_dir()))
+}
+
+/// Preview (`dry_run`, the default) or apply a backup. Applying first backs up the
+/// current state; its id is returned so the UI can offer to undo.
+#[tauri::command]
+pub async fn restore_claude_backup(id: String, dry_run: Option<bool>) -> AppResult<BackupRestore> {
+    validate_id(&id).map_err(AppError::invalid_input)?;
+    let home = security::safe_home_dir()?;
+    let dry_run = dry_run.unwrap_or(true);
+    let restore = tauri::async_runtime::spawn_blocking(move || {
+        let _guard = BACKUP_LOCK.lock().map_err(|_| "Backup lock poisoned")?;
+        restore_in(
+            &home,
+            &backups_dir(),
+            &id,
+            dry_run,
+            &file_trash::remove_path,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if restore.applied && restore.changes.iter().any(|c| c.path == ".claude.json") {
+        claude_json_history::record_lovcode_change(format!(
+            "Restored backup {}",
+            restore.backup.id
+        ));
+    }
+    Ok(restore)
+}
+
+#[tauri::command]
+pub fn delete_claude_backup(id: String) -> AppResult<()> {
+    validate_id(&id).map_err(AppError::invalid_input)?;
+    let dir = backups_dir();
+    let _guard = BACKUP_LOCK.lock().map_err(|_| "Backup lock poisoned")?;
+    let manifest = manifest_path(&dir, &id);
+    if !manifest.exists() {
+        return Err(AppError::not_found(format!("Backup '{}' not found", id)));
+    }
+    let archive = archive_path(&dir, &id);
+    if archive.exists() {
+        fs::remove_file(&archive).map_err(|e| AppError::io("Failed to delete backup", &e))?;
+    }
+    fs::remove_file(&manifest).map_err(|e| AppError::io("Failed to delete backup", &e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(home: &Path, rel: &str, content: &str) {
+        let path = home.join(rel);
+        fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+        fs::write(path, content).expect("write");
+    }
+
+    fn delete(path: &Path) -> Result<(), String> {
+        fs::remove_file(path).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_backup_excludes_projects_by_default() {
+        let temp = TempDir::new().expect("create temp dir");
+        let home = temp.path().join("home");
+        let dir = temp.path().join("backups");
+        write(&home, ".claude/settings.json", "{}");
+        write(&home, ".claude/commands/review.md", "# review");
+        write(&home, ".claude/projects/p/s.jsonl", "{}\n");
+        write(&home, ".claude.json", "{}");
+
+        let backup = create_in(&home, &dir, " nightly ", default_roots(false)).expect("backup");
+        assert_eq!(backup.label, "nightly");
+        assert_eq!(backup.file_count, 3);
+        assert!(!backup.include_projects);
+
+        let full = create_in(&home, &dir, "full", default_roots(true)).expect("backup");
+        assert_ne!(full.id, backup.id);
+        assert_eq!(full.file_count, 4);
+
+        let listed = list_in(&dir);
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().any(|b| b.include_projects));
+        assert!(load_backup(&dir, "../etc").is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| {
+                fs::metadata(path).expect("metadata").permissions().mode() & 0o777
+            };
+            assert_eq!(mode(&dir), 0o700);
+            assert_eq!(mode(&archive_path(&dir, &backup.id)), 0o600);
+        }
+    }
+
+    #[test]
+    fn test_restore_dry_run_then_apply() {
+        let temp = TempDir::new().expect("create temp dir");
+        let home = temp.path().join("home");
+        let dir = temp.path().join("backups");
+        write(
+            &home,
+            ".claude/settings.json",
+            "{\n  \"model\": \"opus\"\n}\n",
+        );
+        write(&home, ".claude/agents/planner.md", "plan\n");
+        write(&home, ".claude/projects/p/s.jsonl", "{}\n");
+        let backup = create_in(&home, &dir, "before", default_roots(false)).expect("backup");
+
+        write(
+            &home,
+            ".claude/settings.json",
+            "{\n  \"model\": \"haiku\"\n}\n",
+        );
+        fs::remove_file(home.join(".claude/agents/planner.md")).expect("remove");
+        write(&home, ".claude/agents/extra.md", "extra\n");
+        write(&home, ".claude/projects/p/t.jsonl", "{}\n");
+
+        let preview = restore_in(&home, &dir, &backup.id, true, &delete).expect("preview");
+        assert!(!preview.applied);
+        let kinds: Vec<(&str, BackupChangeKind)> = preview
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (".claude/agents/extra.md", BackupChangeKind::Removed),
+                (".claude/agents/planner.md", BackupChangeKind::Added),
+                (".claude/settings.json", BackupChangeKind::Modified),
+            ]
+        );
+        let diff = preview
+            .changes
+            .iter()
+            .find(|c| c.kind == BackupChangeKind::Modified)
+            .and_then(|c| c.diff.as_deref())
+            .expect("diff");
+        assert!(diff.contains("-  \"model\": \"haiku\""));
+        assert!(diff.contains("+  \"model\": \"opus\""));
+        // Nothing was touched
+        assert!(home.join(".claude/agents/extra.md").exists());
+
+        let restored = restore_in(&home, &dir, &backup.id, false, &delete).expect("restore");
+        assert!(restored.applied);
+        assert!(fs::read_to_string(home.join(".claude/settings.json"))
+            .expect("read")
+            .contains("opus"));
+        assert!(home.join(".claude/agents/planner.md").exists());
+        assert!(!home.join(".claude/agents/extra.md").exists());
+        assert!(
+            home.join(".claude/projects/p/t.jsonl").exists(),
+            "projects untouched"
+        );
+
+        // The safety backup undoes the restore
+        let safety = restored.safety_backup_id.expect("safety backup");
+        restore_in(&home, &dir, &safety, false, &delete).expect("undo");
+        assert!(home.join(".claude/agents/extra.md").exists());
+        assert!(fs::read_to_string(home.join(".claude/settings.json"))
+            .expect("read")
+            .contains("haiku"));
+    }
+}
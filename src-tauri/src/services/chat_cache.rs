@@ -0,0 +1,608 @@
+/**
+ * [INPUT]: 依赖 rusqlite (缓存库), jsonl (流式读取会话), types (RawLine, ChatMessage, Session), services::search::extract_content_with_meta, commands::projects (decode_project_path, read_session_head, history 摘要), security (get_claude_dir)
+ * [OUTPUT]: 对外提供 page_chats 供 list_all_chats 分页读取, page_sessions 与 SessionSort 供 list_all_sessions 分页排序
+ * [POS]: services/ 模块的全局聊天消息与会话元数据缓存，按文件 mtime/size 增量同步会话 JSONL 到 SQLite，按时间戳分页并去除续接会话中复制的重复消息
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::projects::{
+    build_session_index_from_history, decode_project_path, read_session_head,
+    restore_slash_command, HistoryIndex,
+};
+use crate::jsonl;
+use crate::security::get_claude_dir;
+use crate::services::search::extract_content_with_meta;
+use crate::types::{ChatMessage, ChatsResponse, RawLine, Session, SessionsResponse};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Bump when the schema or the parsed fields change; the cache is rebuilt on mismatch
+const CHAT_CACHE_VERSION: i32 = 2;
+
+/// Lines read from the top of a session file for its summary
+const SESSION_HEAD_LINES: usize = 20;
+
+/// Opened on first use and kept for the app's lifetime
+static CHAT_CACHE: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// Order of `page_sessions` results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionSort {
+    /// Most recently modified first
+    LastModified,
+    /// Most messages first
+    MessageCount,
+    /// By project path, then most recently modified
+    Project,
+}
+
+impl SessionSort {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "last_modified" => Ok(Self::LastModified),
+            "message_count" => Ok(Self::MessageCount),
+            "project" => Ok(Self::Project),
+            _ => Err(format!("Unknown session sort: {}", name)),
+        }
+    }
+
+    const fn order_by(self) -> &'static str {
+        match self {
+            Self::LastModified => "last_modified DESC, file_path",
+            Self::MessageCount => "message_count DESC, last_modified DESC, file_path",
+            Self::Project => "project_path, project_id, last_modified DESC, file_path",
+        }
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn get_chat_cache_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("lovcode")
+        .join("chat-cache.db")
+}
+
+fn open_chat_cache(path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version != CHAT_CACHE_VERSION {
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS chat_messages;
+             DROP TABLE IF EXISTS chat_sessions;
+             DROP TABLE IF EXISTS chat_files;",
+        )?;
+    }
+    conn.execute_batch(&format!(
+        "PRAGMA journal_mode = WAL;
+         CREATE TABLE IF NOT EXISTS chat_files (
+             path TEXT PRIMARY KEY,
+             mtime INTEGER NOT NULL,
+             size INTEGER NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS chat_messages (
+             file_path TEXT NOT NULL,
+             uuid TEXT NOT NULL,
+             role TEXT NOT NULL,
+             content TEXT NOT NULL,
+             timestamp TEXT NOT NULL,
+             project_id TEXT NOT NULL,
+             project_path TEXT NOT NULL,
+             session_id TEXT NOT NULL,
+             session_summary TEXT,
+             duplicate INTEGER NOT NULL DEFAULT 0
+         );
+         CREATE INDEX IF NOT EXISTS chat_messages_file ON chat_messages (file_path);
+         CREATE INDEX IF NOT EXISTS chat_messages_uuid ON chat_messages (uuid);
+         CREATE INDEX IF NOT EXISTS chat_messages_page ON chat_messages (duplicate, timestamp DESC);
+         CREATE TABLE IF NOT EXISTS chat_sessions (
+             file_path TEXT PRIMARY KEY,
+             session_id TEXT NOT NULL,
+             project_id TEXT NOT NULL,
+             project_path TEXT NOT NULL,
+             summary TEXT,
+             message_count INTEGER NOT NULL,
+             last_modified INTEGER NOT NULL
+         );
+         PRAGMA user_version = {};",
+        CHAT_CACHE_VERSION
+    ))
+}
+
+fn file_signature(path: &Path) -> Option<(i64, i64)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis();
+    Some((
+        i64::try_from(mtime).unwrap_or(i64::MAX),
+        i64::try_from(meta.len()).unwrap_or(i64::MAX),
+    ))
+}
+
+/// Non-meta user/assistant messages with text, plus the session's last summary line
+fn parse_session_chats(
+    lines: impl Iterator<Item = RawLine>,
+    project_id: &str,
+    project_path: &str,
+    session_id: &str,
+) -> Vec<ChatMessage> {
+    let mut summary: Option<String> = None;
+    let mut messages: Vec<ChatMessage> = Vec::new();
+
+    for parsed in lines {
+        let line_type = parsed.line_type.as_deref();
+        if line_type == Some("summary") {
+            summary = parsed.summary;
+            continue;
+        }
+        if line_type != Some("user") && line_type != Some("assistant") {
+            continue;
+        }
+        let Some(msg) = &parsed.message else {
+            continue;
+        };
+        let (text, _) = extract_content_with_meta(&msg.content);
+        if parsed.is_meta.unwrap_or(false) || text.is_empty() {
+            continue;
+        }
+        messages.push(ChatMessage {
+            uuid: parsed.uuid.unwrap_or_default(),
+            role: msg.role.clone().unwrap_or_default(),
+            content: text,
+            timestamp: parsed.timestamp.unwrap_or_default(),
+            project_id: project_id.to_string(),
+            project_path: project_path.to_string(),
+            session_id: session_id.to_string(),
+            session_summary: None,
+        });
+    }
+
+    for msg in &mut messages {
+        msg.session_summary = summary.clone();
+    }
+    messages
+}
+
+fn replace_file(
+    conn: &Connection,
+    path: &str,
+    signature: (i64, i64),
+    session: &Session,
+    messages: &[ChatMessage],
+) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM chat_messages WHERE file_path = ?1", [path])?;
+    conn.execute(
+        "INSERT OR REPLACE INTO chat_sessions
+             (file_path, session_id, project_id, project_path, summary, message_count, last_modified)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            path,
+            session.id,
+            session.project_id,
+            session.project_path,
+            session.summary,
+            i64::try_from(session.message_count).unwrap_or(i64::MAX),
+            i64::try_from(session.last_modified).unwrap_or(i64::MAX),
+        ],
+    )?;
+    let mut insert = conn.prepare_cached(
+        "INSERT INTO chat_messages
+             (file_path, uuid, role, content, timestamp, project_id, project_path, session_id, session_summary)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    for msg in messages {
+        insert.execute(params![
+            path,
+            msg.uuid,
+            msg.role,
+            msg.content,
+            msg.timestamp,
+            msg.project_id,
+            msg.project_path,
+            msg.session_id,
+            msg.session_summary,
+        ])?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO chat_files (path, mtime, size) VALUES (?1, ?2, ?3)",
+        params![path, signature.0, signature.1],
+    )?;
+    Ok(())
+}
+
+/// A resumed or compacted session starts by copying its parent's messages, uuids included.
+/// Keep the copy in the least recently modified file: the parent stops growing once its
+/// continuation takes over.
+fn mark_duplicates(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "UPDATE chat_messages SET duplicate = 0;
+         UPDATE chat_messages SET duplicate = 1 WHERE rowid IN (
+             SELECT id FROM (
+                 SELECT m.rowid AS id,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY m.uuid ORDER BY f.mtime, m.file_path, m.rowid
+                        ) AS n
+                 FROM chat_messages m JOIN chat_files f ON f.path = m.file_path
+                 WHERE m.uuid != ''
+             ) WHERE n > 1
+         );",
+    )
+}
+
+/// Reparse session files whose mtime/size changed and drop deleted ones.
+/// Returns whether the cache was modified.
+fn refresh_chat_cache(conn: &mut Connection, projects_dir: &Path) -> rusqlite::Result<bool> {
+    let tx = conn.transaction()?;
+    let mut changed = false;
+    let mut seen: HashSet<String> = HashSet::new();
+    // history.jsonl prompts, read only if a session has no summary of its own
+    let mut history: Option<HistoryIndex> = None;
+
+    for project_entry in fs::read_dir(projects_dir).into_iter().flatten().flatten() {
+        let project_dir = project_entry.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        let project_id = project_entry.file_name().to_string_lossy().to_string();
+        let mut project_path: Option<String> = None;
+
+        for entry in fs::read_dir(&project_dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".jsonl") || name.starts_with("agent-") {
+                continue;
+            }
+            let Some(signature) = file_signature(&path) else {
+                continue;
+            };
+            let key = path.to_string_lossy().to_string();
+            seen.insert(key.clone());
+
+            let cached: Option<(i64, i64)> = tx
+                .query_row(
+                    "SELECT mtime, size FROM chat_files WHERE path = ?1",
+                    [&key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            if cached == Some(signature) {
+                continue;
+            }
+
+            let project_path = project_path.get_or_insert_with(|| decode_project_path(&project_id));
+            let session_id = name.trim_end_matches(".jsonl");
+            let mut message_count = 0;
+            let messages = match jsonl::open(&path) {
+                Ok(lines) => parse_session_chats(
+                    lines
+                        .records::<RawLine>()
+                        .map(|(_, line)| line)
+                        .inspect(|line| {
+                            if matches!(line.line_type.as_deref(), Some("user" | "assistant")) {
+                                message_count += 1;
+                            }
+                        }),
+                    &project_id,
+                    project_path,
+                    session_id,
+                ),
+                Err(_) => Vec::new(),
+            };
+            let summary = read_session_head(&path, SESSION_HEAD_LINES).0.or_else(|| {
+                history
+                    .get_or_insert_with(build_session_index_from_history)
+                    .get(&(project_id.clone(), session_id.to_string()))
+                    .and_then(|(_, display)| display.as_deref())
+                    .map(restore_slash_command)
+            });
+            let session = Session {
+                id: session_id.to_string(),
+                project_id: project_id.clone(),
+                project_path: Some(project_path.clone()),
+                summary,
+                message_count,
+                last_modified: u64::try_from(signature.0 / 1000).unwrap_or(0),
+            };
+            replace_file(&tx, &key, signature, &session, &messages)?;
+            changed = true;
+        }
+    }
+
+    let cached_paths: Vec<String> = tx
+        .prepare("SELECT path FROM chat_files")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for path in cached_paths.iter().filter(|p| !seen.contains(*p)) {
+        tx.execute("DELETE FROM chat_messages WHERE file_path = ?1", [path])?;
+        tx.execute("DELETE FROM chat_sessions WHERE file_path = ?1", [path])?;
+        tx.execute("DELETE FROM chat_files WHERE path = ?1", [path])?;
+        changed = true;
+    }
+
+    if changed {
+        mark_duplicates(&tx)?;
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+/// One page of non-duplicate messages, newest first
+fn query_page(conn: &Connection, limit: usize, offset: usize) -> rusqlite::Result<ChatsResponse> {
+    let total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM chat_messages WHERE duplicate = 0",
+        [],
+        |row| row.get(0),
+    )?;
+    let items = conn
+        .prepare(
+            "SELECT uuid, role, content, timestamp, project_id, project_path, session_id, session_summary
+             FROM chat_messages WHERE duplicate = 0
+             ORDER BY timestamp DESC, rowid
+             LIMIT ?1 OFFSET ?2",
+        )?
+        .query_map(
+            params![
+                i64::try_from(limit).unwrap_or(i64::MAX),
+                i64::try_from(offset).unwrap_or(i64::MAX)
+            ],
+            |row| {
+                Ok(ChatMessage {
+                    uuid: row.get(0)?,
+                    role: row.get(1)?,
+                    content: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    project_id: row.get(4)?,
+                    project_path: row.get(5)?,
+                    session_id: row.get(6)?,
+                    session_summary: row.get(7)?,
+                })
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(ChatsResponse {
+        items,
+        total: usize::try_from(total).unwrap_or(0),
+    })
+}
+
+/// One page of sessions in `sort` order; every session when `limit` is None
+fn query_sessions(
+    conn: &Connection,
+    limit: Option<usize>,
+    offset: usize,
+    sort: SessionSort,
+) -> rusqlite::Result<SessionsResponse> {
+    let total: i64 = conn.query_row("SELECT COUNT(*) FROM chat_sessions", [], |row| row.get(0))?;
+    let items = conn
+        .prepare(&format!(
+            "SELECT session_id, project_id, project_path, summary, message_count, last_modified
+             FROM chat_sessions ORDER BY {} LIMIT ?1 OFFSET ?2",
+            sort.order_by()
+        ))?
+        .query_map(
+            params![
+                // SQLite reads a negative limit as "no limit"
+                limit.map_or(-1, |l| i64::try_from(l).unwrap_or(i64::MAX)),
+                i64::try_from(offset).unwrap_or(i64::MAX)
+            ],
+            |row| {
+                Ok(Session {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    project_path: row.get(2)?,
+                    summary: row.get(3)?,
+                    message_count: usize::try_from(row.get::<_, i64>(4)?).unwrap_or(0),
+                    last_modified: u64::try_from(row.get::<_, i64>(5)?).unwrap_or(0),
+                })
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(SessionsResponse {
+        items,
+        total: usize::try_from(total).unwrap_or(0),
+    })
+}
+
+/// Open the cache on first use, sync it with the session files when `refresh` is set
+/// (always on first use), and run `f` against it
+fn with_cache<T>(
+    refresh: bool,
+    f: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+) -> Result<T, String> {
+    let mut guard = CHAT_CACHE.lock().map_err(|_| "Chat cache lock poisoned")?;
+    let opened = guard.is_none();
+    if opened {
+        let path = get_chat_cache_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let conn =
+            open_chat_cache(&path).map_err(|e| format!("Failed to open chat cache: {}", e))?;
+        *guard = Some(conn);
+    }
+    let Some(conn) = guard.as_mut() else {
+        return Err("Chat cache unavailable".into());
+    };
+
+    if opened || refresh {
+        let projects_dir = get_claude_dir()
+            .map(|d| d.join("projects"))
+            .map_err(|e| e.to_string())?;
+        refresh_chat_cache(conn, &projects_dir)
+            .map_err(|e| format!("Failed to refresh chat cache: {}", e))?;
+    }
+    f(conn).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Page through every chat message across projects, newest first.
+///
+/// The first page (offset 0) syncs the cache with the session files first, so later
+/// pages stay stable while the user scrolls.
+pub fn page_chats(limit: usize, offset: usize) -> Result<ChatsResponse, String> {
+    with_cache(offset == 0, |conn| query_page(conn, limit, offset))
+}
+
+/// Page through every session across projects (subagent files excluded).
+///
+/// Like `page_chats`, the first page syncs the cache first. Message counts cover the
+/// whole file; summaries come from the session head, falling back to history.jsonl.
+pub fn page_sessions(
+    limit: Option<usize>,
+    offset: usize,
+    sort: SessionSort,
+) -> Result<SessionsResponse, String> {
+    with_cache(offset == 0, |conn| {
+        query_sessions(conn, limit, offset, sort)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn line(uuid: &str, role: &str, text: &str, ts: &str) -> String {
+        format!(
+            r#"{{"type":"{role}","uuid":"{uuid}","timestamp":"{ts}","message":{{"role":"{role}","content":"{text}"}}}}"#
+        )
+    }
+
+    fn write_session(dir: &Path, name: &str, lines: &[String], age_secs: u64) {
+        let path = dir.join(name);
+        fs::write(&path, lines.join("\n")).expect("write session");
+        let file = fs::File::options()
+            .write(true)
+            .open(&path)
+            .expect("open session");
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+            .expect("set mtime");
+    }
+
+    #[test]
+    fn test_refresh_dedupes_continuations_and_pages_by_timestamp() {
+        let temp = TempDir::new().expect("temp dir");
+        let project = temp.path().join("-work-app");
+        fs::create_dir_all(&project).expect("project dir");
+
+        let first = line("u1", "user", "Fix the bug", "2025-03-04T10:00:00Z");
+        let reply = line("a1", "assistant", "Done", "2025-03-04T10:00:05Z");
+        write_session(
+            &project,
+            "parent.jsonl",
+            &[first.clone(), reply.clone()],
+            600,
+        );
+        write_session(
+            &project,
+            "child.jsonl",
+            &[
+                r#"{"type":"summary","summary":"Bug fix","leafUuid":"a1"}"#.to_string(),
+                first,
+                reply,
+                line("u2", "user", "Add a test", "2025-03-04T11:00:00Z"),
+            ],
+            0,
+        );
+        write_session(
+            &project,
+            "agent-1.jsonl",
+            &[line("x1", "user", "sub", "2025-03-04T12:00:00Z")],
+            0,
+        );
+
+        let mut conn = Connection::open_in_memory().expect("open db");
+        init_schema(&conn).expect("schema");
+        assert!(refresh_chat_cache(&mut conn, temp.path()).expect("refresh"));
+        assert!(!refresh_chat_cache(&mut conn, temp.path()).expect("second refresh"));
+
+        let page = query_page(&conn, 2, 0).expect("page");
+        assert_eq!(page.total, 3);
+        let ids: Vec<(&str, &str)> = page
+            .items
+            .iter()
+            .map(|m| (m.uuid.as_str(), m.session_id.as_str()))
+            .collect();
+        assert_eq!(ids, [("u2", "child"), ("a1", "parent")]);
+        assert_eq!(page.items[0].session_summary.as_deref(), Some("Bug fix"));
+
+        let rest = query_page(&conn, 2, 2).expect("second page");
+        assert_eq!(rest.items.len(), 1);
+        assert_eq!(rest.items[0].uuid, "u1");
+        assert_eq!(rest.items[0].session_id, "parent");
+
+        // Deleting the parent promotes the continuation's copies
+        fs::remove_file(project.join("parent.jsonl")).expect("remove parent");
+        assert!(refresh_chat_cache(&mut conn, temp.path()).expect("refresh after delete"));
+        let page = query_page(&conn, 10, 0).expect("page after delete");
+        assert_eq!(page.total, 3);
+        assert!(page.items.iter().all(|m| m.session_id == "child"));
+    }
+
+    #[test]
+    fn test_sessions_page_and_sort() {
+        let temp = TempDir::new().expect("temp dir");
+        let (app, lib) = (temp.path().join("-work-app"), temp.path().join("-work-lib"));
+        fs::create_dir_all(&app).expect("app dir");
+        fs::create_dir_all(&lib).expect("lib dir");
+        let ts = "2025-03-04T10:00:00Z";
+        write_session(
+            &app,
+            "old.jsonl",
+            &[
+                line("u1", "user", "First", ts),
+                line("a1", "assistant", "Ok", ts),
+                line("u2", "user", "Second", ts),
+            ],
+            600,
+        );
+        write_session(&app, "new.jsonl", &[line("u3", "user", "Newest", ts)], 0);
+        write_session(&lib, "mid.jsonl", &[line("u4", "user", "Middle", ts)], 300);
+
+        let mut conn = Connection::open_in_memory().expect("open db");
+        init_schema(&conn).expect("schema");
+        refresh_chat_cache(&mut conn, temp.path()).expect("refresh");
+
+        let ids = |page: &SessionsResponse| -> Vec<String> {
+            page.items.iter().map(|s| s.id.clone()).collect()
+        };
+        let recent = query_sessions(&conn, Some(2), 0, SessionSort::LastModified).expect("page");
+        assert_eq!(recent.total, 3);
+        assert_eq!(ids(&recent), ["new", "mid"]);
+        assert_eq!(recent.items[0].summary.as_deref(), Some("Newest"));
+        let rest = query_sessions(&conn, Some(2), 2, SessionSort::LastModified).expect("page 2");
+        assert_eq!(ids(&rest), ["old"]);
+
+        let by_count = query_sessions(&conn, None, 0, SessionSort::MessageCount).expect("count");
+        assert_eq!(by_count.items[0].id, "old");
+        assert_eq!(by_count.items[0].message_count, 3);
+        let by_project = query_sessions(&conn, None, 0, SessionSort::Project).expect("project");
+        assert_eq!(ids(&by_project), ["new", "old", "mid"]);
+        assert!(SessionSort::parse("size").is_err());
+
+        fs::remove_file(app.join("new.jsonl")).expect("remove");
+        refresh_chat_cache(&mut conn, temp.path()).expect("refresh after delete");
+        let after = query_sessions(&conn, None, 0, SessionSort::LastModified).expect("after");
+        assert_eq!(ids(&after), ["mid", "old"]);
+    }
+}
@@ -0,0 +1,409 @@
+/**
+ * [INPUT]: 依赖 git CLI (临时 GIT_INDEX_FILE + commit-tree), serde, uuid, crate::security
+ * [OUTPUT]: 对外提供 create_checkpoint, list_checkpoints, restore_checkpoint, delete_checkpoint 命令 + checkpoint_project 供 workspace 调用
+ * [POS]: services/ 模块的项目快照服务，在 Claude 运行前保存工作区，必要时回滚
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A snapshot of a project's working tree (tracked + untracked, minus ignored files).
+/// Stored as a commit under refs/lovcode/checkpoints/<id>; HEAD and the index are untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub project_path: String,
+    pub label: String,
+    pub commit: String,
+    #[serde(default)]
+    pub feature_id: Option<String>,
+    pub created_at: u64,
+}
+
+/// Oldest checkpoints beyond this count are pruned per project
+const MAX_CHECKPOINTS_PER_PROJECT: usize = 50;
+
+const CHECKPOINT_REF_PREFIX: &str = "refs/lovcode/checkpoints/";
+
+/// Serializes access to checkpoints.json
+static CHECKPOINT_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn store_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("checkpoints.json")
+}
+
+fn load_store(path: &Path) -> Vec<Checkpoint> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(path: &Path, checkpoints: &[Checkpoint]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let json = serde_json::to_string_pretty(checkpoints).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &json)
+}
+
+// ============================================================================
+// Git Plumbing
+// ============================================================================
+
+/// Run git in `dir`, optionally against a scratch index so the user's staging area is untouched
+fn git(dir: &Path, index: Option<&Path>, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(dir).args(args);
+    if let Some(index) = index {
+        cmd.env("GIT_INDEX_FILE", index);
+    }
+    // Snapshot commits are internal; don't depend on the user's identity being configured
+    cmd.env("GIT_AUTHOR_NAME", "lovcode")
+        .env("GIT_AUTHOR_EMAIL", "checkpoint@lovcode.local")
+        .env("GIT_COMMITTER_NAME", "lovcode")
+        .env("GIT_COMMITTER_EMAIL", "checkpoint@lovcode.local");
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run git {}: {}", args.first().unwrap_or(&""), e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Scratch index file, removed on drop
+struct ScratchIndex(PathBuf);
+
+impl ScratchIndex {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!(
+            "lovcode-checkpoint-{}.index",
+            uuid::Uuid::new_v4()
+        )))
+    }
+}
+
+impl Drop for ScratchIndex {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn repo_root(project: &Path) -> AppResult<PathBuf> {
+    git(project, None, &["rev-parse", "--show-toplevel"])
+        .map(PathBuf::from)
+        .map_err(|_| {
+            AppError::invalid_input(format!(
+                "Checkpoints require a git repository: {}",
+                project.display()
+            ))
+        })
+}
+
+/// Write the whole working tree into a commit object and return its hash
+fn snapshot_worktree(project: &Path, message: &str) -> AppResult<String> {
+    let root = repo_root(project)?;
+    let scratch = ScratchIndex::new();
+    let head = git(&root, None, &["rev-parse", "--verify", "-q", "HEAD"]).ok();
+
+    // Start from a copy of the real index so unchanged files keep their stat cache
+    let real_index = git(&root, None, &["rev-parse", "--git-path", "index"])
+        .map(|p| root.join(p))
+        .ok()
+        .filter(|p| p.exists());
+    match (&real_index, &head) {
+        (Some(index), _) => {
+            fs::copy(index, &scratch.0).map_err(|e| AppError::io("Failed to copy index", &e))?;
+        }
+        (None, Some(_)) => {
+            git(&root, Some(&scratch.0), &["read-tree", "HEAD"])?;
+        }
+        (None, None) => {
+            git(&root, Some(&scratch.0), &["read-tree", "--empty"])?;
+        }
+    }
+
+    git(&root, Some(&scratch.0), &["add", "-A"])?;
+    let tree = git(&root, Some(&scratch.0), &["write-tree"])?;
+
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+    if let Some(head) = &head {
+        args.extend(["-p", head.as_str()]);
+    }
+    Ok(git(&root, None, &args)?)
+}
+
+fn tree_files(root: &Path, commit: &str) -> Result<HashSet<String>, String> {
+    Ok(git(root, None, &["ls-tree", "-r", "--name-only", "-z", commit])?
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Make the working tree match `target`: write its files and delete files it doesn't have.
+/// `current` is a snapshot of the present state, used to find the files to delete.
+fn restore_worktree(project: &Path, target: &str, current: &str) -> AppResult<()> {
+    let root = repo_root(project)?;
+    let scratch = ScratchIndex::new();
+
+    git(&root, Some(&scratch.0), &["read-tree", target])?;
+    git(&root, Some(&scratch.0), &["checkout-index", "-a", "-f"])?;
+
+    let keep = tree_files(&root, target)?;
+    for extra in tree_files(&root, current)?.difference(&keep) {
+        let path = root.join(extra);
+        fs::remove_file(&path).map_err(|e| {
+            AppError::io("Failed to delete file", &e).with_context(path.to_string_lossy())
+        })?;
+    }
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn create_in_store(
+    store: &Path,
+    project_path: &str,
+    label: &str,
+    feature_id: Option<String>,
+) -> AppResult<Checkpoint> {
+    let project = Path::new(project_path);
+    let id = uuid::Uuid::new_v4().to_string();
+    let commit = snapshot_worktree(project, &format!("lovcode checkpoint: {}", label))?;
+    git(
+        project,
+        None,
+        &["update-ref", &format!("{}{}", CHECKPOINT_REF_PREFIX, id), &commit],
+    )?;
+
+    let checkpoint = Checkpoint {
+        id,
+        project_path: project_path.to_string(),
+        label: label.to_string(),
+        commit,
+        feature_id,
+        created_at: now_secs(),
+    };
+
+    let _guard = CHECKPOINT_LOCK.lock().map_err(|_| "Checkpoint lock poisoned")?;
+    let mut checkpoints = load_store(store);
+    checkpoints.push(checkpoint.clone());
+
+    // Prune the oldest checkpoints of this project
+    let count = checkpoints
+        .iter()
+        .filter(|c| c.project_path == project_path)
+        .count();
+    let mut excess = count.saturating_sub(MAX_CHECKPOINTS_PER_PROJECT);
+    checkpoints.retain(|c| {
+        if excess > 0 && c.project_path == project_path {
+            excess -= 1;
+            let _ = git(
+                project,
+                None,
+                &["update-ref", "-d", &format!("{}{}", CHECKPOINT_REF_PREFIX, c.id)],
+            );
+            return false;
+        }
+        true
+    });
+
+    save_store(store, &checkpoints)?;
+    Ok(checkpoint)
+}
+
+fn find_checkpoint(store: &Path, id: &str) -> AppResult<Checkpoint> {
+    let _guard = CHECKPOINT_LOCK.lock().map_err(|_| "Checkpoint lock poisoned")?;
+    load_store(store)
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Checkpoint '{}' not found", id)))
+}
+
+fn restore_in_store(store: &Path, id: &str) -> AppResult<Checkpoint> {
+    let checkpoint = find_checkpoint(store, id)?;
+
+    // Snapshot the current state first so the restore itself can be undone
+    let safety = create_in_store(
+        store,
+        &checkpoint.project_path,
+        &format!("Before restoring \"{}\"", checkpoint.label),
+        checkpoint.feature_id.clone(),
+    )?;
+    restore_worktree(
+        Path::new(&checkpoint.project_path),
+        &checkpoint.commit,
+        &safety.commit,
+    )?;
+
+    Ok(safety)
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Checkpoint a project from other modules (e.g. when a workspace feature starts)
+pub fn checkpoint_project(
+    project_path: &str,
+    label: &str,
+    feature_id: Option<String>,
+) -> AppResult<Checkpoint> {
+    create_in_store(&store_path(), project_path, label, feature_id)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Snapshot the project's working tree without touching HEAD, the index or branches
+#[tauri::command]
+pub fn create_checkpoint(
+    project_path: String,
+    label: String,
+    feature_id: Option<String>,
+) -> AppResult<Checkpoint> {
+    security::validate_decoded_path(&project_path)?;
+    checkpoint_project(&project_path, &label, feature_id)
+}
+
+/// List checkpoints, newest first (optionally for one project)
+#[tauri::command]
+pub fn list_checkpoints(project_path: Option<String>) -> AppResult<Vec<Checkpoint>> {
+    let _guard = CHECKPOINT_LOCK.lock().map_err(|_| "Checkpoint lock poisoned")?;
+    let mut checkpoints: Vec<Checkpoint> = load_store(&store_path())
+        .into_iter()
+        .filter(|c| project_path.as_ref().is_none_or(|p| &c.project_path == p))
+        .collect();
+    checkpoints.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(checkpoints)
+}
+
+/// Roll the working tree back to a checkpoint. Returns the checkpoint taken of the
+/// state just before the restore, so the UI can offer to undo it.
+#[tauri::command]
+pub fn restore_checkpoint(id: String) -> AppResult<Checkpoint> {
+    let store = store_path();
+    let checkpoint = find_checkpoint(&store, &id)?;
+    security::validate_decoded_path(&checkpoint.project_path)?;
+    restore_in_store(&store, &id)
+}
+
+#[tauri::command]
+pub fn delete_checkpoint(id: String) -> AppResult<()> {
+    let store = store_path();
+    let _guard = CHECKPOINT_LOCK.lock().map_err(|_| "Checkpoint lock poisoned")?;
+    let mut checkpoints = load_store(&store);
+    let index = checkpoints
+        .iter()
+        .position(|c| c.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Checkpoint '{}' not found", id)))?;
+    let checkpoint = checkpoints.remove(index);
+
+    let _ = git(
+        Path::new(&checkpoint.project_path),
+        None,
+        &["update-ref", "-d", &format!("{}{}", CHECKPOINT_REF_PREFIX, checkpoint.id)],
+    );
+    save_store(&store, &checkpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorCode;
+
+    fn init_repo(dir: &Path) {
+        git(dir, None, &["init", "-q"]).expect("git init");
+        fs::write(dir.join("tracked.txt"), "v1\n").expect("write tracked");
+        fs::write(dir.join(".gitignore"), "ignored.txt\n").expect("write gitignore");
+        git(dir, None, &["add", "-A"]).expect("git add");
+        git(dir, None, &["commit", "-q", "-m", "init"]).expect("git commit");
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let project = temp.path().join("project");
+        fs::create_dir_all(&project).expect("create project");
+        init_repo(&project);
+        let store = temp.path().join("checkpoints.json");
+        let project_path = project.to_string_lossy().to_string();
+
+        // Uncommitted and untracked changes are captured
+        fs::write(project.join("tracked.txt"), "v2\n").expect("modify");
+        fs::write(project.join("notes.md"), "draft\n").expect("untracked");
+        git(&project, None, &["add", "tracked.txt"]).expect("stage");
+        let staged_before = git(&project, None, &["diff", "--cached", "--name-only"]).expect("diff");
+
+        let checkpoint =
+            create_in_store(&store, &project_path, "before run", None).expect("checkpoint");
+        assert_eq!(
+            git(&project, None, &["diff", "--cached", "--name-only"]).expect("diff"),
+            staged_before,
+            "index must be untouched"
+        );
+
+        // Simulate a Claude run
+        fs::write(project.join("tracked.txt"), "broken\n").expect("modify");
+        fs::write(project.join("new.rs"), "fn main() {}\n").expect("new file");
+        fs::remove_file(project.join("notes.md")).expect("delete");
+        fs::write(project.join("ignored.txt"), "keep me\n").expect("ignored");
+
+        let safety = restore_in_store(&store, &checkpoint.id).expect("restore");
+        assert_eq!(fs::read_to_string(project.join("tracked.txt")).expect("read"), "v2\n");
+        assert_eq!(fs::read_to_string(project.join("notes.md")).expect("read"), "draft\n");
+        assert!(!project.join("new.rs").exists());
+        assert!(project.join("ignored.txt").exists(), "ignored files are left alone");
+
+        // The safety checkpoint undoes the restore
+        restore_in_store(&store, &safety.id).expect("undo");
+        assert_eq!(fs::read_to_string(project.join("tracked.txt")).expect("read"), "broken\n");
+        assert!(project.join("new.rs").exists());
+
+        assert_eq!(load_store(&store).len(), 3);
+        assert!(git(
+            &project,
+            None,
+            &["rev-parse", &format!("{}{}", CHECKPOINT_REF_PREFIX, checkpoint.id)]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_checkpoint_requires_git() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let store = temp.path().join("checkpoints.json");
+        let err = create_in_store(&store, &temp.path().to_string_lossy(), "x", None)
+            .expect_err("not a repo");
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
+        assert!(err.to_string().contains("require a git repository"));
+    }
+}
@@ -0,0 +1,420 @@
+/**
+ * [INPUT]: 依赖 notify (监听 ~/.claude.json), chrono, serde_json, crate::store (快照索引), crate::security
+ * [OUTPUT]: 对外提供 list_claude_json_history, diff_claude_json_history 命令, start_claude_json_watcher 后台监听, record_lovcode_change 供 lovcode 自身写入时标注来源, ClaudeJsonSnapshot/ClaudeJsonDiff 类型
+ * [POS]: services/ 模块的 ~/.claude.json 变更审计，变更时快照到有上限的历史中，按 JSON Pointer 对比任意两次快照，回答"谁加了这个 MCP server / 项目条目"
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::store::{self, Schema};
+use notify::{Event, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// One recorded state of ~/.claude.json, stored as
+/// ~/.lovstudio/lovcode/claude-json-history/<id>.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeJsonSnapshot {
+    pub id: String,
+    /// Unix seconds
+    pub taken_at: u64,
+    pub size: u64,
+    /// What lovcode did when it made the change itself (e.g. "Installed MCP server github").
+    /// None when another program changed the file: Claude Code, an installer, an editor.
+    pub source: Option<String>,
+    /// Top-level keys that differ from the previous snapshot (all keys for the first one)
+    pub changed_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonChange {
+    /// JSON Pointer, e.g. "/mcpServers/github". Objects are compared key by key; arrays
+    /// and scalars are reported whole.
+    pub path: String,
+    pub kind: JsonChangeKind,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeJsonDiff {
+    pub from: ClaudeJsonSnapshot,
+    pub to: ClaudeJsonSnapshot,
+    pub changes: Vec<JsonChange>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    /// Oldest first
+    snapshots: Vec<ClaudeJsonSnapshot>,
+}
+
+/// Snapshots kept (oldest are dropped first)
+const MAX_SNAPSHOTS: usize = 100;
+/// Claude Code rewrites the file several times while starting; one snapshot per burst
+const DEBOUNCE: Duration = Duration::from_secs(1);
+const INDEX_SCHEMA: Schema = Schema::new(1);
+
+/// Serializes snapshotting between the watcher and lovcode's own writes
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn history_dir() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("claude-json-history")
+}
+
+fn index_path(dir: &Path) -> PathBuf {
+    dir.join("index.json")
+}
+
+fn snapshot_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+/// Ids are generated timestamps; anything else could escape the history directory
+fn validate_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid snapshot id: {}", id));
+    }
+    Ok(())
+}
+
+fn load_index(dir: &Path) -> HistoryIndex {
+    store::load_as(&index_path(dir), &INDEX_SCHEMA)
+        .unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Local timestamp, suffixed when two snapshots land in the same second
+fn next_id(dir: &Path) -> String {
+    let base = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut id = base.clone();
+    let mut n = 2;
+    while snapshot_path(dir, &id).exists() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn read_snapshot(dir: &Path, id: &str) -> Result<Value, String> {
+    let content = fs::read_to_string(snapshot_path(dir, id))
+        .map_err(|_| format!("Snapshot '{}' not found", id))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse snapshot {}: {}", id, e))
+}
+
+/// Snapshot `content` unless it is unparseable (caught mid-write) or the same as the
+/// latest snapshot. Returns the new snapshot.
+fn record_in(
+    dir: &Path,
+    content: &str,
+    source: Option<String>,
+) -> Result<Option<ClaudeJsonSnapshot>, String> {
+    let Ok(current) = serde_json::from_str::<Value>(content) else {
+        return Ok(None);
+    };
+    let mut index = load_index(dir);
+    let previous = index
+        .snapshots
+        .last()
+        .and_then(|latest| read_snapshot(dir, &latest.id).ok());
+    if previous.as_ref() == Some(&current) {
+        return Ok(None);
+    }
+
+    let mut changed_keys: Vec<String> = Vec::new();
+    if let Some(object) = current.as_object() {
+        changed_keys.extend(
+            object
+                .iter()
+                .filter(|(key, value)| previous.as_ref().and_then(|p| p.get(key)) != Some(*value))
+                .map(|(key, _)| key.clone()),
+        );
+    }
+    if let Some(old) = previous.as_ref().and_then(Value::as_object) {
+        changed_keys.extend(old.keys().filter(|key| current.get(key).is_none()).cloned());
+    }
+    changed_keys.sort();
+
+    // Snapshots hold whatever ~/.claude.json holds, API keys included
+    security::create_private_dir(dir)
+        .map_err(|e| format!("Failed to create history dir: {}", e))?;
+    let snapshot = ClaudeJsonSnapshot {
+        id: next_id(dir),
+        taken_at: now_secs(),
+        size: content.len() as u64,
+        source,
+        changed_keys,
+    };
+    let path = snapshot_path(dir, &snapshot.id);
+    security::atomic_write_string(&path, content)
+        .map_err(|e| format!("Failed to write snapshot: {}", e))?;
+    security::make_private(&path).map_err(|e| format!("Failed to restrict snapshot: {}", e))?;
+    index.snapshots.push(snapshot.clone());
+
+    let excess = index.snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+    for old in index.snapshots.drain(..excess) {
+        let _ = fs::remove_file(snapshot_path(dir, &old.id));
+    }
+    store::save(&index_path(dir), &INDEX_SCHEMA, &index)?;
+    Ok(Some(snapshot))
+}
+
+fn record(source: Option<String>) {
+    let Ok(content) = fs::read_to_string(security::get_claude_json_path_or_fallback()) else {
+        return;
+    };
+    let Ok(_guard) = HISTORY_LOCK.lock() else {
+        return;
+    };
+    if let Err(e) = record_in(&history_dir(), &content, source) {
+        tracing::warn!("Failed to snapshot ~/.claude.json: {}", e);
+    }
+}
+
+// ============================================================================
+// Diff
+// ============================================================================
+
+fn diff_values(before: &Value, after: &Value, pointer: &str, out: &mut Vec<JsonChange>) {
+    let (Value::Object(old), Value::Object(new)) = (before, after) else {
+        if before != after {
+            out.push(JsonChange {
+                path: pointer.to_string(),
+                kind: JsonChangeKind::Changed,
+                before: Some(before.clone()),
+                after: Some(after.clone()),
+            });
+        }
+        return;
+    };
+
+    let child = |key: &str| format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+    for (key, old_value) in old {
+        match new.get(key) {
+            Some(new_value) => diff_values(old_value, new_value, &child(key), out),
+            None => out.push(JsonChange {
+                path: child(key),
+                kind: JsonChangeKind::Removed,
+                before: Some(old_value.clone()),
+                after: None,
+            }),
+        }
+    }
+    for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+        out.push(JsonChange {
+            path: child(key),
+            kind: JsonChangeKind::Added,
+            before: None,
+            after: Some(new_value.clone()),
+        });
+    }
+}
+
+fn diff_in(dir: &Path, from: &str, to: &str) -> Result<ClaudeJsonDiff, String> {
+    let index = load_index(dir);
+    let find = |id: &str| {
+        index
+            .snapshots
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Snapshot '{}' not found", id))
+    };
+    let (from_snapshot, to_snapshot) = (find(from)?, find(to)?);
+
+    let mut changes = Vec::new();
+    diff_values(
+        &read_snapshot(dir, from)?,
+        &read_snapshot(dir, to)?,
+        "",
+        &mut changes,
+    );
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(ClaudeJsonDiff {
+        from: from_snapshot,
+        to: to_snapshot,
+        changes,
+    })
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Snapshot ~/.claude.json right after lovcode changed it, so the change is attributed
+/// to lovcode instead of showing up unlabeled when the watcher sees it
+pub fn record_lovcode_change(source: impl Into<String>) {
+    record(Some(source.into()));
+}
+
+/// Snapshot ~/.claude.json now (catching changes made while the app was closed) and
+/// whenever it changes. ~/.claude.json is replaced rather than edited in place, so its
+/// directory is watched.
+pub fn start_claude_json_watcher() {
+    std::thread::spawn(|| {
+        record(None);
+
+        let path = security::get_claude_json_path_or_fallback();
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return;
+        };
+        let name = name.to_os_string();
+        let (tx, rx) = channel::<()>();
+        let mut watcher =
+            match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    if (event.kind.is_create() || event.kind.is_modify())
+                        && event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name() == Some(name.as_os_str()))
+                    {
+                        let _ = tx.send(());
+                    }
+                }
+            }) {
+                Ok(w) => w,
+                Err(e) => {
+                    tracing::warn!("Failed to create ~/.claude.json watcher: {}", e);
+                    return;
+                }
+            };
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            tracing::warn!("Failed to watch {}: {}", dir.display(), e);
+            return;
+        }
+
+        while rx.recv().is_ok() {
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+            record(None);
+        }
+    });
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Recorded states of ~/.claude.json, newest first
+#[tauri::command]
+pub fn list_claude_json_history() -> AppResult<Vec<ClaudeJsonSnapshot>> {
+    let mut snapshots = load_index(&history_dir()).snapshots;
+    snapshots.reverse();
+    Ok(snapshots)
+}
+
+/// What changed in ~/.claude.json between two snapshots
+#[tauri::command]
+pub fn diff_claude_json_history(from: String, to: String) -> AppResult<ClaudeJsonDiff> {
+    for id in [&from, &to] {
+        validate_id(id).map_err(AppError::invalid_input)?;
+    }
+    diff_in(&history_dir(), &from, &to).map_err(AppError::not_found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_diff_history() {
+        let temp = TempDir::new().expect("temp dir");
+        let dir = temp.path();
+
+        let first = record_in(dir, r#"{"numStartups": 1, "mcpServers": {}}"#, None)
+            .expect("record")
+            .expect("first snapshot");
+        assert_eq!(first.changed_keys, ["mcpServers", "numStartups"]);
+        // Unchanged content and half-written files are not recorded
+        assert!(
+            record_in(dir, r#"{"mcpServers": {}, "numStartups": 1}"#, None)
+                .expect("record")
+                .is_none()
+        );
+        assert!(record_in(dir, r#"{"numStartups": "#, None)
+            .expect("record")
+            .is_none());
+
+        let second = record_in(
+            dir,
+            r#"{"mcpServers": {"github": {"command": "gh-mcp"}}, "projects": {"/work/app": {}}}"#,
+            Some("Installed MCP server github".to_string()),
+        )
+        .expect("record")
+        .expect("second snapshot");
+        assert_eq!(
+            second.changed_keys,
+            ["mcpServers", "numStartups", "projects"]
+        );
+        assert_eq!(
+            second.source.as_deref(),
+            Some("Installed MCP server github")
+        );
+
+        let diff = diff_in(dir, &first.id, &second.id).expect("diff");
+        let summary: Vec<(&str, JsonChangeKind)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("/mcpServers/github", JsonChangeKind::Added),
+                ("/numStartups", JsonChangeKind::Removed),
+                ("/projects", JsonChangeKind::Added),
+            ]
+        );
+        assert_eq!(diff.changes[0].after, Some(json!({"command": "gh-mcp"})));
+        assert!(diff_in(dir, &first.id, "missing").is_err());
+        assert_eq!(load_index(dir).snapshots.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_snapshots_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp = TempDir::new().expect("temp dir");
+        let dir = temp.path().join("history");
+        let snapshot = record_in(&dir, r#"{"primaryApiKey": "sk-test"}"#, None)
+            .expect("record")
+            .expect("snapshot");
+
+        let mode = |path: &Path| fs::metadata(path).expect("metadata").permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&snapshot_path(&dir, &snapshot.id)), 0o600);
+    }
+}
@@ -0,0 +1,252 @@
+/**
+ * [INPUT]: 依赖 tauri::Emitter, crate::workspace_store (feature 所属项目), crate::commands::projects (项目路径编解码, 会话摘要), crate::services::{pty_output, notifications}
+ * [OUTPUT]: 对外提供 parse_deep_link, dispatch 供 lib.rs 的 deep-link 插件回调使用, open_deep_link, take_pending_deep_link 命令与 DeepLink/DeepLinkTarget
+ * [POS]: services/ 模块的 lovcode:// 链接分发，解析会话/feature 链接并解析出前端导航所需信息，广播 deep-link 事件
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::projects::{decode_project_path, encode_project_path, read_session_head};
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::pty_output::percent_decode;
+use crate::workspace_store::{self, WorkspaceData};
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+pub const SCHEME: &str = "lovcode";
+
+/// What a link points at, as written in the URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkTarget {
+    /// lovcode://session/<project>/<session-id>; the project is a ~/.claude/projects
+    /// directory name or a percent-encoded absolute path
+    Session { project: String, session_id: String },
+    /// lovcode://feature/<id>; the feature id or its sequence number
+    Feature { feature: String },
+}
+
+/// A link resolved to what the frontend needs to navigate; payload of the "deep-link" event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeepLink {
+    Session {
+        project_id: String,
+        project_path: String,
+        session_id: String,
+        summary: Option<String>,
+    },
+    Feature {
+        project_id: String,
+        feature_id: String,
+    },
+}
+
+/// The last link opened, kept until the frontend takes it; links that arrive on a
+/// cold start come before the webview listens
+static PENDING: LazyLock<Mutex<Option<DeepLink>>> = LazyLock::new(|| Mutex::new(None));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Non-empty, decoded path segments; rejects anything that could leave a directory
+fn segments(path: &str) -> Result<Vec<String>, String> {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let segment = percent_decode(s);
+            if segment == ".." || segment.contains('\0') {
+                Err(format!("Invalid link segment: {}", s))
+            } else {
+                Ok(segment)
+            }
+        })
+        .collect()
+}
+
+/// Find a feature by id or sequence number; returns (project id, feature id)
+fn find_feature(data: &WorkspaceData, key: &str) -> Option<(String, String)> {
+    let seq = key.trim_start_matches('#').parse::<u32>().ok();
+    data.projects.iter().find_map(|project| {
+        project
+            .features
+            .iter()
+            .find(|f| f.id == key || seq == Some(f.seq))
+            .map(|f| (project.id.clone(), f.id.clone()))
+    })
+}
+
+fn resolve(target: DeepLinkTarget) -> AppResult<DeepLink> {
+    match target {
+        DeepLinkTarget::Session {
+            project,
+            session_id,
+        } => {
+            let (project_id, project_path) = if project.starts_with('/') {
+                (encode_project_path(&project), project)
+            } else {
+                let path = decode_project_path(&project);
+                (project, path)
+            };
+            // Both become path components under ~/.claude/projects
+            if [&project_id, &session_id]
+                .iter()
+                .any(|s| s.contains(['/', '\\']) || s.as_str() == "..")
+            {
+                return Err(AppError::invalid_input("Invalid session link"));
+            }
+            let session_file = security::get_claude_dir_or_fallback()
+                .join("projects")
+                .join(&project_id)
+                .join(format!("{}.jsonl", session_id));
+            if !session_file.is_file() {
+                return Err(AppError::not_found("Session not found")
+                    .with_context(format!("{}/{}", project_id, session_id)));
+            }
+            Ok(DeepLink::Session {
+                summary: read_session_head(&session_file, 20).0,
+                project_id,
+                project_path,
+                session_id,
+            })
+        }
+        DeepLinkTarget::Feature { feature } => {
+            let data = workspace_store::load_workspace()?;
+            let (project_id, feature_id) = find_feature(&data, &feature)
+                .ok_or_else(|| AppError::not_found("Feature not found").with_context(feature))?;
+            Ok(DeepLink::Feature {
+                project_id,
+                feature_id,
+            })
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Parse a lovcode:// URL; the query string and fragment are ignored
+pub fn parse_deep_link(url: &str) -> Result<DeepLinkTarget, String> {
+    let rest = url
+        .strip_prefix(SCHEME)
+        .and_then(|r| r.strip_prefix("://"))
+        .ok_or_else(|| format!("Not a {}:// link: {}", SCHEME, url))?;
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let parts = segments(path)?;
+    let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+    match parts.as_slice() {
+        ["session", project, session_id] => Ok(DeepLinkTarget::Session {
+            project: (*project).to_string(),
+            session_id: (*session_id).to_string(),
+        }),
+        ["feature", feature] => Ok(DeepLinkTarget::Feature {
+            feature: (*feature).to_string(),
+        }),
+        _ => Err(format!("Unsupported link: {}", url)),
+    }
+}
+
+/// Handle a link the OS opened the app with: resolve it, keep it for
+/// take_pending_deep_link and emit "deep-link". Bad links end in a notification.
+pub fn dispatch(app: &AppHandle, url: &str) {
+    let link = parse_deep_link(url)
+        .map_err(AppError::invalid_input)
+        .and_then(resolve);
+    match link {
+        Ok(link) => {
+            if let Ok(mut pending) = PENDING.lock() {
+                *pending = Some(link.clone());
+            }
+            let _ = app.emit("deep-link", link);
+        }
+        Err(e) => {
+            tracing::warn!(url, error = %e, "Failed to open deep link");
+            notifications::notify(
+                "Couldn't open link",
+                &e.to_string(),
+                NotificationKind::General,
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Resolve a lovcode:// link, e.g. one pasted into the app
+#[tauri::command]
+pub fn open_deep_link(url: String) -> AppResult<DeepLink> {
+    resolve(parse_deep_link(url.trim()).map_err(AppError::invalid_input)?)
+}
+
+/// The last link the app was opened with, if the frontend hasn't handled it yet
+#[tauri::command]
+pub fn take_pending_deep_link() -> AppResult<Option<DeepLink>> {
+    Ok(PENDING
+        .lock()
+        .map_err(|_| "Deep link lock poisoned")?
+        .take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_link() {
+        assert_eq!(
+            parse_deep_link("lovcode://session/-Users-me-app/abc-123"),
+            Ok(DeepLinkTarget::Session {
+                project: "-Users-me-app".to_string(),
+                session_id: "abc-123".to_string(),
+            })
+        );
+        // Hooks know the cwd, so an encoded absolute path works as the project
+        assert_eq!(
+            parse_deep_link("lovcode://session/%2FUsers%2Fme%2Fmy%20app/abc?from=slack"),
+            Ok(DeepLinkTarget::Session {
+                project: "/Users/me/my app".to_string(),
+                session_id: "abc".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_deep_link("lovcode://feature/feat-1/"),
+            Ok(DeepLinkTarget::Feature {
+                feature: "feat-1".to_string(),
+            })
+        );
+        assert!(parse_deep_link("https://session/a/b").is_err());
+        assert!(parse_deep_link("lovcode://session/only-project").is_err());
+        assert!(parse_deep_link("lovcode://session/%2E%2E/abc").is_err());
+    }
+
+    #[test]
+    fn test_find_feature_by_id_or_seq() {
+        let data: WorkspaceData = serde_json::from_value(serde_json::json!({
+            "projects": [{
+                "id": "proj-1",
+                "name": "App",
+                "path": "/tmp/app",
+                "created_at": 0,
+                "features": [
+                    { "id": "feat-a", "seq": 7, "name": "A", "status": "pending", "panels": [], "created_at": 0 }
+                ]
+            }],
+            "active_project_id": null
+        }))
+        .expect("workspace");
+        let expected = Some(("proj-1".to_string(), "feat-a".to_string()));
+        assert_eq!(find_feature(&data, "feat-a"), expected);
+        assert_eq!(find_feature(&data, "7"), expected);
+        assert_eq!(find_feature(&data, "#7"), expected);
+        assert_eq!(find_feature(&data, "8"), None);
+    }
+}
@@ -0,0 +1,934 @@
+/**
+ * [INPUT]: 依赖 notify 文件监听, crate::workspace_store (会话自动关联), claude CLI (-p 无头模式, 经 claude_binary 使用首选安装), crate::commands::knowledge 的 distill 目录/索引/开关, tauri::Emitter
+ * [OUTPUT]: 对外提供 start_session_watcher 后台监听 (新会话关联工作区功能), distill_session_file 会话摘要提炼, distill_with_claude/distill_sessions_with_claude 调用 claude -p 蒸馏, run_claude_print (被 claude_md_gen 复用), kill_process_group 连同子进程一并终止 (task_queue 复用), DistillProgress 事件
+ * [POS]: services/ 的会话蒸馏服务，监听 ~/.claude/projects 并把已结束的会话提炼进 distill 目录
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::claude_binary::with_preferred_claude;
+use crate::commands::knowledge::{
+    append_distill_index, get_distill_dir, read_distill_index, write_distill_index,
+    DistillDocument, DISTILL_INDEX_LOCK, DISTILL_WATCH_ENABLED,
+};
+use crate::security;
+use crate::workspace_store;
+use notify::{Event, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// A session counts as finished once its transcript has been quiet this long
+const SESSION_IDLE: Duration = Duration::from_secs(300);
+/// How often pending sessions are checked for idleness
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Sessions with fewer real prompts are not worth a note
+const MIN_USER_PROMPTS: usize = 2;
+const MAX_LISTED_PROMPTS: usize = 20;
+/// Transcript budget handed to `claude -p`; longer sessions keep their head and tail
+const MAX_TRANSCRIPT_CHARS: usize = 120_000;
+const CLAUDE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Placeholders: {{transcript}}, {{project}}, {{session_id}}, {{date}}
+pub const DEFAULT_DISTILL_TEMPLATE: &str =
+    "You are distilling a Claude Code session into a reusable knowledge note.
+
+Project: {{project}}
+Session: {{session_id}}
+Date: {{date}}
+
+Write a concise Markdown note that captures what was learned, not a play-by-play:
+- Start with a single `# Title` line naming the problem or technique.
+- Then a line `Tags: tag1, tag2` with 1-5 lowercase tags.
+- Sections: Context, Key Insights, Solution (with the essential code), Pitfalls.
+- Omit anything specific to this one run that would not help next time.
+
+Reply with the note only.
+
+<transcript>
+{{transcript}}
+</transcript>
+";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Payload of the "distill-progress" event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistillProgress {
+    pub session_id: String,
+    pub project_id: String,
+    /// "queued" | "processing" | "done" | "skipped" | "error"
+    pub stage: String,
+    /// Distill file name once written
+    pub file: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Payload of the "feature-session-linked" event
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureSessionLinked {
+    pub project_id: String,
+    pub feature_id: String,
+    pub session_id: String,
+}
+
+#[derive(Debug, Default)]
+struct SessionDigest {
+    title: Option<String>,
+    cwd: Option<String>,
+    first_timestamp: Option<String>,
+    prompts: Vec<String>,
+    files: Vec<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn session_ids(path: &Path) -> (String, String) {
+    let session_id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let project_id = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (session_id, project_id)
+}
+
+fn is_main_session(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "jsonl")
+        && !path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with("agent-"))
+}
+
+/// Typed prompts only: skips meta lines, tool results and command/hook wrappers
+fn user_prompt(line: &serde_json::Value) -> Option<String> {
+    if line.get("type").and_then(|t| t.as_str()) != Some("user")
+        || line.get("isMeta").and_then(serde_json::Value::as_bool) == Some(true)
+    {
+        return None;
+    }
+    let text = line.get("message")?.get("content")?.as_str()?.trim();
+    if text.is_empty() || text.starts_with('<') {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+fn digest_session(content: &str) -> SessionDigest {
+    let mut digest = SessionDigest::default();
+
+    for raw in content.lines() {
+        let Ok(line) = serde_json::from_str::<serde_json::Value>(raw) else {
+            continue;
+        };
+        if line.get("type").and_then(|t| t.as_str()) == Some("summary") {
+            if let Some(summary) = line.get("summary").and_then(|s| s.as_str()) {
+                digest.title = Some(summary.to_string());
+            }
+            continue;
+        }
+        if digest.cwd.is_none() {
+            digest.cwd = line.get("cwd").and_then(|c| c.as_str()).map(String::from);
+        }
+        if digest.first_timestamp.is_none() {
+            digest.first_timestamp = line
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .map(String::from);
+        }
+        if let Some(prompt) = user_prompt(&line) {
+            digest.prompts.push(prompt);
+        }
+
+        let blocks = line
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array());
+        for block in blocks.into_iter().flatten() {
+            if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let file = block
+                .get("input")
+                .and_then(|i| i.get("file_path").or_else(|| i.get("notebook_path")))
+                .and_then(|f| f.as_str());
+            if let Some(file) = file {
+                if !digest.files.iter().any(|f| f == file) {
+                    digest.files.push(file.to_string());
+                }
+            }
+        }
+    }
+    digest
+}
+
+fn first_line_truncated(text: &str, max_chars: usize) -> String {
+    let line = text.lines().next().unwrap_or("").trim();
+    if line.chars().count() > max_chars {
+        format!("{}…", line.chars().take(max_chars).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn render_digest(session_id: &str, title: &str, digest: &SessionDigest) -> String {
+    let mut md = format!(
+        "---\nsession: {}\nproject: {}\ndate: {}\ntags: [auto]\n---\n\n# {}\n\n## Prompts\n\n",
+        session_id,
+        digest.cwd.as_deref().unwrap_or(""),
+        digest.first_timestamp.as_deref().unwrap_or(""),
+        title
+    );
+    for (i, prompt) in digest.prompts.iter().take(MAX_LISTED_PROMPTS).enumerate() {
+        md.push_str(&format!(
+            "{}. {}\n",
+            i + 1,
+            first_line_truncated(prompt, 200)
+        ));
+    }
+    if digest.prompts.len() > MAX_LISTED_PROMPTS {
+        md.push_str(&format!(
+            "\n_…and {} more_\n",
+            digest.prompts.len() - MAX_LISTED_PROMPTS
+        ));
+    }
+    if !digest.files.is_empty() {
+        md.push_str("\n## Files Touched\n\n");
+        for file in &digest.files {
+            md.push_str(&format!("- `{}`\n", file));
+        }
+    }
+    md
+}
+
+/// Extract a summary note for one session into `distill_dir` and index it.
+/// Returns None when the session was already distilled or is too short to be useful.
+pub fn distill_session_file(
+    session_path: &Path,
+    distill_dir: &Path,
+) -> Result<Option<DistillDocument>, String> {
+    let (session_id, _) = session_ids(session_path);
+    let content =
+        fs::read_to_string(session_path).map_err(|e| format!("Failed to read session: {}", e))?;
+    let digest = digest_session(&content);
+    if digest.prompts.len() < MIN_USER_PROMPTS {
+        return Ok(None);
+    }
+
+    let _guard = DISTILL_INDEX_LOCK
+        .lock()
+        .map_err(|_| "Distill index lock poisoned")?;
+    if read_distill_index(distill_dir)
+        .iter()
+        .any(|d| d.session.as_deref() == Some(session_id.as_str()))
+    {
+        return Ok(None);
+    }
+
+    let title = digest
+        .title
+        .clone()
+        .unwrap_or_else(|| first_line_truncated(&digest.prompts[0], 60));
+    let now = chrono::Local::now();
+    let short_id: String = session_id.chars().take(8).collect();
+    let file = format!("{}-{}.md", now.format("%Y-%m-%d"), short_id);
+
+    fs::create_dir_all(distill_dir).map_err(|e| e.to_string())?;
+    security::atomic_write_string(
+        &distill_dir.join(&file),
+        &render_digest(&session_id, &title, &digest),
+    )
+    .map_err(|e| format!("Failed to write distill note: {}", e))?;
+
+    let doc = DistillDocument {
+        date: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        file,
+        title,
+        tags: vec!["auto".to_string()],
+        session: Some(session_id),
+    };
+    append_distill_index(distill_dir, &doc)?;
+    Ok(Some(doc))
+}
+
+/// Distill one finished session, reporting each stage through `emit`
+fn process_session(session_path: &Path, distill_dir: &Path, emit: &dyn Fn(DistillProgress)) {
+    let (session_id, project_id) = session_ids(session_path);
+    let progress = |stage: &str, file: Option<String>, error: Option<String>| DistillProgress {
+        session_id: session_id.clone(),
+        project_id: project_id.clone(),
+        stage: stage.to_string(),
+        file,
+        error,
+    };
+
+    emit(progress("processing", None, None));
+    match distill_session_file(session_path, distill_dir) {
+        Ok(Some(doc)) => emit(progress("done", Some(doc.file), None)),
+        Ok(None) => emit(progress("skipped", None, None)),
+        Err(e) => {
+            tracing::warn!("Failed to distill session {}: {}", session_id, e);
+            emit(progress("error", None, Some(e)));
+        }
+    }
+}
+
+/// Remove and return sessions that have been idle for at least `idle`
+fn take_idle_sessions(
+    pending: &mut HashMap<PathBuf, Instant>,
+    now: Instant,
+    idle: Duration,
+) -> Vec<PathBuf> {
+    let mut ready: Vec<PathBuf> = pending
+        .iter()
+        .filter(|(_, last)| now.duration_since(**last) >= idle)
+        .map(|(path, _)| path.clone())
+        .collect();
+    ready.sort();
+    for path in &ready {
+        pending.remove(path);
+    }
+    ready
+}
+
+// ============================================================================
+// Claude Pipeline
+// ============================================================================
+
+/// Plain-text transcript of a session for prompting: user prompts, assistant text,
+/// and one line per tool call
+fn render_transcript(content: &str) -> String {
+    let mut out = String::new();
+    for raw in content.lines() {
+        let Ok(line) = serde_json::from_str::<serde_json::Value>(raw) else {
+            continue;
+        };
+        if let Some(prompt) = user_prompt(&line) {
+            out.push_str(&format!("## User\n{}\n\n", prompt));
+            continue;
+        }
+        if line.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+            continue;
+        }
+        let blocks = line
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array());
+        for block in blocks.into_iter().flatten() {
+            match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => {
+                    if let Some(text) = block.get("text").and_then(|t| t.as_str()) {
+                        out.push_str(&format!("## Assistant\n{}\n\n", text.trim()));
+                    }
+                }
+                Some("tool_use") => {
+                    let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+                    let target = block
+                        .get("input")
+                        .and_then(|i| {
+                            ["file_path", "command", "pattern", "url"]
+                                .iter()
+                                .find_map(|k| i.get(*k).and_then(|v| v.as_str()))
+                        })
+                        .map(|t| first_line_truncated(t, 120))
+                        .unwrap_or_default();
+                    out.push_str(&format!("[{} {}]\n\n", name, target));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let total = out.chars().count();
+    if total <= MAX_TRANSCRIPT_CHARS {
+        return out;
+    }
+    let head = MAX_TRANSCRIPT_CHARS * 2 / 5;
+    let tail = MAX_TRANSCRIPT_CHARS - head;
+    format!(
+        "{}\n\n[... {} characters omitted ...]\n\n{}",
+        out.chars().take(head).collect::<String>(),
+        total - head - tail,
+        out.chars().skip(total - tail).collect::<String>()
+    )
+}
+
+fn fill_template(
+    template: &str,
+    transcript: &str,
+    project: &str,
+    session_id: &str,
+    date: &str,
+) -> String {
+    // Transcript last so placeholders inside the session text are left alone
+    template
+        .replace("{{project}}", project)
+        .replace("{{session_id}}", session_id)
+        .replace("{{date}}", date)
+        .replace("{{transcript}}", transcript)
+}
+
+/// Pull the `# Title` and `Tags:` lines out of the model's note. Returns (title, tags, body);
+/// the Tags line is removed from the body.
+fn parse_distill_output(output: &str) -> (Option<String>, Vec<String>, String) {
+    let mut title = None;
+    let mut tags = Vec::new();
+    let mut body = Vec::new();
+    for line in output.trim().lines() {
+        if title.is_none() {
+            if let Some(t) = line.strip_prefix("# ") {
+                title = Some(t.trim().to_string());
+            }
+        }
+        if tags.is_empty() {
+            let lower = line.trim_start().to_lowercase();
+            if lower.starts_with("tags:") {
+                tags = line
+                    .trim_start()
+                    .get(5..)
+                    .unwrap_or("")
+                    .split(',')
+                    .map(|t| {
+                        t.trim()
+                            .trim_matches('`')
+                            .trim_start_matches('#')
+                            .to_lowercase()
+                    })
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                continue;
+            }
+        }
+        body.push(line);
+    }
+    (title, tags, body.join("\n"))
+}
+
+/// Kill a child spawned with `process_group(0)` together with everything it started;
+/// killing only the login shell would leave `claude` running with the pipes open
+pub fn kill_process_group(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = i32::try_from(child.id()) {
+        // SAFETY: killpg only sends a signal; the group was created for this child
+        unsafe {
+            libc::killpg(pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Run `claude -p` (the preferred binary if one is set) through the login shell
+/// (GUI apps lack the user's PATH), feeding the prompt on stdin
+pub fn run_claude_print(prompt: &str, cwd: &Path) -> Result<String, String> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+    let mut cmd = Command::new(&shell);
+    cmd.arg("-ilc")
+        .arg(with_preferred_claude("claude -p --output-format text"))
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start claude: {}", e))?;
+
+    let mut stdin = child.stdin.take().ok_or("Failed to open claude stdin")?;
+    let input = prompt.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut stdout = child.stdout.take().ok_or("Failed to open claude stdout")?;
+    let reader = std::thread::spawn(move || {
+        let mut out = String::new();
+        stdout.read_to_string(&mut out).map(|_| out)
+    });
+    let mut stderr = child.stderr.take().ok_or("Failed to open claude stderr")?;
+    let err_reader = std::thread::spawn(move || {
+        let mut err = String::new();
+        let _ = stderr.read_to_string(&mut err);
+        err
+    });
+
+    let deadline = Instant::now() + CLAUDE_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                kill_process_group(&mut child);
+                return Err("claude -p timed out".to_string());
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(200)),
+            Err(e) => return Err(format!("Failed to wait for claude: {}", e)),
+        }
+    };
+
+    let _ = writer.join();
+    let output = reader
+        .join()
+        .map_err(|_| "claude output reader panicked")?
+        .map_err(|e| format!("Failed to read claude output: {}", e))?;
+    let stderr = err_reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("claude -p failed: {}", stderr.trim()));
+    }
+    if output.trim().is_empty() {
+        return Err("claude -p returned no output".to_string());
+    }
+    Ok(output)
+}
+
+/// Resolve ~/.claude/projects/<project_id>/<session_id>.jsonl, rejecting path-like ids
+pub fn session_transcript_path(project_id: &str, session_id: &str) -> Result<PathBuf, String> {
+    let invalid =
+        |id: &str| id.is_empty() || id.contains('/') || id.contains('\\') || id.contains("..");
+    if invalid(project_id) || invalid(session_id) {
+        return Err("Invalid project or session id".to_string());
+    }
+    let path = security::get_claude_dir_or_fallback()
+        .join("projects")
+        .join(project_id)
+        .join(format!("{}.jsonl", session_id));
+    if !path.exists() {
+        return Err(format!("Session not found: {}", session_id));
+    }
+    Ok(path)
+}
+
+/// Write a Claude-produced note for a session. Replaces the index entry with the same
+/// file name and supersedes the heuristic "auto" digest of that session.
+fn store_claude_distill(
+    distill_dir: &Path,
+    session_id: &str,
+    project: &str,
+    output: &str,
+) -> Result<DistillDocument, String> {
+    let (title, tags, body) = parse_distill_output(output);
+    let now = chrono::Local::now();
+    let short_id: String = session_id.chars().take(8).collect();
+    let file = format!("{}-{}.md", now.format("%Y-%m-%d"), short_id);
+    let doc = DistillDocument {
+        date: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        file: file.clone(),
+        title: title.unwrap_or_else(|| format!("Session {}", short_id)),
+        tags,
+        session: Some(session_id.to_string()),
+    };
+    let note = format!(
+        "---\nsession: {}\nproject: {}\ndate: {}\ntags: [{}]\n---\n\n{}\n",
+        session_id,
+        project,
+        doc.date,
+        doc.tags.join(", "),
+        body
+    );
+
+    fs::create_dir_all(distill_dir).map_err(|e| e.to_string())?;
+    let _guard = DISTILL_INDEX_LOCK
+        .lock()
+        .map_err(|_| "Distill index lock poisoned")?;
+    security::atomic_write_string(&distill_dir.join(&file), &note)
+        .map_err(|e| format!("Failed to write distill note: {}", e))?;
+
+    let mut docs = read_distill_index(distill_dir);
+    docs.retain(|d| {
+        let superseded = d.session.as_deref() == Some(session_id) && d.tags == ["auto"];
+        if superseded && d.file != file {
+            let _ = fs::remove_file(distill_dir.join(&d.file));
+        }
+        d.file != file && !superseded
+    });
+    docs.push(doc.clone());
+    write_distill_index(distill_dir, &docs)?;
+    Ok(doc)
+}
+
+/// Distill one session through `claude -p` with `template` (see DEFAULT_DISTILL_TEMPLATE)
+pub fn distill_with_claude(
+    session_path: &Path,
+    distill_dir: &Path,
+    template: &str,
+) -> Result<DistillDocument, String> {
+    let (session_id, _) = session_ids(session_path);
+    let content =
+        fs::read_to_string(session_path).map_err(|e| format!("Failed to read session: {}", e))?;
+    let digest = digest_session(&content);
+    let project = digest.cwd.clone().unwrap_or_default();
+    let date = digest
+        .first_timestamp
+        .as_deref()
+        .and_then(|t| t.get(..10))
+        .unwrap_or("");
+
+    let prompt = fill_template(
+        template,
+        &render_transcript(&content),
+        &project,
+        &session_id,
+        date,
+    );
+    // Run from the distill dir so the headless run is not filed under the user's project
+    fs::create_dir_all(distill_dir).map_err(|e| e.to_string())?;
+    let output = run_claude_print(&prompt, distill_dir)?;
+    store_claude_distill(distill_dir, &session_id, &project, &output)
+}
+
+/// Main session files last modified within [start_ms, end_ms), oldest first
+pub fn sessions_in_range(projects_dir: &Path, start_ms: i64, end_ms: i64) -> Vec<PathBuf> {
+    let mut sessions: Vec<(i64, PathBuf)> = Vec::new();
+    for project in fs::read_dir(projects_dir).into_iter().flatten().flatten() {
+        for entry in fs::read_dir(project.path()).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if !is_main_session(&path) {
+                continue;
+            }
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64);
+            if let Some(ms) = modified.filter(|ms| *ms >= start_ms && *ms < end_ms) {
+                sessions.push((ms, path));
+            }
+        }
+    }
+    sessions.sort();
+    sessions.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Batch-distill sessions through Claude, reporting each stage through `emit`.
+/// Sessions that already have a non-"auto" note are skipped unless `force`.
+pub fn distill_sessions_with_claude(
+    sessions: &[PathBuf],
+    distill_dir: &Path,
+    template: &str,
+    force: bool,
+    emit: &dyn Fn(DistillProgress),
+) -> Vec<DistillProgress> {
+    let distilled: Vec<String> = read_distill_index(distill_dir)
+        .into_iter()
+        .filter(|d| d.tags != ["auto"])
+        .filter_map(|d| d.session)
+        .collect();
+    let progress = |path: &Path, stage: &str, file: Option<String>, error: Option<String>| {
+        let (session_id, project_id) = session_ids(path);
+        DistillProgress {
+            session_id,
+            project_id,
+            stage: stage.to_string(),
+            file,
+            error,
+        }
+    };
+
+    for path in sessions {
+        emit(progress(path, "queued", None, None));
+    }
+    let mut results = Vec::new();
+    for path in sessions {
+        let (session_id, _) = session_ids(path);
+        let result = if !force && distilled.contains(&session_id) {
+            progress(path, "skipped", None, None)
+        } else {
+            emit(progress(path, "processing", None, None));
+            match distill_with_claude(path, distill_dir, template) {
+                Ok(doc) => progress(path, "done", Some(doc.file), None),
+                Err(e) => progress(path, "error", None, Some(e)),
+            }
+        };
+        emit(result.clone());
+        results.push(result);
+    }
+    results
+}
+
+// ============================================================================
+// Watcher
+// ============================================================================
+
+/// Attach a just-created session to its workspace feature and tell the frontend
+fn link_new_session(app_handle: &AppHandle, session_path: &Path) {
+    let (session_id, project_id) = session_ids(session_path);
+    match workspace_store::auto_link_session(&project_id, &session_id) {
+        Ok(Some((project_id, feature_id))) => {
+            let _ = app_handle.emit(
+                "feature-session-linked",
+                FeatureSessionLinked {
+                    project_id,
+                    feature_id,
+                    session_id,
+                },
+            );
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to link session {}: {}", session_id, e),
+    }
+}
+
+/// Watch ~/.claude/projects: link new sessions to workspace features and
+/// distill sessions once they go quiet.
+/// Runs on its own thread for the lifetime of the app; does nothing while the
+/// distill watch toggle is off.
+pub fn start_session_watcher(app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let projects_dir = security::get_claude_dir_or_fallback().join("projects");
+        if !projects_dir.exists() {
+            let _ = fs::create_dir_all(&projects_dir);
+        }
+
+        // (session path, whether the event created the file)
+        let (tx, rx) = channel::<(PathBuf, bool)>();
+        let mut watcher =
+            match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    if event.kind.is_create() || event.kind.is_modify() {
+                        let created = event.kind.is_create();
+                        for path in event.paths.into_iter().filter(|p| is_main_session(p)) {
+                            let _ = tx.send((path, created));
+                        }
+                    }
+                }
+            }) {
+                Ok(w) => w,
+                Err(e) => {
+                    tracing::warn!("Failed to create session watcher: {}", e);
+                    return;
+                }
+            };
+        if let Err(e) = watcher.watch(&projects_dir, RecursiveMode::Recursive) {
+            tracing::warn!("Failed to watch {}: {}", projects_dir.display(), e);
+            return;
+        }
+
+        let emit = |progress: DistillProgress| {
+            let _ = app_handle.emit("distill-progress", progress);
+        };
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+        let mut last_check = Instant::now();
+        loop {
+            match rx.recv_timeout(IDLE_CHECK_INTERVAL) {
+                Ok((path, created)) => {
+                    if created {
+                        link_new_session(&app_handle, &path);
+                    }
+                    if DISTILL_WATCH_ENABLED.load(std::sync::atomic::Ordering::Relaxed) {
+                        pending.insert(path, Instant::now());
+                    }
+                    // A steady stream of writes must not starve the idle check
+                    if last_check.elapsed() < IDLE_CHECK_INTERVAL {
+                        continue;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            last_check = Instant::now();
+
+            if !DISTILL_WATCH_ENABLED.load(std::sync::atomic::Ordering::Relaxed) {
+                pending.clear();
+                continue;
+            }
+            let ready = take_idle_sessions(&mut pending, Instant::now(), SESSION_IDLE);
+            let distill_dir = get_distill_dir();
+            for path in &ready {
+                let (session_id, project_id) = session_ids(path);
+                emit(DistillProgress {
+                    session_id,
+                    project_id,
+                    stage: "queued".to_string(),
+                    file: None,
+                    error: None,
+                });
+            }
+            for path in ready {
+                process_session(&path, &distill_dir, &emit);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn session_lines() -> String {
+        [
+            r#"{"type":"user","cwd":"/tmp/demo","timestamp":"2026-01-01T09:00:00Z","message":{"role":"user","content":"Fix the login bug\nDetails follow"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/tmp/demo/auth.rs"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"ok"}]}}"#,
+            r#"{"type":"user","isMeta":true,"message":{"content":"Caveat: meta"}}"#,
+            r#"{"type":"user","message":{"content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","message":{"content":"Now add a test"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Write","input":{"file_path":"/tmp/demo/auth.rs"}}]}}"#,
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn test_digest_session() {
+        let digest = digest_session(&session_lines());
+        assert_eq!(
+            digest.prompts,
+            vec!["Fix the login bug\nDetails follow", "Now add a test"]
+        );
+        assert_eq!(digest.files, vec!["/tmp/demo/auth.rs"]);
+        assert_eq!(digest.cwd.as_deref(), Some("/tmp/demo"));
+        assert_eq!(digest.title, None);
+    }
+
+    #[test]
+    fn test_distill_session_file_writes_once() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let session = temp.path().join("-tmp-demo").join("abcdef1234.jsonl");
+        fs::create_dir_all(session.parent().expect("parent")).expect("create project");
+        fs::write(&session, session_lines()).expect("write session");
+        let distill_dir = temp.path().join("distill");
+
+        let doc = distill_session_file(&session, &distill_dir)
+            .expect("distill")
+            .expect("document");
+        assert_eq!(doc.title, "Fix the login bug");
+        assert_eq!(doc.session.as_deref(), Some("abcdef1234"));
+        assert!(doc.file.ends_with("-abcdef12.md"));
+        let note = fs::read_to_string(distill_dir.join(&doc.file)).expect("read note");
+        assert!(note.contains("session: abcdef1234\n"));
+        assert!(note.contains("1. Fix the login bug\n2. Now add a test\n"));
+        assert!(note.contains("- `/tmp/demo/auth.rs`"));
+
+        // Already indexed: skipped
+        assert!(distill_session_file(&session, &distill_dir)
+            .expect("distill again")
+            .is_none());
+        assert_eq!(read_distill_index(&distill_dir).len(), 1);
+    }
+
+    #[test]
+    fn test_process_session_reports_stages() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let session = temp.path().join("-tmp-demo").join("short.jsonl");
+        fs::create_dir_all(session.parent().expect("parent")).expect("create project");
+        fs::write(&session, r#"{"type":"user","message":{"content":"hi"}}"#)
+            .expect("write session");
+
+        let events = RefCell::new(Vec::new());
+        process_session(&session, &temp.path().join("distill"), &|p| {
+            events.borrow_mut().push(p.stage);
+        });
+        assert_eq!(events.into_inner(), vec!["processing", "skipped"]);
+    }
+
+    #[test]
+    fn test_take_idle_sessions() {
+        let start = Instant::now();
+        let mut pending = HashMap::from([
+            (PathBuf::from("/a.jsonl"), start),
+            (PathBuf::from("/b.jsonl"), start + Duration::from_secs(200)),
+        ]);
+        let ready =
+            take_idle_sessions(&mut pending, start + Duration::from_secs(300), SESSION_IDLE);
+        assert_eq!(ready, vec![PathBuf::from("/a.jsonl")]);
+        assert_eq!(pending.len(), 1);
+        assert!(is_main_session(Path::new("/p/x.jsonl")));
+        assert!(!is_main_session(Path::new("/p/agent-x.jsonl")));
+    }
+
+    #[test]
+    fn test_render_transcript_and_template() {
+        let content = [
+            r#"{"type":"user","message":{"content":"Why is {{date}} empty?"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":" Checking. "},{"type":"tool_use","name":"Bash","input":{"command":"cargo test\nmore"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"ok"}]}}"#,
+        ]
+        .join("\n");
+        let transcript = render_transcript(&content);
+        assert_eq!(
+            transcript,
+            "## User\nWhy is {{date}} empty?\n\n## Assistant\nChecking.\n\n[Bash cargo test]\n\n"
+        );
+
+        let prompt = fill_template(
+            "{{project}}|{{session_id}}|{{date}}|{{transcript}}",
+            &transcript,
+            "/tmp/demo",
+            "abc",
+            "2026-01-01",
+        );
+        assert!(prompt.starts_with("/tmp/demo|abc|2026-01-01|## User\nWhy is {{date}} empty?"));
+    }
+
+    #[test]
+    fn test_parse_distill_output() {
+        let output =
+            "\n# Retry flaky IPC\nTags: Tauri, `ipc`, #retry\n\n## Key Insights\nTags: stay\n";
+        let (title, tags, body) = parse_distill_output(output);
+        assert_eq!(title.as_deref(), Some("Retry flaky IPC"));
+        assert_eq!(tags, vec!["tauri", "ipc", "retry"]);
+        assert_eq!(body, "# Retry flaky IPC\n\n## Key Insights\nTags: stay");
+
+        let (title, tags, _) = parse_distill_output("just text");
+        assert_eq!(title, None);
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn test_claude_distill_supersedes_auto_digest() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let projects_dir = temp.path().join("projects");
+        let session = projects_dir.join("-tmp-demo").join("abcdef1234.jsonl");
+        fs::create_dir_all(session.parent().expect("parent")).expect("create project");
+        fs::write(&session, session_lines()).expect("write session");
+        let distill_dir = temp.path().join("distill");
+
+        let auto = distill_session_file(&session, &distill_dir)
+            .expect("auto distill")
+            .expect("auto document");
+        let doc = store_claude_distill(
+            &distill_dir,
+            "abcdef1234",
+            "/tmp/demo",
+            "# Login fix\nTags: auth\n\nBody",
+        )
+        .expect("store");
+        assert_eq!(doc.title, "Login fix");
+        assert_eq!(doc.tags, vec!["auth"]);
+        // Same day, same short id: the note replaces the auto digest in place
+        assert_eq!(doc.file, auto.file);
+        let index = read_distill_index(&distill_dir);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].title, "Login fix");
+        let note = fs::read_to_string(distill_dir.join(&doc.file)).expect("read note");
+        assert!(note.contains("tags: [auth]\n---\n\n# Login fix\n\nBody\n"));
+
+        // Batch mode skips sessions that already have a Claude note
+        let events = RefCell::new(Vec::new());
+        let results = distill_sessions_with_claude(
+            std::slice::from_ref(&session),
+            &distill_dir,
+            DEFAULT_DISTILL_TEMPLATE,
+            false,
+            &|p| events.borrow_mut().push(p.stage),
+        );
+        assert_eq!(results[0].stage, "skipped");
+        assert_eq!(events.into_inner(), vec!["queued", "skipped"]);
+
+        let in_range = sessions_in_range(&projects_dir, 0, i64::MAX);
+        assert_eq!(in_range, vec![session]);
+        assert!(sessions_in_range(&projects_dir, 0, 1).is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+/**
+ * [INPUT]: 依赖 notify 文件监听, tauri::Emitter, crate::security
+ * [OUTPUT]: 对外提供 watch_directory, unwatch_directory 命令与 FsChangedEvent, set_paused/is_paused 供托盘暂停监听
+ * [POS]: services/ 模块的项目目录监听，合并短时间内的变更后发出 fs-changed 事件，供文件树/git 状态自动刷新
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::security;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Payload of the "fs-changed" event
+#[derive(Debug, Clone, Serialize)]
+pub struct FsChangedEvent {
+    pub watch_id: String,
+    /// Directory passed to watch_directory
+    pub root: String,
+    /// Created, modified or removed paths, sorted. Anything inside .git is reported
+    /// as the .git directory itself.
+    pub paths: Vec<String>,
+    /// More paths changed than are listed (e.g. npm install); refresh everything
+    pub truncated: bool,
+}
+
+/// A batch is sent once no change arrived for this long...
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// ...or when it has been collecting for this long, so steady writes still refresh
+const MAX_BATCH_WAIT: Duration = Duration::from_secs(2);
+const MAX_EVENT_PATHS: usize = 500;
+/// How often a held batch checks whether watchers were resumed
+const PAUSE_POLL: Duration = Duration::from_millis(500);
+
+/// While set, changes are collected but not sent; they go out as one batch on resume
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Dropping a watcher stops it and ends its debounce thread
+static WATCHERS: LazyLock<Mutex<HashMap<String, RecommendedWatcher>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Report `path` under `root` as given by the caller; notify may use the canonical
+/// spelling (`watched`), e.g. /private/var on macOS
+fn event_path(watched: &Path, root: &Path, path: &Path) -> PathBuf {
+    let Ok(rel) = path
+        .strip_prefix(watched)
+        .or_else(|_| path.strip_prefix(root))
+    else {
+        return path.to_path_buf();
+    };
+    match rel.components().next() {
+        Some(Component::Normal(first)) if first == ".git" => root.join(".git"),
+        _ => root.join(rel),
+    }
+}
+
+/// Wait for a change, then collect more until things go quiet for `quiet` or
+/// `max_wait` has passed. None once the watcher is gone.
+fn next_batch(
+    rx: &Receiver<PathBuf>,
+    quiet: Duration,
+    max_wait: Duration,
+) -> Option<BTreeSet<PathBuf>> {
+    let first = rx.recv().ok()?;
+    let started = Instant::now();
+    let mut batch = BTreeSet::from([first]);
+    loop {
+        let remaining = max_wait.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            break;
+        }
+        match rx.recv_timeout(quiet.min(remaining)) {
+            // One past the limit marks the batch as truncated
+            Ok(path) => {
+                if batch.len() <= MAX_EVENT_PATHS {
+                    batch.insert(path);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+    Some(batch)
+}
+
+fn emit_batches(app: &AppHandle, watch_id: &str, root: &Path, rx: &Receiver<PathBuf>) {
+    while let Some(mut batch) = next_batch(rx, DEBOUNCE, MAX_BATCH_WAIT) {
+        while PAUSED.load(Ordering::Relaxed) {
+            match rx.recv_timeout(PAUSE_POLL) {
+                Ok(path) => {
+                    if batch.len() <= MAX_EVENT_PATHS {
+                        batch.insert(path);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        let truncated = batch.len() > MAX_EVENT_PATHS;
+        let event = FsChangedEvent {
+            watch_id: watch_id.to_string(),
+            root: root.to_string_lossy().to_string(),
+            paths: batch
+                .iter()
+                .take(MAX_EVENT_PATHS)
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            truncated,
+        };
+        if let Err(e) = app.emit("fs-changed", event) {
+            tracing::warn!(watch_id, error = %e, "Failed to emit fs-changed event");
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Hold "fs-changed" events for every watch, e.g. during a big checkout
+pub fn set_paused(paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+}
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Watch `path` recursively; returns the watch id used in "fs-changed" events
+#[tauri::command]
+pub fn watch_directory(app: AppHandle, path: String) -> AppResult<String> {
+    security::validate_decoded_path(&path)?;
+    let root = PathBuf::from(&path);
+    if !root.is_dir() {
+        return Err(AppError::invalid_input(format!("Not a directory: {}", path)));
+    }
+    let watched = root.canonicalize().unwrap_or_else(|_| root.clone());
+
+    let (tx, rx) = channel::<PathBuf>();
+    let (callback_watched, callback_root) = (watched.clone(), root.clone());
+    let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+            for changed in &event.paths {
+                let _ = tx.send(event_path(&callback_watched, &callback_root, changed));
+            }
+        }
+    })
+    .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to create watcher: {}", e)))?;
+    watcher
+        .watch(&watched, RecursiveMode::Recursive)
+        .map_err(|e| AppError::new(ErrorCode::Io, format!("Failed to watch {}: {}", path, e)))?;
+
+    let watch_id = uuid::Uuid::new_v4().to_string();
+    WATCHERS
+        .lock()
+        .map_err(|_| "Watcher registry lock poisoned")?
+        .insert(watch_id.clone(), watcher);
+
+    let thread_id = watch_id.clone();
+    std::thread::spawn(move || emit_batches(&app, &thread_id, &root, &rx));
+    Ok(watch_id)
+}
+
+#[tauri::command]
+pub fn unwatch_directory(id: String) -> AppResult<()> {
+    WATCHERS
+        .lock()
+        .map_err(|_| "Watcher registry lock poisoned")?
+        .remove(&id)
+        .map(drop)
+        .ok_or_else(|| AppError::not_found(format!("Watch not found: {}", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_path_maps_root_and_collapses_git() {
+        let watched = Path::new("/private/tmp/project");
+        let root = Path::new("/tmp/project");
+        assert_eq!(
+            event_path(watched, root, Path::new("/private/tmp/project/src/main.rs")),
+            PathBuf::from("/tmp/project/src/main.rs")
+        );
+        assert_eq!(
+            event_path(
+                watched,
+                root,
+                Path::new("/tmp/project/.git/objects/ab/cdef")
+            ),
+            PathBuf::from("/tmp/project/.git")
+        );
+        // Only the top-level .git is collapsed
+        assert_eq!(
+            event_path(watched, root, Path::new("/tmp/project/.github/ci.yml")),
+            PathBuf::from("/tmp/project/.github/ci.yml")
+        );
+    }
+
+    #[test]
+    fn test_next_batch_debounces_and_stops_when_unwatched() {
+        let (tx, rx) = channel::<PathBuf>();
+        for name in ["b.rs", "a.rs", "b.rs"] {
+            tx.send(PathBuf::from(name)).expect("send");
+        }
+        let batch =
+            next_batch(&rx, Duration::from_millis(20), Duration::from_secs(1)).expect("batch");
+        assert_eq!(
+            batch.into_iter().collect::<Vec<_>>(),
+            vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")]
+        );
+
+        for i in 0..MAX_EVENT_PATHS * 2 {
+            tx.send(PathBuf::from(format!("{}.txt", i))).expect("send");
+        }
+        let batch =
+            next_batch(&rx, Duration::from_millis(20), Duration::from_secs(1)).expect("batch");
+        assert_eq!(batch.len(), MAX_EVENT_PATHS + 1);
+
+        drop(tx);
+        assert!(next_batch(&rx, Duration::from_millis(20), Duration::from_secs(1)).is_none());
+    }
+}
@@ -9,10 +9,65 @@
 // 子模块声明
 // ============================================================================
 
+pub mod api_server; // 本地只读 REST API (可选)
+pub mod backup; // ~/.claude 配置备份与恢复
+pub mod chat_cache; // 全局聊天消息缓存 (SQLite, 去重分页)
+pub mod checkpoint; // 项目快照服务
+pub mod claude_json_history; // ~/.claude.json 变更审计
+pub mod deep_link; // lovcode:// 链接分发
+pub mod distill; // 会话蒸馏服务
+pub mod fs_watcher; // 项目目录变更监听
+pub mod notifications; // 系统通知 (免打扰规则)
+pub mod pty_notify; // 终端空闲/完成通知
+pub mod pty_output; // PTY 输出解析 (Claude 状态检测)
+pub mod redact; // 密钥检测与脱敏
+pub mod scheduler; // 定时 claude -p 任务 (cron)
 pub mod search; // 全文搜索服务
+pub mod task_queue; // 无头 claude -p 任务队列
+pub mod tray; // 系统托盘状态统计
+pub mod webhooks; // Webhook 事件推送 (Slack/Discord/通用)
 
 // ============================================================================
 // 重导出所有服务
 // ============================================================================
 
-pub use search::{build_search_index, extract_content_with_meta, search_chats};
+pub use api_server::{
+    get_api_server_status, regenerate_api_server_token, set_api_server_settings, ApiServerSettings,
+    ApiServerStatus,
+};
+pub use backup::{
+    create_claude_backup, delete_claude_backup, list_claude_backups, restore_claude_backup,
+    BackupChange, BackupChangeKind, BackupRestore, ClaudeBackup,
+};
+pub use checkpoint::{
+    create_checkpoint, delete_checkpoint, list_checkpoints, restore_checkpoint, Checkpoint,
+};
+pub use claude_json_history::{
+    diff_claude_json_history, list_claude_json_history, ClaudeJsonDiff, ClaudeJsonSnapshot,
+    JsonChange, JsonChangeKind,
+};
+pub use deep_link::{open_deep_link, take_pending_deep_link, DeepLink};
+pub use fs_watcher::{unwatch_directory, watch_directory, FsChangedEvent};
+pub use notifications::{
+    get_notification_settings, send_notification, set_notification_settings, AppNotification,
+    NotificationKind, NotificationSettings, QuietHours,
+};
+pub use pty_notify::{get_pty_notify_settings, set_pty_notify_settings, PtyNotifySettings};
+pub use redact::{redact_secrets, scan_session_secrets, SecretFinding};
+pub use scheduler::{
+    delete_scheduled_task, list_scheduled_tasks, schedule_task, set_scheduled_task_enabled,
+    ScheduledTask, ScheduledTaskSpec,
+};
+pub use search::{
+    build_search_index, extract_content_with_meta, extract_thinking, get_search_index_stats,
+    optimize_search_index, remove_project_from_index, search_chats, ProjectDocCount,
+    SearchIndexStats,
+};
+pub use task_queue::{
+    cancel_claude_task, enqueue_claude_task, get_claude_task, list_claude_tasks,
+    remove_claude_task, ClaudeTask, ClaudeTaskOptions, ClaudeTaskProgress, ClaudeTaskStatus,
+};
+pub use webhooks::{
+    add_webhook, list_webhook_deliveries, list_webhooks, remove_webhook, set_webhook_enabled,
+    test_webhook, Webhook, WebhookDelivery, WebhookEvent, WebhookKind,
+};
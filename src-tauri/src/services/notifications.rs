@@ -0,0 +1,277 @@
+/**
+ * [INPUT]: 依赖 tauri-plugin-notification (macOS/Windows/Linux 系统通知), chrono (本地时间), crate::security
+ * [OUTPUT]: 对外提供 init, notify 供长任务调用, send_notification, get/set_notification_settings 命令与 NotificationKind/NotificationSettings/AppNotification
+ * [POS]: services/ 模块的统一系统通知出口，按用户规则 (总开关、静音类型、免打扰时段) 过滤后发送，并广播 app-notification 事件
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock, RwLock};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What a notification is about; each kind can be muted on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A busy terminal went idle or printed a watched pattern
+    Terminal,
+    TaskFinished,
+    TaskFailed,
+    IndexBuilt,
+    FeatureReview,
+    /// Sent by the frontend
+    General,
+}
+
+/// Local-time window without native notifications, e.g. 22:00 to 08:00
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// "HH:MM"
+    pub start: String,
+    /// "HH:MM"; earlier than start means the window crosses midnight
+    pub end: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    pub muted_kinds: Vec<NotificationKind>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            muted_kinds: Vec::new(),
+            quiet_hours: None,
+        }
+    }
+}
+
+/// Payload of the "app-notification" event, sent even when the native notification is held back
+#[derive(Debug, Clone, Serialize)]
+pub struct AppNotification {
+    pub title: String,
+    pub body: String,
+    pub kind: NotificationKind,
+    /// Whether a native notification was shown
+    pub shown: bool,
+}
+
+static SETTINGS: LazyLock<RwLock<NotificationSettings>> =
+    LazyLock::new(|| RwLock::new(load_settings(&settings_path())));
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn settings_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("notifications.json")
+}
+
+fn load_settings(path: &Path) -> NotificationSettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(path: &Path, settings: &NotificationSettings) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content)
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{}' (expected HH:MM)", value))
+}
+
+fn in_quiet_hours(quiet: &QuietHours, now: NaiveTime) -> bool {
+    let (Ok(start), Ok(end)) = (parse_time(&quiet.start), parse_time(&quiet.end)) else {
+        return false;
+    };
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+/// Whether the settings let a notification of `kind` through at `now`
+fn allows(settings: &NotificationSettings, kind: NotificationKind, now: NaiveTime) -> bool {
+    settings.enabled
+        && !settings.muted_kinds.contains(&kind)
+        && !settings
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|quiet| in_quiet_hours(quiet, now))
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Keep the app handle so background work can notify; called once at startup
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Show a native notification unless the user's rules hold it back, and emit
+/// "app-notification" either way. Returns whether it was shown.
+pub fn notify(title: &str, body: &str, kind: NotificationKind) -> bool {
+    let Some(app) = APP_HANDLE.get() else {
+        return false;
+    };
+    let allowed = SETTINGS
+        .read()
+        .is_ok_and(|settings| allows(&settings, kind, chrono::Local::now().time()));
+    let shown = allowed
+        && match app.notification().builder().title(title).body(body).show() {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(?kind, error = %e, "Failed to show notification");
+                false
+            }
+        };
+    let _ = app.emit(
+        "app-notification",
+        AppNotification {
+            title: title.to_string(),
+            body: body.to_string(),
+            kind,
+            shown,
+        },
+    );
+    shown
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Notify from the frontend, following the same rules as backend notifications
+#[tauri::command]
+pub fn send_notification(
+    title: String,
+    body: String,
+    kind: Option<NotificationKind>,
+) -> AppResult<bool> {
+    if title.trim().is_empty() {
+        return Err(AppError::invalid_input(
+            "Notification title cannot be empty",
+        ));
+    }
+    Ok(notify(
+        &title,
+        &body,
+        kind.unwrap_or(NotificationKind::General),
+    ))
+}
+
+#[tauri::command]
+pub fn get_notification_settings() -> AppResult<NotificationSettings> {
+    Ok(SETTINGS
+        .read()
+        .map(|settings| settings.clone())
+        .map_err(|_| "Notification settings lock poisoned".to_string())?)
+}
+
+#[tauri::command]
+pub fn set_notification_settings(settings: NotificationSettings) -> AppResult<()> {
+    if let Some(quiet) = &settings.quiet_hours {
+        parse_time(&quiet.start).map_err(AppError::invalid_input)?;
+        parse_time(&quiet.end).map_err(AppError::invalid_input)?;
+    }
+    save_settings(&settings_path(), &settings)?;
+    let mut current = SETTINGS
+        .write()
+        .map_err(|_| "Notification settings lock poisoned")?;
+    *current = settings;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> NaiveTime {
+        parse_time(value).expect("valid time")
+    }
+
+    #[test]
+    fn test_rules() {
+        let overnight = NotificationSettings {
+            muted_kinds: vec![NotificationKind::Terminal],
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "08:00".to_string(),
+            }),
+            ..NotificationSettings::default()
+        };
+        let allowed =
+            |settings: &NotificationSettings, kind, at: &str| allows(settings, kind, time(at));
+        assert!(allowed(&overnight, NotificationKind::TaskFinished, "12:00"));
+        assert!(allowed(&overnight, NotificationKind::TaskFinished, "08:00"));
+        assert!(!allowed(
+            &overnight,
+            NotificationKind::TaskFinished,
+            "23:30"
+        ));
+        assert!(!allowed(
+            &overnight,
+            NotificationKind::TaskFinished,
+            "07:59"
+        ));
+        assert!(!allowed(&overnight, NotificationKind::Terminal, "12:00"));
+
+        let lunch = QuietHours {
+            start: "12:00".to_string(),
+            end: "13:00".to_string(),
+        };
+        assert!(in_quiet_hours(&lunch, time("12:30")));
+        assert!(!in_quiet_hours(&lunch, time("13:00")));
+
+        let off = NotificationSettings {
+            enabled: false,
+            ..NotificationSettings::default()
+        };
+        assert!(!allowed(&off, NotificationKind::General, "12:00"));
+        assert!(parse_time("25:00").is_err());
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("notifications.json");
+        assert_eq!(load_settings(&path), NotificationSettings::default());
+
+        let settings = NotificationSettings {
+            enabled: true,
+            muted_kinds: vec![NotificationKind::IndexBuilt],
+            quiet_hours: None,
+        };
+        save_settings(&path, &settings).expect("save settings");
+        assert_eq!(load_settings(&path), settings);
+        assert_eq!(
+            serde_json::to_value(&settings.muted_kinds).expect("serialize"),
+            serde_json::json!(["index_built"])
+        );
+    }
+}
@@ -0,0 +1,359 @@
+/**
+ * [INPUT]: 依赖 pty_manager (会话标题), services::pty_output (去除 ANSI), services::notifications (系统通知与免打扰规则), regex, crate::security
+ * [OUTPUT]: 对外提供 start_pty_notifier, record_input/record_output/forget_session 钩子, get/set_pty_notify_settings 命令
+ * [POS]: services/ 模块的终端通知服务，忙碌的终端转为空闲或输出匹配模式时发送系统通知
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::pty_manager;
+use crate::security;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::pty_output::strip_ansi;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PtyNotifySettings {
+    pub enabled: bool,
+    /// A busy session that prints nothing for this long counts as finished
+    pub idle_seconds: u64,
+    /// Regexes matched against output (ANSI stripped), e.g. "Tests? passed"
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for PtyNotifySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_seconds: 10,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Payload of the "pty-notification" event
+#[derive(Debug, Clone, Serialize)]
+pub struct PtyNotification {
+    pub id: String,
+    /// "idle" | "pattern"
+    pub kind: String,
+    pub title: String,
+    pub message: String,
+}
+
+/// Output within this window after input is treated as echo, not work
+const ECHO_WINDOW_MS: i64 = 500;
+/// Sessions busy for less than this don't notify when they go idle
+const MIN_BUSY_MS: i64 = 3_000;
+/// At most one pattern notification per session in this window
+const PATTERN_COOLDOWN_MS: i64 = 30_000;
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Busy/idle bookkeeping for one session (times in Unix ms)
+#[derive(Debug, Default)]
+struct ActivityTracker {
+    last_input: i64,
+    last_output: i64,
+    busy_since: Option<i64>,
+    last_pattern_notice: Option<i64>,
+}
+
+struct NotifyConfig {
+    settings: PtyNotifySettings,
+    patterns: Vec<Regex>,
+}
+
+static CONFIG: LazyLock<RwLock<NotifyConfig>> = LazyLock::new(|| {
+    let settings = load_settings(&settings_path());
+    RwLock::new(NotifyConfig {
+        patterns: compile_patterns(&settings.patterns).unwrap_or_default(),
+        settings,
+    })
+});
+
+static TRACKERS: LazyLock<Mutex<HashMap<String, ActivityTracker>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Pattern notifications wait here until the checker thread sends them
+static PENDING: LazyLock<Mutex<Vec<PtyNotification>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn settings_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("pty_notify.json")
+}
+
+fn load_settings(path: &Path) -> PtyNotifySettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(path: &Path, settings: &PtyNotifySettings) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &content)
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|p| Regex::new(p).map_err(|e| format!("Invalid pattern '{}': {}", p, e)))
+        .collect()
+}
+
+impl ActivityTracker {
+    const fn on_input(&mut self, now: i64) {
+        self.last_input = now;
+    }
+
+    const fn on_output(&mut self, now: i64) {
+        let is_echo = now - self.last_input < ECHO_WINDOW_MS;
+        if self.busy_since.is_none() && !is_echo {
+            self.busy_since = Some(now);
+        }
+        self.last_output = now;
+    }
+
+    /// True once when a session that worked for a while has been quiet for `idle_ms`
+    const fn take_idle(&mut self, now: i64, idle_ms: i64) -> bool {
+        let Some(since) = self.busy_since else {
+            return false;
+        };
+        if now - self.last_output < idle_ms {
+            return false;
+        }
+        self.busy_since = None;
+        self.last_output - since >= MIN_BUSY_MS
+    }
+
+    fn take_pattern_slot(&mut self, now: i64) -> bool {
+        if self
+            .last_pattern_notice
+            .is_some_and(|last| now - last < PATTERN_COOLDOWN_MS)
+        {
+            return false;
+        }
+        self.last_pattern_notice = Some(now);
+        true
+    }
+}
+
+fn session_title(id: &str) -> String {
+    pty_manager::list_session_info()
+        .into_iter()
+        .find(|s| s.id == id)
+        .map_or_else(|| "Terminal".to_string(), |s| s.title)
+}
+
+fn send(app: &AppHandle, notification: &PtyNotification) {
+    notifications::notify(
+        &notification.title,
+        &notification.message,
+        NotificationKind::Terminal,
+    );
+    let _ = app.emit("pty-notification", notification.clone());
+}
+
+// ============================================================================
+// Hooks (called by pty_manager)
+// ============================================================================
+
+pub fn record_input(id: &str) {
+    if let Ok(mut trackers) = TRACKERS.lock() {
+        trackers
+            .entry(id.to_string())
+            .or_default()
+            .on_input(chrono::Utc::now().timestamp_millis());
+    }
+}
+
+pub fn record_output(id: &str, data: &[u8]) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let Ok(mut trackers) = TRACKERS.lock() else {
+        return;
+    };
+    let tracker = trackers.entry(id.to_string()).or_default();
+    tracker.on_output(now);
+
+    let Ok(config) = CONFIG.read() else {
+        return;
+    };
+    if !config.settings.enabled || config.patterns.is_empty() {
+        return;
+    }
+    let text = strip_ansi(&String::from_utf8_lossy(data));
+    let Some(matched) = config.patterns.iter().find_map(|re| re.find(&text)) else {
+        return;
+    };
+    if tracker.take_pattern_slot(now) {
+        let message = format!("Output matched: {}", matched.as_str().trim());
+        if let Ok(mut pending) = PENDING.lock() {
+            pending.push(PtyNotification {
+                id: id.to_string(),
+                kind: "pattern".to_string(),
+                title: String::new(),
+                message,
+            });
+        }
+    }
+}
+
+pub fn forget_session(id: &str) {
+    if let Ok(mut trackers) = TRACKERS.lock() {
+        trackers.remove(id);
+    }
+}
+
+/// Check busy sessions for idleness and deliver queued pattern notifications
+pub fn start_pty_notifier(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+
+        let (enabled, idle_ms) = match CONFIG.read() {
+            Ok(config) => (
+                config.settings.enabled,
+                i64::try_from(config.settings.idle_seconds.saturating_mul(1000))
+                    .unwrap_or(i64::MAX),
+            ),
+            Err(_) => continue,
+        };
+        let now = chrono::Utc::now().timestamp_millis();
+        let idle_ids: Vec<String> = TRACKERS
+            .lock()
+            .map(|mut trackers| {
+                trackers
+                    .iter_mut()
+                    .filter_map(|(id, tracker)| tracker.take_idle(now, idle_ms).then(|| id.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let pending: Vec<PtyNotification> = PENDING
+            .lock()
+            .map(|mut pending| pending.drain(..).collect())
+            .unwrap_or_default();
+        if !enabled {
+            continue;
+        }
+
+        for id in idle_ids {
+            let title = session_title(&id);
+            send(
+                &app,
+                &PtyNotification {
+                    message: format!("{} finished (idle for {}s)", title, idle_ms / 1000),
+                    id,
+                    kind: "idle".to_string(),
+                    title,
+                },
+            );
+        }
+        for mut notification in pending {
+            notification.title = session_title(&notification.id);
+            send(&app, &notification);
+        }
+    });
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn get_pty_notify_settings() -> AppResult<PtyNotifySettings> {
+    Ok(CONFIG
+        .read()
+        .map(|config| config.settings.clone())
+        .map_err(|_| "Notification settings lock poisoned".to_string())?)
+}
+
+#[tauri::command]
+pub fn set_pty_notify_settings(settings: PtyNotifySettings) -> AppResult<()> {
+    if settings.idle_seconds == 0 {
+        return Err(AppError::invalid_input("idle_seconds must be at least 1"));
+    }
+    let patterns = compile_patterns(&settings.patterns).map_err(AppError::invalid_input)?;
+    save_settings(&settings_path(), &settings)?;
+    let mut config = CONFIG
+        .write()
+        .map_err(|_| "Notification settings lock poisoned")?;
+    *config = NotifyConfig { settings, patterns };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busy_session_going_idle_notifies_once() {
+        let mut tracker = ActivityTracker::default();
+        tracker.on_output(10_000);
+        tracker.on_output(15_000);
+        assert!(!tracker.take_idle(20_000, 10_000));
+        assert!(tracker.take_idle(25_000, 10_000));
+        assert!(!tracker.take_idle(40_000, 10_000));
+    }
+
+    #[test]
+    fn test_echo_and_short_bursts_do_not_notify() {
+        let mut tracker = ActivityTracker::default();
+        // Typed characters echoed back
+        tracker.on_input(10_000);
+        tracker.on_output(10_100);
+        assert!(tracker.busy_since.is_none());
+
+        // A prompt redraw shorter than MIN_BUSY_MS
+        tracker.on_output(12_000);
+        tracker.on_output(13_000);
+        assert!(!tracker.take_idle(30_000, 10_000));
+        assert!(tracker.busy_since.is_none());
+    }
+
+    #[test]
+    fn test_pattern_cooldown() {
+        let mut tracker = ActivityTracker::default();
+        assert!(tracker.take_pattern_slot(1_000));
+        assert!(!tracker.take_pattern_slot(1_000 + PATTERN_COOLDOWN_MS - 1));
+        assert!(tracker.take_pattern_slot(1_000 + PATTERN_COOLDOWN_MS));
+    }
+
+    #[test]
+    fn test_settings_roundtrip_and_validation() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("lovcode").join("pty_notify.json");
+        assert_eq!(load_settings(&path), PtyNotifySettings::default());
+
+        let settings = PtyNotifySettings {
+            enabled: false,
+            idle_seconds: 30,
+            patterns: vec!["Tests? passed".to_string()],
+        };
+        save_settings(&path, &settings).expect("save settings");
+        assert_eq!(load_settings(&path), settings);
+
+        assert_eq!(
+            compile_patterns(&settings.patterns).expect("compile").len(),
+            1
+        );
+        assert!(compile_patterns(&["(unclosed".to_string()]).is_err());
+    }
+}
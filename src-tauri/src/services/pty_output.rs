@@ -0,0 +1,342 @@
+/**
+ * [INPUT]: 依赖 serde
+ * [OUTPUT]: 对外提供 ClaudeState, ClaudeStateDetector (从终端输出识别 Claude Code 状态), OscParser (OSC 7 工作目录 / OSC 0,2 标题), percent_decode
+ * [POS]: services/ 模块的 PTY 输出解析，被 pty_manager 的读取循环逐块调用
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use serde::Serialize;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What a Claude Code TUI running in a terminal is doing, as far as its output shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaudeState {
+    /// No Claude Code UI seen in the output
+    #[default]
+    None,
+    /// Thinking or running a tool ("esc to interrupt" spinner)
+    Working,
+    /// Asking to approve a tool call or edit
+    AwaitingPermission,
+    /// Idle at its prompt, waiting for the next message
+    WaitingForInput,
+}
+
+/// Output tail kept for matching; markers are short, redraws are not
+const TAIL_CHARS: usize = 4096;
+
+const WORKING_MARKERS: &[&str] = &["esc to interrupt"];
+const PERMISSION_MARKERS: &[&str] = &[
+    "Do you want to proceed?",
+    "Do you want to make this edit",
+    "Do you want to create",
+    "Do you want to allow",
+];
+const INPUT_MARKERS: &[&str] = &["? for shortcuts", "│ > "];
+
+/// Incremental detector fed with raw PTY output chunks
+#[derive(Debug, Default)]
+pub struct ClaudeStateDetector {
+    tail: String,
+    state: ClaudeState,
+}
+
+/// Cwd or title reported by the program through an OSC sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OscUpdate {
+    /// OSC 7 `file://host/path`
+    Cwd(String),
+    /// OSC 0 (icon name and title) or OSC 2 (title)
+    Title(String),
+}
+
+/// Longer OSC payloads (e.g. inline images via OSC 1337) are skipped unparsed
+const OSC_MAX_BYTES: usize = 4096;
+
+#[derive(Debug, Default)]
+enum OscScan {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    /// ESC seen inside an OSC; `ESC \` ends it
+    OscEscape,
+}
+
+/// Incremental OSC scanner; sequences may be split across output chunks
+#[derive(Debug, Default)]
+pub struct OscParser {
+    scan: OscScan,
+    payload: Vec<u8>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Drop ANSI escape sequences (CSI, OSC, two-byte ESC) and other control characters
+/// except newlines
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            // Cursor-positioned redraws lose their layout; keep words apart
+            '\r' => out.push(' '),
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Position just past the last occurrence of any marker
+fn last_marker(text: &str, markers: &[&str]) -> Option<usize> {
+    markers
+        .iter()
+        .filter_map(|m| text.rfind(m).map(|pos| pos + m.len()))
+        .max()
+}
+
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while let Some(&byte) = bytes.get(i) {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (byte, hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Parse an OSC payload (`Ps;Pt`) into an update we track
+fn parse_osc(payload: &[u8]) -> Option<OscUpdate> {
+    let payload = String::from_utf8_lossy(payload);
+    let (code, text) = payload.split_once(';')?;
+    match code {
+        "0" | "2" => Some(OscUpdate::Title(text.to_string())),
+        "7" => {
+            // file://hostname/path; the hostname may be empty
+            let path = text
+                .strip_prefix("file://")
+                .map_or(Some(text), |rest| rest.find('/').map(|i| &rest[i..]))?;
+            (!path.is_empty()).then(|| OscUpdate::Cwd(percent_decode(path)))
+        }
+        _ => None,
+    }
+}
+
+impl OscParser {
+    /// Feed an output chunk; returns the cwd/title updates completed in it, in order
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<OscUpdate> {
+        let mut updates = Vec::new();
+        for &byte in chunk {
+            self.scan = match (&self.scan, byte) {
+                (OscScan::Ground | OscScan::Escape, 0x1b) => OscScan::Escape,
+                // In an OSC, an ESC not followed by `\` aborts it; `ESC ]` starts a new one
+                (OscScan::Escape | OscScan::OscEscape, b']') => {
+                    self.payload.clear();
+                    OscScan::Osc
+                }
+                (OscScan::Ground | OscScan::Escape, _) => OscScan::Ground,
+                (OscScan::Osc, 0x07) | (OscScan::OscEscape, b'\\') => {
+                    if self.payload.len() <= OSC_MAX_BYTES {
+                        updates.extend(parse_osc(&self.payload));
+                    }
+                    self.payload.clear();
+                    OscScan::Ground
+                }
+                (OscScan::Osc, 0x1b) => OscScan::OscEscape,
+                (OscScan::OscEscape, _) => {
+                    self.payload.clear();
+                    OscScan::Ground
+                }
+                (OscScan::Osc, _) => {
+                    if self.payload.len() <= OSC_MAX_BYTES {
+                        self.payload.push(byte);
+                    }
+                    OscScan::Osc
+                }
+            };
+        }
+        updates
+    }
+}
+
+impl ClaudeStateDetector {
+    pub const fn state(&self) -> ClaudeState {
+        self.state
+    }
+
+    /// Feed an output chunk; returns the new state when it changed.
+    /// The marker printed last decides, so a prompt drawn after a spinner wins.
+    pub fn feed(&mut self, chunk: &[u8]) -> Option<ClaudeState> {
+        self.tail
+            .push_str(&strip_ansi(&String::from_utf8_lossy(chunk)));
+        let excess = self.tail.chars().count().saturating_sub(TAIL_CHARS);
+        if excess > 0 {
+            let cut = self
+                .tail
+                .char_indices()
+                .nth(excess)
+                .map_or(self.tail.len(), |(i, _)| i);
+            self.tail.drain(..cut);
+        }
+
+        let candidates = [
+            (
+                last_marker(&self.tail, WORKING_MARKERS),
+                ClaudeState::Working,
+            ),
+            (
+                last_marker(&self.tail, PERMISSION_MARKERS),
+                ClaudeState::AwaitingPermission,
+            ),
+            (
+                last_marker(&self.tail, INPUT_MARKERS),
+                ClaudeState::WaitingForInput,
+            ),
+        ];
+        let detected = candidates
+            .iter()
+            .filter_map(|(pos, state)| pos.map(|pos| (pos, *state)))
+            .max_by_key(|(pos, _)| *pos)
+            .map(|(_, state)| state)?;
+
+        if detected == self.state {
+            return None;
+        }
+        self.state = detected;
+        Some(detected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        let raw = "\x1b[1;32m✻ Thinking…\x1b[0m (3s · esc to interrupt)\x1b]0;claude\x07\r\n";
+        assert_eq!(strip_ansi(raw), "✻ Thinking… (3s · esc to interrupt) \n");
+        assert_eq!(strip_ansi("a\x1b]7;file:///tmp\x1b\\b"), "ab");
+    }
+
+    #[test]
+    fn test_detector_follows_latest_marker() {
+        let mut detector = ClaudeStateDetector::default();
+        assert_eq!(detector.feed(b"$ ls\r\nsrc\r\n"), None);
+        assert_eq!(detector.state(), ClaudeState::None);
+
+        assert_eq!(
+            detector.feed(b"\x1b[2K\xe2\x9c\xbb Reading... (esc to interrupt)"),
+            Some(ClaudeState::Working)
+        );
+        // Spinner redraws do not re-emit
+        assert_eq!(
+            detector.feed(b"\x1b[2K(4s \xc2\xb7 esc to interrupt)"),
+            None
+        );
+
+        assert_eq!(
+            detector.feed(b"Edit file src/main.rs\r\n Do you want to make this edit to main.rs?\r\n \xe2\x9d\xaf 1. Yes"),
+            Some(ClaudeState::AwaitingPermission)
+        );
+        assert_eq!(
+            detector.feed(b"\x1b[2K(1s \xc2\xb7 esc to interrupt)"),
+            Some(ClaudeState::Working)
+        );
+        assert_eq!(
+            detector.feed(b"\xe2\x94\x82 > \x1b[7m \x1b[0m\r\n  ? for shortcuts"),
+            Some(ClaudeState::WaitingForInput)
+        );
+    }
+
+    #[test]
+    fn test_osc_parser_tracks_cwd_and_title() {
+        let mut parser = OscParser::default();
+        assert_eq!(
+            parser.feed(b"\x1b]7;file://mac.local/Users/me/My%20Project\x07$ "),
+            vec![OscUpdate::Cwd("/Users/me/My Project".to_string())]
+        );
+        assert_eq!(
+            parser.feed(b"\x1b]0;vim main.rs\x07\x1b]2;zsh\x1b\\\x1b]1;icon\x07"),
+            vec![
+                OscUpdate::Title("vim main.rs".to_string()),
+                OscUpdate::Title("zsh".to_string())
+            ]
+        );
+        // Other escapes and text don't produce updates
+        assert!(parser.feed(b"\x1b[1;32mok\x1b[0m\r\n").is_empty());
+    }
+
+    #[test]
+    fn test_osc_parser_handles_split_and_oversized_sequences() {
+        let mut parser = OscParser::default();
+        assert!(parser.feed(b"out\x1b").is_empty());
+        assert!(parser.feed(b"]7;file:///tmp/pro").is_empty());
+        assert!(parser.feed(b"ject\x1b").is_empty());
+        assert_eq!(
+            parser.feed(b"\\"),
+            vec![OscUpdate::Cwd("/tmp/project".to_string())]
+        );
+
+        let mut huge = b"\x1b]2;".to_vec();
+        huge.extend(std::iter::repeat_n(b'x', OSC_MAX_BYTES * 2));
+        huge.push(0x07);
+        assert!(parser.feed(&huge).is_empty());
+        assert_eq!(
+            parser.feed(b"\x1b]2;short\x07"),
+            vec![OscUpdate::Title("short".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_detector_tail_is_bounded() {
+        let mut detector = ClaudeStateDetector::default();
+        detector.feed(b"esc to interrupt");
+        detector.feed("x".repeat(TAIL_CHARS * 2).as_bytes());
+        assert!(detector.tail.chars().count() <= TAIL_CHARS);
+        assert_eq!(detector.state(), ClaudeState::Working);
+    }
+}
@@ -0,0 +1,447 @@
+/**
+ * [INPUT]: 依赖 chrono (本地时间), services::task_queue (validate_task, queue_task, task_status), crate::security
+ * [OUTPUT]: 对外提供 start_scheduler 后台线程, schedule_task, list_scheduled_tasks, set_scheduled_task_enabled, delete_scheduled_task 命令与 ScheduledTask/ScheduledTaskSpec
+ * [POS]: services/ 的定时任务，按 cron 表达式 (本地时间) 把 claude -p 任务投入 task_queue，计划持久化到 task_schedules.json
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use crate::services::task_queue::{self, ClaudeTaskOptions, ClaudeTaskStatus};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// How often due schedules are checked
+const TICK_SECS: u64 = 30;
+/// A cron expression that matches nothing within this many years is rejected
+const MAX_SEARCH_YEARS: i32 = 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What a schedule queues each time it fires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTaskSpec {
+    pub project_path: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub options: ClaudeTaskOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub id: String,
+    pub name: String,
+    pub spec: ScheduledTaskSpec,
+    /// Five-field cron expression in local time, e.g. "0 2 * * *"
+    pub cron: String,
+    pub enabled: bool,
+    pub created_at: u64,
+    /// Next time it fires; None while disabled
+    pub next_run_at: Option<u64>,
+    pub last_run_at: Option<u64>,
+    /// Task queued by the last run
+    pub last_task_id: Option<String>,
+    /// Why the last run queued nothing (e.g. the previous run was still going)
+    pub last_error: Option<String>,
+}
+
+/// A parsed cron expression; each field is a bit set of allowed values
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and day-of-week were both restricted: either may match (as in crontab)
+    day_or_weekday: bool,
+}
+
+static SCHEDULES: LazyLock<Mutex<Vec<ScheduledTask>>> =
+    LazyLock::new(|| Mutex::new(load_store(&store_path())));
+
+// ============================================================================
+// Cron
+// ============================================================================
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Ok(n) = value.parse::<u32>() {
+        return Ok(n);
+    }
+    let lower = value.to_ascii_lowercase();
+    names
+        .iter()
+        .position(|name| *name == lower)
+        .and_then(|i| u32::try_from(i).ok())
+        .map(|i| i + min)
+        .ok_or_else(|| format!("Invalid value '{}'", value))
+}
+
+/// Parse one field ("*", "*/15", "1-5", "mon-fri", "0,30") into a bit set of allowed values
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step in '{}'", part))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, names)?, parse_value(b, min, names)?)
+        } else {
+            let start = parse_value(range, min, names)?;
+            // "5/10" means every 10 starting at 5
+            (start, if step > 1 { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("'{}' is out of range ({}-{})", part, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_cron(expr: &str) -> Result<CronExpr, String> {
+    let expanded = match expr.trim() {
+        "@hourly" => "0 * * * *",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@weekly" => "0 0 * * 0",
+        "@monthly" => "0 0 1 * *",
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        other => other,
+    };
+    let fields: Vec<&str> = expanded.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields.as_slice() else {
+        return Err(format!(
+            "Expected 5 fields (minute hour day month weekday), got {}",
+            fields.len()
+        ));
+    };
+    let field = |value: &str, label: &str, min, max, names| {
+        parse_field(value, min, max, names).map_err(|e| format!("Invalid {}: {}", label, e))
+    };
+    let mut weekdays = field(weekday, "weekday", 0, 7, &WEEKDAY_NAMES)?;
+    // Both 0 and 7 mean Sunday
+    if weekdays & (1 << 7) != 0 {
+        weekdays = (weekdays & !(1 << 7)) | 1;
+    }
+    Ok(CronExpr {
+        minutes: field(minute, "minute", 0, 59, &[])?,
+        hours: field(hour, "hour", 0, 23, &[])?,
+        days: field(day, "day", 1, 31, &[])?,
+        months: field(month, "month", 1, 12, &MONTH_NAMES)?,
+        weekdays,
+        day_or_weekday: *day != "*" && *weekday != "*",
+    })
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+impl CronExpr {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.day_or_weekday {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after.year() + MAX_SEARCH_YEARS;
+        while t.year() <= limit {
+            if !has(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !has(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    /// Next run in local time as unix seconds; times skipped by a DST change are passed over
+    fn next_run(&self, after: DateTime<Local>) -> Option<u64> {
+        let mut naive = after.naive_local();
+        loop {
+            naive = self.next_after(naive)?;
+            if let Some(local) = Local.from_local_datetime(&naive).earliest() {
+                return u64::try_from(local.timestamp()).ok();
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn store_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("task_schedules.json")
+}
+
+fn load_store(path: &Path) -> Vec<ScheduledTask> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(path: &Path, schedules: &[ScheduledTask]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let json = serde_json::to_string_pretty(schedules).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &json)
+}
+
+fn now_secs() -> u64 {
+    u64::try_from(Local::now().timestamp()).unwrap_or(0)
+}
+
+fn next_run_from_now(cron: &str) -> Option<u64> {
+    parse_cron(cron).ok()?.next_run(Local::now())
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+/// Queue one due schedule and move it to its next time
+fn fire(schedule: &mut ScheduledTask) {
+    let now = now_secs();
+    schedule.last_run_at = Some(now);
+    schedule.next_run_at = next_run_from_now(&schedule.cron);
+
+    // Don't pile up runs of a task that takes longer than its interval
+    let previous = schedule
+        .last_task_id
+        .as_deref()
+        .and_then(task_queue::task_status);
+    if matches!(
+        previous,
+        Some(ClaudeTaskStatus::Queued | ClaudeTaskStatus::Running)
+    ) {
+        schedule.last_error = Some("Skipped: the previous run hasn't finished".to_string());
+        return;
+    }
+
+    let mut options = schedule.spec.options.clone();
+    if options.label.is_none() {
+        options.label = Some(schedule.name.clone());
+    }
+    match task_queue::queue_task(&schedule.spec.project_path, &schedule.spec.prompt, options) {
+        Ok(task) => {
+            tracing::info!(schedule = %schedule.name, task_id = %task.id, "Scheduled task queued");
+            schedule.last_task_id = Some(task.id);
+            schedule.last_error = None;
+        }
+        Err(e) => {
+            tracing::warn!(schedule = %schedule.name, error = %e, "Scheduled task failed to queue");
+            schedule.last_error = Some(e.to_string());
+        }
+    }
+}
+
+/// Fire every enabled schedule that is due; returns whether anything changed
+fn run_due(schedules: &mut [ScheduledTask], now: u64) -> bool {
+    let mut changed = false;
+    for schedule in schedules
+        .iter_mut()
+        .filter(|s| s.enabled && s.next_run_at.is_some_and(|next| next <= now))
+    {
+        fire(schedule);
+        changed = true;
+    }
+    changed
+}
+
+/// Check for due schedules every 30 seconds. A run missed while lovcode was closed
+/// fires once at startup.
+pub fn start_scheduler() {
+    std::thread::spawn(|| loop {
+        if let Ok(mut schedules) = SCHEDULES.lock() {
+            if run_due(&mut schedules, now_secs()) {
+                if let Err(e) = save_store(&store_path(), &schedules) {
+                    tracing::warn!(error = %e, "Failed to save task schedules");
+                }
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_secs(TICK_SECS));
+    });
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Queue `spec` as a `claude -p` task whenever `cron_expr` matches (local time).
+/// Accepts five-field crontab syntax and @hourly/@daily/@weekly/@monthly/@yearly.
+#[tauri::command]
+pub fn schedule_task(
+    spec: ScheduledTaskSpec,
+    cron_expr: String,
+    name: Option<String>,
+) -> AppResult<ScheduledTask> {
+    let cron = parse_cron(&cron_expr)
+        .map_err(|e| AppError::invalid_input(e).with_context(cron_expr.clone()))?;
+    let next_run_at = cron
+        .next_run(Local::now())
+        .ok_or_else(|| AppError::invalid_input("Cron expression never matches"))?;
+    let project_path = task_queue::validate_task(&spec.project_path, &spec.prompt, &spec.options)?;
+
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .or_else(|| spec.options.label.clone())
+        .unwrap_or_else(|| spec.prompt.lines().next().unwrap_or_default().to_string());
+    let schedule = ScheduledTask {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        spec: ScheduledTaskSpec {
+            project_path,
+            ..spec
+        },
+        cron: cron_expr.trim().to_string(),
+        enabled: true,
+        created_at: now_secs(),
+        next_run_at: Some(next_run_at),
+        last_run_at: None,
+        last_task_id: None,
+        last_error: None,
+    };
+
+    let mut schedules = SCHEDULES.lock().map_err(|_| "Schedule lock poisoned")?;
+    schedules.push(schedule.clone());
+    save_store(&store_path(), &schedules)?;
+    Ok(schedule)
+}
+
+#[tauri::command]
+pub fn list_scheduled_tasks() -> AppResult<Vec<ScheduledTask>> {
+    let schedules = SCHEDULES.lock().map_err(|_| "Schedule lock poisoned")?;
+    Ok(schedules.clone())
+}
+
+/// Pause or resume a schedule; resuming doesn't catch up on missed runs
+#[tauri::command]
+pub fn set_scheduled_task_enabled(id: String, enabled: bool) -> AppResult<ScheduledTask> {
+    let mut schedules = SCHEDULES.lock().map_err(|_| "Schedule lock poisoned")?;
+    let schedule = schedules
+        .iter_mut()
+        .find(|s| s.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Schedule '{}' not found", id)))?;
+    schedule.enabled = enabled;
+    schedule.next_run_at = if enabled {
+        next_run_from_now(&schedule.cron)
+    } else {
+        None
+    };
+    let schedule = schedule.clone();
+    save_store(&store_path(), &schedules)?;
+    Ok(schedule)
+}
+
+/// Delete a schedule; tasks it already queued are left alone
+#[tauri::command]
+pub fn delete_scheduled_task(id: String) -> AppResult<()> {
+    let mut schedules = SCHEDULES.lock().map_err(|_| "Schedule lock poisoned")?;
+    let before = schedules.len();
+    schedules.retain(|s| s.id != id);
+    if schedules.len() == before {
+        return Err(AppError::not_found(format!("Schedule '{}' not found", id)));
+    }
+    save_store(&store_path(), &schedules)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").expect("valid datetime")
+    }
+
+    fn next(expr: &str, after: &str) -> Option<String> {
+        parse_cron(expr)
+            .expect("valid cron")
+            .next_after(at(after))
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2026-10-16 is a Friday
+        assert_eq!(
+            next("0 2 * * *", "2026-10-16 02:00").as_deref(),
+            Some("2026-10-17 02:00")
+        );
+        assert_eq!(
+            next("*/15 9-17 * * mon-fri", "2026-10-16 17:50").as_deref(),
+            Some("2026-10-19 09:00")
+        );
+        assert_eq!(
+            next("@weekly", "2026-10-16 12:00").as_deref(),
+            Some("2026-10-18 00:00")
+        );
+        // Day-of-month or Sunday (7), as crontab does
+        assert_eq!(
+            next("30 8 1 * 7", "2026-10-16 12:00").as_deref(),
+            Some("2026-10-18 08:30")
+        );
+        assert_eq!(
+            next("0 0 29 feb *", "2026-03-01 00:00").as_deref(),
+            Some("2028-02-29 00:00")
+        );
+        assert_eq!(next("0 0 31 feb *", "2026-01-01 00:00"), None);
+    }
+
+    #[test]
+    fn test_parse_cron_errors() {
+        assert!(parse_cron("0 2 * *").is_err());
+        assert!(parse_cron("60 * * * *").is_err());
+        assert!(parse_cron("0 5-1 * * *").is_err());
+        assert!(parse_cron("*/0 * * * *").is_err());
+        assert!(parse_cron("0 0 * * funday").is_err());
+        assert_eq!(
+            parse_cron("0 12 * jan,jul sun").map(|c| (c.months, c.weekdays)),
+            Ok(((1 << 1) | (1 << 7), 1))
+        );
+    }
+}
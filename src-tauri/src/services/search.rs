@@ -1,23 +1,33 @@
 /**
- * [INPUT]: 依赖 jieba-rs, tantivy, types (RawLine, SearchResult), security (get_claude_dir)
- * [OUTPUT]: 对外提供 build_search_index, search_chats 命令 + 搜索基础设施
+ * [INPUT]: 依赖 jieba-rs, tantivy, rayon + scan (并行解析会话), jsonl (流式读取), types (RawLine, SearchResult), security (get_claude_dir), services::notifications (索引完成通知), commands::importers (导入的外部会话), commands::report (写入 command-stats 周索引), commands::saved_searches (记录搜索历史)
+ * [OUTPUT]: 对外提供 build_search_index, search_chats, remove_project_from_index, get_search_index_stats, optimize_search_index 命令与 SearchIndexStats 类型 + reindex_session 单会话增量更新 + remove_project_documents 按项目删除 + extract_content_with_meta/extract_thinking 消息内容提取 + 搜索基础设施
  * [POS]: services/ 模块的全文搜索服务中心
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
 use crate::commands::decode_project_path;
+use crate::commands::importers;
+use crate::commands::report;
+use crate::commands::saved_searches::{self, SearchFilters};
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::jsonl;
+use crate::scan;
 use crate::security::get_claude_dir;
+use crate::services::notifications::{self, NotificationKind};
 use crate::types::{RawLine, SearchResult};
 
 use jieba_rs::Jieba;
+use rayon::prelude::*;
 use regex::Regex;
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, Mutex};
-use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tantivy::collector::{Count, TopDocs};
+use tantivy::query::{BooleanQuery, Occur, Query, QueryParser, TermQuery};
 use tantivy::schema::{self, Schema, Value as TantivyValue, *};
 use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, Tokenizer};
 use tantivy::{doc, Index, IndexWriter, ReloadPolicy};
@@ -34,6 +44,9 @@ static COMMAND_NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"<command-name>(/[^<]+)</command-name>").expect("COMMAND_NAME_RE should compile")
 });
 
+/// Session files parsed in parallel per batch while building the index
+const INDEX_CHUNK_SESSIONS: usize = 64;
+
 /// Global search index state
 static SEARCH_INDEX: Mutex<Option<SearchIndex>> = Mutex::new(None);
 
@@ -42,6 +55,31 @@ struct SearchIndex {
     schema: Schema,
 }
 
+/// Unix seconds of the last full build, written next to the index files
+const BUILD_STAMP_FILE: &str = "lovcode-built-at";
+
+#[derive(Debug, Default, Serialize)]
+pub struct SearchIndexStats {
+    /// False until build_search_index has run; everything else is then empty
+    pub built: bool,
+    /// Messages that can be found
+    pub doc_count: u64,
+    /// Removed messages still taking disk space until optimize_search_index
+    pub deleted_doc_count: u64,
+    pub segment_count: usize,
+    pub size_bytes: u64,
+    /// Unix seconds
+    pub last_built_at: Option<u64>,
+    /// Most messages first
+    pub projects: Vec<ProjectDocCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectDocCount {
+    pub project_id: String,
+    pub doc_count: u64,
+}
+
 // ============================================================================
 // Jieba Tokenizer (中英文混合分词)
 // ============================================================================
@@ -117,13 +155,6 @@ fn get_index_dir() -> PathBuf {
         .join("search-index")
 }
 
-fn get_command_stats_path() -> PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("lovcode")
-        .join("command-stats.json")
-}
-
 fn create_schema() -> Schema {
     let mut schema_builder = Schema::builder();
 
@@ -183,20 +214,363 @@ pub fn extract_content_with_meta(value: &Option<Value>) -> (String, bool) {
     }
 }
 
+/// Extended-thinking text of a message, None when it has no (readable) thinking blocks.
+/// `redacted_thinking` blocks are encrypted and skipped.
+pub fn extract_thinking(value: Option<&Value>) -> Option<String> {
+    let Some(Value::Array(arr)) = value else {
+        return None;
+    };
+    let thinking = arr
+        .iter()
+        .filter(|item| item.get("type").and_then(Value::as_str) == Some("thinking"))
+        .filter_map(|item| item.get("thinking").and_then(Value::as_str))
+        .filter(|t| !t.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!thinking.is_empty()).then_some(thinking)
+}
+
+/// Messages and command uses of one session file, parsed off the indexing thread
+struct ParsedSession {
+    summary: Option<String>,
+    messages: Vec<ParsedMessage>,
+    /// (canonical command name, ISO week key, unix seconds)
+    commands: Vec<(String, String, i64)>,
+}
+
+struct ParsedMessage {
+    uuid: String,
+    role: String,
+    content: String,
+    timestamp: String,
+}
+
+/// Indexable messages, the first summary line and slash-command uses of a session file
+fn parse_session_for_index(path: &Path, alias_map: &HashMap<String, String>) -> ParsedSession {
+    let mut session = ParsedSession {
+        summary: None,
+        messages: Vec::new(),
+        commands: Vec::new(),
+    };
+    let Ok(lines) = jsonl::open(path) else {
+        return session;
+    };
+
+    for (_, line) in lines {
+        let Ok(parsed) = serde_json::from_str::<RawLine>(&line) else {
+            continue;
+        };
+        let line_type = parsed.line_type.as_deref();
+
+        if line_type == Some("summary") && session.summary.is_none() {
+            session.summary.clone_from(&parsed.summary);
+        }
+
+        if line_type == Some("user") || line_type == Some("assistant") {
+            if let Some(msg) = &parsed.message {
+                let (text_content, _) = extract_content_with_meta(&msg.content);
+                let is_meta = parsed.is_meta.unwrap_or(false);
+
+                if !is_meta && !text_content.is_empty() {
+                    session.messages.push(ParsedMessage {
+                        uuid: parsed.uuid.clone().unwrap_or_default(),
+                        role: msg.role.clone().unwrap_or_default(),
+                        content: text_content,
+                        timestamp: parsed.timestamp.clone().unwrap_or_default(),
+                    });
+                }
+            }
+        }
+
+        // Collect command stats
+        if line.contains("<command-name>") && !line.contains("\"type\":\"queue-operation\"") {
+            let Some(ts) = parsed
+                .timestamp
+                .as_deref()
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            else {
+                continue;
+            };
+            let week_key = ts.format("%Y-W%V").to_string();
+            for cap in COMMAND_NAME_RE.captures_iter(&line) {
+                if let Some(cmd_match) = cap.get(1) {
+                    let raw_name = cmd_match.as_str().trim_start_matches('/').to_string();
+                    let name = alias_map.get(&raw_name).cloned().unwrap_or(raw_name);
+                    session
+                        .commands
+                        .push((name, week_key.clone(), ts.timestamp()));
+                }
+            }
+        }
+    }
+    session
+}
+
+/// Open the on-disk index into `slot` unless it is already loaded; false if never built
+fn load_index(slot: &mut Option<SearchIndex>) -> Result<bool, String> {
+    if slot.is_some() {
+        return Ok(true);
+    }
+    let index_dir = get_index_dir();
+    if !index_dir.exists() {
+        return Ok(false);
+    }
+    let index = Index::open_in_dir(&index_dir).map_err(|e| e.to_string())?;
+    register_jieba_tokenizer(&index);
+    *slot = Some(SearchIndex {
+        index,
+        schema: create_schema(),
+    });
+    Ok(true)
+}
+
+/// Replace one session's messages in the built index
+///
+/// Existing documents are dropped and re-read from `path` when given, so a trashed or
+/// restored session shows up in search without a full rebuild. Does nothing before the
+/// index is first built.
+pub fn reindex_session(
+    project_id: &str,
+    session_id: &str,
+    path: Option<&Path>,
+) -> Result<(), String> {
+    let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
+    if !load_index(&mut guard)? {
+        return Ok(());
+    }
+    let search_index = guard.as_ref().expect("search index should be initialized");
+    let field = |name: &str| search_index.schema.get_field(name).expect("search index field");
+
+    let mut index_writer: IndexWriter = search_index
+        .index
+        .writer_with_num_threads(1, 15_000_000)
+        .map_err(|e| e.to_string())?;
+    index_writer.delete_term(Term::from_field_text(field("session_id"), session_id));
+
+    if let Some(path) = path {
+        let project_path = decode_project_path(project_id);
+        let session = parse_session_for_index(path, &HashMap::new());
+        let session_summary = session.summary.unwrap_or_default();
+        for msg in session.messages {
+            index_writer
+                .add_document(doc!(
+                    field("uuid") => msg.uuid,
+                    field("content") => msg.content,
+                    field("role") => msg.role,
+                    field("project_id") => project_id,
+                    field("project_path") => project_path.clone(),
+                    field("session_id") => session_id,
+                    field("session_summary") => session_summary.clone(),
+                    field("timestamp") => msg.timestamp,
+                ))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    index_writer.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Matches every document of a project: project_id is indexed as a single raw term
+fn project_term(schema: &Schema, project_id: &str) -> Term {
+    Term::from_field_text(
+        schema.get_field("project_id").expect("project_id field"),
+        project_id,
+    )
+}
+
+/// `query` over message content and summaries, limited to one project when given, so a
+/// scoped search fills its limit from that project alone
+fn build_query(
+    search_index: &SearchIndex,
+    query: &str,
+    project_id: Option<&str>,
+) -> Result<Box<dyn Query>, String> {
+    let content_field = search_index
+        .schema
+        .get_field("content")
+        .expect("content field");
+    let session_summary_field = search_index
+        .schema
+        .get_field("session_summary")
+        .expect("session_summary field");
+
+    let query_parser =
+        QueryParser::for_index(&search_index.index, vec![content_field, session_summary_field]);
+    let parsed_query = query_parser.parse_query(query).map_err(|e| e.to_string())?;
+    Ok(match project_id {
+        Some(id) => Box::new(BooleanQuery::new(vec![
+            (Occur::Must, parsed_query),
+            (
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    project_term(&search_index.schema, id),
+                    IndexRecordOption::Basic,
+                )),
+            ),
+        ])),
+        None => parsed_query,
+    })
+}
+
+fn remove_project_in(search_index: &SearchIndex, project_id: &str) -> Result<usize, String> {
+    let term = project_term(&search_index.schema, project_id);
+    let searcher = search_index
+        .index
+        .reader()
+        .map_err(|e| e.to_string())?
+        .searcher();
+    let count = searcher
+        .search(
+            &TermQuery::new(term.clone(), IndexRecordOption::Basic),
+            &Count,
+        )
+        .map_err(|e| e.to_string())?;
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let mut index_writer: IndexWriter = search_index
+        .index
+        .writer_with_num_threads(1, 15_000_000)
+        .map_err(|e| e.to_string())?;
+    index_writer.delete_term(term);
+    index_writer.commit().map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// Drop every message of a project from the built index; returns how many were removed.
+/// Does nothing before the index is first built.
+pub fn remove_project_documents(project_id: &str) -> Result<usize, String> {
+    let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
+    if !load_index(&mut guard)? {
+        return Ok(0);
+    }
+    remove_project_in(
+        guard.as_ref().expect("search index should be initialized"),
+        project_id,
+    )
+}
+
+fn index_stats_in(search_index: &SearchIndex) -> Result<SearchIndexStats, String> {
+    let segments = search_index
+        .index
+        .searchable_segment_metas()
+        .map_err(|e| e.to_string())?;
+    let searcher = search_index
+        .index
+        .reader()
+        .map_err(|e| e.to_string())?
+        .searcher();
+
+    // Project ids are the terms of the raw project_id field
+    let project_field = search_index
+        .schema
+        .get_field("project_id")
+        .expect("project_id field");
+    let mut project_ids = BTreeSet::new();
+    for segment in searcher.segment_readers() {
+        let inverted_index = segment
+            .inverted_index(project_field)
+            .map_err(|e| e.to_string())?;
+        let mut terms = inverted_index.terms().stream().map_err(|e| e.to_string())?;
+        while terms.advance() {
+            project_ids.insert(String::from_utf8_lossy(terms.key()).to_string());
+        }
+    }
+    // Term frequencies include deleted documents; a query doesn't
+    let mut projects = Vec::new();
+    for project_id in project_ids {
+        let query = TermQuery::new(
+            project_term(&search_index.schema, &project_id),
+            IndexRecordOption::Basic,
+        );
+        let count = searcher.search(&query, &Count).map_err(|e| e.to_string())?;
+        if count > 0 {
+            projects.push(ProjectDocCount {
+                project_id,
+                doc_count: u64::try_from(count).unwrap_or(u64::MAX),
+            });
+        }
+    }
+    projects.sort_by_key(|p| std::cmp::Reverse(p.doc_count));
+
+    Ok(SearchIndexStats {
+        built: true,
+        doc_count: segments.iter().map(|s| u64::from(s.num_docs())).sum(),
+        deleted_doc_count: segments
+            .iter()
+            .map(|s| u64::from(s.num_deleted_docs()))
+            .sum(),
+        segment_count: segments.len(),
+        size_bytes: 0,
+        last_built_at: None,
+        projects,
+    })
+}
+
+/// Merge all segments into one, dropping deleted documents, and remove unused files
+fn optimize_in(search_index: &SearchIndex) -> Result<(), String> {
+    let segments = search_index
+        .index
+        .searchable_segment_metas()
+        .map_err(|e| e.to_string())?;
+    let mut index_writer: IndexWriter = search_index
+        .index
+        .writer_with_num_threads(1, 15_000_000)
+        .map_err(|e| e.to_string())?;
+    if segments.len() > 1 || segments.iter().any(|s| s.num_deleted_docs() > 0) {
+        let ids: Vec<_> = segments.iter().map(tantivy::SegmentMeta::id).collect();
+        index_writer.merge(&ids).wait().map_err(|e| e.to_string())?;
+    }
+    index_writer
+        .garbage_collect_files()
+        .wait()
+        .map_err(|e| e.to_string())?;
+    index_writer
+        .wait_merging_threads()
+        .map_err(|e| e.to_string())
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(fs::Metadata::is_file)
+        .map(|meta| meta.len())
+        .sum()
+}
+
+/// Stats of the on-disk index, loading it if needed
+fn current_stats(slot: &mut Option<SearchIndex>) -> Result<SearchIndexStats, String> {
+    if !load_index(slot)? {
+        return Ok(SearchIndexStats::default());
+    }
+    let mut stats = index_stats_in(slot.as_ref().expect("search index should be initialized"))?;
+    let index_dir = get_index_dir();
+    stats.size_bytes = dir_size(&index_dir);
+    stats.last_built_at = fs::read_to_string(index_dir.join(BUILD_STAMP_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse().ok());
+    Ok(stats)
+}
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
 
 #[tauri::command]
-pub async fn build_search_index() -> Result<usize, String> {
+pub async fn build_search_index() -> AppResult<usize> {
     tauri::async_runtime::spawn_blocking(|| {
         let index_dir = get_index_dir();
 
         // Remove old index if exists
         if index_dir.exists() {
-            fs::remove_dir_all(&index_dir).map_err(|e| e.to_string())?;
+            fs::remove_dir_all(&index_dir)
+                .map_err(|e| AppError::io("Failed to delete directory", &e))?;
         }
-        fs::create_dir_all(&index_dir).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&index_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
 
         let schema = create_schema();
         let index = Index::create_in_dir(&index_dir, schema.clone()).map_err(|e| e.to_string())?;
@@ -216,18 +590,17 @@ pub async fn build_search_index() -> Result<usize, String> {
             .expect("session_summary field");
         let timestamp_field = schema.get_field("timestamp").expect("timestamp field");
 
-        let projects_dir = get_claude_dir()
-            .map(|d| d.join("projects"))
-            .map_err(|e| e.to_string())?;
+        let projects_dir = get_claude_dir()?.join("projects");
 
         let mut indexed_count = 0;
 
         // === Command stats collection ===
         let mut command_stats: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        let mut command_last_used: HashMap<String, i64> = HashMap::new();
 
         // Build alias -> canonical name mapping
         let mut alias_map: HashMap<String, String> = HashMap::new();
-        let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+        let claude_dir = get_claude_dir()?;
         let commands_dir = claude_dir.join("commands");
 
         fn scan_commands_for_aliases(
@@ -285,151 +658,134 @@ pub async fn build_search_index() -> Result<usize, String> {
             return Ok(0);
         }
 
-        for project_entry in fs::read_dir(&projects_dir).map_err(|e| e.to_string())? {
-            let project_entry = project_entry.map_err(|e| e.to_string())?;
-            let project_path_buf = project_entry.path();
-
-            if !project_path_buf.is_dir() {
-                continue;
-            }
+        // Parse sessions on the scan pool a chunk at a time, bounding the parsed
+        // messages held in memory; documents are added from this thread
+        let session_files: Vec<(String, String, PathBuf)> =
+            scan::project_dirs(&projects_dir, false)
+                .into_iter()
+                .flat_map(|project| {
+                    let display_path = decode_project_path(&project.id);
+                    project
+                        .sessions
+                        .into_iter()
+                        .map(move |path| (project.id.clone(), display_path.clone(), path))
+                })
+                .collect();
+
+        for chunk in session_files.chunks(INDEX_CHUNK_SESSIONS) {
+            let parsed: Vec<ParsedSession> = scan::install(|| {
+                chunk
+                    .par_iter()
+                    .map(|(_, _, path)| parse_session_for_index(path, &alias_map))
+                    .collect()
+            });
 
-            let project_id = project_path_buf
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-            let display_path = decode_project_path(&project_id);
-
-            for entry in fs::read_dir(&project_path_buf).map_err(|e| e.to_string())? {
-                let entry = entry.map_err(|e| e.to_string())?;
-                let path = entry.path();
-                let name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
+            for ((project_id, display_path, path), session) in chunk.iter().zip(parsed) {
+                let session_id = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
                     .unwrap_or_default();
+                let session_summary = session.summary.unwrap_or_default();
+
+                for msg in session.messages {
+                    index_writer
+                        .add_document(doc!(
+                            uuid_field => msg.uuid,
+                            content_field => msg.content,
+                            role_field => msg.role,
+                            project_id_field => project_id.clone(),
+                            project_path_field => display_path.clone(),
+                            session_id_field => session_id.clone(),
+                            session_summary_field => session_summary.clone(),
+                            timestamp_field => msg.timestamp,
+                        ))
+                        .map_err(|e| e.to_string())?;
+                    indexed_count += 1;
+                }
 
-                if name.ends_with(".jsonl") && !name.starts_with("agent-") {
-                    let session_id = name.trim_end_matches(".jsonl").to_string();
-                    let file_content = fs::read_to_string(&path).unwrap_or_default();
-
-                    let mut session_summary: Option<String> = None;
-
-                    // First pass: get summary
-                    for line in file_content.lines() {
-                        if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                            if parsed.line_type.as_deref() == Some("summary") {
-                                session_summary = parsed.summary;
-                                break;
-                            }
-                        }
-                    }
-
-                    // Second pass: index messages + collect command stats
-                    for line in file_content.lines() {
-                        if let Ok(parsed) = serde_json::from_str::<RawLine>(line) {
-                            let line_type = parsed.line_type.as_deref();
-
-                            if line_type == Some("user") || line_type == Some("assistant") {
-                                if let Some(msg) = &parsed.message {
-                                    let role = msg.role.clone().unwrap_or_default();
-                                    let (text_content, _) = extract_content_with_meta(&msg.content);
-                                    let is_meta = parsed.is_meta.unwrap_or(false);
-
-                                    if !is_meta && !text_content.is_empty() {
-                                        index_writer
-                                            .add_document(doc!(
-                                                uuid_field => parsed.uuid.clone().unwrap_or_default(),
-                                                content_field => text_content,
-                                                role_field => role,
-                                                project_id_field => project_id.clone(),
-                                                project_path_field => display_path.clone(),
-                                                session_id_field => session_id.clone(),
-                                                session_summary_field => session_summary.clone().unwrap_or_default(),
-                                                timestamp_field => parsed.timestamp.clone().unwrap_or_default(),
-                                            ))
-                                            .map_err(|e| e.to_string())?;
-
-                                        indexed_count += 1;
-                                    }
-                                }
-                            }
+                for (name, week_key, ts) in session.commands {
+                    let last_used = command_last_used.entry(name.clone()).or_insert(0);
+                    *last_used = (*last_used).max(ts);
+                    *command_stats
+                        .entry(name)
+                        .or_default()
+                        .entry(week_key)
+                        .or_insert(0) += 1;
+                }
+            }
+        }
 
-                            // Collect command stats
-                            if line.contains("<command-name>")
-                                && !line.contains("\"type\":\"queue-operation\"")
-                            {
-                                if let Some(ts_str) = &parsed.timestamp {
-                                    if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(ts_str) {
-                                        let week_key = ts.format("%Y-W%V").to_string();
-                                        for cap in COMMAND_NAME_RE.captures_iter(line) {
-                                            if let Some(cmd_match) = cap.get(1) {
-                                                let raw_name =
-                                                    cmd_match.as_str().trim_start_matches('/').to_string();
-                                                let name =
-                                                    alias_map.get(&raw_name).cloned().unwrap_or(raw_name);
-                                                command_stats
-                                                    .entry(name)
-                                                    .or_default()
-                                                    .entry(week_key.clone())
-                                                    .and_modify(|c| *c += 1)
-                                                    .or_insert(1);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+        // Sessions imported from other tools
+        for session in importers::load_imported_sessions() {
+            let project_id = session.project_id();
+            let project_path = session.project_path.clone().unwrap_or_default();
+            let summary = session.summary.clone().unwrap_or_default();
+            for msg in session.messages {
+                if msg.is_meta || msg.content.is_empty() {
+                    continue;
                 }
+                index_writer
+                    .add_document(doc!(
+                        uuid_field => msg.uuid,
+                        content_field => msg.content,
+                        role_field => msg.role,
+                        project_id_field => project_id.clone(),
+                        project_path_field => project_path.clone(),
+                        session_id_field => session.id.clone(),
+                        session_summary_field => summary.clone(),
+                        timestamp_field => msg.timestamp,
+                    ))
+                    .map_err(|e| e.to_string())?;
+                indexed_count += 1;
             }
         }
 
         index_writer.commit().map_err(|e| e.to_string())?;
+        let built_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if let Err(e) = fs::write(index_dir.join(BUILD_STAMP_FILE), built_at.to_string()) {
+            tracing::warn!("Failed to record search index build time: {}", e);
+        }
 
         // Store search index in global state
         let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
         *guard = Some(SearchIndex { index, schema });
 
         // Write command stats to file
-        let stats_path = get_command_stats_path();
-        if let Some(parent) = stats_path.parent() {
-            fs::create_dir_all(parent).ok();
+        if let Err(e) = report::save_command_stats(&command_stats, &command_last_used) {
+            tracing::warn!("Failed to write command stats: {}", e);
         }
-        let stats_json = serde_json::json!({
-            "updated_at": chrono::Utc::now().timestamp(),
-            "commands": command_stats,
-        });
-        fs::write(
-            &stats_path,
-            serde_json::to_string_pretty(&stats_json).unwrap_or_default(),
-        )
-        .ok();
 
+        notifications::notify(
+            "Search index ready",
+            &format!("Indexed {} messages", indexed_count),
+            NotificationKind::IndexBuilt,
+        );
         Ok(indexed_count)
     })
     .await
     .map_err(|e| e.to_string())?
 }
 
+/// `record` adds the query to the search history; callers pass it for a submitted
+/// search, not for search-as-you-type
 #[tauri::command]
 pub fn search_chats(
     query: String,
     limit: Option<usize>,
     project_id: Option<String>,
-) -> Result<Vec<SearchResult>, String> {
+    record: Option<bool>,
+) -> AppResult<Vec<SearchResult>> {
     let max_results = limit.unwrap_or(50);
 
     let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
 
-    if guard.is_none() {
-        let index_dir = get_index_dir();
-        if !index_dir.exists() {
-            return Err("Search index not built. Please build index first.".to_string());
-        }
-
-        let schema = create_schema();
-        let index = Index::open_in_dir(&index_dir).map_err(|e| e.to_string())?;
-        register_jieba_tokenizer(&index);
-        *guard = Some(SearchIndex { index, schema });
+    if !load_index(&mut guard)? {
+        return Err(AppError::new(
+            ErrorCode::Unavailable,
+            "Search index not built. Please build index first.",
+        ));
     }
 
     let search_index = guard.as_ref().expect("search index should be initialized");
@@ -442,18 +798,9 @@ pub fn search_chats(
 
     let searcher = reader.searcher();
 
-    let content_field = search_index
-        .schema
-        .get_field("content")
-        .expect("content field");
-    let session_summary_field = search_index
-        .schema
-        .get_field("session_summary")
-        .expect("session_summary field");
-
-    let query_parser =
-        QueryParser::for_index(&search_index.index, vec![content_field, session_summary_field]);
-    let parsed_query = query_parser.parse_query(&query).map_err(|e| e.to_string())?;
+    // Only an unparsable query makes build_query fail
+    let parsed_query = build_query(search_index, &query, project_id.as_deref())
+        .map_err(AppError::invalid_input)?;
 
     let top_docs = searcher
         .search(&parsed_query, &TopDocs::with_limit(max_results))
@@ -477,22 +824,13 @@ pub fn search_chats(
                 .to_string()
         };
 
-        let doc_project_id = get_text("project_id");
-
-        // Filter by project_id if specified
-        if let Some(ref filter_id) = project_id {
-            if &doc_project_id != filter_id {
-                continue;
-            }
-        }
-
         let summary = get_text("session_summary");
 
         results.push(SearchResult {
             uuid: get_text("uuid"),
             content: get_text("content"),
             role: get_text("role"),
-            project_id: doc_project_id,
+            project_id: get_text("project_id"),
             project_path: get_text("project_path"),
             session_id: get_text("session_id"),
             session_summary: if summary.is_empty() {
@@ -505,5 +843,147 @@ pub fn search_chats(
         });
     }
 
+    if record.unwrap_or(false) {
+        saved_searches::record_search(
+            &query,
+            SearchFilters {
+                project_id,
+                limit,
+            },
+            results.len(),
+        );
+    }
     Ok(results)
 }
+
+/// Remove a project's messages from the search index without a rebuild
+#[tauri::command]
+pub async fn remove_project_from_index(project_id: String) -> AppResult<usize> {
+    Ok(
+        tauri::async_runtime::spawn_blocking(move || remove_project_documents(&project_id))
+            .await
+            .map_err(|e| e.to_string())??,
+    )
+}
+
+/// Size, message counts and last build time of the search index
+#[tauri::command]
+pub async fn get_search_index_stats() -> AppResult<SearchIndexStats> {
+    Ok(tauri::async_runtime::spawn_blocking(|| {
+        let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
+        current_stats(&mut guard)
+    })
+    .await
+    .map_err(|e| e.to_string())??)
+}
+
+/// Merge segments and purge deleted messages; returns the stats afterwards
+#[tauri::command]
+pub async fn optimize_search_index() -> AppResult<SearchIndexStats> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let mut guard = SEARCH_INDEX.lock().map_err(|e| e.to_string())?;
+        if !load_index(&mut guard)? {
+            return Err(AppError::new(
+                ErrorCode::Unavailable,
+                "Search index not built. Please build index first.",
+            ));
+        }
+        optimize_in(guard.as_ref().expect("search index should be initialized"))?;
+        Ok(current_stats(&mut guard)?)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_scoped_search_and_removal() {
+        let schema = create_schema();
+        let index = Index::create_in_ram(schema.clone());
+        register_jieba_tokenizer(&index);
+        let search_index = SearchIndex { index, schema };
+        let field = |name: &str| search_index.schema.get_field(name).expect("field");
+
+        let mut writer: IndexWriter = search_index
+            .index
+            .writer_with_num_threads(1, 15_000_000)
+            .expect("writer");
+        for (project_id, uuid) in [("p1", "a"), ("p1", "b"), ("p2", "c")] {
+            writer
+                .add_document(doc!(
+                    field("uuid") => uuid,
+                    field("content") => "tokio runtime panic",
+                    field("project_id") => project_id,
+                ))
+                .expect("add");
+        }
+        writer.commit().expect("commit");
+        drop(writer);
+
+        let hits = |project_id: Option<&str>| {
+            let query = build_query(&search_index, "tokio", project_id).expect("query");
+            let searcher = search_index.index.reader().expect("reader").searcher();
+            searcher.search(&query, &Count).expect("search")
+        };
+        assert_eq!(hits(None), 3);
+        assert_eq!(hits(Some("p2")), 1);
+
+        assert_eq!(remove_project_in(&search_index, "p1").expect("remove"), 2);
+        assert_eq!(
+            remove_project_in(&search_index, "p1").expect("remove again"),
+            0
+        );
+        assert_eq!(hits(None), 1);
+        assert_eq!(hits(Some("p2")), 1);
+    }
+
+    #[test]
+    fn test_index_stats_and_optimize() {
+        let schema = create_schema();
+        let index = Index::create_in_ram(schema.clone());
+        register_jieba_tokenizer(&index);
+        let search_index = SearchIndex { index, schema };
+        let project_field = search_index.schema.get_field("project_id").expect("field");
+
+        // Two commits, two segments
+        for batch in [["p1", "p2"], ["p1", "p2"]] {
+            let mut writer: IndexWriter = search_index
+                .index
+                .writer_with_num_threads(1, 15_000_000)
+                .expect("writer");
+            for project_id in batch {
+                writer
+                    .add_document(doc!(project_field => project_id))
+                    .expect("add");
+            }
+            writer.commit().expect("commit");
+        }
+        remove_project_in(&search_index, "p1").expect("remove");
+
+        let stats = index_stats_in(&search_index).expect("stats");
+        assert_eq!(
+            (stats.doc_count, stats.deleted_doc_count, stats.segment_count),
+            (2, 2, 2)
+        );
+        let projects: Vec<(&str, u64)> = stats
+            .projects
+            .iter()
+            .map(|p| (p.project_id.as_str(), p.doc_count))
+            .collect();
+        assert_eq!(projects, vec![("p2", 2)]);
+
+        optimize_in(&search_index).expect("optimize");
+        let stats = index_stats_in(&search_index).expect("stats");
+        assert_eq!(
+            (stats.doc_count, stats.deleted_doc_count, stats.segment_count),
+            (2, 0, 1)
+        );
+    }
+}
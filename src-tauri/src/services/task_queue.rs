@@ -0,0 +1,873 @@
+/**
+ * [INPUT]: 依赖 claude CLI (-p 无头模式, stream-json 输出), crate::jsonl (逐行读取输出), commands::claude_binary (首选二进制), services::distill (kill_process_group), services::notifications (完成通知), services::webhooks (完成推送), crate::security, tauri::Emitter
+ * [OUTPUT]: 对外提供 start_task_queue 后台工作线程, enqueue_claude_task, list_claude_tasks, get_claude_task, cancel_claude_task, remove_claude_task 命令, queue_task/validate_task/task_status 供其他模块 (定时任务) 使用, active_task_count 供托盘统计, ClaudeTaskProgress 事件
+ * [POS]: services/ 的无头任务编排，有界工作线程串行消费 claude -p 任务，队列持久化到 claude_tasks.json，重启后继续
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::claude_binary::with_preferred_claude;
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::jsonl;
+use crate::security;
+use crate::services::distill::kill_process_group;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::webhooks::{self, WebhookEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Condvar, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Runs executing at the same time; the rest wait in the queue
+const WORKER_COUNT: usize = 2;
+/// Enqueueing fails once this many tasks are waiting
+const MAX_QUEUED_TASKS: usize = 100;
+/// Oldest finished tasks beyond this count are pruned
+const MAX_FINISHED_TASKS: usize = 200;
+/// Only the tail of each task's output is kept
+const MAX_OUTPUT_BYTES: usize = 32 * 1024;
+const DEFAULT_TIMEOUT_SECS: u64 = 1800;
+/// How long output is still read after claude exits; a background job it started can
+/// hold the pipes open indefinitely
+const OUTPUT_GRACE: Duration = Duration::from_secs(2);
+const PERMISSION_MODES: [&str; 4] = ["default", "acceptEdits", "bypassPermissions", "plan"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClaudeTaskOptions {
+    /// Shown in the task list instead of the prompt
+    pub label: Option<String>,
+    /// e.g. "sonnet", "opus" or a full model id
+    pub model: Option<String>,
+    /// "default" | "acceptEdits" | "bypassPermissions" | "plan"
+    pub permission_mode: Option<String>,
+    /// Tools allowed without asking, e.g. ["Read", "Edit", "Bash(cargo test:*)"]
+    pub allowed_tools: Vec<String>,
+    pub max_turns: Option<u32>,
+    /// The run is killed after this long (default 30 minutes)
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaudeTaskStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl ClaudeTaskStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A headless `claude -p` run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeTask {
+    pub id: String,
+    pub project_path: String,
+    pub prompt: String,
+    pub options: ClaudeTaskOptions,
+    pub status: ClaudeTaskStatus,
+    pub created_at: u64,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    /// Assistant text and tool calls as they streamed, tail only
+    #[serde(default)]
+    pub output: String,
+    /// Claude's final answer
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// Session the run was recorded as, so it can be opened or resumed
+    pub session_id: Option<String>,
+    pub cost_usd: Option<f64>,
+    pub num_turns: Option<u32>,
+}
+
+/// Payload of the "claude-task-progress" event
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeTaskProgress {
+    pub task_id: String,
+    pub status: ClaudeTaskStatus,
+    /// A new line of output (assistant text or a tool call), if that's what changed
+    pub line: Option<String>,
+}
+
+struct QueueState {
+    tasks: Vec<ClaudeTask>,
+    /// Ids of running tasks whose cancellation was requested
+    cancelled: HashSet<String>,
+}
+
+static QUEUE: LazyLock<Mutex<QueueState>> = LazyLock::new(|| {
+    let path = store_path();
+    let mut tasks = load_store(&path);
+    if recover_interrupted(&mut tasks) {
+        let _ = save_store(&path, &tasks);
+    }
+    Mutex::new(QueueState {
+        tasks,
+        cancelled: HashSet::new(),
+    })
+});
+
+/// Signalled when a task is queued
+static QUEUE_READY: Condvar = Condvar::new();
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+// ============================================================================
+// Store
+// ============================================================================
+
+fn store_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("claude_tasks.json")
+}
+
+fn load_store(path: &Path) -> Vec<ClaudeTask> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(path: &Path, tasks: &[ClaudeTask]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(tasks).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &json).map_err(|e| e.to_string())
+}
+
+fn persist(state: &QueueState) {
+    if let Err(e) = save_store(&store_path(), &state.tasks) {
+        tracing::warn!(error = %e, "Failed to save task queue");
+    }
+}
+
+/// Runs cut short by quitting can't be resumed; re-running could repeat side effects.
+/// Returns whether anything changed.
+fn recover_interrupted(tasks: &mut [ClaudeTask]) -> bool {
+    let mut changed = false;
+    for task in tasks
+        .iter_mut()
+        .filter(|t| t.status == ClaudeTaskStatus::Running)
+    {
+        task.status = ClaudeTaskStatus::Failed;
+        task.error = Some("Interrupted because lovcode quit".to_string());
+        task.finished_at = Some(now_secs());
+        changed = true;
+    }
+    changed
+}
+
+fn prune_finished(tasks: &mut Vec<ClaudeTask>) {
+    let finished = tasks.iter().filter(|t| t.status.is_finished()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_TASKS);
+    tasks.retain(|t| {
+        if excess > 0 && t.status.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn emit(task_id: &str, status: ClaudeTaskStatus, line: Option<String>) {
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit(
+            "claude-task-progress",
+            ClaudeTaskProgress {
+                task_id: task_id.to_string(),
+                status,
+                line,
+            },
+        );
+    }
+}
+
+/// Apply `f` to a task in the queue; false when it no longer exists
+fn update_task(id: &str, f: impl FnOnce(&mut ClaudeTask)) -> bool {
+    let Ok(mut state) = QUEUE.lock() else {
+        return false;
+    };
+    match state.tasks.iter_mut().find(|t| t.id == id) {
+        Some(task) => {
+            f(task);
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================================================
+// Running
+// ============================================================================
+
+/// `claude -p` command line for the options; the prompt goes in on stdin
+fn claude_command(options: &ClaudeTaskOptions) -> String {
+    let mut args = vec![
+        "claude".to_string(),
+        "-p".to_string(),
+        "--output-format".to_string(),
+        "stream-json".to_string(),
+        "--verbose".to_string(),
+    ];
+    if let Some(model) = &options.model {
+        args.extend(["--model".to_string(), security::shell_escape(model)]);
+    }
+    if let Some(mode) = &options.permission_mode {
+        args.extend([
+            "--permission-mode".to_string(),
+            security::shell_escape(mode),
+        ]);
+    }
+    if !options.allowed_tools.is_empty() {
+        args.push("--allowedTools".to_string());
+        args.extend(
+            options
+                .allowed_tools
+                .iter()
+                .map(|t| security::shell_escape(t)),
+        );
+    }
+    if let Some(turns) = options.max_turns {
+        args.extend(["--max-turns".to_string(), turns.to_string()]);
+    }
+    with_preferred_claude(&args.join(" "))
+}
+
+fn append_output(output: &mut String, line: &str) {
+    output.push_str(line);
+    output.push('\n');
+    if output.len() > MAX_OUTPUT_BYTES {
+        let cut = output.len() - MAX_OUTPUT_BYTES;
+        let cut = (cut..output.len())
+            .find(|i| output.is_char_boundary(*i))
+            .unwrap_or(0);
+        output.drain(..cut);
+    }
+}
+
+/// One-line summary of a tool call, e.g. "→ Bash: cargo test"
+fn describe_tool_use(block: &serde_json::Value) -> String {
+    let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+    let detail = block.get("input").and_then(|input| {
+        ["command", "file_path", "pattern", "url", "description"]
+            .iter()
+            .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+    });
+    match detail {
+        Some(detail) => format!("→ {}: {}", name, detail.lines().next().unwrap_or_default()),
+        None => format!("→ {}", name),
+    }
+}
+
+/// Fold one stream-json event into the task; returns the output it added, if any
+fn apply_stream_event(task: &mut ClaudeTask, line: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(line).ok()?;
+    let text = |key: &str| event.get(key).and_then(|v| v.as_str()).map(String::from);
+    match event.get("type").and_then(|t| t.as_str())? {
+        "system" => {
+            if let Some(session_id) = text("session_id") {
+                task.session_id = Some(session_id);
+            }
+            None
+        }
+        "assistant" => {
+            let blocks = event
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .and_then(|c| c.as_array())?;
+            let lines: Vec<String> = blocks
+                .iter()
+                .filter_map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                    Some("text") => block
+                        .get("text")
+                        .and_then(|t| t.as_str())
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty()),
+                    Some("tool_use") => Some(describe_tool_use(block)),
+                    _ => None,
+                })
+                .collect();
+            if lines.is_empty() {
+                return None;
+            }
+            let added = lines.join("\n");
+            append_output(&mut task.output, &added);
+            Some(added)
+        }
+        "result" => {
+            task.result = text("result");
+            if let Some(session_id) = text("session_id") {
+                task.session_id = Some(session_id);
+            }
+            task.cost_usd = event
+                .get("total_cost_usd")
+                .and_then(serde_json::Value::as_f64);
+            task.num_turns = event
+                .get("num_turns")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|n| u32::try_from(n).ok());
+            if event.get("is_error").and_then(serde_json::Value::as_bool) == Some(true) {
+                task.error = Some(
+                    task.result
+                        .clone()
+                        .or_else(|| text("subtype"))
+                        .unwrap_or_else(|| "claude reported an error".to_string()),
+                );
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// How a run ended, before it's folded into the task
+enum RunEnd {
+    Exited { success: bool, stderr: String },
+    TimedOut,
+    Cancelled,
+}
+
+/// Run the task's `claude -p` through the login shell (GUI apps lack the user's PATH),
+/// streaming events into the queue as they arrive
+fn run_task(task: &ClaudeTask) -> Result<RunEnd, String> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+    let mut cmd = Command::new(&shell);
+    cmd.args(["-ilc", &claude_command(&task.options)])
+        .current_dir(&task.project_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start claude: {}", e))?;
+
+    let mut stdin = child.stdin.take().ok_or("Failed to open claude stdin")?;
+    let prompt = task.prompt.clone();
+    std::thread::spawn(move || stdin.write_all(prompt.as_bytes()));
+    let stdout = child.stdout.take().ok_or("Failed to open claude stdout")?;
+    let (tx, rx) = channel::<String>();
+    std::thread::spawn(move || {
+        for (_, line) in jsonl::from_reader(stdout) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let mut stderr = child.stderr.take().ok_or("Failed to open claude stderr")?;
+    let (err_tx, err_rx) = channel::<String>();
+    std::thread::spawn(move || {
+        let mut err = String::new();
+        let _ = stderr.read_to_string(&mut err);
+        let _ = err_tx.send(err);
+    });
+
+    let handle_line = |line: String| {
+        let mut added = None;
+        update_task(&task.id, |t| added = apply_stream_event(t, &line));
+        if added.is_some() {
+            emit(&task.id, ClaudeTaskStatus::Running, added);
+        }
+    };
+
+    let timeout = Duration::from_secs(task.options.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let deadline = Instant::now() + timeout;
+    let end = loop {
+        // Checked after every line too: a chatty run must still stop on cancel or timeout
+        if let Ok(line) = rx.recv_timeout(Duration::from_millis(200)) {
+            handle_line(line);
+        }
+        let cancelled = QUEUE
+            .lock()
+            .is_ok_and(|state| state.cancelled.contains(&task.id));
+        if cancelled || Instant::now() >= deadline {
+            kill_process_group(&mut child);
+            break if cancelled {
+                RunEnd::Cancelled
+            } else {
+                RunEnd::TimedOut
+            };
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                // The readers finish once the pipes close; take what's left, but don't
+                // wait past the grace period on pipes a leftover background job holds
+                let grace = Instant::now() + OUTPUT_GRACE;
+                let stdout_closed = loop {
+                    let left = grace.saturating_duration_since(Instant::now());
+                    match rx.recv_timeout(left) {
+                        Ok(line) if !left.is_zero() => handle_line(line),
+                        Ok(_) | Err(RecvTimeoutError::Timeout) => break false,
+                        Err(RecvTimeoutError::Disconnected) => break true,
+                    }
+                };
+                let stderr = err_rx.recv_timeout(grace.saturating_duration_since(Instant::now()));
+                if !stdout_closed || stderr.is_err() {
+                    kill_process_group(&mut child);
+                }
+                break RunEnd::Exited {
+                    success: status.success(),
+                    stderr: stderr.unwrap_or_default(),
+                };
+            }
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to wait for claude: {}", e)),
+        }
+    };
+    Ok(end)
+}
+
+/// Final status and error for a task whose run ended
+fn finish(task: &mut ClaudeTask, end: Result<RunEnd, String>, timeout_secs: u64) {
+    task.finished_at = Some(now_secs());
+    let (status, error) = match end {
+        Ok(RunEnd::Cancelled) => (ClaudeTaskStatus::Cancelled, None),
+        Ok(RunEnd::TimedOut) => (
+            ClaudeTaskStatus::Failed,
+            Some(format!("claude -p timed out after {}s", timeout_secs)),
+        ),
+        Ok(RunEnd::Exited { success, stderr }) => {
+            if task.error.is_some() {
+                (ClaudeTaskStatus::Failed, task.error.clone())
+            } else if !success {
+                let stderr = stderr.trim();
+                let message = if stderr.is_empty() {
+                    "claude -p exited with an error".to_string()
+                } else {
+                    format!("claude -p failed: {}", stderr)
+                };
+                (ClaudeTaskStatus::Failed, Some(message))
+            } else {
+                (ClaudeTaskStatus::Succeeded, None)
+            }
+        }
+        Err(e) => (ClaudeTaskStatus::Failed, Some(e)),
+    };
+    task.status = status;
+    task.error = error;
+}
+
+/// Take the oldest queued task and mark it running, waiting until there is one
+fn claim_next() -> Option<ClaudeTask> {
+    let mut state = QUEUE.lock().ok()?;
+    loop {
+        if let Some(task) = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.status == ClaudeTaskStatus::Queued)
+        {
+            task.status = ClaudeTaskStatus::Running;
+            task.started_at = Some(now_secs());
+            let task = task.clone();
+            persist(&state);
+            return Some(task);
+        }
+        state = QUEUE_READY.wait(state).ok()?;
+    }
+}
+
+/// Native notification for a run that ended on its own (not cancelled)
+fn notify_finished(task: &ClaudeTask, status: ClaudeTaskStatus, error: Option<&str>) {
+    let name = task
+        .options
+        .label
+        .clone()
+        .unwrap_or_else(|| task.prompt.lines().next().unwrap_or_default().to_string());
+    if matches!(
+        status,
+        ClaudeTaskStatus::Succeeded | ClaudeTaskStatus::Failed
+    ) {
+        let succeeded = status == ClaudeTaskStatus::Succeeded;
+        webhooks::emit(
+            WebhookEvent::TaskCompleted,
+            if succeeded {
+                "Claude task finished"
+            } else {
+                "Claude task failed"
+            },
+            &error.map_or_else(|| name.clone(), |e| format!("{}: {}", name, e)),
+            serde_json::json!({
+                "task_id": task.id,
+                "label": name,
+                "project_path": task.project_path,
+                "status": status,
+                "error": error,
+            }),
+        );
+    }
+    match status {
+        ClaudeTaskStatus::Succeeded => {
+            notifications::notify(
+                "Claude task finished",
+                &name,
+                NotificationKind::TaskFinished,
+            );
+        }
+        ClaudeTaskStatus::Failed => {
+            let body = format!("{}: {}", name, error.unwrap_or("failed"));
+            notifications::notify("Claude task failed", &body, NotificationKind::TaskFailed);
+        }
+        _ => {}
+    }
+}
+
+fn worker_loop() {
+    while let Some(task) = claim_next() {
+        emit(&task.id, ClaudeTaskStatus::Running, None);
+        tracing::info!(task_id = %task.id, project = %task.project_path, "Claude task started");
+
+        let end = run_task(&task);
+        let timeout_secs = task.options.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
+        let Ok(mut state) = QUEUE.lock() else {
+            return;
+        };
+        state.cancelled.remove(&task.id);
+        let finished = state.tasks.iter_mut().find(|t| t.id == task.id).map(|t| {
+            finish(t, end, timeout_secs);
+            (t.status, t.error.clone())
+        });
+        prune_finished(&mut state.tasks);
+        persist(&state);
+        drop(state);
+
+        if let Some((status, error)) = finished {
+            tracing::info!(task_id = %task.id, ?status, "Claude task finished");
+            emit(&task.id, status, None);
+            notify_finished(&task, status, error.as_deref());
+        }
+    }
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Start the worker threads; queued tasks from the last run resume here
+pub fn start_task_queue(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+    LazyLock::force(&QUEUE);
+    for _ in 0..WORKER_COUNT {
+        std::thread::spawn(worker_loop);
+    }
+}
+
+fn validate_options(options: &ClaudeTaskOptions) -> AppResult<()> {
+    if let Some(mode) = &options.permission_mode {
+        if !PERMISSION_MODES.contains(&mode.as_str()) {
+            return Err(AppError::invalid_input(format!(
+                "Invalid permission mode: {} (expected one of {})",
+                mode,
+                PERMISSION_MODES.join(", ")
+            )));
+        }
+    }
+    if options.timeout_secs == Some(0) {
+        return Err(AppError::invalid_input("Timeout must be at least 1 second"));
+    }
+    Ok(())
+}
+
+/// Check a task before it's queued or saved for later; returns the validated project path
+pub fn validate_task(
+    project_path: &str,
+    prompt: &str,
+    options: &ClaudeTaskOptions,
+) -> AppResult<String> {
+    let project = security::validate_decoded_path(project_path)?;
+    if !Path::new(&project).is_dir() {
+        return Err(AppError::not_found("Project directory not found").with_context(project));
+    }
+    if prompt.trim().is_empty() {
+        return Err(AppError::invalid_input("Prompt cannot be empty"));
+    }
+    validate_options(options)?;
+    Ok(project)
+}
+
+/// Current status of a task; None once it has been removed or pruned
+pub fn task_status(id: &str) -> Option<ClaudeTaskStatus> {
+    let state = QUEUE.lock().ok()?;
+    state.tasks.iter().find(|t| t.id == id).map(|t| t.status)
+}
+
+/// Tasks waiting for or holding a worker
+pub fn active_task_count() -> usize {
+    QUEUE.lock().map_or(0, |state| {
+        state
+            .tasks
+            .iter()
+            .filter(|t| {
+                matches!(
+                    t.status,
+                    ClaudeTaskStatus::Queued | ClaudeTaskStatus::Running
+                )
+            })
+            .count()
+    })
+}
+
+/// Queue a run from other modules (e.g. schedules)
+pub fn queue_task(
+    project_path: &str,
+    prompt: &str,
+    options: ClaudeTaskOptions,
+) -> AppResult<ClaudeTask> {
+    let project = validate_task(project_path, prompt, &options)?;
+
+    let mut state = QUEUE.lock().map_err(|_| "Task queue lock poisoned")?;
+    let queued = state
+        .tasks
+        .iter()
+        .filter(|t| t.status == ClaudeTaskStatus::Queued)
+        .count();
+    if queued >= MAX_QUEUED_TASKS {
+        return Err(AppError::new(
+            ErrorCode::Unavailable,
+            format!("Task queue is full ({} waiting)", queued),
+        ));
+    }
+
+    let task = ClaudeTask {
+        id: uuid::Uuid::new_v4().to_string(),
+        project_path: project,
+        prompt: prompt.to_string(),
+        options,
+        status: ClaudeTaskStatus::Queued,
+        created_at: now_secs(),
+        started_at: None,
+        finished_at: None,
+        output: String::new(),
+        result: None,
+        error: None,
+        session_id: None,
+        cost_usd: None,
+        num_turns: None,
+    };
+    state.tasks.push(task.clone());
+    persist(&state);
+    drop(state);
+
+    QUEUE_READY.notify_one();
+    emit(&task.id, task.status, None);
+    Ok(task)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Queue a headless `claude -p` run in `project_path`. Progress is emitted as
+/// "claude-task-progress" events.
+#[tauri::command]
+pub fn enqueue_claude_task(
+    project_path: String,
+    prompt: String,
+    options: Option<ClaudeTaskOptions>,
+) -> AppResult<ClaudeTask> {
+    queue_task(&project_path, &prompt, options.unwrap_or_default())
+}
+
+/// All tasks, newest first
+#[tauri::command]
+pub fn list_claude_tasks() -> AppResult<Vec<ClaudeTask>> {
+    let state = QUEUE.lock().map_err(|_| "Task queue lock poisoned")?;
+    Ok(state.tasks.iter().rev().cloned().collect())
+}
+
+#[tauri::command]
+pub fn get_claude_task(id: String) -> AppResult<ClaudeTask> {
+    let state = QUEUE.lock().map_err(|_| "Task queue lock poisoned")?;
+    state
+        .tasks
+        .iter()
+        .find(|t| t.id == id)
+        .cloned()
+        .ok_or_else(|| AppError::not_found(format!("Task '{}' not found", id)))
+}
+
+/// Drop a queued task, or stop a running one
+#[tauri::command]
+pub fn cancel_claude_task(id: String) -> AppResult<()> {
+    let mut state = QUEUE.lock().map_err(|_| "Task queue lock poisoned")?;
+    let task = state
+        .tasks
+        .iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Task '{}' not found", id)))?;
+    match task.status {
+        ClaudeTaskStatus::Queued => {
+            task.status = ClaudeTaskStatus::Cancelled;
+            task.finished_at = Some(now_secs());
+            persist(&state);
+            drop(state);
+            emit(&id, ClaudeTaskStatus::Cancelled, None);
+        }
+        // The worker kills the process and records the cancellation
+        ClaudeTaskStatus::Running => {
+            state.cancelled.insert(id);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Remove a finished task from the list
+#[tauri::command]
+pub fn remove_claude_task(id: String) -> AppResult<()> {
+    let mut state = QUEUE.lock().map_err(|_| "Task queue lock poisoned")?;
+    let index = state
+        .tasks
+        .iter()
+        .position(|t| t.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Task '{}' not found", id)))?;
+    if state
+        .tasks
+        .get(index)
+        .is_some_and(|t| !t.status.is_finished())
+    {
+        return Err(AppError::new(
+            ErrorCode::Conflict,
+            "Cancel the task before removing it",
+        ));
+    }
+    state.tasks.remove(index);
+    persist(&state);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> ClaudeTask {
+        ClaudeTask {
+            id: "t1".to_string(),
+            project_path: "/tmp/p".to_string(),
+            prompt: "fix lint".to_string(),
+            options: ClaudeTaskOptions::default(),
+            status: ClaudeTaskStatus::Running,
+            created_at: 0,
+            started_at: Some(0),
+            finished_at: None,
+            output: String::new(),
+            result: None,
+            error: None,
+            session_id: None,
+            cost_usd: None,
+            num_turns: None,
+        }
+    }
+
+    #[test]
+    fn test_stream_events() {
+        let mut t = task();
+        let lines = [
+            r#"{"type":"system","subtype":"init","session_id":"s-1","tools":[]}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Running the linter."},{"type":"tool_use","name":"Bash","input":{"command":"cargo clippy\n--fix"}}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"ok"}]}}"#,
+            r#"{"type":"result","subtype":"success","is_error":false,"result":"Fixed 3 warnings","session_id":"s-1","total_cost_usd":0.042,"num_turns":4}"#,
+        ];
+        let added: Vec<Option<String>> = lines
+            .iter()
+            .map(|l| apply_stream_event(&mut t, l))
+            .collect();
+        assert_eq!(
+            added,
+            vec![
+                None,
+                Some("Running the linter.\n→ Bash: cargo clippy".to_string()),
+                None,
+                None
+            ]
+        );
+        assert_eq!(t.session_id.as_deref(), Some("s-1"));
+        assert_eq!(t.result.as_deref(), Some("Fixed 3 warnings"));
+        assert_eq!(t.num_turns, Some(4));
+        assert!(t.error.is_none());
+
+        finish(
+            &mut t,
+            Ok(RunEnd::Exited {
+                success: true,
+                stderr: String::new(),
+            }),
+            60,
+        );
+        assert_eq!(t.status, ClaudeTaskStatus::Succeeded);
+
+        let mut failed = task();
+        apply_stream_event(
+            &mut failed,
+            r#"{"type":"result","subtype":"error_max_turns","is_error":true}"#,
+        );
+        finish(&mut failed, Ok(RunEnd::TimedOut), 60);
+        assert_eq!(failed.status, ClaudeTaskStatus::Failed);
+        assert_eq!(
+            failed.error.as_deref(),
+            Some("claude -p timed out after 60s")
+        );
+    }
+
+    #[test]
+    fn test_claude_command_and_recovery() {
+        let options = ClaudeTaskOptions {
+            model: Some("sonnet".to_string()),
+            permission_mode: Some("acceptEdits".to_string()),
+            allowed_tools: vec!["Read".to_string(), "Bash(git log:*)".to_string()],
+            max_turns: Some(5),
+            ..ClaudeTaskOptions::default()
+        };
+        let command = claude_command(&options);
+        assert!(command.ends_with(
+            "-p --output-format stream-json --verbose --model 'sonnet' --permission-mode 'acceptEdits' --allowedTools 'Read' 'Bash(git log:*)' --max-turns 5"
+        ));
+        assert!(validate_options(&ClaudeTaskOptions {
+            permission_mode: Some("yolo".to_string()),
+            ..ClaudeTaskOptions::default()
+        })
+        .is_err());
+
+        let mut tasks = vec![task(), task()];
+        if let Some(t) = tasks.get_mut(1) {
+            t.status = ClaudeTaskStatus::Queued;
+        }
+        assert!(recover_interrupted(&mut tasks));
+        let statuses: Vec<ClaudeTaskStatus> = tasks.iter().map(|t| t.status).collect();
+        assert_eq!(
+            statuses,
+            vec![ClaudeTaskStatus::Failed, ClaudeTaskStatus::Queued]
+        );
+
+        let mut output = String::new();
+        for _ in 0..5000 {
+            append_output(&mut output, "line of output");
+        }
+        assert!(output.len() <= MAX_OUTPUT_BYTES);
+        assert!(output.ends_with("line of output\n"));
+    }
+}
@@ -0,0 +1,177 @@
+/**
+ * [INPUT]: 依赖 tauri::Listener (事件订阅), crate::pty_manager, crate::workspace_store, crate::services::{task_queue, fs_watcher}, crate::commands::DISTILL_WATCH_ENABLED
+ * [OUTPUT]: 对外提供 TrayStats, collect_stats, stat_labels, tooltip, watchers_paused/set_watchers_paused, start_refresher
+ * [POS]: services/ 模块的系统托盘状态源，汇总运行中终端、待审核 feature、活跃 claude 任务，由 lib.rs 的托盘图标与菜单使用
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::commands::DISTILL_WATCH_ENABLED;
+use crate::services::{fs_watcher, task_queue};
+use crate::{pty_manager, workspace_store};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What the tray shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrayStats {
+    pub running_terminals: usize,
+    pub features_needing_review: usize,
+    /// Queued or running headless Claude tasks
+    pub active_claude_tasks: usize,
+    pub watchers_paused: bool,
+}
+
+/// Events after which the stats may have changed
+const REFRESH_EVENTS: [&str; 7] = [
+    "pty-exit",
+    "pty-restored",
+    "pty-info-changed",
+    "claude-task-progress",
+    "feature-complete",
+    "app-notification",
+    "watchers-paused",
+];
+
+/// Catches what no event reports, e.g. a new terminal or a review being closed
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn count_label(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+pub fn collect_stats() -> TrayStats {
+    TrayStats {
+        running_terminals: pty_manager::list_session_info()
+            .iter()
+            .filter(|s| s.running)
+            .count(),
+        features_needing_review: workspace_store::get_pending_reviews()
+            .map_or(0, |reviews| reviews.len()),
+        active_claude_tasks: task_queue::active_task_count(),
+        watchers_paused: watchers_paused(),
+    }
+}
+
+/// Menu lines for terminals, reviews and Claude tasks, in that order
+pub fn stat_labels(stats: &TrayStats) -> [String; 3] {
+    [
+        count_label(
+            stats.running_terminals,
+            "terminal running",
+            "terminals running",
+        ),
+        count_label(
+            stats.features_needing_review,
+            "feature needs review",
+            "features need review",
+        ),
+        count_label(
+            stats.active_claude_tasks,
+            "Claude task active",
+            "Claude tasks active",
+        ),
+    ]
+}
+
+pub fn tooltip(stats: &TrayStats) -> String {
+    let mut tooltip = format!(
+        "Lovcode: {} running, {} to review, {} tasks",
+        stats.running_terminals, stats.features_needing_review, stats.active_claude_tasks
+    );
+    if stats.watchers_paused {
+        tooltip.push_str(" (watchers paused)");
+    }
+    tooltip
+}
+
+pub fn watchers_paused() -> bool {
+    fs_watcher::is_paused()
+}
+
+/// Pause or resume the project directory watchers and the distill watcher together,
+/// and emit "watchers-paused" so the frontend can sync its distill toggle
+pub fn set_watchers_paused(app: &AppHandle, paused: bool) {
+    fs_watcher::set_paused(paused);
+    DISTILL_WATCH_ENABLED.store(!paused, Ordering::Relaxed);
+    let _ = app.emit("watchers-paused", paused);
+}
+
+/// Call `on_change` with the current stats now and whenever they change, checking
+/// after each of REFRESH_EVENTS and every POLL_INTERVAL
+pub fn start_refresher(app: &AppHandle, on_change: impl Fn(&TrayStats) + Send + 'static) {
+    let (tx, rx) = channel::<()>();
+    for event in REFRESH_EVENTS {
+        let tx = tx.clone();
+        app.listen_any(event, move |_| {
+            let _ = tx.send(());
+        });
+    }
+
+    std::thread::spawn(move || {
+        let mut shown: Option<TrayStats> = None;
+        loop {
+            let stats = collect_stats();
+            if shown != Some(stats) {
+                on_change(&stats);
+                shown = Some(stats);
+            }
+            match rx.recv_timeout(POLL_INTERVAL) {
+                // Events come in bursts (task output, terminal titles); one refresh covers them
+                Ok(()) => while rx.try_recv().is_ok() {},
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stat_labels() {
+        let stats = TrayStats {
+            running_terminals: 1,
+            features_needing_review: 0,
+            active_claude_tasks: 3,
+            watchers_paused: false,
+        };
+        assert_eq!(
+            stat_labels(&stats),
+            [
+                "1 terminal running".to_string(),
+                "0 features need review".to_string(),
+                "3 Claude tasks active".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tooltip_mentions_pause() {
+        let stats = TrayStats {
+            features_needing_review: 2,
+            ..TrayStats::default()
+        };
+        assert_eq!(tooltip(&stats), "Lovcode: 0 running, 2 to review, 0 tasks");
+        let paused = TrayStats {
+            watchers_paused: true,
+            ..stats
+        };
+        assert!(tooltip(&paused).ends_with("(watchers paused)"));
+    }
+}
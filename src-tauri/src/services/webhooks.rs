@@ -0,0 +1,462 @@
+/**
+ * [INPUT]: 依赖 reqwest (POST), tauri::async_runtime (后台投递), crate::security
+ * [OUTPUT]: 对外提供 emit 供 workspace/task_queue/hook_watcher 调用, add_webhook, list_webhooks, remove_webhook, set_webhook_enabled, test_webhook, list_webhook_deliveries 命令与 Webhook/WebhookKind/WebhookEvent/WebhookDelivery
+ * [POS]: services/ 模块的 Webhook 出口，把 feature 状态变化、claude 任务结束、会话结束以 Slack/Discord/通用 JSON 推送到用户配置的 URL，失败重试并记录投递日志
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::errors::{AppError, AppResult};
+use crate::security;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Attempts per event delivery; test deliveries are tried once
+const MAX_ATTEMPTS: u32 = 3;
+/// Wait before the second and third attempt
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(10)];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries kept in the log, newest last
+const MAX_LOG_ENTRIES: usize = 200;
+/// Discord rejects longer messages
+const DISCORD_MAX_CHARS: usize = 2000;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Payload format the receiving end expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookKind {
+    /// Slack incoming webhook: {"text"}
+    Slack,
+    /// Discord webhook: {"content"}
+    Discord,
+    /// The full event as JSON
+    Generic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A workspace feature moved to another status
+    FeatureStatusChanged,
+    /// A queued `claude -p` task succeeded or failed
+    TaskCompleted,
+    /// Claude finished responding in a monitored feature session (Stop hook)
+    SessionFinished,
+    /// Sent by test_webhook
+    Test,
+}
+
+impl WebhookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::FeatureStatusChanged => "feature_status_changed",
+            Self::TaskCompleted => "task_completed",
+            Self::SessionFinished => "session_finished",
+            Self::Test => "test",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub kind: WebhookKind,
+    /// Events to send; every event when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+    pub created_at: u64,
+}
+
+/// One delivery of one event to one webhook, after all attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    /// Unix seconds of the first attempt
+    pub timestamp: u64,
+    pub attempts: u32,
+    pub success: bool,
+    /// HTTP status of the last attempt, if there was a response
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Total time including retries
+    pub duration_ms: u64,
+}
+
+/// What is sent; rendered per webhook kind
+#[derive(Debug, Clone, Serialize)]
+struct WebhookPayload {
+    event: WebhookEvent,
+    /// RFC 3339
+    timestamp: String,
+    title: String,
+    text: String,
+    data: Value,
+}
+
+static WEBHOOKS: LazyLock<Mutex<Vec<Webhook>>> =
+    LazyLock::new(|| Mutex::new(load_json(&store_path())));
+
+static DELIVERIES: LazyLock<Mutex<Vec<WebhookDelivery>>> =
+    LazyLock::new(|| Mutex::new(load_json(&log_path())));
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn store_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("webhooks.json")
+}
+
+fn log_path() -> PathBuf {
+    security::get_lovstudio_dir_or_fallback().join("webhook_deliveries.json")
+}
+
+fn load_json<T: serde::de::DeserializeOwned + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    security::atomic_write_string(&path.to_path_buf(), &json)
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Slack and Discord webhook URLs are recognizable; anything else gets the generic payload
+fn detect_kind(url: &str) -> WebhookKind {
+    let host = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split(['/', '?']).next())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if host == "hooks.slack.com" {
+        WebhookKind::Slack
+    } else if (host == "discord.com" || host == "discordapp.com" || host.ends_with(".discord.com"))
+        && url.contains("/api/webhooks/")
+    {
+        WebhookKind::Discord
+    } else {
+        WebhookKind::Generic
+    }
+}
+
+fn validate_url(url: &str) -> AppResult<String> {
+    let url = url.trim();
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| AppError::invalid_input(format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::invalid_input(
+            "Webhook URL must be an http(s) URL",
+        ));
+    }
+    Ok(url.to_string())
+}
+
+fn render_body(kind: WebhookKind, payload: &WebhookPayload) -> Value {
+    match kind {
+        WebhookKind::Slack => json!({ "text": format!("*{}*\n{}", payload.title, payload.text) }),
+        WebhookKind::Discord => {
+            let content = format!("**{}**\n{}", payload.title, payload.text);
+            json!({ "content": content.chars().take(DISCORD_MAX_CHARS).collect::<String>() })
+        }
+        WebhookKind::Generic => json!(payload),
+    }
+}
+
+/// Rate limits and server errors are worth another try; other client errors aren't
+fn is_retryable(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+fn record(delivery: WebhookDelivery) {
+    let Ok(mut log) = DELIVERIES.lock() else {
+        return;
+    };
+    log.push(delivery);
+    let excess = log.len().saturating_sub(MAX_LOG_ENTRIES);
+    log.drain(..excess);
+    if let Err(e) = save_json(&log_path(), log.as_slice()) {
+        tracing::warn!(error = %e, "Failed to save webhook delivery log");
+    }
+}
+
+/// POST the payload, retrying failed attempts, and log the outcome
+async fn deliver(webhook: Webhook, payload: WebhookPayload, max_attempts: u32) -> WebhookDelivery {
+    let started = Instant::now();
+    let mut delivery = WebhookDelivery {
+        webhook_id: webhook.id.clone(),
+        event: payload.event,
+        timestamp: now_secs(),
+        attempts: 0,
+        success: false,
+        status: None,
+        error: None,
+        duration_ms: 0,
+    };
+    let body = render_body(webhook.kind, &payload);
+
+    match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => {
+            while delivery.attempts < max_attempts {
+                if let Some(delay) = delivery
+                    .attempts
+                    .checked_sub(1)
+                    .and_then(|i| RETRY_DELAYS.get(i as usize))
+                {
+                    tokio::time::sleep(*delay).await;
+                }
+                delivery.attempts += 1;
+                let retry = match client.post(&webhook.url).json(&body).send().await {
+                    Ok(response) => {
+                        let status = response.status().as_u16();
+                        delivery.status = Some(status);
+                        if response.status().is_success() {
+                            delivery.success = true;
+                            delivery.error = None;
+                            break;
+                        }
+                        delivery.error = Some(format!("HTTP {}", status));
+                        is_retryable(status)
+                    }
+                    Err(e) => {
+                        delivery.status = None;
+                        delivery.error = Some(e.to_string());
+                        true
+                    }
+                };
+                if !retry {
+                    break;
+                }
+            }
+        }
+        Err(e) => delivery.error = Some(e.to_string()),
+    }
+
+    delivery.duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    if !delivery.success {
+        tracing::warn!(
+            webhook = %webhook.name,
+            event = payload.event.as_str(),
+            attempts = delivery.attempts,
+            error = delivery.error.as_deref().unwrap_or_default(),
+            "Webhook delivery failed"
+        );
+    }
+    record(delivery.clone());
+    delivery
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Send an event to every enabled webhook subscribed to it, in the background.
+/// `title` and `text` are what chat webhooks show; `data` is for generic receivers.
+pub fn emit(event: WebhookEvent, title: &str, text: &str, data: Value) {
+    let targets: Vec<Webhook> = match WEBHOOKS.lock() {
+        Ok(webhooks) => webhooks
+            .iter()
+            .filter(|w| w.enabled && (w.events.is_empty() || w.events.contains(&event)))
+            .cloned()
+            .collect(),
+        Err(_) => return,
+    };
+    if targets.is_empty() {
+        return;
+    }
+    let payload = WebhookPayload {
+        event,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        title: title.to_string(),
+        text: text.to_string(),
+        data,
+    };
+    for webhook in targets {
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            deliver(webhook, payload, MAX_ATTEMPTS).await;
+        });
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Add a webhook. `kind` is detected from the URL when omitted (Slack and Discord
+/// webhook URLs, otherwise generic); `events` defaults to every event.
+#[tauri::command]
+pub fn add_webhook(
+    name: String,
+    url: String,
+    kind: Option<WebhookKind>,
+    events: Option<Vec<WebhookEvent>>,
+) -> AppResult<Webhook> {
+    let url = validate_url(&url)?;
+    let name = name.trim();
+    let webhook = Webhook {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: if name.is_empty() {
+            reqwest::Url::parse(&url)
+                .ok()
+                .and_then(|u| u.host_str().map(String::from))
+                .unwrap_or_default()
+        } else {
+            name.to_string()
+        },
+        kind: kind.unwrap_or_else(|| detect_kind(&url)),
+        url,
+        events: events
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| *e != WebhookEvent::Test)
+            .collect(),
+        enabled: true,
+        created_at: now_secs(),
+    };
+    let mut webhooks = WEBHOOKS.lock().map_err(|_| "Webhook lock poisoned")?;
+    webhooks.push(webhook.clone());
+    save_json(&store_path(), webhooks.as_slice())?;
+    Ok(webhook)
+}
+
+#[tauri::command]
+pub fn list_webhooks() -> AppResult<Vec<Webhook>> {
+    Ok(WEBHOOKS
+        .lock()
+        .map_err(|_| "Webhook lock poisoned")?
+        .clone())
+}
+
+#[tauri::command]
+pub fn remove_webhook(id: String) -> AppResult<()> {
+    let mut webhooks = WEBHOOKS.lock().map_err(|_| "Webhook lock poisoned")?;
+    let before = webhooks.len();
+    webhooks.retain(|w| w.id != id);
+    if webhooks.len() == before {
+        return Err(AppError::not_found(format!("Webhook '{}' not found", id)));
+    }
+    save_json(&store_path(), webhooks.as_slice())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_webhook_enabled(id: String, enabled: bool) -> AppResult<Webhook> {
+    let mut webhooks = WEBHOOKS.lock().map_err(|_| "Webhook lock poisoned")?;
+    let webhook = webhooks
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| AppError::not_found(format!("Webhook '{}' not found", id)))?;
+    webhook.enabled = enabled;
+    let webhook = webhook.clone();
+    save_json(&store_path(), webhooks.as_slice())?;
+    Ok(webhook)
+}
+
+/// Send a test event now, once, even to a disabled webhook, and wait for the result
+#[tauri::command]
+pub async fn test_webhook(id: String) -> AppResult<WebhookDelivery> {
+    let webhook = WEBHOOKS
+        .lock()
+        .map_err(|_| "Webhook lock poisoned")?
+        .iter()
+        .find(|w| w.id == id)
+        .cloned()
+        .ok_or_else(|| AppError::not_found(format!("Webhook '{}' not found", id)))?;
+    let payload = WebhookPayload {
+        event: WebhookEvent::Test,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        title: "Lovcode webhook test".to_string(),
+        text: format!("\"{}\" is set up correctly.", webhook.name),
+        data: json!({}),
+    };
+    Ok(deliver(webhook, payload, 1).await)
+}
+
+/// Recent deliveries, newest first, optionally for one webhook
+#[tauri::command]
+pub fn list_webhook_deliveries(
+    webhook_id: Option<String>,
+    limit: Option<usize>,
+) -> AppResult<Vec<WebhookDelivery>> {
+    let log = DELIVERIES.lock().map_err(|_| "Webhook log lock poisoned")?;
+    Ok(log
+        .iter()
+        .rev()
+        .filter(|d| webhook_id.as_ref().is_none_or(|id| &d.webhook_id == id))
+        .take(limit.unwrap_or(MAX_LOG_ENTRIES))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_kind_and_validate() {
+        assert_eq!(
+            detect_kind("https://hooks.slack.com/services/T0/B0/xyz"),
+            WebhookKind::Slack
+        );
+        assert_eq!(
+            detect_kind("https://discord.com/api/webhooks/1/abc"),
+            WebhookKind::Discord
+        );
+        assert_eq!(
+            detect_kind("https://example.com/hooks.slack.com/x"),
+            WebhookKind::Generic
+        );
+        assert!(validate_url("ftp://example.com/hook").is_err());
+        assert!(validate_url("not a url").is_err());
+        assert_eq!(
+            validate_url(" https://example.com/hook ").ok().as_deref(),
+            Some("https://example.com/hook")
+        );
+        assert!(is_retryable(503) && is_retryable(429) && !is_retryable(404));
+    }
+
+    #[test]
+    fn test_render_body_per_kind() {
+        let payload = WebhookPayload {
+            event: WebhookEvent::TaskCompleted,
+            timestamp: "2025-03-04T10:00:00+00:00".to_string(),
+            title: "Claude task finished".to_string(),
+            text: "Nightly lint".to_string(),
+            data: json!({ "status": "succeeded" }),
+        };
+        assert_eq!(
+            render_body(WebhookKind::Slack, &payload),
+            json!({ "text": "*Claude task finished*\nNightly lint" })
+        );
+        assert_eq!(
+            render_body(WebhookKind::Discord, &payload),
+            json!({ "content": "**Claude task finished**\nNightly lint" })
+        );
+        let generic = render_body(WebhookKind::Generic, &payload);
+        assert_eq!(generic["event"], "task_completed");
+        assert_eq!(generic["data"]["status"], "succeeded");
+    }
+}
@@ -0,0 +1,295 @@
+/**
+ * [INPUT]: 依赖 serde_json, crate::security (原子写入)
+ * [OUTPUT]: 对外提供 Schema (版本号 + 逐级迁移钩子), load / load_as (读取前自动恢复中断的写入), save (先写日志再写目标), remove, VERSION_KEY
+ * [POS]: src-tauri/src 的 JSON 存储层，workspace.json / disabled_env.json / PTY 会话 spec / command-stats.json / bookmarks.json / prompts.json / copy-history.json / searches.json 共用的预写日志与 schema 版本
+ * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
+ */
+
+use crate::security;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Top-level key holding the schema version of a store file. Files written before
+/// the storage layer existed don't have it and are version 0.
+pub const VERSION_KEY: &str = "_schema_version";
+
+/// Serializes saves and journal recovery, so a `load` never replays or clears a
+/// journal that a `save` on another thread is still working through
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Version of a store's data and how to upgrade older files
+pub struct Schema {
+    /// Version written by this build
+    pub version: u32,
+    /// Upgrades data from version `from` to `from + 1`; called once per step
+    pub migrate: fn(from: u32, data: &mut Value) -> Result<(), String>,
+}
+
+impl Schema {
+    /// A schema whose older versions read as-is
+    pub const fn new(version: u32) -> Self {
+        Self {
+            version,
+            migrate: |_, _| Ok(()),
+        }
+    }
+}
+
+/// Pending write, committed to `<file>.journal` before the store file is replaced
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    /// Byte length of `content`, so a journal cut short on disk is detected
+    len: usize,
+    content: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn journal_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".journal");
+    path.with_file_name(name)
+}
+
+fn write_lock() -> Result<MutexGuard<'static, ()>, String> {
+    WRITE_LOCK.lock().map_err(|_| "Store write lock poisoned".to_string())
+}
+
+fn clear_journal(journal: &Path) -> Result<(), String> {
+    match fs::remove_file(journal) {
+        // Already gone, e.g. recovered by another app instance
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to clear {}: {}", journal.display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    security::atomic_write_string(&path.to_path_buf(), content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Finish a write that was interrupted after its journal was committed.
+/// A journal that doesn't parse or has the wrong length never committed, so it is
+/// dropped and the store file keeps its previous content. Callers hold WRITE_LOCK.
+fn recover(path: &Path) -> Result<(), String> {
+    let journal = journal_path(path);
+    let Ok(raw) = fs::read_to_string(&journal) else {
+        return Ok(());
+    };
+    match serde_json::from_str::<JournalEntry>(&raw) {
+        Ok(entry) if entry.len == entry.content.len() => {
+            tracing::warn!("Replaying interrupted write to {}", path.display());
+            write_file(path, &entry.content)?;
+        }
+        _ => tracing::warn!("Discarding incomplete journal {}", journal.display()),
+    }
+    clear_journal(&journal)
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Read a store file at the current schema version; `None` if it doesn't exist.
+/// An interrupted write is recovered first, and data from an older version is
+/// migrated step by step. The version key is removed from the returned value.
+pub fn load(path: &Path, schema: &Schema) -> Result<Option<Value>, String> {
+    if journal_path(path).exists() {
+        let _guard = write_lock()?;
+        recover(path)?;
+    }
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut data: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let version = data
+        .as_object_mut()
+        .and_then(|object| object.remove(VERSION_KEY))
+        .and_then(|v| v.as_u64())
+        .map_or(0, |v| u32::try_from(v).unwrap_or(u32::MAX));
+    if version > schema.version {
+        tracing::warn!(
+            "{} has schema version {}, newer than {}; reading it as-is",
+            path.display(),
+            version,
+            schema.version
+        );
+    }
+    for from in version..schema.version {
+        (schema.migrate)(from, &mut data)
+            .map_err(|e| format!("Failed to migrate {} from v{}: {}", path.display(), from, e))?;
+    }
+    Ok(Some(data))
+}
+
+/// `load`, deserialized into `T`
+pub fn load_as<T: DeserializeOwned>(path: &Path, schema: &Schema) -> Result<Option<T>, String> {
+    load(path, schema)?
+        .map(|data| {
+            serde_json::from_value(data)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+        })
+        .transpose()
+}
+
+/// Replace a store file with `data` (a JSON object) tagged with the schema version.
+/// The new content is committed to a journal next to the file first, so a write
+/// killed midway is completed by the next `load` or `save`.
+pub fn save<T: Serialize>(path: &Path, schema: &Schema, data: &T) -> Result<(), String> {
+    let mut value = serde_json::to_value(data)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| format!("Store data for {} must be an object", path.display()))?;
+    object.insert(VERSION_KEY.to_string(), Value::from(schema.version));
+    let content = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let _guard = write_lock()?;
+    recover(path)?;
+
+    let journal = journal_path(path);
+    let entry = JournalEntry {
+        len: content.len(),
+        content,
+    };
+    let entry_json = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    write_file(&journal, &entry_json)?;
+    write_file(path, &entry.content)?;
+    clear_journal(&journal)
+}
+
+/// Delete a store file and any pending write to it
+pub fn remove(path: &Path) -> Result<(), String> {
+    let _guard = write_lock()?;
+    for file in [journal_path(path), path.to_path_buf()] {
+        match fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", file.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const SCHEMA: Schema = Schema {
+        version: 2,
+        migrate: |from, data| {
+            if from == 0 {
+                data["renamed"] = data
+                    .as_object_mut()
+                    .and_then(|o| o.remove("old"))
+                    .unwrap_or(Value::Null);
+            } else {
+                data["steps"] = json!(from + 1);
+            }
+            Ok(())
+        },
+    };
+
+    #[test]
+    fn test_save_load_and_migrate() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("nested").join("store.json");
+        assert_eq!(load(&path, &SCHEMA).expect("missing"), None);
+
+        // A file from before the storage layer is version 0 and runs every step
+        fs::create_dir_all(path.parent().expect("parent")).expect("dir");
+        fs::write(&path, r#"{"old":"x"}"#).expect("legacy file");
+        assert_eq!(
+            load(&path, &SCHEMA).expect("legacy"),
+            Some(json!({"renamed": "x", "steps": 2}))
+        );
+
+        save(&path, &SCHEMA, &json!({"renamed": "y"})).expect("save");
+        let raw: Value =
+            serde_json::from_str(&fs::read_to_string(&path).expect("read")).expect("json");
+        assert_eq!(raw[VERSION_KEY], json!(2));
+        assert!(!journal_path(&path).exists());
+        assert_eq!(
+            load(&path, &SCHEMA).expect("current"),
+            Some(json!({"renamed": "y"}))
+        );
+        assert!(save(&path, &SCHEMA, &json!([1])).is_err());
+
+        remove(&path).expect("remove");
+        assert!(!path.exists());
+        remove(&path).expect("remove again");
+    }
+
+    #[test]
+    fn test_interrupted_write_is_recovered() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("store.json");
+        save(&path, &SCHEMA, &json!({"renamed": "before"})).expect("save");
+
+        // Killed after committing the journal: the write is replayed
+        let content = r#"{"renamed":"after","_schema_version":2}"#.to_string();
+        let entry = JournalEntry {
+            len: content.len(),
+            content,
+        };
+        fs::write(
+            journal_path(&path),
+            serde_json::to_string(&entry).expect("journal"),
+        )
+        .expect("write journal");
+        assert_eq!(
+            load(&path, &SCHEMA).expect("replayed"),
+            Some(json!({"renamed": "after"}))
+        );
+        assert!(!journal_path(&path).exists());
+
+        // Killed while writing the journal: the old content stays
+        fs::write(journal_path(&path), r#"{"len":90,"content":"{\"ren"#).expect("torn");
+        assert_eq!(
+            load(&path, &SCHEMA).expect("discarded"),
+            Some(json!({"renamed": "after"}))
+        );
+        assert!(!journal_path(&path).exists());
+    }
+
+    #[test]
+    fn test_load_during_saves_never_fails() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = temp.path().join("store.json");
+        save(&path, &SCHEMA, &json!({"n": 0})).expect("save");
+
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || {
+            for n in 1..=200 {
+                save(&writer_path, &SCHEMA, &json!({"n": n})).expect("save");
+            }
+        });
+        for _ in 0..200 {
+            assert!(load(&path, &SCHEMA).expect("load").is_some());
+        }
+        writer.join().expect("writer");
+        assert_eq!(load(&path, &SCHEMA).expect("load"), Some(json!({"n": 200})));
+    }
+}
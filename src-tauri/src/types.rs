@@ -31,6 +31,12 @@ pub struct Session {
     pub last_modified: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionsResponse {
+    pub items: Vec<Session>,
+    pub total: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Message {
     pub uuid: String,
@@ -40,6 +46,9 @@ pub struct Message {
     pub is_meta: bool,
     pub is_tool: bool,
     pub line_number: usize,
+    /// Extended-thinking text; only filled when requested with `include_thinking`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -109,6 +118,9 @@ pub struct LocalCommand {
     pub changelog: Option<String>,
     pub aliases: Vec<String>,
     pub frontmatter: Option<String>,
+    pub scope: String, // "user" (~/.claude) | "project" (<project>/.claude)
+    pub usage_count: usize,
+    pub last_used: Option<i64>, // Unix seconds, from the command-stats index
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,13 +1,24 @@
 //! Workspace data persistence
 //!
 //! Stores workspace configuration including projects, features, and panel states.
-//! Data is persisted to ~/.lovstudio/lovcode/workspace.json
+//! Data is persisted to ~/.lovstudio/lovcode/workspace.json; named workspaces live in
+//! ~/.lovstudio/lovcode/workspaces/<name>.json and the active one is recorded in
+//! ~/.lovstudio/lovcode/active-workspace. Deleted projects and features are journaled to
+//! ~/.lovstudio/lovcode/workspace-trash.json so they can be restored.
 //!
 //! Thread Safety: Uses RwLock to prevent race conditions during concurrent access.
-
+//! Process Safety: an advisory lock on workspace.lock serializes app instances, and the
+//! `generation` counter rejects full saves built from data that has been replaced since
+//! the caller loaded it.
+//! Crash Safety: workspace files are written through the journaled store (crate::store),
+//! so a save interrupted by a kill is completed on the next load.
+
+use crate::errors::{AppError, AppResult, ErrorCode};
+use crate::store::{self, Schema};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
 // ============================================================================
@@ -18,13 +29,112 @@ use std::sync::RwLock;
 /// Uses RwLock because reads are more frequent than writes.
 static WORKSPACE_LOCK: RwLock<()> = RwLock::new(());
 
-/// Get the workspace data file path
-fn get_workspace_path() -> PathBuf {
+/// Prefix of the error returned when another app instance changed the workspace
+pub const WORKSPACE_CONFLICT: &str = "Workspace conflict";
+
+/// The workspace stored in workspace.json
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Deletions kept in workspace-trash.json (oldest are dropped first)
+const MAX_TRASH_ENTRIES: usize = 30;
+
+/// Schema of workspace files (default, named and exported workspaces)
+const WORKSPACE_SCHEMA: Schema = Schema::new(1);
+
+fn get_lovcode_dir() -> PathBuf {
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".lovstudio")
         .join("lovcode")
-        .join("workspace.json")
+}
+
+/// Data file of a named workspace (the default one keeps the original location)
+fn workspace_path_for(lovcode_dir: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_WORKSPACE {
+        lovcode_dir.join("workspace.json")
+    } else {
+        lovcode_dir.join("workspaces").join(format!("{}.json", name))
+    }
+}
+
+/// Name of the active workspace (falls back to the default one)
+fn active_workspace_name() -> String {
+    active_workspace_name_in(&get_lovcode_dir())
+}
+
+fn active_workspace_name_in(lovcode_dir: &Path) -> String {
+    fs::read_to_string(lovcode_dir.join("active-workspace"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|name| validate_workspace_name(name).is_ok())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+}
+
+/// Get the workspace data file path
+fn get_workspace_path() -> PathBuf {
+    workspace_path_in(&get_lovcode_dir())
+}
+
+fn workspace_path_in(lovcode_dir: &Path) -> PathBuf {
+    workspace_path_for(lovcode_dir, &active_workspace_name_in(lovcode_dir))
+}
+
+/// Run `f` while holding the advisory lock on ~/.lovstudio/lovcode/workspace.lock.
+/// WORKSPACE_LOCK only serializes threads; this also serializes app instances.
+fn with_file_lock<T>(exclusive: bool, f: impl FnOnce() -> AppResult<T>) -> AppResult<T> {
+    let lovcode_dir = get_lovcode_dir();
+    fs::create_dir_all(&lovcode_dir).map_err(|e| AppError::io("Failed to create directory", &e))?;
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(lovcode_dir.join("workspace.lock"))
+        .map_err(|e| AppError::io("Failed to open workspace lock", &e))?;
+    let mut lock = fd_lock::RwLock::new(file);
+
+    if exclusive {
+        let _guard = lock.write().map_err(|e| AppError::io("Failed to lock workspace", &e))?;
+        f()
+    } else {
+        let _guard = lock.read().map_err(|e| AppError::io("Failed to lock workspace", &e))?;
+        f()
+    }
+}
+
+/// A full overwrite is only safe if nobody wrote the file since the caller loaded it
+fn check_generation(loaded: Option<u64>, on_disk: u64) -> AppResult<()> {
+    match loaded {
+        Some(loaded) if loaded == on_disk => Ok(()),
+        Some(loaded) => Err(AppError::new(
+            ErrorCode::Conflict,
+            format!(
+                "{}: the workspace was changed since it was loaded (generation {} -> {}), reload it and try again",
+                WORKSPACE_CONFLICT, loaded, on_disk
+            ),
+        )),
+        None => Err(AppError::new(
+            ErrorCode::Conflict,
+            format!(
+                "{}: the data has no generation, load the workspace before saving it",
+                WORKSPACE_CONFLICT
+            ),
+        )),
+    }
+}
+
+/// Workspace names become file names
+fn validate_workspace_name(name: &str) -> AppResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ' ')
+        && !name.starts_with(' ');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::invalid_input(format!("Invalid workspace name: {}", name)))
+    }
 }
 
 /// Feature status
@@ -50,6 +160,9 @@ pub struct SessionState {
     pub pty_id: String,
     pub title: String,
     pub command: Option<String>,
+    /// SSH host alias for remote terminals (`command` then runs on that host)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_host: Option<String>,
 }
 
 /// Panel state (container for multiple session tabs)
@@ -62,6 +175,9 @@ pub struct PanelState {
     pub active_session_id: String,
     pub is_shared: bool,
     pub cwd: String,
+    /// Extra environment for terminals started in this panel (e.g. ANTHROPIC_MODEL)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
 }
 
 /// Layout tree node - either a panel leaf or a split container
@@ -73,9 +189,22 @@ pub enum LayoutNode {
         direction: String,
         first: Box<LayoutNode>,
         second: Box<LayoutNode>,
+        /// Share of the split taken by `first`, between 0 and 1
+        #[serde(default = "default_split_ratio")]
+        ratio: f64,
+        /// Minimum pane sizes in pixels (None = renderer default)
+        #[serde(default)]
+        min_first: Option<u32>,
+        #[serde(default)]
+        min_second: Option<u32>,
     },
 }
 
+/// Even split, also used for layouts saved before ratios existed
+const fn default_split_ratio() -> f64 {
+    0.5
+}
+
 /// Feature within a project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Feature {
@@ -88,13 +217,28 @@ pub struct Feature {
     #[serde(default)]
     pub description: Option<String>,
     pub status: FeatureStatus,
+    /// Rank within the project (kanban columns show features of one status by rank)
+    #[serde(default)]
+    pub order: Option<u32>,
     #[serde(default)]
     pub pinned: Option<bool>,
     #[serde(default)]
     pub archived: Option<bool>,
     pub archived_note: Option<String>,
     pub git_branch: Option<String>,
+    /// Branch git_branch was started from; completing the feature merges back into it
+    #[serde(default)]
+    pub base_branch: Option<String>,
+    /// Git worktree the feature runs in (None = the project directory itself)
+    #[serde(default)]
+    pub worktree_path: Option<String>,
+    /// Snapshot the project (services::checkpoint) when the feature starts running
+    #[serde(default)]
+    pub auto_checkpoint: Option<bool>,
     pub chat_session_id: Option<String>,
+    /// Every Claude session linked to this feature (chat_session_id is the primary one)
+    #[serde(default)]
+    pub session_ids: Vec<String>,
     pub panels: Vec<PanelState>,
     /// @deprecated Use layout instead
     #[serde(default)]
@@ -122,6 +266,42 @@ pub struct WorkspaceProject {
     pub created_at: u64,
 }
 
+/// A named workspace and whether it is the one currently loaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceProfile {
+    pub name: String,
+    pub active: bool,
+    pub project_count: usize,
+}
+
+/// Result of importing a workspace file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct WorkspaceImportSummary {
+    pub projects_added: usize,
+    pub features_added: usize,
+}
+
+/// A deleted project or feature, with enough context to put it back where it was
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TrashedEntity {
+    Project { index: usize, project: Box<WorkspaceProject> },
+    Feature {
+        project_id: String,
+        index: usize,
+        feature: Box<Feature>,
+    },
+}
+
+/// Entry of the workspace trash journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    /// Named workspace the entity was deleted from
+    pub workspace: String,
+    pub deleted_at: u64,
+    pub entity: TrashedEntity,
+}
+
 /// Complete workspace data
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WorkspaceData {
@@ -130,28 +310,39 @@ pub struct WorkspaceData {
     /// Global feature counter across all projects
     #[serde(default)]
     pub feature_counter: Option<u32>,
+    /// Bumped on every write. A full save must send back the value it loaded, so
+    /// data replaced in the meantime (another window, a background update) isn't lost.
+    /// Always set on loaded data; files from before generations existed load as 0.
+    #[serde(default)]
+    pub generation: Option<u64>,
 }
 
 /// Load workspace data from disk (thread-safe with read lock)
-pub fn load_workspace() -> Result<WorkspaceData, String> {
+pub fn load_workspace() -> AppResult<WorkspaceData> {
     let _guard = WORKSPACE_LOCK
         .read()
         .map_err(|_| "Workspace lock poisoned")?;
 
-    load_workspace_internal()
+    with_file_lock(false, || load_workspace_from(&get_workspace_path()))
 }
 
-/// Internal load without locking (for use within write-locked operations)
-fn load_workspace_internal() -> Result<WorkspaceData, String> {
-    let path = get_workspace_path();
-
-    if !path.exists() {
-        return Ok(WorkspaceData::default());
-    }
-
-    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read workspace: {}", e))?;
+/// Paths of all projects in the workspace (empty if it can't be loaded).
+/// Used by security::validate_user_path to allow file access inside them.
+pub fn project_roots() -> Vec<PathBuf> {
+    load_workspace()
+        .map(|data| data.projects.into_iter().map(|p| PathBuf::from(p.path)).collect())
+        .unwrap_or_default()
+}
 
-    let mut data: WorkspaceData = serde_json::from_str(&content).map_err(|e| format!("Failed to parse workspace: {}", e))?;
+/// Read and migrate a workspace file (a missing file is an empty workspace)
+fn load_workspace_from(path: &Path) -> AppResult<WorkspaceData> {
+    let Some(mut data) = store::load_as::<WorkspaceData>(path, &WORKSPACE_SCHEMA)? else {
+        return Ok(WorkspaceData {
+            generation: Some(0),
+            ..WorkspaceData::default()
+        });
+    };
+    data.generation.get_or_insert(0);
 
     // Migrate: initialize global feature_counter from max seq if not set
     if data.feature_counter.is_none() {
@@ -165,60 +356,376 @@ fn load_workspace_internal() -> Result<WorkspaceData, String> {
         }
     }
 
+    // Migrate: rank features that predate explicit ordering by their list position
+    for project in &mut data.projects {
+        if project.features.iter().any(|f| f.order.is_none()) {
+            normalize_feature_order(project);
+        }
+    }
+
     Ok(data)
 }
 
-/// Save workspace data to disk (atomic write, thread-safe with write lock)
-pub fn save_workspace(data: &WorkspaceData) -> Result<(), String> {
+/// Save workspace data to disk (atomic write, thread-safe with write lock) and return
+/// the new generation. Fails with a WORKSPACE_CONFLICT error unless `data.generation`
+/// is the generation of the file on disk, i.e. nothing wrote it since `data` was loaded.
+pub fn save_workspace(data: &WorkspaceData) -> AppResult<u64> {
+    let _guard = WORKSPACE_LOCK
+        .write()
+        .map_err(|_| "Workspace lock poisoned")?;
+
+    with_file_lock(true, || save_workspace_checked(&get_workspace_path(), data))
+}
+
+fn save_workspace_checked(path: &Path, data: &WorkspaceData) -> AppResult<u64> {
+    let on_disk = load_workspace_from(path)?.generation.unwrap_or(0);
+    check_generation(data.generation, on_disk)?;
+
+    let mut data = data.clone();
+    data.generation = Some(on_disk + 1);
+    save_workspace_to(path, &data)?;
+    Ok(on_disk + 1)
+}
+
+fn save_workspace_to(path: &Path, data: &WorkspaceData) -> AppResult<()> {
+    // 经预写日志原子化写入，崩溃后下次读取时自动恢复
+    Ok(store::save(path, &WORKSPACE_SCHEMA, data)?)
+}
+
+/// Execute a read-modify-write operation atomically with write lock
+fn with_workspace_mut<F, T>(f: F) -> AppResult<T>
+where
+    F: FnOnce(&mut WorkspaceData) -> AppResult<T>,
+{
     let _guard = WORKSPACE_LOCK
         .write()
         .map_err(|_| "Workspace lock poisoned")?;
 
-    save_workspace_internal(data)
+    with_file_lock(true, || {
+        let path = get_workspace_path();
+        let mut data = load_workspace_from(&path)?;
+        let generation = data.generation.unwrap_or(0);
+        let result = f(&mut data)?;
+
+        data.generation = Some(generation + 1);
+        save_workspace_to(&path, &data)?;
+        Ok(result)
+    })
+}
+
+// ============================================================================
+// Named Workspaces and Export/Import
+// ============================================================================
+
+/// All named workspaces, default first
+pub fn list_workspaces() -> AppResult<Vec<WorkspaceProfile>> {
+    let _guard = WORKSPACE_LOCK
+        .read()
+        .map_err(|_| "Workspace lock poisoned")?;
+    with_file_lock(false, || Ok(workspace_profiles()))
+}
+
+fn workspace_profiles() -> Vec<WorkspaceProfile> {
+    let lovcode_dir = get_lovcode_dir();
+    let mut names: Vec<String> = fs::read_dir(lovcode_dir.join("workspaces"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().to_string())
+        })
+        .filter(|name| name != DEFAULT_WORKSPACE && validate_workspace_name(name).is_ok())
+        .collect();
+    names.sort();
+    names.insert(0, DEFAULT_WORKSPACE.to_string());
+
+    let active = active_workspace_name();
+    names
+        .into_iter()
+        .map(|name| {
+            let project_count = load_workspace_from(&workspace_path_for(&lovcode_dir, &name))
+                .map_or(0, |d| d.projects.len());
+            WorkspaceProfile {
+                active: name == active,
+                name,
+                project_count,
+            }
+        })
+        .collect()
 }
 
-/// Internal save without locking (for use within already-locked operations)
-fn save_workspace_internal(data: &WorkspaceData) -> Result<(), String> {
-    use crate::security;
+/// Make `name` the active workspace, creating it empty if it does not exist yet
+pub fn switch_workspace(name: &str) -> AppResult<WorkspaceData> {
+    validate_workspace_name(name)?;
+    let _guard = WORKSPACE_LOCK
+        .write()
+        .map_err(|_| "Workspace lock poisoned")?;
 
-    let path = get_workspace_path();
+    with_file_lock(true, || {
+        let lovcode_dir = get_lovcode_dir();
+        let path = workspace_path_for(&lovcode_dir, name);
+        let data = load_workspace_from(&path)?;
+        if !path.exists() {
+            save_workspace_to(&path, &data)?;
+        }
+        crate::security::atomic_write_string(&lovcode_dir.join("active-workspace"), name)?;
+        Ok(data)
+    })
+}
 
-    // Ensure directory exists
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+/// Delete a named workspace; the default and the active workspace cannot be deleted
+pub fn delete_workspace(name: &str) -> AppResult<()> {
+    validate_workspace_name(name)?;
+    let _guard = WORKSPACE_LOCK
+        .write()
+        .map_err(|_| "Workspace lock poisoned")?;
+
+    with_file_lock(true, || {
+        if name == DEFAULT_WORKSPACE || name == active_workspace_name() {
+            return Err(AppError::invalid_input(format!("Cannot delete the {} workspace", name)));
+        }
+        let path = workspace_path_for(&get_lovcode_dir(), name);
+        if !path.exists() {
+            return Err(AppError::not_found(format!("Workspace '{}' not found", name)));
+        }
+        store::remove(&path).map_err(|e| {
+            AppError::new(ErrorCode::Io, format!("Failed to delete workspace: {}", e))
+        })
+    })
+}
+
+/// Write the active workspace to `path` (same format as workspace.json)
+pub fn export_workspace(path: &Path) -> AppResult<()> {
+    let data = load_workspace()?;
+    save_workspace_to(path, &data)
+}
+
+/// Merge `incoming` into `data`: projects are matched by path, features by id.
+/// Added features get fresh sequence numbers so seq stays unique.
+fn merge_workspace_data(data: &mut WorkspaceData, incoming: WorkspaceData) -> WorkspaceImportSummary {
+    let mut summary = WorkspaceImportSummary::default();
+    let mut counter = data.feature_counter.unwrap_or(0);
+
+    for mut project in incoming.projects {
+        let features = std::mem::take(&mut project.features);
+        let index = if let Some(index) = data.projects.iter().position(|p| p.path == project.path) {
+            index
+        } else {
+            if data.projects.iter().any(|p| p.id == project.id) {
+                project.id = uuid::Uuid::new_v4().to_string();
+            }
+            project.active_feature_id = None;
+            summary.projects_added += 1;
+            data.projects.push(project);
+            data.projects.len() - 1
+        };
+        let Some(target) = data.projects.get_mut(index) else {
+            continue;
+        };
+
+        let mut next_order = target
+            .features
+            .iter()
+            .filter_map(|f| f.order)
+            .max()
+            .map_or(0, |max| max + 1);
+        for mut feature in features {
+            if target.features.iter().any(|f| f.id == feature.id) {
+                continue;
+            }
+            counter += 1;
+            feature.seq = counter;
+            feature.order = Some(next_order);
+            next_order += 1;
+            // Terminals from another machine or profile are not running here
+            for panel in &mut feature.panels {
+                for session in &mut panel.sessions {
+                    session.pty_id.clear();
+                }
+            }
+            if target.active_feature_id.is_none() {
+                target.active_feature_id = Some(feature.id.clone());
+            }
+            target.features.push(feature);
+            summary.features_added += 1;
+        }
+    }
+
+    if counter > 0 {
+        data.feature_counter = Some(counter);
     }
+    if data.active_project_id.is_none() {
+        data.active_project_id = data.projects.first().map(|p| p.id.clone());
+    }
+    summary
+}
+
+/// Import a workspace file into the active workspace, merging or replacing it
+pub fn import_workspace(path: &Path, merge: bool) -> AppResult<WorkspaceImportSummary> {
+    if !path.exists() {
+        return Err(AppError::not_found(format!("File not found: {}", path.display())));
+    }
+    let incoming = load_workspace_from(path)?;
+    with_workspace_mut(|data| {
+        if merge {
+            return Ok(merge_workspace_data(data, incoming));
+        }
+        let summary = WorkspaceImportSummary {
+            projects_added: incoming.projects.len(),
+            features_added: incoming.projects.iter().map(|p| p.features.len()).sum(),
+        };
+        *data = incoming;
+        Ok(summary)
+    })
+}
+
+// ============================================================================
+// Trash (undo for deleted projects and features)
+// ============================================================================
+
+fn get_trash_path() -> PathBuf {
+    get_lovcode_dir().join("workspace-trash.json")
+}
 
+fn load_trash(path: &Path) -> Vec<TrashEntry> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_trash(path: &Path, entries: &[TrashEntry]) -> AppResult<()> {
     let content =
-        serde_json::to_string_pretty(data).map_err(|e| format!("Failed to serialize workspace: {}", e))?;
+        serde_json::to_string_pretty(entries).map_err(|e| format!("Failed to serialize trash: {}", e))?;
+    crate::security::atomic_write_string(&path.to_path_buf(), &content)
+}
 
-    // 使用原子化写入，防止崩溃时数据损坏
-    security::atomic_write_string(&path, &content)
-        .map_err(|e| format!("Failed to write workspace: {}", e))?;
+/// Journal a deletion (called with the workspace locks held)
+fn push_trash(entity: TrashedEntity) -> AppResult<()> {
+    let path = get_trash_path();
+    let mut entries = load_trash(&path);
+    entries.push(TrashEntry {
+        workspace: active_workspace_name(),
+        deleted_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        entity,
+    });
+    let excess = entries.len().saturating_sub(MAX_TRASH_ENTRIES);
+    entries.drain(..excess);
+    save_trash(&path, &entries)
+}
 
+/// Put a trashed entity back at its old position
+fn restore_entity(data: &mut WorkspaceData, entity: TrashedEntity) -> AppResult<()> {
+    match entity {
+        TrashedEntity::Project { index, project } => {
+            if data.projects.iter().any(|p| p.id == project.id || p.path == project.path) {
+                return Err(AppError::new(
+                    ErrorCode::Conflict,
+                    format!("Project '{}' is already in the workspace", project.name),
+                ));
+            }
+            if data.active_project_id.is_none() {
+                data.active_project_id = Some(project.id.clone());
+            }
+            let index = index.min(data.projects.len());
+            data.projects.insert(index, *project);
+        }
+        TrashedEntity::Feature {
+            project_id,
+            index,
+            feature,
+        } => {
+            let project = data
+                .projects
+                .iter_mut()
+                .find(|p| p.id == project_id)
+                .ok_or_else(|| {
+                    AppError::not_found(format!("Project '{}' no longer exists", project_id))
+                })?;
+            if project.features.iter().any(|f| f.id == feature.id) {
+                return Err(AppError::new(
+                    ErrorCode::Conflict,
+                    format!("Feature '{}' is already in the project", feature.name),
+                ));
+            }
+            // Reopen the feature's rank so the board looks as before the delete
+            if let Some(rank) = feature.order {
+                for f in &mut project.features {
+                    if let Some(order) = f.order.as_mut().filter(|order| **order >= rank) {
+                        *order += 1;
+                    }
+                }
+            }
+            if project.active_feature_id.is_none() {
+                project.active_feature_id = Some(feature.id.clone());
+            }
+            let index = index.min(project.features.len());
+            project.features.insert(index, *feature);
+        }
+    }
     Ok(())
 }
 
-/// Execute a read-modify-write operation atomically with write lock
-fn with_workspace_mut<F, T>(f: F) -> Result<T, String>
-where
-    F: FnOnce(&mut WorkspaceData) -> Result<T, String>,
-{
+/// Restore the trash entry picked by `find` into the active workspace and drop it from the trash
+fn restore_trash_entry(find: impl Fn(&[TrashEntry], &str) -> Option<usize>) -> AppResult<TrashEntry> {
+    with_workspace_mut(|data| {
+        let path = get_trash_path();
+        let mut entries = load_trash(&path);
+        let position = find(&entries, &active_workspace_name())
+            .ok_or_else(|| AppError::not_found("Nothing to restore"))?;
+        let entry = entries.remove(position);
+        restore_entity(data, entry.entity.clone())?;
+        save_trash(&path, &entries)?;
+        Ok(entry)
+    })
+}
+
+/// Deleted entities of the active workspace, most recent first
+pub fn list_trash() -> AppResult<Vec<TrashEntry>> {
     let _guard = WORKSPACE_LOCK
-        .write()
+        .read()
         .map_err(|_| "Workspace lock poisoned")?;
+    with_file_lock(false, || {
+        let workspace = active_workspace_name();
+        let mut entries: Vec<TrashEntry> = load_trash(&get_trash_path())
+            .into_iter()
+            .filter(|e| e.workspace == workspace)
+            .collect();
+        entries.reverse();
+        Ok(entries)
+    })
+}
+
+/// Undo the most recent deletion in the active workspace
+pub fn undo_last_change() -> AppResult<TrashEntry> {
+    restore_trash_entry(|entries, workspace| entries.iter().rposition(|e| e.workspace == workspace))
+}
 
-    let mut data = load_workspace_internal()?;
-    let result = f(&mut data)?;
-    save_workspace_internal(&data)?;
-    Ok(result)
+/// Restore a deleted feature by id (its project must still exist)
+pub fn restore_deleted_feature(feature_id: &str) -> AppResult<TrashEntry> {
+    restore_trash_entry(|entries, workspace| {
+        entries.iter().rposition(|e| {
+            e.workspace == workspace
+                && matches!(&e.entity, TrashedEntity::Feature { feature, .. } if feature.id == feature_id)
+        })
+    })
 }
 
 /// Add a new project to the workspace
-pub fn add_project(path: String) -> Result<WorkspaceProject, String> {
+pub fn add_project(path: String) -> AppResult<WorkspaceProject> {
     with_workspace_mut(|data| {
         // Check if project already exists
         if data.projects.iter().any(|p| p.path == path) {
-            return Err(format!("Project '{}' already exists", path));
+            return Err(AppError::new(
+                ErrorCode::Conflict,
+                format!("Project '{}' already exists", path),
+            ));
         }
 
         // Extract project name from path
@@ -255,16 +762,20 @@ pub fn add_project(path: String) -> Result<WorkspaceProject, String> {
 }
 
 /// Remove a project from the workspace
-pub fn remove_project(id: &str) -> Result<(), String> {
+pub fn remove_project(id: &str) -> AppResult<()> {
     let id = id.to_string();
     with_workspace_mut(|data| {
         let index = data
             .projects
             .iter()
             .position(|p| p.id == id)
-            .ok_or_else(|| format!("Project '{}' not found", id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", id)))?;
 
-        data.projects.remove(index);
+        let project = data.projects.remove(index);
+        push_trash(TrashedEntity::Project {
+            index,
+            project: Box::new(project),
+        })?;
 
         // Update active project if needed
         if data.active_project_id.as_deref() == Some(id.as_str()) {
@@ -276,11 +787,11 @@ pub fn remove_project(id: &str) -> Result<(), String> {
 }
 
 /// Set the active project
-pub fn set_active_project(id: &str) -> Result<(), String> {
+pub fn set_active_project(id: &str) -> AppResult<()> {
     let id = id.to_string();
     with_workspace_mut(|data| {
         if !data.projects.iter().any(|p| p.id == id) {
-            return Err(format!("Project '{}' not found", id));
+            return Err(AppError::not_found(format!("Project '{}' not found", id)));
         }
 
         data.active_project_id = Some(id);
@@ -289,7 +800,7 @@ pub fn set_active_project(id: &str) -> Result<(), String> {
 }
 
 /// Create a new feature in a project
-pub fn create_feature(project_id: &str, name: String, description: Option<String>) -> Result<Feature, String> {
+pub fn create_feature(project_id: &str, name: String, description: Option<String>) -> AppResult<Feature> {
     let project_id = project_id.to_string();
     with_workspace_mut(|data| {
         // Increment global feature counter
@@ -300,19 +811,30 @@ pub fn create_feature(project_id: &str, name: String, description: Option<String
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
+        let order = project
+            .features
+            .iter()
+            .filter_map(|f| f.order)
+            .max()
+            .map_or(0, |max| max + 1);
         let feature = Feature {
             id: uuid::Uuid::new_v4().to_string(),
             seq,
             name: name.clone(),
             description: description.clone(),
             status: FeatureStatus::Pending,
+            order: Some(order),
             pinned: None,
             archived: None,
             archived_note: None,
             git_branch: None,
+            base_branch: None,
+            worktree_path: None,
+            auto_checkpoint: None,
             chat_session_id: None,
+            session_ids: Vec::new(),
             panels: Vec::new(),
             layout_direction: None,
             layout: None,
@@ -334,7 +856,7 @@ pub fn create_feature(project_id: &str, name: String, description: Option<String
 }
 
 /// Rename a feature
-pub fn rename_feature(feature_id: &str, name: String) -> Result<(), String> {
+pub fn rename_feature(feature_id: &str, name: String) -> AppResult<()> {
     let feature_id = feature_id.to_string();
     with_workspace_mut(|data| {
         for project in &mut data.projects {
@@ -343,12 +865,12 @@ pub fn rename_feature(feature_id: &str, name: String) -> Result<(), String> {
                 return Ok(());
             }
         }
-        Err(format!("Feature '{}' not found", feature_id))
+        Err(AppError::not_found(format!("Feature '{}' not found", feature_id)))
     })
 }
 
 /// Update a feature's status
-pub fn update_feature_status(project_id: &str, feature_id: &str, status: FeatureStatus) -> Result<(), String> {
+pub fn update_feature_status(project_id: &str, feature_id: &str, status: FeatureStatus) -> AppResult<()> {
     let project_id = project_id.to_string();
     let feature_id = feature_id.to_string();
     with_workspace_mut(|data| {
@@ -356,21 +878,208 @@ pub fn update_feature_status(project_id: &str, feature_id: &str, status: Feature
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
         let feature = project
             .features
             .iter_mut()
             .find(|f| f.id == feature_id)
-            .ok_or_else(|| format!("Feature '{}' not found", feature_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
 
         feature.status = status;
         Ok(())
     })
 }
 
+/// Sort features by rank (unranked last, keeping list order) and renumber ranks 0..n
+fn normalize_feature_order(project: &mut WorkspaceProject) {
+    project.features.sort_by_key(|f| f.order.unwrap_or(u32::MAX));
+    for (rank, feature) in project.features.iter_mut().enumerate() {
+        feature.order = Some(rank as u32);
+    }
+}
+
+/// Reorder the given features among the ranks they already occupy, so reordering
+/// one kanban column leaves the others untouched. Unknown ids are rejected.
+fn apply_feature_order(project: &mut WorkspaceProject, ordered_ids: &[String]) -> AppResult<()> {
+    normalize_feature_order(project);
+    let mut slots = Vec::with_capacity(ordered_ids.len());
+    for id in ordered_ids {
+        let feature = project
+            .features
+            .iter()
+            .find(|f| &f.id == id)
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", id)))?;
+        slots.push(feature.order.unwrap_or(u32::MAX));
+    }
+    slots.sort_unstable();
+    slots.dedup();
+    if slots.len() != ordered_ids.len() {
+        return Err(AppError::invalid_input("Duplicate feature ids in ordering"));
+    }
+
+    for (id, rank) in ordered_ids.iter().zip(slots) {
+        if let Some(feature) = project.features.iter_mut().find(|f| &f.id == id) {
+            feature.order = Some(rank);
+        }
+    }
+    normalize_feature_order(project);
+    Ok(())
+}
+
+/// Move a feature into the `status` column at `position` (clamped to the column length)
+fn move_feature_in_project(
+    project: &mut WorkspaceProject,
+    feature_id: &str,
+    status: FeatureStatus,
+    position: usize,
+) -> AppResult<()> {
+    normalize_feature_order(project);
+    let index = project
+        .features
+        .iter()
+        .position(|f| f.id == feature_id)
+        .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
+    let mut feature = project.features.remove(index);
+
+    let column: Vec<usize> = project
+        .features
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.status == status)
+        .map(|(i, _)| i)
+        .collect();
+    let insert_at = match column.get(position) {
+        Some(&i) => i,
+        None => column.last().map_or(project.features.len(), |&i| i + 1),
+    };
+
+    feature.status = status;
+    project.features.insert(insert_at, feature);
+    for (rank, feature) in project.features.iter_mut().enumerate() {
+        feature.order = Some(rank as u32);
+    }
+    Ok(())
+}
+
+/// Persist a drag-and-drop reordering of features within a project
+pub fn reorder_features(project_id: &str, ordered_ids: Vec<String>) -> AppResult<()> {
+    let project_id = project_id.to_string();
+    with_workspace_mut(|data| {
+        let project = data
+            .projects
+            .iter_mut()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
+
+        apply_feature_order(project, &ordered_ids)
+    })
+}
+
+/// Move a feature to another kanban column (or within its own) at `position`
+pub fn move_feature_status(feature_id: &str, status: FeatureStatus, position: usize) -> AppResult<()> {
+    let feature_id = feature_id.to_string();
+    with_workspace_mut(|data| {
+        let project = data
+            .projects
+            .iter_mut()
+            .find(|p| p.features.iter().any(|f| f.id == feature_id))
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
+
+        move_feature_in_project(project, &feature_id, status, position)
+    })
+}
+
+fn find_feature_mut<'a>(data: &'a mut WorkspaceData, feature_id: &str) -> AppResult<&'a mut Feature> {
+    data.projects
+        .iter_mut()
+        .flat_map(|p| p.features.iter_mut())
+        .find(|f| f.id == feature_id)
+        .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))
+}
+
+/// Link a session to a feature; the first linked session becomes its primary chat
+fn link_session(feature: &mut Feature, session_id: &str) {
+    if !feature.session_ids.iter().any(|s| s == session_id) {
+        feature.session_ids.push(session_id.to_string());
+    }
+    if feature.chat_session_id.is_none() {
+        feature.chat_session_id = Some(session_id.to_string());
+    }
+}
+
+/// Drop a session link; if it was the primary chat, the latest remaining link takes over
+fn unlink_session(feature: &mut Feature, session_id: &str) {
+    feature.session_ids.retain(|s| s != session_id);
+    if feature.chat_session_id.as_deref() == Some(session_id) {
+        feature.chat_session_id = feature.session_ids.last().cloned();
+    }
+}
+
+/// Link a Claude session to a feature
+pub fn link_feature_session(feature_id: &str, session_id: &str) -> AppResult<()> {
+    with_workspace_mut(|data| {
+        link_session(find_feature_mut(data, feature_id)?, session_id);
+        Ok(())
+    })
+}
+
+/// Unlink a Claude session from a feature
+pub fn unlink_feature_session(feature_id: &str, session_id: &str) -> AppResult<()> {
+    with_workspace_mut(|data| {
+        unlink_session(find_feature_mut(data, feature_id)?, session_id);
+        Ok(())
+    })
+}
+
+/// Feature a new session in ~/.claude/projects/<encoded_dir> belongs to: a feature whose
+/// worktree is that directory, else the active feature of the project rooted there.
+/// Returns (project_id, feature_id).
+fn match_session_feature(data: &WorkspaceData, encoded_dir: &str) -> Option<(String, String)> {
+    use crate::commands::projects::encode_project_path;
+
+    let projects = || data.projects.iter().filter(|p| !p.archived.unwrap_or(false));
+    for project in projects() {
+        let worktree_feature = project.features.iter().find(|f| {
+            !f.archived.unwrap_or(false)
+                && f.worktree_path.as_deref().map(encode_project_path).as_deref() == Some(encoded_dir)
+        });
+        if let Some(feature) = worktree_feature {
+            return Some((project.id.clone(), feature.id.clone()));
+        }
+    }
+    projects()
+        .find(|p| encode_project_path(&p.path) == encoded_dir)
+        .and_then(|p| Some((p.id.clone(), p.active_feature_id.clone()?)))
+}
+
+/// Attach a newly started session to the matching feature, unless some feature in that
+/// project already has it. Returns (project_id, feature_id) when a link was made.
+pub fn auto_link_session(encoded_dir: &str, session_id: &str) -> AppResult<Option<(String, String)>> {
+    with_workspace_mut(|data| {
+        let Some((project_id, feature_id)) = match_session_feature(data, encoded_dir) else {
+            return Ok(None);
+        };
+        let Some(project) = data.projects.iter_mut().find(|p| p.id == project_id) else {
+            return Ok(None);
+        };
+        if project
+            .features
+            .iter()
+            .any(|f| f.session_ids.iter().any(|s| s == session_id))
+        {
+            return Ok(None);
+        }
+        if let Some(feature) = project.features.iter_mut().find(|f| f.id == feature_id) {
+            link_session(feature, session_id);
+            return Ok(Some((project_id, feature_id)));
+        }
+        Ok(None)
+    })
+}
+
 /// Delete a feature
-pub fn delete_feature(project_id: &str, feature_id: &str) -> Result<(), String> {
+pub fn delete_feature(project_id: &str, feature_id: &str) -> AppResult<()> {
     let project_id = project_id.to_string();
     let feature_id = feature_id.to_string();
     with_workspace_mut(|data| {
@@ -378,15 +1087,20 @@ pub fn delete_feature(project_id: &str, feature_id: &str) -> Result<(), String>
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
         let index = project
             .features
             .iter()
             .position(|f| f.id == feature_id)
-            .ok_or_else(|| format!("Feature '{}' not found", feature_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
 
-        project.features.remove(index);
+        let feature = project.features.remove(index);
+        push_trash(TrashedEntity::Feature {
+            project_id: project_id.clone(),
+            index,
+            feature: Box::new(feature),
+        })?;
 
         // Update active feature if needed
         if project.active_feature_id.as_deref() == Some(feature_id.as_str()) {
@@ -397,8 +1111,48 @@ pub fn delete_feature(project_id: &str, feature_id: &str) -> Result<(), String>
     })
 }
 
+/// Bind a feature to a git worktree (None unbinds it); the branch is recorded alongside
+pub fn set_feature_worktree(
+    project_id: &str,
+    feature_id: &str,
+    worktree_path: Option<String>,
+    git_branch: Option<String>,
+) -> AppResult<()> {
+    let project_id = project_id.to_string();
+    let feature_id = feature_id.to_string();
+    with_workspace_mut(|data| {
+        let project = data
+            .projects
+            .iter_mut()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
+
+        let feature = project
+            .features
+            .iter_mut()
+            .find(|f| f.id == feature_id)
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
+
+        feature.worktree_path = worktree_path;
+        if git_branch.is_some() {
+            feature.git_branch = git_branch;
+        }
+        Ok(())
+    })
+}
+
+/// Record the branch a feature works on and the branch it was started from
+pub fn set_feature_branch(feature_id: &str, git_branch: String, base_branch: Option<String>) -> AppResult<()> {
+    with_workspace_mut(|data| {
+        let feature = find_feature_mut(data, feature_id)?;
+        feature.git_branch = Some(git_branch);
+        feature.base_branch = base_branch;
+        Ok(())
+    })
+}
+
 /// Set the active feature for a project
-pub fn set_active_feature(project_id: &str, feature_id: &str) -> Result<(), String> {
+pub fn set_active_feature(project_id: &str, feature_id: &str) -> AppResult<()> {
     let project_id = project_id.to_string();
     let feature_id = feature_id.to_string();
     with_workspace_mut(|data| {
@@ -406,10 +1160,10 @@ pub fn set_active_feature(project_id: &str, feature_id: &str) -> Result<(), Stri
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
         if !project.features.iter().any(|f| f.id == feature_id) {
-            return Err(format!("Feature '{}' not found", feature_id));
+            return Err(AppError::not_found(format!("Feature '{}' not found", feature_id)));
         }
 
         project.active_feature_id = Some(feature_id);
@@ -418,7 +1172,7 @@ pub fn set_active_feature(project_id: &str, feature_id: &str) -> Result<(), Stri
 }
 
 /// Add a panel to a feature
-pub fn add_panel_to_feature(project_id: &str, feature_id: &str, panel: PanelState) -> Result<(), String> {
+pub fn add_panel_to_feature(project_id: &str, feature_id: &str, panel: PanelState) -> AppResult<()> {
     let project_id = project_id.to_string();
     let feature_id = feature_id.to_string();
     with_workspace_mut(|data| {
@@ -426,21 +1180,76 @@ pub fn add_panel_to_feature(project_id: &str, feature_id: &str, panel: PanelStat
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
         let feature = project
             .features
             .iter_mut()
             .find(|f| f.id == feature_id)
-            .ok_or_else(|| format!("Feature '{}' not found", feature_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
 
         feature.panels.push(panel);
         Ok(())
     })
 }
 
+/// Set the ratio of the split at `node_path` (0 = first child, 1 = second child; empty = root)
+fn set_split_ratio(node: &mut LayoutNode, node_path: &[u8], ratio: f64) -> AppResult<()> {
+    let LayoutNode::Split {
+        first,
+        second,
+        ratio: current,
+        ..
+    } = node
+    else {
+        return Err(AppError::invalid_input("Layout node is not a split"));
+    };
+    match node_path.split_first() {
+        None => {
+            *current = ratio;
+            Ok(())
+        }
+        Some((0, rest)) => set_split_ratio(first, rest, ratio),
+        Some((1, rest)) => set_split_ratio(second, rest, ratio),
+        Some((step, _)) => {
+            Err(AppError::invalid_input(format!("Invalid layout path step: {}", step)))
+        }
+    }
+}
+
+/// Persist a resized split so the layout restores as the user left it
+pub fn update_layout_ratio(project_id: &str, feature_id: &str, node_path: &[u8], ratio: f64) -> AppResult<()> {
+    if ratio.is_nan() || ratio <= 0.0 || ratio >= 1.0 {
+        return Err(AppError::invalid_input(format!(
+            "Split ratio must be between 0 and 1, got {}",
+            ratio
+        )));
+    }
+    with_workspace_mut(|data| {
+        let project = data
+            .projects
+            .iter_mut()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
+
+        let feature = project
+            .features
+            .iter_mut()
+            .find(|f| f.id == feature_id)
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
+
+        let layout = feature
+            .layout
+            .as_mut()
+            .ok_or_else(|| {
+                AppError::invalid_input(format!("Feature '{}' has no layout", feature_id))
+            })?;
+        set_split_ratio(layout, node_path, ratio)
+    })
+}
+
 /// Remove a panel from a feature
-pub fn remove_panel_from_feature(project_id: &str, feature_id: &str, panel_id: &str) -> Result<(), String> {
+pub fn remove_panel_from_feature(project_id: &str, feature_id: &str, panel_id: &str) -> AppResult<()> {
     let project_id = project_id.to_string();
     let feature_id = feature_id.to_string();
     let panel_id = panel_id.to_string();
@@ -449,21 +1258,50 @@ pub fn remove_panel_from_feature(project_id: &str, feature_id: &str, panel_id: &
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
         let feature = project
             .features
             .iter_mut()
             .find(|f| f.id == feature_id)
-            .ok_or_else(|| format!("Feature '{}' not found", feature_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Feature '{}' not found", feature_id)))?;
 
         feature.panels.retain(|p| p.id != panel_id);
         Ok(())
     })
 }
 
+fn find_panel_mut<'a>(project: &'a mut WorkspaceProject, panel_id: &str) -> Option<&'a mut PanelState> {
+    if let Some(index) = project.shared_panels.iter().position(|p| p.id == panel_id) {
+        return project.shared_panels.get_mut(index);
+    }
+    project
+        .features
+        .iter_mut()
+        .flat_map(|f| f.panels.iter_mut())
+        .find(|p| p.id == panel_id)
+}
+
+/// Replace the environment variables of a panel (shared or in any feature)
+pub fn set_panel_env(project_id: &str, panel_id: &str, env: BTreeMap<String, String>) -> AppResult<()> {
+    let project_id = project_id.to_string();
+    let panel_id = panel_id.to_string();
+    with_workspace_mut(|data| {
+        let project = data
+            .projects
+            .iter_mut()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
+
+        let panel = find_panel_mut(project, &panel_id)
+            .ok_or_else(|| AppError::not_found(format!("Panel '{}' not found", panel_id)))?;
+        panel.env = env;
+        Ok(())
+    })
+}
+
 /// Toggle panel shared state (move between feature and shared)
-pub fn toggle_panel_shared(project_id: &str, panel_id: &str) -> Result<bool, String> {
+pub fn toggle_panel_shared(project_id: &str, panel_id: &str) -> AppResult<bool> {
     let project_id = project_id.to_string();
     let panel_id = panel_id.to_string();
     with_workspace_mut(|data| {
@@ -471,7 +1309,7 @@ pub fn toggle_panel_shared(project_id: &str, panel_id: &str) -> Result<bool, Str
             .projects
             .iter_mut()
             .find(|p| p.id == project_id)
-            .ok_or_else(|| format!("Project '{}' not found", project_id))?;
+            .ok_or_else(|| AppError::not_found(format!("Project '{}' not found", project_id)))?;
 
         // Check if panel is in shared panels
         if let Some(index) = project.shared_panels.iter().position(|p| p.id == panel_id) {
@@ -500,12 +1338,12 @@ pub fn toggle_panel_shared(project_id: &str, panel_id: &str) -> Result<bool, Str
             }
         }
 
-        Err(format!("Panel '{}' not found", panel_id))
+        Err(AppError::not_found(format!("Panel '{}' not found", panel_id)))
     })
 }
 
 /// Get features that need review
-pub fn get_pending_reviews() -> Result<Vec<(String, String, String)>, String> {
+pub fn get_pending_reviews() -> AppResult<Vec<(String, String, String)>> {
     let data = load_workspace()?;
     let mut reviews = Vec::new();
 
@@ -580,6 +1418,7 @@ mod tests {
             pty_id: "pty-1".to_string(),
             title: "Terminal 1".to_string(),
             command: Some("npm run dev".to_string()),
+            ssh_host: None,
         };
 
         let json = serde_json::to_string(&session).unwrap();
@@ -599,6 +1438,7 @@ mod tests {
             active_session_id: "".to_string(),
             is_shared: false,
             cwd: "/home/user/project".to_string(),
+            env: BTreeMap::new(),
         };
 
         let json = serde_json::to_string(&panel).unwrap();
@@ -607,6 +1447,32 @@ mod tests {
         assert_eq!(deserialized.id, "panel-1");
         assert!(!deserialized.is_shared);
         assert_eq!(deserialized.cwd, "/home/user/project");
+        assert!(!json.contains("env"));
+    }
+
+    #[test]
+    fn test_find_panel_env_in_shared_and_feature_panels() {
+        let panel = |id: &str, shared: bool| PanelState {
+            id: id.to_string(),
+            sessions: vec![],
+            active_session_id: String::new(),
+            is_shared: shared,
+            cwd: "/tmp/project".to_string(),
+            env: BTreeMap::new(),
+        };
+        let mut project = kanban_project(&[("feat-a", FeatureStatus::Pending, Some(0))]);
+        project.features[0].panels.push(panel("feature-panel", false));
+        project.shared_panels.push(panel("shared-panel", true));
+
+        let env = BTreeMap::from([("ANTHROPIC_MODEL".to_string(), "opus".to_string())]);
+        find_panel_mut(&mut project, "feature-panel").expect("feature panel").env = env.clone();
+        find_panel_mut(&mut project, "shared-panel").expect("shared panel").env = env;
+        assert!(find_panel_mut(&mut project, "missing").is_none());
+
+        let json = serde_json::to_string(&project).expect("serialize project");
+        let restored: WorkspaceProject = serde_json::from_str(&json).expect("deserialize project");
+        assert_eq!(restored.features[0].panels[0].env["ANTHROPIC_MODEL"], "opus");
+        assert_eq!(restored.shared_panels[0].env["ANTHROPIC_MODEL"], "opus");
     }
 
     #[test]
@@ -630,11 +1496,41 @@ mod tests {
             second: Box::new(LayoutNode::Panel {
                 panelId: "panel-2".to_string(),
             }),
+            ratio: 0.3,
+            min_first: Some(200),
+            min_second: None,
         };
 
         let json = serde_json::to_string(&node).unwrap();
         assert!(json.contains("\"type\":\"split\""));
         assert!(json.contains("\"direction\":\"horizontal\""));
+        assert!(json.contains("\"ratio\":0.3"));
+        assert!(json.contains("\"min_first\":200"));
+    }
+
+    #[test]
+    fn test_set_split_ratio() {
+        // Saved before ratios existed: every split defaults to even
+        let mut layout: LayoutNode = serde_json::from_str(
+            r#"{"type":"split","direction":"horizontal",
+                "first":{"type":"panel","panelId":"a"},
+                "second":{"type":"split","direction":"vertical",
+                    "first":{"type":"panel","panelId":"b"},
+                    "second":{"type":"panel","panelId":"c"}}}"#,
+        )
+        .expect("parse layout");
+
+        set_split_ratio(&mut layout, &[1], 0.25).expect("nested split");
+        set_split_ratio(&mut layout, &[], 0.6).expect("root split");
+        let json = serde_json::to_value(&layout).expect("serialize layout");
+        assert_eq!(json["ratio"], 0.6);
+        assert_eq!(json["first"]["type"], "panel");
+        assert_eq!(json["second"]["ratio"], 0.25);
+        assert_eq!(json["second"]["min_first"], serde_json::Value::Null);
+
+        // Panels and unknown steps are rejected
+        assert!(set_split_ratio(&mut layout, &[0], 0.5).is_err());
+        assert!(set_split_ratio(&mut layout, &[2], 0.5).is_err());
     }
 
     #[test]
@@ -645,11 +1541,16 @@ mod tests {
             name: "Test Feature".to_string(),
             description: Some("Description".to_string()),
             status: FeatureStatus::Running,
+            order: Some(0),
             pinned: Some(true),
             archived: None,
             archived_note: None,
             git_branch: Some("feature/test".to_string()),
+            base_branch: Some("main".to_string()),
+            worktree_path: None,
+            auto_checkpoint: None,
             chat_session_id: None,
+            session_ids: vec![],
             panels: vec![],
             layout_direction: None,
             layout: None,
@@ -696,6 +1597,7 @@ mod tests {
             projects: vec![],
             active_project_id: Some("proj-1".to_string()),
             feature_counter: Some(10),
+            generation: Some(3),
         };
 
         let json = serde_json::to_string(&data).unwrap();
@@ -703,6 +1605,48 @@ mod tests {
 
         assert_eq!(deserialized.active_project_id, Some("proj-1".to_string()));
         assert_eq!(deserialized.feature_counter, Some(10));
+        assert_eq!(deserialized.generation, Some(3));
+    }
+
+    #[test]
+    fn test_check_generation() {
+        assert!(check_generation(Some(7), 7).is_ok());
+
+        let err = check_generation(Some(6), 7).expect_err("stale save must conflict");
+        assert_eq!(err.code(), ErrorCode::Conflict);
+        assert!(err.to_string().starts_with(WORKSPACE_CONFLICT));
+        // Data that never came from a load can't prove it is current
+        assert_eq!(
+            check_generation(None, 0).expect_err("missing generation").code(),
+            ErrorCode::Conflict
+        );
+    }
+
+    #[test]
+    fn test_stale_save_conflicts_after_background_load() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let path = temp.path().join("workspace.json");
+        // Files written before generations existed load as generation 0
+        fs::write(&path, r#"{"projects":[],"active_project_id":null}"#).expect("legacy file");
+
+        let stale = load_workspace_from(&path).expect("load");
+        assert_eq!(stale.generation, Some(0));
+        let mut other = load_workspace_from(&path).expect("load");
+        other.active_project_id = Some("from-other-window".to_string());
+        assert_eq!(save_workspace_checked(&path, &other).expect("save"), 1);
+
+        // Background readers (status updates, deep links, the REST API) load the file too;
+        // that must not make the stale copy look current
+        load_workspace_from(&path).expect("background load");
+        let err = save_workspace_checked(&path, &stale).expect_err("stale save");
+        assert_eq!(err.code(), ErrorCode::Conflict);
+        assert_eq!(
+            load_workspace_from(&path).expect("load").active_project_id.as_deref(),
+            Some("from-other-window")
+        );
+
+        let fresh = load_workspace_from(&path).expect("reload");
+        assert_eq!(save_workspace_checked(&path, &fresh).expect("save"), 2);
     }
 
     // ========================================================================
@@ -711,13 +1655,51 @@ mod tests {
 
     #[test]
     fn test_get_workspace_path() {
-        let path = get_workspace_path();
-        let path_str = path.to_string_lossy();
+        let lovcode_dir = get_lovcode_dir();
+        let lovcode_str = lovcode_dir.to_string_lossy();
+        assert!(lovcode_str.contains(".lovstudio"));
+        assert!(lovcode_str.contains("lovcode"));
+
+        // No named workspace active: the default workspace.json
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = workspace_path_in(temp.path());
+        assert!(path.to_string_lossy().ends_with("workspace.json"));
+        assert_eq!(path, temp.path().join("workspace.json"));
+    }
+
+    #[test]
+    fn test_get_workspace_path_named() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        fs::write(temp.path().join("active-workspace"), "Work Board\n").expect("write active");
+        assert_eq!(
+            workspace_path_in(temp.path()),
+            temp.path().join("workspaces").join("Work Board.json")
+        );
+
+        // An invalid name falls back to the default workspace
+        fs::write(temp.path().join("active-workspace"), "../x").expect("write active");
+        assert_eq!(
+            workspace_path_in(temp.path()),
+            temp.path().join("workspace.json")
+        );
+    }
 
-        // Should be in .lovstudio/lovcode/workspace.json
-        assert!(path_str.contains(".lovstudio"));
-        assert!(path_str.contains("lovcode"));
-        assert!(path_str.ends_with("workspace.json"));
+    #[test]
+    fn test_workspace_path_for_and_names() {
+        let dir = Path::new("/home/u/.lovstudio/lovcode");
+        assert_eq!(
+            workspace_path_for(dir, DEFAULT_WORKSPACE),
+            dir.join("workspace.json")
+        );
+        assert_eq!(
+            workspace_path_for(dir, "Work Board"),
+            dir.join("workspaces").join("Work Board.json")
+        );
+
+        assert!(validate_workspace_name("personal_2").is_ok());
+        assert!(validate_workspace_name("").is_err());
+        assert!(validate_workspace_name("../x").is_err());
+        assert!(validate_workspace_name(" lead").is_err());
     }
 
     // ========================================================================
@@ -743,6 +1725,9 @@ mod tests {
         assert!(feature.pinned.is_none());
         assert!(feature.archived.is_none());
         assert!(feature.git_branch.is_none());
+        assert!(feature.worktree_path.is_none());
+        assert!(feature.auto_checkpoint.is_none());
+        assert!(feature.order.is_none());
         assert!(feature.layout.is_none());
     }
 
@@ -777,11 +1762,16 @@ mod tests {
             name: "Test".to_string(),
             description: None,
             status: FeatureStatus::Pending,
+            order: None,
             pinned: None,
             archived: None,
             archived_note: None,
             git_branch: None,
+            base_branch: None,
+            worktree_path: None,
+            auto_checkpoint: None,
             chat_session_id: None,
+            session_ids: vec![],
             panels: vec![],
             layout_direction: None,
             layout: None,
@@ -811,4 +1801,285 @@ mod tests {
         assert_eq!(cloned.id, project.id);
         assert_eq!(cloned.path, project.path);
     }
+
+    // ========================================================================
+    // Ordering Tests
+    // ========================================================================
+
+    fn kanban_project(features: &[(&str, FeatureStatus, Option<u32>)]) -> WorkspaceProject {
+        let mut project: WorkspaceProject = serde_json::from_str(
+            r#"{"id":"p","name":"p","path":"/p","features":[],"active_feature_id":null,"created_at":0}"#,
+        )
+        .expect("parse project");
+        for (id, status, order) in features {
+            let mut feature: Feature = serde_json::from_str(
+                r#"{"id":"","name":"f","status":"pending","panels":[],"created_at":0}"#,
+            )
+            .expect("parse feature");
+            feature.id = id.to_string();
+            feature.status = status.clone();
+            feature.order = *order;
+            project.features.push(feature);
+        }
+        project
+    }
+
+    fn ids(project: &WorkspaceProject) -> Vec<&str> {
+        project.features.iter().map(|f| f.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_normalize_feature_order_migrates_unranked() {
+        let mut project = kanban_project(&[
+            ("a", FeatureStatus::Pending, None),
+            ("b", FeatureStatus::Pending, Some(0)),
+            ("c", FeatureStatus::Running, None),
+        ]);
+        normalize_feature_order(&mut project);
+        assert_eq!(ids(&project), vec!["b", "a", "c"]);
+        let orders: Vec<_> = project.features.iter().map(|f| f.order).collect();
+        assert_eq!(orders, vec![Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_apply_feature_order_keeps_other_columns() {
+        let mut project = kanban_project(&[
+            ("a", FeatureStatus::Pending, Some(0)),
+            ("x", FeatureStatus::Running, Some(1)),
+            ("b", FeatureStatus::Pending, Some(2)),
+            ("c", FeatureStatus::Pending, Some(3)),
+        ]);
+        apply_feature_order(&mut project, &["c".into(), "a".into(), "b".into()]).expect("reorder");
+        assert_eq!(ids(&project), vec!["c", "x", "a", "b"]);
+
+        assert!(apply_feature_order(&mut project, &["nope".into()]).is_err());
+        assert!(apply_feature_order(&mut project, &["a".into(), "a".into()]).is_err());
+    }
+
+    #[test]
+    fn test_move_feature_in_project() {
+        let mut project = kanban_project(&[
+            ("a", FeatureStatus::Pending, Some(0)),
+            ("b", FeatureStatus::Pending, Some(1)),
+            ("x", FeatureStatus::Running, Some(2)),
+            ("y", FeatureStatus::Running, Some(3)),
+        ]);
+
+        // Into the middle of another column
+        move_feature_in_project(&mut project, "a", FeatureStatus::Running, 1).expect("move");
+        assert_eq!(ids(&project), vec!["b", "x", "a", "y"]);
+        assert_eq!(project.features[2].status, FeatureStatus::Running);
+
+        // Past the end of a column appends after its last card
+        move_feature_in_project(&mut project, "y", FeatureStatus::Pending, 99).expect("move");
+        assert_eq!(ids(&project), vec!["b", "y", "x", "a"]);
+
+        // Into an empty column
+        move_feature_in_project(&mut project, "b", FeatureStatus::Completed, 0).expect("move");
+        assert_eq!(ids(&project), vec!["y", "x", "a", "b"]);
+        let orders: Vec<_> = project.features.iter().filter_map(|f| f.order).collect();
+        assert_eq!(orders, vec![0, 1, 2, 3]);
+
+        assert!(move_feature_in_project(&mut project, "nope", FeatureStatus::Pending, 0).is_err());
+    }
+
+    // ========================================================================
+    // Session Linking Tests
+    // ========================================================================
+
+    #[test]
+    fn test_link_and_unlink_session() {
+        let mut project = kanban_project(&[("a", FeatureStatus::Running, Some(0))]);
+        let feature = &mut project.features[0];
+        link_session(feature, "s1");
+        link_session(feature, "s2");
+        link_session(feature, "s1");
+        assert_eq!(feature.session_ids, vec!["s1", "s2"]);
+        assert_eq!(feature.chat_session_id.as_deref(), Some("s1"));
+
+        unlink_session(feature, "s1");
+        assert_eq!(feature.session_ids, vec!["s2"]);
+        assert_eq!(feature.chat_session_id.as_deref(), Some("s2"));
+        unlink_session(feature, "s2");
+        assert!(feature.chat_session_id.is_none());
+
+        let mut data = WorkspaceData {
+            projects: vec![project],
+            active_project_id: None,
+            feature_counter: None,
+            generation: None,
+        };
+        assert!(find_feature_mut(&mut data, "a").is_ok());
+        assert!(find_feature_mut(&mut data, "missing").is_err());
+    }
+
+    #[test]
+    fn test_match_session_feature() {
+        let mut project = kanban_project(&[
+            ("main", FeatureStatus::Running, Some(0)),
+            ("wt", FeatureStatus::Running, Some(1)),
+        ]);
+        project.path = "/work/app".to_string();
+        project.active_feature_id = Some("main".to_string());
+        project.features[1].worktree_path = Some("/work/app-wt".to_string());
+        let data = WorkspaceData {
+            projects: vec![project],
+            active_project_id: None,
+            feature_counter: None,
+            generation: None,
+        };
+
+        assert_eq!(
+            match_session_feature(&data, "-work-app"),
+            Some(("p".to_string(), "main".to_string()))
+        );
+        assert_eq!(
+            match_session_feature(&data, "-work-app-wt"),
+            Some(("p".to_string(), "wt".to_string()))
+        );
+        assert_eq!(match_session_feature(&data, "-elsewhere"), None);
+    }
+
+    // ========================================================================
+    // Import Tests
+    // ========================================================================
+
+    #[test]
+    fn test_merge_workspace_data() {
+        let mut local = kanban_project(&[("a", FeatureStatus::Pending, Some(0))]);
+        local.features[0].seq = 1;
+        let mut data = WorkspaceData {
+            projects: vec![local],
+            active_project_id: Some("p".to_string()),
+            feature_counter: Some(1),
+            generation: None,
+        };
+
+        let mut same_path = kanban_project(&[
+            ("a", FeatureStatus::Pending, Some(0)),
+            ("b", FeatureStatus::Running, Some(1)),
+        ]);
+        same_path.features[1].seq = 1;
+        same_path.features[1].panels = serde_json::from_str(
+            r#"[{"id":"panel","sessions":[{"id":"s","pty_id":"pty-1","title":"t","command":null}],"is_shared":false,"cwd":"/p"}]"#,
+        )
+        .expect("parse panels");
+        let mut other = kanban_project(&[("c", FeatureStatus::Pending, Some(0))]);
+        other.path = "/other".to_string();
+        let incoming = WorkspaceData {
+            projects: vec![same_path, other],
+            active_project_id: None,
+            feature_counter: Some(1),
+            generation: None,
+        };
+
+        let summary = merge_workspace_data(&mut data, incoming);
+        assert_eq!(
+            summary,
+            WorkspaceImportSummary {
+                projects_added: 1,
+                features_added: 2,
+            }
+        );
+        assert_eq!(data.projects.len(), 2);
+        assert_eq!(ids(&data.projects[0]), vec!["a", "b"]);
+        let b = &data.projects[0].features[1];
+        assert_eq!((b.seq, b.order), (2, Some(1)));
+        assert!(b.panels[0].sessions[0].pty_id.is_empty());
+        // Colliding project id is regenerated
+        assert_ne!(data.projects[1].id, "p");
+        assert_eq!(data.projects[1].active_feature_id.as_deref(), Some("c"));
+        assert_eq!(data.feature_counter, Some(3));
+    }
+
+    #[test]
+    fn test_workspace_file_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("export").join("board.json");
+        let data = WorkspaceData {
+            projects: vec![kanban_project(&[("a", FeatureStatus::Pending, None)])],
+            active_project_id: Some("p".to_string()),
+            feature_counter: None,
+            generation: None,
+        };
+        save_workspace_to(&path, &data).expect("save");
+
+        let loaded = load_workspace_from(&path).expect("load");
+        assert_eq!(loaded.projects[0].features[0].order, Some(0));
+        assert!(load_workspace_from(&temp.path().join("missing.json"))
+            .expect("missing is empty")
+            .projects
+            .is_empty());
+    }
+
+    // ========================================================================
+    // Trash Tests
+    // ========================================================================
+
+    #[test]
+    fn test_restore_trashed_feature_reopens_its_slot() {
+        let mut project = kanban_project(&[
+            ("a", FeatureStatus::Pending, Some(0)),
+            ("b", FeatureStatus::Pending, Some(1)),
+            ("c", FeatureStatus::Pending, Some(2)),
+        ]);
+        let feature = project.features.remove(1);
+        project.features[1].order = Some(2);
+        let mut data = WorkspaceData {
+            projects: vec![project],
+            ..Default::default()
+        };
+
+        let entity = TrashedEntity::Feature {
+            project_id: "p".to_string(),
+            index: 1,
+            feature: Box::new(feature),
+        };
+        restore_entity(&mut data, entity.clone()).expect("restore feature");
+        assert_eq!(ids(&data.projects[0]), vec!["a", "b", "c"]);
+        let orders: Vec<_> = data.projects[0].features.iter().map(|f| f.order).collect();
+        assert_eq!(orders, vec![Some(0), Some(1), Some(3)]);
+
+        // Restoring twice, or into a project that is gone, fails
+        assert!(restore_entity(&mut data, entity.clone()).is_err());
+        data.projects.clear();
+        assert!(restore_entity(&mut data, entity).is_err());
+    }
+
+    #[test]
+    fn test_restore_trashed_project() {
+        let mut data = WorkspaceData::default();
+        let entity = TrashedEntity::Project {
+            index: 3,
+            project: Box::new(kanban_project(&[])),
+        };
+        restore_entity(&mut data, entity.clone()).expect("restore project");
+        assert_eq!(data.projects.len(), 1);
+        assert_eq!(data.active_project_id.as_deref(), Some("p"));
+        assert!(restore_entity(&mut data, entity).is_err());
+    }
+
+    #[test]
+    fn test_trash_roundtrip() {
+        let temp = tempfile::TempDir::new().expect("create temp dir");
+        let path = temp.path().join("workspace-trash.json");
+        assert!(load_trash(&path).is_empty());
+
+        let entry = TrashEntry {
+            workspace: DEFAULT_WORKSPACE.to_string(),
+            deleted_at: 42,
+            entity: TrashedEntity::Project {
+                index: 0,
+                project: Box::new(kanban_project(&[("a", FeatureStatus::Pending, Some(0))])),
+            },
+        };
+        save_trash(&path, &[entry]).expect("save trash");
+
+        let loaded = load_trash(&path);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].deleted_at, 42);
+        let json = serde_json::to_value(&loaded[0]).expect("serialize entry");
+        assert_eq!(json["entity"]["kind"], "project");
+        assert_eq!(json["entity"]["project"]["features"][0]["id"], "a");
+    }
 }
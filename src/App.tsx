@@ -23,7 +23,7 @@ import {
   workspaceDataAtom,
 } from "./store";
 import { AppConfigContext, type AppConfig } from "./context";
-import type { TemplatesCatalog } from "./types";
+import type { DeepLink, TemplatesCatalog } from "./types";
 
 // ============================================================================
 // App Component
@@ -76,6 +76,49 @@ function App() {
     };
   }, []);
 
+  // Tray events
+  useEffect(() => {
+    const unlistenWorkspace = listen("menu-open-workspace", () => navigate({ type: "workspace" }));
+    const unlistenPaused = listen<boolean>("watchers-paused", (event) =>
+      setDistillWatchEnabled(!event.payload)
+    );
+    return () => {
+      unlistenWorkspace.then((fn) => fn());
+      unlistenPaused.then((fn) => fn());
+    };
+  }, [navigate]);
+
+  // Deep links (lovcode://); the link the app was launched with waits in the backend
+  useEffect(() => {
+    const openPendingLink = () => {
+      invoke<DeepLink | null>("take_pending_deep_link")
+        .then((link) => {
+          if (link?.type === "session") {
+            navigate({
+              type: "chat-messages",
+              projectId: link.project_id,
+              projectPath: link.project_path,
+              sessionId: link.session_id,
+              summary: link.summary,
+            });
+          } else if (link?.type === "feature") {
+            navigate({
+              type: "workspace",
+              projectId: link.project_id,
+              featureId: link.feature_id,
+              mode: "features",
+            });
+          }
+        })
+        .catch(logNonCriticalError("打开链接"));
+    };
+    openPendingLink();
+    const unlisten = listen("deep-link", openPendingLink);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+
   // Keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
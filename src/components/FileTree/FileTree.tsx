@@ -12,6 +12,7 @@ import {
   ContextMenuSeparator,
   ContextMenuTrigger,
 } from "../ui/context-menu";
+import { errorMessage } from "../../lib/errorHandler";
 
 interface DirEntry {
   name: string;
@@ -198,7 +199,7 @@ export function FileTree({ rootPath, onFileClick, selectedFile }: FileTreeProps)
         }
       } catch (err) {
         if (!cancelled) {
-          setError(String(err));
+          setError(errorMessage(err));
         }
       } finally {
         if (!cancelled) {
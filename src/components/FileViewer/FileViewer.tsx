@@ -6,6 +6,7 @@ import { Cross2Icon, ExternalLinkIcon, CodeIcon, ReaderIcon, ColumnsIcon } from
 import Editor from "@monaco-editor/react";
 import { MarkdownRenderer } from "../MarkdownRenderer";
 import { isImageFile } from "@/lib/utils";
+import { errorMessage } from "../../lib/errorHandler";
 
 const EDITOR_OPTIONS = {
   readOnly: true,
@@ -119,7 +120,7 @@ export function FileViewer({ filePath, onClose }: FileViewerProps) {
         }
       } catch (err) {
         if (!cancelled) {
-          setError(String(err));
+          setError(errorMessage(err));
         }
       } finally {
         if (!cancelled) {
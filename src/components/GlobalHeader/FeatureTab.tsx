@@ -29,6 +29,7 @@ import {
 } from '@/components/ui/dialog';
 import { workspaceDataAtom } from '@/store';
 import { invoke } from '@tauri-apps/api/core';
+import { reloadWorkspaceData, saveWorkspaceData } from '@/lib/workspace';
 import type { Feature, FeatureStatus, WorkspaceData } from '@/views/Workspace/types';
 
 interface FeatureTabProps {
@@ -72,7 +73,7 @@ export const FeatureTab = memo(function FeatureTab({
 
   const saveWorkspace = async (data: WorkspaceData) => {
     setWorkspace(data);
-    await invoke('workspace_save', { data });
+    await saveWorkspaceData(data);
   };
 
   const handleRename = async () => {
@@ -84,17 +85,7 @@ export const FeatureTab = memo(function FeatureTab({
     }
 
     await invoke('workspace_rename_feature', { featureId: feature.id, name: trimmed });
-
-    const newProjects = workspace.projects.map((p) =>
-      p.id === projectId
-        ? {
-            ...p,
-            features: p.features.map((f) => (f.id === feature.id ? { ...f, name: trimmed } : f)),
-          }
-        : p
-    );
-
-    await saveWorkspace({ ...workspace, projects: newProjects });
+    await reloadWorkspaceData();
     setIsRenaming(false);
   };
 
@@ -155,11 +146,14 @@ export const FeatureTab = memo(function FeatureTab({
     const trimmedName = detailForm.name.trim();
     if (!trimmedName) return;
 
+    // Renaming writes the file, so build the rest of the update on what it wrote
+    let current = workspace;
     if (trimmedName !== feature.name) {
       await invoke('workspace_rename_feature', { featureId: feature.id, name: trimmedName });
+      current = await reloadWorkspaceData();
     }
 
-    const newProjects = workspace.projects.map((p) =>
+    const newProjects = current.projects.map((p) =>
       p.id === projectId
         ? {
             ...p,
@@ -178,7 +172,7 @@ export const FeatureTab = memo(function FeatureTab({
         : p
     );
 
-    await saveWorkspace({ ...workspace, projects: newProjects });
+    await saveWorkspace({ ...current, projects: newProjects });
     setIsDetailOpen(false);
   };
 
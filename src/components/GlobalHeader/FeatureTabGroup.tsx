@@ -4,7 +4,7 @@ import { SortableContext, horizontalListSortingStrategy, useSortable } from "@dn
 import { CSS } from "@dnd-kit/utilities";
 import { workspaceDataAtom, collapsedProjectGroupsAtom } from "@/store";
 import { useNavigate, useFeatureCreation } from "@/hooks";
-import { invoke } from "@tauri-apps/api/core";
+import { saveWorkspaceData } from "@/lib/workspace";
 import {
   ContextMenu,
   ContextMenuContent,
@@ -81,7 +81,7 @@ export function FeatureTabGroup({
       active_project_id: project.id,
     };
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const handleOpenDashboard = async () => {
@@ -98,7 +98,7 @@ export function FeatureTabGroup({
       active_project_id: project.id,
     };
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const handleArchiveProject = async () => {
@@ -117,7 +117,7 @@ export function FeatureTabGroup({
           : workspace.active_project_id,
     };
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const handleUnarchiveFeature = async (featureId: string) => {
@@ -142,7 +142,7 @@ export function FeatureTabGroup({
       active_project_id: project.id,
     };
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const handleSelectFeature = async (featureId: string) => {
@@ -163,7 +163,7 @@ export function FeatureTabGroup({
     };
 
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const projectDisplayName = project.name
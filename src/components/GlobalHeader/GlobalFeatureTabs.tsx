@@ -17,6 +17,7 @@ import { open } from "@tauri-apps/plugin-dialog";
 import { workspaceDataAtom, collapsedProjectGroupsAtom } from "@/store";
 import { useNavigate } from "@/hooks";
 import { invoke } from "@tauri-apps/api/core";
+import { reloadWorkspaceData, saveWorkspaceData } from "@/lib/workspace";
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -64,7 +65,7 @@ export function GlobalFeatureTabs() {
       active_project_id: id,
     };
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const handleAddProject = async () => {
@@ -82,13 +83,10 @@ export function GlobalFeatureTabs() {
 
         navigate({ type: "workspace", projectId: project.id, mode: "dashboard" });
 
-        const newWorkspace: WorkspaceData = {
-          ...workspace,
-          projects: [...workspace.projects, project],
-          active_project_id: project.id,
-        };
+        const current = await reloadWorkspaceData();
+        const newWorkspace: WorkspaceData = { ...current, active_project_id: project.id };
         setWorkspace(newWorkspace);
-        await invoke("workspace_save", { data: newWorkspace });
+        await saveWorkspaceData(newWorkspace);
       }
     } catch (err) {
       console.error("Failed to add project:", err);
@@ -143,7 +141,7 @@ export function GlobalFeatureTabs() {
 
       const newWorkspace: WorkspaceData = { ...workspace, projects };
       setWorkspace(newWorkspace);
-      await invoke("workspace_save", { data: newWorkspace });
+      await saveWorkspaceData(newWorkspace);
       return;
     }
 
@@ -186,7 +184,7 @@ export function GlobalFeatureTabs() {
 
       const newWorkspace: WorkspaceData = { ...workspace, projects: newProjects };
       setWorkspace(newWorkspace);
-      await invoke("workspace_save", { data: newWorkspace });
+      await saveWorkspaceData(newWorkspace);
     }
   };
 
@@ -12,8 +12,8 @@ import {
   verticalTabsSidebarWidthAtom,
 } from "@/store";
 import { useNavigate, useInvokeQuery } from "@/hooks";
-import { invoke } from "@tauri-apps/api/core";
-import type { Session } from "@/types";
+import { saveWorkspaceData } from "@/lib/workspace";
+import type { Session, SessionsResponse } from "@/types";
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -120,10 +120,11 @@ function ProjectSessionsGroup({
   const navigate = useNavigate();
 
   // Fetch all CC sessions, then filter by project path
-  const { data: allSessions = [], isLoading } = useInvokeQuery<Session[]>(
+  const { data: sessionsPage, isLoading } = useInvokeQuery<SessionsResponse>(
     ["sessions"],
     "list_all_sessions"
   );
+  const allSessions = useMemo(() => sessionsPage?.items ?? [], [sessionsPage]);
 
   // Filter to sessions matching this project's path
   const filteredSessions = useMemo(() => {
@@ -154,7 +155,7 @@ function ProjectSessionsGroup({
       ),
     };
     setWorkspace(newWorkspace);
-    await invoke("workspace_save", { data: newWorkspace });
+    await saveWorkspaceData(newWorkspace);
   };
 
   const handleResumeSession = async (session: Session) => {
@@ -264,7 +265,7 @@ function ProjectSessionsGroup({
 
     // Save after state update - use the captured workspace from functional update
     if (savedWorkspace) {
-      await invoke("workspace_save", { data: savedWorkspace });
+      await saveWorkspaceData(savedWorkspace);
       navigate({ type: "workspace", projectId: project.id, mode: "features" });
     }
   };
@@ -367,7 +368,7 @@ function ProjectSessionsGroup({
     });
 
     if (savedWorkspace) {
-      await invoke("workspace_save", { data: savedWorkspace });
+      await saveWorkspaceData(savedWorkspace);
       navigate({ type: "workspace", projectId: project.id, mode: "features" });
     }
   };
@@ -3,7 +3,7 @@ import { Allotment } from "allotment";
 import "allotment/dist/style.css";
 import { ChevronLeftIcon, ChevronRightIcon, DrawingPinFilledIcon } from "@radix-ui/react-icons";
 import { SessionPanel } from "./SessionPanel";
-import type { LayoutNode } from "../../views/Workspace/types";
+import type { LayoutNode, LayoutPath } from "../../views/Workspace/types";
 import { NewTerminalSplitButton } from "../ui/new-terminal-button";
 
 export interface SessionState {
@@ -11,6 +11,7 @@ export interface SessionState {
   ptyId: string;
   title: string;
   command?: string;
+  sshHost?: string;
 }
 
 export interface PanelState {
@@ -19,6 +20,7 @@ export interface PanelState {
   activeSessionId: string;
   isShared: boolean;
   cwd: string;
+  env?: Record<string, string>;
 }
 
 export interface PanelGridProps {
@@ -29,6 +31,8 @@ export interface PanelGridProps {
   onPanelClose: (id: string) => void;
   /** Split a panel in the given direction (tmux-style) */
   onPanelSplit: (panelId: string, direction: "horizontal" | "vertical") => void;
+  /** A split was resized; ratio is the share of its first pane */
+  onLayoutResize?: (path: LayoutPath, ratio: number) => void;
   onPanelToggleShared: (id: string) => void;
   onPanelReload: (id: string) => void;
   onSessionAdd: (panelId: string) => void;
@@ -44,11 +48,13 @@ export interface PanelGridProps {
 /** Recursively render layout tree */
 function LayoutRenderer({
   node,
+  path,
   panels,
   activePanelId,
   onPanelFocus,
   onPanelClose,
   onPanelSplit,
+  onLayoutResize,
   onPanelToggleShared,
   onPanelReload,
   onSessionAdd,
@@ -57,11 +63,13 @@ function LayoutRenderer({
   onSessionTitleChange,
 }: {
   node: LayoutNode;
+  path: LayoutPath;
   panels: PanelState[];
   activePanelId?: string;
   onPanelFocus?: (id: string) => void;
   onPanelClose: (id: string) => void;
   onPanelSplit: (panelId: string, direction: "horizontal" | "vertical") => void;
+  onLayoutResize?: (path: LayoutPath, ratio: number) => void;
   onPanelToggleShared: (id: string) => void;
   onPanelReload: (id: string) => void;
   onSessionAdd: (panelId: string) => void;
@@ -96,17 +104,28 @@ function LayoutRenderer({
     );
   }
 
-  // Split node - render children in Allotment
+  // Split node - render children in Allotment, sized by the saved ratio
+  const ratio = node.ratio ?? 0.5;
   return (
-    <Allotment vertical={node.direction === "vertical"} className="h-full">
-      <Allotment.Pane minSize={100}>
+    <Allotment
+      vertical={node.direction === "vertical"}
+      className="h-full"
+      defaultSizes={[ratio * 1000, (1 - ratio) * 1000]}
+      onDragEnd={(sizes) => {
+        const total = sizes[0] + sizes[1];
+        if (total > 0) onLayoutResize?.(path, sizes[0] / total);
+      }}
+    >
+      <Allotment.Pane minSize={node.min_first ?? 100}>
         <LayoutRenderer
           node={node.first}
+          path={[...path, 0]}
           panels={panels}
           activePanelId={activePanelId}
           onPanelFocus={onPanelFocus}
           onPanelClose={onPanelClose}
           onPanelSplit={onPanelSplit}
+          onLayoutResize={onLayoutResize}
           onPanelToggleShared={onPanelToggleShared}
           onPanelReload={onPanelReload}
           onSessionAdd={onSessionAdd}
@@ -115,14 +134,16 @@ function LayoutRenderer({
           onSessionTitleChange={onSessionTitleChange}
         />
       </Allotment.Pane>
-      <Allotment.Pane minSize={100}>
+      <Allotment.Pane minSize={node.min_second ?? 100}>
         <LayoutRenderer
           node={node.second}
+          path={[...path, 1]}
           panels={panels}
           activePanelId={activePanelId}
           onPanelFocus={onPanelFocus}
           onPanelClose={onPanelClose}
           onPanelSplit={onPanelSplit}
+          onLayoutResize={onLayoutResize}
           onPanelToggleShared={onPanelToggleShared}
           onPanelReload={onPanelReload}
           onSessionAdd={onSessionAdd}
@@ -142,6 +163,7 @@ export function PanelGrid({
   onPanelFocus: controlledOnPanelFocus,
   onPanelClose,
   onPanelSplit,
+  onLayoutResize,
   onPanelToggleShared,
   onPanelReload,
   onSessionAdd,
@@ -192,11 +214,13 @@ export function PanelGrid({
       <div className="h-full w-full">
         <LayoutRenderer
           node={layout}
+          path={[]}
           panels={panels}
           activePanelId={activePanelId}
           onPanelFocus={handlePanelFocus}
           onPanelClose={onPanelClose}
           onPanelSplit={onPanelSplit}
+          onLayoutResize={onLayoutResize}
           onPanelToggleShared={onPanelToggleShared}
           onPanelReload={onPanelReload}
           onSessionAdd={onSessionAdd}
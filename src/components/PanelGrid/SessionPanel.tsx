@@ -227,6 +227,8 @@ export const SessionPanel = memo(function SessionPanel({
                 ptyId={session.ptyId}
                 cwd={panel.cwd}
                 command={session.command}
+                sshHost={session.sshHost}
+                env={panel.env}
                 autoFocus={session.id === panel.activeSessionId && isActive}
                 onTitleChange={handleTitleChange(session.id)}
                 onExit={() => onSessionClose(session.id)}
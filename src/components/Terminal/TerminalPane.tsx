@@ -9,7 +9,7 @@ import {
   ptyReadySessions,
   ptyInitLocks,
 } from "./terminalPool";
-import { handlePtyResizeError } from "../../lib/errorHandler";
+import { handlePtyResizeError, errorMessage } from "../../lib/errorHandler";
 
 interface PtyDataEvent {
   id: string;
@@ -18,6 +18,9 @@ interface PtyDataEvent {
 
 interface PtyExitEvent {
   id: string;
+  /** null if the process could not be waited on */
+  exit_code: number | null;
+  signal: string | null;
 }
 
 export interface TerminalPaneProps {
@@ -27,6 +30,10 @@ export interface TerminalPaneProps {
   cwd: string;
   /** Optional command to run instead of shell */
   command?: string;
+  /** Extra environment variables for the shell */
+  env?: Record<string, string>;
+  /** Run on this SSH host instead of locally; starts in the remote login directory */
+  sshHost?: string;
   /** Auto focus terminal when ready */
   autoFocus?: boolean;
   /** Callback when terminal is ready */
@@ -43,6 +50,8 @@ export function TerminalPane({
   ptyId,
   cwd,
   command,
+  env,
+  sshHost,
   autoFocus = false,
   onReady,
   onExit,
@@ -52,6 +61,8 @@ export function TerminalPane({
   const containerRef = useRef<HTMLDivElement>(null);
   const cwdRef = useRef(cwd);
   const commandRef = useRef(command);
+  const envRef = useRef(env);
+  const sshHostRef = useRef(sshHost);
   const autoFocusRef = useRef(autoFocus);
   const onReadyRef = useRef(onReady);
   const onExitRef = useRef(onExit);
@@ -59,6 +70,8 @@ export function TerminalPane({
 
   useEffect(() => { cwdRef.current = cwd; }, [cwd]);
   useEffect(() => { commandRef.current = command; }, [command]);
+  useEffect(() => { envRef.current = env; }, [env]);
+  useEffect(() => { sshHostRef.current = sshHost; }, [sshHost]);
   useEffect(() => { autoFocusRef.current = autoFocus; }, [autoFocus]);
   useEffect(() => { onReadyRef.current = onReady; }, [onReady]);
   useEffect(() => { onExitRef.current = onExit; }, [onExit]);
@@ -140,7 +153,26 @@ export function TerminalPane({
         if (!mountState.isMounted) return;
 
         if (!exists) {
-          await invoke("pty_create", { id: sessionId, cwd: cwdRef.current, command: commandRef.current });
+          // After an app restart, bring the session back with its recorded cwd/shell/command;
+          // sessions that never ran have nothing recorded and are created fresh
+          const respawned = await invoke("pty_respawn", { id: sessionId }).then(
+            () => true,
+            () => false
+          );
+          if (!respawned && sshHostRef.current) {
+            await invoke("pty_create_ssh", {
+              id: sessionId,
+              hostAlias: sshHostRef.current,
+              command: commandRef.current,
+            });
+          } else if (!respawned) {
+            await invoke("pty_create", {
+              id: sessionId,
+              cwd: cwdRef.current,
+              command: commandRef.current,
+              env: envRef.current,
+            });
+          }
         }
 
         // Replay scrollback buffer (works for both page refresh and app restart)
@@ -178,7 +210,7 @@ export function TerminalPane({
       } catch (err) {
         if (mountState.isMounted) {
           console.error("Failed to initialize PTY:", err);
-          term.writeln(`\r\n\x1b[31mFailed to create terminal: ${err}\x1b[0m`);
+          term.writeln(`\r\n\x1b[31mFailed to create terminal: ${errorMessage(err)}\x1b[0m`);
         }
       } finally {
         ptyInitLocks.delete(sessionId);
@@ -334,6 +366,15 @@ export function TerminalPane({
         // User can manually close or reload. Only auto-close shell sessions.
         if (!commandRef.current) {
           onExitRef.current?.();
+        } else {
+          // Write any batched output first so the notice lands after it
+          if (writeFrameId !== null) {
+            cancelAnimationFrame(writeFrameId);
+            flushPendingData();
+          }
+          const { exit_code, signal } = event.payload;
+          const reason = signal ? `was killed by ${signal}` : `exited with code ${exit_code ?? "?"}`;
+          term.write(`\r\n\x1b[2m[process ${reason}]\x1b[0m\r\n`);
         }
       }
     });
@@ -12,6 +12,7 @@ import {
 } from "../ui/dropdown-menu";
 import { Button } from "../ui/button";
 import { differenceInMinutes, differenceInHours, differenceInDays, differenceInWeeks, differenceInMonths } from "date-fns";
+import { errorMessage } from "../../lib/errorHandler";
 
 function formatRelativeTime(date: Date): string {
   const now = new Date();
@@ -443,7 +444,7 @@ export function useAsyncData<T>(
     setError(null);
     fetcher()
       .then(setData)
-      .catch((e) => setError(String(e)))
+      .catch((e) => setError(errorMessage(e)))
       .finally(() => setLoading(false));
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, deps);
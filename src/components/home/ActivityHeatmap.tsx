@@ -9,6 +9,13 @@ interface ActivityHeatmapProps {
   detailed: Record<string, number>;
 }
 
+/** YYYY-MM-DD in local time, matching the backend's local-offset buckets */
+function toLocalDateKey(d: Date): string {
+  const month = (d.getMonth() + 1).toString().padStart(2, "0");
+  const day = d.getDate().toString().padStart(2, "0");
+  return `${d.getFullYear()}-${month}-${day}`;
+}
+
 export function ActivityHeatmap({ daily, detailed }: ActivityHeatmapProps) {
   const [mode, setMode] = useAtom(activityViewModeAtom);
   const scrollRef = useRef<HTMLDivElement>(null);
@@ -26,7 +33,7 @@ export function ActivityHeatmap({ daily, detailed }: ActivityHeatmapProps) {
     for (let i = daysToShow - 1; i >= 0; i--) {
       const d = new Date(today);
       d.setDate(d.getDate() - i);
-      const dateStr = d.toISOString().split("T")[0];
+      const dateStr = toLocalDateKey(d);
       cells.push({
         date: dateStr,
         count: dailyMap.get(dateStr) || 0,
@@ -55,7 +62,7 @@ export function ActivityHeatmap({ daily, detailed }: ActivityHeatmapProps) {
     weeks.forEach((week, weekIdx) => {
       const firstValidCell = week.find((c) => c.date);
       if (firstValidCell) {
-        const month = new Date(firstValidCell.date).getMonth();
+        const month = Number(firstValidCell.date.slice(5, 7)) - 1;
         if (month !== lastMonth) {
           const monthNames = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
           monthLabels.push({ month: monthNames[month], weekIdx });
@@ -79,7 +86,7 @@ export function ActivityHeatmap({ daily, detailed }: ActivityHeatmapProps) {
     for (let i = daysToShow - 1; i >= 0; i--) {
       const d = new Date(today);
       d.setDate(d.getDate() - i);
-      const dateStr = d.toISOString().split("T")[0];
+      const dateStr = toLocalDateKey(d);
       // 6 periods: 0-3, 4-7, 8-11, 12-15, 16-19, 20-23
       const periods: number[] = [0, 0, 0, 0, 0, 0];
       for (let h = 0; h < 24; h++) {
@@ -94,7 +101,7 @@ export function ActivityHeatmap({ daily, detailed }: ActivityHeatmapProps) {
     const monthLabels: { month: string; dayIdx: number }[] = [];
     let lastMonth = -1;
     days.forEach((day, dayIdx) => {
-      const month = new Date(day.date).getMonth();
+      const month = Number(day.date.slice(5, 7)) - 1;
       if (month !== lastMonth) {
         const monthNames = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
         monthLabels.push({ month: monthNames[month], dayIdx });
@@ -1,6 +1,7 @@
 import { useState } from "react";
 import { useAtom } from "jotai";
 import { invoke } from "@tauri-apps/api/core";
+import { reloadWorkspaceData, saveWorkspaceData } from "@/lib/workspace";
 import { workspaceDataAtom } from "@/store";
 import { useNavigate } from "@/hooks";
 import type { WorkspaceProject, Feature, WorkspaceData } from "@/views/Workspace/types";
@@ -30,11 +31,12 @@ export function useFeatureCreation(project: WorkspaceProject) {
 
       navigate({ type: "workspace", projectId: project.id, featureId: feature.id, mode: "features" });
 
-      const newProjects = workspace.projects.map((p) =>
+      // The feature and counter are already saved; only the selection is left to store
+      const current = await reloadWorkspaceData();
+      const newProjects = current.projects.map((p) =>
         p.id === project.id
           ? {
               ...p,
-              features: [...p.features, feature],
               active_feature_id: feature.id,
               view_mode: "features" as const,
             }
@@ -42,14 +44,13 @@ export function useFeatureCreation(project: WorkspaceProject) {
       );
 
       const newWorkspace: WorkspaceData = {
-        ...workspace,
+        ...current,
         projects: newProjects,
         active_project_id: project.id,
-        feature_counter: feature.seq,
       };
 
       setWorkspace(newWorkspace);
-      await invoke("workspace_save", { data: newWorkspace });
+      await saveWorkspaceData(newWorkspace);
     } catch (err) {
       console.error("Failed to create feature:", err);
     }
@@ -6,6 +6,9 @@
 
 import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
 import {
+  errorCode,
+  errorMessage,
+  isAppError,
   handleInvokeError,
   logNonCriticalError,
   handleClipboardError,
@@ -57,6 +60,13 @@ describe('errorHandler utilities', () => {
       expect(console.warn).toHaveBeenCalledWith('[测试] 失败:', '字符串错误');
     });
 
+    it('logs the message of command errors', () => {
+      const handler = handleInvokeError({ operation: '读取文件' });
+      handler({ code: 'not_found', message: 'File does not exist: /a', context: '/a' });
+
+      expect(console.warn).toHaveBeenCalledWith('[读取文件] 失败:', 'File does not exist: /a');
+    });
+
     it('handles unknown error types', () => {
       const handler = handleInvokeError({ operation: '测试' });
       handler({ custom: 'error object' });
@@ -68,6 +78,23 @@ describe('errorHandler utilities', () => {
     });
   });
 
+  describe('errorMessage / errorCode', () => {
+    const appError = { code: 'permission_denied', message: 'Invalid path: /etc', context: '/etc' };
+
+    it('reads command errors', () => {
+      expect(isAppError(appError)).toBe(true);
+      expect(errorMessage(appError)).toBe('Invalid path: /etc');
+      expect(errorCode(appError)).toBe('permission_denied');
+    });
+
+    it('falls back for Error and other values', () => {
+      expect(isAppError(new Error('boom'))).toBe(false);
+      expect(errorMessage(new Error('boom'))).toBe('boom');
+      expect(errorMessage('plain')).toBe('plain');
+      expect(errorCode('plain')).toBeUndefined();
+    });
+  });
+
   describe('logNonCriticalError', () => {
     it('logs with info severity and silent mode', () => {
       const handler = logNonCriticalError('可选操作');
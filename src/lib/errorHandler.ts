@@ -1,10 +1,12 @@
 /**
- * [INPUT]: Tauri invoke API errors
- * [OUTPUT]: handleInvokeError, logError utilities
+ * [INPUT]: Tauri invoke API errors (AppError: { code, message, context })
+ * [OUTPUT]: errorMessage, errorCode, isAppError, handleInvokeError, logError utilities
  * [POS]: 统一错误处理工具
  * [PROTOCOL]: 变更时更新此头部，然后检查 CLAUDE.md
  */
 
+import type { AppError, AppErrorCode } from "../types";
+
 type ErrorSeverity = "info" | "warning" | "error";
 
 interface ErrorContext {
@@ -16,6 +18,39 @@ interface ErrorContext {
   silent?: boolean;
 }
 
+/**
+ * 判断是否为后端命令返回的结构化错误
+ */
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as AppError).code === "string" &&
+    typeof (error as AppError).message === "string"
+  );
+}
+
+/**
+ * 取错误的可读消息：AppError、Error 或任意值
+ *
+ * 用法:
+ * ```ts
+ * invoke("read_file", { path }).catch((e) => setError(errorMessage(e)));
+ * ```
+ */
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
+/**
+ * 取后端错误分类，非 AppError 时为 undefined
+ */
+export function errorCode(error: unknown): AppErrorCode | undefined {
+  return isAppError(error) ? error.code : undefined;
+}
+
 /**
  * 记录 Tauri invoke 调用错误
  *
@@ -29,12 +64,12 @@ interface ErrorContext {
 export function handleInvokeError(context: ErrorContext): (error: unknown) => void {
   return (error: unknown) => {
     const { operation, severity = "warning", silent = false } = context;
-    const errorMessage = error instanceof Error ? error.message : String(error);
+    const message = errorMessage(error);
 
     // 开发模式下输出到控制台
     if (import.meta.env.DEV) {
       const logFn = severity === "error" ? console.error : console.warn;
-      logFn(`[${operation}] 失败:`, errorMessage);
+      logFn(`[${operation}] 失败:`, message);
     }
 
     // 未来可接入:
@@ -44,7 +79,7 @@ export function handleInvokeError(context: ErrorContext): (error: unknown) => vo
 
     if (!silent) {
       // 预留: 显示 toast 或其他用户反馈
-      // toast.error(`${operation}失败: ${errorMessage}`);
+      // toast.error(`${operation}失败: ${message}`);
     }
   };
 }
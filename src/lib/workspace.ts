@@ -0,0 +1,59 @@
+/**
+ * [INPUT]: Tauri invoke (workspace_save / workspace_load), workspaceDataAtom
+ * [OUTPUT]: saveWorkspaceData, reloadWorkspaceData
+ * [POS]: Workspace 全量保存：带 generation 冲突检测
+ * [PROTOCOL]: 变更时更新此头部
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { getDefaultStore } from "jotai";
+import { workspaceDataAtom } from "@/store";
+import type { WorkspaceData } from "@/views/Workspace/types";
+import { errorCode } from "./errorHandler";
+
+// Generations replaced by this window's own saves (sent -> returned), so data built
+// before an earlier save finished is still recognised as current
+const replaced = new Map<number, number>();
+let pending: Promise<void> = Promise.resolve();
+
+function latestGeneration(generation: number | undefined): number | undefined {
+  let current = generation;
+  while (current !== undefined && replaced.has(current)) current = replaced.get(current);
+  return current;
+}
+
+/**
+ * Load the workspace from disk into workspaceDataAtom
+ */
+export async function reloadWorkspaceData(): Promise<WorkspaceData> {
+  const data = await invoke<WorkspaceData>("workspace_load");
+  getDefaultStore().set(workspaceDataAtom, data);
+  return data;
+}
+
+/**
+ * Save the whole workspace. Saves run one at a time; if the file was changed since
+ * `data` was loaded (another window, a granular workspace_* command), the save is
+ * rejected, the atom is reloaded from disk and the conflict error is rethrown.
+ */
+export function saveWorkspaceData(data: WorkspaceData): Promise<void> {
+  const run = async () => {
+    const generation = latestGeneration(data.generation);
+    try {
+      const next = await invoke<number>("workspace_save", { data: { ...data, generation } });
+      if (generation !== undefined) replaced.set(generation, next);
+
+      const store = getDefaultStore();
+      const current = store.get(workspaceDataAtom);
+      if (current && latestGeneration(current.generation) === next) {
+        store.set(workspaceDataAtom, { ...current, generation: next });
+      }
+    } catch (err) {
+      if (errorCode(err) === "conflict") await reloadWorkspaceData();
+      throw err;
+    }
+  };
+  const result = pending.then(run, run);
+  pending = result.catch(() => undefined);
+  return result;
+}
@@ -54,6 +54,60 @@ export interface Message {
   is_meta: boolean;
   is_tool: boolean;
   line_number: number;
+  thinking?: string;
+}
+
+export interface ChainedSession {
+  session_id: string;
+  summary: string | null;
+  message_count: number;
+  started_at: string | null;
+  ended_at: string | null;
+  last_modified: number;
+  linked_by: "summary" | "parent" | "copied" | null;
+  compacted: boolean;
+}
+
+export interface SessionChain {
+  project_id: string;
+  project_path: string;
+  sessions: ChainedSession[];
+}
+
+export interface SearchFilters {
+  project_id?: string | null;
+  limit?: number | null;
+}
+
+export interface SavedSearch {
+  id: string;
+  name: string;
+  query: string;
+  filters: SearchFilters;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface SearchHistoryEntry {
+  query: string;
+  filters: SearchFilters;
+  searched_at: number;
+  result_count: number;
+}
+
+export interface ProjectDocCount {
+  project_id: string;
+  doc_count: number;
+}
+
+export interface SearchIndexStats {
+  built: boolean;
+  doc_count: number;
+  deleted_doc_count: number;
+  segment_count: number;
+  size_bytes: number;
+  last_built_at: number | null;
+  projects: ProjectDocCount[];
 }
 
 export interface ChatMessage {
@@ -79,6 +133,109 @@ export interface SearchResult {
   score: number;
 }
 
+export interface MalformedLine {
+  line: number;
+  preview: string;
+  error: string;
+}
+
+export interface HistoryAnalysis {
+  path: string;
+  exists: boolean;
+  size_bytes: number;
+  total_lines: number;
+  valid_lines: number;
+  malformed_lines: number;
+  blank_lines: number;
+  duplicate_lines: number;
+  undated_lines: number;
+  oldest: number | null;
+  newest: number | null;
+  malformed_samples: MalformedLine[];
+}
+
+export interface HistoryCompaction {
+  backup_path: string;
+  lines_before: number;
+  lines_after: number;
+  removed_malformed: number;
+  removed_duplicates: number;
+  removed_expired: number;
+  size_before: number;
+  size_after: number;
+}
+
+export interface CodeBlock {
+  message_uuid: string;
+  line_number: number;
+  timestamp: string;
+  index: number;
+  language: string | null;
+  code: string;
+  prompt: string | null;
+  context: string | null;
+}
+
+export interface SavedPrompt {
+  id: string;
+  text: string;
+  tags: string[];
+  created_at: number;
+  last_used_at: number | null;
+  use_count: number;
+}
+
+export interface PromptSuggestion {
+  text: string;
+  reason: "frequent" | "long";
+  occurrences: number;
+  sessions: number;
+  last_used: string | null;
+}
+
+export interface CopySource {
+  project_id: string | null;
+  session_id: string | null;
+  message_uuid: string | null;
+}
+
+export interface CopyHistoryItem {
+  text: string;
+  copied_at: number;
+  source: CopySource | null;
+  truncated: boolean;
+}
+
+export interface Bookmark {
+  project_id: string;
+  session_id: string;
+  uuid: string;
+  project_path: string | null;
+  note: string | null;
+  role: string;
+  content: string;
+  timestamp: string;
+  created_at: number;
+}
+
+export interface BookmarkFilter {
+  project_id?: string;
+  session_id?: string;
+  query?: string;
+  limit?: number;
+}
+
+export interface TrashedSession extends Session {
+  trash_id: string;
+  deleted_at: number;
+  original_path: string;
+}
+
+export interface SessionsResponse {
+  items: Session[];
+  total: number;
+}
+
 export interface ChatsResponse {
   items: ChatMessage[];
   total: number;
@@ -97,6 +254,9 @@ export interface LocalCommand {
   changelog: string | null;
   aliases: string[];
   frontmatter: string | null;
+  scope: 'user' | 'project';
+  usage_count: number;
+  last_used: number | null;
 }
 
 export interface LocalAgent {
@@ -106,6 +266,7 @@ export interface LocalAgent {
   model: string | null;
   tools: string | null;
   content: string;
+  status: 'active' | 'archived';
 }
 
 export interface LocalSkill {
@@ -113,6 +274,7 @@ export interface LocalSkill {
   path: string;
   description: string | null;
   content: string;
+  status: 'active' | 'archived';
 }
 
 export interface DistillDocument {
@@ -123,6 +285,19 @@ export interface DistillDocument {
   session: string | null;
 }
 
+export interface DistillTag {
+  name: string;
+  count: number;
+}
+
+export interface DistillProgress {
+  session_id: string;
+  project_id: string;
+  stage: 'queued' | 'processing' | 'done' | 'skipped' | 'error';
+  file: string | null;
+  error: string | null;
+}
+
 export interface McpServer {
   name: string;
   description: string | null;
@@ -140,6 +315,46 @@ export interface ClaudeSettings {
   mcp_servers: McpServer[];
 }
 
+/** A problem found in ~/.claude/settings.json by validate_settings */
+export interface SettingsDiagnostic {
+  /** JSON Pointer to the offending value ("" = whole file) */
+  path: string;
+  rule: 'invalid-json' | 'unknown-key' | 'wrong-type' | 'invalid-value' | 'deprecated';
+  severity: 'error' | 'warning';
+  message: string;
+}
+
+export type ProjectSetupPiece = 'git' | 'claude_md' | 'settings' | 'hooks' | 'mcp_config';
+
+/** A formatter hook suggested for the project's stack */
+export interface RecommendedHook {
+  id: string;
+  event: string;
+  matcher: string;
+  command: string;
+  description: string;
+  installed: boolean;
+}
+
+/** Claude Code setup of a project, from analyze_project_setup */
+export interface ProjectSetup {
+  path: string;
+  git_repo: boolean;
+  claude_md: string | null;
+  settings: boolean;
+  mcp_config: boolean;
+  recommended_hooks: RecommendedHook[];
+  missing: ProjectSetupPiece[];
+}
+
+export type ProjectScaffoldOptions = Partial<Record<ProjectSetupPiece, boolean>>;
+
+export interface ProjectScaffoldResult {
+  created: string[];
+  updated: string[];
+  setup: ProjectSetup;
+}
+
 export interface ContextFile {
   name: string;
   path: string;
@@ -148,6 +363,53 @@ export interface ContextFile {
   last_modified: number;
 }
 
+export interface ContextNode {
+  name: string;
+  path: string;
+  scope: "global" | "project" | "local" | "import";
+  content: string;
+  last_modified: number;
+  depth: number;
+  imports: ContextNode[];
+  error: string | null;
+}
+
+export interface MemoryFile {
+  scope: "user" | "project" | "local";
+  path: string;
+  exists: boolean;
+  content: string;
+  last_modified: number;
+  backup_path: string | null;
+}
+
+/** Repository scan behind a generated CLAUDE.md */
+export interface RepoScan {
+  name: string;
+  stack: {
+    runtime: string;
+    package_manager: string | null;
+    orm: string | null;
+    frameworks: string[];
+  };
+  languages: { language: string; files: number }[];
+  manifests: string[];
+  commands: { label: string; command: string }[];
+  layout: { path: string; files: number }[];
+  truncated: boolean;
+}
+
+/** Draft from generate_claude_md; save `content` with update_memory */
+export interface ClaudeMdDraft {
+  path: string;
+  content: string;
+  existing: string | null;
+  diff: string;
+  generator: "model" | "template";
+  model_error: string | null;
+  scan: RepoScan;
+}
+
 export interface TemplateComponent {
   name: string;
   path: string;
@@ -250,7 +512,136 @@ export interface TopCommand {
   count: number;
 }
 
-export interface AnnualReport2025 {
+/** Half-open range [start, end) in Unix milliseconds (UTC) */
+export interface ReportPeriod {
+  start: number;
+  end: number;
+}
+
+export type ReportPeriodPreset = "year" | "quarter" | "month" | "week";
+
+export interface UsageShare {
+  name: string;
+  count: number;
+  /** 0.0 - 1.0 */
+  share: number;
+}
+
+export interface ModelUsage {
+  model: string;
+  family: "opus" | "sonnet" | "haiku" | "other";
+  count: number;
+  share: number;
+}
+
+/** Returned by get_session_metrics_summary; medians are null when there are no sessions */
+export interface SessionMetricsSummary {
+  period_start: number;
+  period_end: number;
+  sessions: number;
+  median_duration_ms: number | null;
+  median_user_turns: number | null;
+  median_assistant_turns: number | null;
+  median_response_ms: number | null;
+  median_longest_gap_ms: number | null;
+}
+
+export type ApiErrorType =
+  | "overloaded"
+  | "rate_limit"
+  | "server_error"
+  | "timeout"
+  | "connection"
+  | "auth"
+  | "invalid_request"
+  | "other";
+
+export interface ApiErrorStats {
+  period_start: number;
+  period_end: number;
+  total: number;
+  /** Errors / (errors + successful replies) */
+  error_rate: number;
+  /** `name` is an ApiErrorType */
+  by_type: UsageShare[];
+  /** Inferred from the session's model: "anthropic", "deepseek", "zhipu", ..., "unknown" */
+  by_provider: UsageShare[];
+  /** 24 counts by local hour of day */
+  by_hour: number[];
+  /** Local date (YYYY-MM-DD) -> count */
+  daily: Record<string, number>;
+}
+
+export interface TokenUsage {
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_read_input_tokens: number;
+}
+
+/** USD per million tokens; `pattern` matches a substring of the model id */
+export interface ModelPrice {
+  pattern: string;
+  input: number;
+  output: number;
+  cache_write: number;
+  cache_read: number;
+}
+
+export type CostGroupBy = "project" | "week" | "model";
+
+export interface CostGroup {
+  key: string;
+  label: string;
+  cost: number;
+  tokens: TokenUsage;
+}
+
+export interface CostReport {
+  period_start: number;
+  period_end: number;
+  group_by: CostGroupBy;
+  total_cost: number;
+  total_tokens: TokenUsage;
+  groups: CostGroup[];
+  unpriced_models: string[];
+}
+
+/** Returned by get_project_stats: all-time dashboard for one project */
+export interface ProjectStats {
+  project_id: string;
+  path: string;
+  total_sessions: number;
+  total_messages: number;
+  first_active: number | null;
+  last_active: number | null;
+  /** ISO week ("2026-W03") -> sessions started that week */
+  sessions_by_week: Record<string, number>;
+  top_commands: { name: string; count: number }[];
+  tokens: TokenUsage;
+  cost: number;
+  unpriced_models: string[];
+  /** 24 message counts by local hour of day */
+  active_hours: number[];
+  /** Messages per local day, oldest first, ending today */
+  last_30_days: number[];
+}
+
+export interface Achievement {
+  id: string;
+  title: string;
+  description: string;
+  /** Capped at threshold */
+  progress: number;
+  threshold: number;
+  unlocked: boolean;
+  /** Unix ms */
+  unlocked_at: number | null;
+}
+
+export interface PeriodReport {
+  period_start: number;
+  period_end: number;
   total_sessions: number;
   total_messages: number;
   total_commands: number;
@@ -321,6 +712,702 @@ export interface ClaudeCodeVersionInfo {
   current_version: string | null;
   available_versions: VersionWithDownloads[];
   autoupdater_disabled: boolean;
+  /** Set while a version is pinned */
+  pinned: ClaudeCodePin | null;
+}
+
+/** Returned by pin_claude_code_version */
+export interface ClaudeCodePin {
+  version: string;
+  install_type: ClaudeCodeInstallType;
+  /** Unix seconds */
+  pinned_at: number;
+  /** Autoupdater setting before the pin, restored by unpin_claude_code_version */
+  autoupdater_was_disabled: boolean;
+}
+
+/** Returned by list_claude_code_versions (newest first) */
+export interface ClaudeCodeRelease {
+  version: string;
+  /** ISO 8601 */
+  published_at: string | null;
+  prerelease: boolean;
+  /** Dist-tags pointing at this version */
+  tags: string[];
+}
+
+export interface ClaudeCodeVersionList {
+  dist_tags: Record<string, string>;
+  versions: ClaudeCodeRelease[];
+}
+
+/** Returned by get_claude_code_changelog (newest first) */
+export interface ClaudeCodeChangelogEntry {
+  version: string;
+  /** Markdown bullet list */
+  notes: string;
+}
+
+export type ClaudeInstallMethod = 'native' | 'npm' | 'homebrew' | 'bun' | 'other';
+
+/** Returned by detect_claude_installations */
+export interface ClaudeInstallation {
+  path: string;
+  /** After following symlinks */
+  resolved_path: string;
+  version: string | null;
+  method: ClaudeInstallMethod;
+  on_path: boolean;
+  /** The binary lovcode runs: preferred if set, else first on PATH */
+  active: boolean;
+  preferred: boolean;
+}
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+export type AppErrorCode =
+  | 'not_found'
+  | 'permission_denied'
+  | 'invalid_input'
+  | 'parse'
+  | 'io'
+  | 'network'
+  | 'timeout'
+  | 'conflict'
+  | 'unavailable'
+  | 'internal';
+
+/** Rejection value of every Tauri command */
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  /** What the error is about, usually the path or id passed in */
+  context: string | null;
+}
+
+// ============================================================================
+// Log Types
+// ============================================================================
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+/** Returned by get_recent_logs (oldest first) */
+export interface LogEntry {
+  /** RFC 3339 */
+  timestamp: string;
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+}
+
+// ============================================================================
+// Notification Types
+// ============================================================================
+
+export type NotificationKind =
+  | 'terminal'
+  | 'task_finished'
+  | 'task_failed'
+  | 'index_built'
+  | 'feature_review'
+  | 'general';
+
+export interface QuietHours {
+  /** Local 'HH:MM' */
+  start: string;
+  /** Earlier than start means the window crosses midnight */
+  end: string;
+}
+
+export interface NotificationSettings {
+  enabled: boolean;
+  muted_kinds: NotificationKind[];
+  quiet_hours: QuietHours | null;
+}
+
+/** Payload of the app-notification event */
+export interface AppNotification {
+  title: string;
+  body: string;
+  kind: NotificationKind;
+  /** false when the rules held back the native notification */
+  shown: boolean;
+}
+
+// ============================================================================
+// History Import Types
+// ============================================================================
+
+/** Imported sessions are listed under the project id `import:<source>` */
+export type ImportSource = 'cursor' | 'codex' | 'aider';
+
+export interface ImportReport {
+  source: ImportSource;
+  /** Where history was read from */
+  path: string;
+  /** New or updated sessions */
+  imported: number;
+  /** Conversations without any message */
+  skipped: number;
+  /** Files or databases that couldn't be read */
+  errors: string[];
+}
+
+// ============================================================================
+// Session Export Types
+// ============================================================================
+
+export interface VaultExportFilter {
+  /** Only these projects; every project when omitted */
+  project_ids?: string[];
+  /** Unix seconds, by last activity */
+  since?: number;
+  until?: number;
+  /** Also export sessions from History Import */
+  include_imported?: boolean;
+  /** Include tool calls and their output as folded callouts */
+  include_tools?: boolean;
+  redact?: boolean;
+}
+
+export interface VaultExportReport {
+  dest: string;
+  sessions: number;
+  projects: number;
+  errors: string[];
+}
+
+// ============================================================================
+// Local REST API Types
+// ============================================================================
+
+export interface ApiServerStatus {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  /** e.g. "http://127.0.0.1:51217/api"; null while stopped */
+  url: string | null;
+  /** Bearer token for every endpoint except /api/health */
+  token: string;
+  /** Why an enabled server isn't running, e.g. the port is taken */
+  error: string | null;
+}
+
+// ============================================================================
+// Deep Link Types
+// ============================================================================
+
+/** A resolved lovcode:// link (session/<project>/<id> or feature/<id>) */
+export type DeepLink =
+  | {
+      type: 'session';
+      project_id: string;
+      project_path: string;
+      session_id: string;
+      summary: string | null;
+    }
+  | { type: 'feature'; project_id: string; feature_id: string };
+
+// ============================================================================
+// Webhook Types
+// ============================================================================
+
+/** Payload format: Slack {text}, Discord {content}, or the full event as JSON */
+export type WebhookKind = 'slack' | 'discord' | 'generic';
+
+export type WebhookEvent =
+  | 'feature_status_changed'
+  | 'task_completed'
+  | 'session_finished'
+  | 'test';
+
+export interface Webhook {
+  id: string;
+  name: string;
+  url: string;
+  kind: WebhookKind;
+  /** Events to send; every event when empty */
+  events: WebhookEvent[];
+  enabled: boolean;
+  /** Unix seconds */
+  created_at: number;
+}
+
+/** One event sent to one webhook, after all retries */
+export interface WebhookDelivery {
+  webhook_id: string;
+  event: WebhookEvent;
+  /** Unix seconds of the first attempt */
+  timestamp: number;
+  attempts: number;
+  success: boolean;
+  /** HTTP status of the last attempt; null on network errors */
+  status: number | null;
+  error: string | null;
+  /** Including retries */
+  duration_ms: number;
+}
+
+// ============================================================================
+// Hook Event Log Types
+// ============================================================================
+
+/** ok: exit 0, blocked: exit 2, failed: other exit codes, unknown: raw line */
+export type HookEventStatus = 'ok' | 'blocked' | 'failed' | 'unknown';
+
+/** Returned by list_hook_events (newest first) */
+export interface HookEvent {
+  /** Line in events.jsonl */
+  line_number: number;
+  /** RFC 3339, UTC */
+  timestamp: string | null;
+  /** Template name */
+  hook: string | null;
+  /** e.g. 'PreToolUse', 'Stop' */
+  event: string | null;
+  session_id: string | null;
+  cwd: string | null;
+  tool_name: string | null;
+  tool_input: unknown | null;
+  exit_code: number | null;
+  duration_secs: number | null;
+  stderr: string | null;
+  status: HookEventStatus;
+}
+
+export interface HookEventFilter {
+  event?: string;
+  hook?: string;
+  session_id?: string;
+  tool_name?: string;
+  status?: HookEventStatus;
+  /** RFC 3339 */
+  since?: string;
+  /** Default 200 */
+  limit?: number;
+}
+
+// ============================================================================
+// Backup Types
+// ============================================================================
+
+/** A tar.gz snapshot of ~/.claude config (and ~/.claude.json) */
+export interface ClaudeBackup {
+  id: string;
+  label: string;
+  created_at: number;
+  include_projects: boolean;
+  /** Home-relative paths the backup covers */
+  roots: string[];
+  file_count: number;
+  /** Archive size in bytes */
+  size: number;
+}
+
+export interface BackupChange {
+  /** Home-relative, e.g. .claude/commands/review.md */
+  path: string;
+  kind: 'added' | 'modified' | 'removed';
+  /** Unified diff from the current file to the backup's (small text files only) */
+  diff: string | null;
+}
+
+/** Returned by restore_claude_backup */
+export interface BackupRestore {
+  backup: ClaudeBackup;
+  applied: boolean;
+  changes: BackupChange[];
+  unchanged: number;
+  safety_backup_id: string | null;
+}
+
+/** A recorded state of ~/.claude.json */
+export interface ClaudeJsonSnapshot {
+  id: string;
+  /** Unix seconds */
+  taken_at: number;
+  size: number;
+  /** Set when lovcode made the change (e.g. "Installed MCP server github"); null = another program */
+  source: string | null;
+  /** Top-level keys that differ from the previous snapshot */
+  changed_keys: string[];
+}
+
+export interface JsonChange {
+  /** JSON Pointer, e.g. "/mcpServers/github" */
+  path: string;
+  kind: 'added' | 'removed' | 'changed';
+  before: unknown | null;
+  after: unknown | null;
+}
+
+export interface ClaudeJsonDiff {
+  from: ClaudeJsonSnapshot;
+  to: ClaudeJsonSnapshot;
+  changes: JsonChange[];
+}
+
+// ============================================================================
+// Todo Types
+// ============================================================================
+
+export interface TodoItem {
+  content: string;
+  status: 'pending' | 'in_progress' | 'completed';
+  /** Label Claude shows while the item is in progress */
+  active_form: string | null;
+}
+
+/** A plan proposed in plan mode (ExitPlanMode) */
+export interface SessionPlan {
+  /** Markdown */
+  plan: string;
+  timestamp: string | null;
+  line_number: number;
+  /** null when the user never answered */
+  approved: boolean | null;
+}
+
+/** Returned by get_session_todos */
+export interface SessionTodos {
+  session_id: string;
+  /** Latest TodoWrite list */
+  todos: TodoItem[];
+  updated_at: string | null;
+  updates: number;
+  plans: SessionPlan[];
+}
+
+/** Returned by get_project_open_todos */
+export interface OpenTodos {
+  session_id: string;
+  summary: string | null;
+  updated_at: string | null;
+  /** Only items that aren't completed */
+  todos: TodoItem[];
+}
+
+// ============================================================================
+// Tool Call Types
+// ============================================================================
+
+export interface ToolCallFilter {
+  /** Tool names, case-insensitive; every tool when empty */
+  tools?: string[];
+  /** Case-insensitive substring of the tool name or input */
+  query?: string;
+  errors_only?: boolean;
+  /** Only mcp__<server>__<tool> tools */
+  mcp_only?: boolean;
+}
+
+/** Returned by get_session_tool_calls: a tool_use and the tool_result answering it */
+export interface ToolCall {
+  id: string;
+  name: string;
+  mcp_server: string | null;
+  input: unknown;
+  /** Bash command, file path, pattern or URL */
+  summary: string;
+  output_bytes: number;
+  output_preview: string | null;
+  is_error: boolean;
+  started_at: string | null;
+  /** null when the call never got a result */
+  finished_at: string | null;
+  duration_ms: number | null;
+  /** Made by a subagent */
+  is_sidechain: boolean;
+  line_number: number;
+}
+
+// ============================================================================
+// Session Metrics Types
+// ============================================================================
+
+/** Returned by get_session_metrics */
+export interface SessionMetrics {
+  started_at: string | null;
+  ended_at: string | null;
+  /** Wall clock, first to last line */
+  duration_ms: number;
+  /** Typed prompts */
+  user_turns: number;
+  /** Model replies */
+  assistant_turns: number;
+  avg_response_ms: number | null;
+  median_response_ms: number | null;
+  longest_gap_ms: number;
+  /** When the longest pause started */
+  longest_gap_at: string | null;
+}
+
+// ============================================================================
+// Secret Redaction Types
+// ============================================================================
+
+/** Returned by scan_session_secrets */
+export interface SecretFinding {
+  /** e.g. 'openai_api_key', 'github_token', 'generic_secret' */
+  kind: string;
+  message_uuid: string;
+  role: string;
+  /** Line in the session JSONL */
+  line_number: number;
+  /** Masked value, e.g. 'sk-p...9xYz' */
+  preview: string;
+}
+
+// ============================================================================
+// Claude Task Queue Types
+// ============================================================================
+
+export interface ClaudeTaskOptions {
+  /** Shown instead of the prompt */
+  label?: string;
+  /** e.g. 'sonnet', 'opus' */
+  model?: string;
+  permission_mode?: 'default' | 'acceptEdits' | 'bypassPermissions' | 'plan';
+  /** e.g. ['Read', 'Edit', 'Bash(cargo test:*)'] */
+  allowed_tools?: string[];
+  max_turns?: number;
+  /** Default 1800 */
+  timeout_secs?: number;
+}
+
+export type ClaudeTaskStatus = 'queued' | 'running' | 'succeeded' | 'failed' | 'cancelled';
+
+/** A headless claude -p run, returned by enqueue_claude_task / list_claude_tasks */
+export interface ClaudeTask {
+  id: string;
+  project_path: string;
+  prompt: string;
+  options: ClaudeTaskOptions;
+  status: ClaudeTaskStatus;
+  created_at: number;
+  started_at: number | null;
+  finished_at: number | null;
+  /** Assistant text and tool calls (last 32KB) */
+  output: string;
+  /** Claude's final answer */
+  result: string | null;
+  error: string | null;
+  session_id: string | null;
+  cost_usd: number | null;
+  num_turns: number | null;
+}
+
+/** Payload of the claude-task-progress event */
+export interface ClaudeTaskProgress {
+  task_id: string;
+  status: ClaudeTaskStatus;
+  /** New output, when that's what changed */
+  line: string | null;
+}
+
+/** Task a schedule queues each time it fires */
+export interface ScheduledTaskSpec {
+  project_path: string;
+  prompt: string;
+  options?: ClaudeTaskOptions;
+}
+
+/** Returned by schedule_task / list_scheduled_tasks */
+export interface ScheduledTask {
+  id: string;
+  name: string;
+  spec: ScheduledTaskSpec;
+  /** Five-field cron expression in local time, e.g. '0 2 * * *' */
+  cron: string;
+  enabled: boolean;
+  created_at: number;
+  /** null while disabled */
+  next_run_at: number | null;
+  last_run_at: number | null;
+  /** Task queued by the last run */
+  last_task_id: string | null;
+  /** Why the last run queued nothing */
+  last_error: string | null;
+}
+
+// ============================================================================
+// File Reading Types
+// ============================================================================
+
+/** Returned by read_file_range / read_file_tail */
+export interface FileChunk {
+  content: string;
+  /** Byte offset where content starts */
+  offset: number;
+  /** Pass as offset to continue reading; equals file_size at the end */
+  next_offset: number;
+  file_size: number;
+}
+
+// ============================================================================
+// File Tree Types
+// ============================================================================
+
+/** Git marker: modified, added, deleted, renamed, untracked, conflicted */
+export type FileGitStatus = 'M' | 'A' | 'D' | 'R' | '?' | 'U';
+
+/** Returned by get_file_tree */
+export interface FileTreeNode {
+  name: string;
+  path: string;
+  is_dir: boolean;
+  /** Bytes (0 for directories) */
+  size: number;
+  /** Directories containing changes are marked 'M' */
+  git_status: FileGitStatus | null;
+  /** null for directories below the requested depth */
+  children: FileTreeNode[] | null;
+}
+
+/** Payload of the fs-changed event (see watch_directory) */
+export interface FsChangedEvent {
+  watch_id: string;
+  root: string;
+  /** Created, modified or removed paths; changes inside .git are reported as .git */
+  paths: string[];
+  /** More paths changed than are listed */
+  truncated: boolean;
+}
+
+// ============================================================================
+// File Search Types
+// ============================================================================
+
+/** Returned by find_files */
+export interface FindFilesResult {
+  /** Relative to the project, sorted */
+  files: string[];
+  truncated: boolean;
+}
+
+export interface GrepOptions {
+  case_insensitive?: boolean;
+  fixed_strings?: boolean;
+  whole_word?: boolean;
+  /** Only search files matching this glob */
+  glob?: string;
+  include_ignored?: boolean;
+  max_results?: number;
+}
+
+export interface GrepMatch {
+  /** Relative to the project */
+  path: string;
+  line_number: number;
+  line: string;
+  /** First match within line, in characters */
+  match_start: number;
+  match_end: number;
+}
+
+/** Returned by grep_project */
+export interface GrepResult {
+  matches: GrepMatch[];
+  files_searched: number;
+  truncated: boolean;
+}
+
+// ============================================================================
+// Shell Execution Types
+// ============================================================================
+
+/** Payload of the "exec-output" event (exec_shell_stream) */
+export interface ExecOutputEvent {
+  exec_id: string;
+  stream: 'stdout' | 'stderr';
+  data: string;
+}
+
+/** Payload of the "exec-exit" event, sent after all output */
+export interface ExecExitEvent {
+  exec_id: string;
+  /** null when the process was killed */
+  exit_code: number | null;
+  reason: 'exited' | 'cancelled' | 'timed_out' | 'failed';
+}
+
+// ============================================================================
+// Terminal Types
+// ============================================================================
+
+/** Claude Code state detected from terminal output */
+export type ClaudeState = 'none' | 'working' | 'awaiting_permission' | 'waiting_for_input';
+
+export interface PtySessionInfo {
+  id: string;
+  cwd: string;
+  /** false once the process exited (the session stays listed until pty_kill) */
+  running: boolean;
+  title: string;
+  /** Unix ms of the last input or output */
+  last_activity: number;
+  claude_state: ClaudeState;
+}
+
+/** Returned by pty_get_info and sent as the "pty-info-changed" event (OSC 7 cwd, OSC 0/2 title) */
+export interface PtyInfo {
+  id: string;
+  cwd: string;
+  title: string;
+}
+
+/** Returned by pty_get_state and sent as the "pty-claude-state" event */
+export interface PtyState {
+  id: string;
+  claude_state: ClaudeState;
+  /** Unix ms of the last state change */
+  since: number;
+}
+
+/** Persisted in ~/.lovstudio/lovcode/pty_notify.json */
+export interface PtyNotifySettings {
+  enabled: boolean;
+  /** A busy terminal quiet for this long counts as finished */
+  idle_seconds: number;
+  /** Regexes matched against output with ANSI codes stripped */
+  patterns: string[];
+}
+
+/** Persisted in ~/.lovstudio/lovcode/pty_batch.json */
+export interface PtyBatchSettings {
+  /** Output read within this many ms is sent as one pty-data event (0-50, 0 disables) */
+  batch_ms: number;
+}
+
+/** Payload of the "pty-notification" event */
+export interface PtyNotification {
+  id: string;
+  kind: 'idle' | 'pattern';
+  title: string;
+  message: string;
+}
+
+/** Returned by pty_get_exit_status */
+export interface PtyExitStatus {
+  /** null if the process could not be waited on */
+  exit_code: number | null;
+  /** Signal name when the process was terminated by a signal */
+  signal: string | null;
+}
+
+/** Payload of the "pty-exit" event */
+export interface PtyExitEvent extends PtyExitStatus {
+  id: string;
+}
+
+/** Payload of the "pty-restored" event */
+export interface PtyRestoredEvent {
+  id: string;
+  cwd: string;
+  command: string | null;
 }
 
 // ============================================================================
@@ -6,7 +6,7 @@ import { domToCanvas } from "modern-screenshot";
 import { save } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
-import type { AnnualReport2025 as AnnualReportData } from "../../types";
+import type { PeriodReport as AnnualReportData } from "../../types";
 import { useInvokeQuery } from "../../hooks";
 
 interface AnnualReport2025Props {
@@ -709,8 +709,9 @@ export function AnnualReport2025({ onClose }: AnnualReport2025Props) {
   const shareCardRef = useRef<HTMLDivElement>(null);
 
   const { data: report, isLoading } = useInvokeQuery<AnnualReportData>(
-    ["annualReport2025"],
-    "get_annual_report_2025"
+    ["annualReport", 2025],
+    "get_annual_report",
+    { year: 2025 }
   );
 
   const screens = [
@@ -18,6 +18,7 @@ const exportSeparatorAtom = atomWithStorage("lovcode:exportSeparator", true);
 const exportOriginalAtom = atomWithStorage("lovcode:exportOriginal", true);
 const exportWatermarkAtom = atomWithStorage("lovcode:exportWatermark", true);
 const exportJsonPrettyAtom = atomWithStorage("lovcode:exportJsonPretty", true);
+const exportRedactAtom = atomWithStorage("lovcode:exportRedact", true);
 
 interface ExportDialogProps {
   open: boolean;
@@ -59,6 +60,7 @@ export function ExportDialog({
   const [exportOriginal, setExportOriginal] = useAtom(exportOriginalAtom);
   const [addWatermark, setAddWatermark] = useAtom(exportWatermarkAtom);
   const [jsonPretty, setJsonPretty] = useAtom(exportJsonPrettyAtom);
+  const [redact, setRedact] = useAtom(exportRedactAtom);
 
   const messages = exportOriginal
     ? allMessages.filter((m) => selectedIds.has(m.uuid) && !m.is_meta && !m.is_tool)
@@ -120,8 +122,14 @@ export function ExportDialog({
     return output;
   };
 
+  // API keys, tokens and passwords are replaced with [REDACTED:<kind>] by the backend
+  const finalOutput = async () => {
+    const output = generateOutput();
+    return redact ? invoke<string>("redact_secrets", { content: output }) : output;
+  };
+
   const handleCopy = async () => {
-    await navigator.clipboard.writeText(generateOutput());
+    await navigator.clipboard.writeText(await finalOutput());
     onOpenChange(false);
   };
 
@@ -133,12 +141,26 @@ export function ExportDialog({
       filters: [{ name: filterName, extensions: [ext] }],
     });
     if (path) {
-      await invoke("write_file", { path, content: generateOutput() });
+      await invoke("write_file", { path, content: await finalOutput() });
       onOpenChange(false);
     }
   };
 
-  const preview = generateOutput();
+  const rawOutput = generateOutput();
+  const [redactedOutput, setRedactedOutput] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!redact) return;
+    let cancelled = false;
+    invoke<string>("redact_secrets", { content: rawOutput }).then((content) => {
+      if (!cancelled) setRedactedOutput(content);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [redact, rawOutput]);
+
+  const preview = redact ? (redactedOutput ?? "") : rawOutput;
 
   return (
     <Dialog open={open} onOpenChange={onOpenChange}>
@@ -198,6 +220,15 @@ export function ExportDialog({
               />
               <span>Original</span>
             </label>
+            <label className="flex items-center gap-2 text-sm text-muted-foreground cursor-pointer">
+              <input
+                type="checkbox"
+                checked={redact}
+                onChange={(e) => setRedact(e.target.checked)}
+                className="w-4 h-4 accent-primary cursor-pointer"
+              />
+              <span>Redact secrets</span>
+            </label>
 
             {format === "markdown" && (
               <>
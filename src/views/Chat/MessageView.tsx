@@ -63,8 +63,11 @@ export function MessageView({ projectId, projectPath, sessionId, summary: initia
     return originalChat ? restoreSlashCommand(content) : content;
   };
 
-  const handleCopyContent = (content: string) => {
-    invoke("copy_to_clipboard", { text: content });
+  const handleCopyContent = (content: string, messageUuid: string) => {
+    invoke("copy_to_clipboard", {
+      text: content,
+      source: { project_id: projectId, session_id: sessionId, message_uuid: messageUuid },
+    });
   };
 
   const handleCopyFileLine = (lineNumber: number) => {
@@ -161,7 +164,7 @@ export function MessageView({ projectId, projectPath, sessionId, summary: initia
                   </button>
                 </DropdownMenuTrigger>
                 <DropdownMenuContent align="end">
-                  <DropdownMenuItem onClick={() => handleCopyContent(displayContent)}>
+                  <DropdownMenuItem onClick={() => handleCopyContent(displayContent, msg.uuid)}>
                     <Copy size={14} />
                     Copy Content
                   </DropdownMenuItem>
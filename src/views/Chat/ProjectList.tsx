@@ -8,7 +8,8 @@ import { useAppConfig } from "../../context";
 import { VirtualChatList } from "./VirtualChatList";
 import { formatRelativeTime } from "./utils";
 import { useInvokeQuery } from "../../hooks";
-import type { Project, Session, ChatMessage, SearchResult, ChatsResponse } from "../../types";
+import type { Project, Session, SessionsResponse, ChatMessage, SearchResult, ChatsResponse } from "../../types";
+import { errorCode, errorMessage } from "../../lib/errorHandler";
 
 interface ProjectListProps {
   onSelectProject: (p: Project) => void;
@@ -22,7 +23,8 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
 
   // Use react-query for cached data fetching
   const { data: projects, isLoading: loadingProjects } = useInvokeQuery<Project[]>(["projects"], "list_projects");
-  const { data: allSessions, isLoading: loadingSessions } = useInvokeQuery<Session[]>(["sessions"], "list_all_sessions");
+  const { data: sessionsPage, isLoading: loadingSessions } = useInvokeQuery<SessionsResponse>(["sessions"], "list_all_sessions");
+  const allSessions = sessionsPage?.items;
   const { data: chatsResponse, isLoading: loadingChats } = useInvokeQuery<ChatsResponse>(["chats"], "list_all_chats", { limit: 50 });
 
   // Local state for pagination (chats loaded beyond initial fetch)
@@ -66,7 +68,7 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
       await invoke<number>("build_search_index");
       setIndexBuilt(true);
     } catch (e) {
-      setIndexStatus(`Error: ${e}`);
+      setIndexStatus(`Error: ${errorMessage(e)}`);
     } finally {
       setIndexBuilding(false);
     }
@@ -78,28 +80,31 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
     }
   }, [indexBuilt, indexBuilding]);
 
+  // record: only searches submitted with Enter go into the search history
+  const runSearch = useCallback(async (query: string, record: boolean) => {
+    setSearching(true);
+    try {
+      const results = await invoke<SearchResult[]>("search_chats", { query, limit: 50, record });
+      setSearchResults(results);
+    } catch (e) {
+      if (errorCode(e) === "unavailable") {
+        setIndexStatus("Search index not built. Click 'Build Index' to create it.");
+      }
+      setSearchResults([]);
+    } finally {
+      setSearching(false);
+    }
+  }, []);
+
   useEffect(() => {
     if (viewMode !== "chats") return;
     if (!searchQuery.trim()) {
       setSearchResults(null);
       return;
     }
-    const timer = setTimeout(async () => {
-      setSearching(true);
-      try {
-        const results = await invoke<SearchResult[]>("search_chats", { query: searchQuery, limit: 50 });
-        setSearchResults(results);
-      } catch (e) {
-        if (String(e).includes("not built")) {
-          setIndexStatus("Search index not built. Click 'Build Index' to create it.");
-        }
-        setSearchResults([]);
-      } finally {
-        setSearching(false);
-      }
-    }, 300);
+    const timer = setTimeout(() => runSearch(searchQuery, false), 300);
     return () => clearTimeout(timer);
-  }, [searchQuery, viewMode]);
+  }, [searchQuery, viewMode, runSearch]);
 
   const sortedProjects = [...(projects || [])].sort((a, b) => {
     switch (sortBy) {
@@ -220,6 +225,9 @@ export function ProjectList({ onSelectProject, onSelectSession, onSelectChat }:
                 type="text"
                 value={searchQuery}
                 onChange={(e) => setSearchQuery(e.target.value)}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter" && searchQuery.trim()) runSearch(searchQuery, true);
+                }}
                 placeholder="Search messages..."
                 className="w-full px-3 py-2 pr-8 rounded-lg bg-card border border-border text-ink placeholder:text-muted-foreground focus:outline-none focus:border-primary"
               />
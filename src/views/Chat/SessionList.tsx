@@ -1,4 +1,4 @@
-import { useState, useEffect, useMemo } from "react";
+import { useState, useEffect, useMemo, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { save } from "@tauri-apps/plugin-dialog";
 import { FileIcon, GlobeIcon, ChatBubbleIcon } from "@radix-ui/react-icons";
@@ -52,24 +52,30 @@ export function SessionList({ projectId, projectPath, onBack, onSelect }: Sessio
 
   const filteredSessions = hideEmptySessions ? sessions.filter((s) => s.message_count > 0) : sessions;
 
-  useEffect(() => {
-    if (!searchQuery.trim()) {
-      setSearchResults(null);
-      return;
-    }
-    const timer = setTimeout(async () => {
+  // record: only searches submitted with Enter go into the search history
+  const runSearch = useCallback(
+    async (query: string, record: boolean) => {
       setSearching(true);
       try {
-        const results = await invoke<SearchResult[]>("search_chats", { query: searchQuery, limit: 50, projectId });
+        const results = await invoke<SearchResult[]>("search_chats", { query, limit: 50, projectId, record });
         setSearchResults(results);
       } catch {
         setSearchResults([]);
       } finally {
         setSearching(false);
       }
-    }, 300);
+    },
+    [projectId]
+  );
+
+  useEffect(() => {
+    if (!searchQuery.trim()) {
+      setSearchResults(null);
+      return;
+    }
+    const timer = setTimeout(() => runSearch(searchQuery, false), 300);
     return () => clearTimeout(timer);
-  }, [searchQuery, projectId]);
+  }, [searchQuery, runSearch]);
 
   const toggleSelect = (id: string) => {
     setSelectedIds((prev) => {
@@ -172,6 +178,9 @@ generator: "Lovcode"
           type="text"
           value={searchQuery}
           onChange={(e) => setSearchQuery(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter" && searchQuery.trim()) runSearch(searchQuery, true);
+          }}
           placeholder="Search chats..."
           className="w-full max-w-md px-4 py-2 pr-8 bg-card border border-border rounded-lg text-ink placeholder:text-muted-foreground focus:outline-none focus:border-primary"
         />
@@ -16,6 +16,7 @@ import {
 } from "../../components/ui/dialog";
 import { ConfigPage, DetailHeader, DetailCard, ContentCard } from "../../components/config";
 import type { LocalCommand } from "../../types";
+import { errorMessage } from "../../lib/errorHandler";
 
 interface CommandDetailViewProps {
   command: LocalCommand;
@@ -137,7 +138,7 @@ export function CommandDetailView({
       });
       onRenamed?.(newPath);
     } catch (e) {
-      const error = String(e);
+      const error = errorMessage(e);
       if (error.startsWith("DIR_NOT_EXIST:")) {
         const dirPath = error.slice("DIR_NOT_EXIST:".length);
         setPendingRename({ newName, dirPath });
@@ -9,6 +9,7 @@ import { useState, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useQueryClient } from "../../../hooks";
 import type { LocalCommand } from "../../../types";
+import { errorMessage } from "../../../lib/errorHandler";
 
 interface PendingMove {
   cmd: LocalCommand;
@@ -82,7 +83,7 @@ export function useCommandActions() {
         setSelectedCommand(null);
         await refreshCommands();
       } catch (e) {
-        const error = String(e);
+        const error = errorMessage(e);
         if (error.startsWith("DIR_NOT_EXIST:")) {
           const dirPath = error.slice("DIR_NOT_EXIST:".length);
           const filename = cmd.path.split("/").pop()?.replace(".md", "") || "";
@@ -1,5 +1,5 @@
 import { useMemo } from "react";
-import type { FeatureType, Project, Session, LocalCommand } from "../../types";
+import type { FeatureType, Project, Session, SessionsResponse, LocalCommand } from "../../types";
 import { FEATURES, FEATURE_ICONS } from "../../constants";
 import { ActivityHeatmap, RecentActivity, QuickActions, FeaturedCarousel } from "../../components/home";
 import { useInvokeQuery } from "../../hooks";
@@ -16,13 +16,21 @@ interface ActivityStats {
   daily: Record<string, number>;
   hourly: Record<string, number>;
   detailed: Record<string, number>;
+  /** 7x24 counts, rows Sunday..Saturday */
+  weekday_hour: number[][];
+  utc_offset_minutes: number;
 }
 
 export function Home({ onFeatureClick, onProjectClick, onSessionClick, onSearch, onOpenAnnualReport }: HomeProps) {
   const { data: projects } = useInvokeQuery<Project[]>(["projects"], "list_projects");
-  const { data: sessions } = useInvokeQuery<Session[]>(["sessions"], "list_all_sessions");
+  const { data: sessionsPage } = useInvokeQuery<SessionsResponse>(["sessions"], "list_all_sessions");
+  const sessions = sessionsPage?.items;
   const { data: commands } = useInvokeQuery<LocalCommand[]>(["commands"], "list_local_commands");
-  const { data: activityStats } = useInvokeQuery<ActivityStats>(["activityStats"], "get_activity_stats");
+  const { data: activityStats } = useInvokeQuery<ActivityStats>(
+    ["activityStats"],
+    "get_activity_stats",
+    { utcOffsetMinutes: -new Date().getTimezoneOffset() },
+  );
 
   const data = projects && sessions && commands ? { projects, sessions, commands } : null;
 
@@ -17,6 +17,7 @@ interface ReferenceDoc {
   name: string;
   path: string;
   group: string | null;
+  url: string | null;
 }
 
 function ReferenceDocTree({
@@ -6,6 +6,7 @@ import type { TemplateComponent, TemplateCategory } from "../../types";
 import { TEMPLATE_CATEGORIES } from "../../constants";
 import { DetailCard, ConfigPage } from "../../components/config";
 import { CodePreview } from "../../components/shared";
+import { errorMessage } from "../../lib/errorHandler";
 
 function getLanguageForCategory(category: TemplateCategory): string {
   switch (category) {
@@ -54,7 +55,7 @@ export function TemplateDetailView({
       await invoke("uninstall_mcp_template", { name: template.name });
       setInstalled(false);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setUninstalling(false);
     }
@@ -96,7 +97,7 @@ export function TemplateDetailView({
       }
       setInstalled(true);
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setInstalling(false);
     }
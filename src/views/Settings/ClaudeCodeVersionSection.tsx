@@ -10,6 +10,7 @@ import {
 } from "../../components/ui/select";
 import { CollapsibleCard } from "../../components/shared";
 import type { ClaudeCodeVersionInfo, ClaudeCodeInstallType } from "../../types";
+import { errorMessage } from "../../lib/errorHandler";
 
 function formatDownloads(n: number): string {
   if (n >= 1_000_000) return `${(n / 1_000_000).toFixed(1)}M`;
@@ -45,7 +46,7 @@ export function ClaudeCodeVersionSection() {
         setSelectedInstallType(info.install_type);
       }
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -68,7 +69,7 @@ export function ClaudeCodeVersionSection() {
       setSuccess(`Successfully installed Claude Code ${selectedVersion} (${typeLabel})`);
       await loadVersionInfo();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setInstalling(false);
     }
@@ -80,7 +81,7 @@ export function ClaudeCodeVersionSection() {
       await invoke("set_claude_code_autoupdater", { disabled: !versionInfo.autoupdater_disabled });
       setVersionInfo({ ...versionInfo, autoupdater_disabled: !versionInfo.autoupdater_disabled });
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     }
   };
 
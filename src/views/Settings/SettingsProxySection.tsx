@@ -22,6 +22,7 @@ import { CollapsibleCard } from "../../components/shared";
 import { trackProviderEvent, isAnalyticsEnabled, setAnalyticsEnabled } from "../../lib/analytics";
 import type { MarketplaceItem } from "../../components/config";
 import type { ClaudeSettings } from "../../types";
+import { errorMessage } from "../../lib/errorHandler";
 
 interface SettingsProxySectionProps {
   marketplaceItems: MarketplaceItem[];
@@ -357,8 +358,8 @@ export function SettingsProxySection({
           return;
         } catch (e) {
           setTestStatus((prev) => ({ ...prev, [presetKey]: "error" }));
-          setTestMessage((prev) => ({ ...prev, [presetKey]: `UniVibe test error: ${String(e)}` }));
-          trackProviderEvent({ action: "test", provider: presetKey, success: false, error_message: String(e) });
+          setTestMessage((prev) => ({ ...prev, [presetKey]: `UniVibe test error: ${errorMessage(e)}` }));
+          trackProviderEvent({ action: "test", provider: presetKey, success: false, error_message: errorMessage(e) });
           return;
         }
       }
@@ -395,8 +396,8 @@ export function SettingsProxySection({
           return;
         } catch (e) {
           setTestStatus((prev) => ({ ...prev, [presetKey]: "error" }));
-          setTestMessage((prev) => ({ ...prev, [presetKey]: `SiliconFlow test error: ${String(e)}` }));
-          trackProviderEvent({ action: "test", provider: presetKey, success: false, error_message: String(e) });
+          setTestMessage((prev) => ({ ...prev, [presetKey]: `SiliconFlow test error: ${errorMessage(e)}` }));
+          trackProviderEvent({ action: "test", provider: presetKey, success: false, error_message: errorMessage(e) });
           return;
         }
       }
@@ -437,8 +438,8 @@ export function SettingsProxySection({
           return;
         } catch (e) {
           setTestStatus((prev) => ({ ...prev, [presetKey]: "error" }));
-          setTestMessage((prev) => ({ ...prev, [presetKey]: `${label} test error: ${String(e)}` }));
-          trackProviderEvent({ action: "test", provider: presetKey, model, success: false, error_message: String(e) });
+          setTestMessage((prev) => ({ ...prev, [presetKey]: `${label} test error: ${errorMessage(e)}` }));
+          trackProviderEvent({ action: "test", provider: presetKey, model, success: false, error_message: errorMessage(e) });
           return;
         }
       }
@@ -512,8 +513,8 @@ export function SettingsProxySection({
       }, 1500);
     } catch (e) {
       setApplyStatus((prev) => ({ ...prev, [presetKey]: "error" }));
-      setApplyError(String(e));
-      trackProviderEvent({ action: "apply", provider: presetKey, model: selectedModels[presetKey], success: false, error_message: String(e) });
+      setApplyError(errorMessage(e));
+      trackProviderEvent({ action: "apply", provider: presetKey, model: selectedModels[presetKey], success: false, error_message: errorMessage(e) });
     }
   };
 
@@ -21,6 +21,7 @@ import {
 } from "@/components/ui/dropdown-menu";
 import { Tabs, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import type { CommitInfo, Feature } from "./types";
+import { errorMessage } from "../../lib/errorHandler";
 
 interface GitHistoryProps {
   projectPath: string;
@@ -109,7 +110,7 @@ export function GitHistory({ projectPath, features = [], onRefresh, embedded = f
       onRefresh?.();
     } catch (e) {
       console.error("Failed to revert:", e);
-      alert(`Revert failed: ${e}`);
+      alert(`Revert failed: ${errorMessage(e)}`);
     }
   };
 
@@ -132,7 +133,7 @@ export function GitHistory({ projectPath, features = [], onRefresh, embedded = f
       }
     } catch (e) {
       console.error("Failed to export changelog:", e);
-      alert(`Export failed: ${e}`);
+      alert(`Export failed: ${errorMessage(e)}`);
     }
   };
 
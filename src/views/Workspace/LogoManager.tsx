@@ -12,6 +12,7 @@ import {
   DialogHeader,
   DialogTitle,
 } from "@/components/ui/dialog";
+import { errorMessage } from "../../lib/errorHandler";
 
 interface LogoManagerProps {
   projectPath: string;
@@ -142,7 +143,7 @@ export function LogoManager({ projectPath, embedded = false }: LogoManagerProps)
       setGenPreviews(prev => [...results, ...prev]);
       setSelectedPreview(0); // Select first of new batch
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setGenerating(false);
     }
@@ -167,7 +168,7 @@ export function LogoManager({ projectPath, embedded = false }: LogoManagerProps)
       // Notify ProjectLogo to refresh
       window.dispatchEvent(new CustomEvent("logo-updated", { detail: { projectPath } }));
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     }
   };
 
@@ -8,7 +8,7 @@ import {
 } from "@/components/ui/dropdown-menu";
 import { useInvokeQuery } from "@/hooks";
 import { workspaceDataAtom } from "@/store";
-import { invoke } from "@tauri-apps/api/core";
+import { saveWorkspaceData } from "@/lib/workspace";
 import { ProjectLogo } from "./ProjectLogo";
 import { GitHistory } from "./GitHistory";
 import { ProjectDiagnostics } from "./ProjectDiagnostics";
@@ -16,7 +16,7 @@ import { LogoManager } from "./LogoManager";
 import { SessionDropdownMenuItems } from "@/components/shared/SessionMenuItems";
 import { NewTerminalSplitButton } from "@/components/ui/new-terminal-button";
 import type { WorkspaceProject } from "./types";
-import type { Session } from "@/types";
+import type { Session, SessionsResponse } from "@/types";
 import type { WorkspaceData } from "./types";
 
 interface ProjectDashboardProps {
@@ -56,10 +56,11 @@ export function ProjectDashboard({ project }: ProjectDashboardProps) {
   const [, setWorkspace] = useAtom(workspaceDataAtom);
 
   // Fetch all CC sessions
-  const { data: allSessions = [], isLoading } = useInvokeQuery<Session[]>(
+  const { data: sessionsPage, isLoading } = useInvokeQuery<SessionsResponse>(
     ["sessions"],
     "list_all_sessions"
   );
+  const allSessions = useMemo(() => sessionsPage?.items ?? [], [sessionsPage]);
 
   // Filter to sessions matching this project's path
   const filteredSessions = useMemo(() => {
@@ -175,7 +176,7 @@ export function ProjectDashboard({ project }: ProjectDashboardProps) {
     });
 
     if (savedWorkspace) {
-      await invoke("workspace_save", { data: savedWorkspace });
+      await saveWorkspaceData(savedWorkspace);
     }
   };
 
@@ -277,7 +278,7 @@ export function ProjectDashboard({ project }: ProjectDashboardProps) {
     });
 
     if (savedWorkspace) {
-      await invoke("workspace_save", { data: savedWorkspace });
+      await saveWorkspaceData(savedWorkspace);
     }
   };
 
@@ -24,6 +24,7 @@ import {
 } from "../../components/ui/dropdown-menu";
 import { FilePath } from "../../components/shared/FilePath";
 import type { TechStack, EnvCheckResult, FileLineCount } from "./types";
+import { errorMessage } from "../../lib/errorHandler";
 
 interface ProjectDiagnosticsProps {
   projectPath: string;
@@ -421,7 +422,7 @@ export function ProjectDiagnostics({ projectPath, embedded = false }: ProjectDia
                           alert(`Added ${count} keys to .env`);
                           runEnvCheck();
                         } catch (error) {
-                          alert(`Failed: ${error}`);
+                          alert(`Failed: ${errorMessage(error)}`);
                         }
                       }}
                       className="text-[10px] px-2 py-0.5 bg-amber-100 text-amber-800 hover:bg-amber-200 rounded"
@@ -34,6 +34,7 @@ export function WorkspaceView() {
     handleSessionClose,
     handleSessionSelect,
     handleSessionTitleChange,
+    handleLayoutResize,
   } = useWorkspaceHandlers({
     workspace,
     activeProject,
@@ -130,6 +131,7 @@ export function WorkspaceView() {
                 onPanelFocus={setActivePanelId}
                 onPanelClose={handlePanelClose}
                 onPanelSplit={handlePanelSplit}
+                onLayoutResize={handleLayoutResize}
                 onPanelToggleShared={handlePanelToggleShared}
                 onPanelReload={handlePanelReload}
                 onSessionAdd={handleSessionAdd}
@@ -1,3 +1,3 @@
 export { useWorkspaceState } from './useWorkspaceState';
 export { useWorkspaceHandlers } from './useWorkspaceHandlers';
-export { splitLayoutNode, removeFromLayout } from './layoutUtils';
+export { splitLayoutNode, removeFromLayout, setLayoutRatio } from './layoutUtils';
@@ -1,11 +1,11 @@
 /**
  * [INPUT]: LayoutNode type
- * [OUTPUT]: splitLayoutNode, removeFromLayout
+ * [OUTPUT]: splitLayoutNode, removeFromLayout, setLayoutRatio
  * [POS]: Workspace 布局树操作工具函数
  * [PROTOCOL]: 变更时更新此头部
  */
 
-import type { LayoutNode } from '../types';
+import type { LayoutNode, LayoutPath } from '../types';
 
 /**
  * Split a layout node at the target panel, creating a new split node
@@ -50,3 +50,15 @@ export function removeFromLayout(node: LayoutNode, targetPanelId: string): Layou
   if (!second) return first;
   return { ...node, first, second };
 }
+
+/**
+ * Set the ratio of the split at `path`, leaving the tree unchanged if the path is invalid
+ */
+export function setLayoutRatio(node: LayoutNode, path: LayoutPath, ratio: number): LayoutNode {
+  if (node.type === 'panel') return node;
+  if (path.length === 0) return { ...node, ratio };
+  const [step, ...rest] = path;
+  if (step === 0) return { ...node, first: setLayoutRatio(node.first, rest, ratio) };
+  if (step === 1) return { ...node, second: setLayoutRatio(node.second, rest, ratio) };
+  return node;
+}
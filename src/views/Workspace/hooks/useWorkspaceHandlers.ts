@@ -9,7 +9,8 @@ import { useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import { disposeTerminal } from '../../../components/Terminal';
-import { splitLayoutNode, removeFromLayout } from './layoutUtils';
+import { reloadWorkspaceData } from '@/lib/workspace';
+import { splitLayoutNode, removeFromLayout, setLayoutRatio } from './layoutUtils';
 import type {
   WorkspaceData,
   WorkspaceProject,
@@ -17,6 +18,7 @@ import type {
   PanelState as StoredPanelState,
   SessionState as StoredSessionState,
   LayoutNode,
+  LayoutPath,
 } from '../types';
 
 interface UseWorkspaceHandlersProps {
@@ -51,11 +53,8 @@ export function useWorkspaceHandlers({
           path: selected,
         });
 
-        saveWorkspace((current) => ({
-          ...current,
-          projects: [...current.projects, project],
-          active_project_id: project.id,
-        }));
+        await reloadWorkspaceData();
+        saveWorkspace((current) => ({ ...current, active_project_id: project.id }));
       }
     } catch (err) {
       console.error('Failed to add project:', err);
@@ -80,22 +79,12 @@ export function useWorkspaceHandlers({
         });
 
         const targetId = targetProject.id;
+        await reloadWorkspaceData();
         saveWorkspace((current) => {
           const newProjects = current.projects.map((p) =>
-            p.id === targetId
-              ? {
-                  ...p,
-                  features: [...p.features, feature],
-                  active_feature_id: feature.id,
-                }
-              : p
+            p.id === targetId ? { ...p, active_feature_id: feature.id } : p
           );
-          return {
-            ...current,
-            projects: newProjects,
-            active_project_id: targetId,
-            feature_counter: feature.seq,
-          };
+          return { ...current, projects: newProjects, active_project_id: targetId };
         });
         return { featureId: feature.id, featureName: feature.name };
       } catch (err) {
@@ -632,12 +621,37 @@ export function useWorkspaceHandlers({
     [activeProject, saveWorkspace]
   );
 
+  const handleLayoutResize = useCallback(
+    (path: LayoutPath, ratio: number) => {
+      if (!activeProject || !activeFeature) return;
+      const projectId = activeProject.id;
+      const featureId = activeFeature.id;
+
+      saveWorkspace((current) => {
+        const newProjects = current.projects.map((p) => {
+          if (p.id !== projectId) return p;
+          return {
+            ...p,
+            features: p.features.map((f) =>
+              f.id === featureId && f.layout
+                ? { ...f, layout: setLayoutRatio(f.layout, path, ratio) }
+                : f
+            ),
+          };
+        });
+        return { ...current, projects: newProjects };
+      });
+    },
+    [activeProject, activeFeature, saveWorkspace]
+  );
+
   return {
     // Project & Feature
     handleAddProject,
     handleAddFeature,
     // Panel
     handlePanelSplit,
+    handleLayoutResize,
     handleInitialPanelCreate,
     handlePanelClose,
     handlePanelToggleShared,
@@ -8,6 +8,7 @@
 import { useEffect, useCallback, useMemo, useRef } from 'react';
 import { useAtom } from 'jotai';
 import { invoke } from '@tauri-apps/api/core';
+import { saveWorkspaceData } from '@/lib/workspace';
 import { listen } from '@tauri-apps/api/event';
 import { activePanelIdAtom, workspaceDataAtom, workspaceLoadingAtom, viewAtom } from '@/store';
 import type { WorkspaceData } from '../types';
@@ -56,7 +57,7 @@ export function useWorkspaceState() {
         ...(projectId && { active_project_id: projectId }),
       };
 
-      invoke('workspace_save', { data: newWorkspace }).catch(console.error);
+      saveWorkspaceData(newWorkspace).catch(console.error);
       return newWorkspace;
     });
   }, [view, setWorkspace]);
@@ -80,7 +81,7 @@ export function useWorkspaceState() {
             })),
           };
           setWorkspace(resetData);
-          invoke('workspace_save', { data: resetData }).catch(console.error);
+          saveWorkspaceData(resetData).catch(console.error);
         } else {
           setWorkspace(data);
         }
@@ -127,7 +128,7 @@ export function useWorkspaceState() {
       });
       if (savedData) {
         try {
-          await invoke('workspace_save', { data: savedData });
+          await saveWorkspaceData(savedData);
         } catch (err) {
           console.error('Failed to save workspace:', err);
         }
@@ -166,6 +167,7 @@ export function useWorkspaceState() {
             if (cached && cached.ptyId === s.pty_id) {
               cached.title = s.title;
               cached.command = s.command;
+              cached.sshHost = s.ssh_host;
               return cached;
             }
             const session = {
@@ -173,6 +175,7 @@ export function useWorkspaceState() {
               ptyId: s.pty_id,
               title: s.title,
               command: s.command,
+              sshHost: s.ssh_host,
             };
             cache.set(s.id, session);
             return session;
@@ -180,6 +183,7 @@ export function useWorkspaceState() {
           activeSessionId: p.active_session_id,
           isShared: p.is_shared,
           cwd: activeProject?.path || '',
+          env: p.env,
         }))
       );
     });
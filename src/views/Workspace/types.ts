@@ -7,6 +7,8 @@ export interface SessionState {
   pty_id: string;
   title: string;
   command?: string;
+  /** SSH host alias for remote terminals */
+  ssh_host?: string;
 }
 
 /** Panel state (container for multiple session tabs) */
@@ -16,12 +18,27 @@ export interface PanelState {
   active_session_id: string;
   is_shared: boolean;
   cwd: string;
+  /** Extra environment for terminals started in this panel */
+  env?: Record<string, string>;
 }
 
 /** Layout tree node - either a panel leaf or a split container */
 export type LayoutNode =
   | { type: "panel"; panelId: string }
-  | { type: "split"; direction: "horizontal" | "vertical"; first: LayoutNode; second: LayoutNode };
+  | {
+      type: "split";
+      direction: "horizontal" | "vertical";
+      first: LayoutNode;
+      second: LayoutNode;
+      /** Share taken by `first` (0..1, default 0.5) */
+      ratio?: number;
+      /** Minimum pane sizes in pixels */
+      min_first?: number;
+      min_second?: number;
+    };
+
+/** Path from the root layout node to a split: 0 = first child, 1 = second child */
+export type LayoutPath = number[];
 
 /** Feature within a project */
 export interface Feature {
@@ -32,11 +49,21 @@ export interface Feature {
   /** Optional description (markdown) - e.g., background, goals */
   description?: string;
   status: FeatureStatus;
+  /** Rank within the project; kanban columns list features of one status by rank */
+  order?: number;
   pinned?: boolean;
   archived?: boolean;
   archived_note?: string;
   git_branch?: string;
+  /** Branch git_branch was started from; completing the feature merges back into it */
+  base_branch?: string;
+  /** Git worktree the feature runs in (defaults to the project directory) */
+  worktree_path?: string;
+  /** Checkpoint the project when the feature starts running */
+  auto_checkpoint?: boolean;
   chat_session_id?: string;
+  /** All Claude sessions linked to this feature (chat_session_id is the primary one) */
+  session_ids?: string[];
   panels: PanelState[];
   /** @deprecated Use layout instead */
   layout_direction?: "horizontal" | "vertical";
@@ -45,6 +72,16 @@ export interface Feature {
   created_at: number;
 }
 
+/** How workspace_complete_feature merges the feature branch */
+export type MergeStrategy = "merge" | "squash" | "fast-forward";
+
+/** Payload of the "feature-session-linked" event (new session auto-linked to a feature) */
+export interface FeatureSessionLinked {
+  project_id: string;
+  feature_id: string;
+  session_id: string;
+}
+
 /** Project view mode */
 export type ProjectViewMode = "features" | "home" | "dashboard";
 
@@ -68,6 +105,32 @@ export interface WorkspaceData {
   active_project_id?: string;
   /** Global feature counter across all projects */
   feature_counter?: number;
+  /** Incremented on every write; send it back unchanged with workspace_save */
+  generation?: number;
+}
+
+/** A named workspace (e.g. separate work and personal boards) */
+export interface WorkspaceProfile {
+  name: string;
+  active: boolean;
+  project_count: number;
+}
+
+/** A deleted project or feature kept in the workspace trash */
+export interface TrashEntry {
+  /** Named workspace it was deleted from */
+  workspace: string;
+  /** Unix seconds */
+  deleted_at: number;
+  entity:
+    | { kind: "project"; index: number; project: WorkspaceProject }
+    | { kind: "feature"; project_id: string; index: number; feature: Feature };
+}
+
+/** Result of workspace_import */
+export interface WorkspaceImportSummary {
+  projects_added: number;
+  features_added: number;
 }
 
 // ============================================================================
@@ -82,6 +145,19 @@ export interface CommitInfo {
   timestamp: number;
   author: string;
   feat_name?: string;
+  /** Parent hashes, for commit graph rendering */
+  parents: string[];
+  /** Ref decorations, e.g. "HEAD -> main" */
+  refs: string[];
+}
+
+/** Filters and pagination for git_log */
+export interface GitLogOptions {
+  skip?: number;
+  author?: string;
+  since?: number;
+  until?: number;
+  all?: boolean;
 }
 
 /** Git note for commit association */
@@ -91,6 +167,92 @@ export interface CommitNote {
   override_assoc?: boolean;
 }
 
+/** Branch returned by git_list_branches */
+export interface GitBranch {
+  name: string;
+  is_current: boolean;
+  is_remote: boolean;
+  commit: string;
+  timestamp: number;
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+}
+
+/** Commit returned by git_show */
+export interface CommitDetail {
+  commit: CommitInfo;
+  body: string;
+  files: FileDiff[];
+}
+
+/** Worktree returned by git_list_worktrees */
+export interface GitWorktree {
+  path: string;
+  head: string;
+  branch: string | null;
+  is_main: boolean;
+  locked: boolean;
+  prunable: boolean;
+}
+
+/** Payload of the "git-progress" event emitted by git_push / git_pull */
+export interface GitProgressEvent {
+  project_path: string;
+  operation: "push" | "pull";
+  stage: string;
+  percent: number | null;
+  current: number | null;
+  total: number | null;
+}
+
+/** Working tree snapshot returned by create_checkpoint */
+export interface Checkpoint {
+  id: string;
+  project_path: string;
+  label: string;
+  commit: string;
+  feature_id: string | null;
+  created_at: number;
+}
+
+/** Options for git_diff */
+export interface GitDiffOptions {
+  mode?: "unstaged" | "staged" | "range";
+  from?: string;
+  to?: string;
+  paths?: string[];
+  context_lines?: number;
+  include_untracked?: boolean;
+}
+
+export interface DiffLine {
+  kind: "context" | "add" | "delete";
+  content: string;
+  old_line: number | null;
+  new_line: number | null;
+}
+
+export interface DiffHunk {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  header: string;
+  lines: DiffLine[];
+}
+
+/** Per-file diff returned by git_diff */
+export interface FileDiff {
+  path: string;
+  old_path: string | null;
+  status: "added" | "modified" | "deleted" | "renamed" | "copied";
+  binary: boolean;
+  additions: number;
+  deletions: number;
+  hunks: DiffHunk[];
+}
+
 // ============================================================================
 // Diagnostics Types
 // ============================================================================